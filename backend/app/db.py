@@ -1,7 +1,7 @@
 import aiosqlite
 import json
 import os
-from datetime import datetime
+from datetime import datetime, timedelta
 from typing import Optional, Dict
 import logging
 from contextlib import asynccontextmanager
@@ -53,7 +53,9 @@ class DatabaseManager:
                     id TEXT PRIMARY KEY,
                     title TEXT NOT NULL,
                     created_at TEXT NOT NULL,
-                    updated_at TEXT NOT NULL
+                    updated_at TEXT NOT NULL,
+                    series_id TEXT,
+                    metadata TEXT
                 )
             """)
             
@@ -67,6 +69,7 @@ class DatabaseManager:
                     summary TEXT,
                     action_items TEXT,
                     key_points TEXT,
+                    segment_id TEXT,
                     FOREIGN KEY (meeting_id) REFERENCES meetings(id)
                 )
             """)
@@ -103,6 +106,34 @@ class DatabaseManager:
                 )
             """)
 
+            # Create action_items table
+            cursor.execute("""
+                CREATE TABLE IF NOT EXISTS action_items (
+                    id TEXT PRIMARY KEY,
+                    text TEXT NOT NULL,
+                    owner TEXT,
+                    status TEXT NOT NULL,
+                    source_meeting_id TEXT NOT NULL,
+                    last_seen_meeting_id TEXT NOT NULL,
+                    carried_forward_count INTEGER DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                )
+            """)
+
+            # Create recording_signatures table (duplicate recording detection across devices)
+            cursor.execute("""
+                CREATE TABLE IF NOT EXISTS recording_signatures (
+                    meeting_id TEXT PRIMARY KEY,
+                    title TEXT NOT NULL,
+                    device_id TEXT,
+                    started_at TEXT NOT NULL,
+                    ended_at TEXT NOT NULL,
+                    envelope TEXT NOT NULL,
+                    fingerprint TEXT
+                )
+            """)
+
             # Create settings table
             cursor.execute("""
                 CREATE TABLE IF NOT EXISTS settings (
@@ -335,7 +366,7 @@ class DatabaseManager:
                     return dict(zip([col[0] for col in cursor.description], row))
                 return None
 
-    async def save_meeting(self, meeting_id: str, title: str):
+    async def save_meeting(self, meeting_id: str, title: str, metadata: Optional[Dict] = None):
         """Save or update a meeting"""
         try:
             with sqlite3.connect(self.db_path) as conn:
@@ -348,9 +379,9 @@ class DatabaseManager:
                 if not existing_meeting:
                     # Create new meeting
                     cursor.execute("""
-                        INSERT INTO meetings (id, title, created_at, updated_at)
-                        VALUES (?, ?, datetime('now'), datetime('now'))
-                    """, (meeting_id, title))
+                        INSERT INTO meetings (id, title, created_at, updated_at, metadata)
+                        VALUES (?, ?, datetime('now'), datetime('now'), ?)
+                    """, (meeting_id, title, json.dumps(metadata) if metadata else None))
                 else:
                     # If we get here and meeting exists, throw error since we don't want duplicates
                     raise Exception(f"Meeting with ID {meeting_id} already exists")
@@ -360,7 +391,7 @@ class DatabaseManager:
             logger.error(f"Error saving meeting: {str(e)}")
             raise
 
-    async def save_meeting_transcript(self, meeting_id: str, transcript: str, timestamp: str, summary: str = "", action_items: str = "", key_points: str = ""):
+    async def save_meeting_transcript(self, meeting_id: str, transcript: str, timestamp: str, summary: str = "", action_items: str = "", key_points: str = "", segment_id: Optional[str] = None):
         """Save a transcript for a meeting"""
         try:
             with sqlite3.connect(self.db_path) as conn:
@@ -369,9 +400,9 @@ class DatabaseManager:
                 # Save transcript
                 cursor.execute("""
                     INSERT INTO transcripts (
-                        meeting_id, transcript, timestamp, summary, action_items, key_points
-                    ) VALUES (?, ?, ?, ?, ?, ?)
-                """, (meeting_id, transcript, timestamp, summary, action_items, key_points))
+                        meeting_id, transcript, timestamp, summary, action_items, key_points, segment_id
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                """, (meeting_id, transcript, timestamp, summary, action_items, key_points, segment_id))
                 
                 conn.commit()
                 return True
@@ -379,13 +410,21 @@ class DatabaseManager:
             logger.error(f"Error saving transcript: {str(e)}")
             raise
 
+    async def get_transcript_segment_ids(self, meeting_id: str):
+        """Segment ids the app sent with the transcripts already stored for a meeting"""
+        async with self._get_connection() as conn:
+            cursor = await conn.execute(
+                "SELECT segment_id FROM transcripts WHERE meeting_id = ? AND segment_id IS NOT NULL", (meeting_id,)
+            )
+            return {row[0] for row in await cursor.fetchall()}
+
     async def get_meeting(self, meeting_id: str):
         """Get a meeting by ID with all its transcripts"""
         try:
             async with self._get_connection() as conn:
                 # Get meeting details
                 cursor = await conn.execute("""
-                    SELECT id, title, created_at, updated_at
+                    SELECT id, title, created_at, updated_at, series_id, metadata
                     FROM meetings
                     WHERE id = ?
                 """, (meeting_id,))
@@ -407,6 +446,8 @@ class DatabaseManager:
                     'title': meeting[1],
                     'created_at': meeting[2],
                     'updated_at': meeting[3],
+                    'series_id': meeting[4],
+                    'metadata': json.loads(meeting[5]) if meeting[5] else None,
                     'transcripts': [{
                         'id': meeting_id,
                         'text': transcript[0],
@@ -443,6 +484,319 @@ class DatabaseManager:
                 'created_at': row[2]
             } for row in rows]
 
+    async def get_meetings_page(self, page: int = 1, limit: int = 50, sort: str = "created_at",
+                                order: str = "desc", date_from: Optional[str] = None,
+                                date_to: Optional[str] = None, tag: Optional[str] = None,
+                                has_summary: Optional[bool] = None):
+        """One page of meetings plus the number of meetings matching the filters.
+
+        Duration comes from the "recording" entry of the meeting metadata; meetings without
+        one sort last. Tags are matched case-insensitively against metadata "tags".
+        """
+        duration = ("(julianday(json_extract(metadata, '$.recording.ended_at'))"
+                    " - julianday(json_extract(metadata, '$.recording.started_at'))) * 86400")
+        summary_exists = ("EXISTS (SELECT 1 FROM summary_processes sp WHERE sp.meeting_id = meetings.id"
+                          " AND sp.status = 'completed' AND sp.result IS NOT NULL)")
+        filters, params = [], []
+        if date_from:
+            filters.append("julianday(created_at) >= julianday(?)")
+            params.append(date_from)
+        if date_to:
+            filters.append("julianday(created_at) <= julianday(?)")
+            params.append(date_to)
+        if tag:
+            filters.append("EXISTS (SELECT 1 FROM json_each(metadata, '$.tags') WHERE value = ? COLLATE NOCASE)")
+            params.append(tag)
+        if has_summary is not None:
+            filters.append(summary_exists if has_summary else f"NOT {summary_exists}")
+        where = f"WHERE {' AND '.join(filters)}" if filters else ""
+
+        direction = "ASC" if order == "asc" else "DESC"
+        order_by = {
+            "title": f"title COLLATE NOCASE {direction}, created_at DESC",
+            "duration": f"duration IS NULL, duration {direction}, created_at DESC",
+        }.get(sort, f"created_at {direction}")
+
+        async with self._get_connection() as conn:
+            cursor = await conn.execute(f"SELECT COUNT(*) FROM meetings {where}", params)
+            total = (await cursor.fetchone())[0]
+            cursor = await conn.execute(f"""
+                SELECT id, title, created_at, {duration} AS duration
+                FROM meetings
+                {where}
+                ORDER BY {order_by}
+                LIMIT ? OFFSET ?
+            """, (*params, limit, (page - 1) * limit))
+            rows = await cursor.fetchall()
+            return [{
+                'id': row[0],
+                'title': row[1],
+                'created_at': row[2],
+                'duration_secs': row[3]
+            } for row in rows], total
+
+    async def update_meeting_metadata(self, meeting_id: str, metadata: Dict):
+        """Merge keys into a meeting's metadata, replacing keys that already exist"""
+        now = datetime.utcnow().isoformat()
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("SELECT metadata FROM meetings WHERE id = ?", (meeting_id,))
+            row = await cursor.fetchone()
+            if not row:
+                raise ValueError(f"Meeting with ID {meeting_id} not found")
+
+            merged = json.loads(row[0]) if row[0] else {}
+            merged.update(metadata)
+            await conn.execute("""
+                UPDATE meetings
+                SET metadata = ?, updated_at = ?
+                WHERE id = ?
+            """, (json.dumps(merged), now, meeting_id))
+            await conn.commit()
+            return merged
+
+    async def find_concurrent_recordings(self, meeting_id: str, device_id: str, started_at: str,
+                                         ended_at: Optional[str] = None, max_open_hours: int = 8):
+        """Meetings recorded on other devices whose recording time overlaps the given one.
+
+        Uses the "recording" entry of the meeting metadata. A recording without an end is
+        treated as still running for at most max_open_hours (it may have crashed).
+        """
+        def parse(value):
+            try:
+                return datetime.fromisoformat(value.replace("Z", "+00:00")) if value else None
+            except ValueError:
+                return None
+
+        start = parse(started_at)
+        if start is None:
+            raise ValueError(f"Invalid started_at: {started_at}")
+        end = parse(ended_at) or datetime.now(start.tzinfo)
+
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("""
+                SELECT id, title, metadata
+                FROM meetings
+                WHERE id != ? AND metadata IS NOT NULL
+                  AND created_at >= datetime('now', '-2 days')
+            """, (meeting_id,))
+            rows = await cursor.fetchall()
+
+        concurrent = []
+        for row in rows:
+            try:
+                recording = (json.loads(row[2]) or {}).get("recording") or {}
+            except json.JSONDecodeError:
+                continue
+            other_start = parse(recording.get("started_at"))
+            if other_start is None or recording.get("device_id") in (None, device_id):
+                continue
+            other_end = parse(recording.get("ended_at"))
+            if other_end is None:
+                other_end = min(end, other_start + timedelta(hours=max_open_hours))
+            if other_start <= end and other_end >= start:
+                concurrent.append({
+                    'meeting_id': row[0],
+                    'title': row[1],
+                    'device_id': recording.get("device_id"),
+                    'device_name': recording.get("device_name"),
+                    'started_at': recording.get("started_at"),
+                    'ended_at': recording.get("ended_at")
+                })
+        return concurrent
+
+    async def merge_meetings(self, source_meeting_id: str, target_meeting_id: str):
+        """Move the transcripts of one meeting into another and delete the source meeting.
+
+        The target's metadata records the merged meeting under "merged_from".
+        """
+        if source_meeting_id == target_meeting_id:
+            raise ValueError("Cannot merge a meeting into itself")
+
+        now = datetime.utcnow().isoformat()
+        async with self._get_connection() as conn:
+            cursor = await conn.execute(
+                "SELECT id, metadata FROM meetings WHERE id IN (?, ?)", (source_meeting_id, target_meeting_id)
+            )
+            found = {row[0]: row[1] for row in await cursor.fetchall()}
+            for meeting_id in (source_meeting_id, target_meeting_id):
+                if meeting_id not in found:
+                    raise ValueError(f"Meeting with ID {meeting_id} not found")
+
+            metadata = json.loads(found[target_meeting_id]) if found[target_meeting_id] else {}
+            source_metadata = json.loads(found[source_meeting_id]) if found[source_meeting_id] else {}
+            metadata.setdefault("merged_from", []).append({
+                'meeting_id': source_meeting_id,
+                'recording': source_metadata.get("recording")
+            })
+
+            cursor = await conn.execute(
+                "UPDATE transcripts SET meeting_id = ? WHERE meeting_id = ?", (target_meeting_id, source_meeting_id)
+            )
+            moved = cursor.rowcount
+            await conn.execute(
+                "UPDATE meetings SET metadata = ?, updated_at = ? WHERE id = ?",
+                (json.dumps(metadata), now, target_meeting_id)
+            )
+            await conn.execute("DELETE FROM transcript_chunks WHERE meeting_id = ?", (source_meeting_id,))
+            await conn.execute("DELETE FROM summary_processes WHERE meeting_id = ?", (source_meeting_id,))
+            await conn.execute("DELETE FROM meetings WHERE id = ?", (source_meeting_id,))
+            await conn.commit()
+            return moved
+
+    async def set_meeting_series(self, meeting_id: str, series_id: Optional[str]):
+        """Link a meeting to a recurring series, or unlink it when series_id is None"""
+        now = datetime.utcnow().isoformat()
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("SELECT id FROM meetings WHERE id = ?", (meeting_id,))
+            if not await cursor.fetchone():
+                raise ValueError(f"Meeting with ID {meeting_id} not found")
+
+            await conn.execute("""
+                UPDATE meetings
+                SET series_id = ?, updated_at = ?
+                WHERE id = ?
+            """, (series_id, now, meeting_id))
+            await conn.commit()
+
+    async def save_recording_signature(self, meeting_id: str, title: str, device_id: Optional[str], started_at: str,
+                                       ended_at: str, envelope: list, fingerprint: Optional[str] = None):
+        """Store the signature of a meeting's recording so other devices can spot duplicates"""
+        if not meeting_id or not meeting_id.strip():
+            raise ValueError("meeting_id cannot be empty")
+
+        async with self._get_connection() as conn:
+            await conn.execute("""
+                INSERT OR REPLACE INTO recording_signatures (
+                    meeting_id, title, device_id, started_at, ended_at, envelope, fingerprint
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+            """, (meeting_id, title, device_id, started_at, ended_at, json.dumps(envelope), fingerprint))
+            await conn.commit()
+
+    async def get_recording_signatures_since(self, since: str):
+        """Signatures of recordings that ended at or after `since`, newest first"""
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("""
+                SELECT meeting_id, title, device_id, started_at, ended_at, envelope, fingerprint
+                FROM recording_signatures
+                WHERE ended_at >= ?
+                ORDER BY ended_at DESC
+            """, (since,))
+            rows = await cursor.fetchall()
+
+        return [{
+            'meeting_id': row[0],
+            'title': row[1],
+            'device_id': row[2],
+            'started_at': row[3],
+            'ended_at': row[4],
+            'envelope': json.loads(row[5]),
+            'fingerprint': row[6]
+        } for row in rows]
+
+    async def get_series_meetings(self, series_id: str):
+        """Get all meetings of a series, oldest first"""
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("""
+                SELECT id, title, created_at
+                FROM meetings
+                WHERE series_id = ?
+                ORDER BY created_at ASC
+            """, (series_id,))
+            rows = await cursor.fetchall()
+            return [{
+                'id': row[0],
+                'title': row[1],
+                'created_at': row[2]
+            } for row in rows]
+
+    async def get_previous_series_summary(self, meeting_id: str):
+        """Get the most recent earlier meeting in the same series that has a completed summary.
+
+        Returns a dict with the meeting id, title, created_at and the parsed summary, or None.
+        """
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("""
+                SELECT series_id, created_at
+                FROM meetings
+                WHERE id = ?
+            """, (meeting_id,))
+            meeting = await cursor.fetchone()
+            if not meeting or not meeting[0]:
+                return None
+
+            cursor = await conn.execute("""
+                SELECT m.id, m.title, m.created_at, sp.result
+                FROM meetings m
+                JOIN summary_processes sp ON sp.meeting_id = m.id
+                WHERE m.series_id = ? AND m.id != ? AND m.created_at < ?
+                  AND sp.status = 'completed' AND sp.result IS NOT NULL
+                ORDER BY m.created_at DESC
+                LIMIT 1
+            """, (meeting[0], meeting_id, meeting[1]))
+            row = await cursor.fetchone()
+            if not row:
+                return None
+
+            try:
+                summary = json.loads(row[3])
+            except json.JSONDecodeError:
+                logger.warning(f"Stored summary for meeting {row[0]} is not valid JSON")
+                return None
+
+            return {
+                'id': row[0],
+                'title': row[1],
+                'created_at': row[2],
+                'summary': summary
+            }
+
+    async def sync_action_items(self, items: list):
+        """Upsert action items, keeping whichever copy was updated last, and return all items"""
+        async with self._get_connection() as conn:
+            for item in items:
+                await conn.execute("""
+                    INSERT INTO action_items (
+                        id, text, owner, status, source_meeting_id, last_seen_meeting_id,
+                        carried_forward_count, created_at, updated_at
+                    ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    ON CONFLICT(id) DO UPDATE SET
+                        text = excluded.text,
+                        owner = excluded.owner,
+                        status = excluded.status,
+                        last_seen_meeting_id = excluded.last_seen_meeting_id,
+                        carried_forward_count = excluded.carried_forward_count,
+                        updated_at = excluded.updated_at
+                    WHERE excluded.updated_at > action_items.updated_at
+                """, (
+                    item['id'], item['text'], item.get('owner'), item['status'],
+                    item['source_meeting_id'], item['last_seen_meeting_id'],
+                    item.get('carried_forward_count', 0), item['created_at'], item['updated_at']
+                ))
+            await conn.commit()
+        return await self.get_action_items()
+
+    async def get_action_items(self):
+        """Get all action items, most recently updated first"""
+        async with self._get_connection() as conn:
+            cursor = await conn.execute("""
+                SELECT id, text, owner, status, source_meeting_id, last_seen_meeting_id,
+                       carried_forward_count, created_at, updated_at
+                FROM action_items
+                ORDER BY updated_at DESC
+            """)
+            rows = await cursor.fetchall()
+            return [{
+                'id': row[0],
+                'text': row[1],
+                'owner': row[2],
+                'status': row[3],
+                'source_meeting_id': row[4],
+                'last_seen_meeting_id': row[5],
+                'carried_forward_count': row[6] or 0,
+                'created_at': row[7],
+                'updated_at': row[8]
+            } for row in rows]
+
     async def delete_meeting(self, meeting_id: str):
         """Delete a meeting and all its associated data"""
         if not meeting_id or not meeting_id.strip():
@@ -471,6 +825,9 @@ class DatabaseManager:
                     
                     # Delete from transcripts
                     await conn.execute("DELETE FROM transcripts WHERE meeting_id = ?", (meeting_id,))
+
+                    # Delete from recording_signatures
+                    await conn.execute("DELETE FROM recording_signatures WHERE meeting_id = ?", (meeting_id,))
                     
                     # Delete from meetings
                     cursor = await conn.execute("DELETE FROM meetings WHERE id = ?", (meeting_id,))
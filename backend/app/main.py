@@ -1,16 +1,18 @@
-from fastapi import FastAPI, HTTPException, BackgroundTasks
+from fastapi import FastAPI, HTTPException, BackgroundTasks, Query
 from fastapi.middleware.cors import CORSMiddleware
 from fastapi.responses import JSONResponse
 from pydantic import BaseModel
 import uvicorn
-from typing import Optional, List
+from typing import Optional, List, Dict, Any
 import logging
 from dotenv import load_dotenv
 from .db import DatabaseManager
 from .transcript_processor import TranscriptProcessor
 import json
+import asyncio
 from threading import Lock
 import time
+from datetime import datetime
 
 # Load environment variables
 load_dotenv()
@@ -65,12 +67,22 @@ class Transcript(BaseModel):
 class MeetingResponse(BaseModel):
     id: str
     title: str
+    created_at: Optional[str] = None
+    duration_secs: Optional[float] = None
+
+class MeetingListResponse(BaseModel):
+    meetings: List[MeetingResponse]
+    total: int
+    page: int
+    limit: int
 
 class MeetingDetailsResponse(BaseModel):
     id: str
     title: str
     created_at: str
     updated_at: str
+    series_id: Optional[str] = None
+    metadata: Optional[Dict[str, Any]] = None
     transcripts: List[Transcript]
 
 class MeetingTitleUpdate(BaseModel):
@@ -83,6 +95,7 @@ class DeleteMeetingRequest(BaseModel):
 class SaveTranscriptRequest(BaseModel):
     meeting_title: str
     transcripts: List[Transcript]
+    metadata: Optional[Dict[str, Any]] = None
 
 class SaveModelConfigRequest(BaseModel):
     provider: str
@@ -104,6 +117,9 @@ class TranscriptRequest(BaseModel):
     chunk_size: Optional[int] = 5000
     overlap: Optional[int] = 1000
     custom_prompt: Optional[str] = "Generate a summary of the meeting transcript."
+    include_series_context: Optional[bool] = True
+    # Sent by the app from the OS keychain; never stored
+    api_key: Optional[str] = None
 
 class SummaryProcessor:
     """Handles the processing of summaries in a thread-safe way"""
@@ -167,12 +183,24 @@ class SummaryProcessor:
 processor = SummaryProcessor()
 
 # New meeting management endpoints
-@app.get("/get-meetings", response_model=List[MeetingResponse])
-async def get_meetings():
-    """Get all meetings with their basic information"""
+@app.get("/get-meetings", response_model=MeetingListResponse)
+async def get_meetings(
+    page: int = Query(1, ge=1),
+    limit: int = Query(50, ge=1, le=500),
+    sort: str = Query("created_at", pattern="^(created_at|title|duration)$"),
+    order: str = Query("desc", pattern="^(asc|desc)$"),
+    date_from: Optional[str] = Query(None, alias="from"),
+    date_to: Optional[str] = Query(None, alias="to"),
+    tag: Optional[str] = None,
+    has_summary: Optional[bool] = None,
+):
+    """Get one page of meetings, sorted and filtered, with the total number of matches"""
     try:
-        meetings = await db.get_all_meetings()
-        return [{"id": meeting["id"], "title": meeting["title"]} for meeting in meetings]
+        meetings, total = await db.get_meetings_page(
+            page=page, limit=limit, sort=sort, order=order,
+            date_from=date_from, date_to=date_to, tag=tag, has_summary=has_summary
+        )
+        return {"meetings": meetings, "total": total, "page": page, "limit": limit}
     except Exception as e:
         logger.error(f"Error getting meetings: {str(e)}", exc_info=True)
         raise HTTPException(status_code=500, detail=str(e))
@@ -214,6 +242,181 @@ async def delete_meeting(data: DeleteMeetingRequest):
         logger.error(f"Error deleting meeting: {str(e)}", exc_info=True)
         raise HTTPException(status_code=500, detail=str(e))
 
+class LinkMeetingSeriesRequest(BaseModel):
+    meeting_id: str
+    series_id: Optional[str] = None
+
+@app.post("/link-meeting-series")
+async def link_meeting_series(data: LinkMeetingSeriesRequest):
+    """Link a meeting to a recurring series, or unlink it when no series_id is given"""
+    try:
+        await db.set_meeting_series(data.meeting_id, data.series_id)
+        return {"message": "Meeting series updated successfully"}
+    except ValueError as ve:
+        logger.error(f"Value error linking meeting series: {str(ve)}")
+        raise HTTPException(status_code=404, detail=str(ve))
+    except Exception as e:
+        logger.error(f"Error linking meeting series: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+class SaveMeetingMetadataRequest(BaseModel):
+    meeting_id: str
+    metadata: Dict[str, Any]
+
+@app.post("/save-meeting-metadata")
+async def save_meeting_metadata(data: SaveMeetingMetadataRequest):
+    """Merge keys into a meeting's metadata (e.g. the audio pipeline settings it was recorded with)"""
+    try:
+        metadata = await db.update_meeting_metadata(data.meeting_id, data.metadata)
+        return {"message": "Meeting metadata saved successfully", "metadata": metadata}
+    except ValueError as ve:
+        logger.error(f"Value error saving meeting metadata: {str(ve)}")
+        raise HTTPException(status_code=404, detail=str(ve))
+    except Exception as e:
+        logger.error(f"Error saving meeting metadata: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+class FindConcurrentRecordingsRequest(BaseModel):
+    meeting_id: str
+    device_id: str
+    started_at: str
+    ended_at: Optional[str] = None
+
+@app.post("/find-concurrent-recordings")
+async def find_concurrent_recordings(data: FindConcurrentRecordingsRequest):
+    """Find meetings recorded at the same time on another device of the same account"""
+    try:
+        meetings = await db.find_concurrent_recordings(data.meeting_id, data.device_id, data.started_at, data.ended_at)
+        return {"meetings": meetings}
+    except ValueError as ve:
+        logger.error(f"Value error finding concurrent recordings: {str(ve)}")
+        raise HTTPException(status_code=400, detail=str(ve))
+    except Exception as e:
+        logger.error(f"Error finding concurrent recordings: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+class RecordingSignature(BaseModel):
+    meeting_id: str
+    title: str
+    device_id: Optional[str] = None
+    started_at: str
+    ended_at: str
+    envelope: List[float]
+    fingerprint: Optional[str] = None  # base64 of the little-endian u32 hashes
+
+@app.post("/save-recording-signature")
+async def save_recording_signature(data: RecordingSignature):
+    """Store a recording's signature so recordings of the same meeting on other devices can be matched"""
+    try:
+        await db.save_recording_signature(
+            data.meeting_id, data.title, data.device_id, data.started_at, data.ended_at, data.envelope, data.fingerprint
+        )
+        return {"message": "Recording signature saved successfully"}
+    except ValueError as ve:
+        logger.error(f"Value error saving recording signature: {str(ve)}")
+        raise HTTPException(status_code=400, detail=str(ve))
+    except Exception as e:
+        logger.error(f"Error saving recording signature: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+class RecentRecordingSignaturesRequest(BaseModel):
+    since: str
+
+@app.post("/recent-recording-signatures")
+async def recent_recording_signatures(data: RecentRecordingSignaturesRequest):
+    """Signatures of recordings from any device that ended since the given time"""
+    try:
+        signatures = await db.get_recording_signatures_since(data.since)
+        return {"signatures": signatures}
+    except Exception as e:
+        logger.error(f"Error getting recording signatures: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+class MergeMeetingsRequest(BaseModel):
+    source_meeting_id: str
+    target_meeting_id: str
+
+@app.post("/merge-meetings")
+async def merge_meetings(data: MergeMeetingsRequest):
+    """Move a meeting's transcripts into another meeting and delete it"""
+    try:
+        moved = await db.merge_meetings(data.source_meeting_id, data.target_meeting_id)
+        logger.info(f"Merged meeting {data.source_meeting_id} into {data.target_meeting_id} ({moved} transcripts)")
+        return {"message": "Meetings merged successfully", "meeting_id": data.target_meeting_id, "moved": moved}
+    except ValueError as ve:
+        logger.error(f"Value error merging meetings: {str(ve)}")
+        raise HTTPException(status_code=404, detail=str(ve))
+    except Exception as e:
+        logger.error(f"Error merging meetings: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+@app.get("/get-meeting-series/{series_id}")
+async def get_meeting_series(series_id: str):
+    """Get all meetings of a series, oldest first"""
+    try:
+        meetings = await db.get_series_meetings(series_id)
+        return {"series_id": series_id, "meetings": meetings}
+    except Exception as e:
+        logger.error(f"Error getting meeting series: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+def aggregate_chunk_summaries(all_json_data: List[str], process_id: str) -> dict:
+    """Merge the per-chunk summaries returned by the processor into one summary"""
+    final_summary = {
+        "MeetingName": "",
+        "People": {"title": "People", "blocks": []},
+        "SessionSummary": {"title": "Session Summary", "blocks": []},
+        "CriticalDeadlines": {"title": "Critical Deadlines", "blocks": []},
+        "KeyItemsDecisions": {"title": "Key Items & Decisions", "blocks": []},
+        "ImmediateActionItems": {"title": "Immediate Action Items", "blocks": []},
+        "NextSteps": {"title": "Next Steps", "blocks": []},
+        # "OtherImportantPoints": {"title": "Other Important Points", "blocks": []},
+        # "ClosingRemarks": {"title": "Closing Remarks", "blocks": []},
+        "MeetingNotes": {
+            "meeting_name": "",
+            "sections": []
+        }
+    }
+
+    # Process each chunk's data
+    for json_str in all_json_data:
+        try:
+            json_dict = json.loads(json_str)
+            if "MeetingName" in json_dict and json_dict["MeetingName"]:
+                final_summary["MeetingName"] = json_dict["MeetingName"]
+            for key in final_summary:
+                if key == "MeetingNotes" and key in json_dict:
+                    # Handle MeetingNotes sections
+                    if isinstance(json_dict[key].get("sections"), list):
+                        # Ensure each section has blocks array
+                        for section in json_dict[key]["sections"]:
+                            if not section.get("blocks"):
+                                section["blocks"] = []
+                        final_summary[key]["sections"].extend(json_dict[key]["sections"])
+                    if json_dict[key].get("meeting_name"):
+                        final_summary[key]["meeting_name"] = json_dict[key]["meeting_name"]
+                elif key != "MeetingName" and key in json_dict and isinstance(json_dict[key], dict) and "blocks" in json_dict[key]:
+                    if isinstance(json_dict[key]["blocks"], list):
+                        final_summary[key]["blocks"].extend(json_dict[key]["blocks"])
+                        # Also add as a new section in MeetingNotes if not already present
+                        section_exists = False
+                        for section in final_summary["MeetingNotes"]["sections"]:
+                            if section["title"] == json_dict[key]["title"]:
+                                section["blocks"].extend(json_dict[key]["blocks"])
+                                section_exists = True
+                                break
+                        
+                        if not section_exists:
+                            final_summary["MeetingNotes"]["sections"].append({
+                                "title": json_dict[key]["title"],
+                                "blocks": json_dict[key]["blocks"].copy() if json_dict[key]["blocks"] else []
+                            })
+        except json.JSONDecodeError as e:
+            logger.error(f"Failed to parse JSON chunk for {process_id}: {e}. Chunk: {json_str[:100]}...")
+        except Exception as e:
+            logger.error(f"Error processing chunk data for {process_id}: {e}. Chunk: {json_str[:100]}...")
+    return final_summary
+
 async def process_transcript_background(process_id: str, transcript: TranscriptRequest, custom_prompt: str):
     """Background task to process transcript"""
     try:
@@ -225,7 +428,7 @@ async def process_transcript_background(process_id: str, transcript: TranscriptR
         
         if transcript.model in ["claude", "groq", "openai"]:
             # Check if API key is available for cloud providers
-            api_key = await processor.db.get_api_key(transcript.model)
+            api_key = transcript.api_key or await processor.db.get_api_key(transcript.model)
             if not api_key:
                 provider_names = {"claude": "Anthropic", "groq": "Groq", "openai": "OpenAI"}
                 raise ValueError(f"{provider_names.get(transcript.model, transcript.model)} API key not configured. Please set your API key in the model settings.")
@@ -236,63 +439,11 @@ async def process_transcript_background(process_id: str, transcript: TranscriptR
             model_name=transcript.model_name,
             chunk_size=transcript.chunk_size,
             overlap=transcript.overlap,
-            custom_prompt=custom_prompt
+            custom_prompt=custom_prompt,
+            api_key=transcript.api_key
         )
 
-        # Create final summary structure by aggregating chunk results
-        final_summary = {
-            "MeetingName": "",
-            "People": {"title": "People", "blocks": []},
-            "SessionSummary": {"title": "Session Summary", "blocks": []},
-            "CriticalDeadlines": {"title": "Critical Deadlines", "blocks": []},
-            "KeyItemsDecisions": {"title": "Key Items & Decisions", "blocks": []},
-            "ImmediateActionItems": {"title": "Immediate Action Items", "blocks": []},
-            "NextSteps": {"title": "Next Steps", "blocks": []},
-            # "OtherImportantPoints": {"title": "Other Important Points", "blocks": []},
-            # "ClosingRemarks": {"title": "Closing Remarks", "blocks": []},
-            "MeetingNotes": {
-                "meeting_name": "",
-                "sections": []
-            }
-        }
-
-        # Process each chunk's data
-        for json_str in all_json_data:
-            try:
-                json_dict = json.loads(json_str)
-                if "MeetingName" in json_dict and json_dict["MeetingName"]:
-                    final_summary["MeetingName"] = json_dict["MeetingName"]
-                for key in final_summary:
-                    if key == "MeetingNotes" and key in json_dict:
-                        # Handle MeetingNotes sections
-                        if isinstance(json_dict[key].get("sections"), list):
-                            # Ensure each section has blocks array
-                            for section in json_dict[key]["sections"]:
-                                if not section.get("blocks"):
-                                    section["blocks"] = []
-                            final_summary[key]["sections"].extend(json_dict[key]["sections"])
-                        if json_dict[key].get("meeting_name"):
-                            final_summary[key]["meeting_name"] = json_dict[key]["meeting_name"]
-                    elif key != "MeetingName" and key in json_dict and isinstance(json_dict[key], dict) and "blocks" in json_dict[key]:
-                        if isinstance(json_dict[key]["blocks"], list):
-                            final_summary[key]["blocks"].extend(json_dict[key]["blocks"])
-                            # Also add as a new section in MeetingNotes if not already present
-                            section_exists = False
-                            for section in final_summary["MeetingNotes"]["sections"]:
-                                if section["title"] == json_dict[key]["title"]:
-                                    section["blocks"].extend(json_dict[key]["blocks"])
-                                    section_exists = True
-                                    break
-                            
-                            if not section_exists:
-                                final_summary["MeetingNotes"]["sections"].append({
-                                    "title": json_dict[key]["title"],
-                                    "blocks": json_dict[key]["blocks"].copy() if json_dict[key]["blocks"] else []
-                                })
-            except json.JSONDecodeError as e:
-                logger.error(f"Failed to parse JSON chunk for {process_id}: {e}. Chunk: {json_str[:100]}...")
-            except Exception as e:
-                logger.error(f"Error processing chunk data for {process_id}: {e}. Chunk: {json_str[:100]}...")
+        final_summary = aggregate_chunk_summaries(all_json_data, process_id)
 
         # Update database with meeting name using meeting_id
         if final_summary["MeetingName"]:
@@ -300,7 +451,18 @@ async def process_transcript_background(process_id: str, transcript: TranscriptR
 
         # Save final result
         if all_json_data:
-            await processor.db.update_process(process_id, status="completed", result=json.dumps(final_summary))
+            summarization = {
+                "provider": transcript.model,
+                "model": transcript.model_name,
+                "chunk_size": transcript.chunk_size,
+                "overlap": transcript.overlap,
+                "completed_at": datetime.utcnow().isoformat()
+            }
+            await processor.db.update_process(process_id, status="completed", result=json.dumps(final_summary), metadata=summarization)
+            try:
+                await processor.db.update_meeting_metadata(transcript.meeting_id, {"summarization": summarization})
+            except ValueError as e:
+                logger.warning(f"Could not record summarization model for {transcript.meeting_id}: {e}")
             logger.info(f"Background processing completed for process_id: {process_id}")
         else:
             error_msg = "Summary generation failed: No chunks were processed successfully. Check logs for specific errors."
@@ -324,6 +486,41 @@ async def process_transcript_background(process_id: str, transcript: TranscriptR
         except Exception as db_e:
             logger.error(f"Failed to update DB status to failed for {process_id}: {db_e}", exc_info=True)
 
+def extract_action_items(summary: dict) -> List[str]:
+    """Collect action item texts from a stored summary.
+
+    Handles both the raw processing result (MeetingNotes.sections) and summaries
+    saved back from the editor (top-level section objects).
+    """
+    sections = []
+    if isinstance(summary.get("MeetingNotes"), dict):
+        sections.extend(summary["MeetingNotes"].get("sections") or [])
+    sections.extend(
+        value for key, value in summary.items()
+        if key != "MeetingNotes" and isinstance(value, dict) and "blocks" in value
+    )
+
+    items = []
+    for section in sections:
+        if not isinstance(section, dict) or "action" not in str(section.get("title", "")).lower():
+            continue
+        for block in section.get("blocks") or []:
+            content = str(block.get("content", "")).strip() if isinstance(block, dict) else ""
+            if content and content not in items:
+                items.append(content)
+    return items
+
+def build_series_prompt(custom_prompt: Optional[str], previous: dict, action_items: List[str]) -> str:
+    """Append the previous instance's open action items to the summary prompt"""
+    items = "\n".join(f"- {item}" for item in action_items)
+    context = (
+        f"This meeting is part of a recurring series. The previous meeting "
+        f"(\"{previous['title']}\", {previous['created_at']}) left these action items open:\n"
+        f"{items}\n"
+        f"For each of them, note in the summary whether it was discussed, completed or is still open."
+    )
+    return f"{custom_prompt}\n\n{context}" if custom_prompt else context
+
 @app.post("/process-transcript")
 async def process_transcript_api(
     transcript: TranscriptRequest,
@@ -346,6 +543,15 @@ async def process_transcript_api(
 
         custom_prompt = transcript.custom_prompt
 
+        # Carry open action items from the previous meeting of a series into the prompt
+        if transcript.include_series_context:
+            previous = await processor.db.get_previous_series_summary(transcript.meeting_id)
+            if previous:
+                action_items = extract_action_items(previous["summary"])
+                if action_items:
+                    logger.info(f"Adding {len(action_items)} open action items from meeting {previous['id']} to prompt")
+                    custom_prompt = build_series_prompt(custom_prompt, previous, action_items)
+
         # Start background processing
         background_tasks.add_task(
             process_transcript_background,
@@ -363,6 +569,105 @@ async def process_transcript_api(
         logger.error(f"Error in process_transcript_api: {str(e)}", exc_info=True)
         raise HTTPException(status_code=500, detail=str(e))
 
+def transform_summary(summary_data: dict) -> dict:
+    """Turn a stored summary into the section map the frontend renders - PRESERVE ORDER"""
+    transformed_data = {}
+    # Add MeetingName to transformed data
+    transformed_data["MeetingName"] = summary_data.get("MeetingName", "")
+
+    # Map backend sections to frontend sections
+    section_mapping = {
+        # "SessionSummary": "key_points",
+        # "ImmediateActionItems": "action_items",
+        # "KeyItemsDecisions": "decisions",
+        # "NextSteps": "next_steps",
+        # "CriticalDeadlines": "critical_deadlines",
+        # "People": "people"
+    }
+
+    # Add each section to transformed data
+    for backend_key, frontend_key in section_mapping.items():
+        if backend_key in summary_data and isinstance(summary_data[backend_key], dict):
+            transformed_data[frontend_key] = summary_data[backend_key]
+    
+    # Add meeting notes sections if available - PRESERVE ORDER AND HANDLE DUPLICATES
+    if "MeetingNotes" in summary_data and isinstance(summary_data["MeetingNotes"], dict):
+        meeting_notes = summary_data["MeetingNotes"]
+        if isinstance(meeting_notes.get("sections"), list):
+            # Add section order array to maintain order
+            transformed_data["_section_order"] = []
+            used_keys = set()
+            
+            for index, section in enumerate(meeting_notes["sections"]):
+                if isinstance(section, dict) and "title" in section and "blocks" in section:
+                    # Ensure blocks is a list to prevent frontend errors
+                    if not isinstance(section.get("blocks"), list):
+                        section["blocks"] = []
+                        
+                    # Convert title to snake_case key
+                    base_key = section["title"].lower().replace(" & ", "_").replace(" ", "_")
+                    
+                    # Handle duplicate section names by adding index
+                    key = base_key
+                    if key in used_keys:
+                        key = f"{base_key}_{index}"
+                    
+                    used_keys.add(key)
+                    transformed_data[key] = section
+                    # Only add to _section_order if the section was successfully added
+                    transformed_data["_section_order"].append(key)
+    return transformed_data
+
+class SummaryModelChoice(BaseModel):
+    provider: str
+    model: str
+    # Sent by the app from the OS keychain; never stored
+    api_key: Optional[str] = None
+
+class CompareSummariesRequest(BaseModel):
+    meeting_id: str
+    models: List[SummaryModelChoice]
+    custom_prompt: Optional[str] = "Generate a summary of the meeting transcript."
+
+@app.post("/compare-summaries")
+async def compare_summaries(request: CompareSummariesRequest):
+    """Summarize a meeting with two or three models concurrently. Nothing is saved; the
+    results are returned side by side so the user can pick a default model."""
+    if not 2 <= len(request.models) <= 3:
+        raise HTTPException(status_code=400, detail="Compare two or three models")
+    meeting = await db.get_meeting(request.meeting_id)
+    if not meeting:
+        raise HTTPException(status_code=404, detail="Meeting not found")
+    text = "\n".join(t["text"] for t in meeting["transcripts"])
+    if not text.strip():
+        raise HTTPException(status_code=400, detail="Meeting has no transcript to summarize")
+
+    async def summarize(choice: SummaryModelChoice) -> dict:
+        started = time.time()
+        result = {"provider": choice.provider, "model": choice.model, "summary": None, "error": None}
+        try:
+            if choice.provider in ["claude", "groq", "openai"] and not (choice.api_key or await processor.db.get_api_key(choice.provider)):
+                raise ValueError(f"{choice.provider} API key not configured")
+            _, all_json_data = await processor.process_transcript(
+                text=text,
+                model=choice.provider,
+                model_name=choice.model,
+                custom_prompt=request.custom_prompt,
+                api_key=choice.api_key
+            )
+            if not all_json_data:
+                raise ValueError("No chunks were processed successfully")
+            final_summary = aggregate_chunk_summaries(all_json_data, f"compare-{request.meeting_id}")
+            result["summary"] = transform_summary(final_summary)
+        except Exception as e:
+            logger.error(f"Comparison summary with {choice.provider}/{choice.model} failed: {str(e)}", exc_info=True)
+            result["error"] = str(e)
+        result["duration_seconds"] = round(time.time() - started, 2)
+        return result
+
+    results = await asyncio.gather(*(summarize(choice) for choice in request.models))
+    return {"meeting_id": request.meeting_id, "results": results}
+
 @app.get("/get-summary/{meeting_id}")
 async def get_summary(meeting_id: str):
     """Get the summary for a given meeting ID"""
@@ -406,53 +711,9 @@ async def get_summary(meeting_id: str):
                 status = "failed"
                 result["error"] = f"Error processing summary data: {str(e)}"
 
-        # Transform summary data into frontend format if available - PRESERVE ORDER
         transformed_data = {}
         if isinstance(summary_data, dict) and status == "completed":
-            # Add MeetingName to transformed data
-            transformed_data["MeetingName"] = summary_data.get("MeetingName", "")
-
-            # Map backend sections to frontend sections
-            section_mapping = {
-                # "SessionSummary": "key_points",
-                # "ImmediateActionItems": "action_items",
-                # "KeyItemsDecisions": "decisions",
-                # "NextSteps": "next_steps",
-                # "CriticalDeadlines": "critical_deadlines",
-                # "People": "people"
-            }
-
-            # Add each section to transformed data
-            for backend_key, frontend_key in section_mapping.items():
-                if backend_key in summary_data and isinstance(summary_data[backend_key], dict):
-                    transformed_data[frontend_key] = summary_data[backend_key]
-            
-            # Add meeting notes sections if available - PRESERVE ORDER AND HANDLE DUPLICATES
-            if "MeetingNotes" in summary_data and isinstance(summary_data["MeetingNotes"], dict):
-                meeting_notes = summary_data["MeetingNotes"]
-                if isinstance(meeting_notes.get("sections"), list):
-                    # Add section order array to maintain order
-                    transformed_data["_section_order"] = []
-                    used_keys = set()
-                    
-                    for index, section in enumerate(meeting_notes["sections"]):
-                        if isinstance(section, dict) and "title" in section and "blocks" in section:
-                            # Ensure blocks is a list to prevent frontend errors
-                            if not isinstance(section.get("blocks"), list):
-                                section["blocks"] = []
-                                
-                            # Convert title to snake_case key
-                            base_key = section["title"].lower().replace(" & ", "_").replace(" ", "_")
-                            
-                            # Handle duplicate section names by adding index
-                            key = base_key
-                            if key in used_keys:
-                                key = f"{base_key}_{index}"
-                            
-                            used_keys.add(key)
-                            transformed_data[key] = section
-                            # Only add to _section_order if the section was successfully added
-                            transformed_data["_section_order"].append(key)
+            transformed_data = transform_summary(summary_data)
 
         response = {
             "status": "processing" if status in ["processing", "pending", "started"] else status,
@@ -517,7 +778,7 @@ async def save_transcript(request: SaveTranscriptRequest):
         meeting_id = f"meeting-{int(time.time() * 1000)}"
 
         # Save the meeting
-        await db.save_meeting(meeting_id, request.meeting_title)
+        await db.save_meeting(meeting_id, request.meeting_title, request.metadata)
 
         # Save each transcript segment
         for transcript in request.transcripts:
@@ -527,7 +788,8 @@ async def save_transcript(request: SaveTranscriptRequest):
                 timestamp=transcript.timestamp,
                 summary="",
                 action_items="",
-                key_points=""
+                key_points="",
+                segment_id=transcript.id
             )
 
         logger.info("Transcripts saved successfully")
@@ -536,6 +798,45 @@ async def save_transcript(request: SaveTranscriptRequest):
         logger.error(f"Error saving transcript: {str(e)}", exc_info=True)
         raise HTTPException(status_code=500, detail=str(e))
 
+class AppendTranscriptRequest(BaseModel):
+    meeting_id: str
+    transcripts: List[Transcript]
+
+@app.post("/append-transcript")
+async def append_transcript(request: AppendTranscriptRequest):
+    """Append transcript segments to an existing meeting (incremental sync)"""
+    try:
+        meeting = await db.get_meeting(request.meeting_id)
+        if not meeting:
+            raise HTTPException(status_code=404, detail="Meeting not found")
+
+        # A retry after a request that succeeded but timed out resends segments already stored
+        stored = await db.get_transcript_segment_ids(request.meeting_id)
+        appended = 0
+        for transcript in request.transcripts:
+            if transcript.id in stored:
+                continue
+            await db.save_meeting_transcript(
+                meeting_id=request.meeting_id,
+                transcript=transcript.text,
+                timestamp=transcript.timestamp,
+                summary="",
+                action_items="",
+                key_points="",
+                segment_id=transcript.id
+            )
+            stored.add(transcript.id)
+            appended += 1
+
+        skipped = len(request.transcripts) - appended
+        logger.info(f"Appended {appended} transcripts to meeting {request.meeting_id} ({skipped} already stored)")
+        return {"status": "success", "meeting_id": request.meeting_id, "appended": appended}
+    except HTTPException:
+        raise
+    except Exception as e:
+        logger.error(f"Error appending transcript: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
 @app.get("/get-model-config")
 async def get_model_config():
     """Get the current model configuration"""
@@ -589,6 +890,24 @@ async def get_transcript_api_key(request: GetApiKeyRequest):
     except Exception as e:
         raise HTTPException(status_code=500, detail=str(e))
 
+@app.post("/delete-api-key")
+async def delete_api_key(request: GetApiKeyRequest):
+    """Forget a stored API key once the app has moved it to the OS keychain"""
+    try:
+        await db.save_api_key("", request.provider)
+        return {"status": "success", "message": "API key deleted"}
+    except Exception as e:
+        raise HTTPException(status_code=500, detail=str(e))
+
+@app.post("/delete-transcript-api-key")
+async def delete_transcript_api_key(request: GetApiKeyRequest):
+    """Forget a stored transcript API key once the app has moved it to the OS keychain"""
+    try:
+        await db.save_transcript_api_key("", request.provider)
+        return {"status": "success", "message": "Transcript API key deleted"}
+    except Exception as e:
+        raise HTTPException(status_code=500, detail=str(e))
+
 class MeetingSummaryUpdate(BaseModel):
     meeting_id: str
     summary: dict
@@ -606,6 +925,39 @@ async def save_meeting_summary(data: MeetingSummaryUpdate):
         logger.error(f"Error saving meeting summary: {str(e)}", exc_info=True)
         raise HTTPException(status_code=500, detail=str(e))
 
+class ActionItemModel(BaseModel):
+    id: str
+    text: str
+    owner: Optional[str] = None
+    status: str
+    source_meeting_id: str
+    last_seen_meeting_id: str
+    carried_forward_count: int = 0
+    created_at: str
+    updated_at: str
+
+class SyncActionItemsRequest(BaseModel):
+    items: List[ActionItemModel]
+
+@app.post("/sync-action-items")
+async def sync_action_items(data: SyncActionItemsRequest):
+    """Merge the client's action items into the shared store and return the merged list"""
+    try:
+        items = await db.sync_action_items([item.dict() for item in data.items])
+        return {"items": items}
+    except Exception as e:
+        logger.error(f"Error syncing action items: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
+@app.get("/get-action-items")
+async def get_action_items():
+    """Get all tracked action items"""
+    try:
+        return {"items": await db.get_action_items()}
+    except Exception as e:
+        logger.error(f"Error getting action items: {str(e)}", exc_info=True)
+        raise HTTPException(status_code=500, detail=str(e))
+
 class SearchRequest(BaseModel):
     query: str
 
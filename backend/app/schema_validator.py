@@ -35,7 +35,9 @@ class SchemaValidator:
                 ('id', 'TEXT', 'PRIMARY KEY'),
                 ('title', 'TEXT', 'NOT NULL'),
                 ('created_at', 'TEXT', 'NOT NULL'),
-                ('updated_at', 'TEXT', 'NOT NULL')
+                ('updated_at', 'TEXT', 'NOT NULL'),
+                ('series_id', 'TEXT', ''),
+                ('metadata', 'TEXT', '')
             ],
             'transcripts': [
                 ('id', 'TEXT', 'PRIMARY KEY'),
@@ -44,7 +46,8 @@ class SchemaValidator:
                 ('timestamp', 'TEXT', 'NOT NULL'),
                 ('summary', 'TEXT', ''),
                 ('action_items', 'TEXT', ''),
-                ('key_points', 'TEXT', '')
+                ('key_points', 'TEXT', ''),
+                ('segment_id', 'TEXT', '')
             ],
             'summary_processes': [
                 ('meeting_id', 'TEXT', 'PRIMARY KEY'),
@@ -69,6 +72,26 @@ class SchemaValidator:
                 ('overlap', 'INTEGER', ''),
                 ('created_at', 'TEXT', 'NOT NULL')
             ],
+            'action_items': [
+                ('id', 'TEXT', 'PRIMARY KEY'),
+                ('text', 'TEXT', 'NOT NULL'),
+                ('owner', 'TEXT', ''),
+                ('status', 'TEXT', 'NOT NULL'),
+                ('source_meeting_id', 'TEXT', 'NOT NULL'),
+                ('last_seen_meeting_id', 'TEXT', 'NOT NULL'),
+                ('carried_forward_count', 'INTEGER', 'DEFAULT 0'),
+                ('created_at', 'TEXT', 'NOT NULL'),
+                ('updated_at', 'TEXT', 'NOT NULL')
+            ],
+            'recording_signatures': [
+                ('meeting_id', 'TEXT', 'PRIMARY KEY'),
+                ('title', 'TEXT', 'NOT NULL'),
+                ('device_id', 'TEXT', ''),
+                ('started_at', 'TEXT', 'NOT NULL'),
+                ('ended_at', 'TEXT', 'NOT NULL'),
+                ('envelope', 'TEXT', 'NOT NULL'),
+                ('fingerprint', 'TEXT', '')
+            ],
             'settings': [
                 ('id', 'TEXT', 'PRIMARY KEY'),
                 ('provider', 'TEXT', 'NOT NULL'),
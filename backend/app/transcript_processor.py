@@ -1,5 +1,5 @@
 from pydantic import BaseModel
-from typing import List, Tuple, Literal
+from typing import List, Optional, Tuple, Literal
 from pydantic_ai import Agent
 from pydantic_ai.models.anthropic import AnthropicModel
 from pydantic_ai.models.groq import GroqModel
@@ -84,7 +84,7 @@ class TranscriptProcessor:
         logger.info("TranscriptProcessor initialized.")
         self.db = DatabaseManager()
         self.active_clients = []  # Track active Ollama client sessions
-    async def process_transcript(self, text: str, model: str, model_name: str, chunk_size: int = 5000, overlap: int = 1000, custom_prompt: str = "") -> Tuple[int, List[str]]:
+    async def process_transcript(self, text: str, model: str, model_name: str, chunk_size: int = 5000, overlap: int = 1000, custom_prompt: str = "", api_key: Optional[str] = None) -> Tuple[int, List[str]]:
         """
         Process transcript text into chunks and generate structured summaries for each chunk using an AI model.
 
@@ -95,6 +95,8 @@ class TranscriptProcessor:
             chunk_size: The size of each text chunk.
             overlap: The overlap between consecutive chunks.
             custom_prompt: A custom prompt to use for the AI model.
+            api_key: The provider's API key. The app keeps keys in the OS keychain and sends
+                them with each request; without one, a key saved here by older versions is used.
 
         Returns:
             A tuple containing:
@@ -111,7 +113,7 @@ class TranscriptProcessor:
         try:
             # Select and initialize the AI model and agent
             if model == "claude":
-                api_key = await db.get_api_key("claude")
+                api_key = api_key or await db.get_api_key("claude")
                 if not api_key: raise ValueError("ANTHROPIC_API_KEY environment variable not set")
                 llm = AnthropicModel(model_name, provider=AnthropicProvider(api_key=api_key))
                 logger.info(f"Using Claude model: {model_name}")
@@ -131,13 +133,13 @@ class TranscriptProcessor:
                     overlap = 1000
                 logger.info(f"Using Ollama model: {model_name}")
             elif model == "groq":
-                api_key = await db.get_api_key("groq")
+                api_key = api_key or await db.get_api_key("groq")
                 if not api_key: raise ValueError("GROQ_API_KEY environment variable not set")
                 llm = GroqModel(model_name, provider=GroqProvider(api_key=api_key))
                 logger.info(f"Using Groq model: {model_name}")
             # --- ADD OPENAI SUPPORT HERE ---
             elif model == "openai":
-                api_key = await db.get_api_key("openai")
+                api_key = api_key or await db.get_api_key("openai")
                 if not api_key: raise ValueError("OPENAI_API_KEY environment variable not set")
                 llm = OpenAIModel(model_name, provider=OpenAIProvider(api_key=api_key))
                 logger.info(f"Using OpenAI model: {model_name}")
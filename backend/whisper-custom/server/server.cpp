@@ -12,6 +12,10 @@
 #include <vector>
 #include <cstring>
 #include <sstream>
+#include <map>
+#include <cstdint>
+#include <chrono>
+#include <algorithm>
 
 #if defined(_MSC_VER)
 #pragma warning(disable: 4244 4267) // possible loss of data
@@ -492,6 +496,94 @@ void get_req_parameters(const Request & req, whisper_params & params)
 
 }  // namespace
 
+// Run a translation pass (into English) over the same audio and attach each translated
+// segment to the original segment its midpoint falls in, or the nearest one, as "translation".
+// Returns false if the pass failed; the untranslated segments are still usable then.
+bool attach_translations(struct whisper_context * ctx, whisper_full_params wparams, const float * samples, int n_samples, json & segments) {
+    wparams.translate = true;
+    if (segments.empty() || whisper_full(ctx, wparams, samples, n_samples) != 0) {
+        return false;
+    }
+    const int n_translated = whisper_full_n_segments(ctx);
+    for (int i = 0; i < n_translated; ++i) {
+        const int64_t midpoint = (whisper_full_get_segment_t0(ctx, i) + whisper_full_get_segment_t1(ctx, i)) / 2;
+        // Audio past the last segment is still open (streaming) and translated later
+        if (midpoint > segments.back()["t1"].get<int64_t>()) {
+            continue;
+        }
+        size_t best = 0;
+        int64_t best_distance = INT64_MAX;
+        for (size_t j = 0; j < segments.size(); ++j) {
+            const int64_t t0 = segments[j]["t0"].get<int64_t>();
+            const int64_t t1 = segments[j]["t1"].get<int64_t>();
+            const int64_t distance = midpoint < t0 ? t0 - midpoint : std::max<int64_t>(0, midpoint - t1);
+            if (distance < best_distance) {
+                best = j;
+                best_distance = distance;
+            }
+        }
+        std::string translation = segments[best].value("translation", "");
+        if (!translation.empty()) {
+            translation += " ";
+        }
+        translation += whisper_full_get_segment_text(ctx, i);
+        segments[best]["translation"] = translation;
+    }
+    return true;
+}
+
+// Words of segment `i` with their times (whisper's 10 ms units) and confidence, the lowest
+// probability among the word's tokens. A token starting with a space starts a new word.
+json segment_words(struct whisper_context * ctx, int i) {
+    json words = json::array();
+    const int n_tokens = whisper_full_n_tokens(ctx, i);
+    for (int j = 0; j < n_tokens; ++j) {
+        const whisper_token_data token = whisper_full_get_token_data(ctx, i, j);
+        if (token.id >= whisper_token_eot(ctx)) {
+            continue;
+        }
+        const std::string text = whisper_full_get_token_text(ctx, i, j);
+        if (words.empty() || (!text.empty() && text[0] == ' ')) {
+            const size_t start = text.find_first_not_of(' ');
+            if (start == std::string::npos) {
+                continue;
+            }
+            words.push_back({{"word", text.substr(start)}, {"t0", token.t0}, {"t1", token.t1}, {"p", token.p}});
+        } else {
+            json & word = words.back();
+            word["word"] = word["word"].get<std::string>() + text;
+            word["t1"] = token.t1;
+            word["p"] = std::min(word["p"].get<float>(), token.p);
+        }
+    }
+    return words;
+}
+
+// Raw sample formats accepted by /stream and /stream/frame, advertised by /capabilities
+const std::vector<std::string> stream_sample_formats = {"f32le", "s16le"};
+
+// Decode a raw audio upload in one of `stream_sample_formats` to f32 samples.
+// Returns false for an unknown format.
+bool decode_raw_audio(const std::string & content, const std::string & format, std::vector<float> & samples) {
+    if (format.empty() || format == "f32le") {
+        const size_t n_samples = content.size() / sizeof(float);
+        samples.resize(n_samples);
+        std::memcpy(samples.data(), content.data(), n_samples * sizeof(float));
+        return true;
+    }
+    if (format == "s16le") {
+        const size_t n_samples = content.size() / sizeof(int16_t);
+        samples.resize(n_samples);
+        for (size_t i = 0; i < n_samples; ++i) {
+            int16_t sample;
+            std::memcpy(&sample, content.data() + i * sizeof(int16_t), sizeof(int16_t));
+            samples[i] = sample / 32768.0f;
+        }
+        return true;
+    }
+    return false;
+}
+
 int main(int argc, char ** argv) {
     whisper_params params;
     server_params sparams;
@@ -678,6 +770,33 @@ int main(int argc, char ** argv) {
     svr.Options(sparams.request_path + sparams.inference_path, [&](const Request &, Response &){
     });
 
+    // What clients of the raw streaming endpoints can rely on for the loaded model
+    svr.Get(sparams.request_path + "/capabilities", [&](const Request &, Response &res){
+        std::lock_guard<std::mutex> lock(whisper_mutex);
+
+        const bool multilingual = whisper_is_multilingual(ctx);
+        json languages = json::array();
+        if (multilingual) {
+            for (int id = 0; id <= whisper_lang_max_id(); ++id) {
+                languages.push_back(whisper_lang_str(id));
+            }
+        } else {
+            languages.push_back("en");
+        }
+
+        json capabilities = {
+            {"sample_rate", WHISPER_SAMPLE_RATE},
+            {"sample_formats", stream_sample_formats},
+            {"languages", languages},
+            {"language_detection", multilingual},
+            {"translation", multilingual},
+            {"streaming", true},
+            {"diarization", false},
+            {"word_timestamps", true},
+        };
+        res.set_content(capabilities.dump(-1, ' ', false, json::error_handler_t::replace), "application/json");
+    });
+
     svr.Post(sparams.request_path + sparams.inference_path, [&](const Request &req, Response &res){
         // acquire whisper model mutex lock
         std::lock_guard<std::mutex> lock(whisper_mutex);
@@ -1008,12 +1127,19 @@ int main(int argc, char ** argv) {
             return;
         }
 
-        auto audio_file = req.get_file_value("audio");
-        const float* audio_data = reinterpret_cast<const float*>(audio_file.content.c_str());
-        int n_samples = audio_file.content.size() / sizeof(float);
+        std::vector<float> samples;
+        const std::string format = req.has_file("format") ? req.get_file_value("format").content : "";
+        if (!decode_raw_audio(req.get_file_value("audio").content, format, samples)) {
+            res.set_content("{\"error\":\"unsupported sample format\"}", "application/json");
+            return;
+        }
 
         // Add new samples to buffer
-        audio_buffer.insert(audio_buffer.end(), audio_data, audio_data + n_samples);
+        audio_buffer.insert(audio_buffer.end(), samples.begin(), samples.end());
+
+        // Per-request language; "auto" lets whisper detect it
+        const std::string language = req.has_file("language") ? req.get_file_value("language").content : params.language;
+        const bool translate = req.has_file("translate") && req.get_file_value("translate").content == "true";
 
         // Calculate minimum required samples
         const int min_samples = (MIN_AUDIO_LENGTH_MS * 16000) / 1000;
@@ -1027,8 +1153,9 @@ int main(int argc, char ** argv) {
             whisper_full_params wparams = whisper_full_default_params(WHISPER_SAMPLING_GREEDY);
             wparams.print_progress = false;
             wparams.print_special = params.print_special;
-            wparams.language = params.language.c_str();
+            wparams.language = language.c_str();
             wparams.n_threads = params.n_threads;
+            wparams.token_timestamps = true;
             
             if (whisper_full(ctx, wparams, audio_buffer.data(), audio_buffer.size()) != 0) {
                 res.set_content("{\"error\":\"failed to process audio\"}", "application/json");
@@ -1037,6 +1164,9 @@ int main(int argc, char ** argv) {
 
             // Get transcription
             const int n_segments = whisper_full_n_segments(ctx);
+            if (const char * detected = whisper_lang_str(whisper_full_lang_id(ctx))) {
+                response["language"] = detected;
+            }
             
             for (int i = 0; i < n_segments; ++i) {
                 const char* text = whisper_full_get_segment_text(ctx, i);
@@ -1047,9 +1177,14 @@ int main(int argc, char ** argv) {
                 segment["text"] = text;
                 segment["t0"] = t0;
                 segment["t1"] = t1;
+                segment["words"] = segment_words(ctx, i);
                 response["segments"].push_back(segment);
             }
 
+            if (translate && !attach_translations(ctx, wparams, audio_buffer.data(), audio_buffer.size(), response["segments"])) {
+                fprintf(stderr, "%s: translation pass failed\n", __func__);
+            }
+
             // Keep a small overlap for context
             const int overlap_samples = (200 * 16000) / 1000; // 200ms overlap
             if (audio_buffer.size() > overlap_samples) {
@@ -1063,6 +1198,135 @@ int main(int argc, char ** argv) {
         res.set_content(response.dump(), "application/json");
     });
 
+    // Incremental streaming: clients send ~1 second frames tagged with a session id and get
+    // back the segments that are final plus a partial transcript of the audio still open.
+    // Each session keeps its own buffer; audio is dropped once its segments are committed.
+    struct stream_session {
+        std::vector<float> buffer;
+        int64_t buffer_start_ms = 0;
+        std::chrono::steady_clock::time_point last_seen;
+    };
+    std::map<std::string, stream_session> stream_sessions;
+    // Segments ending this close to the end of the buffer may still change with more audio
+    const int64_t STREAM_HOLDBACK_MS = 1500;
+    // Past this length everything but the last segment is committed regardless
+    const int64_t STREAM_MAX_BUFFER_MS = 10000;
+    const auto STREAM_SESSION_TIMEOUT = std::chrono::minutes(5);
+
+    svr.Post(sparams.request_path + "/stream/frame", [&](const Request &req, Response &res) {
+        std::lock_guard<std::mutex> lock(whisper_mutex);
+
+        if (!req.has_file("session")) {
+            res.set_content("{\"error\":\"no session id\"}", "application/json");
+            return;
+        }
+        const std::string session_id = req.get_file_value("session").content;
+        const bool is_final = req.has_file("final") && req.get_file_value("final").content == "true";
+        const std::string language = req.has_file("language") ? req.get_file_value("language").content : params.language;
+        const bool translate = req.has_file("translate") && req.get_file_value("translate").content == "true";
+        int64_t offset_ms = 0;
+        if (req.has_file("offset_ms")) {
+            try {
+                offset_ms = std::stoll(req.get_file_value("offset_ms").content);
+            } catch (const std::exception &) {
+                res.set_content("{\"error\":\"invalid offset_ms\"}", "application/json");
+                return;
+            }
+        }
+
+        // Forget sessions whose client went away without a final frame
+        const auto now = std::chrono::steady_clock::now();
+        for (auto it = stream_sessions.begin(); it != stream_sessions.end();) {
+            if (now - it->second.last_seen > STREAM_SESSION_TIMEOUT) {
+                it = stream_sessions.erase(it);
+            } else {
+                ++it;
+            }
+        }
+
+        stream_session &session = stream_sessions[session_id];
+        session.last_seen = now;
+        if (req.has_file("audio")) {
+            std::vector<float> samples;
+            const std::string format = req.has_file("format") ? req.get_file_value("format").content : "";
+            if (!decode_raw_audio(req.get_file_value("audio").content, format, samples)) {
+                res.set_content("{\"error\":\"unsupported sample format\"}", "application/json");
+                return;
+            }
+            if (session.buffer.empty()) {
+                session.buffer_start_ms = offset_ms;
+            }
+            session.buffer.insert(session.buffer.end(), samples.begin(), samples.end());
+        }
+
+        json response;
+        response["segments"] = json::array();
+        response["partial"] = "";
+        response["buffer_start_ms"] = session.buffer_start_ms;
+
+        const int64_t buffer_ms = (int64_t) session.buffer.size() * 1000 / WHISPER_SAMPLE_RATE;
+        const int min_samples = (MIN_AUDIO_LENGTH_MS * WHISPER_SAMPLE_RATE) / 1000;
+        if ((int) session.buffer.size() >= min_samples || (is_final && !session.buffer.empty())) {
+            whisper_full_params wparams = whisper_full_default_params(WHISPER_SAMPLING_GREEDY);
+            wparams.print_progress = false;
+            wparams.print_special = params.print_special;
+            wparams.language = language.c_str();
+            wparams.n_threads = params.n_threads;
+            wparams.no_context = true;
+            wparams.token_timestamps = true;
+
+            if (whisper_full(ctx, wparams, session.buffer.data(), session.buffer.size()) != 0) {
+                res.set_content("{\"error\":\"failed to process audio\"}", "application/json");
+                return;
+            }
+
+            const int n_segments = whisper_full_n_segments(ctx);
+            if (const char * detected = whisper_lang_str(whisper_full_lang_id(ctx))) {
+                response["language"] = detected;
+            }
+            int64_t commit_before_ms = buffer_ms - STREAM_HOLDBACK_MS;
+            if (is_final) {
+                commit_before_ms = buffer_ms;
+            } else if (buffer_ms > STREAM_MAX_BUFFER_MS && n_segments > 1) {
+                commit_before_ms = std::max(commit_before_ms, whisper_full_get_segment_t1(ctx, n_segments - 2) * 10);
+            }
+
+            int64_t committed_ms = 0;
+            std::string partial;
+            for (int i = 0; i < n_segments; ++i) {
+                const char* text = whisper_full_get_segment_text(ctx, i);
+                const int64_t t0 = whisper_full_get_segment_t0(ctx, i);
+                const int64_t t1 = whisper_full_get_segment_t1(ctx, i);
+                if (partial.empty() && t1 * 10 <= commit_before_ms) {
+                    json segment;
+                    segment["text"] = text;
+                    segment["t0"] = t0;
+                    segment["t1"] = t1;
+                    segment["words"] = segment_words(ctx, i);
+                    response["segments"].push_back(segment);
+                    committed_ms = t1 * 10;
+                } else {
+                    partial += text;
+                }
+            }
+            response["partial"] = partial;
+            if (translate && !attach_translations(ctx, wparams, session.buffer.data(), session.buffer.size(), response["segments"])) {
+                fprintf(stderr, "%s: translation pass failed\n", __func__);
+            }
+
+            // Only audio behind committed segments is dropped; the rest is decoded again next time
+            const size_t committed_samples = std::min(session.buffer.size(), (size_t) (committed_ms * WHISPER_SAMPLE_RATE / 1000));
+            session.buffer.erase(session.buffer.begin(), session.buffer.begin() + committed_samples);
+            session.buffer_start_ms += committed_ms;
+        }
+
+        if (is_final) {
+            stream_sessions.erase(session_id);
+        }
+        response["buffer_size_ms"] = buffer_ms;
+        res.set_content(response.dump(), "application/json");
+    });
+
     svr.Post(sparams.request_path + "/load", [&](const Request &req, Response &res){
         std::lock_guard<std::mutex> lock(whisper_mutex);
         if (!req.has_file("model"))
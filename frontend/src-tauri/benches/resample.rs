@@ -0,0 +1,33 @@
+// Compares the sinc resampler used for whisper input against linear interpolation.
+// Run with `cargo bench --bench resample`.
+use app_lib::audio::audio_processing::{resample_linear, resample_sinc};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// A few speech-band tones plus one above 8kHz that must not alias into the output
+fn test_signal(sample_rate: u32, seconds: usize) -> Vec<f32> {
+    (0..sample_rate as usize * seconds)
+        .map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            let tone = |hz: f32| (2.0 * std::f32::consts::PI * hz * t).sin();
+            0.3 * tone(220.0) + 0.2 * tone(1000.0) + 0.1 * tone(3400.0) + 0.1 * tone(12000.0)
+        })
+        .collect()
+}
+
+fn bench_resample(c: &mut Criterion) {
+    let mut group = c.benchmark_group("resample_to_16k");
+    for &(from_rate, seconds) in &[(48000u32, 1usize), (48000, 30), (44100, 30)] {
+        let input = test_signal(from_rate, seconds);
+        let label = format!("{}Hz_{}s", from_rate, seconds);
+        group.bench_with_input(BenchmarkId::new("sinc", &label), &input, |b, input| {
+            b.iter(|| resample_sinc(black_box(input), from_rate, 16000).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("linear", &label), &input, |b, input| {
+            b.iter(|| resample_linear(black_box(input), from_rate, 16000))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_resample);
+criterion_main!(benches);
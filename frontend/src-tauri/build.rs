@@ -1,4 +1,9 @@
 fn main() {
+    // Baked in by analytics::AnalyticsConfig::from_build_env
+    println!("cargo:rerun-if-env-changed=MEETILY_POSTHOG_KEY");
+    println!("cargo:rerun-if-env-changed=MEETILY_POSTHOG_HOST");
+    // Baked in by crash::upload_url
+    println!("cargo:rerun-if-env-changed=MEETILY_CRASH_REPORT_URL");
     #[cfg(target_os = "macos")]
     println!("cargo:rustc-link-lib=framework=AVFoundation");
     tauri_build::build()
@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use log::{info as log_info, warn as log_warn};
+
+use crate::api::{make_api_request, MeetingDetails, SummaryResponse};
+use crate::atomic_file;
+use crate::summary::MeetingSummary;
+use crate::voice_commands::{self, VoiceCommandKind};
+
+const ACTION_ITEMS_FILE: &str = "action_items.json";
+// Token overlap at which an item from a new meeting counts as the same open item
+const CARRY_FORWARD_SIMILARITY: f64 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionItemStatus {
+    Open,
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionItem {
+    pub id: String,
+    pub text: String,
+    pub owner: Option<String>,
+    pub status: ActionItemStatus,
+    pub source_meeting_id: String,
+    /// Most recent meeting the item was mentioned in
+    pub last_seen_meeting_id: String,
+    /// How many later meetings repeated the item while it was still open
+    pub carried_forward_count: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+struct SyncActionItemsRequest<'a> {
+    items: &'a [ActionItem],
+}
+
+#[derive(Debug, Deserialize)]
+struct ActionItemsResponse {
+    items: Vec<ActionItem>,
+}
+
+fn items_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(ACTION_ITEMS_FILE))
+}
+
+fn load_items<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<ActionItem>, String> {
+    let path = items_path(app)?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = atomic_file::read_verified(&path).map_err(|e| format!("Failed to read action items: {}", e))?;
+    Ok(serde_json::from_slice(&data).unwrap_or_else(|e| {
+        log_warn!("Ignoring malformed action items file: {}", e);
+        Vec::new()
+    }))
+}
+
+fn save_items<R: Runtime>(app: &AppHandle<R>, items: &[ActionItem]) -> Result<(), String> {
+    let data = serde_json::to_vec_pretty(items).map_err(|e| e.to_string())?;
+    atomic_file::write(items_path(app)?, data).map_err(|e| format!("Failed to write action items: {}", e))?;
+    let _ = app.emit("action-items-updated", items.len());
+    Ok(())
+}
+
+fn tokens(text: &str) -> HashSet<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 2)
+        .map(|word| word.to_string())
+        .collect()
+}
+
+fn similarity(a: &str, b: &str) -> f64 {
+    let (a, b) = (tokens(a), tokens(b));
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / a.union(&b).count() as f64
+}
+
+// Attendees named in the summary or the linked calendar event
+fn participants(summary: Option<&MeetingSummary>, metadata: Option<&Value>) -> Vec<String> {
+    let mut names: Vec<String> = summary.map(|summary| summary.attendees.clone()).unwrap_or_default();
+    if let Some(candidates) = metadata.and_then(|m| m.get("speaker_candidates")).and_then(Value::as_array) {
+        names.extend(candidates.iter().filter_map(Value::as_str).map(str::to_string));
+    }
+    names.retain(|name| !name.trim().is_empty());
+    names
+}
+
+// A participant by full or first name, or a diarization label such as "Speaker 2"
+fn is_participant(name: &str, participants: &[String]) -> bool {
+    let name = name.to_lowercase();
+    let is_speaker_label = name
+        .strip_prefix("speaker ")
+        .is_some_and(|number| !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()));
+    is_speaker_label
+        || participants.iter().any(|participant| {
+            let participant = participant.trim().to_lowercase();
+            participant == name || participant.split_whitespace().next() == Some(name.as_str())
+        })
+}
+
+// Recognizes "Alice: send the deck", "Send the deck (Owner: Alice)" and "@alice send the deck".
+// A leading "Word:" only names an owner if it is a participant, so "Note: send the deck" has none.
+fn split_owner(content: &str, participants: &[String]) -> (String, Option<String>) {
+    let content = content.trim();
+    if let Some(start) = content.find("(Owner:").or_else(|| content.find("(owner:")) {
+        if let Some(end) = content[start..].find(')') {
+            let owner = content[start + 7..start + end].trim().to_string();
+            let text = format!("{}{}", &content[..start], &content[start + end + 1..]);
+            return (text.trim().to_string(), Some(owner).filter(|o| !o.is_empty()));
+        }
+    }
+    if let Some(rest) = content.strip_prefix('@') {
+        if let Some((owner, text)) = rest.split_once(char::is_whitespace) {
+            return (text.trim().to_string(), Some(owner.to_string()));
+        }
+    }
+    if let Some((owner, text)) = content.split_once(':') {
+        let owner = owner.trim();
+        if is_participant(owner, participants) && !text.trim().is_empty() {
+            return (text.trim().to_string(), Some(owner.to_string()));
+        }
+    }
+    (content.to_string(), None)
+}
+
+/// The summary's action items; owners written into the text are split out when the
+/// summary does not name one.
+fn extract_from_summary(summary: &MeetingSummary, participants: &[String]) -> Vec<(String, Option<String>)> {
+    summary
+        .action_items
+        .iter()
+        .map(|item| match &item.owner {
+            Some(owner) => (item.text.trim().to_string(), Some(owner.clone())),
+            None => split_owner(item.text.trim(), participants),
+        })
+        .collect()
+}
+
+/// Action items flagged by voice command while the meeting was recorded.
+fn extract_from_voice_commands(metadata: Option<&Value>, participants: &[String]) -> Vec<(String, Option<String>)> {
+    voice_commands::from_metadata(metadata)
+        .into_iter()
+        .filter(|command| command.kind == VoiceCommandKind::ActionItem)
+        .map(|command| split_owner(&command.text, participants))
+        .filter(|(text, _)| !text.is_empty())
+        .collect()
+}
+
+/// Merge items from a meeting into the tracker. An open item from an earlier meeting that
+/// reappears is carried forward instead of duplicated.
+fn merge_meeting_items(items: &mut Vec<ActionItem>, meeting_id: &str, extracted: Vec<(String, Option<String>)>) -> Vec<String> {
+    let now = Utc::now();
+    let mut touched = Vec::new();
+
+    for (text, owner) in extracted {
+        // Re-importing the same meeting must not add its items twice
+        if items.iter().any(|i| i.last_seen_meeting_id == meeting_id && similarity(&i.text, &text) >= CARRY_FORWARD_SIMILARITY) {
+            continue;
+        }
+
+        let carried = items
+            .iter_mut()
+            .filter(|i| i.status == ActionItemStatus::Open && i.last_seen_meeting_id != meeting_id)
+            .map(|i| (similarity(&i.text, &text), i))
+            .filter(|(score, _)| *score >= CARRY_FORWARD_SIMILARITY)
+            .max_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        match carried {
+            Some((_, item)) => {
+                item.last_seen_meeting_id = meeting_id.to_string();
+                item.carried_forward_count += 1;
+                if item.owner.is_none() {
+                    item.owner = owner;
+                }
+                item.updated_at = now;
+                log_info!("Action item {} carried forward into meeting {}", item.id, meeting_id);
+                touched.push(item.id.clone());
+            }
+            None => {
+                let item = ActionItem {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    text,
+                    owner,
+                    status: ActionItemStatus::Open,
+                    source_meeting_id: meeting_id.to_string(),
+                    last_seen_meeting_id: meeting_id.to_string(),
+                    carried_forward_count: 0,
+                    created_at: now,
+                    updated_at: now,
+                };
+                touched.push(item.id.clone());
+                items.push(item);
+            }
+        }
+    }
+    touched
+}
+
+// Keep whichever copy of each item was updated last
+fn merge_remote(local: Vec<ActionItem>, remote: Vec<ActionItem>) -> Vec<ActionItem> {
+    let mut merged: HashMap<String, ActionItem> = local.into_iter().map(|i| (i.id.clone(), i)).collect();
+    for item in remote {
+        match merged.get(&item.id) {
+            Some(existing) if existing.updated_at >= item.updated_at => {}
+            _ => {
+                merged.insert(item.id.clone(), item);
+            }
+        }
+    }
+    let mut items: Vec<ActionItem> = merged.into_values().collect();
+    items.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    items
+}
+
+// Best effort: the local file stays authoritative when the backend is unreachable
+async fn push_to_backend<R: Runtime>(app: &AppHandle<R>, items: &[ActionItem], auth_token: Option<String>) -> Result<Vec<ActionItem>, String> {
+    let body = serde_json::to_string(&SyncActionItemsRequest { items }).map_err(|e| e.to_string())?;
+    let response = make_api_request::<R, ActionItemsResponse>(app, "/sync-action-items", "POST", Some(&body), None, auth_token).await?;
+    Ok(response.items)
+}
+
+async fn save_and_sync<R: Runtime>(app: &AppHandle<R>, items: Vec<ActionItem>, auth_token: Option<String>) -> Result<Vec<ActionItem>, String> {
+    save_items(app, &items)?;
+    match push_to_backend(app, &items, auth_token).await {
+        Ok(remote) => {
+            let merged = merge_remote(items, remote);
+            save_items(app, &merged)?;
+            Ok(merged)
+        }
+        Err(e) => {
+            log_warn!("Action items saved locally but not synced: {}", e);
+            Ok(items)
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn list_action_items<R: Runtime>(
+    app: AppHandle<R>,
+    status: Option<ActionItemStatus>,
+    meeting_id: Option<String>,
+) -> Result<Vec<ActionItem>, String> {
+    let items = load_items(&app)?;
+    Ok(items
+        .into_iter()
+        .filter(|i| status.is_none() || status == Some(i.status))
+        .filter(|i| match meeting_id.as_deref() {
+            Some(id) => i.source_meeting_id == id || i.last_seen_meeting_id == id,
+            None => true,
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub async fn update_action_item<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    status: Option<ActionItemStatus>,
+    owner: Option<String>,
+    text: Option<String>,
+    auth_token: Option<String>,
+) -> Result<ActionItem, String> {
+    let mut items = load_items(&app)?;
+    let item = items
+        .iter_mut()
+        .find(|i| i.id == id)
+        .ok_or_else(|| format!("Action item not found: {}", id))?;
+
+    if let Some(status) = status {
+        item.status = status;
+    }
+    if let Some(owner) = owner {
+        item.owner = Some(owner.trim().to_string()).filter(|o| !o.is_empty());
+    }
+    if let Some(text) = text {
+        if text.trim().is_empty() {
+            return Err("Action item text cannot be empty".to_string());
+        }
+        item.text = text.trim().to_string();
+    }
+    item.updated_at = Utc::now();
+    let updated = item.clone();
+
+    save_and_sync(&app, items, auth_token).await?;
+    log_info!("Updated action item {}", id);
+    Ok(updated)
+}
+
+/// Pull the action items out of a meeting's summary and its spoken "action item" commands
+/// into the tracker and return the items that were created or carried forward.
+#[tauri::command]
+pub async fn import_meeting_action_items<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<Vec<ActionItem>, String> {
+    log_info!("import_meeting_action_items called for meeting_id: {}", meeting_id);
+
+    let summary = make_api_request::<R, SummaryResponse>(
+        &app,
+        &format!("/get-summary/{}", meeting_id),
+        "GET",
+        None,
+        None,
+        auth_token.clone(),
+    )
+    .await?
+    .summary()?;
+    let meeting = make_api_request::<R, MeetingDetails>(
+        &app,
+        &format!("/get-meeting/{}", meeting_id),
+        "GET",
+        None,
+        None,
+        auth_token.clone(),
+    )
+    .await?;
+
+    let participants = participants(summary.as_ref(), meeting.metadata.as_ref());
+    let mut extracted = summary.as_ref().map(|summary| extract_from_summary(summary, &participants)).unwrap_or_default();
+    extracted.extend(extract_from_voice_commands(meeting.metadata.as_ref(), &participants));
+    if summary.is_none() && extracted.is_empty() {
+        return Err(format!("Meeting {} has no completed summary", meeting_id));
+    }
+
+    let mut items = load_items(&app)?;
+    let touched = merge_meeting_items(&mut items, &meeting_id, extracted);
+    let items = save_and_sync(&app, items, auth_token).await?;
+
+    log_info!("Imported {} action items from meeting {}", touched.len(), meeting_id);
+    Ok(items.into_iter().filter(|i| touched.contains(&i.id)).collect())
+}
+
+/// Exchange action items with the backend so other clients see the same list.
+#[tauri::command]
+pub async fn sync_action_items<R: Runtime>(app: AppHandle<R>, auth_token: Option<String>) -> Result<Vec<ActionItem>, String> {
+    let local = load_items(&app)?;
+    let remote = push_to_backend(&app, &local, auth_token).await?;
+    let merged = merge_remote(local, remote);
+    save_items(&app, &merged)?;
+    log_info!("Synced {} action items", merged.len());
+    Ok(merged)
+}
@@ -0,0 +1,158 @@
+// In-app activity feed. Things the user should hear about (a recording was saved, a summary
+// is ready, transcript sync keeps failing, audio was dropped) are written to the local store
+// as well as announced, so events that fired while the window was closed are still there.
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{error as log_error, warn as log_warn};
+
+use crate::storage::with_connection;
+
+// Oldest entries beyond this many are pruned as new ones arrive
+const MAX_ACTIVITY_ENTRIES: i64 = 500;
+const DEFAULT_FEED_LIMIT: u32 = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    RecordingFinished,
+    SummaryReady,
+    SyncFailed,
+    ChunksDropped,
+    DataPurged,
+}
+
+impl ActivityKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ActivityKind::RecordingFinished => "recording_finished",
+            ActivityKind::SummaryReady => "summary_ready",
+            ActivityKind::SyncFailed => "sync_failed",
+            ActivityKind::ChunksDropped => "chunks_dropped",
+            ActivityKind::DataPurged => "data_purged",
+        }
+    }
+
+    fn from_name(kind: &str) -> Option<Self> {
+        [Self::RecordingFinished, Self::SummaryReady, Self::SyncFailed, Self::ChunksDropped, Self::DataPurged]
+            .into_iter()
+            .find(|k| k.as_str() == kind)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityEntry {
+    pub id: i64,
+    pub kind: ActivityKind,
+    pub message: String,
+    pub meeting_id: Option<String>,
+    pub created_at: String,
+    pub read: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ActivityFeed {
+    pub entries: Vec<ActivityEntry>,
+    pub unread: u32,
+}
+
+/// Add an entry to the feed. With a `dedupe_key`, only the first entry with that key is
+/// kept, for events that can be reported repeatedly (e.g. a summary polled as ready).
+/// Returns whether a new entry was added.
+pub(crate) fn record<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: ActivityKind,
+    message: &str,
+    meeting_id: Option<&str>,
+    dedupe_key: Option<&str>,
+) -> bool {
+    let result = with_connection(app, "record activity", |conn| {
+        let inserted = conn.execute(
+            "INSERT OR IGNORE INTO activity (kind, message, meeting_id, dedupe_key, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![kind.as_str(), message, meeting_id, dedupe_key, Utc::now().to_rfc3339()],
+        )?;
+        if inserted == 0 {
+            return Ok(None);
+        }
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "DELETE FROM activity WHERE id <= (SELECT id FROM activity ORDER BY id DESC LIMIT 1 OFFSET ?1)",
+            [MAX_ACTIVITY_ENTRIES],
+        )?;
+        Ok(Some(id))
+    });
+
+    match result {
+        Ok(Some(id)) => {
+            let entry = ActivityEntry {
+                id,
+                kind,
+                message: message.to_string(),
+                meeting_id: meeting_id.map(str::to_string),
+                created_at: Utc::now().to_rfc3339(),
+                read: false,
+            };
+            if let Err(e) = app.emit("activity-added", &entry) {
+                log_error!("Failed to emit activity-added event: {}", e);
+            }
+            true
+        }
+        Ok(None) => false,
+        Err(e) => {
+            log_warn!("{}", e);
+            false
+        }
+    }
+}
+
+/// Most recent entries first, with the number of unread ones.
+#[tauri::command]
+pub async fn get_activity_feed<R: Runtime>(
+    app: AppHandle<R>,
+    limit: Option<u32>,
+    unread_only: Option<bool>,
+) -> Result<ActivityFeed, String> {
+    let limit = limit.unwrap_or(DEFAULT_FEED_LIMIT);
+    let unread_only = unread_only.unwrap_or(false);
+    with_connection(&app, "read activity feed", |conn| {
+        let mut query = conn.prepare(
+            "SELECT id, kind, message, meeting_id, created_at, read_at FROM activity
+             WHERE (?1 = 0 OR read_at IS NULL) ORDER BY id DESC LIMIT ?2",
+        )?;
+        let entries = query
+            .query_map(params![unread_only, limit], |row| {
+                // Kinds written by a newer version are left out rather than failing the feed
+                let Some(kind) = ActivityKind::from_name(&row.get::<_, String>(1)?) else { return Ok(None) };
+                Ok(Some(ActivityEntry {
+                    id: row.get(0)?,
+                    kind,
+                    message: row.get(2)?,
+                    meeting_id: row.get(3)?,
+                    created_at: row.get(4)?,
+                    read: row.get::<_, Option<String>>(5)?.is_some(),
+                }))
+            })?
+            .filter_map(Result::transpose)
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        let unread = conn.query_row("SELECT COUNT(*) FROM activity WHERE read_at IS NULL", [], |row| row.get(0))?;
+        Ok(ActivityFeed { entries, unread })
+    })
+}
+
+/// Mark the given entries as read, or all of them when `ids` is omitted.
+#[tauri::command]
+pub async fn mark_activity_read<R: Runtime>(app: AppHandle<R>, ids: Option<Vec<i64>>) -> Result<u32, String> {
+    let now = Utc::now().to_rfc3339();
+    let marked = with_connection(&app, "mark activity as read", |conn| match &ids {
+        None => conn.execute("UPDATE activity SET read_at = ?1 WHERE read_at IS NULL", [&now]),
+        Some(ids) => {
+            let mut update = conn.prepare("UPDATE activity SET read_at = ?1 WHERE id = ?2 AND read_at IS NULL")?;
+            ids.iter().try_fold(0, |marked, id| -> rusqlite::Result<usize> { Ok(marked + update.execute(params![now, id])?) })
+        }
+    })?;
+    if let Err(e) = app.emit("activity-read", marked) {
+        log_error!("Failed to emit activity-read event: {}", e);
+    }
+    Ok(marked as u32)
+}
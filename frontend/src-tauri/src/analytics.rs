@@ -1,10 +1,24 @@
-use posthog_rs::{Client, Event};
+use posthog_rs::{Client, ClientOptions, ClientOptionsBuilder, Event};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tokio::sync::Mutex;
 use uuid::Uuid;
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Utc};
+
+use crate::atomic_file;
+
+const DEFAULT_HOST: &str = "https://us.i.posthog.com";
+pub const QUEUE_FILE: &str = "analytics-queue.json";
+// Queued events are sent every FLUSH_INTERVAL, or as soon as FLUSH_THRESHOLD have piled up
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+const FLUSH_THRESHOLD: usize = 20;
+const MAX_BATCH_SIZE: usize = 100;
+// Oldest events are dropped beyond this, e.g. after a long time offline
+const MAX_QUEUED_EVENTS: usize = 1000;
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30 * 60);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AnalyticsConfig {
@@ -17,12 +31,35 @@ impl Default for AnalyticsConfig {
     fn default() -> Self {
         Self {
             api_key: String::new(),
-            host: Some("https://us.i.posthog.com".to_string()),
+            host: Some(DEFAULT_HOST.to_string()),
             enabled: false,
         }
     }
 }
 
+impl AnalyticsConfig {
+    /// The PostHog project set at build time through `MEETILY_POSTHOG_KEY` and, optionally,
+    /// `MEETILY_POSTHOG_HOST`. Builds without a key don't track anything.
+    pub fn from_build_env() -> Self {
+        let api_key = option_env!("MEETILY_POSTHOG_KEY").unwrap_or_default().trim().to_string();
+        Self {
+            enabled: !api_key.is_empty(),
+            api_key,
+            host: Some(option_env!("MEETILY_POSTHOG_HOST").unwrap_or(DEFAULT_HOST).to_string()),
+        }
+    }
+
+    fn client_options(&self) -> Option<ClientOptions> {
+        let host = self.host.as_deref().map(str::trim).filter(|host| !host.is_empty()).unwrap_or(DEFAULT_HOST);
+        ClientOptionsBuilder::default()
+            .api_key(self.api_key.trim().to_string())
+            .api_endpoint(format!("{}/i/v0/e/", host.trim_end_matches('/')))
+            .build()
+            .map_err(|e| eprintln!("Invalid analytics configuration: {}", e))
+            .ok()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserSession {
     pub session_id: String,
@@ -47,26 +84,187 @@ impl UserSession {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedEvent {
+    name: String,
+    distinct_id: String,
+    properties: HashMap<String, String>,
+    queued_at: DateTime<Utc>,
+}
+
+impl QueuedEvent {
+    fn new(name: &str, distinct_id: &str, properties: HashMap<String, String>) -> Self {
+        Self { name: name.to_string(), distinct_id: distinct_id.to_string(), properties, queued_at: Utc::now() }
+    }
+
+    fn to_event(&self) -> Event {
+        let mut event = Event::new(&self.name, &self.distinct_id);
+        for (key, value) in &self.properties {
+            if let Err(e) = event.insert_prop(key, value.clone()) {
+                eprintln!("Failed to add property {}: {}", key, e);
+            }
+        }
+        event
+    }
+}
+
+/// Events waiting to be sent. The queue is mirrored to disk, so events tracked while offline
+/// or just before quitting are sent later instead of being lost.
+#[derive(Default)]
+struct EventQueue {
+    events: VecDeque<QueuedEvent>,
+    path: Option<PathBuf>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl EventQueue {
+    fn load(path: Option<PathBuf>) -> Self {
+        let events = path
+            .as_ref()
+            .filter(|path| path.exists())
+            .and_then(|path| atomic_file::read_verified(path).map_err(|e| eprintln!("Failed to read analytics queue: {}", e)).ok())
+            .and_then(|data| serde_json::from_slice(&data).map_err(|e| eprintln!("Ignoring malformed analytics queue: {}", e)).ok())
+            .unwrap_or_default();
+        Self { events, path, ..Default::default() }
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_vec(&self.events)
+            .map_err(|e| e.to_string())
+            .and_then(|data| atomic_file::write(path, data).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            eprintln!("Failed to write analytics queue: {}", e);
+        }
+    }
+
+    fn enforce_cap(&mut self) {
+        while self.events.len() > MAX_QUEUED_EVENTS {
+            self.events.pop_front();
+        }
+    }
+
+    fn backing_off(&self) -> bool {
+        self.retry_at.is_some_and(|at| Instant::now() < at)
+    }
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    (FLUSH_INTERVAL * 2_u32.pow(failures.min(5))).min(MAX_RETRY_DELAY)
+}
+
+/// Send queued events in batches until the queue is empty or a send fails. Failed batches go
+/// back to the front of the queue and sending pauses with exponential backoff.
+async fn flush_queue(client: &Client, queue: &Mutex<EventQueue>) {
+    loop {
+        let batch: Vec<QueuedEvent> = {
+            let mut queue = queue.lock().await;
+            if queue.events.is_empty() || queue.backing_off() {
+                return;
+            }
+            let count = queue.events.len().min(MAX_BATCH_SIZE);
+            queue.events.drain(..count).collect()
+        };
+
+        let result = client.capture_batch(batch.iter().map(QueuedEvent::to_event).collect()).await;
+        let mut queue = queue.lock().await;
+        match result {
+            Ok(()) => {
+                queue.failures = 0;
+                queue.retry_at = None;
+                queue.persist();
+            }
+            Err(e) => {
+                queue.failures += 1;
+                let delay = retry_delay(queue.failures);
+                eprintln!("Failed to send {} analytics events, retrying in {}s: {}", batch.len(), delay.as_secs(), e);
+                queue.retry_at = Some(Instant::now() + delay);
+                for event in batch.into_iter().rev() {
+                    queue.events.push_front(event);
+                }
+                queue.enforce_cap();
+                queue.persist();
+                return;
+            }
+        }
+    }
+}
+
 pub struct AnalyticsClient {
     client: Option<Arc<Client>>,
     config: AnalyticsConfig,
     user_id: Arc<Mutex<Option<String>>>,
     current_session: Arc<Mutex<Option<UserSession>>>,
+    queue: Arc<Mutex<EventQueue>>,
 }
 
 impl AnalyticsClient {
-    pub async fn new(config: AnalyticsConfig) -> Self {
-        let client = if config.enabled && !config.api_key.is_empty() {
-            Some(Arc::new(posthog_rs::client(config.api_key.as_str()).await))
-        } else {
-            None
+    /// Create the client. With `queue_path` unsent events are kept in that file across
+    /// restarts; without it they are only queued in memory.
+    pub async fn new(config: AnalyticsConfig, queue_path: Option<PathBuf>) -> Self {
+        let client = match config.client_options() {
+            Some(options) if config.enabled && !config.api_key.trim().is_empty() => Some(Arc::new(posthog_rs::client(options).await)),
+            _ => None,
         };
+        let queue = Arc::new(Mutex::new(if client.is_some() { EventQueue::load(queue_path) } else { EventQueue::default() }));
+
+        // Flushes until the client is dropped, e.g. when analytics are disabled
+        if let Some(client) = &client {
+            let client = Arc::downgrade(client);
+            let queue = Arc::downgrade(&queue);
+            tauri::async_runtime::spawn(async move {
+                loop {
+                    tokio::time::sleep(FLUSH_INTERVAL).await;
+                    let (Some(client), Some(queue)) = (client.upgrade(), queue.upgrade()) else {
+                        break;
+                    };
+                    flush_queue(&client, &queue).await;
+                }
+            });
+        }
 
         Self {
             client,
             config,
             user_id: Arc::new(Mutex::new(None)),
             current_session: Arc::new(Mutex::new(None)),
+            queue,
+        }
+    }
+
+    // Queue an event, flushing in the background once enough have piled up
+    async fn enqueue(&self, client: Arc<Client>, event: QueuedEvent) {
+        let flush_now = {
+            let mut queue = self.queue.lock().await;
+            queue.events.push_back(event);
+            queue.enforce_cap();
+            queue.persist();
+            queue.events.len() >= FLUSH_THRESHOLD && !queue.backing_off()
+        };
+        if flush_now {
+            let queue = Arc::clone(&self.queue);
+            tauri::async_runtime::spawn(async move { flush_queue(&client, &queue).await });
+        }
+    }
+
+    /// Send everything queued now, unless sending is backing off after a failure.
+    pub async fn flush(&self) {
+        if let Some(client) = &self.client {
+            flush_queue(client, &self.queue).await;
+        }
+    }
+
+    /// Drop queued events without sending them, for when the user opts out.
+    pub async fn discard_queue(&self) {
+        let mut queue = self.queue.lock().await;
+        queue.events.clear();
+        if let Some(path) = &queue.path {
+            if let Err(e) = atomic_file::remove(path) {
+                eprintln!("Failed to remove analytics queue: {}", e);
+            }
         }
     }
 
@@ -81,18 +279,7 @@ impl AnalyticsClient {
 
         let properties = properties.unwrap_or_default();
         
-        let mut event = Event::new("$identify", &user_id);
-        
-        // Add user properties
-        for (key, value) in properties {
-            if let Err(e) = event.insert_prop(&key, value) {
-                eprintln!("Failed to add property {}: {}", key, e);
-            }
-        }
-        
-        if let Err(e) = client.capture(event).await {
-            eprintln!("Failed to identify user: {}", e);
-        }
+        self.enqueue(client, QueuedEvent::new("$identify", &user_id, properties)).await;
         
         Ok(())
     }
@@ -121,18 +308,7 @@ impl AnalyticsClient {
             properties.insert("session_duration".to_string(), session.duration_seconds().to_string());
         }
         
-        let mut event = Event::new(&event_name, &user_id);
-        
-        // Add event properties
-        for (key, value) in properties {
-            if let Err(e) = event.insert_prop(&key, value) {
-                eprintln!("Failed to add property {}: {}", key, e);
-            }
-        }
-        
-        if let Err(e) = client.capture(event).await {
-            eprintln!("Failed to track event {}: {}", event_name, e);
-        }
+        self.enqueue(client, QueuedEvent::new(&event_name, &user_id, properties)).await;
         
         Ok(())
     }
@@ -350,18 +526,7 @@ impl AnalyticsClient {
             }
         };
         
-        let mut event = Event::new("$set", &user_id);
-        
-        // Add user properties
-        for (key, value) in properties {
-            if let Err(e) = event.insert_prop(&key, value) {
-                eprintln!("Failed to add property {}: {}", key, e);
-            }
-        }
-        
-        if let Err(e) = client.capture(event).await {
-            eprintln!("Failed to set user properties: {}", e);
-        }
+        self.enqueue(client, QueuedEvent::new("$set", &user_id, properties)).await;
         
         Ok(())
     }
@@ -369,5 +534,5 @@ impl AnalyticsClient {
 
 // Helper function to create analytics client from config
 pub async fn create_analytics_client(config: AnalyticsConfig) -> AnalyticsClient {
-    AnalyticsClient::new(config).await
+    AnalyticsClient::new(config, None).await
 } 
\ No newline at end of file
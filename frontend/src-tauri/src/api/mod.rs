@@ -0,0 +1,1059 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, debug as log_debug, warn as log_warn};
+
+use crate::activity::{self, ActivityKind};
+use crate::summary::MeetingSummary;
+use crate::error::AppError;
+use crate::http;
+use crate::logging;
+use crate::telemetry::Dependency;
+use crate::secrets::{self, KeyKind};
+use crate::slack;
+use crate::usage_stats::{self, UsageKind};
+use crate::webhooks::{self, WebhookEvent};
+
+pub mod outbox;
+
+// Where the backend runs unless another address is saved under `backendUrl`
+pub const DEFAULT_APP_SERVER_URL: &str = "http://localhost:5167";
+const BACKEND_URL_STORE_KEY: &str = "backendUrl";
+const DEFAULT_MEETINGS_PAGE_SIZE: u32 = 50;
+const MAX_MEETINGS_PAGE_SIZE: u32 = 500;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiResponse<T> {
+    pub success: bool,
+    pub data: Option<T>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Meeting {
+    pub id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<String>,
+    /// Length of the recording, when the meeting was recorded in the app
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_secs: Option<f64>,
+    /// The local folder the meeting is filed in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub folder_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeetingSort {
+    #[default]
+    CreatedAt,
+    Title,
+    Duration,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    Asc,
+    #[default]
+    Desc,
+}
+
+/// Which meetings to list: one page of them, sorted and filtered. Every field is optional
+/// for the frontend; the defaults give the first 50 meetings, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingQuery {
+    /// Starts at 1
+    pub page: u32,
+    pub limit: u32,
+    pub sort: MeetingSort,
+    pub order: SortOrder,
+    /// Only meetings created at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only meetings created at or before this time
+    pub to: Option<DateTime<Utc>>,
+    /// Only meetings with this tag in their metadata (case-insensitive)
+    pub tag: Option<String>,
+    pub has_summary: Option<bool>,
+    /// Only meetings in this folder; folders exist in the local store only
+    pub folder_id: Option<String>,
+}
+
+impl Default for MeetingQuery {
+    fn default() -> Self {
+        Self {
+            page: 1,
+            limit: DEFAULT_MEETINGS_PAGE_SIZE,
+            sort: MeetingSort::default(),
+            order: SortOrder::default(),
+            from: None,
+            to: None,
+            tag: None,
+            has_summary: None,
+            folder_id: None,
+        }
+    }
+}
+
+impl MeetingQuery {
+    /// The query with page and limit clamped to valid values and a blank tag dropped.
+    pub fn normalized(mut self) -> Self {
+        self.page = self.page.max(1);
+        self.limit = self.limit.clamp(1, MAX_MEETINGS_PAGE_SIZE);
+        self.tag = self.tag.map(|tag| tag.trim().to_string()).filter(|tag| !tag.is_empty());
+        self
+    }
+
+    /// Rows to skip before this page.
+    pub fn offset(&self) -> u64 {
+        u64::from(self.page.saturating_sub(1)) * u64::from(self.limit)
+    }
+
+    fn query_string(&self) -> String {
+        let sort = match self.sort {
+            MeetingSort::CreatedAt => "created_at",
+            MeetingSort::Title => "title",
+            MeetingSort::Duration => "duration",
+        };
+        let order = match self.order {
+            SortOrder::Asc => "asc",
+            SortOrder::Desc => "desc",
+        };
+        let mut params = vec![
+            format!("page={}", self.page),
+            format!("limit={}", self.limit),
+            format!("sort={}", sort),
+            format!("order={}", order),
+        ];
+        if let Some(from) = self.from {
+            params.push(format!("from={}", urlencoding::encode(&from.to_rfc3339_opts(SecondsFormat::Secs, true))));
+        }
+        if let Some(to) = self.to {
+            params.push(format!("to={}", urlencoding::encode(&to.to_rfc3339_opts(SecondsFormat::Secs, true))));
+        }
+        if let Some(tag) = &self.tag {
+            params.push(format!("tag={}", urlencoding::encode(tag)));
+        }
+        if let Some(has_summary) = self.has_summary {
+            params.push(format!("has_summary={}", has_summary));
+        }
+        params.join("&")
+    }
+}
+
+/// One page of meetings, with the number of meetings matching the query across all pages.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingPage {
+    pub meetings: Vec<Meeting>,
+    pub total: u64,
+    pub page: u32,
+    pub limit: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchRequest {
+    pub query: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranscriptSearchResult {
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "matchContext")]
+    pub match_context: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProfileRequest {
+    pub email: String,
+    pub license_key: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveProfileRequest {
+    pub id: String,
+    pub email: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateProfileRequest {
+    pub email: String,
+    pub license_key: String,
+    pub company: String,
+    pub position: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModelConfig {
+    pub provider: String,
+    pub model: String,
+    #[serde(rename = "whisperModel")]
+    pub whisper_model: String,
+    #[serde(rename = "apiKey")]
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveModelConfigRequest {
+    pub provider: String,
+    pub model: String,
+    #[serde(rename = "whisperModel")]
+    pub whisper_model: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetApiKeyRequest {
+    pub provider: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranscriptConfig {
+    pub provider: String,
+    pub model: String,
+    #[serde(rename = "apiKey")]
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveTranscriptConfigRequest {
+    pub provider: String,
+    pub model: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeleteMeetingRequest {
+    pub meeting_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingDetails {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub series_id: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    pub transcripts: Vec<MeetingTranscript>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingTranscript {
+    pub id: String,
+    pub text: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinkMeetingSeriesRequest {
+    pub meeting_id: String,
+    pub series_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MeetingSeries {
+    pub series_id: String,
+    pub meetings: Vec<Meeting>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveMeetingTitleRequest {
+    pub meeting_id: String,
+    pub title: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveMeetingSummaryRequest {
+    pub meeting_id: String,
+    pub summary: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveMeetingMetadataRequest {
+    pub meeting_id: String,
+    pub metadata: serde_json::Value,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SummaryResponse {
+    pub status: String,
+    #[serde(rename = "meetingName")]
+    pub meeting_name: Option<String>,
+    pub meeting_id: String,
+    pub start: Option<String>,
+    pub end: Option<String>,
+    pub data: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
+impl SummaryResponse {
+    /// The completed summary, if there is one, read into the typed schema.
+    pub fn summary(&self) -> Result<Option<MeetingSummary>, String> {
+        self.data.as_ref().map(MeetingSummary::from_value).transpose()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SaveTranscriptRequest {
+    pub meeting_title: String,
+    pub transcripts: Vec<TranscriptSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    pub id: String,
+    pub text: String,
+    pub timestamp: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessTranscriptRequest {
+    pub text: String,
+    pub model: String,
+    pub model_name: String,
+    pub meeting_id: Option<String>,
+    pub chunk_size: Option<i32>,
+    pub overlap: Option<i32>,
+    pub custom_prompt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_series_context: Option<bool>,
+    /// The provider's key from the keychain; the backend uses it for this request only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessTranscriptResponse {
+    pub message: String,
+    pub process_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Profile {
+    pub id: String,
+    pub name: Option<String>,
+    pub email: String,
+    pub license_key: String,
+    pub company: Option<String>,
+    pub position: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub is_licensed: bool,
+}
+
+// Helper function to get auth token from store (optional)
+async fn get_auth_token<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    let store = match app.store("store.json") {
+        Ok(store) => store,
+        Err(_) => return None,
+    };
+    
+    match store.get("authToken") {
+        Some(token) => {
+            if let Some(token_str) = token.as_str() {
+                log_info!("Found auth token: {}", &token_str[..std::cmp::min(20, token_str.len())]);
+                Some(token_str.to_string())
+            } else {
+                log_warn!("Auth token is not a string");
+                None
+            }
+        }
+        None => {
+            log_warn!("No auth token found in store");
+            None
+        },
+    }
+}
+
+fn normalize_backend_url(url: &str) -> Result<String, AppError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::InvalidInput(format!("Invalid backend address '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::InvalidInput(format!("Backend address must be an http(s) URL: '{}'", url)));
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
+
+// Helper function to get server address: the saved one, or the local default
+pub(crate) async fn get_server_address<R: Runtime>(app: &AppHandle<R>) -> Result<String, AppError> {
+    let saved = app
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get(BACKEND_URL_STORE_KEY))
+        .and_then(|value| value.as_str().map(str::to_string));
+    match saved.map(|url| normalize_backend_url(&url)) {
+        Some(Ok(url)) => Ok(url),
+        Some(Err(e)) => {
+            log_warn!("Ignoring saved backend address: {}", e);
+            Ok(DEFAULT_APP_SERVER_URL.to_string())
+        }
+        None => Ok(DEFAULT_APP_SERVER_URL.to_string()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_backend_url<R: Runtime>(app: AppHandle<R>) -> Result<String, AppError> {
+    get_server_address(&app).await
+}
+
+/// Point the app at a backend on another port or machine, e.g. `https://minutes.example.com`.
+/// Pass nothing to go back to the local default. The connection is checked right away and
+/// reported as a `backend-status` event.
+#[tauri::command]
+pub async fn set_backend_url<R: Runtime>(app: AppHandle<R>, url: Option<String>) -> Result<String, AppError> {
+    let url = match url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        Some(url) => Some(normalize_backend_url(url)?),
+        None => None,
+    };
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    match &url {
+        Some(url) => store.set(BACKEND_URL_STORE_KEY, serde_json::json!(url)),
+        None => {
+            store.delete(BACKEND_URL_STORE_KEY);
+        }
+    }
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    let url = url.unwrap_or_else(|| DEFAULT_APP_SERVER_URL.to_string());
+    log_info!("Backend address set to {}", url);
+    crate::backend_status::probe(&app).await;
+    Ok(url)
+}
+
+// Generic API call function with optional authentication. Timeouts and retries follow the
+// saved `HttpPolicy`; POST requests are only retried when they never reached the backend.
+pub(crate) async fn make_api_request<R: Runtime, T: for<'de> Deserialize<'de>>(
+    app: &AppHandle<R>,
+    endpoint: &str,
+    method: &str,
+    body: Option<&str>,
+    additional_headers: Option<HashMap<String, String>>,
+    auth_token: Option<String>, // Pass auth token from frontend
+) -> Result<T, AppError> {
+    let client = http::client();
+    let server_url = get_server_address(app).await?;
+    
+    let url = format!("{}{}", server_url, endpoint);
+    log_info!("Making {} request to: {}", method, url);
+    
+    let method = match method.to_uppercase().as_str() {
+        "GET" => reqwest::Method::GET,
+        "POST" => reqwest::Method::POST,
+        "PUT" => reqwest::Method::PUT,
+        "DELETE" => reqwest::Method::DELETE,
+        _ => return Err(AppError::InvalidInput(format!("Unsupported HTTP method: {}", method))),
+    };
+    
+    if auth_token.is_some() {
+        log_info!("Adding authorization header");
+    } else {
+        log_warn!("No auth token provided, making unauthenticated request");
+    }
+    let workspace = crate::workspace::active_workspace(app);
+    let build = || {
+        let mut request = client.request(method.clone(), &url);
+        
+        // Add authorization header if auth token is provided
+        if let Some(token) = &auth_token {
+            request = request.header("Authorization", format!("Bearer {}", token));
+        }
+        
+        request = request.header("Content-Type", "application/json");
+        request = request.header(crate::workspace::WORKSPACE_HEADER, workspace.as_str());
+        
+        // Add additional headers if provided
+        if let Some(headers) = &additional_headers {
+            for (key, value) in headers {
+                request = request.header(key, value);
+            }
+        }
+        
+        // Add body if provided
+        if let Some(body_str) = body {
+            request = request.body(body_str.to_string());
+        }
+        request
+    };
+    
+    let options = http::policy(app).options(Dependency::Backend, http::is_idempotent(method.as_str()));
+    let response = http::send(&options, &format!("{} {}", method, endpoint), build).await.map_err(|error_msg| {
+        log_error!("{}", error_msg);
+        error_msg
+    })?;
+    
+    let status = response.status();
+    log_info!("Response status: {}", status);
+    
+    let response_text = response.text().await.map_err(|e| {
+        let error_msg = format!("Failed to read response: {}", e);
+        log_error!("{}", error_msg);
+        error_msg
+    })?;
+    
+    log_debug!("Response body: {}", logging::redact(&response_text));
+    
+    serde_json::from_str(&response_text).map_err(|e| {
+        let error_msg = format!("Failed to parse JSON: {}", e);
+        log_error!("{}", error_msg);
+        AppError::Internal(error_msg)
+    })
+}
+
+// API Commands for Tauri
+
+/// One page of meetings from the backend; see `MeetingQuery` for paging, sorting and filters.
+#[tauri::command]
+pub async fn api_get_meetings<R: Runtime>(
+    app: AppHandle<R>, 
+    auth_token: Option<String>,
+    query: Option<MeetingQuery>,
+) -> Result<MeetingPage, AppError> {
+    log_info!("api_get_meetings called with auth_token: {}", auth_token.is_some());
+    let query = query.unwrap_or_default().normalized();
+    let endpoint = format!("/get-meetings?{}", query.query_string());
+    
+    let cache_headers = HashMap::from([
+        ("Cache-Control".to_string(), "no-cache, no-store, must-revalidate".to_string()),
+        ("Pragma".to_string(), "no-cache".to_string()),
+        ("Expires".to_string(), "0".to_string()),
+    ]);
+    
+    let result = make_api_request::<R, MeetingPage>(&app, &endpoint, "GET", None, Some(cache_headers), auth_token).await;
+    
+    match &result {
+        Ok(page) => log_info!("Successfully got {} of {} meetings", page.meetings.len(), page.total),
+        Err(e) => log_error!("Error getting meetings: {}", e),
+    }
+    
+    result
+}
+
+#[tauri::command]
+pub async fn api_search_transcripts<R: Runtime>(
+    app: AppHandle<R>,
+    query: String,
+    auth_token: Option<String>,
+) -> Result<Vec<TranscriptSearchResult>, AppError> {
+    log_info!("api_search_transcripts called with query: {}, auth_token: {}", logging::redact(&query), auth_token.is_some());
+    
+    let search_request = SearchRequest { query: query.clone() };
+    let body = serde_json::to_string(&search_request).map_err(|e| e.to_string())?;
+    
+    match make_api_request::<R, Vec<TranscriptSearchResult>>(&app, "/search-transcripts", "POST", Some(&body), None, auth_token).await {
+        Ok(results) => Ok(results),
+        Err(e) => {
+            // Backend unavailable: answer from the meetings stored locally
+            log_warn!("Backend search failed, searching locally: {}", e);
+            let hits = crate::storage::search::search(&app, &query, crate::storage::search::DEFAULT_SEARCH_LIMIT).map_err(|local| {
+                log_error!("Local search failed too: {}", local);
+                e
+            })?;
+            Ok(hits
+                .into_iter()
+                .map(|hit| TranscriptSearchResult {
+                    id: hit.meeting_id,
+                    title: hit.title,
+                    match_context: hit.context,
+                    timestamp: hit.timestamp,
+                })
+                .collect())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn api_get_profile<R: Runtime>(
+    app: AppHandle<R>,
+    email: String,
+    license_key: String,
+    auth_token: Option<String>,
+) -> Result<Profile, AppError> {
+    log_info!("api_get_profile called for email: {}, auth_token: {}", email, auth_token.is_some());
+    
+    let profile_request = ProfileRequest { email, license_key };
+    let body = serde_json::to_string(&profile_request).map_err(|e| e.to_string())?;
+    
+    make_api_request::<R, Profile>(&app, "/get-profile", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_save_profile<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    email: String,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_profile called for email: {}, auth_token: {}", email, auth_token.is_some());
+    
+    let save_request = SaveProfileRequest { id, email };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    make_api_request::<R, serde_json::Value>(&app, "/save-profile", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_update_profile<R: Runtime>(
+    app: AppHandle<R>,
+    email: String,
+    license_key: String,
+    company: String,
+    position: String,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_update_profile called for email: {}, auth_token: {}", email, auth_token.is_some());
+    
+    let update_request = UpdateProfileRequest { 
+        email, 
+        license_key, 
+        company, 
+        position 
+    };
+    let body = serde_json::to_string(&update_request).map_err(|e| e.to_string())?;
+    
+    make_api_request::<R, serde_json::Value>(&app, "/update-profile", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_get_model_config<R: Runtime>(
+    app: AppHandle<R>,
+    auth_token: Option<String>,
+) -> Result<Option<ModelConfig>, AppError> {
+    log_info!("api_get_model_config called with auth_token: {}", auth_token.is_some());
+    
+    let mut config = make_api_request::<R, Option<ModelConfig>>(&app, "/get-model-config", "GET", None, None, auth_token.clone()).await?;
+    if let Some(config) = config.as_mut() {
+        config.api_key = secrets::api_key(&app, KeyKind::Summary, &config.provider, auth_token).await?;
+    }
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn api_save_model_config<R: Runtime>(
+    app: AppHandle<R>,
+    provider: String,
+    model: String,
+    whisper_model: String,
+    api_key: Option<String>,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_model_config called for provider: {}, auth_token: {}", provider, auth_token.is_some());
+    
+    if let Some(key) = &api_key {
+        secrets::store_api_key(KeyKind::Summary, &provider, key)?;
+    }
+    // The key stays in the keychain; /process-transcript is sent it with each request
+    let save_request = SaveModelConfigRequest { 
+        provider, 
+        model, 
+        whisper_model, 
+    };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    make_api_request::<R, serde_json::Value>(&app, "/save-model-config", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_get_api_key<R: Runtime>(
+    app: AppHandle<R>,
+    provider: String,
+    auth_token: Option<String>,
+) -> Result<String, AppError> {
+    log_info!("api_get_api_key called for provider: {}, auth_token: {}", provider, auth_token.is_some());
+    
+    Ok(secrets::api_key(&app, KeyKind::Summary, &provider, auth_token).await?.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn api_get_transcript_config<R: Runtime>(
+    app: AppHandle<R>,
+    auth_token: Option<String>,
+) -> Result<Option<TranscriptConfig>, AppError> {
+    log_info!("api_get_transcript_config called with auth_token: {}", auth_token.is_some());
+    
+    let mut config =
+        make_api_request::<R, Option<TranscriptConfig>>(&app, "/get-transcript-config", "GET", None, None, auth_token.clone()).await?;
+    if let Some(config) = config.as_mut() {
+        config.api_key = secrets::api_key(&app, KeyKind::Transcript, &config.provider, auth_token).await?;
+    }
+    Ok(config)
+}
+
+#[tauri::command]
+pub async fn api_save_transcript_config<R: Runtime>(
+    app: AppHandle<R>,
+    provider: String,
+    model: String,
+    api_key: Option<String>,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_transcript_config called for provider: {}, auth_token: {}", provider, auth_token.is_some());
+    
+    if let Some(key) = &api_key {
+        secrets::store_api_key(KeyKind::Transcript, &provider, key)?;
+    }
+    let save_request = SaveTranscriptConfigRequest { 
+        provider, 
+        model, 
+    };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    make_api_request::<R, serde_json::Value>(&app, "/save-transcript-config", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_get_transcript_api_key<R: Runtime>(
+    app: AppHandle<R>,
+    provider: String,
+    auth_token: Option<String>,
+) -> Result<String, AppError> {
+    log_info!("api_get_transcript_api_key called for provider: {}, auth_token: {}", provider, auth_token.is_some());
+    
+    Ok(secrets::api_key(&app, KeyKind::Transcript, &provider, auth_token).await?.unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn api_delete_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_delete_meeting called for meeting_id: {}, auth_token: {}", meeting_id, auth_token.is_some());
+    
+    let delete_request = DeleteMeetingRequest { meeting_id };
+    let body = serde_json::to_string(&delete_request).map_err(|e| e.to_string())?;
+    
+    make_api_request::<R, serde_json::Value>(&app, "/delete-meeting", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_get_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<MeetingDetails, AppError> {
+    log_info!("api_get_meeting called for meeting_id: {}, auth_token: {}", meeting_id, auth_token.is_some());
+    
+    make_api_request::<R, MeetingDetails>(&app, &format!("/get-meeting/{}", meeting_id), "GET", None, None, auth_token).await
+}
+
+/// Links a meeting to a recurring series. Passing no series id unlinks it.
+#[tauri::command]
+pub async fn api_link_meeting_series<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    series_id: Option<String>,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_link_meeting_series called for meeting_id: {}, series_id: {:?}", meeting_id, series_id);
+
+    let link_request = LinkMeetingSeriesRequest { meeting_id, series_id };
+    let body = serde_json::to_string(&link_request).map_err(|e| e.to_string())?;
+
+    make_api_request::<R, serde_json::Value>(&app, "/link-meeting-series", "POST", Some(&body), None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_get_meeting_series<R: Runtime>(
+    app: AppHandle<R>,
+    series_id: String,
+    auth_token: Option<String>,
+) -> Result<MeetingSeries, AppError> {
+    log_info!("api_get_meeting_series called for series_id: {}", series_id);
+
+    make_api_request::<R, MeetingSeries>(&app, &format!("/get-meeting-series/{}", series_id), "GET", None, None, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_save_meeting_title<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    title: String,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_meeting_title called for meeting_id: {}, auth_token: {}", meeting_id, auth_token.is_some());
+    
+    let save_request = SaveMeetingTitleRequest { meeting_id, title };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    outbox::send(&app, "/save-meeting-title", "POST", body, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_save_meeting_summary<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    summary: serde_json::Value,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_meeting_summary called for meeting_id: {}, auth_token: {}", meeting_id, auth_token.is_some());
+    
+    // Refuse anything exports and integrations would not be able to read back
+    MeetingSummary::from_value(&summary).map_err(AppError::InvalidInput)?;
+    let save_request = SaveMeetingSummaryRequest { meeting_id, summary };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    let response = outbox::send(&app, "/save-meeting-summary", "POST", body, auth_token).await?;
+    webhooks::notify(&app, WebhookEvent::SummaryGenerated, &save_request.meeting_id, None, Some(save_request.summary));
+    Ok(response)
+}
+
+/// Merge keys into a meeting's metadata; existing keys not present in `metadata` are kept.
+#[tauri::command]
+pub async fn api_save_meeting_metadata<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    metadata: serde_json::Value,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_meeting_metadata called for meeting_id: {}, auth_token: {}", meeting_id, auth_token.is_some());
+    
+    let save_request = SaveMeetingMetadataRequest { meeting_id, metadata };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    outbox::send(&app, "/save-meeting-metadata", "POST", body, auth_token).await
+}
+
+#[tauri::command]
+pub async fn api_get_summary<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<SummaryResponse, AppError> {
+    log_debug!("=== api_get_summary DEBUG ===");
+    log_debug!("meeting_id: {}", meeting_id);
+    log_debug!("auth_token present: {}", auth_token.is_some());
+    if let Some(ref token) = auth_token {
+        log_debug!("auth_token length: {}", token.len());
+    }
+    
+    let result =
+        make_api_request::<R, SummaryResponse>(&app, &format!("/get-summary/{}", meeting_id), "GET", None, None, auth_token.clone()).await;
+    
+    match &result {
+        Ok(summary) => {
+            log_debug!("✓ api_get_summary successful");
+            if summary.status == "completed" {
+                let name = summary.meeting_name.as_deref().filter(|n| !n.trim().is_empty()).unwrap_or(&meeting_id);
+                let first_seen = activity::record(
+                    &app,
+                    ActivityKind::SummaryReady,
+                    &format!("Summary of {} is ready", name),
+                    Some(&meeting_id),
+                    Some(&format!("summary:{}", meeting_id)),
+                );
+                // Polling reports the same summary again and again; only announce it once
+                if first_seen {
+                    webhooks::notify(
+                        &app,
+                        WebhookEvent::SummaryGenerated,
+                        &meeting_id,
+                        summary.meeting_name.clone(),
+                        summary.data.clone(),
+                    );
+                    slack::auto_post(&app, &meeting_id, auth_token);
+                }
+            }
+        }
+        Err(e) => log_error!("✗ api_get_summary failed: {}", e),
+    }
+    
+    result
+}
+
+/// A meeting's summary in the typed schema; None while it is not generated yet.
+#[tauri::command]
+pub async fn api_get_meeting_summary<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<Option<MeetingSummary>, AppError> {
+    log_info!("api_get_meeting_summary called for meeting_id: {}", meeting_id);
+    let response =
+        make_api_request::<R, SummaryResponse>(&app, &format!("/get-summary/{}", meeting_id), "GET", None, None, auth_token).await?;
+    Ok(response.summary()?)
+}
+
+#[tauri::command]
+pub async fn api_save_transcript<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_title: String,
+    transcripts: Vec<serde_json::Value>,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    log_info!("api_save_transcript called for meeting: {}, transcripts: {}, auth_token: {}", 
+             meeting_title, transcripts.len(), auth_token.is_some());
+    
+    // Convert serde_json::Value to TranscriptSegment
+    let transcript_segments: Result<Vec<TranscriptSegment>, _> = transcripts
+        .into_iter()
+        .map(|t| serde_json::from_value(t))
+        .collect();
+    
+    let transcript_segments =
+        transcript_segments.map_err(|e| AppError::InvalidInput(format!("Invalid transcript segment: {}", e)))?;
+    
+    let save_request = SaveTranscriptRequest { 
+        meeting_title, 
+        transcripts: transcript_segments,
+        metadata: crate::meeting_metadata::snapshot(),
+    };
+    let body = serde_json::to_string(&save_request).map_err(|e| e.to_string())?;
+    
+    let response = outbox::send(&app, "/save-transcript", "POST", body, auth_token).await?;
+    // A queued transcript has no meeting id yet, so there is nothing to announce
+    if let Some(meeting_id) = response.get("meeting_id").and_then(|id| id.as_str()) {
+        webhooks::notify(
+            &app,
+            WebhookEvent::TranscriptCompleted,
+            meeting_id,
+            Some(save_request.meeting_title),
+            None,
+        );
+    }
+    Ok(response)
+}
+
+#[tauri::command]
+pub async fn api_process_transcript<R: Runtime>(
+    app: AppHandle<R>,
+    text: String,
+    model: String,
+    model_name: String,
+    meeting_id: Option<String>,
+    chunk_size: Option<i32>,
+    overlap: Option<i32>,
+    custom_prompt: Option<String>,
+    include_series_context: Option<bool>,
+    auth_token: Option<String>,
+) -> Result<ProcessTranscriptResponse, AppError> {
+    log_info!("api_process_transcript called for meeting_id: {:?}, model: {}, auth_token: {}", 
+             meeting_id, model, auth_token.is_some());
+    
+    let started = std::time::Instant::now();
+    let used_model = format!("{} / {}", model, model_name);
+    let api_key = secrets::api_key(&app, KeyKind::Summary, &model, auth_token.clone()).await?;
+    let process_request = ProcessTranscriptRequest {
+        text,
+        model,
+        model_name,
+        meeting_id,
+        chunk_size,
+        overlap,
+        custom_prompt,
+        include_series_context,
+        api_key,
+    };
+    let body = serde_json::to_string(&process_request).map_err(|e| e.to_string())?;
+    
+    let response = make_api_request::<R, ProcessTranscriptResponse>(&app, "/process-transcript", "POST", Some(&body), None, auth_token).await?;
+    usage_stats::record(&app, UsageKind::Summary, Some(started.elapsed().as_secs_f64()), Some(&used_model));
+    Ok(response)
+}
+
+
+
+// Simple test command to check backend connectivity
+#[tauri::command]
+pub async fn test_backend_connection<R: Runtime>(
+    app: AppHandle<R>,
+    auth_token: Option<String>
+) -> Result<String, AppError> {
+    log_debug!("Testing backend connection...");
+    
+    let client = reqwest::Client::new();
+    let server_url = get_server_address(&app).await?;
+    
+    log_debug!("Testing connection to: {}", server_url);
+    
+    let mut request = client.get(&format!("{}/docs", server_url));
+    
+    if let Some(token) = auth_token {
+        request = request.header("Authorization", format!("Bearer {}", token));
+    }
+    
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            log_debug!("Backend responded with status: {}", status);
+            Ok(format!("Backend is reachable. Status: {}", status))
+        }
+        Err(e) => {
+            let error_msg = format!("Failed to connect to backend: {}", e);
+            log_debug!("{}", error_msg);
+            Err(AppError::Offline(error_msg))
+        }
+    }
+} 
+
+#[tauri::command]
+pub async fn debug_backend_connection<R: Runtime>(
+    app: AppHandle<R>,
+) -> Result<String, AppError> {
+    log_debug!("=== DEBUG: Testing backend connection ===");
+    
+    // Test 1: Check server address from store
+    let server_url = match get_server_address(&app).await {
+        Ok(url) => {
+            log_debug!("✓ Server URL from store: {}", url);
+            url
+        }
+        Err(e) => {
+            log_error!("✗ Failed to get server URL: {}", e);
+            return Err(format!("Failed to get server URL: {}", e).into());
+        }
+    };
+    
+    // Test 2: Make a simple HTTP request to the backend
+    let client = reqwest::Client::new();
+    let test_url = format!("{}/docs", server_url); // Try the docs endpoint which should be public
+    
+    log_debug!("Testing connection to: {}", test_url);
+    
+    match client.get(&test_url).send().await {
+        Ok(response) => {
+            let status = response.status();
+            log_debug!("✓ Backend responded with status: {}", status);
+            Ok(format!("Backend connection successful! Status: {}, URL: {}", status, server_url))
+        }
+        Err(e) => {
+            log_error!("✗ Backend connection failed: {}", e);
+            Err(AppError::Offline(format!("Backend connection failed: {}", e)))
+        }
+    }
+} 
+
+#[tauri::command]
+pub async fn open_external_url(url: String) -> Result<(), AppError> {
+    use std::process::Command;
+    
+    let result = if cfg!(target_os = "windows") {
+        Command::new("cmd")
+            .args(&["/C", "start", &url])
+            .output()
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+            .arg(&url)
+            .output()
+    } else {
+        // Linux and other Unix-like systems
+        Command::new("xdg-open")
+            .arg(&url)
+            .output()
+    };
+    
+    match result {
+        Ok(_) => Ok(()),
+        Err(e) => Err(AppError::Internal(format!("Failed to open URL: {}", e)))
+    }
+} 
\ No newline at end of file
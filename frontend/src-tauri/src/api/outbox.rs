@@ -0,0 +1,290 @@
+// Outbox for backend writes. Saving a transcript, summary, title or metadata while the
+// backend is down (or failing with a 5xx) doesn't lose the data: the request is written to
+// `outbox.json` in the app data directory and replayed in order once the backend answers
+// again, backing off exponentially between attempts. Requests the backend rejects outright
+// (4xx) are dropped and reported in the activity feed, since sending them again won't help.
+//
+// While anything is queued new writes go to the back of the queue too, so a later save of
+// the same meeting can never be overtaken by an earlier one.
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tokio::sync::Notify;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use super::{get_auth_token, make_api_request};
+use crate::activity::{self, ActivityKind};
+use crate::atomic_file;
+use crate::crash;
+use crate::error::AppError;
+
+const OUTBOX_FILE: &str = "outbox.json";
+const BASE_RETRY_DELAY: Duration = Duration::from_secs(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OutboxEntry {
+    id: String,
+    endpoint: String,
+    method: String,
+    body: String,
+    queued_at: DateTime<Utc>,
+    attempts: u32,
+    // Only kept in memory; after a restart the token saved in the store is used
+    #[serde(skip)]
+    auth_token: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SyncStatus {
+    /// Writes waiting for the backend
+    pub pending: usize,
+    pub oldest_queued_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct Outbox {
+    entries: Vec<OutboxEntry>,
+    path: Option<PathBuf>,
+    failures: u32,
+    last_error: Option<String>,
+    next_attempt_at: Option<DateTime<Utc>>,
+}
+
+static OUTBOX: Lazy<Mutex<Outbox>> = Lazy::new(|| Mutex::new(Outbox::default()));
+static WAKE: Lazy<Notify> = Lazy::new(Notify::new);
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+fn outbox_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(OUTBOX_FILE))
+}
+
+fn load(path: &Path) -> Vec<OutboxEntry> {
+    if !path.exists() {
+        return Vec::new();
+    }
+    match atomic_file::read_verified(path) {
+        Ok(data) => serde_json::from_slice(&data).unwrap_or_else(|e| {
+            log_warn!("Ignoring malformed outbox file: {}", e);
+            Vec::new()
+        }),
+        Err(e) => {
+            log_error!("Failed to read outbox: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+fn persist(outbox: &Outbox) {
+    let Some(path) = &outbox.path else {
+        return;
+    };
+    let result = serde_json::to_vec(&outbox.entries)
+        .map_err(|e| e.to_string())
+        .and_then(|data| atomic_file::write(path, data).map_err(|e| e.to_string()));
+    if let Err(e) = result {
+        log_error!("Failed to write outbox: {}", e);
+    }
+}
+
+fn status(outbox: &Outbox) -> SyncStatus {
+    SyncStatus {
+        pending: outbox.entries.len(),
+        oldest_queued_at: outbox.entries.first().map(|entry| entry.queued_at),
+        last_error: outbox.last_error.clone(),
+        next_attempt_at: outbox.next_attempt_at,
+    }
+}
+
+fn emit_status<R: Runtime>(app: &AppHandle<R>) {
+    let Ok(outbox) = OUTBOX.lock() else {
+        return;
+    };
+    if let Err(e) = app.emit("sync-status", status(&outbox)) {
+        log_error!("Failed to emit sync-status event: {}", e);
+    }
+}
+
+fn retry_delay(failures: u32) -> Duration {
+    (BASE_RETRY_DELAY * 2_u32.pow(failures.min(6))).min(MAX_RETRY_DELAY)
+}
+
+fn is_empty() -> bool {
+    OUTBOX.lock().map(|outbox| outbox.entries.is_empty()).unwrap_or(true)
+}
+
+fn enqueue<R: Runtime>(app: &AppHandle<R>, endpoint: &str, method: &str, body: String, auth_token: Option<String>) -> String {
+    let entry = OutboxEntry {
+        id: uuid::Uuid::new_v4().to_string(),
+        endpoint: endpoint.to_string(),
+        method: method.to_string(),
+        body,
+        queued_at: Utc::now(),
+        attempts: 0,
+        auth_token,
+    };
+    let id = entry.id.clone();
+    if let Ok(mut outbox) = OUTBOX.lock() {
+        if outbox.path.is_none() {
+            outbox.path = outbox_path(app).ok();
+        }
+        outbox.entries.push(entry);
+        persist(&outbox);
+        log_info!("Queued {} {} for later ({} pending)", method, endpoint, outbox.entries.len());
+    }
+    emit_status(app);
+    WAKE.notify_one();
+    id
+}
+
+/// Send a write to the backend, or queue it if the backend can't take it right now. A
+/// queued write resolves to `{"status": "queued", "outbox_id": ...}` instead of the
+/// backend's response.
+pub(crate) async fn send<R: Runtime>(
+    app: &AppHandle<R>,
+    endpoint: &str,
+    method: &str,
+    body: String,
+    auth_token: Option<String>,
+) -> Result<serde_json::Value, AppError> {
+    let queued = |id: String| serde_json::json!({ "status": "queued", "outbox_id": id });
+    if !is_empty() {
+        return Ok(queued(enqueue(app, endpoint, method, body, auth_token)));
+    }
+    match make_api_request::<R, serde_json::Value>(app, endpoint, method, Some(&body), None, auth_token.clone()).await {
+        Err(e) if e.is_retryable() => {
+            log_warn!("Backend unavailable, queueing {} {}: {}", method, endpoint, e);
+            Ok(queued(enqueue(app, endpoint, method, body, auth_token)))
+        }
+        result => result,
+    }
+}
+
+/// Retry queued writes now instead of waiting for the next attempt, e.g. because the
+/// backend just came back.
+pub fn wake() {
+    if !is_empty() {
+        WAKE.notify_one();
+    }
+}
+
+// Send queued writes oldest first until the queue is empty or the backend fails again
+async fn replay<R: Runtime>(app: &AppHandle<R>) -> Result<(), AppError> {
+    loop {
+        let Some(entry) = OUTBOX.lock().ok().and_then(|outbox| outbox.entries.first().cloned()) else {
+            return Ok(());
+        };
+        let auth_token = match entry.auth_token.clone() {
+            Some(token) => Some(token),
+            None => get_auth_token(app).await,
+        };
+        let result =
+            make_api_request::<R, serde_json::Value>(app, &entry.endpoint, &entry.method, Some(&entry.body), None, auth_token).await;
+        let Ok(mut outbox) = OUTBOX.lock() else {
+            return Ok(());
+        };
+        match result {
+            Ok(_) => {
+                outbox.entries.retain(|queued| queued.id != entry.id);
+                log_info!("Replayed {} {} ({} still pending)", entry.method, entry.endpoint, outbox.entries.len());
+            }
+            Err(e) if e.is_retryable() => {
+                if let Some(queued) = outbox.entries.iter_mut().find(|queued| queued.id == entry.id) {
+                    queued.attempts += 1;
+                }
+                persist(&outbox);
+                return Err(e);
+            }
+            Err(e) => {
+                outbox.entries.retain(|queued| queued.id != entry.id);
+                persist(&outbox);
+                drop(outbox);
+                crash::capture_task_error(
+                    "Outbox replay",
+                    &format!("Backend rejected queued {} {}, dropping it: {}", entry.method, entry.endpoint, e),
+                );
+                let message = format!("A change queued while offline could not be saved: {}", e);
+                activity::record(app, ActivityKind::SyncFailed, &message, None, None);
+                continue;
+            }
+        }
+        persist(&outbox);
+    }
+}
+
+async fn replay_loop<R: Runtime>(app: AppHandle<R>) {
+    loop {
+        let delay = retry_delay(OUTBOX.lock().map(|outbox| outbox.failures).unwrap_or(0));
+        if is_empty() {
+            WAKE.notified().await;
+        } else {
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = WAKE.notified() => {}
+            }
+        }
+
+        let result = replay(&app).await;
+        if let Ok(mut outbox) = OUTBOX.lock() {
+            match result {
+                Ok(()) => {
+                    outbox.failures = 0;
+                    outbox.last_error = None;
+                    outbox.next_attempt_at = None;
+                }
+                Err(e) => {
+                    outbox.failures += 1;
+                    let delay = retry_delay(outbox.failures);
+                    log_warn!("Replaying queued writes failed ({} in a row): {}", outbox.failures, e);
+                    outbox.last_error = Some(e.to_string());
+                    outbox.next_attempt_at = chrono::Duration::from_std(delay).ok().map(|delay| Utc::now() + delay);
+                }
+            }
+        }
+        emit_status(&app);
+    }
+}
+
+/// Load writes left over from the last run and start replaying them. Called once at startup.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    match outbox_path(app) {
+        Ok(path) => {
+            let entries = load(&path);
+            if let Ok(mut outbox) = OUTBOX.lock() {
+                if !entries.is_empty() {
+                    log_info!("{} backend writes left in the outbox from the last run", entries.len());
+                }
+                // Anything queued before startup finished stays behind what was saved on disk
+                let queued = std::mem::take(&mut outbox.entries);
+                outbox.entries = entries;
+                outbox.entries.extend(queued);
+                outbox.path = Some(path);
+            }
+        }
+        Err(e) => log_error!("Outbox is not persisted: {}", e),
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(replay_loop(app));
+    WAKE.notify_one();
+}
+
+/// How many backend writes are waiting in the outbox, and why.
+#[tauri::command]
+pub fn get_sync_status() -> SyncStatus {
+    OUTBOX.lock().map(|outbox| status(&outbox)).unwrap_or_else(|_| status(&Outbox::default()))
+}
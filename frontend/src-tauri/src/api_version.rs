@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use log::{info as log_info, warn as log_warn};
+
+/// Version of the invoke command contract shared with the frontend. Bump `major` when a
+/// command or payload changes incompatibly, `minor` when commands or optional fields are added.
+pub const API_VERSION: ApiVersion = ApiVersion { major: 1, minor: 0 };
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl std::fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiVersionInfo {
+    pub api_version: ApiVersion,
+    pub app_version: &'static str,
+}
+
+/// Checks a frontend's API version against this backend. Clients that do not send a
+/// version predate versioning and are accepted as 1.0.
+pub fn check_client_version(client: Option<ApiVersion>) -> Result<(), String> {
+    let client = match client {
+        Some(client) => client,
+        None => return Ok(()),
+    };
+    if client.major != API_VERSION.major {
+        return Err(format!(
+            "Incompatible command API: the frontend uses v{} but the app provides v{}. Update the app so both match.",
+            client, API_VERSION
+        ));
+    }
+    if client.minor > API_VERSION.minor {
+        return Err(format!(
+            "The frontend requires command API v{} but the app only provides v{}. Update the app.",
+            client, API_VERSION
+        ));
+    }
+    Ok(())
+}
+
+/// Command payload tagged with the API version the frontend was built against. The
+/// version is checked before the payload is used, so an incompatible frontend gets a
+/// clear error instead of fields being silently dropped or defaulted.
+#[derive(Debug, Deserialize)]
+pub struct Versioned<T> {
+    #[serde(default)]
+    pub api_version: Option<ApiVersion>,
+    #[serde(flatten)]
+    pub payload: T,
+}
+
+impl<T> Versioned<T> {
+    pub fn into_payload(self) -> Result<T, String> {
+        check_client_version(self.api_version)?;
+        Ok(self.payload)
+    }
+}
+
+#[tauri::command]
+pub fn get_api_version() -> ApiVersionInfo {
+    ApiVersionInfo {
+        api_version: API_VERSION,
+        app_version: env!("CARGO_PKG_VERSION"),
+    }
+}
+
+/// Called by the frontend at startup; fails with a readable message when the two sides
+/// cannot talk to each other.
+#[tauri::command]
+pub fn check_api_compatibility(client_version: ApiVersion) -> Result<ApiVersionInfo, String> {
+    match check_client_version(Some(client_version)) {
+        Ok(()) => {
+            log_info!("Frontend command API v{} is compatible with v{}", client_version, API_VERSION);
+            Ok(get_api_version())
+        }
+        Err(e) => {
+            log_warn!("{}", e);
+            Err(e)
+        }
+    }
+}
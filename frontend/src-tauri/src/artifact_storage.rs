@@ -0,0 +1,401 @@
+// Copies of exports and recordings on remote storage. Files are always written locally
+// first; when a remote target is configured they are then uploaded to an S3-compatible
+// bucket (AWS, MinIO, R2, ...) or a WebDAV server such as Nextcloud. The S3 secret key or
+// WebDAV password lives in the OS keychain, never in store.json. Uploads stream from disk
+// and report progress through `storage-upload-progress` events.
+use std::path::{Path, PathBuf};
+use chrono::Utc;
+use futures_util::stream;
+use hmac::{Hmac, Mac};
+use reqwest::header::{AUTHORIZATION, CONTENT_LENGTH, HOST};
+use reqwest::{Body, Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use tokio::io::AsyncReadExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::keychain;
+
+const STORAGE_STORE_KEY: &str = "remoteStorage";
+const SECRET_ACCOUNT: &str = "remote-storage";
+const UPLOAD_CHUNK_BYTES: usize = 256 * 1024;
+const UNSIGNED_PAYLOAD: &str = "UNSIGNED-PAYLOAD";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactKind {
+    Export,
+    Recording,
+}
+
+impl ArtifactKind {
+    fn folder(self) -> &'static str {
+        match self {
+            ArtifactKind::Export => "exports",
+            ArtifactKind::Recording => "recordings",
+        }
+    }
+}
+
+/// Where remote copies go. The secret (S3 secret key, WebDAV password) is kept in the keychain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum RemoteTarget {
+    S3 {
+        /// e.g. https://s3.eu-west-1.amazonaws.com or a MinIO URL; buckets are addressed by path
+        endpoint: String,
+        region: String,
+        bucket: String,
+        access_key_id: String,
+        #[serde(default)]
+        prefix: String,
+    },
+    WebDav {
+        /// e.g. https://cloud.example.com/remote.php/dav/files/alice
+        url: String,
+        username: String,
+        #[serde(default)]
+        directory: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RemoteStorageConfig {
+    pub enabled: bool,
+    pub target: Option<RemoteTarget>,
+    pub upload_exports: bool,
+    pub upload_recordings: bool,
+}
+
+impl Default for RemoteStorageConfig {
+    fn default() -> Self {
+        Self { enabled: false, target: None, upload_exports: true, upload_recordings: true }
+    }
+}
+
+impl RemoteStorageConfig {
+    fn validate(&self) -> Result<(), String> {
+        let Some(target) = &self.target else {
+            return if self.enabled { Err("Choose a storage target first".to_string()) } else { Ok(()) };
+        };
+        let url = match target {
+            RemoteTarget::S3 { endpoint, region, bucket, access_key_id, .. } => {
+                if region.trim().is_empty() || bucket.trim().is_empty() || access_key_id.trim().is_empty() {
+                    return Err("S3 storage needs a region, bucket and access key".to_string());
+                }
+                endpoint
+            }
+            RemoteTarget::WebDav { url, username, .. } => {
+                if username.trim().is_empty() {
+                    return Err("WebDAV storage needs a username".to_string());
+                }
+                url
+            }
+        };
+        let parsed = Url::parse(url).map_err(|e| format!("Invalid storage URL {}: {}", url, e))?;
+        if !matches!(parsed.scheme(), "https" | "http") {
+            return Err("Storage URL must use http or https".to_string());
+        }
+        Ok(())
+    }
+
+    fn uploads(&self, kind: ArtifactKind) -> bool {
+        self.enabled
+            && self.target.is_some()
+            && match kind {
+                ArtifactKind::Export => self.upload_exports,
+                ArtifactKind::Recording => self.upload_recordings,
+            }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgress {
+    pub upload_id: String,
+    pub file: String,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadResult {
+    pub upload_id: String,
+    pub file: String,
+    /// URL of the remote copy when the upload succeeded
+    pub location: Option<String>,
+    pub error: Option<String>,
+}
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> RemoteStorageConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(STORAGE_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+// Percent-encode everything but RFC 3986 unreserved characters, keeping '/' in paths
+fn encode_path(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+fn join_path(parts: &[&str]) -> String {
+    parts
+        .iter()
+        .flat_map(|part| part.split('/'))
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+fn host_header(url: &Url) -> String {
+    let host = url.host_str().unwrap_or_default();
+    match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    }
+}
+
+// AWS Signature Version 4 for a PUT whose body is not hashed up front, so it can stream
+fn sign_s3_put(url: &Url, region: &str, access_key_id: &str, secret: &str) -> (String, String) {
+    let now = Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "PUT\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        url.path(),
+        host_header(url),
+        UNSIGNED_PAYLOAD,
+        amz_date,
+        signed_headers,
+        UNSIGNED_PAYLOAD
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex::encode(Sha256::digest(canonical_request.as_bytes()))
+    );
+    let key = [date.as_str(), region, "s3", "aws4_request"]
+        .iter()
+        .fold(format!("AWS4{}", secret).into_bytes(), |key, part| hmac_sha256(&key, part));
+    let signature = hex::encode(hmac_sha256(&key, &string_to_sign));
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    );
+    (amz_date, authorization)
+}
+
+// The file as a request body, reporting progress each time another percent has been read
+async fn progress_body<R: Runtime>(app: &AppHandle<R>, upload_id: &str, path: &Path) -> Result<(Body, u64), String> {
+    let file = tokio::fs::File::open(path).await.map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let total_bytes = file.metadata().await.map_err(|e| format!("Failed to read {}: {}", path.display(), e))?.len();
+    let progress = UploadProgress {
+        upload_id: upload_id.to_string(),
+        file: path.display().to_string(),
+        bytes_sent: 0,
+        total_bytes,
+    };
+    let state = (file, progress, app.clone(), None::<u64>);
+    let chunks = stream::unfold(state, |(mut file, mut progress, app, last_percent)| async move {
+        let mut buffer = vec![0u8; UPLOAD_CHUNK_BYTES];
+        match file.read(&mut buffer).await {
+            Ok(0) => None,
+            Ok(read) => {
+                buffer.truncate(read);
+                progress.bytes_sent += read as u64;
+                let percent = progress.bytes_sent * 100 / progress.total_bytes.max(1);
+                if last_percent != Some(percent) {
+                    if let Err(e) = app.emit("storage-upload-progress", &progress) {
+                        log_error!("Failed to emit storage-upload-progress event: {}", e);
+                    }
+                }
+                Some((Ok(bytes::Bytes::from(buffer)), (file, progress, app, Some(percent))))
+            }
+            Err(e) => Some((Err(e), (file, progress, app, last_percent))),
+        }
+    });
+    Ok((Body::wrap_stream(chunks), total_bytes))
+}
+
+async fn check_response(response: reqwest::Response, action: &str) -> Result<(), String> {
+    if response.status().is_success() {
+        return Ok(());
+    }
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Err(format!("Failed to {}: {} {}", action, status, body.chars().take(300).collect::<String>()))
+}
+
+// Create each missing directory on the way to `path`; servers answer 405 for existing ones
+async fn webdav_mkdirs(client: &reqwest::Client, base: &str, path: &str, username: &str, password: &str) -> Result<(), String> {
+    let mkcol = Method::from_bytes(b"MKCOL").map_err(|e| e.to_string())?;
+    let mut current = String::new();
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        current = join_path(&[&current, segment]);
+        let response = client
+            .request(mkcol.clone(), format!("{}/{}/", base, encode_path(&current)))
+            .basic_auth(username, Some(password))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to create WebDAV folder {}: {}", current, e))?;
+        if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+            check_response(response, &format!("create WebDAV folder {}", current)).await?;
+        }
+    }
+    Ok(())
+}
+
+async fn upload<R: Runtime>(
+    app: &AppHandle<R>,
+    target: &RemoteTarget,
+    upload_id: &str,
+    path: &Path,
+    kind: ArtifactKind,
+) -> Result<String, String> {
+    let file_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("Not a file: {}", path.display()))?;
+    let secret = keychain::get_secret(SECRET_ACCOUNT)?
+        .ok_or("No storage credentials in the keychain; save the storage settings again")?;
+    let client = reqwest::Client::new();
+    let (body, total_bytes) = progress_body(app, upload_id, path).await?;
+
+    match target {
+        RemoteTarget::S3 { endpoint, region, bucket, access_key_id, prefix } => {
+            let key = join_path(&[prefix, kind.folder(), file_name]);
+            let url = format!("{}/{}/{}", endpoint.trim_end_matches('/'), encode_path(bucket), encode_path(&key));
+            let url = Url::parse(&url).map_err(|e| format!("Invalid S3 URL: {}", e))?;
+            let (amz_date, authorization) = sign_s3_put(&url, region, access_key_id, &secret);
+            let response = client
+                .put(url.clone())
+                .header(HOST, host_header(&url))
+                .header("x-amz-content-sha256", UNSIGNED_PAYLOAD)
+                .header("x-amz-date", amz_date)
+                .header(AUTHORIZATION, authorization)
+                .header(CONTENT_LENGTH, total_bytes)
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to upload to S3: {}", e))?;
+            check_response(response, "upload to S3").await?;
+            Ok(url.to_string())
+        }
+        RemoteTarget::WebDav { url, username, directory } => {
+            let base = url.trim_end_matches('/');
+            let folder = join_path(&[directory, kind.folder()]);
+            webdav_mkdirs(&client, base, &folder, username, &secret).await?;
+            let location = format!("{}/{}", base, encode_path(&join_path(&[&folder, file_name])));
+            let response = client
+                .put(&location)
+                .basic_auth(username, Some(&secret))
+                .header(CONTENT_LENGTH, total_bytes)
+                .body(body)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to upload to WebDAV: {}", e))?;
+            check_response(response, "upload to WebDAV").await?;
+            Ok(location)
+        }
+    }
+}
+
+async fn run_upload<R: Runtime>(app: &AppHandle<R>, target: &RemoteTarget, path: &Path, kind: ArtifactKind) -> UploadResult {
+    let upload_id = uuid::Uuid::new_v4().to_string();
+    log_info!("Uploading {} to remote storage", path.display());
+    let (location, error) = match upload(app, target, &upload_id, path, kind).await {
+        Ok(location) => {
+            log_info!("Uploaded {} to {}", path.display(), location);
+            (Some(location), None)
+        }
+        Err(e) => {
+            log_error!("{}", e);
+            (None, Some(e))
+        }
+    };
+    let result = UploadResult { upload_id, file: path.display().to_string(), location, error };
+    if let Err(e) = app.emit("storage-upload-finished", &result) {
+        log_error!("Failed to emit storage-upload-finished event: {}", e);
+    }
+    result
+}
+
+/// Upload a freshly written file in the background if remote storage is set up for its
+/// kind. The outcome is reported through the `storage-upload-finished` event.
+pub fn upload_in_background<R: Runtime>(app: &AppHandle<R>, path: PathBuf, kind: ArtifactKind) {
+    let config = load_config(app);
+    if !config.uploads(kind) {
+        return;
+    }
+    let Some(target) = config.target else { return };
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run_upload(&app, &target, &path, kind).await;
+    });
+}
+
+#[tauri::command]
+pub async fn get_remote_storage_config<R: Runtime>(app: AppHandle<R>) -> Result<RemoteStorageConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the remote storage settings. A `secret` replaces the stored S3 secret key or WebDAV
+/// password; an empty one removes it, and leaving it out keeps the current one.
+#[tauri::command]
+pub async fn set_remote_storage_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: RemoteStorageConfig,
+    secret: Option<String>,
+) -> Result<(), String> {
+    config.validate()?;
+    match secret.as_deref() {
+        Some("") => keychain::delete_secret(SECRET_ACCOUNT)?,
+        Some(secret) => keychain::set_secret(SECRET_ACCOUNT, secret)?,
+        None => {}
+    }
+    if config.enabled && keychain::get_secret(SECRET_ACCOUNT)?.is_none() {
+        log_warn!("Remote storage enabled without credentials; uploads will fail until they are saved");
+    }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(STORAGE_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log_info!("Remote storage {}", if config.enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
+
+/// Upload a file to the configured target now, whether or not automatic uploads are on.
+#[tauri::command]
+pub async fn upload_to_remote_storage<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+    kind: ArtifactKind,
+) -> Result<UploadResult, String> {
+    let target = load_config(&app).target.ok_or("No remote storage is configured")?;
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("File not found: {}", path.display()));
+    }
+    Ok(run_upload(&app, &target, &path, kind).await)
+}
@@ -0,0 +1,228 @@
+// Questions about one meeting, answered by the configured model from the parts of the
+// transcript that matter. The transcript is cut into passages of consecutive lines, the
+// passages closest in meaning to the question are found with local embeddings, and only
+// those go into the prompt, so even a meeting far longer than the model's context can be
+// asked about. The answer streams in as `ask-meeting-progress` events.
+//
+// Passage embeddings are kept in memory per meeting, so follow-up questions only embed the
+// question itself.
+use std::collections::HashMap;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api::{make_api_request, MeetingDetails, MeetingTranscript};
+use crate::embeddings::{self, cosine_similarity};
+use crate::llm;
+use crate::storage;
+
+// About 200 tokens per passage, so a handful fit in any model's context
+const PASSAGE_CHARS: usize = 800;
+const TOP_PASSAGES: usize = 6;
+// Meetings whose passage embeddings are kept; the oldest is dropped beyond this
+const MAX_CACHED_MEETINGS: usize = 8;
+
+const ANSWER_PROMPT: &str = "You answer questions about a meeting using only the transcript excerpts \
+provided. Each excerpt starts with its timestamp; cite the timestamps you relied on in square \
+brackets. If the excerpts do not contain the answer, say so instead of guessing.";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AnswerSource {
+    /// Transcript line the passage starts at
+    pub transcript_id: String,
+    pub timestamp: String,
+    pub text: String,
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingAnswer {
+    pub answer: String,
+    pub sources: Vec<AnswerSource>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct AnswerProgress<'a> {
+    meeting_id: &'a str,
+    token: &'a str,
+}
+
+#[derive(Clone)]
+struct Passage {
+    transcript_id: String,
+    timestamp: String,
+    text: String,
+}
+
+struct MeetingIndex {
+    // Number of transcript lines indexed; a different count means the transcript changed
+    line_count: usize,
+    passages: Vec<Passage>,
+    vectors: Vec<Vec<f32>>,
+}
+
+static INDEXES: Lazy<Mutex<Vec<(String, MeetingIndex)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn passages(lines: &[MeetingTranscript]) -> Vec<Passage> {
+    let mut passages: Vec<Passage> = Vec::new();
+    let mut current: Option<Passage> = None;
+    for line in lines.iter().filter(|line| !line.text.trim().is_empty()) {
+        let passage = current.get_or_insert_with(|| Passage {
+            transcript_id: line.id.clone(),
+            timestamp: line.timestamp.clone(),
+            text: String::new(),
+        });
+        if !passage.text.is_empty() {
+            passage.text.push(' ');
+        }
+        passage.text.push_str(line.text.trim());
+        if passage.text.len() >= PASSAGE_CHARS {
+            passages.extend(current.take());
+        }
+    }
+    passages.extend(current);
+    passages
+}
+
+// Local meetings are read from the local store, anything else from the backend
+async fn load_meeting<R: Runtime>(
+    app: &AppHandle<R>,
+    meeting_id: &str,
+    auth_token: Option<String>,
+) -> Result<MeetingDetails, String> {
+    if let Ok(meeting) = storage::meetings::get_meeting(app, meeting_id) {
+        return Ok(meeting);
+    }
+    Ok(make_api_request::<R, MeetingDetails>(app, &format!("/get-meeting/{}", meeting_id), "GET", None, None, auth_token).await?)
+}
+
+fn cached(meeting_id: &str, line_count: usize) -> Option<(Vec<Passage>, Vec<Vec<f32>>)> {
+    let indexes = INDEXES.lock().ok()?;
+    indexes
+        .iter()
+        .find(|(id, index)| id == meeting_id && index.line_count == line_count)
+        .map(|(_, index)| (index.passages.clone(), index.vectors.clone()))
+}
+
+fn cache(meeting_id: &str, index: MeetingIndex) {
+    if let Ok(mut indexes) = INDEXES.lock() {
+        indexes.retain(|(id, _)| id != meeting_id);
+        if indexes.len() >= MAX_CACHED_MEETINGS {
+            indexes.remove(0);
+        }
+        indexes.push((meeting_id.to_string(), index));
+    }
+}
+
+// Without embeddings, rank by how many of the question's words a passage contains
+fn keyword_scores(question: &str, passages: &[Passage]) -> Vec<f32> {
+    let words: Vec<String> = question
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.len() > 2)
+        .map(str::to_string)
+        .collect();
+    passages
+        .iter()
+        .map(|passage| {
+            let text = passage.text.to_lowercase();
+            words.iter().filter(|word| text.contains(word.as_str())).count() as f32 / words.len().max(1) as f32
+        })
+        .collect()
+}
+
+async fn rank_passages(meeting: &MeetingDetails, question: &str) -> Vec<(Passage, f32)> {
+    let line_count = meeting.transcripts.len();
+    let (passages, vectors) = match cached(&meeting.id, line_count) {
+        Some(index) => index,
+        None => {
+            let passages = passages(&meeting.transcripts);
+            let texts: Vec<String> = passages.iter().map(|passage| passage.text.clone()).collect();
+            match embeddings::embed(&texts).await {
+                Ok(vectors) => {
+                    cache(&meeting.id, MeetingIndex { line_count, passages: passages.clone(), vectors: vectors.clone() });
+                    (passages, vectors)
+                }
+                Err(e) => {
+                    log_warn!("Embeddings unavailable, matching passages by keyword: {}", e);
+                    (passages, Vec::new())
+                }
+            }
+        }
+    };
+
+    let scores = if vectors.is_empty() {
+        keyword_scores(question, &passages)
+    } else {
+        match embeddings::embed(&[question.to_string()]).await {
+            Ok(query) => vectors.iter().map(|vector| cosine_similarity(&query[0], vector)).collect(),
+            Err(e) => {
+                log_warn!("Failed to embed the question, matching passages by keyword: {}", e);
+                keyword_scores(question, &passages)
+            }
+        }
+    };
+    let mut ranked: Vec<(Passage, f32)> = passages.into_iter().zip(scores).collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+    ranked.truncate(TOP_PASSAGES);
+    // Back in transcript order, which reads better in the prompt
+    let position: HashMap<String, usize> = meeting
+        .transcripts
+        .iter()
+        .enumerate()
+        .map(|(index, line)| (line.id.clone(), index))
+        .collect();
+    ranked.sort_by_key(|(passage, _)| position.get(&passage.transcript_id).copied().unwrap_or(usize::MAX));
+    ranked
+}
+
+/// Answer a question about a meeting from its transcript with the configured model. The
+/// answer is streamed as `ask-meeting-progress` events and returned with the passages it
+/// was based on.
+#[tauri::command]
+pub async fn ask_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    question: String,
+    auth_token: Option<String>,
+) -> Result<MeetingAnswer, String> {
+    let question = question.trim();
+    if question.is_empty() {
+        return Err("Ask a question".to_string());
+    }
+    log_info!("ask_meeting called for meeting_id: {}", meeting_id);
+    let meeting = load_meeting(&app, &meeting_id, auth_token.clone()).await?;
+    if meeting.transcripts.is_empty() {
+        return Err(format!("Meeting {} has no transcript", meeting_id));
+    }
+    let ranked = rank_passages(&meeting, question).await;
+    let excerpts: String = ranked
+        .iter()
+        .map(|(passage, _)| format!("[{}] {}\n\n", passage.timestamp, passage.text))
+        .collect();
+    let prompt = format!("Meeting: {}\n\nTranscript excerpts:\n\n{}Question: {}", meeting.title, excerpts, question);
+
+    let config = llm::configured(&app, auth_token).await?;
+    let answer = llm::stream(&config, ANSWER_PROMPT, &prompt, |token| {
+        let progress = AnswerProgress { meeting_id: &meeting_id, token };
+        if let Err(e) = app.emit("ask-meeting-progress", progress) {
+            log_error!("Failed to emit ask-meeting-progress event: {}", e);
+        }
+    })
+    .await?;
+
+    Ok(MeetingAnswer {
+        answer,
+        sources: ranked
+            .into_iter()
+            .map(|(passage, score)| AnswerSource {
+                transcript_id: passage.transcript_id,
+                timestamp: passage.timestamp,
+                text: passage.text,
+                score,
+            })
+            .collect(),
+    })
+}
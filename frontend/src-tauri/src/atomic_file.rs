@@ -0,0 +1,146 @@
+// Crash-safe file writes. Data goes to a temporary file next to the target, is fsynced and
+// then renamed over the target, so readers only ever see the old file or the complete new
+// one. Each write also leaves a `<file>.sha256` sidecar in sha256sum format, which
+// `read_verified` checks to catch files that were damaged or truncated afterwards.
+//
+// These mirror `std::fs::write`/`std::fs::read` and return `io::Result`, so callers keep
+// their own error messages.
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+
+const CHECKSUM_EXTENSION: &str = "sha256";
+
+fn checksum_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(CHECKSUM_EXTENSION);
+    path.with_file_name(name)
+}
+
+fn temp_path(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4()))
+}
+
+// Make the renames themselves durable; directories can't be opened for syncing on Windows
+fn sync_parent(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+fn replace(temp: &Path, path: &Path) -> io::Result<()> {
+    std::fs::rename(temp, path).inspect_err(|_| {
+        let _ = std::fs::remove_file(temp);
+    })
+}
+
+fn write_checksum(path: &Path, digest: &[u8]) -> io::Result<()> {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let line = format!("{}  {}\n", hex::encode(digest), name);
+    let sidecar = checksum_path(path);
+    let temp = temp_path(&sidecar);
+    let mut file = File::create(&temp)?;
+    file.write_all(line.as_bytes())?;
+    file.sync_all()?;
+    replace(&temp, &sidecar)
+}
+
+/// Write `contents` to `path` atomically, replacing any existing file.
+pub fn write(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let mut file = AtomicFile::create(path)?;
+    file.write_all(contents.as_ref())?;
+    file.commit()
+}
+
+/// A file being written that only appears at its path once `commit` is called. Dropping it
+/// uncommitted discards what was written. Seekable, for writers that patch headers.
+pub struct AtomicFile {
+    file: File,
+    path: PathBuf,
+    temp: PathBuf,
+    committed: bool,
+}
+
+impl AtomicFile {
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let temp = temp_path(&path);
+        let file = File::options().read(true).write(true).create_new(true).open(&temp)?;
+        Ok(Self { file, path, temp, committed: false })
+    }
+
+    /// Sync the data, move it into place and record its checksum.
+    pub fn commit(mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file.sync_all()?;
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut hasher = Sha256::new();
+        io::copy(&mut self.file, &mut hasher)?;
+
+        replace(&self.temp, &self.path)?;
+        self.committed = true;
+        write_checksum(&self.path, &hasher.finalize())?;
+        sync_parent(&self.path)
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Seek for AtomicFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos)
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.temp);
+        }
+    }
+}
+
+/// Read a file and check it against its checksum sidecar. Files without a sidecar (written
+/// before checksums existed, or by hand) are returned as they are.
+pub fn read_verified(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let path = path.as_ref();
+    let mut data = Vec::new();
+    File::open(path)?.read_to_end(&mut data)?;
+    let expected = match std::fs::read_to_string(checksum_path(path)) {
+        Ok(line) => line.split_whitespace().next().unwrap_or_default().to_lowercase(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(data),
+        Err(e) => return Err(e),
+    };
+    if hex::encode(Sha256::digest(&data)) != expected {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} does not match its checksum; the file is damaged", path.display()),
+        ));
+    }
+    Ok(data)
+}
+
+/// Remove a file along with its checksum sidecar.
+pub fn remove(path: impl AsRef<Path>) -> io::Result<()> {
+    let path = path.as_ref();
+    match std::fs::remove_file(checksum_path(path)) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    std::fs::remove_file(path)
+}
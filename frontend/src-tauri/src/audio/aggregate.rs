@@ -0,0 +1,147 @@
+// User-created Aggregate Devices on macOS (e.g. built-in mic + BlackHole) show up in cpal
+// as plain input devices. CoreAudio is queried directly to find out what they combine.
+use serde::Serialize;
+
+// Virtual drivers that route system output back in as an input
+const LOOPBACK_DRIVERS: [&str; 5] = ["blackhole", "soundflower", "loopback audio", "vb-cable", "background music"];
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct SubDevice {
+    pub name: String,
+    pub has_input: bool,
+    pub has_output: bool,
+    /// Virtual loopback driver carrying system audio
+    pub is_loopback: bool,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct AggregateDevice {
+    pub name: String,
+    pub sub_devices: Vec<SubDevice>,
+}
+
+impl AggregateDevice {
+    /// True when the aggregate combines a real input with a loopback driver, so a single
+    /// stream from it already contains both the user's voice and the system audio.
+    pub fn captures_system_audio(&self) -> bool {
+        self.sub_devices.iter().any(|d| d.is_loopback && d.has_input)
+            && self.sub_devices.iter().any(|d| !d.is_loopback && d.has_input)
+    }
+}
+
+pub fn is_loopback_driver(name: &str) -> bool {
+    let name = name.to_lowercase();
+    LOOPBACK_DRIVERS.iter().any(|driver| name.contains(driver))
+}
+
+#[cfg(target_os = "macos")]
+mod coreaudio {
+    use super::{is_loopback_driver, AggregateDevice, SubDevice};
+    use core_foundation::base::TCFType;
+    use core_foundation::string::{CFString, CFStringRef};
+    use coreaudio_sys::{
+        kAudioAggregateDevicePropertyActiveSubDeviceList, kAudioDevicePropertyStreams,
+        kAudioDevicePropertyTransportType, kAudioDeviceTransportTypeAggregate,
+        kAudioHardwarePropertyDevices, kAudioObjectPropertyElementMaster, kAudioObjectPropertyName,
+        kAudioObjectPropertyScopeGlobal, kAudioObjectPropertyScopeInput,
+        kAudioObjectPropertyScopeOutput, kAudioObjectSystemObject, AudioObjectGetPropertyData,
+        AudioObjectGetPropertyDataSize, AudioObjectID, AudioObjectPropertyAddress,
+        AudioObjectPropertyScope, AudioObjectPropertySelector,
+    };
+    use std::mem;
+    use std::ptr;
+
+    fn address(selector: AudioObjectPropertySelector, scope: AudioObjectPropertyScope) -> AudioObjectPropertyAddress {
+        AudioObjectPropertyAddress {
+            mSelector: selector,
+            mScope: scope,
+            mElement: kAudioObjectPropertyElementMaster,
+        }
+    }
+
+    fn data_size(object: AudioObjectID, address: &AudioObjectPropertyAddress) -> Option<u32> {
+        let mut size = 0u32;
+        let status = unsafe { AudioObjectGetPropertyDataSize(object, address, 0, ptr::null(), &mut size) };
+        (status == 0).then_some(size)
+    }
+
+    fn object_list(object: AudioObjectID, selector: AudioObjectPropertySelector) -> Vec<AudioObjectID> {
+        let address = address(selector, kAudioObjectPropertyScopeGlobal);
+        let mut size = match data_size(object, &address) {
+            Some(size) if size > 0 => size,
+            _ => return Vec::new(),
+        };
+        let mut ids = vec![0 as AudioObjectID; size as usize / mem::size_of::<AudioObjectID>()];
+        let status = unsafe {
+            AudioObjectGetPropertyData(object, &address, 0, ptr::null(), &mut size, ids.as_mut_ptr() as *mut _)
+        };
+        if status != 0 {
+            return Vec::new();
+        }
+        ids.truncate(size as usize / mem::size_of::<AudioObjectID>());
+        ids
+    }
+
+    fn u32_property(object: AudioObjectID, selector: AudioObjectPropertySelector) -> Option<u32> {
+        let address = address(selector, kAudioObjectPropertyScopeGlobal);
+        let mut value = 0u32;
+        let mut size = mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            AudioObjectGetPropertyData(object, &address, 0, ptr::null(), &mut size, &mut value as *mut u32 as *mut _)
+        };
+        (status == 0).then_some(value)
+    }
+
+    fn name(object: AudioObjectID) -> Option<String> {
+        let address = address(kAudioObjectPropertyName, kAudioObjectPropertyScopeGlobal);
+        let mut cf_name: CFStringRef = ptr::null();
+        let mut size = mem::size_of::<CFStringRef>() as u32;
+        let status = unsafe {
+            AudioObjectGetPropertyData(object, &address, 0, ptr::null(), &mut size, &mut cf_name as *mut CFStringRef as *mut _)
+        };
+        if status != 0 || cf_name.is_null() {
+            return None;
+        }
+        // The property follows the create rule, so the CFString is released on drop
+        Some(unsafe { CFString::wrap_under_create_rule(cf_name) }.to_string())
+    }
+
+    fn has_streams(object: AudioObjectID, scope: AudioObjectPropertyScope) -> bool {
+        data_size(object, &address(kAudioDevicePropertyStreams, scope)).is_some_and(|size| size > 0)
+    }
+
+    pub fn list() -> Vec<AggregateDevice> {
+        object_list(kAudioObjectSystemObject, kAudioHardwarePropertyDevices)
+            .into_iter()
+            .filter(|&id| u32_property(id, kAudioDevicePropertyTransportType) == Some(kAudioDeviceTransportTypeAggregate))
+            .filter_map(|id| {
+                let sub_devices = object_list(id, kAudioAggregateDevicePropertyActiveSubDeviceList)
+                    .into_iter()
+                    .filter_map(|sub_id| {
+                        let sub_name = name(sub_id)?;
+                        Some(SubDevice {
+                            is_loopback: is_loopback_driver(&sub_name),
+                            has_input: has_streams(sub_id, kAudioObjectPropertyScopeInput),
+                            has_output: has_streams(sub_id, kAudioObjectPropertyScopeOutput),
+                            name: sub_name,
+                        })
+                    })
+                    .collect();
+                Some(AggregateDevice { name: name(id)?, sub_devices })
+            })
+            .collect()
+    }
+}
+
+/// Aggregate devices currently configured in Audio MIDI Setup. Always empty on other platforms.
+pub fn list_aggregate_devices() -> Vec<AggregateDevice> {
+    #[cfg(target_os = "macos")]
+    {
+        coreaudio::list()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        Vec::new()
+    }
+}
@@ -124,6 +124,207 @@ pub fn audio_to_mono(audio: &[f32], channels: u16) -> Vec<f32> {
     mono_samples
 }
 
+/// First-order high-pass filter. Keeps its state between calls so consecutive blocks
+/// of the same stream are filtered without clicks at the boundaries.
+#[derive(Debug, Clone)]
+pub struct HighPassFilter {
+    alpha: f32,
+    prev_input: f32,
+    prev_output: f32,
+}
+
+impl HighPassFilter {
+    pub fn new(cutoff_hz: f32, sample_rate: u32) -> Self {
+        let rc = 1.0 / (2.0 * std::f32::consts::PI * cutoff_hz);
+        let dt = 1.0 / sample_rate as f32;
+        Self {
+            alpha: rc / (rc + dt),
+            prev_input: 0.0,
+            prev_output: 0.0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            let output = self.alpha * (self.prev_output + *sample - self.prev_input);
+            self.prev_input = *sample;
+            self.prev_output = output;
+            *sample = output;
+        }
+    }
+}
+
+/// Noise gate that mutes the signal while its level stays below a threshold, e.g. to keep
+/// HVAC hum and keyboard bleed between sentences out of the transcript. Opens quickly,
+/// holds briefly after speech and fades out so word endings are not clipped.
+#[derive(Debug, Clone)]
+pub struct NoiseGate {
+    threshold: f32,
+    attack: f32,
+    release: f32,
+    hold_samples: usize,
+    detector: f32,
+    level: f32,
+    gain: f32,
+    hold_remaining: usize,
+}
+
+impl NoiseGate {
+    const ATTACK_SECS: f32 = 0.005;
+    const RELEASE_SECS: f32 = 0.15;
+    const HOLD_SECS: f32 = 0.1;
+    // Time constant of the level detector
+    const DETECTOR_SECS: f32 = 0.01;
+
+    pub fn new(threshold_db: f32, sample_rate: u32) -> Self {
+        let coefficient = |secs: f32| (-1.0 / (secs * sample_rate as f32)).exp();
+        Self {
+            threshold: 10f32.powf(threshold_db / 20.0),
+            attack: coefficient(Self::ATTACK_SECS),
+            release: coefficient(Self::RELEASE_SECS),
+            hold_samples: (Self::HOLD_SECS * sample_rate as f32) as usize,
+            detector: coefficient(Self::DETECTOR_SECS),
+            level: 0.0,
+            gain: 0.0,
+            hold_remaining: 0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            self.level = sample.abs().max(self.level * self.detector);
+            let open = if self.level >= self.threshold {
+                self.hold_remaining = self.hold_samples;
+                true
+            } else if self.hold_remaining > 0 {
+                self.hold_remaining -= 1;
+                true
+            } else {
+                false
+            };
+            let (target, coefficient) = if open { (1.0, self.attack) } else { (0.0, self.release) };
+            self.gain = target + (self.gain - target) * coefficient;
+            *sample *= self.gain;
+        }
+    }
+}
+
+/// Automatic gain control that steers the signal's RMS towards a target level, so quiet
+/// speakers are transcribed as reliably as loud ones. The gain drops quickly when the
+/// level rises (attack) and recovers slowly (release); below the floor the input counts
+/// as silence and the gain is held, so pauses are not boosted into audible noise.
+#[derive(Debug, Clone)]
+pub struct AutoGainControl {
+    target: f32,
+    attack: f32,
+    release: f32,
+    detector: f32,
+    mean_square: f32,
+    gain: f32,
+}
+
+impl AutoGainControl {
+    const ATTACK_SECS: f32 = 0.01;
+    const RELEASE_SECS: f32 = 0.8;
+    // Time constant of the RMS detector, about a syllable
+    const DETECTOR_SECS: f32 = 0.1;
+    const FLOOR_RMS: f32 = 0.002;
+    const MIN_GAIN: f32 = 0.1;
+    const MAX_GAIN: f32 = 10.0;
+
+    pub fn new(target_db: f32, sample_rate: u32) -> Self {
+        let coefficient = |secs: f32| (-1.0 / (secs * sample_rate as f32)).exp();
+        Self {
+            target: 10f32.powf(target_db / 20.0),
+            attack: coefficient(Self::ATTACK_SECS),
+            release: coefficient(Self::RELEASE_SECS),
+            detector: coefficient(Self::DETECTOR_SECS),
+            mean_square: 0.0,
+            gain: 1.0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for sample in samples.iter_mut() {
+            self.mean_square = sample * sample + (self.mean_square - sample * sample) * self.detector;
+            let rms = self.mean_square.sqrt();
+            if rms >= Self::FLOOR_RMS {
+                let desired = (self.target / rms).clamp(Self::MIN_GAIN, Self::MAX_GAIN);
+                let coefficient = if desired < self.gain { self.attack } else { self.release };
+                self.gain = desired + (self.gain - desired) * coefficient;
+            }
+            *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+// Input block size fed to the streaming sinc resampler
+const SINC_CHUNK_SIZE: usize = 1024;
+
+/// Windowed-sinc resampling of a complete buffer. The resampler's delay is trimmed and
+/// its tail flushed, so the output lines up with the input and has exactly
+/// `len * to / from` samples. Cheaper than `resample` and meant for live audio.
+pub fn resample_sinc(input: &[f32], from_sample_rate: u32, to_sample_rate: u32) -> Result<Vec<f32>> {
+    let ratio = to_sample_rate as f64 / from_sample_rate as f64;
+    let expected_len = (input.len() as f64 * ratio).round() as usize;
+    if input.is_empty() || from_sample_rate == to_sample_rate {
+        return Ok(input.to_vec());
+    }
+
+    let params = SincInterpolationParameters {
+        sinc_len: 128,
+        f_cutoff: 0.95,
+        interpolation: SincInterpolationType::Cubic,
+        oversampling_factor: 128,
+        window: WindowFunction::BlackmanHarris2,
+    };
+    let mut resampler = SincFixedIn::<f32>::new(ratio, 1.0, params, SINC_CHUNK_SIZE, 1)?;
+    let delay = resampler.output_delay();
+    let mut output = Vec::with_capacity(expected_len + delay + SINC_CHUNK_SIZE);
+
+    let mut blocks = input.chunks_exact(SINC_CHUNK_SIZE);
+    for block in blocks.by_ref() {
+        let out = resampler.process(&[block], None)?;
+        output.extend_from_slice(&out[0]);
+    }
+    let remainder = blocks.remainder();
+    if !remainder.is_empty() {
+        let out = resampler.process_partial(Some(&[remainder]), None)?;
+        output.extend_from_slice(&out[0]);
+    }
+    // Push zeros through until the delayed end of the input has come out
+    while output.len() < expected_len + delay {
+        let out = resampler.process_partial::<&[f32]>(None, None)?;
+        if out[0].is_empty() {
+            break;
+        }
+        output.extend_from_slice(&out[0]);
+    }
+
+    output.drain(..delay.min(output.len()));
+    output.resize(expected_len, 0.0);
+    Ok(output)
+}
+
+/// Linear interpolation resampling. Used as a fallback and as the benchmark baseline.
+pub fn resample_linear(input: &[f32], from_sample_rate: u32, to_sample_rate: u32) -> Vec<f32> {
+    if input.is_empty() || from_sample_rate == to_sample_rate {
+        return input.to_vec();
+    }
+    let step = from_sample_rate as f64 / to_sample_rate as f64;
+    let output_len = (input.len() as f64 / step).round() as usize;
+    (0..output_len)
+        .map(|i| {
+            let position = i as f64 * step;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = input[index.min(input.len() - 1)];
+            let next = input[(index + 1).min(input.len() - 1)];
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
 pub fn resample(input: &[f32], from_sample_rate: u32, to_sample_rate: u32) -> Result<Vec<f32>> {
     debug!("Resampling audio");
     let params = SincInterpolationParameters {
@@ -1,4 +1,4 @@
-use super::audio_processing::audio_to_mono; 
+use super::audio_processing::{audio_to_mono, resample_linear};
 use anyhow::{anyhow, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::StreamError;
@@ -27,6 +27,12 @@ pub enum AudioTranscriptionEngine {
     WhisperDistilLargeV3,
     WhisperLargeV3Turbo,
     WhisperLargeV3,
+    /// External whisper server, falling back to the local model when unreachable
+    WhisperServer,
+    /// In-process whisper.cpp via whisper-rs
+    WhisperLocal,
+    /// OpenAI's hosted transcription API
+    OpenAi,
 }
 
 impl fmt::Display for AudioTranscriptionEngine {
@@ -37,6 +43,30 @@ impl fmt::Display for AudioTranscriptionEngine {
             AudioTranscriptionEngine::WhisperDistilLargeV3 => write!(f, "WhisperLarge"),
             AudioTranscriptionEngine::WhisperLargeV3Turbo => write!(f, "WhisperLargeV3Turbo"),
             AudioTranscriptionEngine::WhisperLargeV3 => write!(f, "WhisperLargeV3"),
+            AudioTranscriptionEngine::WhisperServer => write!(f, "WhisperServer"),
+            AudioTranscriptionEngine::WhisperLocal => write!(f, "WhisperLocal"),
+            AudioTranscriptionEngine::OpenAi => write!(f, "OpenAI"),
+        }
+    }
+}
+
+impl AudioTranscriptionEngine {
+    /// Engine stored in the app settings under `transcriptionEngine`.
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "whisper_server" => Some(AudioTranscriptionEngine::WhisperServer),
+            "whisper_local" => Some(AudioTranscriptionEngine::WhisperLocal),
+            "openai" => Some(AudioTranscriptionEngine::OpenAi),
+            _ => None,
+        }
+    }
+
+    pub fn setting_name(&self) -> Option<&'static str> {
+        match self {
+            AudioTranscriptionEngine::WhisperServer => Some("whisper_server"),
+            AudioTranscriptionEngine::WhisperLocal => Some("whisper_local"),
+            AudioTranscriptionEngine::OpenAi => Some("openai"),
+            _ => None,
         }
     }
 }
@@ -386,9 +416,14 @@ pub struct AudioStream {
     stream_control: mpsc::Sender<StreamControl>,
     stream_thread: Option<Arc<tokio::sync::Mutex<Option<thread::JoinHandle<()>>>>>,
     is_disconnected: Arc<AtomicBool>,
+    // Capture standing in for this one after its device was lost, and the task feeding it in
+    replacement: Arc<tokio::sync::Mutex<Option<(AudioStream, tokio::task::JoinHandle<()>)>>>,
 }
 
-enum StreamControl {
+// How long a reconnected device gets to deliver its first audio before it counts as failed
+const RECONNECT_FIRST_AUDIO_TIMEOUT: Duration = Duration::from_secs(3);
+
+pub(super) enum StreamControl {
     Stop(oneshot::Sender<()>),
 }
 
@@ -491,10 +526,10 @@ impl AudioStream {
                 } else {
                     error!("an error occurred on the audio stream: {}", err);
                     if err.to_string().contains("device is no longer valid") {
-                        warn!("audio device disconnected. stopping recording.");
-                        if let Some(arc) = is_running_weak_for_error.upgrade() {
-                            arc.store(false, Ordering::Relaxed);
-                        }
+                        // Left to the device watchdog to reconnect rather than ending the recording
+                        warn!("audio device {} disconnected. stopping stream.", device_name_clone);
+                        let _ = stream_control_tx_clone.send(StreamControl::Stop(oneshot::channel().0));
+                        is_disconnected_clone.store(true, Ordering::Relaxed);
                     }
                 }
             };
@@ -659,14 +694,112 @@ impl AudioStream {
             stream_control: stream_control_tx,
             stream_thread: Some(stream_thread),
             is_disconnected,
+            replacement: Arc::new(tokio::sync::Mutex::new(None)),
         })
     }
 
+    /// Capture only the audio played by process `pid` (and the processes it started) instead
+    /// of a whole output device. `name` labels the stream's device in logs and events.
+    #[cfg(target_os = "windows")]
+    pub fn from_process(pid: u32, name: &str, is_running: Arc<AtomicBool>) -> Result<Self> {
+        use super::process_loopback;
+
+        info!("Initializing audio stream for process {} ({})", name, pid);
+        let (tx, _) = broadcast::channel::<Vec<f32>>(1000);
+        let transmitter = Arc::new(tx.clone());
+        let is_disconnected = Arc::new(AtomicBool::new(false));
+        let is_disconnected_clone = is_disconnected.clone();
+        let is_running_weak = Arc::downgrade(&is_running);
+        let (stream_control_tx, stream_control_rx) = mpsc::channel();
+        let stream_thread = thread::spawn(move || {
+            if let Err(e) = process_loopback::capture(pid, &tx, &stream_control_rx, is_running_weak) {
+                error!("Process audio capture for {} failed: {}", pid, e);
+                // Lets the device watchdog fall back to capturing the whole output device
+                is_disconnected_clone.store(true, Ordering::Relaxed);
+            }
+        });
+
+        let device_config = cpal::SupportedStreamConfig::new(
+            process_loopback::CHANNELS,
+            cpal::SampleRate(process_loopback::SAMPLE_RATE),
+            cpal::SupportedBufferSize::Unknown,
+            cpal::SampleFormat::F32,
+        );
+        Ok(AudioStream {
+            device: Arc::new(AudioDevice::new(format!("{} (application)", name), DeviceType::Output)),
+            device_config,
+            transmitter,
+            stream_control: stream_control_tx,
+            stream_thread: Some(Arc::new(tokio::sync::Mutex::new(Some(stream_thread)))),
+            is_disconnected,
+            replacement: Arc::new(tokio::sync::Mutex::new(None)),
+        })
+    }
+
+    /// Whether the device currently feeding this stream has gone away.
+    pub async fn is_disconnected(&self) -> bool {
+        match &*self.replacement.lock().await {
+            Some((replacement, _)) => replacement.is_disconnected.load(Ordering::Acquire),
+            None => self.is_disconnected.load(Ordering::Acquire),
+        }
+    }
+
+    /// Capture from `device` in place of the device this stream lost. Its audio is resampled
+    /// to this stream's rate and delivered to the existing subscribers, so consumers carry
+    /// on without noticing. Fails unless the device produces audio within a few seconds.
+    pub async fn reconnect(&self, device: Arc<AudioDevice>, is_running: Arc<AtomicBool>) -> Result<()> {
+        let stream = AudioStream::from_device(device.clone(), is_running).await?;
+        let mut receiver = stream.subscribe().await;
+        // A stream whose thread failed to start still constructs, so wait for real audio
+        let first = match tokio::time::timeout(RECONNECT_FIRST_AUDIO_TIMEOUT, receiver.recv()).await {
+            Ok(Ok(samples)) => samples,
+            _ => {
+                let _ = stream.stop().await;
+                return Err(anyhow!("No audio from {} after reconnecting", device));
+            }
+        };
+
+        let from_rate = stream.device_config.sample_rate().0;
+        let to_rate = self.device_config.sample_rate().0;
+        let transmitter = self.transmitter.clone();
+        let forwarder = tokio::spawn(async move {
+            let _ = transmitter.send(resample_linear(&first, from_rate, to_rate));
+            loop {
+                match receiver.recv().await {
+                    Ok(samples) => {
+                        let _ = transmitter.send(resample_linear(&samples, from_rate, to_rate));
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!("Reconnected device fell behind, skipped {} buffers", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
+        let previous = self.replacement.lock().await.replace((stream, forwarder));
+        if let Some((previous, task)) = previous {
+            task.abort();
+            if let Err(e) = previous.stop().await {
+                warn!("Failed to stop previous replacement stream: {}", e);
+            }
+        }
+        info!("Audio stream for {} now captures from {} ({} Hz)", self.device, device, from_rate);
+        Ok(())
+    }
+
     pub async fn subscribe(&self) -> broadcast::Receiver<Vec<f32>> {
         self.transmitter.subscribe()
     }
 
     pub async fn stop(&self) -> Result<()> {
+        if let Some((replacement, task)) = self.replacement.lock().await.take() {
+            task.abort();
+            if let Err(e) = Box::pin(replacement.stop()).await {
+                warn!("Failed to stop replacement stream: {}", e);
+            }
+        }
+
         // Mark as disconnected first
         self.is_disconnected.store(true, Ordering::Release);
         
@@ -0,0 +1,186 @@
+// Speaker diarization with the pyannote/wespeaker embedding model: each transcribed
+// segment is embedded and matched against the speakers seen so far in the session.
+use anyhow::{anyhow, Context, Result};
+use log::{debug, info};
+use ndarray::{Array2, Axis};
+use ort::session::builder::GraphOptimizationLevel;
+use ort::session::Session;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const EMBEDDING_MODEL_FILE: &str = "wespeaker_en_voxceleb_CAM++.onnx";
+pub const EMBEDDING_MODEL_URL: &str =
+    "https://github.com/thewh1teagle/pyannote-rs/releases/download/v0.1.0/wespeaker_en_voxceleb_CAM++.onnx";
+pub const DEFAULT_SIMILARITY_THRESHOLD: f32 = 0.5;
+// Shorter segments give unreliable embeddings
+const MIN_SEGMENT_SAMPLES: usize = 16000;
+
+/// Computes speaker embeddings from 16kHz mono audio.
+pub struct EmbeddingExtractor {
+    session: Mutex<Session>,
+}
+
+impl EmbeddingExtractor {
+    pub fn new(model_path: &Path) -> Result<Self> {
+        info!("Loading speaker embedding model from {:?}", model_path);
+        let session = Session::builder()?
+            .with_optimization_level(GraphOptimizationLevel::Level3)?
+            .with_intra_threads(1)?
+            .commit_from_file(model_path)
+            .map_err(|e| anyhow!("Failed to load embedding model: {}", e))?;
+        Ok(Self { session: Mutex::new(session) })
+    }
+
+    pub fn compute(&self, samples: &[f32]) -> Result<Vec<f32>> {
+        // Kaldi fbank features expect 16-bit sample magnitudes
+        let scaled: Vec<f32> = samples.iter().map(|s| s * 32768.0).collect();
+        let features: Array2<f32> = knf_rs::compute_fbank(&scaled).map_err(|e| anyhow!("Failed to compute fbank: {}", e))?;
+        let features = features.insert_axis(Axis(0));
+
+        let session = self.session.lock().map_err(|_| anyhow!("Embedding session lock poisoned"))?;
+        let outputs = session.run(ort::inputs!["feats" => features.view()]?)?;
+        let embedding = outputs
+            .get("embs")
+            .context("Embedding output tensor not found")?
+            .try_extract_tensor::<f32>()?
+            .iter()
+            .copied()
+            .collect();
+        Ok(embedding)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Speaker {
+    pub id: usize,
+    pub name: String,
+    #[serde(skip)]
+    centroid: Vec<f32>,
+    pub segments: usize,
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b: f32 = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+/// Keeps the speakers of a session and assigns embeddings to them.
+pub struct EmbeddingManager {
+    speakers: Vec<Speaker>,
+    max_speakers: usize,
+}
+
+impl EmbeddingManager {
+    pub fn new(max_speakers: usize) -> Self {
+        Self { speakers: Vec::new(), max_speakers }
+    }
+
+    /// Returns the index of the best matching speaker, registering a new speaker when
+    /// nothing is similar enough and the speaker limit has not been reached.
+    pub fn search_speaker(&mut self, embedding: Vec<f32>, threshold: f32) -> Option<usize> {
+        let best = self
+            .speakers
+            .iter()
+            .enumerate()
+            .map(|(index, speaker)| (index, cosine_similarity(&speaker.centroid, &embedding)))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        match best {
+            Some((index, score)) if score >= threshold || self.speakers.len() >= self.max_speakers => {
+                debug!("Matched speaker {} with similarity {:.3}", index + 1, score);
+                let speaker = &mut self.speakers[index];
+                // Running mean keeps the centroid stable as more speech comes in
+                let weight = speaker.segments as f32;
+                for (c, e) in speaker.centroid.iter_mut().zip(&embedding) {
+                    *c = (*c * weight + e) / (weight + 1.0);
+                }
+                speaker.segments += 1;
+                Some(index)
+            }
+            _ if self.speakers.len() < self.max_speakers => {
+                let id = self.speakers.len() + 1;
+                info!("New speaker detected: Speaker {}", id);
+                self.speakers.push(Speaker {
+                    id,
+                    name: format!("Speaker {}", id),
+                    centroid: embedding,
+                    segments: 1,
+                });
+                Some(id - 1)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn speakers(&self) -> &[Speaker] {
+        &self.speakers
+    }
+
+    pub fn name_of(&self, index: usize) -> Option<&str> {
+        self.speakers.get(index).map(|s| s.name.as_str())
+    }
+
+    /// Renames a speaker by current name, returning false when no speaker has that name.
+    pub fn rename(&mut self, current: &str, name: &str) -> bool {
+        match self.speakers.iter_mut().find(|s| s.name == current) {
+            Some(speaker) => {
+                speaker.name = name.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Session-level diarization state shared by the transcription workers.
+pub struct Diarizer {
+    extractor: EmbeddingExtractor,
+    manager: EmbeddingManager,
+    threshold: f32,
+}
+
+impl Diarizer {
+    pub fn new(model_path: &Path, threshold: f32) -> Result<Self> {
+        Ok(Self {
+            extractor: EmbeddingExtractor::new(model_path)?,
+            manager: EmbeddingManager::new(usize::MAX),
+            threshold,
+        })
+    }
+
+    pub fn set_threshold(&mut self, threshold: f32) {
+        self.threshold = threshold;
+    }
+
+    /// Speaker name for a stretch of 16kHz audio, or None if it is too short to identify.
+    pub fn identify(&mut self, samples: &[f32]) -> Result<Option<String>> {
+        if samples.len() < MIN_SEGMENT_SAMPLES {
+            return Ok(None);
+        }
+        let embedding = self.extractor.compute(samples)?;
+        Ok(self
+            .manager
+            .search_speaker(embedding, self.threshold)
+            .and_then(|index| self.manager.name_of(index).map(|name| name.to_string())))
+    }
+
+    pub fn speakers(&self) -> Vec<Speaker> {
+        self.manager.speakers().to_vec()
+    }
+
+    pub fn rename(&mut self, current: &str, name: &str) -> bool {
+        self.manager.rename(current, name)
+    }
+}
+
+/// Location of the embedding model inside the app's models folder, if it has been downloaded.
+pub fn find_embedding_model(models_dir: &Path) -> Option<PathBuf> {
+    let path = models_dir.join(EMBEDDING_MODEL_FILE);
+    path.is_file().then_some(path)
+}
@@ -0,0 +1,77 @@
+// Acoustic echo cancellation. When the meeting plays through speakers, the mic picks the
+// other participants up a second time and both streams get transcribed. The WebRTC echo
+// canceller removes what the system stream played from the mic signal, using the system
+// stream as its reference.
+//
+// The processor works on 10 ms frames at 48 kHz, so chunks are resampled up for it and
+// back down afterwards. Its adaptive filter carries over from chunk to chunk.
+use webrtc_audio_processing::{
+    Config, EchoCancellation, EchoCancellationSuppressionLevel, InitializationConfig, Processor,
+    NUM_SAMPLES_PER_FRAME,
+};
+
+use super::audio_processing::resample_sinc;
+
+const PROCESSOR_SAMPLE_RATE: u32 = 48_000;
+
+pub struct EchoCanceller {
+    processor: Processor,
+    sample_rate: u32,
+}
+
+impl EchoCanceller {
+    /// A canceller for mono mic and system audio at `sample_rate`.
+    pub fn new(sample_rate: u32) -> Result<Self, String> {
+        let mut processor = Processor::new(&InitializationConfig {
+            num_capture_channels: 1,
+            num_render_channels: 1,
+            ..InitializationConfig::default()
+        })
+        .map_err(|e| format!("Failed to create echo canceller: {}", e))?;
+        processor.set_config(Config {
+            echo_cancellation: Some(EchoCancellation {
+                suppression_level: EchoCancellationSuppressionLevel::High,
+                stream_delay_ms: None,
+                // Speaker-to-mic delay is unknown and differs per setup; let the filter find it
+                enable_delay_agnostic: true,
+                enable_extended_filter: true,
+            }),
+            ..Config::default()
+        });
+        Ok(Self { processor, sample_rate })
+    }
+
+    /// Remove the echo of `system` from `mic`. Both cover the same stretch of time; a
+    /// shorter system chunk is padded with silence. A trailing partial frame is left as is.
+    pub fn process(&mut self, mic: &mut [f32], system: &[f32]) -> Result<(), String> {
+        if mic.is_empty() {
+            return Ok(());
+        }
+        let upsample = |samples: &[f32]| {
+            resample_sinc(samples, self.sample_rate, PROCESSOR_SAMPLE_RATE)
+                .map_err(|e| format!("Failed to resample for echo cancellation: {}", e))
+        };
+        let mut capture = upsample(mic)?;
+        let mut render = upsample(system)?;
+        render.resize(capture.len(), 0.0);
+
+        for (capture_frame, render_frame) in capture
+            .chunks_exact_mut(NUM_SAMPLES_PER_FRAME as usize)
+            .zip(render.chunks_exact_mut(NUM_SAMPLES_PER_FRAME as usize))
+        {
+            // The reference goes in first so the canceller knows what was played
+            self.processor
+                .process_render_frame(render_frame)
+                .map_err(|e| format!("Echo cancellation failed: {}", e))?;
+            self.processor
+                .process_capture_frame(capture_frame)
+                .map_err(|e| format!("Echo cancellation failed: {}", e))?;
+        }
+
+        let cleaned = resample_sinc(&capture, PROCESSOR_SAMPLE_RATE, self.sample_rate)
+            .map_err(|e| format!("Failed to resample after echo cancellation: {}", e))?;
+        let len = cleaned.len().min(mic.len());
+        mic[..len].copy_from_slice(&cleaned[..len]);
+        Ok(())
+    }
+}
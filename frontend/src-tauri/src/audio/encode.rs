@@ -1,9 +1,11 @@
 use super::ffmpeg::find_ffmpeg_path; // Correct path to encode module
 use super::AudioDevice;
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
 use std::io::Write;
 use std::sync::Arc;
 use std::{
-    path::PathBuf,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
 use tracing::{debug, error};
@@ -15,25 +17,64 @@ pub struct AudioInput {
     pub device: Arc<AudioDevice>,
 }
 
+/// Container/codec for saved meeting recordings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioFormat {
+    #[default]
+    Wav,
+    Flac,
+    Ogg,
+}
+
+impl AudioFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            AudioFormat::Wav => "wav",
+            AudioFormat::Flac => "flac",
+            AudioFormat::Ogg => "ogg",
+        }
+    }
+
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension.to_lowercase().as_str() {
+            "wav" => Some(AudioFormat::Wav),
+            "flac" => Some(AudioFormat::Flac),
+            "ogg" | "opus" => Some(AudioFormat::Ogg),
+            _ => None,
+        }
+    }
+
+    fn codec_args(&self) -> &'static [&'static str] {
+        match self {
+            AudioFormat::Wav => &["-c:a", "pcm_s16le", "-f", "wav"],
+            AudioFormat::Flac => &["-c:a", "flac", "-f", "flac"],
+            // Opus is tuned for speech and handles 16kHz input natively
+            AudioFormat::Ogg => &["-c:a", "libopus", "-b:a", "32k", "-f", "ogg"],
+        }
+    }
+}
+
 pub fn encode_single_audio(
     data: &[u8],
     sample_rate: u32,
     channels: u16,
     output_path: &PathBuf,
 ) -> anyhow::Result<()> {
-    debug!("Starting FFmpeg process");
-
-    let mut command = Command::new(find_ffmpeg_path().unwrap());
-    command
-        .args([
-            "-f",
-            "f32le",
-            "-ar",
-            &sample_rate.to_string(),
-            "-ac",
-            &channels.to_string(),
-            "-i",
-            "pipe:0",
+    let input_args = [
+        "-f".to_string(),
+        "f32le".to_string(),
+        "-ar".to_string(),
+        sample_rate.to_string(),
+        "-ac".to_string(),
+        channels.to_string(),
+        "-i".to_string(),
+        "pipe:0".to_string(),
+    ];
+    run_ffmpeg(
+        &input_args,
+        Some(data),
+        &[
             "-c:a",
             "aac",
             "-b:a",
@@ -44,25 +85,66 @@ pub fn encode_single_audio(
             "+faststart", // Optimize for web streaming
             "-f",
             "mp4",
-            output_path.to_str().unwrap(),
-        ])
-        .stdin(Stdio::piped())
+        ],
+        output_path,
+    )
+}
+
+/// Convert a recorded WAV file to `output_path` in the given format.
+pub fn transcode_recording(
+    input_path: &Path,
+    format: AudioFormat,
+    output_path: &PathBuf,
+) -> anyhow::Result<()> {
+    let input_args = ["-i".to_string(), input_path.to_string_lossy().to_string()];
+    run_ffmpeg(&input_args, None, format.codec_args(), output_path)
+}
+
+fn run_ffmpeg(
+    input_args: &[String],
+    stdin_data: Option<&[u8]>,
+    codec_args: &[&str],
+    output_path: &PathBuf,
+) -> anyhow::Result<()> {
+    debug!("Starting FFmpeg process");
+
+    let ffmpeg_path = find_ffmpeg_path().ok_or_else(|| anyhow::anyhow!("FFmpeg not found"))?;
+    let output = output_path
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Output path is not valid UTF-8: {:?}", output_path))?;
+    let mut command = Command::new(ffmpeg_path);
+    command
+        .arg("-y")
+        .args(input_args)
+        .args(codec_args)
+        .arg(output)
+        .stdin(if stdin_data.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
     debug!("FFmpeg command: {:?}", command);
 
     #[allow(clippy::zombie_processes)]
-    let mut ffmpeg = command.spawn().expect("Failed to spawn FFmpeg process");
+    let mut ffmpeg = command.spawn().context("Failed to spawn FFmpeg process")?;
     debug!("FFmpeg process spawned");
-    let mut stdin = ffmpeg.stdin.take().expect("Failed to open stdin");
-
-    stdin.write_all(data)?;
-
-    debug!("Dropping stdin");
-    drop(stdin);
+    // Stdin is dropped at the end of its arm, which tells FFmpeg the input is complete
+    let written = match (stdin_data, ffmpeg.stdin.take()) {
+        (Some(data), Some(mut stdin)) => stdin.write_all(data).context("Failed to write audio to FFmpeg"),
+        (Some(_), None) => Err(anyhow::anyhow!("Failed to open FFmpeg stdin")),
+        (None, _) => Ok(()),
+    };
+    if let Err(e) = written {
+        // Don't leave the process running or unreaped
+        let _ = ffmpeg.kill();
+        let _ = ffmpeg.wait();
+        return Err(e);
+    }
     debug!("Waiting for FFmpeg process to exit");
-    let output = ffmpeg.wait_with_output().unwrap();
+    let output = ffmpeg.wait_with_output().context("Failed to wait for FFmpeg process")?;
     let status = output.status;
     let stdout = String::from_utf8_lossy(&output.stdout);
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -0,0 +1,179 @@
+use realfft::{RealFftPlanner, RealToComplex};
+use std::sync::Arc;
+
+// Lightweight acoustic fingerprint in the spirit of chromaprint / Haitsma-Kalker:
+// every frame produces a 32-bit sub-fingerprint from the sign of energy differences
+// between adjacent frequency bands, compared with the previous frame.
+pub const FINGERPRINT_SAMPLE_RATE: u32 = 16000;
+const FRAME_SIZE: usize = 4096; // 256ms analysis window
+const HOP_SIZE: usize = 2048; // 128ms between sub-fingerprints
+const NUM_BANDS: usize = 33; // 33 bands -> 32 bits per frame
+const MIN_FREQ: f32 = 300.0;
+const MAX_FREQ: f32 = 2000.0;
+
+/// Bit error rate below which two fingerprints are considered the same audio.
+pub const MATCH_BIT_ERROR_RATE: f32 = 0.35;
+
+pub struct Fingerprinter {
+    fft: Arc<dyn RealToComplex<f32>>,
+    window: Vec<f32>,
+    band_edges: Vec<usize>,
+    pending: Vec<f32>,
+    previous_energies: Option<Vec<f32>>,
+    hashes: Vec<u32>,
+}
+
+impl Fingerprinter {
+    pub fn new() -> Self {
+        let mut planner = RealFftPlanner::<f32>::new();
+        let fft = planner.plan_fft_forward(FRAME_SIZE);
+
+        // Hann window
+        let window = (0..FRAME_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME_SIZE as f32).cos())
+            .collect();
+
+        // Logarithmically spaced band edges expressed as FFT bin indices
+        let band_edges = (0..=NUM_BANDS)
+            .map(|k| {
+                let freq = MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(k as f32 / NUM_BANDS as f32);
+                (freq * FRAME_SIZE as f32 / FINGERPRINT_SAMPLE_RATE as f32).round() as usize
+            })
+            .collect();
+
+        Self {
+            fft,
+            window,
+            band_edges,
+            pending: Vec::with_capacity(FRAME_SIZE * 2),
+            previous_energies: None,
+            hashes: Vec::new(),
+        }
+    }
+
+    /// Feed mono samples at `FINGERPRINT_SAMPLE_RATE`. Can be called repeatedly as audio arrives.
+    pub fn push_samples(&mut self, samples: &[f32]) {
+        self.pending.extend_from_slice(samples);
+        while self.pending.len() >= FRAME_SIZE {
+            self.process_frame();
+            self.pending.drain(..HOP_SIZE);
+        }
+    }
+
+    fn process_frame(&mut self) {
+        let mut input: Vec<f32> = self.pending[..FRAME_SIZE]
+            .iter()
+            .zip(self.window.iter())
+            .map(|(s, w)| s * w)
+            .collect();
+        let mut spectrum = self.fft.make_output_vec();
+        if self.fft.process(&mut input, &mut spectrum).is_err() {
+            return;
+        }
+
+        let energies: Vec<f32> = self
+            .band_edges
+            .windows(2)
+            .map(|edge| {
+                let end = edge[1].max(edge[0] + 1).min(spectrum.len());
+                spectrum[edge[0].min(end)..end].iter().map(|c| c.norm_sqr()).sum()
+            })
+            .collect();
+
+        if let Some(previous) = &self.previous_energies {
+            let mut hash = 0u32;
+            for m in 0..NUM_BANDS - 1 {
+                let diff = (energies[m] - energies[m + 1]) - (previous[m] - previous[m + 1]);
+                if diff > 0.0 {
+                    hash |= 1 << m;
+                }
+            }
+            self.hashes.push(hash);
+        }
+        self.previous_energies = Some(energies);
+    }
+
+    pub fn finish(self) -> Vec<u32> {
+        self.hashes
+    }
+}
+
+impl Default for Fingerprinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fingerprint a complete buffer of mono samples at `FINGERPRINT_SAMPLE_RATE`.
+pub fn fingerprint(samples: &[f32]) -> Vec<u32> {
+    let mut fingerprinter = Fingerprinter::new();
+    fingerprinter.push_samples(samples);
+    fingerprinter.finish()
+}
+
+/// Duration covered by a number of sub-fingerprints.
+pub fn frames_to_seconds(frames: usize) -> f64 {
+    frames as f64 * HOP_SIZE as f64 / FINGERPRINT_SAMPLE_RATE as f64
+}
+
+pub fn seconds_to_frames(seconds: f64) -> usize {
+    (seconds * FINGERPRINT_SAMPLE_RATE as f64 / HOP_SIZE as f64).max(0.0) as usize
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FingerprintMatch {
+    pub offset_frames: usize,
+    pub bit_error_rate: f32,
+}
+
+/// Slide `needle` over `haystack` and return the alignment with the lowest bit error rate.
+pub fn find_best_match(haystack: &[u32], needle: &[u32]) -> Option<FingerprintMatch> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+
+    let total_bits = (needle.len() * 32) as u32;
+    let mut best: Option<(usize, u32)> = None;
+
+    for offset in 0..=(haystack.len() - needle.len()) {
+        let limit = best.map(|(_, errors)| errors).unwrap_or(u32::MAX);
+        let mut errors = 0u32;
+        for (a, b) in needle.iter().zip(&haystack[offset..]) {
+            errors += (a ^ b).count_ones();
+            if errors >= limit {
+                break;
+            }
+        }
+        if errors < limit {
+            best = Some((offset, errors));
+        }
+    }
+
+    best.map(|(offset_frames, errors)| FingerprintMatch {
+        offset_frames,
+        bit_error_rate: errors as f32 / total_bits as f32,
+    })
+}
+
+/// Compare two whole-recording fingerprints using an excerpt of the shorter one,
+/// so recordings of different length (or started at different times) still line up.
+pub fn compare_recordings(a: &[u32], b: &[u32], excerpt_frames: usize) -> Option<FingerprintMatch> {
+    let (shorter, longer) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    if shorter.is_empty() {
+        return None;
+    }
+    let len = excerpt_frames.min(shorter.len());
+    let start = (shorter.len() - len) / 2;
+    find_best_match(longer, &shorter[start..start + len])
+}
+
+pub fn to_bytes(fingerprint: &[u32]) -> Vec<u8> {
+    fingerprint.iter().flat_map(|h| h.to_le_bytes()).collect()
+}
+
+pub fn from_bytes(bytes: &[u8]) -> Vec<u32> {
+    bytes
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
@@ -0,0 +1,79 @@
+// Loudness matching between the microphone and system audio streams. Each stream's
+// speech level is tracked separately and gains are chosen so that both land on the
+// same level before they are mixed or transcribed.
+
+// Blocks quieter than this are treated as silence and do not move the level estimate
+const ACTIVITY_RMS: f32 = 0.003;
+// Smoothing of the level estimate per block of audio
+const LEVEL_SMOOTHING: f32 = 0.05;
+const MIN_GAIN: f32 = 0.25;
+const MAX_GAIN: f32 = 8.0;
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct LevelTracker {
+    level: Option<f32>,
+}
+
+impl LevelTracker {
+    fn observe(&mut self, samples: &[f32]) {
+        let block = rms(samples);
+        if block < ACTIVITY_RMS {
+            return;
+        }
+        self.level = Some(match self.level {
+            Some(level) => level + (block - level) * LEVEL_SMOOTHING,
+            None => block,
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GainMatcher {
+    mic: LevelTracker,
+    system: LevelTracker,
+}
+
+impl GainMatcher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the latest captured blocks of both streams.
+    pub fn observe(&mut self, mic_samples: &[f32], system_samples: &[f32]) {
+        self.mic.observe(mic_samples);
+        self.system.observe(system_samples);
+    }
+
+    /// Gains for (mic, system). Both streams are brought to the geometric mean of their
+    /// levels so the overall loudness stays roughly where it was. Until both sides have
+    /// been heard the gains stay at unity.
+    pub fn gains(&self) -> (f32, f32) {
+        match (self.mic.level, self.system.level) {
+            (Some(mic), Some(system)) => {
+                let target = (mic * system).sqrt();
+                (
+                    (target / mic).clamp(MIN_GAIN, MAX_GAIN),
+                    (target / system).clamp(MIN_GAIN, MAX_GAIN),
+                )
+            }
+            _ => (1.0, 1.0),
+        }
+    }
+}
+
+/// Scale samples in place, soft-limiting so boosted peaks do not clip.
+pub fn apply_gain(samples: &mut [f32], gain: f32) {
+    if (gain - 1.0).abs() < f32::EPSILON {
+        return;
+    }
+    for sample in samples.iter_mut() {
+        *sample = (*sample * gain).tanh();
+    }
+}
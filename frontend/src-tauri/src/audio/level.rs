@@ -0,0 +1,103 @@
+// Live level metering for the mic and system streams, reported at a fixed rate so the
+// UI can drive VU meters.
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+pub const LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+// Floor reported for silence, in dBFS
+const MIN_DB: f32 = -100.0;
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct StreamLevel {
+    pub rms: f32,
+    pub peak: f32,
+    pub rms_db: f32,
+    pub peak_db: f32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct AudioLevels {
+    pub mic: StreamLevel,
+    pub system: StreamLevel,
+}
+
+fn to_db(value: f32) -> f32 {
+    if value <= 0.0 {
+        return MIN_DB;
+    }
+    (20.0 * value.log10()).max(MIN_DB)
+}
+
+#[derive(Debug, Default)]
+struct Accumulator {
+    sum_squares: f64,
+    count: usize,
+    peak: f32,
+}
+
+impl Accumulator {
+    fn observe(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            self.sum_squares += (sample as f64) * (sample as f64);
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.count += samples.len();
+    }
+
+    fn take(&mut self) -> StreamLevel {
+        let rms = if self.count == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.count as f64).sqrt() as f32
+        };
+        let level = StreamLevel {
+            rms,
+            peak: self.peak,
+            rms_db: to_db(rms),
+            peak_db: to_db(self.peak),
+        };
+        *self = Self::default();
+        level
+    }
+}
+
+/// Accumulates both streams and hands out one reading per `LEVEL_INTERVAL`.
+#[derive(Debug)]
+pub struct LevelMeter {
+    mic: Accumulator,
+    system: Accumulator,
+    last_report: Instant,
+}
+
+impl Default for LevelMeter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LevelMeter {
+    pub fn new() -> Self {
+        Self {
+            mic: Accumulator::default(),
+            system: Accumulator::default(),
+            last_report: Instant::now(),
+        }
+    }
+
+    pub fn observe(&mut self, mic_samples: &[f32], system_samples: &[f32]) {
+        self.mic.observe(mic_samples);
+        self.system.observe(system_samples);
+    }
+
+    /// Levels since the previous reading, once the interval has passed.
+    pub fn take_if_due(&mut self) -> Option<AudioLevels> {
+        if self.last_report.elapsed() < LEVEL_INTERVAL {
+            return None;
+        }
+        self.last_report = Instant::now();
+        Some(AudioLevels {
+            mic: self.mic.take(),
+            system: self.system.take(),
+        })
+    }
+}
@@ -1,8 +1,21 @@
 // src/audio/mod.rs
 pub mod core;
+pub mod aggregate;
 pub mod audio_processing;
 pub mod encode;
 pub mod ffmpeg;
+pub mod decode;
+pub mod diarization;
+pub mod echo;
+pub mod fingerprint;
+pub mod gain;
+pub mod level;
+pub mod vad;
+pub mod wav_writer;
+pub mod whisper_local;
+pub mod openai;
+#[cfg(target_os = "windows")]
+pub mod process_loopback;
 
 pub use core::{
     default_input_device, default_output_device, get_device_and_config, list_audio_devices,
@@ -11,5 +24,7 @@ pub use core::{
     LAST_AUDIO_CAPTURE,
 };
 pub use encode::{
-    encode_single_audio, AudioInput
-};
\ No newline at end of file
+    encode_single_audio, transcode_recording, AudioFormat, AudioInput
+};
+pub use decode::decode_audio_file;
+pub use aggregate::{list_aggregate_devices, AggregateDevice};
\ No newline at end of file
@@ -0,0 +1,70 @@
+use anyhow::{anyhow, Result};
+use log::debug;
+use reqwest::multipart::{Form, Part};
+use serde::Deserialize;
+use std::io::Cursor;
+
+pub const TRANSCRIPTIONS_URL: &str = "https://api.openai.com/v1/audio/transcriptions";
+pub const DEFAULT_MODEL: &str = "whisper-1";
+
+/// A segment returned by OpenAI's transcription API. Times are in seconds.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenAiSegment {
+    pub text: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerboseTranscription {
+    #[serde(default)]
+    segments: Vec<OpenAiSegment>,
+}
+
+// The API takes audio files, not raw samples; 16-bit mono WAV is the smallest lossless one
+fn wav_bytes(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::with_capacity(samples.len() * 2 + 44));
+    let mut writer = hound::WavWriter::new(&mut cursor, spec)?;
+    for sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(cursor.into_inner())
+}
+
+/// Transcribe mono samples with OpenAI's audio API. `language` is an ISO-639-1 code;
+/// None lets the API detect it.
+pub async fn transcribe(
+    client: &reqwest::Client,
+    api_key: &str,
+    model: &str,
+    samples: &[f32],
+    sample_rate: u32,
+    language: Option<&str>,
+) -> Result<Vec<OpenAiSegment>> {
+    let part = Part::bytes(wav_bytes(samples, sample_rate)?)
+        .file_name("chunk.wav")
+        .mime_str("audio/wav")?;
+    let mut form = Form::new()
+        .part("file", part)
+        .text("model", model.to_string())
+        .text("response_format", "verbose_json");
+    if let Some(language) = language {
+        form = form.text("language", language.to_string());
+    }
+
+    debug!("Sending {} samples to OpenAI ({})", samples.len(), model);
+    let response = client.post(TRANSCRIPTIONS_URL).bearer_auth(api_key).multipart(form).send().await?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("OpenAI returned {}: {}", status, body));
+    }
+    Ok(response.json::<VerboseTranscription>().await?.segments)
+}
@@ -0,0 +1,90 @@
+// System audio from a single application on Windows, through WASAPI application loopback
+// (Windows 10 2004 and later). Only what the chosen process and its child processes play
+// is captured, so music players and notification sounds stay out of the recording.
+//
+// WASAPI delivers no packets while the application is silent, so the gaps are filled with
+// silence to keep the stream in step with the microphone.
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Weak};
+use std::time::Instant;
+use anyhow::{anyhow, Result};
+use log::{info, warn};
+use tokio::sync::broadcast;
+use wasapi::{initialize_mta, AudioClient, Direction, SampleType, ShareMode, WaveFormat};
+
+use super::audio_processing::audio_to_mono;
+use super::core::StreamControl;
+
+pub const SAMPLE_RATE: u32 = 48_000;
+pub const CHANNELS: u16 = 2;
+const BYTES_PER_SAMPLE: usize = 4;
+const EVENT_TIMEOUT_MS: u32 = 100;
+// Silence is only filled in once audio has been missing for longer than this
+const MAX_GAP_SAMPLES: u64 = SAMPLE_RATE as u64 / 10;
+
+/// Capture `pid` until a stop request arrives, sending mono buffers to `tx`.
+pub(super) fn capture(
+    pid: u32,
+    tx: &broadcast::Sender<Vec<f32>>,
+    control: &mpsc::Receiver<StreamControl>,
+    is_running: Weak<AtomicBool>,
+) -> Result<()> {
+    initialize_mta().ok().map_err(|e| anyhow!("Failed to initialize COM: {}", e))?;
+    let format = WaveFormat::new(32, 32, &SampleType::Float, SAMPLE_RATE as usize, CHANNELS as usize, None);
+    let mut client = AudioClient::new_application_loopback_client(pid, true)
+        .map_err(|e| anyhow!("Failed to open loopback capture for process {}: {}", pid, e))?;
+    client
+        .initialize_client(&format, 0, &Direction::Capture, &ShareMode::Shared, true)
+        .map_err(|e| anyhow!("Failed to initialize loopback capture: {}", e))?;
+    let event = client.set_get_eventhandle().map_err(|e| anyhow!("Failed to get capture event: {}", e))?;
+    let capture_client = client
+        .get_audiocaptureclient()
+        .map_err(|e| anyhow!("Failed to get capture client: {}", e))?;
+    client.start_stream().map_err(|e| anyhow!("Failed to start loopback capture: {}", e))?;
+    info!("Capturing audio of process {}", pid);
+
+    let started = Instant::now();
+    let mut delivered: u64 = 0;
+    let mut queue = VecDeque::new();
+    loop {
+        match control.try_recv() {
+            Ok(StreamControl::Stop(response)) => {
+                client.stop_stream().map_err(|e| anyhow!("Failed to stop loopback capture: {}", e))?;
+                response.send(()).ok();
+                info!("Stopped capturing audio of process {}", pid);
+                return Ok(());
+            }
+            Err(mpsc::TryRecvError::Disconnected) => return Ok(()),
+            Err(mpsc::TryRecvError::Empty) => {}
+        }
+        let running = match is_running.upgrade() {
+            Some(flag) => flag.load(Ordering::Relaxed),
+            None => return Ok(()),
+        };
+
+        capture_client
+            .read_from_device_to_deque(&mut queue)
+            .map_err(|e| anyhow!("Failed to read loopback audio: {}", e))?;
+        let bytes: Vec<u8> = queue.drain(..).collect();
+        let mut samples: Vec<f32> = bytes
+            .chunks_exact(BYTES_PER_SAMPLE)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let frames = (samples.len() / CHANNELS as usize) as u64;
+        let expected = (started.elapsed().as_secs_f64() * SAMPLE_RATE as f64) as u64;
+        let missing = expected.saturating_sub(delivered + frames);
+        if missing > MAX_GAP_SAMPLES {
+            samples.splice(0..0, std::iter::repeat(0.0).take(missing as usize * CHANNELS as usize));
+        }
+        if !samples.is_empty() {
+            delivered += (samples.len() / CHANNELS as usize) as u64;
+            if running && tx.send(audio_to_mono(&samples, CHANNELS)).is_err() {
+                warn!("No receivers for process {} audio", pid);
+            }
+        }
+
+        // Times out while the application is silent; the gap is filled on the next round
+        let _ = event.wait_for_event(EVENT_TIMEOUT_MS);
+    }
+}
@@ -0,0 +1,52 @@
+// Voice activity detection with the WebRTC VAD. Chunks without speech are not worth
+// sending to whisper, which tends to answer them with [BLANK_AUDIO] or hallucinations.
+use anyhow::{anyhow, Result};
+use log::debug;
+use webrtc_vad::{SampleRate, Vad, VadMode};
+
+pub const VAD_SAMPLE_RATE: u32 = 16000;
+// 30ms frames, the longest the WebRTC VAD accepts
+const FRAME_SAMPLES: usize = 480;
+// Voiced time a chunk needs before it counts as containing speech
+const MIN_SPEECH_MS: usize = 300;
+
+// Higher sensitivity lets more audio through, so it maps to a less aggressive mode
+fn mode_for(sensitivity: f32) -> VadMode {
+    match sensitivity {
+        s if s >= 0.75 => VadMode::Quality,
+        s if s >= 0.5 => VadMode::LowBitrate,
+        s if s >= 0.25 => VadMode::Aggressive,
+        _ => VadMode::VeryAggressive,
+    }
+}
+
+/// Number of 30ms frames classified as speech in 16kHz mono audio.
+pub fn speech_frames(samples: &[f32], sensitivity: f32) -> Result<usize> {
+    // The detector is cheap to create and not Send, so one is made per call
+    let mut vad = Vad::new_with_rate_and_mode(SampleRate::Rate16kHz, mode_for(sensitivity));
+    let mut frame = Vec::with_capacity(FRAME_SAMPLES);
+    let mut voiced = 0;
+    for chunk in samples.chunks_exact(FRAME_SAMPLES) {
+        frame.clear();
+        frame.extend(chunk.iter().map(|s| (s.clamp(-1.0, 1.0) * 32767.0) as i16));
+        if vad
+            .is_voice_segment(&frame)
+            .map_err(|_| anyhow!("Invalid VAD frame length"))?
+        {
+            voiced += 1;
+        }
+    }
+    Ok(voiced)
+}
+
+/// Whether 16kHz mono audio contains enough speech to be worth transcribing.
+pub fn contains_speech(samples: &[f32], sensitivity: f32) -> Result<bool> {
+    let voiced = speech_frames(samples, sensitivity)?;
+    let min_frames = MIN_SPEECH_MS * VAD_SAMPLE_RATE as usize / 1000 / FRAME_SAMPLES;
+    debug!(
+        "VAD: {} of {} frames voiced",
+        voiced,
+        samples.len() / FRAME_SAMPLES
+    );
+    Ok(voiced >= min_frames.max(1))
+}
@@ -0,0 +1,96 @@
+// Incremental 16-bit PCM WAV writer. The header is rewritten on every flush so the
+// file on disk is always a playable WAV, even if the app dies mid-recording.
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+const HEADER_LEN: u32 = 44;
+const BITS_PER_SAMPLE: u16 = 16;
+
+pub struct WavWriter {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    sample_rate: u32,
+    samples_written: u64,
+}
+
+impl WavWriter {
+    pub fn create(path: &Path, sample_rate: u32) -> Result<Self> {
+        let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+        let mut writer = Self {
+            writer: BufWriter::new(file),
+            path: path.to_path_buf(),
+            sample_rate,
+            samples_written: 0,
+        };
+        writer.write_header()?;
+        Ok(writer)
+    }
+
+    fn write_header(&mut self) -> Result<()> {
+        let data_size = (self.samples_written * 2).min((u32::MAX - HEADER_LEN) as u64) as u32;
+        let block_align = BITS_PER_SAMPLE / 8;
+        let byte_rate = self.sample_rate * block_align as u32;
+
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(b"RIFF");
+        header.extend_from_slice(&(data_size + HEADER_LEN - 8).to_le_bytes());
+        header.extend_from_slice(b"WAVE");
+        header.extend_from_slice(b"fmt ");
+        header.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+        header.extend_from_slice(&1u16.to_le_bytes()); // PCM
+        header.extend_from_slice(&1u16.to_le_bytes()); // mono
+        header.extend_from_slice(&self.sample_rate.to_le_bytes());
+        header.extend_from_slice(&byte_rate.to_le_bytes());
+        header.extend_from_slice(&block_align.to_le_bytes());
+        header.extend_from_slice(&BITS_PER_SAMPLE.to_le_bytes());
+        header.extend_from_slice(b"data");
+        header.extend_from_slice(&data_size.to_le_bytes());
+
+        let file = self.writer.get_mut();
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&header)?;
+        file.seek(SeekFrom::End(0))?;
+        Ok(())
+    }
+
+    pub fn write_samples(&mut self, samples: &[f32]) -> Result<()> {
+        for &sample in samples {
+            let value = (sample.clamp(-1.0, 1.0) * 32767.0) as i16;
+            self.writer.write_all(&value.to_le_bytes())?;
+        }
+        self.samples_written += samples.len() as u64;
+        Ok(())
+    }
+
+    /// Hand buffered samples to the OS and update the header to cover them, so they
+    /// survive the app dying.
+    pub fn flush(&mut self) -> Result<()> {
+        self.writer.flush()?;
+        self.write_header()?;
+        Ok(())
+    }
+
+    /// Flush and wait until the samples are on disk, so they survive the system going down.
+    pub fn sync(&mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.get_ref().sync_data()?;
+        debug!("Synced {} samples to {:?}", self.samples_written, self.path);
+        Ok(())
+    }
+
+    pub fn finalize(mut self) -> Result<(PathBuf, u64)> {
+        self.sync()?;
+        Ok((self.path, self.samples_written))
+    }
+
+    pub fn samples_written(&self) -> u64 {
+        self.samples_written
+    }
+
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+}
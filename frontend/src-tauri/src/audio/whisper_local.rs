@@ -0,0 +1,203 @@
+use anyhow::{anyhow, Result};
+use log::{debug, info};
+use once_cell::sync::Lazy;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters, WhisperState};
+
+/// A segment produced by the in-process engine. Times use whisper's native
+/// 10ms units, the same as the whisper server's `/stream` endpoint.
+#[derive(Debug, Clone)]
+pub struct LocalSegment {
+    pub text: String,
+    pub t0: i64,
+    pub t1: i64,
+    /// English translation of the segment, when requested
+    pub translation: Option<String>,
+    pub words: Vec<LocalWord>,
+}
+
+/// A word of a segment, timed like the segment, with the model's confidence in it.
+#[derive(Debug, Clone)]
+pub struct LocalWord {
+    pub word: String,
+    pub t0: i64,
+    pub t1: i64,
+    /// Lowest probability among the word's tokens
+    pub p: f32,
+}
+
+// Tokens starting with a space begin a new word; the rest continue the previous one
+fn push_token(words: &mut Vec<LocalWord>, text: &str, t0: i64, t1: i64, p: f32) {
+    match words.last_mut() {
+        Some(word) if !text.starts_with(' ') => {
+            word.word.push_str(text);
+            word.t1 = t1;
+            word.p = word.p.min(p);
+        }
+        _ => {
+            let text = text.trim_start();
+            if !text.is_empty() {
+                words.push(LocalWord { word: text.to_string(), t0, t1, p });
+            }
+        }
+    }
+}
+
+// Special tokens (timestamps, end of text) are rendered like "[_TT_150]" or "<|endoftext|>"
+fn is_special_token(text: &str) -> bool {
+    text.starts_with("[_") || text.starts_with("<|")
+}
+
+// The translation pass segments the audio differently; each translated segment is
+// attached to the original segment its midpoint falls in (or the closest one).
+fn attach_translation(segments: &mut [LocalSegment], translated: &[LocalSegment]) {
+    for piece in translated {
+        let midpoint = (piece.t0 + piece.t1) / 2;
+        let target = segments
+            .iter_mut()
+            .min_by_key(|s| if midpoint < s.t0 { s.t0 - midpoint } else { (midpoint - s.t1).max(0) });
+        if let Some(segment) = target {
+            let translation = segment.translation.get_or_insert_with(String::new);
+            if !translation.is_empty() {
+                translation.push(' ');
+            }
+            translation.push_str(piece.text.trim());
+        }
+    }
+}
+
+/// whisper.cpp model loaded into the app process.
+pub struct LocalWhisper {
+    model_path: PathBuf,
+    // whisper.cpp is not re-entrant per state, so inference is serialized like the server's model mutex
+    state: Mutex<WhisperState>,
+    _context: WhisperContext,
+}
+
+impl LocalWhisper {
+    pub fn load(model_path: &Path) -> Result<Self> {
+        info!("Loading local whisper model from {:?}", model_path);
+        let path = model_path
+            .to_str()
+            .ok_or_else(|| anyhow!("Model path is not valid UTF-8: {:?}", model_path))?;
+        let context = WhisperContext::new_with_params(path, WhisperContextParameters::default())
+            .map_err(|e| anyhow!("Failed to load whisper model: {}", e))?;
+        let state = context
+            .create_state()
+            .map_err(|e| anyhow!("Failed to create whisper state: {}", e))?;
+
+        Ok(Self {
+            model_path: model_path.to_path_buf(),
+            state: Mutex::new(state),
+            _context: context,
+        })
+    }
+
+    pub fn model_path(&self) -> &Path {
+        &self.model_path
+    }
+
+    /// Transcribe mono 16kHz samples with the model's default language.
+    pub fn transcribe(&self, samples: &[f32]) -> Result<Vec<LocalSegment>> {
+        self.transcribe_language(samples, None, false).map(|(segments, _)| segments)
+    }
+
+    /// Transcribe mono 16kHz samples in `language` ("auto" to detect it), returning the
+    /// segments and the language the model used. With `translate`, a second pass adds an
+    /// English translation to each segment.
+    pub fn transcribe_language(
+        &self,
+        samples: &[f32],
+        language: Option<&str>,
+        translate: bool,
+    ) -> Result<(Vec<LocalSegment>, Option<String>)> {
+        let mut state = self.state.lock().map_err(|_| anyhow!("Whisper state lock poisoned"))?;
+        let mut segments = Self::run(&mut state, samples, language, false)?;
+        let detected = state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .map(str::to_string);
+        if translate {
+            let translated = Self::run(&mut state, samples, language, true)?;
+            attach_translation(&mut segments, &translated);
+        }
+        debug!("Local whisper produced {} segments for {} samples ({:?})", segments.len(), samples.len(), detected);
+        Ok((segments, detected))
+    }
+
+    fn run(state: &mut WhisperState, samples: &[f32], language: Option<&str>, translate: bool) -> Result<Vec<LocalSegment>> {
+        let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+        params.set_translate(translate);
+        params.set_token_timestamps(!translate);
+        if let Some(language) = language {
+            params.set_language(Some(language));
+        }
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_special(false);
+        params.set_print_timestamps(false);
+        params.set_n_threads(std::thread::available_parallelism().map(|n| n.get() as i32).unwrap_or(4).min(8));
+
+        state
+            .full(params, samples)
+            .map_err(|e| anyhow!("Local transcription failed: {}", e))?;
+
+        let n_segments = state.full_n_segments().map_err(|e| anyhow!("{}", e))?;
+        let mut segments = Vec::with_capacity(n_segments as usize);
+        for i in 0..n_segments {
+            let mut words = Vec::new();
+            if !translate {
+                let n_tokens = state.full_n_tokens(i).map_err(|e| anyhow!("{}", e))?;
+                for j in 0..n_tokens {
+                    let text = state.full_get_token_text(i, j).map_err(|e| anyhow!("{}", e))?;
+                    if is_special_token(&text) {
+                        continue;
+                    }
+                    let token = state.full_get_token_data(i, j).map_err(|e| anyhow!("{}", e))?;
+                    push_token(&mut words, &text, token.t0, token.t1, token.p);
+                }
+            }
+            segments.push(LocalSegment {
+                text: state.full_get_segment_text(i).map_err(|e| anyhow!("{}", e))?,
+                t0: state.full_get_segment_t0(i).map_err(|e| anyhow!("{}", e))?,
+                t1: state.full_get_segment_t1(i).map_err(|e| anyhow!("{}", e))?,
+                translation: None,
+                words,
+            });
+        }
+        Ok(segments)
+    }
+}
+
+static LOADED_MODEL: Lazy<Mutex<Option<Arc<LocalWhisper>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Returns the loaded model for `model_path`, loading it (and unloading any other model) on first use.
+pub fn get_or_load(model_path: &Path) -> Result<Arc<LocalWhisper>> {
+    let mut loaded = LOADED_MODEL.lock().map_err(|_| anyhow!("Local model lock poisoned"))?;
+    if let Some(model) = loaded.as_ref() {
+        if model.model_path() == model_path {
+            return Ok(model.clone());
+        }
+    }
+    let model = Arc::new(LocalWhisper::load(model_path)?);
+    *loaded = Some(model.clone());
+    Ok(model)
+}
+
+/// First `ggml-*.bin` model found in `models_dir`, if any.
+pub fn find_model_in(models_dir: &Path) -> Option<PathBuf> {
+    let mut models: Vec<PathBuf> = std::fs::read_dir(models_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(|n| n.starts_with("ggml-") && n.ends_with(".bin"))
+                .unwrap_or(false)
+        })
+        .collect();
+    models.sort();
+    models.into_iter().next()
+}
@@ -0,0 +1,102 @@
+// Connection indicator for the backend: its `/health` endpoint is checked every few
+// seconds and the result sent to the frontend as a `backend-status` event, so the UI can
+// show when the backend goes away (and comes back) before a save fails.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api::{get_server_address, outbox};
+
+const PROBE_INTERVAL: Duration = Duration::from_secs(15);
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendStatus {
+    pub url: String,
+    pub reachable: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static LAST_STATUS: Lazy<Mutex<Option<BackendStatus>>> = Lazy::new(|| Mutex::new(None));
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+async fn check(url: &str) -> Result<(), String> {
+    let response = CLIENT
+        .get(format!("{}/health", url))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the backend: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backend health check returned {}", response.status()));
+    }
+    Ok(())
+}
+
+/// Check the backend now, emit the result as `backend-status` and return it.
+pub async fn probe<R: Runtime>(app: &AppHandle<R>) -> BackendStatus {
+    let url = get_server_address(app).await.unwrap_or_default();
+    let started = Instant::now();
+    let result = check(&url).await;
+    let status = BackendStatus {
+        url,
+        reachable: result.is_ok(),
+        latency_ms: started.elapsed().as_millis() as u64,
+        error: result.err(),
+        checked_at: Utc::now(),
+    };
+
+    let was_reachable = LAST_STATUS
+        .lock()
+        .ok()
+        .and_then(|mut last| last.replace(status.clone()))
+        .map(|last| last.reachable);
+    match (was_reachable, status.reachable) {
+        (Some(false), true) => {
+            log_info!("Backend at {} is reachable again", status.url);
+            outbox::wake();
+        }
+        (Some(true) | None, false) => log_warn!(
+            "Backend at {} is unreachable: {}",
+            status.url,
+            status.error.as_deref().unwrap_or_default()
+        ),
+        _ => {}
+    }
+    if let Err(e) = app.emit("backend-status", &status) {
+        log_error!("Failed to emit backend-status event: {}", e);
+    }
+    status
+}
+
+/// Start checking the backend periodically. Called once at startup.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            probe(&app).await;
+            tokio::time::sleep(PROBE_INTERVAL).await;
+        }
+    });
+}
+
+/// The backend's current status, checked on the spot.
+#[tauri::command]
+pub async fn get_backend_status<R: Runtime>(app: AppHandle<R>) -> BackendStatus {
+    probe(&app).await
+}
@@ -0,0 +1,297 @@
+// Backup of all local data in one zip file, for moving to another machine or recovering
+// from a lost disk. A backup holds:
+//
+//   manifest.json    format and schema versions, when and by which app version it was made
+//   meetings.sqlite  a consistent copy of the local meeting store
+//   settings.json    the settings store, prompt templates included
+//   audio/...        the recordings the app saved and, optionally, the audio in the
+//                    folder the user saves recordings to
+//
+// Settings that only make sense on the machine they came from (the device id, a signed-in
+// session, the path of a downloaded model) are left out and kept as they are on restore.
+// Secrets live in the OS keychain and are never part of a backup.
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::atomic_file::AtomicFile;
+use crate::recordings;
+use crate::storage;
+
+const FORMAT_VERSION: u32 = 1;
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DATABASE_ENTRY: &str = "meetings.sqlite";
+const SETTINGS_ENTRY: &str = "settings.json";
+const AUDIO_PREFIX: &str = "audio/";
+const MACHINE_SETTINGS: [&str; 3] = ["deviceId", "authToken", "localWhisperModelPath"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub format_version: u32,
+    pub schema_version: usize,
+    pub app_version: String,
+    pub created_at: DateTime<Utc>,
+    pub audio_files: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupSummary {
+    pub path: String,
+    pub schema_version: usize,
+    pub settings: usize,
+    pub audio_files: usize,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreSummary {
+    pub schema_version: usize,
+    pub created_at: DateTime<Utc>,
+    pub settings: usize,
+    /// Audio files written; files that already existed are left alone
+    pub audio_files_restored: usize,
+    pub audio_files_skipped: usize,
+}
+
+fn app_data_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+fn zip_error(e: zip::result::ZipError) -> String {
+    format!("Failed to write backup: {}", e)
+}
+
+fn write_backup<R: Runtime>(app: &AppHandle<R>, path: &Path, audio_dir: Option<&Path>) -> Result<BackupSummary, String> {
+    let data_dir = app_data_dir(app)?;
+    let database_copy = data_dir.join(format!(".backup-{}.sqlite", uuid::Uuid::new_v4()));
+    let schema_version = storage::snapshot(app, &database_copy)?;
+    let result = (|| {
+        let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+        let settings: Map<String, Value> = store
+            .entries()
+            .into_iter()
+            .filter(|(key, _)| !MACHINE_SETTINGS.contains(&key.as_str()))
+            .collect();
+
+        let mut audio = recordings::app_recordings(app);
+        if let Some(dir) = audio_dir {
+            let extra: Vec<PathBuf> = recordings::audio_files(dir).into_iter().filter(|path| !audio.contains(path)).collect();
+            audio.extend(extra);
+        }
+        let mut names: Vec<String> = Vec::with_capacity(audio.len());
+        let audio: Vec<(String, PathBuf)> = audio
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                if names.contains(&name) {
+                    log_warn!("Skipping {} in backup, a file with the same name is already included", path.display());
+                    return None;
+                }
+                names.push(name.clone());
+                Some((name, path))
+            })
+            .collect();
+
+        let manifest = BackupManifest {
+            format_version: FORMAT_VERSION,
+            schema_version,
+            app_version: app.package_info().version.to_string(),
+            created_at: Utc::now(),
+            audio_files: names,
+        };
+
+        let file = AtomicFile::create(path).map_err(|e| format!("Failed to create backup file: {}", e))?;
+        let mut zip = ZipWriter::new(file);
+        let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+        // Audio is already compressed or barely compressible; storing it is much faster
+        let stored = FileOptions::default().compression_method(CompressionMethod::Stored).large_file(true);
+
+        zip.start_file(MANIFEST_ENTRY, options).map_err(zip_error)?;
+        zip.write_all(&serde_json::to_vec_pretty(&manifest).map_err(|e| e.to_string())?)
+            .map_err(|e| format!("Failed to write backup: {}", e))?;
+        zip.start_file(SETTINGS_ENTRY, options).map_err(zip_error)?;
+        zip.write_all(&serde_json::to_vec_pretty(&settings).map_err(|e| e.to_string())?)
+            .map_err(|e| format!("Failed to write backup: {}", e))?;
+        zip.start_file(DATABASE_ENTRY, options.large_file(true)).map_err(zip_error)?;
+        let mut database = File::open(&database_copy).map_err(|e| format!("Failed to read database copy: {}", e))?;
+        std::io::copy(&mut database, &mut zip).map_err(|e| format!("Failed to write backup: {}", e))?;
+        for (name, source) in &audio {
+            zip.start_file(format!("{}{}", AUDIO_PREFIX, name), stored).map_err(zip_error)?;
+            let mut file = File::open(source).map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+            std::io::copy(&mut file, &mut zip).map_err(|e| format!("Failed to write backup: {}", e))?;
+        }
+        zip.finish()
+            .map_err(zip_error)?
+            .commit()
+            .map_err(|e| format!("Failed to save backup: {}", e))?;
+
+        Ok(BackupSummary {
+            path: path.display().to_string(),
+            schema_version,
+            settings: settings.len(),
+            audio_files: audio.len(),
+            size_bytes: std::fs::metadata(path).map(|m| m.len()).unwrap_or(0),
+        })
+    })();
+    let _ = std::fs::remove_file(&database_copy);
+    result
+}
+
+fn read_manifest(archive: &mut ZipArchive<File>) -> Result<BackupManifest, String> {
+    let entry = archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|_| "Not a backup made by this app: manifest.json is missing".to_string())?;
+    let manifest: BackupManifest = serde_json::from_reader(entry).map_err(|e| format!("Invalid backup manifest: {}", e))?;
+    if manifest.format_version > FORMAT_VERSION {
+        return Err(format!(
+            "This backup was made by a newer version of the app ({}); update the app to restore it",
+            manifest.app_version
+        ));
+    }
+    if manifest.schema_version > storage::SCHEMA_VERSION {
+        return Err(format!(
+            "The backup's database is newer (schema {}) than this app supports (schema {}); update the app to restore it",
+            manifest.schema_version,
+            storage::SCHEMA_VERSION
+        ));
+    }
+    Ok(manifest)
+}
+
+fn read_backup<R: Runtime>(app: &AppHandle<R>, path: &Path, audio_dir: Option<&Path>) -> Result<RestoreSummary, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open backup: {}", e))?;
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("Failed to read backup: {}", e))?;
+    let manifest = read_manifest(&mut archive)?;
+
+    let settings: Map<String, Value> = {
+        let entry = archive
+            .by_name(SETTINGS_ENTRY)
+            .map_err(|e| format!("Backup has no settings: {}", e))?;
+        serde_json::from_reader(entry).map_err(|e| format!("Invalid settings in backup: {}", e))?
+    };
+
+    // Check the database before anything is replaced, so a bad backup changes nothing
+    let data_dir = app_data_dir(app)?;
+    let database_copy = data_dir.join(format!(".restore-{}.sqlite", uuid::Uuid::new_v4()));
+    let result = (|| {
+        {
+            let mut entry = archive
+                .by_name(DATABASE_ENTRY)
+                .map_err(|e| format!("Backup has no database: {}", e))?;
+            let mut out = File::create(&database_copy).map_err(|e| format!("Failed to extract database: {}", e))?;
+            std::io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to extract database: {}", e))?;
+        }
+        let schema_version = storage::check_database(&database_copy)?;
+        if schema_version != manifest.schema_version {
+            return Err(format!(
+                "Backup database has schema {} but its manifest says {}",
+                schema_version, manifest.schema_version
+            ));
+        }
+        storage::replace_database(app, &database_copy)
+    })();
+    let _ = std::fs::remove_file(&database_copy);
+    result?;
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    let kept: Vec<(String, Value)> = MACHINE_SETTINGS
+        .iter()
+        .filter_map(|key| store.get(*key).map(|value| (key.to_string(), value)))
+        .collect();
+    store.clear();
+    for (key, value) in settings.iter().filter(|(key, _)| !MACHINE_SETTINGS.contains(&key.as_str())) {
+        store.set(key.clone(), value.clone());
+    }
+    for (key, value) in kept {
+        store.set(key, value);
+    }
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    let audio_dir = match audio_dir {
+        Some(dir) => dir.to_path_buf(),
+        None => data_dir.join("recordings"),
+    };
+    std::fs::create_dir_all(&audio_dir).map_err(|e| format!("Failed to create audio directory: {}", e))?;
+    let (mut restored, mut skipped) = (0, 0);
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| format!("Failed to read backup: {}", e))?;
+        if !entry.name().starts_with(AUDIO_PREFIX) || entry.is_dir() {
+            continue;
+        }
+        // Only the file name is used, so an entry can't point outside the audio directory
+        let Some(name) = entry.enclosed_name().and_then(|name| name.file_name().map(|name| name.to_os_string())) else {
+            continue;
+        };
+        let target = audio_dir.join(name);
+        if target.exists() {
+            skipped += 1;
+            continue;
+        }
+        let mut out = File::create(&target).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+        restored += 1;
+    }
+
+    Ok(RestoreSummary {
+        schema_version: manifest.schema_version,
+        created_at: manifest.created_at,
+        settings: settings.len(),
+        audio_files_restored: restored,
+        audio_files_skipped: skipped,
+    })
+}
+
+/// Write a backup of all local data to `path`. Audio is the recordings the app saved plus,
+/// if given, the audio files in `audio_dir`.
+#[tauri::command]
+pub async fn create_backup<R: Runtime>(app: AppHandle<R>, path: String, audio_dir: Option<String>) -> Result<BackupSummary, String> {
+    log_info!("Creating backup at {}", path);
+    let summary = tauri::async_runtime::spawn_blocking(move || {
+        write_backup(&app, Path::new(&path), audio_dir.as_deref().map(Path::new))
+    })
+    .await
+    .map_err(|e| format!("Backup task failed: {}", e))?
+    .inspect_err(|e| log_error!("Backup failed: {}", e))?;
+    log_info!(
+        "Backup written to {} ({} bytes, {} audio files)",
+        summary.path,
+        summary.size_bytes,
+        summary.audio_files
+    );
+    Ok(summary)
+}
+
+/// Replace the local meetings and settings with those in the backup at `path`. Audio files
+/// go to `audio_dir`, or the app's recordings folder. Emits `backup-restored` when done so
+/// the UI can reload.
+#[tauri::command]
+pub async fn restore_backup<R: Runtime>(app: AppHandle<R>, path: String, audio_dir: Option<String>) -> Result<RestoreSummary, String> {
+    log_info!("Restoring backup from {}", path);
+    let handle = app.clone();
+    let summary = tauri::async_runtime::spawn_blocking(move || {
+        read_backup(&handle, Path::new(&path), audio_dir.as_deref().map(Path::new))
+    })
+    .await
+    .map_err(|e| format!("Restore task failed: {}", e))?
+    .inspect_err(|e| log_error!("Restore failed: {}", e))?;
+    log_info!(
+        "Restored backup from {} (schema {}, {} audio files)",
+        summary.created_at,
+        summary.schema_version,
+        summary.audio_files_restored
+    );
+    if let Err(e) = app.emit("backup-restored", &summary) {
+        log_error!("Failed to emit backup-restored event: {}", e);
+    }
+    Ok(summary)
+}
@@ -0,0 +1,164 @@
+// Operations on several meetings at once: deleting them from the backend, exporting them
+// to a folder and tagging them. Meetings are handled one after the other and a failure
+// doesn't stop the rest; after each one a `batch-progress` event reports how far the batch
+// got, and the command returns which meetings succeeded and why the others failed.
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api::{self, DeleteMeetingRequest};
+use crate::export::{self, hooks, MinutesFormat};
+use crate::storage::tags;
+
+// Longest file name stem taken from a meeting title
+const MAX_FILE_STEM_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    pub operation: &'static str,
+    pub meeting_id: String,
+    /// Meetings handled so far, this one included
+    pub completed: usize,
+    pub total: usize,
+    /// Why this meeting failed; None if it succeeded
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchFailure {
+    pub meeting_id: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BatchResult {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<BatchFailure>,
+}
+
+impl BatchResult {
+    fn record<R: Runtime>(&mut self, app: &AppHandle<R>, operation: &'static str, total: usize, meeting_id: String, result: Result<(), String>) {
+        let error = result.err();
+        let progress = BatchProgress {
+            operation,
+            meeting_id: meeting_id.clone(),
+            completed: self.succeeded.len() + self.failed.len() + 1,
+            total,
+            error: error.clone(),
+        };
+        if let Err(e) = app.emit("batch-progress", &progress) {
+            log_error!("Failed to emit batch-progress event: {}", e);
+        }
+        match error {
+            None => self.succeeded.push(meeting_id),
+            Some(error) => {
+                log_warn!("{} failed for meeting {}: {}", operation, meeting_id, error);
+                self.failed.push(BatchFailure { meeting_id, error });
+            }
+        }
+    }
+
+    fn log(&self, operation: &str) {
+        log_info!("Batch {} finished: {} succeeded, {} failed", operation, self.succeeded.len(), self.failed.len());
+    }
+}
+
+// Each meeting once, in the order given
+fn unique_ids(ids: Vec<String>) -> Result<Vec<String>, String> {
+    let mut unique: Vec<String> = Vec::with_capacity(ids.len());
+    for id in ids {
+        let id = id.trim().to_string();
+        if !id.is_empty() && !unique.contains(&id) {
+            unique.push(id);
+        }
+    }
+    if unique.is_empty() {
+        return Err("No meetings selected".to_string());
+    }
+    Ok(unique)
+}
+
+// A file name from the meeting title that doesn't collide with one already written
+fn export_path(directory: &Path, title: &str, meeting_id: &str, format: MinutesFormat) -> PathBuf {
+    let stem: String = title
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_' | '.') { c } else { '_' })
+        .take(MAX_FILE_STEM_CHARS)
+        .collect();
+    let stem = stem.trim().trim_matches('.');
+    let stem = if stem.is_empty() { meeting_id } else { stem };
+    let path = directory.join(format!("{}.{}", stem, format.extension()));
+    if path.exists() {
+        directory.join(format!("{} ({}).{}", stem, meeting_id, format.extension()))
+    } else {
+        path
+    }
+}
+
+/// Delete meetings from the backend.
+#[tauri::command]
+pub async fn api_delete_meetings<R: Runtime>(
+    app: AppHandle<R>,
+    ids: Vec<String>,
+    auth_token: Option<String>,
+) -> Result<BatchResult, String> {
+    let ids = unique_ids(ids)?;
+    log_info!("Deleting {} meetings", ids.len());
+    let mut result = BatchResult::default();
+    for meeting_id in &ids {
+        let body = serde_json::to_string(&DeleteMeetingRequest { meeting_id: meeting_id.clone() }).map_err(|e| e.to_string())?;
+        let deleted = api::make_api_request::<R, serde_json::Value>(&app, "/delete-meeting", "POST", Some(&body), None, auth_token.clone())
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string());
+        result.record(&app, "delete", ids.len(), meeting_id.clone(), deleted);
+    }
+    result.log("delete");
+    Ok(result)
+}
+
+/// Export meetings as Markdown or PDF minutes into `directory`, one file per meeting named
+/// after its title.
+#[tauri::command]
+pub async fn export_meetings<R: Runtime>(
+    app: AppHandle<R>,
+    ids: Vec<String>,
+    format: MinutesFormat,
+    directory: String,
+    auth_token: Option<String>,
+) -> Result<BatchResult, String> {
+    let ids = unique_ids(ids)?;
+    let directory = PathBuf::from(directory);
+    std::fs::create_dir_all(&directory).map_err(|e| format!("Failed to create export directory: {}", e))?;
+    log_info!("Exporting {} meetings to {}", ids.len(), directory.display());
+
+    let mut result = BatchResult::default();
+    for meeting_id in &ids {
+        let exported = match export::load_document(&app, meeting_id, auth_token.clone()).await {
+            Ok(document) => {
+                let path = export_path(&directory, &document.title, meeting_id, format);
+                export::write_minutes(&document, format, &path).map(|_| path)
+            }
+            Err(e) => Err(e),
+        };
+        let exported = exported.map(|path| hooks::run_after_export(&app, path, format.name()));
+        result.record(&app, "export", ids.len(), meeting_id.clone(), exported);
+    }
+    result.log("export");
+    Ok(result)
+}
+
+/// Add `tag` to local meetings.
+#[tauri::command]
+pub async fn retag_meetings<R: Runtime>(app: AppHandle<R>, ids: Vec<String>, tag: String) -> Result<BatchResult, String> {
+    let ids = unique_ids(ids)?;
+    let tag = tags::normalize_tag(&tag)?;
+    let mut result = BatchResult::default();
+    for meeting_id in &ids {
+        let tagged = tags::tag_meeting(&app, meeting_id, &tag).map(|_| ());
+        result.record(&app, "retag", ids.len(), meeting_id.clone(), tagged);
+    }
+    result.log("retag");
+    Ok(result)
+}
@@ -0,0 +1,432 @@
+// Calendar subscriptions, used to recognise which meeting is being recorded. The user adds
+// the iCalendar (ICS) addresses of their calendars; Google Calendar, Outlook and iCloud all
+// publish one per calendar. When a recording starts, the feeds are fetched and the event
+// in progress (or about to begin) is linked to the recording: its title names the meeting,
+// its categories become tags, its attendees are offered as speaker names and its end time
+// drives the meeting timer.
+//
+// Recurring events are expanded for daily and weekly rules (with INTERVAL, COUNT, UNTIL,
+// BYDAY and EXDATE), which covers standing meetings. Other rules only match on the first
+// occurrence.
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Weekday};
+use chrono_tz::Tz;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::{meeting_metadata, meeting_timer};
+
+const CALENDAR_STORE_KEY: &str = "calendar";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+// An event starting this soon after the recording counts as the one being recorded
+const EARLY_START_MINUTES: i64 = 10;
+// Recurring events are not expanded further back than this
+const MAX_RECURRENCE_DAYS: i64 = 5 * 366;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub enabled: bool,
+    /// ICS feed addresses; `webcal://` is accepted as an alias for `https://`
+    pub ics_urls: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub uid: String,
+    pub title: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub attendees: Vec<String>,
+    pub tags: Vec<String>,
+    pub location: Option<String>,
+}
+
+// When an event happens, before recurrence is applied
+#[derive(Debug, Clone)]
+enum EventTime {
+    Utc(DateTime<Utc>),
+    Zoned(NaiveDateTime, Tz),
+    Floating(NaiveDateTime),
+}
+
+impl EventTime {
+    fn naive(&self) -> NaiveDateTime {
+        match self {
+            EventTime::Utc(time) => time.naive_utc(),
+            EventTime::Zoned(time, _) | EventTime::Floating(time) => *time,
+        }
+    }
+
+    // The same wall-clock time as this one, moved to another date
+    fn at(&self, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            EventTime::Utc(_) => Some(Utc.from_utc_datetime(&naive)),
+            EventTime::Zoned(_, tz) => tz.from_local_datetime(&naive).earliest().map(|t| t.with_timezone(&Utc)),
+            EventTime::Floating(_) => Local.from_local_datetime(&naive).earliest().map(|t| t.with_timezone(&Utc)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Recurrence {
+    weekly: bool,
+    interval: u32,
+    count: Option<u32>,
+    until: Option<DateTime<Utc>>,
+    by_day: Vec<Weekday>,
+}
+
+#[derive(Debug, Default)]
+struct RawEvent {
+    uid: String,
+    title: String,
+    start: Option<EventTime>,
+    end: Option<EventTime>,
+    attendees: Vec<String>,
+    tags: Vec<String>,
+    location: Option<String>,
+    recurrence: Option<Recurrence>,
+    exdates: Vec<DateTime<Utc>>,
+    cancelled: bool,
+    all_day: bool,
+}
+
+static CURRENT_EVENT: Lazy<Mutex<Option<CalendarEvent>>> = Lazy::new(|| Mutex::new(None));
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> CalendarConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(CALENDAR_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+// Continuation lines start with a space or tab (RFC 5545 section 3.1)
+fn unfold(ics: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in ics.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+// Splits `NAME;PARAM=x:value` into the name, its parameters and the value
+fn parse_line(line: &str) -> Option<(String, Vec<(String, String)>, String)> {
+    let mut in_quotes = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            ':' if !in_quotes => return Some(i),
+            _ => {}
+        }
+        None
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.to_uppercase();
+    let params = parts
+        .filter_map(|param| param.split_once('='))
+        .map(|(key, value)| (key.to_uppercase(), value.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value.to_string()))
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\n", "\n").replace("\\N", "\n").replace("\\,", ",").replace("\\;", ";").replace("\\\\", "\\")
+}
+
+fn param<'a>(params: &'a [(String, String)], key: &str) -> Option<&'a str> {
+    params.iter().find(|(name, _)| name == key).map(|(_, value)| value.as_str())
+}
+
+fn parse_time(value: &str, params: &[(String, String)]) -> Option<EventTime> {
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(EventTime::Utc(Utc.from_utc_datetime(&naive)));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    match param(params, "TZID").and_then(|tzid| tzid.parse::<Tz>().ok()) {
+        Some(tz) => Some(EventTime::Zoned(naive, tz)),
+        None => Some(EventTime::Floating(naive)),
+    }
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    // BYDAY entries may carry an ordinal (`2MO`); only the day is used
+    match code.trim_start_matches(|c: char| c.is_ascii_digit() || c == '-' || c == '+') {
+        "MO" => Some(Weekday::Mon),
+        "TU" => Some(Weekday::Tue),
+        "WE" => Some(Weekday::Wed),
+        "TH" => Some(Weekday::Thu),
+        "FR" => Some(Weekday::Fri),
+        "SA" => Some(Weekday::Sat),
+        "SU" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+fn parse_rrule(value: &str) -> Option<Recurrence> {
+    let mut recurrence = Recurrence { interval: 1, ..Recurrence::default() };
+    for (key, value) in value.split(';').filter_map(|part| part.split_once('=')) {
+        match key.to_uppercase().as_str() {
+            "FREQ" => match value.to_uppercase().as_str() {
+                "DAILY" => recurrence.weekly = false,
+                "WEEKLY" => recurrence.weekly = true,
+                _ => return None,
+            },
+            "INTERVAL" => recurrence.interval = value.parse().ok().filter(|i| *i > 0)?,
+            "COUNT" => recurrence.count = value.parse().ok(),
+            "UNTIL" => {
+                // Either a date-time or, for all-day series, a date that is itself included
+                recurrence.until = match parse_time(value, &[]) {
+                    Some(time) => time.at(time.naive()),
+                    None => NaiveDate::parse_from_str(value, "%Y%m%d")
+                        .ok()
+                        .and_then(|date| date.and_hms_opt(23, 59, 59))
+                        .map(|end| Utc.from_utc_datetime(&end)),
+                }
+            }
+            "BYDAY" => recurrence.by_day = value.split(',').filter_map(weekday).collect(),
+            _ => {}
+        }
+    }
+    Some(recurrence)
+}
+
+fn parse_events(ics: &str) -> Vec<RawEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<RawEvent> = None;
+    for line in unfold(ics) {
+        let Some((name, params, value)) = parse_line(&line) else { continue };
+        match (name.as_str(), current.as_mut()) {
+            ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => current = Some(RawEvent::default()),
+            ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => events.extend(current.take()),
+            ("UID", Some(event)) => event.uid = value,
+            ("SUMMARY", Some(event)) => event.title = unescape(&value),
+            ("LOCATION", Some(event)) => event.location = Some(unescape(&value)).filter(|l| !l.is_empty()),
+            ("STATUS", Some(event)) => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            ("DTSTART", Some(event)) => {
+                event.all_day = param(&params, "VALUE") == Some("DATE") || value.len() == 8;
+                event.start = parse_time(&value, &params);
+            }
+            ("DTEND", Some(event)) => event.end = parse_time(&value, &params),
+            ("RRULE", Some(event)) => event.recurrence = parse_rrule(&value),
+            ("EXDATE", Some(event)) => event.exdates.extend(
+                value.split(',').filter_map(|date| parse_time(date, &params)).filter_map(|t| t.at(t.naive())),
+            ),
+            ("CATEGORIES", Some(event)) => event.tags.extend(
+                value.split(',').map(|tag| unescape(tag).trim().to_string()).filter(|tag| !tag.is_empty()),
+            ),
+            ("ATTENDEE" | "ORGANIZER", Some(event)) => {
+                let name = param(&params, "CN")
+                    .map(str::to_string)
+                    .or_else(|| value.strip_prefix("mailto:").or_else(|| value.strip_prefix("MAILTO:")).map(str::to_string));
+                if let Some(name) = name.filter(|n| !n.is_empty() && !event.attendees.contains(n)) {
+                    event.attendees.push(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    events
+}
+
+// Start and end of the occurrence of `event` that covers `now`, if there is one
+fn occurrence_at(event: &RawEvent, now: DateTime<Utc>) -> Option<(DateTime<Utc>, DateTime<Utc>)> {
+    let start = event.start.as_ref()?;
+    let first_start = start.at(start.naive())?;
+    let duration = match &event.end {
+        Some(end) => end.at(end.naive())? - first_start,
+        None => chrono::Duration::hours(1),
+    };
+    let covers = |start: DateTime<Utc>| {
+        start - chrono::Duration::minutes(EARLY_START_MINUTES) <= now && now < start + duration
+    };
+
+    let Some(recurrence) = &event.recurrence else {
+        return covers(first_start).then_some((first_start, first_start + duration));
+    };
+    let first_date = start.naive().date();
+    let today = now.with_timezone(&Local).date_naive();
+    if (today - first_date).num_days() > MAX_RECURRENCE_DAYS {
+        return None;
+    }
+    let days: Vec<Weekday> = if recurrence.weekly && !recurrence.by_day.is_empty() {
+        recurrence.by_day.clone()
+    } else {
+        vec![first_date.weekday()]
+    };
+    let week_of = |date: NaiveDate| (date - chrono::Duration::days(date.weekday().num_days_from_monday() as i64)).num_days_from_ce() / 7;
+
+    let mut seen = 0u32;
+    let mut date = first_date;
+    while date <= today + chrono::Duration::days(1) {
+        let elapsed = (date - first_date).num_days();
+        let matches = if recurrence.weekly {
+            days.contains(&date.weekday()) && (week_of(date) - week_of(first_date)) % recurrence.interval as i32 == 0
+        } else {
+            elapsed % recurrence.interval as i64 == 0
+        };
+        if matches {
+            seen += 1;
+            if recurrence.count.is_some_and(|count| seen > count) {
+                return None;
+            }
+            let occurrence = start.at(date.and_time(start.naive().time()))?;
+            if recurrence.until.is_some_and(|until| occurrence > until) {
+                return None;
+            }
+            if !event.exdates.contains(&occurrence) && covers(occurrence) {
+                return Some((occurrence, occurrence + duration));
+            }
+        }
+        date += chrono::Duration::days(1);
+    }
+    None
+}
+
+async fn fetch_feed(client: &reqwest::Client, url: &str) -> Result<String, String> {
+    let url = match url.strip_prefix("webcal://") {
+        Some(rest) => format!("https://{}", rest),
+        None => url.to_string(),
+    };
+    let response = client
+        .get(&url)
+        .timeout(FETCH_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch calendar: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Calendar feed returned {}", response.status()));
+    }
+    response.text().await.map_err(|e| format!("Failed to read calendar: {}", e))
+}
+
+/// Events from all configured feeds happening at `now`, earliest start first.
+async fn events_at(config: &CalendarConfig, now: DateTime<Utc>) -> Vec<CalendarEvent> {
+    let client = reqwest::Client::new();
+    let mut events = Vec::new();
+    for url in &config.ics_urls {
+        let ics = match fetch_feed(&client, url).await {
+            Ok(ics) => ics,
+            Err(e) => {
+                log_warn!("{}", e);
+                continue;
+            }
+        };
+        for event in parse_events(&ics).into_iter().filter(|event| !event.cancelled && !event.all_day) {
+            if let Some((start, end)) = occurrence_at(&event, now) {
+                events.push(CalendarEvent {
+                    uid: event.uid,
+                    title: event.title,
+                    start,
+                    end,
+                    attendees: event.attendees,
+                    tags: event.tags,
+                    location: event.location,
+                });
+            }
+        }
+    }
+    // The meeting that started most recently is the likeliest one being recorded
+    events.sort_by_key(|event| (now - event.start).num_seconds().abs());
+    events
+}
+
+fn link_event<R: Runtime>(app: &AppHandle<R>, event: CalendarEvent) {
+    log_info!("Recording linked to calendar event {}", event.title);
+    meeting_metadata::set("calendar_event", serde_json::json!({
+        "uid": event.uid,
+        "title": event.title,
+        "start": event.start.to_rfc3339(),
+        "end": event.end.to_rfc3339(),
+        "location": event.location,
+    }));
+    if !event.tags.is_empty() {
+        meeting_metadata::set("tags", serde_json::json!(event.tags));
+    }
+    if !event.attendees.is_empty() {
+        meeting_metadata::set("speaker_candidates", serde_json::json!(event.attendees));
+    }
+    meeting_timer::set_scheduled_end(app, Some(event.end));
+    if let Ok(mut current) = CURRENT_EVENT.lock() {
+        *current = Some(event.clone());
+    }
+    if let Err(e) = app.emit("calendar-event-linked", &event) {
+        log_error!("Failed to emit calendar-event-linked event: {}", e);
+    }
+}
+
+/// Look up the calendar event being recorded, in the background, and link it.
+pub fn begin_recording<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut current) = CURRENT_EVENT.lock() {
+        *current = None;
+    }
+    let config = load_config(app);
+    if !config.enabled || config.ics_urls.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match events_at(&config, Utc::now()).await.into_iter().next() {
+            Some(event) => link_event(&app, event),
+            None => log_info!("No calendar event found for this recording"),
+        }
+    });
+}
+
+/// Title of the calendar event linked to the current recording, if any.
+pub fn current_title() -> Option<String> {
+    CURRENT_EVENT.lock().ok()?.as_ref().map(|event| event.title.clone()).filter(|title| !title.trim().is_empty())
+}
+
+#[tauri::command]
+pub async fn get_calendar_config<R: Runtime>(app: AppHandle<R>) -> Result<CalendarConfig, String> {
+    Ok(load_config(&app))
+}
+
+#[tauri::command]
+pub async fn set_calendar_config<R: Runtime>(app: AppHandle<R>, config: CalendarConfig) -> Result<(), String> {
+    for url in &config.ics_urls {
+        let valid = ["https://", "http://", "webcal://"].iter().any(|scheme| url.starts_with(scheme));
+        if !valid {
+            return Err(format!("Calendar address must be an http(s) or webcal URL: {}", url));
+        }
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(CALENDAR_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Calendar integration {} with {} feed(s)", if config.enabled { "enabled" } else { "disabled" }, config.ics_urls.len());
+    Ok(())
+}
+
+/// Events happening now (or starting within a few minutes) across the configured feeds.
+#[tauri::command]
+pub async fn get_current_calendar_events<R: Runtime>(app: AppHandle<R>) -> Result<Vec<CalendarEvent>, String> {
+    let config = load_config(&app);
+    Ok(events_at(&config, Utc::now()).await)
+}
+
+/// The event linked to the current recording, if one was found.
+#[tauri::command]
+pub fn get_linked_calendar_event() -> Option<CalendarEvent> {
+    CURRENT_EVENT.lock().ok().and_then(|current| current.clone())
+}
+
+/// Attendees of the linked event, offered as names when labelling speakers.
+#[tauri::command]
+pub fn get_speaker_candidates() -> Vec<String> {
+    CURRENT_EVENT
+        .lock()
+        .ok()
+        .and_then(|current| current.as_ref().map(|event| event.attendees.clone()))
+        .unwrap_or_default()
+}
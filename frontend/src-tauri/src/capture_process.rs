@@ -0,0 +1,102 @@
+// Restricting system audio to one application. On Windows the system stream can capture a
+// single process (e.g. Zoom, Teams or a browser) rather than everything the output device
+// plays. The target is stored under `captureProcess` as a process name, which is looked up
+// again at the start of each recording, or as a process id for a one-off choice.
+// Recordings fall back to capturing the whole device when the process isn't running.
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::info as log_info;
+
+const CAPTURE_PROCESS_STORE_KEY: &str = "captureProcess";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureProcess {
+    pub pid: u32,
+    pub name: String,
+}
+
+fn stored_target<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(CAPTURE_PROCESS_STORE_KEY))
+        .and_then(|value| value.as_str().map(str::to_string))
+}
+
+#[cfg(target_os = "windows")]
+fn base_name(name: &str) -> String {
+    let name = name.trim().to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+/// Find the running process `target` names, by id or by executable name. For a name, the
+/// top process of its tree is chosen, since capture includes the processes it started.
+#[cfg(target_os = "windows")]
+fn find_process(target: &str) -> Option<CaptureProcess> {
+    use sysinfo::{Pid, System};
+
+    let mut system = System::new();
+    system.refresh_processes();
+    if let Ok(pid) = target.trim().parse::<u32>() {
+        return system.process(Pid::from_u32(pid)).map(|process| CaptureProcess {
+            pid,
+            name: process.name().to_string(),
+        });
+    }
+    let wanted = base_name(target);
+    let matches = |pid: Pid| system.process(pid).is_some_and(|process| base_name(process.name()) == wanted);
+    system
+        .processes()
+        .iter()
+        .filter(|(pid, _)| matches(**pid))
+        .filter(|(_, process)| !process.parent().is_some_and(matches))
+        .min_by_key(|(pid, _)| pid.as_u32())
+        .map(|(pid, process)| CaptureProcess { pid: pid.as_u32(), name: process.name().to_string() })
+}
+
+#[cfg(not(target_os = "windows"))]
+fn find_process(_target: &str) -> Option<CaptureProcess> {
+    None
+}
+
+/// The process to capture system audio from for a new recording, if one is set and running.
+pub fn resolve<R: Runtime>(app: &AppHandle<R>) -> Option<CaptureProcess> {
+    stored_target(app).and_then(|target| find_process(&target))
+}
+
+/// Limit system audio to one application, given its process id or executable name
+/// (`Zoom`, `Teams.exe`, `chrome`). Pass nothing to capture the whole output device again.
+/// Returns the matching process when it is running now. Windows only.
+#[tauri::command]
+pub async fn set_capture_process<R: Runtime>(
+    app: AppHandle<R>,
+    pid_or_name: Option<String>,
+) -> Result<Option<CaptureProcess>, String> {
+    if cfg!(not(target_os = "windows")) {
+        return Err("Capturing a single application is only supported on Windows".to_string());
+    }
+    let target = pid_or_name.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let process = target.as_deref().and_then(find_process);
+    // A name may be set before the app is started; a process id has to exist now
+    if let Some(target) = &target {
+        if process.is_none() && target.parse::<u32>().is_ok() {
+            return Err(format!("No running process with id {}", target));
+        }
+    }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    match &target {
+        Some(target) => store.set(CAPTURE_PROCESS_STORE_KEY, serde_json::json!(target)),
+        None => {
+            store.delete(CAPTURE_PROCESS_STORE_KEY);
+        }
+    }
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("System audio capture limited to {}", target.as_deref().unwrap_or("the whole output device"));
+    Ok(process)
+}
+
+#[tauri::command]
+pub async fn get_capture_process<R: Runtime>(app: AppHandle<R>) -> Result<Option<String>, String> {
+    Ok(stored_target(&app))
+}
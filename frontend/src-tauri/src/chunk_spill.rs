@@ -0,0 +1,315 @@
+// Chunks that do not fit in the transcription queue are written to disk instead of being
+// dropped, and transcribed once the recording has stopped so slow models leave no gaps.
+// Chunks whose transcription failed for good go to a separate dead-letter directory and
+// are only retried on request.
+use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::atomic_file;
+use crate::audio::decode_audio_file;
+use crate::audio::wav_writer::WavWriter;
+use crate::{
+    assign_speakers, is_recording, load_diarizer, load_transcription_config, transcribe_chunk, transcript_server_url,
+    AudioChunk, CaptureSource, SessionHandles, TranscriptAccumulator, TranscriptUpdate, TranscriptionConfig,
+    WHISPER_SAMPLE_RATE,
+};
+
+const SPILL_DIR: &str = "spilled-chunks";
+const DEAD_LETTER_DIR: &str = "dead-letter-chunks";
+
+// Stored next to each spilled WAV so the chunk lands at the right place in the transcript
+#[derive(Debug, Serialize, Deserialize)]
+struct SpilledChunkInfo {
+    chunk_id: u64,
+    source: String,
+    timestamp: f64,
+    overlap_secs: f64,
+    /// Why transcription gave up, for dead-lettered chunks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl SpilledChunkInfo {
+    fn for_chunk(chunk: &AudioChunk, error: Option<&str>) -> Self {
+        Self {
+            chunk_id: chunk.chunk_id,
+            source: chunk.source.key().to_string(),
+            timestamp: chunk.timestamp,
+            overlap_secs: chunk.overlap_secs,
+            error: error.map(str::to_string),
+        }
+    }
+}
+
+/// A chunk whose transcription failed after all retries.
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetterChunk {
+    pub chunk_id: u64,
+    pub source: String,
+    /// Seconds into the recording
+    pub timestamp: f64,
+    pub error: Option<String>,
+}
+
+struct SpilledChunk {
+    info: SpilledChunkInfo,
+    audio_path: PathBuf,
+    info_path: PathBuf,
+}
+
+impl SpilledChunk {
+    fn remove(&self) {
+        let removed = [std::fs::remove_file(&self.audio_path), atomic_file::remove(&self.info_path)];
+        for (path, result) in [&self.audio_path, &self.info_path].into_iter().zip(removed) {
+            if let Err(e) = result {
+                log_warn!("Failed to remove spilled chunk file {:?}: {}", path, e);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PendingChunksProgress {
+    processed: usize,
+    total: usize,
+    failed: usize,
+}
+
+fn chunk_dir<R: Runtime>(app: &AppHandle<R>, name: &str) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_cache_dir()
+        .map_err(|e| format!("Failed to resolve app cache directory: {}", e))?
+        .join(name);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {} directory: {}", name, e))?;
+    Ok(dir)
+}
+
+fn spill_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    chunk_dir(app, SPILL_DIR)
+}
+
+fn dead_letter_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    chunk_dir(app, DEAD_LETTER_DIR)
+}
+
+fn write_chunk(dir: &Path, info: &SpilledChunkInfo, samples: &[f32]) -> Result<PathBuf, String> {
+    let name = format!("chunk-{:08}-{}", info.chunk_id, info.source);
+    let audio_path = dir.join(format!("{}.wav", name));
+    let mut writer = WavWriter::create(&audio_path, WHISPER_SAMPLE_RATE).map_err(|e| format!("Failed to spill chunk: {}", e))?;
+    writer.write_samples(samples).map_err(|e| format!("Failed to spill chunk: {}", e))?;
+    writer.finalize().map_err(|e| format!("Failed to spill chunk: {}", e))?;
+    // The info file is written last, so a chunk without one is incomplete and ignored
+    let data = serde_json::to_vec(info).map_err(|e| e.to_string())?;
+    atomic_file::write(dir.join(format!("{}.json", name)), data).map_err(|e| format!("Failed to spill chunk: {}", e))?;
+    Ok(audio_path)
+}
+
+/// Write a chunk that was pushed out of a full queue to disk.
+pub(crate) fn spill<R: Runtime>(app: &AppHandle<R>, chunk: &AudioChunk) -> Result<PathBuf, String> {
+    write_chunk(&spill_dir(app)?, &SpilledChunkInfo::for_chunk(chunk, None), &chunk.samples)
+}
+
+/// Keep a chunk whose transcription failed after all retries, so its audio is not lost.
+pub(crate) fn dead_letter<R: Runtime>(app: &AppHandle<R>, chunk: &AudioChunk, samples: &[f32], error: &str) -> Result<PathBuf, String> {
+    write_chunk(&dead_letter_dir(app)?, &SpilledChunkInfo::for_chunk(chunk, Some(error)), samples)
+}
+
+fn load_pending(dir: &Path) -> Result<Vec<SpilledChunk>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("Failed to read spill directory: {}", e))?;
+    let mut chunks: Vec<SpilledChunk> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|info_path| {
+            let info: SpilledChunkInfo = match atomic_file::read_verified(&info_path).map(|data| serde_json::from_slice(&data)) {
+                Ok(Ok(info)) => info,
+                _ => {
+                    log_warn!("Ignoring unreadable spilled chunk {:?}", info_path);
+                    return None;
+                }
+            };
+            let audio_path = info_path.with_extension("wav");
+            audio_path.is_file().then_some(SpilledChunk { info, audio_path, info_path })
+        })
+        .collect();
+    chunks.sort_by_key(|chunk| chunk.info.chunk_id);
+    Ok(chunks)
+}
+
+/// Number of spilled chunks waiting to be transcribed.
+#[tauri::command]
+pub async fn get_pending_chunk_count<R: Runtime>(app: AppHandle<R>) -> Result<usize, String> {
+    Ok(load_pending(&spill_dir(&app)?)?.len())
+}
+
+/// Transcribes audio outside a live recording (spilled chunks, recovered audio) with the
+/// same engine, diarization and sentence building as the recording itself.
+pub(crate) struct Retranscriber<R: Runtime> {
+    app: AppHandle<R>,
+    config: TranscriptionConfig,
+    stream_url: String,
+    client: reqwest::Client,
+    handles: SessionHandles,
+    // One accumulator per transcript label, so sentences never mix sources
+    accumulators: Vec<(&'static str, TranscriptAccumulator)>,
+    started: std::time::Instant,
+    updates: Vec<(&'static str, TranscriptUpdate)>,
+}
+
+impl<R: Runtime> Retranscriber<R> {
+    pub(crate) fn new(app: &AppHandle<R>) -> Self {
+        Self {
+            app: app.clone(),
+            config: load_transcription_config(app),
+            stream_url: format!("{}/stream", transcript_server_url(app)),
+            client: reqwest::Client::new(),
+            handles: SessionHandles::new(Arc::new(AtomicBool::new(true)), load_diarizer(app)),
+            accumulators: Vec::new(),
+            started: std::time::Instant::now(),
+            updates: Vec::new(),
+        }
+    }
+
+    /// Transcribe one stretch of 16kHz audio that starts `timestamp` seconds into the
+    /// recording. Lines are labelled with `label` instead of the source's own label.
+    pub(crate) async fn transcribe(
+        &mut self,
+        source: CaptureSource,
+        label: &'static str,
+        chunk_id: u64,
+        timestamp: f64,
+        overlap_secs: f64,
+        samples: Vec<f32>,
+    ) -> Result<(), String> {
+        let samples = Arc::new(samples);
+        let mut response =
+            transcribe_chunk(&self.app, samples.clone(), &self.client, &self.stream_url, &self.config, &self.handles).await?;
+        if let Some(diarizer) = &self.handles.diarizer {
+            assign_speakers(diarizer.clone(), samples, &mut response.segments).await;
+        }
+
+        let index = match self.accumulators.iter().position(|(l, _)| *l == label) {
+            Some(index) => index,
+            None => {
+                let accumulator = TranscriptAccumulator::new(source, self.handles.recent_words(source));
+                self.accumulators.push((label, accumulator));
+                self.accumulators.len() - 1
+            }
+        };
+        let accumulator = &mut self.accumulators[index].1;
+        accumulator.set_chunk_context(chunk_id, timestamp, overlap_secs, self.started);
+        for segment in &response.segments {
+            if let Some(update) = accumulator.add_segment(segment) {
+                self.updates.push((label, update));
+            }
+        }
+        Ok(())
+    }
+
+    /// All lines produced, in recording order.
+    pub(crate) fn finish(mut self) -> Vec<TranscriptUpdate> {
+        for (label, accumulator) in self.accumulators.iter_mut() {
+            if let Some(update) = accumulator.take_partial() {
+                self.updates.push((*label, update));
+            }
+        }
+        let mut updates: Vec<TranscriptUpdate> = self
+            .updates
+            .into_iter()
+            .map(|(label, mut update)| {
+                update.source = label.to_string();
+                update
+            })
+            .collect();
+        updates.sort_by(|a, b| a.chunk_start_time.total_cmp(&b.chunk_start_time).then(a.sequence_id.cmp(&b.sequence_id)));
+        updates
+    }
+}
+
+/// Transcribe every spilled chunk, oldest first. Chunks that fail stay on disk for the
+/// next attempt.
+pub(crate) async fn transcribe_pending<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<TranscriptUpdate>, String> {
+    transcribe_dir(app, &spill_dir(app)?, "pending-chunks-progress").await
+}
+
+async fn transcribe_dir<R: Runtime>(app: &AppHandle<R>, dir: &Path, progress_event: &str) -> Result<Vec<TranscriptUpdate>, String> {
+    let pending = load_pending(dir)?;
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+    log_info!("Retranscribing {} stored chunks from {:?}", pending.len(), dir);
+
+    let mut retranscriber = Retranscriber::new(app);
+    let mut failed = 0;
+    for (index, chunk) in pending.iter().enumerate() {
+        let result = match CaptureSource::from_key(&chunk.info.source) {
+            Some(source) => match decode_audio_file(&chunk.audio_path) {
+                Ok((samples, _)) => {
+                    retranscriber
+                        .transcribe(source, source.label(), chunk.info.chunk_id, chunk.info.timestamp, chunk.info.overlap_secs, samples)
+                        .await
+                }
+                Err(e) => Err(format!("Failed to read spilled chunk: {}", e)),
+            },
+            None => Err(format!("Unknown source {}", chunk.info.source)),
+        };
+        match result {
+            Ok(()) => chunk.remove(),
+            Err(e) => {
+                log_error!("Failed to transcribe spilled chunk {}: {}", chunk.info.chunk_id, e);
+                failed += 1;
+            }
+        }
+        let progress = PendingChunksProgress { processed: index + 1, total: pending.len(), failed };
+        if let Err(e) = app.emit(progress_event, progress) {
+            log_error!("Failed to emit {} event: {}", progress_event, e);
+        }
+    }
+    let updates = retranscriber.finish();
+
+    log_info!(
+        "Retranscribed {} stored chunks into {} transcript lines ({} failed)",
+        pending.len() - failed,
+        updates.len(),
+        failed
+    );
+    Ok(updates)
+}
+
+/// Transcribe the chunks spilled during recording and return the resulting transcript
+/// lines in recording order.
+#[tauri::command]
+pub(crate) async fn retranscribe_pending_chunks<R: Runtime>(app: AppHandle<R>) -> Result<Vec<TranscriptUpdate>, String> {
+    if is_recording() {
+        return Err("Pending chunks are transcribed once the recording has stopped".to_string());
+    }
+    transcribe_pending(&app).await
+}
+
+/// Chunks whose transcription failed after all retries, oldest first.
+#[tauri::command]
+pub async fn get_dead_letter_chunks<R: Runtime>(app: AppHandle<R>) -> Result<Vec<DeadLetterChunk>, String> {
+    Ok(load_pending(&dead_letter_dir(&app)?)?
+        .into_iter()
+        .map(|chunk| DeadLetterChunk {
+            chunk_id: chunk.info.chunk_id,
+            source: chunk.info.source,
+            timestamp: chunk.info.timestamp,
+            error: chunk.info.error,
+        })
+        .collect())
+}
+
+/// Try the dead-lettered chunks again and return the resulting transcript lines in
+/// recording order. Chunks that fail again stay in the store.
+#[tauri::command]
+pub(crate) async fn retry_dead_letter_chunks<R: Runtime>(app: AppHandle<R>) -> Result<Vec<TranscriptUpdate>, String> {
+    if is_recording() {
+        return Err("Failed chunks are retried once the recording has stopped".to_string());
+    }
+    transcribe_dir(&app, &dead_letter_dir(&app)?, "dead-letter-progress").await
+}
@@ -1,8 +1,6 @@
 #[cfg(target_os = "windows")]
 use std::ptr;
 use std::process::Command;
-#[cfg(target_os = "windows")]
-use env_logger;
 
 #[cfg(target_os = "windows")]
 #[link(name = "kernel32")]
@@ -28,9 +26,8 @@ pub fn show_console() -> Result<String, String> {
             if AllocConsole() == 0 {
                 return Err("Failed to allocate console".to_string());
             }
-            // Reinitialize stdout, stdin, stderr for the new console
-            std::env::set_var("RUST_LOG", "info");
-            env_logger::init();
+            // Log output goes to stdout per line, so it shows up in the new console as is
+            crate::logging::init();
         } else {
             // Show existing console window
             ShowWindow(console_window, SW_SHOW);
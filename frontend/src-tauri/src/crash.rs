@@ -0,0 +1,231 @@
+// Crash reports. A panic anywhere in the app, or a background task that gives up with an
+// error (a transcription worker stopping the recording, transcript sync or the outbox losing
+// data), leaves a JSON report in `crashes/` in the app data directory: the message and where
+// it happened, a backtrace, the app version and platform, and the last lines of the log.
+// After an abnormal exit the UI reads the newest report with `get_last_crash_report` and
+// asks the user whether to send it.
+//
+// Transcript text is redacted in the logs to begin with. Lines written while transcript
+// logging was switched on carry `logging::TRANSCRIPT_MARKER` and are left out of reports.
+//
+// Nothing is sent without that consent. Reports go to the endpoint set at build time through
+// `MEETILY_CRASH_REPORT_URL`; builds without one keep them on disk only.
+use std::backtrace::Backtrace;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::atomic_file;
+use crate::http;
+use crate::logging;
+use crate::telemetry::Dependency;
+
+const LOG_LINES: usize = 200;
+// Older reports are removed once there are more than this
+const MAX_REPORTS: usize = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashKind {
+    Panic,
+    /// A background task that stopped with an error instead of panicking
+    TaskError,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportStatus {
+    /// Not shown to the user yet
+    New,
+    Dismissed,
+    Uploaded,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrashReport {
+    pub id: String,
+    pub kind: CrashKind,
+    pub occurred_at: DateTime<Utc>,
+    pub message: String,
+    /// Source location of a panic, or the name of the task that failed
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub backtrace: String,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub log_tail: Vec<String>,
+    pub status: ReportStatus,
+}
+
+fn crash_dir() -> Option<PathBuf> {
+    logging::data_dir().map(|dir| dir.join("crashes"))
+}
+
+fn upload_url() -> Option<&'static str> {
+    option_env!("MEETILY_CRASH_REPORT_URL").map(str::trim).filter(|url| !url.is_empty())
+}
+
+fn report_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", id))
+}
+
+fn new_report(kind: CrashKind, message: String, location: Option<String>) -> CrashReport {
+    let occurred_at = Utc::now();
+    let id = format!("crash-{}-{}", occurred_at.format("%Y%m%d-%H%M%S"), &uuid::Uuid::new_v4().simple().to_string()[..8]);
+    CrashReport {
+        id,
+        kind,
+        occurred_at,
+        message,
+        location,
+        thread: std::thread::current().name().map(str::to_string),
+        backtrace: Backtrace::force_capture().to_string(),
+        // Panics can happen before there is an AppHandle to ask
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        log_tail: logging::recent_lines(LOG_LINES)
+            .into_iter()
+            .map(|line| match line.contains(logging::TRANSCRIPT_MARKER) {
+                true => "[transcript line removed]".to_string(),
+                false => line,
+            })
+            .collect(),
+        status: ReportStatus::New,
+    }
+}
+
+fn save(report: &CrashReport) -> Result<PathBuf, String> {
+    let dir = crash_dir().ok_or("No app data folder on this system")?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = report_path(&dir, &report.id);
+    let json = serde_json::to_vec_pretty(report).map_err(|e| e.to_string())?;
+    atomic_file::write(&path, json).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(path)
+}
+
+fn load(path: &Path) -> Option<CrashReport> {
+    let data = atomic_file::read_verified(path)
+        .map_err(|e| log_warn!("Skipping crash report {}: {}", path.display(), e))
+        .ok()?;
+    serde_json::from_slice(&data)
+        .map_err(|e| log_warn!("Skipping unreadable crash report {}: {}", path.display(), e))
+        .ok()
+}
+
+// Report files, oldest first; the ids start with the time so names sort chronologically
+fn report_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+fn prune(dir: &Path) {
+    let files = report_files(dir);
+    for path in files.iter().take(files.len().saturating_sub(MAX_REPORTS)) {
+        if let Err(e) = atomic_file::remove(path) {
+            log_warn!("Failed to remove old crash report {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn record(report: CrashReport) {
+    match save(&report) {
+        Ok(path) => {
+            log_info!("Crash report written to {}", path.display());
+            if let Some(dir) = path.parent() {
+                prune(dir);
+            }
+        }
+        // Nothing more can be done; the report still went to stderr and the log
+        Err(e) => eprintln!("Failed to write crash report: {}", e),
+    }
+}
+
+/// Write a report for every panic, then hand over to the default hook so the message still
+/// reaches stderr. Call once at startup, after `logging::init`.
+pub fn install_panic_hook() {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "Box<dyn Any>".to_string());
+        let location = info.location().map(|location| location.to_string());
+        log_error!("Panic at {}: {}", location.as_deref().unwrap_or("unknown location"), message);
+        record(new_report(CrashKind::Panic, message, location));
+        previous(info);
+    }));
+}
+
+/// Report a background task that stopped because of `error`. Panics in tasks are caught by
+/// the panic hook; this is for tasks that give up and return an error nobody awaits.
+pub fn capture_task_error(task: &str, error: &dyn fmt::Display) {
+    log_error!("{} task failed: {}", task, error);
+    record(new_report(CrashKind::TaskError, error.to_string(), Some(task.to_string())));
+}
+
+fn find(id: &str) -> Result<(PathBuf, CrashReport), String> {
+    let dir = crash_dir().ok_or("No app data folder on this system")?;
+    // Ids come from the UI; keep them from pointing outside the crash folder
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid crash report id '{}'", id));
+    }
+    let path = report_path(&dir, id);
+    let report = load(&path).ok_or_else(|| format!("Crash report {} not found", id))?;
+    Ok((path, report))
+}
+
+fn set_status(path: &Path, mut report: CrashReport, status: ReportStatus) -> Result<(), String> {
+    report.status = status;
+    let json = serde_json::to_vec_pretty(&report).map_err(|e| e.to_string())?;
+    atomic_file::write(path, json).map_err(|e| format!("Failed to update crash report {}: {}", report.id, e))
+}
+
+/// The newest crash report the user hasn't seen yet, if any.
+#[tauri::command]
+pub fn get_last_crash_report() -> Result<Option<CrashReport>, String> {
+    let Some(dir) = crash_dir() else {
+        return Ok(None);
+    };
+    Ok(report_files(&dir)
+        .iter()
+        .rev()
+        .filter_map(|path| load(path))
+        .find(|report| report.status == ReportStatus::New))
+}
+
+/// Mark a report as seen without sending it.
+#[tauri::command]
+pub fn dismiss_crash_report(id: String) -> Result<(), String> {
+    let (path, report) = find(&id)?;
+    set_status(&path, report, ReportStatus::Dismissed)
+}
+
+/// Send a report the user agreed to share.
+#[tauri::command]
+pub async fn upload_crash_report<R: Runtime>(app: AppHandle<R>, id: String) -> Result<(), String> {
+    let url = upload_url().ok_or("This build has no crash report endpoint; the report is kept on disk")?;
+    let (path, report) = find(&id)?;
+    // Reports carry their id, so a repeated upload can be recognized by the receiver
+    let options = http::policy(&app).options(Dependency::Integration, true);
+    http::send(&options, "crash report", || http::client().post(url).json(&report))
+        .await
+        .map_err(|e| e.to_string())?;
+    log_info!("Uploaded crash report {}", id);
+    set_status(&path, report, ReportStatus::Uploaded)
+}
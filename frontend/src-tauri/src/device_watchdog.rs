@@ -0,0 +1,118 @@
+// Keeps a recording going when an audio device drops out, as Bluetooth headsets tend to.
+// While recording, both capture streams are checked every second. When one has lost its
+// device, the same device is retried for a while in case it comes straight back, and after
+// that the system's current default device is tried as well. The replacement feeds the
+// existing stream, so the recording and transcription carry on from the same point.
+//
+// Events: `device-disconnected` when a device goes away, `device-reconnected` when the same
+// device is back, and `device-fallback` when the default device took its place.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::audio::{default_input_device, default_output_device, AudioDevice, AudioStream};
+use crate::CaptureSource;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+// Checks spent retrying only the lost device before the default device is tried too
+const SAME_DEVICE_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceEvent {
+    pub source: String,
+    /// The device that was lost
+    pub device: String,
+    /// The device now capturing, for reconnect and fallback events
+    pub replacement: Option<String>,
+}
+
+struct Watched {
+    source: CaptureSource,
+    stream: Arc<AudioStream>,
+    // Failed reconnect rounds since the device went away; None while connected
+    attempts: Option<u32>,
+}
+
+fn emit<R: Runtime>(app: &AppHandle<R>, event: &str, payload: &DeviceEvent) {
+    if let Err(e) = app.emit(event, payload) {
+        log_error!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+fn default_device(source: CaptureSource) -> Option<AudioDevice> {
+    let device = match source {
+        CaptureSource::Mic => default_input_device(),
+        CaptureSource::System => default_output_device(),
+    };
+    device.map_err(|e| log_warn!("No default {} device: {}", source.key(), e)).ok()
+}
+
+// One reconnect round; returns the event to report and the device capturing now
+async fn try_reconnect(watched: &Watched, attempt: u32, is_running: &Arc<AtomicBool>) -> Option<(&'static str, String)> {
+    let lost = watched.stream.device.clone();
+    match watched.stream.reconnect(lost.clone(), is_running.clone()).await {
+        Ok(()) => return Some(("device-reconnected", lost.to_string())),
+        Err(e) => log_warn!("Reconnecting {} failed (attempt {}): {}", lost, attempt, e),
+    }
+    if attempt < SAME_DEVICE_ATTEMPTS {
+        return None;
+    }
+    let fallback = default_device(watched.source).filter(|device| device.name != lost.name)?;
+    match watched.stream.reconnect(Arc::new(fallback.clone()), is_running.clone()).await {
+        Ok(()) => Some(("device-fallback", fallback.to_string())),
+        Err(e) => {
+            log_warn!("Falling back to {} failed: {}", fallback, e);
+            None
+        }
+    }
+}
+
+/// Watch a recording's streams until `is_running` is cleared.
+pub(crate) fn spawn<R: Runtime>(
+    app: AppHandle<R>,
+    mic_stream: Arc<AudioStream>,
+    system_stream: Arc<AudioStream>,
+    is_running: Arc<AtomicBool>,
+) {
+    tauri::async_runtime::spawn(async move {
+        let mut watched = [
+            Watched { source: CaptureSource::Mic, stream: mic_stream, attempts: None },
+            Watched { source: CaptureSource::System, stream: system_stream, attempts: None },
+        ];
+        while is_running.load(Ordering::SeqCst) {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            for watched in watched.iter_mut() {
+                if !is_running.load(Ordering::SeqCst) {
+                    break;
+                }
+                if !watched.stream.is_disconnected().await {
+                    watched.attempts = None;
+                    continue;
+                }
+                let lost = watched.stream.device.to_string();
+                if watched.attempts.is_none() {
+                    log_warn!("{} device {} disconnected; trying to reconnect", watched.source.key(), lost);
+                    emit(&app, "device-disconnected", &DeviceEvent {
+                        source: watched.source.key().to_string(),
+                        device: lost.clone(),
+                        replacement: None,
+                    });
+                }
+                let attempt = watched.attempts.unwrap_or(0) + 1;
+                watched.attempts = Some(attempt);
+                if let Some((event, replacement)) = try_reconnect(watched, attempt, &is_running).await {
+                    log_info!("{} audio restored after {} attempt(s) via {}", watched.source.key(), attempt, event);
+                    emit(&app, event, &DeviceEvent {
+                        source: watched.source.key().to_string(),
+                        device: lost,
+                        replacement: Some(replacement),
+                    });
+                    watched.attempts = None;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,43 @@
+use std::sync::atomic::Ordering;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use log::info as log_info;
+
+use crate::permissions::{self, PermissionStatus};
+use crate::telemetry::{self, DependencyHealth};
+use crate::{TranscriptionStatus, DROPPED_CHUNK_COUNTER, RECORDING_FLAG, UPLOAD_PART_SIZE_BYTES};
+
+#[derive(Debug, Serialize)]
+pub struct DiagnosticsReport {
+    generated_at: DateTime<Utc>,
+    app_version: String,
+    os: String,
+    arch: String,
+    is_recording: bool,
+    transcription: TranscriptionStatus,
+    dropped_chunks: u64,
+    upload_part_size_bytes: usize,
+    transcript_server_url: String,
+    dependencies: Vec<DependencyHealth>,
+    permissions: PermissionStatus,
+}
+
+/// Collects a point-in-time report that users can attach to bug reports.
+#[tauri::command]
+pub fn get_diagnostics<R: Runtime>(app: AppHandle<R>) -> DiagnosticsReport {
+    log_info!("get_diagnostics called");
+    DiagnosticsReport {
+        generated_at: Utc::now(),
+        app_version: app.package_info().version.to_string(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        is_recording: RECORDING_FLAG.load(Ordering::SeqCst),
+        transcription: crate::get_transcription_status(app.state()),
+        dropped_chunks: DROPPED_CHUNK_COUNTER.load(Ordering::SeqCst),
+        upload_part_size_bytes: UPLOAD_PART_SIZE_BYTES.load(Ordering::SeqCst),
+        transcript_server_url: crate::transcript_server_url(&app),
+        dependencies: telemetry::snapshot(),
+        permissions: permissions::permission_status(),
+    }
+}
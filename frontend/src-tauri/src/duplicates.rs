@@ -0,0 +1,592 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use base64::Engine;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api::{make_api_request, DeleteMeetingRequest, TranscriptSegment};
+use crate::atomic_file;
+use crate::audio::audio_processing::resample;
+use crate::audio::decode_audio_file;
+use crate::audio::fingerprint::{
+    self, Fingerprinter, FINGERPRINT_SAMPLE_RATE, MATCH_BIT_ERROR_RATE,
+};
+use crate::transcript_sync;
+
+// Duplicate detection configuration
+const RECENT_RECORDINGS_KEY: &str = "recentRecordings";
+const RECENT_WINDOW_HOURS: i64 = 24; // Only compare against meetings from the last day
+const MAX_RECENT_RECORDINGS: usize = 20;
+const OVERLAP_THRESHOLD: f64 = 0.5; // Fraction of the shorter recording that overlaps in time
+const SIMILARITY_THRESHOLD: f32 = 0.85; // Envelope correlation considered "same audio"
+const MIN_ENVELOPE_SECONDS: usize = 30; // Too little audio to compare reliably below this
+const MAX_LAG_SECONDS: i64 = 120; // Clock skew tolerated between two devices
+const COMPARE_EXCERPT_SECONDS: f64 = 60.0; // Fingerprint excerpt used to compare two recordings
+const FINGERPRINT_DIR: &str = "fingerprints";
+
+/// Coarse description of a recording: its wall-clock range, a per-second
+/// loudness envelope that two devices capturing the same room will share, and the
+/// acoustic fingerprint (stored separately on disk because of its size).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordingSignature {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub envelope: Vec<f32>,
+    #[serde(skip)]
+    pub fingerprint: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecentRecording {
+    meeting_id: String,
+    title: String,
+    #[serde(flatten)]
+    signature: RecordingSignature,
+}
+
+#[derive(Debug, Serialize)]
+struct SaveRecordingSignatureRequest<'a> {
+    meeting_id: &'a str,
+    title: &'a str,
+    device_id: String,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    envelope: &'a [f32],
+    fingerprint: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RecentSignaturesRequest {
+    since: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RecentSignaturesResponse {
+    signatures: Vec<StoredSignature>,
+}
+
+// A signature saved on the backend by any of the account's devices
+#[derive(Debug, Deserialize)]
+struct StoredSignature {
+    meeting_id: String,
+    title: String,
+    started_at: DateTime<Utc>,
+    ended_at: DateTime<Utc>,
+    envelope: Vec<f32>,
+    fingerprint: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateCandidate {
+    pub meeting_id: String,
+    pub title: String,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub overlap_ratio: f64,
+    pub similarity: f32,
+    pub fingerprint_bit_error_rate: Option<f32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClipMatch {
+    pub meeting_id: String,
+    pub offset_seconds: f64,
+    pub bit_error_rate: f32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DuplicateResolution {
+    Merge,
+    Discard,
+    Keep,
+}
+
+struct SignatureBuilder {
+    started_at: DateTime<Utc>,
+    sum_squares: f64,
+    count: usize,
+    envelope: Vec<f32>,
+    fingerprinter: Fingerprinter,
+}
+
+static CURRENT_RECORDING: Lazy<Mutex<Option<SignatureBuilder>>> = Lazy::new(|| Mutex::new(None));
+static LAST_RECORDING: Lazy<Mutex<Option<RecordingSignature>>> = Lazy::new(|| Mutex::new(None));
+
+/// Called when a recording starts; resets the envelope for the new session.
+pub fn begin_recording() {
+    if let Ok(mut guard) = CURRENT_RECORDING.lock() {
+        *guard = Some(SignatureBuilder {
+            started_at: Utc::now(),
+            sum_squares: 0.0,
+            count: 0,
+            envelope: Vec::new(),
+            fingerprinter: Fingerprinter::new(),
+        });
+    }
+}
+
+/// Feed captured samples into the envelope (one RMS value per second of audio).
+pub fn observe_samples(samples: &[f32], sample_rate: u32) {
+    if samples.is_empty() || sample_rate == 0 {
+        return;
+    }
+    if let Ok(mut guard) = CURRENT_RECORDING.lock() {
+        if let Some(builder) = guard.as_mut() {
+            for &sample in samples {
+                builder.sum_squares += (sample as f64) * (sample as f64);
+                builder.count += 1;
+                if builder.count >= sample_rate as usize {
+                    builder.envelope.push((builder.sum_squares / builder.count as f64).sqrt() as f32);
+                    builder.sum_squares = 0.0;
+                    builder.count = 0;
+                }
+            }
+        }
+    }
+}
+
+/// Feed the 16kHz mono audio sent for transcription into the fingerprint.
+pub fn observe_whisper_samples(samples: &[f32]) {
+    if let Ok(mut guard) = CURRENT_RECORDING.lock() {
+        if let Some(builder) = guard.as_mut() {
+            builder.fingerprinter.push_samples(samples);
+        }
+    }
+}
+
+/// Called when a recording stops; keeps the signature until it is registered or discarded.
+pub fn finish_recording() {
+    let builder = CURRENT_RECORDING.lock().ok().and_then(|mut guard| guard.take());
+    if let Some(builder) = builder {
+        let signature = RecordingSignature {
+            started_at: builder.started_at,
+            ended_at: Utc::now(),
+            envelope: builder.envelope,
+            fingerprint: builder.fingerprinter.finish(),
+        };
+        log_info!("Recording signature captured: {} seconds of envelope, {} fingerprint frames",
+                 signature.envelope.len(), signature.fingerprint.len());
+        if let Ok(mut guard) = LAST_RECORDING.lock() {
+            *guard = Some(signature);
+        }
+    }
+}
+
+fn overlap_ratio(a: &RecordingSignature, b: &RecordingSignature) -> f64 {
+    let start = a.started_at.max(b.started_at);
+    let end = a.ended_at.min(b.ended_at);
+    if end <= start {
+        return 0.0;
+    }
+    let overlap = (end - start).num_milliseconds() as f64;
+    let shorter = (a.ended_at - a.started_at)
+        .num_milliseconds()
+        .min((b.ended_at - b.started_at).num_milliseconds())
+        .max(1) as f64;
+    (overlap / shorter).min(1.0)
+}
+
+// Pearson correlation of two envelopes at a given lag (b shifted by `lag` seconds)
+fn correlation_at_lag(a: &[f32], b: &[f32], lag: i64) -> Option<f32> {
+    let pairs: Vec<(f32, f32)> = a
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &x)| {
+            let j = i as i64 + lag;
+            if j >= 0 && (j as usize) < b.len() {
+                Some((x, b[j as usize]))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    if pairs.len() < MIN_ENVELOPE_SECONDS {
+        return None;
+    }
+
+    let n = pairs.len() as f32;
+    let mean_a = pairs.iter().map(|p| p.0).sum::<f32>() / n;
+    let mean_b = pairs.iter().map(|p| p.1).sum::<f32>() / n;
+    let mut cov = 0.0f32;
+    let mut var_a = 0.0f32;
+    let mut var_b = 0.0f32;
+    for (x, y) in &pairs {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    if var_a == 0.0 || var_b == 0.0 {
+        return None;
+    }
+    Some(cov / (var_a.sqrt() * var_b.sqrt()))
+}
+
+fn envelope_similarity(a: &RecordingSignature, b: &RecordingSignature) -> f32 {
+    // Expected offset of b relative to a, searched around for clock skew between devices
+    let expected = (a.started_at - b.started_at).num_seconds();
+    let mut best = 0.0f32;
+    for lag in (expected - MAX_LAG_SECONDS)..=(expected + MAX_LAG_SECONDS) {
+        if let Some(corr) = correlation_at_lag(&a.envelope, &b.envelope, lag) {
+            best = best.max(corr);
+        }
+    }
+    best
+}
+
+fn fingerprint_dir<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?
+        .join(FINGERPRINT_DIR);
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create fingerprint directory: {}", e))?;
+    Ok(dir)
+}
+
+// Meeting ids come from the UI and name files or URL paths; keep them from pointing
+// anywhere else
+pub(crate) fn check_meeting_id(meeting_id: &str) -> Result<(), String> {
+    if meeting_id.is_empty() || !meeting_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid meeting id '{}'", meeting_id));
+    }
+    Ok(())
+}
+
+fn fingerprint_path<R: Runtime>(app: &AppHandle<R>, meeting_id: &str) -> Result<PathBuf, String> {
+    check_meeting_id(meeting_id)?;
+    Ok(fingerprint_dir(app)?.join(format!("{}.fp", meeting_id)))
+}
+
+fn save_fingerprint<R: Runtime>(app: &AppHandle<R>, meeting_id: &str, hashes: &[u32]) -> Result<(), String> {
+    let path = fingerprint_path(app, meeting_id)?;
+    atomic_file::write(&path, fingerprint::to_bytes(hashes)).map_err(|e| format!("Failed to write fingerprint: {}", e))
+}
+
+fn load_fingerprint<R: Runtime>(app: &AppHandle<R>, meeting_id: &str) -> Option<Vec<u32>> {
+    let path = fingerprint_path(app, meeting_id).ok()?;
+    atomic_file::read_verified(path).ok().map(|bytes| fingerprint::from_bytes(&bytes))
+}
+
+fn load_recent_recordings<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<RecentRecording>, String> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    let recordings = match store.get(RECENT_RECORDINGS_KEY) {
+        Some(value) => serde_json::from_value::<Vec<RecentRecording>>(value).unwrap_or_else(|e| {
+            log_warn!("Ignoring malformed recent recordings: {}", e);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
+
+    let cutoff = Utc::now() - ChronoDuration::hours(RECENT_WINDOW_HOURS);
+    Ok(recordings.into_iter().filter(|r| r.signature.ended_at >= cutoff).collect())
+}
+
+// Share the signature with the other devices of the account through the backend
+async fn upload_signature<R: Runtime>(
+    app: &AppHandle<R>,
+    meeting_id: &str,
+    title: &str,
+    signature: &RecordingSignature,
+    auth_token: Option<String>,
+) -> Result<(), String> {
+    let request = SaveRecordingSignatureRequest {
+        meeting_id,
+        title,
+        device_id: transcript_sync::device_id(app),
+        started_at: signature.started_at,
+        ended_at: signature.ended_at,
+        envelope: &signature.envelope,
+        fingerprint: (!signature.fingerprint.is_empty())
+            .then(|| base64::engine::general_purpose::STANDARD.encode(fingerprint::to_bytes(&signature.fingerprint))),
+    };
+    let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    make_api_request::<R, serde_json::Value>(app, "/save-recording-signature", "POST", Some(&body), None, auth_token)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+async fn fetch_remote_recordings<R: Runtime>(app: &AppHandle<R>, auth_token: Option<String>) -> Result<Vec<RecentRecording>, String> {
+    let request = RecentSignaturesRequest { since: Utc::now() - ChronoDuration::hours(RECENT_WINDOW_HOURS) };
+    let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    let response = make_api_request::<R, RecentSignaturesResponse>(app, "/recent-recording-signatures", "POST", Some(&body), None, auth_token)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(response
+        .signatures
+        .into_iter()
+        .map(|stored| RecentRecording {
+            meeting_id: stored.meeting_id,
+            title: stored.title,
+            signature: RecordingSignature {
+                started_at: stored.started_at,
+                ended_at: stored.ended_at,
+                envelope: stored.envelope,
+                fingerprint: stored
+                    .fingerprint
+                    .and_then(|encoded| base64::engine::general_purpose::STANDARD.decode(encoded).ok())
+                    .map(|bytes| fingerprint::from_bytes(&bytes))
+                    .unwrap_or_default(),
+            },
+        })
+        .collect())
+}
+
+// Recordings to compare against: this device's own, plus those of every device of the
+// account when the backend is reachable. The local copy wins when both know a meeting.
+async fn comparable_recordings<R: Runtime>(app: &AppHandle<R>, auth_token: Option<String>) -> Result<Vec<RecentRecording>, String> {
+    let mut recordings = load_recent_recordings(app)?;
+    for recording in recordings.iter_mut() {
+        recording.signature.fingerprint = load_fingerprint(app, &recording.meeting_id).unwrap_or_default();
+    }
+    match fetch_remote_recordings(app, auth_token).await {
+        Ok(remote) => {
+            for recording in remote {
+                if !recordings.iter().any(|local| local.meeting_id == recording.meeting_id) {
+                    recordings.push(recording);
+                }
+            }
+        }
+        Err(e) => log_warn!("Comparing with this device's recordings only; backend signatures unavailable: {}", e),
+    }
+    Ok(recordings)
+}
+
+// Drop a meeting from the local store, along with its fingerprint
+fn forget_recording<R: Runtime>(app: &AppHandle<R>, meeting_id: &str) -> Result<(), String> {
+    let mut recordings = load_recent_recordings(app)?;
+    recordings.retain(|r| r.meeting_id != meeting_id);
+    save_recent_recordings(app, recordings)?;
+    let path = fingerprint_path(app, meeting_id)?;
+    if path.exists() {
+        atomic_file::remove(&path).map_err(|e| format!("Failed to remove fingerprint: {}", e))?;
+    }
+    Ok(())
+}
+
+async fn delete_meeting<R: Runtime>(app: &AppHandle<R>, meeting_id: &str, auth_token: Option<String>) -> Result<(), String> {
+    let body = serde_json::to_string(&DeleteMeetingRequest { meeting_id: meeting_id.to_string() }).map_err(|e| e.to_string())?;
+    make_api_request::<R, serde_json::Value>(app, "/delete-meeting", "POST", Some(&body), None, auth_token)
+        .await
+        .map_err(|e| format!("Failed to delete meeting {}: {}", meeting_id, e))?;
+    forget_recording(app, meeting_id)
+}
+
+fn save_recent_recordings<R: Runtime>(app: &AppHandle<R>, mut recordings: Vec<RecentRecording>) -> Result<(), String> {
+    recordings.sort_by(|a, b| b.signature.ended_at.cmp(&a.signature.ended_at));
+    recordings.truncate(MAX_RECENT_RECORDINGS);
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    let value = serde_json::to_value(&recordings).map_err(|e| e.to_string())?;
+    store.set(RECENT_RECORDINGS_KEY, value);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
+/// Compare the recording that just stopped with recent meetings, including those recorded
+/// on the account's other devices. Emits `duplicate-recording-detected` when at least one
+/// candidate looks like the same meeting.
+#[tauri::command]
+pub async fn check_duplicate_recording<R: Runtime>(app: AppHandle<R>, auth_token: Option<String>) -> Result<Vec<DuplicateCandidate>, String> {
+    let current = LAST_RECORDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| "No finished recording to check".to_string())?;
+
+    let mut candidates: Vec<DuplicateCandidate> = comparable_recordings(&app, auth_token)
+        .await?
+        .into_iter()
+        .filter_map(|recent| {
+            let overlap = overlap_ratio(&current, &recent.signature);
+            let similarity = envelope_similarity(&current, &recent.signature);
+            let stored = &recent.signature.fingerprint;
+            let bit_error_rate = (!stored.is_empty())
+                .then(|| {
+                    fingerprint::compare_recordings(
+                        &current.fingerprint,
+                        stored,
+                        fingerprint::seconds_to_frames(COMPARE_EXCERPT_SECONDS),
+                    )
+                    .map(|m| m.bit_error_rate)
+                })
+                .flatten();
+            let fingerprint_match = bit_error_rate.map(|ber| ber < MATCH_BIT_ERROR_RATE).unwrap_or(false);
+
+            if overlap >= OVERLAP_THRESHOLD || similarity >= SIMILARITY_THRESHOLD || fingerprint_match {
+                Some(DuplicateCandidate {
+                    meeting_id: recent.meeting_id,
+                    title: recent.title,
+                    started_at: recent.signature.started_at,
+                    ended_at: recent.signature.ended_at,
+                    overlap_ratio: overlap,
+                    similarity,
+                    fingerprint_bit_error_rate: bit_error_rate,
+                })
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap_or(std::cmp::Ordering::Equal));
+
+    if !candidates.is_empty() {
+        log_info!("Found {} possible duplicate recordings", candidates.len());
+        if let Err(e) = app.emit("duplicate-recording-detected", &candidates) {
+            log_error!("Failed to emit duplicate-recording-detected event: {}", e);
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Associate the finished recording with the meeting it was saved as, so later
+/// recordings on this and the account's other devices can be compared against it.
+#[tauri::command]
+pub async fn register_recording<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    title: String,
+    auth_token: Option<String>,
+) -> Result<(), String> {
+    check_meeting_id(&meeting_id)?;
+    let signature = LAST_RECORDING
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or_else(|| "No finished recording to register".to_string())?;
+
+    if !signature.fingerprint.is_empty() {
+        save_fingerprint(&app, &meeting_id, &signature.fingerprint)?;
+    }
+    if let Err(e) = upload_signature(&app, &meeting_id, &title, &signature, auth_token).await {
+        log_warn!("Recording signature of {} not shared with other devices: {}", meeting_id, e);
+    }
+
+    let mut recordings = load_recent_recordings(&app)?;
+    recordings.retain(|r| r.meeting_id != meeting_id);
+    recordings.push(RecentRecording { meeting_id, title, signature });
+    save_recent_recordings(&app, recordings)
+}
+
+/// Find stored meetings whose audio contains the given clip. Results are sorted
+/// best match first and include where in the meeting the clip starts.
+#[tauri::command]
+pub async fn find_meeting_by_clip<R: Runtime>(app: AppHandle<R>, clip_path: String) -> Result<Vec<ClipMatch>, String> {
+    log_info!("find_meeting_by_clip called for: {}", clip_path);
+
+    let (samples, sample_rate) = decode_audio_file(std::path::Path::new(&clip_path))
+        .map_err(|e| format!("Failed to decode clip: {}", e))?;
+    let samples = if sample_rate != FINGERPRINT_SAMPLE_RATE {
+        resample(&samples, sample_rate, FINGERPRINT_SAMPLE_RATE).map_err(|e| format!("Failed to resample clip: {}", e))?
+    } else {
+        samples
+    };
+
+    let needle = fingerprint::fingerprint(&samples);
+    if needle.is_empty() {
+        return Err("Clip is too short to fingerprint".to_string());
+    }
+
+    let dir = fingerprint_dir(&app)?;
+    let entries = std::fs::read_dir(&dir).map_err(|e| format!("Failed to read fingerprint directory: {}", e))?;
+
+    let mut matches = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("fp") {
+            continue;
+        }
+        let meeting_id = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(stem) => stem.to_string(),
+            None => continue,
+        };
+        let stored = match atomic_file::read_verified(&path) {
+            Ok(bytes) => fingerprint::from_bytes(&bytes),
+            Err(e) => {
+                log_warn!("Failed to read fingerprint {:?}: {}", path, e);
+                continue;
+            }
+        };
+
+        if let Some(found) = fingerprint::find_best_match(&stored, &needle) {
+            if found.bit_error_rate < MATCH_BIT_ERROR_RATE {
+                matches.push(ClipMatch {
+                    meeting_id,
+                    offset_seconds: fingerprint::frames_to_seconds(found.offset_frames),
+                    bit_error_rate: found.bit_error_rate,
+                });
+            }
+        }
+    }
+
+    matches.sort_by(|a, b| a.bit_error_rate.partial_cmp(&b.bit_error_rate).unwrap_or(std::cmp::Ordering::Equal));
+    log_info!("Clip matched {} meetings", matches.len());
+    Ok(matches)
+}
+
+/// Apply the user's choice for a suspected duplicate. `merge` appends the new
+/// transcript to the existing meeting, `discard` deletes the new recording's meeting and
+/// transcript, `keep` leaves it as a separate meeting. `meeting_id` is the meeting the new
+/// recording was saved as, if it was saved already (e.g. by transcript sync); it is
+/// deleted after a merge too, so the transcript is not kept twice.
+#[tauri::command]
+pub async fn resolve_duplicate_recording<R: Runtime>(
+    app: AppHandle<R>,
+    resolution: DuplicateResolution,
+    meeting_id: Option<String>,
+    target_meeting_id: Option<String>,
+    transcripts: Option<Vec<serde_json::Value>>,
+    auth_token: Option<String>,
+) -> Result<(), String> {
+    log_info!(
+        "resolve_duplicate_recording called with {:?}, meeting: {:?}, target: {:?}",
+        resolution, meeting_id, target_meeting_id
+    );
+
+    match resolution {
+        DuplicateResolution::Keep => Ok(()),
+        DuplicateResolution::Discard => {
+            let meeting_id = meeting_id.ok_or_else(|| "The meeting to discard is required".to_string())?;
+            delete_meeting(&app, &meeting_id, auth_token).await?;
+            LAST_RECORDING.lock().map_err(|e| e.to_string())?.take();
+            log_info!("Discarded duplicate meeting {}", meeting_id);
+            Ok(())
+        }
+        DuplicateResolution::Merge => {
+            let target = target_meeting_id.ok_or_else(|| "A target meeting is required to merge".to_string())?;
+            let segments: Vec<TranscriptSegment> = transcripts
+                .unwrap_or_default()
+                .into_iter()
+                .map(serde_json::from_value)
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())?;
+
+            if !segments.is_empty() {
+                let body = serde_json::json!({ "meeting_id": target, "transcripts": segments }).to_string();
+                make_api_request::<R, serde_json::Value>(&app, "/append-transcript", "POST", Some(&body), None, auth_token.clone()).await?;
+            }
+            if let Some(duplicate) = meeting_id.filter(|duplicate| *duplicate != target) {
+                delete_meeting(&app, &duplicate, auth_token).await?;
+            }
+
+            // Widen the existing meeting's time range so future checks see the merged span
+            let merged = LAST_RECORDING.lock().map_err(|e| e.to_string())?.take();
+            if let Some(signature) = merged {
+                let mut recordings = load_recent_recordings(&app)?;
+                if let Some(existing) = recordings.iter_mut().find(|r| r.meeting_id == target) {
+                    existing.signature.started_at = existing.signature.started_at.min(signature.started_at);
+                    existing.signature.ended_at = existing.signature.ended_at.max(signature.ended_at);
+                }
+                save_recent_recordings(&app, recordings)?;
+            }
+            Ok(())
+        }
+    }
+}
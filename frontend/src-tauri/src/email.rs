@@ -0,0 +1,273 @@
+// Emailing meeting summaries straight from the app over SMTP, so teams without the hosted
+// backend can still send minutes around. The server settings live in store.json and the
+// SMTP password in the OS keychain. Mails carry an HTML part with the summary, decisions
+// and action items, and a plain-text part for clients that don't render HTML.
+use std::time::Duration;
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{Address, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error};
+
+use crate::export::html::escape_html;
+use crate::export::markdown::render_markdown;
+use crate::export::{self, BlockKind, ExportDocument};
+use crate::keychain;
+
+const EMAIL_STORE_KEY: &str = "smtp";
+const PASSWORD_ACCOUNT: &str = "smtp-password";
+const SMTP_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_RECIPIENTS: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    /// TLS from the first byte, usually port 465
+    Tls,
+    /// Plain connection upgraded with STARTTLS, usually port 587
+    StartTls,
+    /// No encryption; only for relays on a trusted network
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    pub host: String,
+    pub port: u16,
+    pub security: SmtpSecurity,
+    /// Leave empty for relays that don't require a login
+    #[serde(default)]
+    pub username: String,
+    pub from_address: String,
+    #[serde(default)]
+    pub from_name: Option<String>,
+}
+
+impl EmailConfig {
+    fn sender(&self) -> Result<Mailbox, String> {
+        let address: Address = self
+            .from_address
+            .trim()
+            .parse()
+            .map_err(|e| format!("Invalid sender address '{}': {}", self.from_address, e))?;
+        let name = self.from_name.as_deref().map(str::trim).filter(|name| !name.is_empty()).map(str::to_string);
+        Ok(Mailbox::new(name, address))
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.host.trim().is_empty() {
+            return Err("Enter the SMTP server's host name".to_string());
+        }
+        if self.port == 0 {
+            return Err("Enter the SMTP server's port".to_string());
+        }
+        self.sender().map(|_| ())
+    }
+}
+
+/// The SMTP settings plus whether a password is saved; the password itself is never returned.
+#[derive(Debug, Clone, Serialize)]
+pub struct EmailStatus {
+    pub config: Option<EmailConfig>,
+    pub has_password: bool,
+}
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> Option<EmailConfig> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(EMAIL_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+fn mailer(config: &EmailConfig) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+    let host = config.host.trim();
+    let builder = match config.security {
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host),
+        SmtpSecurity::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)),
+    }
+    .map_err(|e| format!("Failed to set up SMTP connection to {}: {}", host, e))?
+    .port(config.port)
+    .timeout(Some(SMTP_TIMEOUT));
+
+    let builder = match config.username.trim() {
+        "" => builder,
+        username => {
+            let password = keychain::get_secret(PASSWORD_ACCOUNT)?.ok_or("Save the SMTP password first")?;
+            builder.credentials(Credentials::new(username.to_string(), password))
+        }
+    };
+    Ok(builder.build())
+}
+
+fn parse_recipients(recipients: &[String]) -> Result<Vec<Mailbox>, String> {
+    let mut mailboxes: Vec<Mailbox> = Vec::new();
+    for recipient in recipients.iter().map(|r| r.trim()).filter(|r| !r.is_empty()) {
+        let mailbox: Mailbox = recipient.parse().map_err(|e| format!("Invalid recipient '{}': {}", recipient, e))?;
+        if !mailboxes.iter().any(|existing| existing.email == mailbox.email) {
+            mailboxes.push(mailbox);
+        }
+    }
+    if mailboxes.is_empty() {
+        return Err("Add at least one recipient".to_string());
+    }
+    if mailboxes.len() > MAX_RECIPIENTS {
+        return Err(format!("Emails are limited to {} recipients", MAX_RECIPIENTS));
+    }
+    Ok(mailboxes)
+}
+
+// Mail clients ignore <style> blocks, so styling is inline
+fn render_email_html(document: &ExportDocument) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<body style=\"font-family: -apple-system, 'Segoe UI', Roboto, sans-serif; color: #1f2937; max-width: 680px;\">\n",
+    );
+    html.push_str(&format!("<h1 style=\"margin-bottom: 4px;\">{}</h1>\n", escape_html(document.title.trim())));
+    let meta = match document.provenance() {
+        Some(provenance) => format!("{} \u{00b7} {}", document.display_date(), provenance),
+        None => document.display_date(),
+    };
+    html.push_str(&format!("<p style=\"color: #6b7280; margin-top: 0;\">{}</p>\n", escape_html(&meta)));
+
+    for section in &document.sections {
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&section.title)));
+        let mut in_list = false;
+        for block in &section.blocks {
+            let is_bullet = block.kind == BlockKind::Bullet;
+            if is_bullet && !in_list {
+                html.push_str("<ul>\n");
+            } else if !is_bullet && in_list {
+                html.push_str("</ul>\n");
+            }
+            in_list = is_bullet;
+            let content = escape_html(&block.content);
+            match block.kind {
+                BlockKind::Heading1 => html.push_str(&format!("<h3>{}</h3>\n", content)),
+                BlockKind::Heading2 => html.push_str(&format!("<h4>{}</h4>\n", content)),
+                BlockKind::Bullet => html.push_str(&format!("<li>{}</li>\n", content)),
+                BlockKind::Text => html.push_str(&format!("<p>{}</p>\n", content)),
+            }
+        }
+        if in_list {
+            html.push_str("</ul>\n");
+        }
+    }
+
+    if !document.decisions.is_empty() {
+        html.push_str("<h2>Decisions</h2>\n<ul>\n");
+        for decision in &document.decisions {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(decision)));
+        }
+        html.push_str("</ul>\n");
+    }
+    if !document.action_items.is_empty() {
+        html.push_str("<h2>Action items</h2>\n<table style=\"border-collapse: collapse; width: 100%;\">\n");
+        for item in &document.action_items {
+            let owner = item.owner.as_deref().map(escape_html).unwrap_or_default();
+            html.push_str(&format!(
+                "<tr><td style=\"padding: 6px 8px; border-bottom: 1px solid #e5e7eb;\">&#9744; {}</td>\
+                 <td style=\"padding: 6px 8px; border-bottom: 1px solid #e5e7eb; color: #6b7280; white-space: nowrap;\">{}</td></tr>\n",
+                escape_html(&item.text),
+                owner
+            ));
+        }
+        html.push_str("</table>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_email_text(document: &ExportDocument) -> String {
+    // The transcript would make the mail far too long; the summary is what gets sent
+    let summary_only = ExportDocument { transcript: Vec::new(), ..document.clone() };
+    let mut text = render_markdown(&summary_only);
+    if !document.decisions.is_empty() {
+        text.push_str("## Decisions\n\n");
+        for decision in &document.decisions {
+            text.push_str(&format!("- {}\n", decision));
+        }
+        text.push('\n');
+    }
+    if !document.action_items.is_empty() {
+        text.push_str("## Action items\n\n");
+        for item in &document.action_items {
+            match item.owner.as_deref() {
+                Some(owner) => text.push_str(&format!("- [ ] {} ({})\n", item.text, owner)),
+                None => text.push_str(&format!("- [ ] {}\n", item.text)),
+            }
+        }
+    }
+    text
+}
+
+#[tauri::command]
+pub async fn get_email_config<R: Runtime>(app: AppHandle<R>) -> Result<EmailStatus, String> {
+    Ok(EmailStatus { config: load_config(&app), has_password: keychain::get_secret(PASSWORD_ACCOUNT)?.is_some() })
+}
+
+/// Save the SMTP settings. A `password` replaces the stored one; an empty one removes it, and
+/// leaving it out keeps the current one.
+#[tauri::command]
+pub async fn set_email_config<R: Runtime>(app: AppHandle<R>, config: EmailConfig, password: Option<String>) -> Result<(), String> {
+    config.validate()?;
+    match password.as_deref() {
+        Some("") => keychain::delete_secret(PASSWORD_ACCOUNT)?,
+        Some(password) => keychain::set_secret(PASSWORD_ACCOUNT, password)?,
+        None => {}
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(EMAIL_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("SMTP settings saved for {}:{}", config.host, config.port);
+    Ok(())
+}
+
+/// Connect and log in to the configured SMTP server without sending anything.
+#[tauri::command]
+pub async fn test_email_connection<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let config = load_config(&app).ok_or("Email is not set up yet")?;
+    match mailer(&config)?.test_connection().await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("SMTP server {} did not accept the connection", config.host)),
+        Err(e) => Err(format!("Failed to connect to {}: {}", config.host, e)),
+    }
+}
+
+/// Email a meeting's summary and action items to `recipients`. With `local` the meeting is
+/// read from the local store instead of the backend.
+#[tauri::command]
+pub async fn send_meeting_email<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    recipients: Vec<String>,
+    local: Option<bool>,
+    auth_token: Option<String>,
+) -> Result<(), String> {
+    let config = load_config(&app).ok_or("Email is not set up yet")?;
+    let recipients = parse_recipients(&recipients)?;
+    let document = if local.unwrap_or(false) {
+        export::load_local_document(&app, &meeting_id).await?
+    } else {
+        export::load_document(&app, &meeting_id, auth_token).await?
+    };
+    if document.sections.is_empty() && document.action_items.is_empty() {
+        return Err(format!("Meeting {} has no summary to send yet", meeting_id));
+    }
+
+    let mut message = Message::builder().from(config.sender()?).subject(format!("Meeting notes: {}", document.title.trim()));
+    for recipient in &recipients {
+        message = message.to(recipient.clone());
+    }
+    let message = message
+        .multipart(MultiPart::alternative_plain_html(render_email_text(&document), render_email_html(&document)))
+        .map_err(|e| format!("Failed to build email: {}", e))?;
+
+    mailer(&config)?.send(message).await.map_err(|e| {
+        log_error!("Failed to send minutes of {}: {}", meeting_id, e);
+        format!("Failed to send email: {}", e)
+    })?;
+    log_info!("Emailed minutes of {} to {} recipients", meeting_id, recipients.len());
+    Ok(())
+}
@@ -0,0 +1,77 @@
+// Text embeddings from the local Ollama server, for finding passages by meaning rather
+// than by the words they share with a query. Nothing leaves the machine.
+use serde::{Deserialize, Serialize};
+use log::info as log_info;
+
+use crate::ollama;
+use crate::telemetry::{self, Dependency};
+
+/// A small model that is cheap enough to embed a whole meeting on demand.
+pub const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+// Texts sent per request, so a long meeting doesn't become one huge request
+const EMBED_BATCH_SIZE: usize = 64;
+
+#[derive(Debug, Serialize)]
+struct EmbedRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbedResponse {
+    embeddings: Vec<Vec<f32>>,
+}
+
+/// One vector per text, in the same order.
+pub async fn embed(texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let client = ollama::client();
+    let mut vectors = Vec::with_capacity(texts.len());
+    for batch in texts.chunks(EMBED_BATCH_SIZE) {
+        let started = std::time::Instant::now();
+        let result = embed_batch(client, batch).await;
+        telemetry::record(Dependency::Ollama, started.elapsed(), result.as_ref().err().map(|e| e.as_str()));
+        vectors.extend(result?);
+    }
+    log_info!("Embedded {} texts with {}", texts.len(), DEFAULT_EMBEDDING_MODEL);
+    Ok(vectors)
+}
+
+async fn embed_batch(client: &reqwest::Client, batch: &[String]) -> Result<Vec<Vec<f32>>, String> {
+    let request = EmbedRequest { model: DEFAULT_EMBEDDING_MODEL, input: batch };
+    let response = client
+        .post(format!("{}/api/embed", ollama::base_url()))
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach Ollama for embeddings: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Embedding request failed with status {}: {}", status, body));
+    }
+    let response: EmbedResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse embedding response: {}", e))?;
+    if response.embeddings.len() != batch.len() {
+        return Err(format!("Expected {} embeddings, got {}", batch.len(), response.embeddings.len()));
+    }
+    Ok(response.embeddings)
+}
+
+/// Cosine similarity of two vectors, 0 when either is all zeros or their lengths differ.
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f32, 0.0f32, 0.0f32);
+    for (x, y) in a.iter().zip(b) {
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
@@ -0,0 +1,150 @@
+// Errors returned to the frontend. Each one reaches JavaScript as
+// `{ code, message, retryable }` (plus `status` for HTTP errors), so the UI can tell an
+// unreachable backend from a bad input or a missing permission without parsing messages.
+//
+// Plenty of helpers still return `Result<_, String>`; a plain string converts into
+// `AppError::Internal` with `?`, and an `AppError` converts back into its message, so
+// both kinds mix freely while commands move over.
+use std::fmt;
+use reqwest::StatusCode;
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// The backend or another server could not be reached at all
+    Offline(String),
+    /// The server took too long to answer
+    Timeout(String),
+    /// The server answered with an error status not covered by a more specific variant
+    Http { status: u16, message: String },
+    /// The request itself was wrong; repeating it won't help
+    InvalidInput(String),
+    /// Missing credentials, a rejected token or an OS permission such as the microphone
+    PermissionDenied(String),
+    NotFound(String),
+    /// The app is in the wrong state for the request, e.g. already recording
+    Conflict(String),
+    Internal(String),
+}
+
+impl AppError {
+    /// Stable identifier the frontend can switch on.
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Offline(_) => "offline",
+            AppError::Timeout(_) => "timeout",
+            AppError::Http { .. } => "http_error",
+            AppError::InvalidInput(_) => "invalid_input",
+            AppError::PermissionDenied(_) => "permission_denied",
+            AppError::NotFound(_) => "not_found",
+            AppError::Conflict(_) => "conflict",
+            AppError::Internal(_) => "internal",
+        }
+    }
+
+    /// Whether the same request may succeed if tried again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Offline(_) | AppError::Timeout(_) => true,
+            AppError::Http { status, .. } => *status >= 500 || *status == 429,
+            _ => false,
+        }
+    }
+
+    /// The error for a response with a non-success `status`.
+    pub fn from_status(status: StatusCode, body: &str) -> Self {
+        let message = format!("HTTP {}: {}", status, body);
+        match status {
+            StatusCode::BAD_REQUEST | StatusCode::UNPROCESSABLE_ENTITY => AppError::InvalidInput(message),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => AppError::PermissionDenied(message),
+            StatusCode::NOT_FOUND => AppError::NotFound(message),
+            StatusCode::CONFLICT => AppError::Conflict(message),
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => AppError::Timeout(message),
+            _ => AppError::Http { status: status.as_u16(), message },
+        }
+    }
+
+    /// The error for a request that got no response.
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        let message = format!("Request failed: {}", error);
+        if error.is_timeout() {
+            AppError::Timeout(message)
+        } else if error.is_connect() {
+            AppError::Offline(message)
+        } else {
+            AppError::Internal(message)
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            AppError::Offline(message)
+            | AppError::Timeout(message)
+            | AppError::Http { message, .. }
+            | AppError::InvalidInput(message)
+            | AppError::PermissionDenied(message)
+            | AppError::NotFound(message)
+            | AppError::Conflict(message)
+            | AppError::Internal(message) => message,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let status = match self {
+            AppError::Http { status, .. } => Some(*status),
+            _ => None,
+        };
+        let mut state = serializer.serialize_struct("AppError", if status.is_some() { 4 } else { 3 })?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", self.message())?;
+        state.serialize_field("retryable", &self.is_retryable())?;
+        if let Some(status) = status {
+            state.serialize_field("status", &status)?;
+        }
+        state.end()
+    }
+}
+
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::Internal(message.to_string())
+    }
+}
+
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::Internal(error.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(error.to_string()),
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(error.to_string()),
+            _ => AppError::Internal(error.to_string()),
+        }
+    }
+}
@@ -0,0 +1,202 @@
+// Export to a Confluence Cloud space: each meeting becomes a page, optionally under a
+// parent page, written in Confluence's storage format with action items as a task list.
+// Confluence authenticates with the account email plus an API token, which is kept in the
+// keychain.
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::info as log_info;
+
+use super::html::escape_html;
+use super::{load_document, BlockKind, ExportDocument, PublishedPage};
+use crate::http;
+use crate::keychain;
+use crate::telemetry::Dependency;
+
+const CONFLUENCE_STORE_KEY: &str = "confluence";
+const TOKEN_ACCOUNT: &str = "confluence-token";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfluenceConfig {
+    /// e.g. https://example.atlassian.net
+    pub base_url: String,
+    pub space_key: String,
+    /// Page the meeting pages are created under; the space root if not set
+    #[serde(default)]
+    pub parent_page_id: Option<String>,
+    /// Atlassian account the API token belongs to
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfluenceStatus {
+    pub config: Option<ConfluenceConfig>,
+    pub has_token: bool,
+}
+
+#[derive(Deserialize)]
+struct CreatedContent {
+    id: String,
+    #[serde(rename = "_links")]
+    links: ContentLinks,
+}
+
+#[derive(Deserialize)]
+struct ContentLinks {
+    base: Option<String>,
+    webui: Option<String>,
+}
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> Option<ConfluenceConfig> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(CONFLUENCE_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+/// The meeting in Confluence storage format (XHTML plus `ac:` macros).
+fn render_storage(document: &ExportDocument) -> String {
+    let mut page = String::new();
+    let meta = match document.provenance() {
+        Some(provenance) => format!("{} \u{00b7} {}", document.display_date(), provenance),
+        None => document.display_date(),
+    };
+    page.push_str(&format!("<p><em>{}</em></p>", escape_html(&meta)));
+
+    for section in &document.sections {
+        page.push_str(&format!("<h2>{}</h2>", escape_html(&section.title)));
+        let mut in_list = false;
+        for block in &section.blocks {
+            let is_bullet = block.kind == BlockKind::Bullet;
+            if is_bullet && !in_list {
+                page.push_str("<ul>");
+            } else if !is_bullet && in_list {
+                page.push_str("</ul>");
+            }
+            in_list = is_bullet;
+            let content = escape_html(&block.content);
+            match block.kind {
+                BlockKind::Heading1 => page.push_str(&format!("<h3>{}</h3>", content)),
+                BlockKind::Heading2 => page.push_str(&format!("<h4>{}</h4>", content)),
+                BlockKind::Bullet => page.push_str(&format!("<li>{}</li>", content)),
+                BlockKind::Text => page.push_str(&format!("<p>{}</p>", content)),
+            }
+        }
+        if in_list {
+            page.push_str("</ul>");
+        }
+    }
+
+    if !document.decisions.is_empty() {
+        page.push_str("<h2>Decisions</h2><ul>");
+        for decision in &document.decisions {
+            page.push_str(&format!("<li>{}</li>", escape_html(decision)));
+        }
+        page.push_str("</ul>");
+    }
+    if !document.action_items.is_empty() {
+        page.push_str("<h2>Action items</h2><ac:task-list>");
+        for item in &document.action_items {
+            let text = match item.owner.as_deref() {
+                Some(owner) => format!("{} ({})", escape_html(item.text.trim()), escape_html(owner.trim())),
+                None => escape_html(item.text.trim()),
+            };
+            page.push_str(&format!(
+                "<ac:task><ac:task-status>incomplete</ac:task-status><ac:task-body>{}</ac:task-body></ac:task>",
+                text
+            ));
+        }
+        page.push_str("</ac:task-list>");
+    }
+    if !document.transcript.is_empty() {
+        page.push_str("<h2>Transcript</h2>");
+        for line in &document.transcript {
+            page.push_str(&format!("<p><strong>[{}]</strong> {}</p>", escape_html(&line.timestamp), escape_html(line.text.trim())));
+        }
+    }
+    page
+}
+
+#[tauri::command]
+pub async fn get_confluence_config<R: Runtime>(app: AppHandle<R>) -> Result<ConfluenceStatus, String> {
+    Ok(ConfluenceStatus { config: load_config(&app), has_token: keychain::get_secret(TOKEN_ACCOUNT)?.is_some() })
+}
+
+/// Save the Confluence settings. A `token` replaces the stored API token; an empty one
+/// removes it, and leaving it out keeps the current one.
+#[tauri::command]
+pub async fn set_confluence_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: ConfluenceConfig,
+    token: Option<String>,
+) -> Result<(), String> {
+    let base_url = reqwest::Url::parse(config.base_url.trim())
+        .map_err(|e| format!("Invalid Confluence URL '{}': {}", config.base_url, e))?;
+    if base_url.scheme() != "https" {
+        return Err("Confluence URL must use https".to_string());
+    }
+    if config.space_key.trim().is_empty() || config.email.trim().is_empty() {
+        return Err("Confluence export needs a space key and the account email".to_string());
+    }
+    let config = ConfluenceConfig {
+        // Pasting the wiki link instead of the site is a common mistake
+        base_url: base_url.as_str().trim_end_matches('/').trim_end_matches("/wiki").to_string(),
+        space_key: config.space_key.trim().to_string(),
+        parent_page_id: config.parent_page_id.map(|id| id.trim().to_string()).filter(|id| !id.is_empty()),
+        email: config.email.trim().to_string(),
+    };
+    match token.as_deref().map(str::trim) {
+        Some("") => keychain::delete_secret(TOKEN_ACCOUNT)?,
+        Some(token) => keychain::set_secret(TOKEN_ACCOUNT, token)?,
+        None => {}
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(CONFLUENCE_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
+/// Create a page for the meeting in the configured Confluence space.
+#[tauri::command]
+pub async fn export_to_confluence<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<PublishedPage, String> {
+    log_info!("export_to_confluence called for meeting_id: {}", meeting_id);
+    let config = load_config(&app).ok_or("Confluence export is not set up yet")?;
+    let token = keychain::get_secret(TOKEN_ACCOUNT)?.ok_or("Save a Confluence API token first")?;
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+
+    // Page titles are unique per space, so the date keeps recurring meetings apart
+    let title = format!("{} ({})", document.title.trim(), document.display_date());
+    let mut body = json!({
+        "type": "page",
+        "title": title,
+        "space": { "key": config.space_key },
+        "body": { "storage": { "value": render_storage(&document), "representation": "storage" } },
+    });
+    if let Some(parent) = &config.parent_page_id {
+        body["ancestors"] = json!([{ "id": parent }]);
+    }
+
+    // Creating the page again would fail on the duplicate title or add a second copy
+    let options = http::policy(&app).options(Dependency::Integration, false);
+    let url = format!("{}/wiki/rest/api/content", config.base_url);
+    let response = http::send(&options, "Confluence page", || {
+        http::client().post(&url).basic_auth(&config.email, Some(&token)).json(&body)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let created: CreatedContent = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read Confluence response: {}", e))?;
+
+    let page_url = match (created.links.base, created.links.webui) {
+        (Some(base), Some(webui)) => format!("{}{}", base, webui),
+        _ => format!("{}/wiki/pages/viewpage.action?pageId={}", config.base_url, created.id),
+    };
+    log_info!("Exported meeting {} to Confluence page {}", meeting_id, page_url);
+    Ok(PublishedPage { id: created.id, url: page_url })
+}
@@ -0,0 +1,360 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use docx_rs::{
+    AlignmentType, Docx, Footer, Header, Paragraph, Pic, Run, RunFonts, Style, StyleType, Table,
+    TableCell, TableRow,
+};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use super::{load_document, BlockKind, ExportDocument};
+use crate::atomic_file::AtomicFile;
+use crate::summary::{SummaryField, SummarySection};
+
+const TEMPLATES_STORE_KEY: &str = "docxTemplates";
+const DEFAULT_PROFILE_KEY: &str = "default";
+const LOGO_MAX_WIDTH_EMU: u32 = 1_371_600; // 1.5 inch
+const EMU_PER_PIXEL: u32 = 9525;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderField {
+    pub label: String,
+    /// Supports `{{title}}`, `{{date}}` and `{{meeting_id}}` placeholders
+    pub value: String,
+}
+
+/// Corporate look of exported Word documents, stored per profile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocxTemplate {
+    pub logo_path: Option<String>,
+    pub header_fields: Vec<HeaderField>,
+    pub font_family: String,
+    pub font_size_pt: usize,
+    pub heading_color: String,
+    pub footer_text: Option<String>,
+    pub include_transcript: bool,
+    /// Lead with Agenda, Decisions and Action Items sections instead of the notes as written
+    pub structured_sections: bool,
+}
+
+impl Default for DocxTemplate {
+    fn default() -> Self {
+        Self {
+            logo_path: None,
+            header_fields: vec![HeaderField {
+                label: "Date".to_string(),
+                value: "{{date}}".to_string(),
+            }],
+            font_family: "Calibri".to_string(),
+            font_size_pt: 11,
+            heading_color: "1F3864".to_string(),
+            footer_text: None,
+            include_transcript: true,
+            structured_sections: true,
+        }
+    }
+}
+
+impl DocxTemplate {
+    fn validate(&self) -> Result<(), String> {
+        if !(6..=72).contains(&self.font_size_pt) {
+            return Err("Font size must be between 6 and 72 points".to_string());
+        }
+        let color = self.heading_color.trim_start_matches('#');
+        if color.len() != 6 || !color.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid heading color: {}", self.heading_color));
+        }
+        if let Some(logo) = &self.logo_path {
+            if !Path::new(logo).is_file() {
+                return Err(format!("Logo file not found: {}", logo));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn load_templates<R: Runtime>(app: &AppHandle<R>) -> Result<HashMap<String, DocxTemplate>, String> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    Ok(store
+        .get(TEMPLATES_STORE_KEY)
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default())
+}
+
+pub fn template_for_profile<R: Runtime>(app: &AppHandle<R>, profile_id: Option<&str>) -> Result<DocxTemplate, String> {
+    let templates = load_templates(app)?;
+    Ok(profile_id
+        .and_then(|id| templates.get(id))
+        .or_else(|| templates.get(DEFAULT_PROFILE_KEY))
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn fill_placeholders(value: &str, document: &ExportDocument) -> String {
+    value
+        .replace("{{title}}", &document.title)
+        .replace("{{date}}", &document.display_date())
+        .replace("{{meeting_id}}", &document.meeting_id)
+        .replace("{{transcription_model}}", document.transcription_model.as_deref().unwrap_or(""))
+        .replace("{{summary_model}}", document.summary_model.as_deref().unwrap_or(""))
+}
+
+fn logo_run(path: &str) -> Option<Run> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log_warn!("Failed to read logo {}: {}", path, e);
+            return None;
+        }
+    };
+    let pic = Pic::new(&bytes);
+    let (width, height) = pic.size;
+    let pic = if width > LOGO_MAX_WIDTH_EMU && width > 0 {
+        let scaled_height = (height as u64 * LOGO_MAX_WIDTH_EMU as u64 / width as u64) as u32;
+        pic.size(LOGO_MAX_WIDTH_EMU, scaled_height.max(EMU_PER_PIXEL))
+    } else {
+        pic
+    };
+    Some(Run::new().add_image(pic))
+}
+
+fn heading(style: &str, text: &str) -> Paragraph {
+    Paragraph::new().style(style).add_run(Run::new().add_text(text))
+}
+
+fn bullet(text: &str) -> Paragraph {
+    Paragraph::new()
+        .indent(Some(360), None, None, None)
+        .add_run(Run::new().add_text(format!("\u{2022} {}", text)))
+}
+
+fn add_section(mut docx: Docx, section: &SummarySection) -> Docx {
+    docx = docx.add_paragraph(heading("Heading1", &section.title));
+    for block in &section.blocks {
+        docx = docx.add_paragraph(match block.kind {
+            BlockKind::Heading1 => heading("Heading2", &block.content),
+            BlockKind::Heading2 => heading("Heading3", &block.content),
+            BlockKind::Bullet => bullet(&block.content),
+            BlockKind::Text => Paragraph::new().add_run(Run::new().add_text(&block.content)),
+        });
+    }
+    docx
+}
+
+fn add_list(mut docx: Docx, title: &str, items: &[String]) -> Docx {
+    docx = docx.add_paragraph(heading("Heading1", title));
+    for item in items {
+        docx = docx.add_paragraph(bullet(item));
+    }
+    docx
+}
+
+// Agenda, Decisions and Action Items from the typed summary, then the notes sections that
+// don't repeat them. Empty structured sections are kept so readers see nothing was recorded.
+fn add_structured_sections(mut docx: Docx, document: &ExportDocument) -> Docx {
+    let none_recorded = || Paragraph::new().add_run(Run::new().add_text("None recorded.").italic().color("808080"));
+
+    if document.agenda.is_empty() {
+        docx = docx.add_paragraph(heading("Heading1", "Agenda")).add_paragraph(none_recorded());
+    } else {
+        docx = add_list(docx, "Agenda", &document.agenda);
+    }
+
+    if document.decisions.is_empty() {
+        docx = docx.add_paragraph(heading("Heading1", "Decisions")).add_paragraph(none_recorded());
+    } else {
+        docx = add_list(docx, "Decisions", &document.decisions);
+    }
+
+    docx = docx.add_paragraph(heading("Heading1", "Action Items"));
+    if document.action_items.is_empty() {
+        docx = docx.add_paragraph(none_recorded());
+    } else {
+        let header = TableRow::new(vec![
+            TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("Action").bold())),
+            TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text("Owner").bold())),
+        ]);
+        let rows = std::iter::once(header)
+            .chain(document.action_items.iter().map(|item| {
+                TableRow::new(vec![
+                    TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(&item.text))),
+                    TableCell::new().add_paragraph(
+                        Paragraph::new().add_run(Run::new().add_text(item.owner.as_deref().unwrap_or("Unassigned"))),
+                    ),
+                ])
+            }))
+            .collect();
+        docx = docx.add_table(Table::new(rows)).add_paragraph(Paragraph::new());
+    }
+
+    let repeated = |section: &&SummarySection| {
+        matches!(
+            SummaryField::for_section_title(&section.title),
+            Some(SummaryField::Agenda | SummaryField::Decisions | SummaryField::ActionItems)
+        )
+    };
+    for section in document.sections.iter().filter(|section| !repeated(section)) {
+        docx = add_section(docx, section);
+    }
+    docx
+}
+
+pub fn render_docx(document: &ExportDocument, template: &DocxTemplate) -> Docx {
+    let color = template.heading_color.trim_start_matches('#').to_string();
+    let half_points = template.font_size_pt * 2;
+
+    let mut docx = Docx::new()
+        .default_fonts(RunFonts::new().ascii(&template.font_family).hi_ansi(&template.font_family))
+        .default_size(half_points)
+        .add_style(
+            Style::new("Title", StyleType::Paragraph)
+                .name("Title")
+                .size(half_points * 2)
+                .bold()
+                .color(&color),
+        )
+        .add_style(
+            Style::new("Heading1", StyleType::Paragraph)
+                .name("Heading 1")
+                .size(half_points + 10)
+                .bold()
+                .color(&color),
+        )
+        .add_style(
+            Style::new("Heading2", StyleType::Paragraph)
+                .name("Heading 2")
+                .size(half_points + 6)
+                .bold()
+                .color(&color),
+        )
+        .add_style(
+            Style::new("Heading3", StyleType::Paragraph)
+                .name("Heading 3")
+                .size(half_points + 2)
+                .bold(),
+        );
+
+    // Page header: logo on the left, meeting title on the right
+    let mut header_paragraph = Paragraph::new();
+    if let Some(run) = template.logo_path.as_deref().and_then(logo_run) {
+        header_paragraph = header_paragraph.add_run(run).add_run(Run::new().add_tab());
+    }
+    header_paragraph = header_paragraph.add_run(Run::new().add_text(&document.title).color(&color));
+    docx = docx.header(Header::new().add_paragraph(header_paragraph));
+
+    if let Some(footer) = &template.footer_text {
+        docx = docx.footer(Footer::new().add_paragraph(
+            Paragraph::new()
+                .align(AlignmentType::Center)
+                .add_run(Run::new().add_text(fill_placeholders(footer, document)).size(half_points - 4)),
+        ));
+    }
+
+    docx = docx.add_paragraph(Paragraph::new().style("Title").add_run(Run::new().add_text(&document.title)));
+    if let Some(provenance) = document.provenance() {
+        docx = docx.add_paragraph(
+            Paragraph::new().add_run(Run::new().add_text(provenance).size(half_points - 4).color("808080")),
+        );
+    }
+
+    // Header fields as a two-column table
+    if !template.header_fields.is_empty() {
+        let rows = template
+            .header_fields
+            .iter()
+            .map(|field| {
+                TableRow::new(vec![
+                    TableCell::new().add_paragraph(Paragraph::new().add_run(Run::new().add_text(&field.label).bold())),
+                    TableCell::new().add_paragraph(
+                        Paragraph::new().add_run(Run::new().add_text(fill_placeholders(&field.value, document))),
+                    ),
+                ])
+            })
+            .collect();
+        docx = docx.add_table(Table::new(rows)).add_paragraph(Paragraph::new());
+    }
+
+    if template.structured_sections {
+        docx = add_structured_sections(docx, document);
+    } else {
+        for section in &document.sections {
+            docx = add_section(docx, section);
+        }
+    }
+
+    if template.include_transcript && !document.transcript.is_empty() {
+        docx = docx.add_paragraph(heading("Heading1", "Transcript"));
+        for line in &document.transcript {
+            docx = docx.add_paragraph(
+                Paragraph::new()
+                    .add_run(Run::new().add_text(format!("[{}] ", line.timestamp)).color("808080"))
+                    .add_run(Run::new().add_text(&line.text)),
+            );
+        }
+    }
+
+    docx
+}
+
+pub fn write_docx(document: &ExportDocument, template: &DocxTemplate, output_path: &Path) -> Result<(), String> {
+    let mut file =
+        AtomicFile::create(output_path).map_err(|e| format!("Failed to create {}: {}", output_path.display(), e))?;
+    render_docx(document, template)
+        .build()
+        .pack(&mut file)
+        .map_err(|e| format!("Failed to write DOCX: {}", e))?;
+    file.commit().map_err(|e| format!("Failed to write DOCX: {}", e))
+}
+
+#[tauri::command]
+pub async fn export_meeting_docx<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    output_path: String,
+    profile_id: Option<String>,
+    auth_token: Option<String>,
+) -> Result<String, String> {
+    log_info!("export_meeting_docx called for meeting_id: {}, profile: {:?}", meeting_id, profile_id);
+
+    let template = template_for_profile(&app, profile_id.as_deref())?;
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+
+    write_docx(&document, &template, Path::new(&output_path)).map_err(|e| {
+        log_error!("{}", e);
+        e
+    })?;
+
+    log_info!("Exported meeting {} to {}", meeting_id, output_path);
+    super::hooks::run_after_export(&app, PathBuf::from(&output_path), "docx");
+    Ok(output_path)
+}
+
+#[tauri::command]
+pub async fn get_docx_template<R: Runtime>(app: AppHandle<R>, profile_id: Option<String>) -> Result<DocxTemplate, String> {
+    template_for_profile(&app, profile_id.as_deref())
+}
+
+/// Saves the template for a profile. Without a profile id it becomes the default
+/// used by profiles that have no template of their own.
+#[tauri::command]
+pub async fn set_docx_template<R: Runtime>(
+    app: AppHandle<R>,
+    profile_id: Option<String>,
+    template: DocxTemplate,
+) -> Result<(), String> {
+    template.validate()?;
+
+    let mut templates = load_templates(&app)?;
+    let key = profile_id.unwrap_or_else(|| DEFAULT_PROFILE_KEY.to_string());
+    templates.insert(key.clone(), template);
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(TEMPLATES_STORE_KEY, serde_json::to_value(&templates).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log_info!("Saved DOCX template for profile {}", key);
+    Ok(())
+}
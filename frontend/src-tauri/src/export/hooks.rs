@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api_version::Versioned;
+use crate::artifact_storage::{self, ArtifactKind};
+use crate::sandbox;
+
+const HOOK_STORE_KEY: &str = "exportHook";
+const FILE_PLACEHOLDER: &str = "{file}";
+const FORMAT_PLACEHOLDER: &str = "{format}";
+const MAX_TIMEOUT_SECS: u64 = 300;
+const MAX_OUTPUT_CHARS: usize = 2000;
+
+/// External command run after a successful export. The program is executed directly,
+/// never through a shell, and each argument is passed as-is apart from whole-argument
+/// `{file}` / `{format}` placeholders.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExportHook {
+    pub enabled: bool,
+    pub program: String,
+    pub args: Vec<String>,
+    pub timeout_secs: u64,
+}
+
+impl Default for ExportHook {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            program: String::new(),
+            args: vec![FILE_PLACEHOLDER.to_string()],
+            timeout_secs: 30,
+        }
+    }
+}
+
+impl ExportHook {
+    fn validate(&self) -> Result<(), String> {
+        if !self.enabled {
+            return Ok(());
+        }
+        let program = Path::new(&self.program);
+        if !program.is_absolute() {
+            return Err("Hook program must be an absolute path".to_string());
+        }
+        if !program.is_file() {
+            return Err(format!("Hook program not found: {}", self.program));
+        }
+        if !(1..=MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Err(format!("Hook timeout must be between 1 and {} seconds", MAX_TIMEOUT_SECS));
+        }
+        if self.args.iter().any(|arg| arg.contains('\0')) {
+            return Err("Hook arguments cannot contain NUL characters".to_string());
+        }
+        Ok(())
+    }
+
+    fn build_args(&self, file: &Path, format: &str) -> Vec<String> {
+        self.args
+            .iter()
+            .map(|arg| match arg.as_str() {
+                FILE_PLACEHOLDER => file.to_string_lossy().to_string(),
+                FORMAT_PLACEHOLDER => format.to_string(),
+                other => other.to_string(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportHookResult {
+    pub file: String,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub output: String,
+}
+
+fn load_hook<R: Runtime>(app: &AppHandle<R>) -> ExportHook {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(HOOK_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn truncate_output(output: &[u8]) -> String {
+    let text = String::from_utf8_lossy(output);
+    text.chars().take(MAX_OUTPUT_CHARS).collect()
+}
+
+async fn execute(hook: &ExportHook, file: &Path, format: &str) -> Result<ExportHookResult, String> {
+    let mut command = sandbox::command(&hook.program);
+    command
+        .args(hook.build_args(file, format))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(dir) = file.parent() {
+        command.current_dir(dir);
+    }
+
+    let child = command.spawn().map_err(|e| format!("Failed to start export hook: {}", e))?;
+    let file = file.display().to_string();
+    // Dropping the timed-out future kills the child
+    match tokio::time::timeout(Duration::from_secs(hook.timeout_secs), child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let mut text = truncate_output(&output.stdout);
+            text.push_str(&truncate_output(&output.stderr));
+            Ok(ExportHookResult {
+                file,
+                success: output.status.success(),
+                exit_code: output.status.code(),
+                timed_out: false,
+                output: text,
+            })
+        }
+        Ok(Err(e)) => Err(format!("Failed to wait for export hook: {}", e)),
+        Err(_) => Ok(ExportHookResult {
+            file,
+            success: false,
+            exit_code: None,
+            timed_out: true,
+            output: format!("Export hook timed out after {} seconds", hook.timeout_secs),
+        }),
+    }
+}
+
+/// Runs the configured hook for an exported file in the background, if one is enabled.
+/// The outcome is reported through the `export-hook-finished` event. The file is also
+/// copied to remote storage when that is set up for exports.
+pub fn run_after_export<R: Runtime>(app: &AppHandle<R>, file: PathBuf, format: &'static str) {
+    artifact_storage::upload_in_background(app, file.clone(), ArtifactKind::Export);
+    let hook = load_hook(app);
+    if !hook.enabled {
+        return;
+    }
+    if let Err(e) = hook.validate() {
+        log_warn!("Skipping export hook: {}", e);
+        return;
+    }
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        log_info!("Running export hook {} for {}", hook.program, file.display());
+        let result = match execute(&hook, &file, format).await {
+            Ok(result) => result,
+            Err(e) => ExportHookResult {
+                file: file.display().to_string(),
+                success: false,
+                exit_code: None,
+                timed_out: false,
+                output: e,
+            },
+        };
+        if result.success {
+            log_info!("Export hook finished for {}", result.file);
+        } else {
+            log_error!("Export hook failed for {}: {}", result.file, result.output);
+        }
+        if let Err(e) = app.emit("export-hook-finished", &result) {
+            log_error!("Failed to emit export-hook-finished event: {}", e);
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_export_hook<R: Runtime>(app: AppHandle<R>) -> Result<ExportHook, String> {
+    Ok(load_hook(&app))
+}
+
+#[tauri::command]
+pub async fn set_export_hook<R: Runtime>(app: AppHandle<R>, hook: Versioned<ExportHook>) -> Result<(), String> {
+    let hook = hook.into_payload()?;
+    hook.validate()?;
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(HOOK_STORE_KEY, serde_json::to_value(&hook).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log_info!("Export hook {}", if hook.enabled { "enabled" } else { "disabled" });
+    Ok(())
+}
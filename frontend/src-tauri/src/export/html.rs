@@ -0,0 +1,240 @@
+use std::path::{Path, PathBuf};
+use base64::Engine;
+use serde::Deserialize;
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, error as log_error};
+
+use super::{load_document, BlockKind, ExportDocument};
+use crate::atomic_file;
+use crate::audio::{decode_audio_file, encode_single_audio};
+
+/// How the recording is attached to the exported page.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HtmlAudioMode {
+    None,
+    /// Audio is base64-encoded into the page, producing a single self-contained file
+    Embed,
+    /// Audio stays a separate file next to (or linked from) the page
+    Reference,
+}
+
+struct AudioSource {
+    src: String,
+    mime: &'static str,
+}
+
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
+// Accepts "HH:MM:SS", "MM:SS" or plain seconds, optionally wrapped in brackets
+fn timestamp_to_seconds(timestamp: &str) -> Option<f64> {
+    let trimmed = timestamp.trim().trim_start_matches('[').trim_end_matches(']');
+    trimmed
+        .split(':')
+        .try_fold(0.0, |total, part| part.trim().parse::<f64>().ok().map(|value| total * 60.0 + value))
+}
+
+fn mime_for(path: &Path) -> &'static str {
+    match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+        Some("mp4") | Some("m4a") => "audio/mp4",
+        Some("mp3") => "audio/mpeg",
+        Some("ogg") | Some("oga") => "audio/ogg",
+        Some("flac") => "audio/flac",
+        _ => "audio/wav",
+    }
+}
+
+// Re-encode the recording as 64 kbit/s AAC to keep the exported page small
+fn compress_audio(input: &Path, output: &PathBuf) -> Result<(), String> {
+    let (samples, sample_rate) = decode_audio_file(input).map_err(|e| format!("Failed to decode audio: {}", e))?;
+    encode_single_audio(bytemuck::cast_slice(&samples), sample_rate, 1, output)
+        .map_err(|e| format!("Failed to compress audio: {}", e))
+}
+
+fn prepare_audio(audio_path: &Path, mode: HtmlAudioMode, compress: bool, output_path: &Path) -> Result<Option<AudioSource>, String> {
+    if mode == HtmlAudioMode::None {
+        return Ok(None);
+    }
+    if !audio_path.is_file() {
+        return Err(format!("Audio file not found: {}", audio_path.display()));
+    }
+
+    let output_dir = output_path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = output_path.file_stem().and_then(|s| s.to_str()).unwrap_or("meeting");
+
+    match mode {
+        HtmlAudioMode::Embed => {
+            let (bytes, mime) = if compress {
+                let temp_path = std::env::temp_dir().join(format!("{}-export.m4a", stem));
+                compress_audio(audio_path, &temp_path)?;
+                let bytes = std::fs::read(&temp_path).map_err(|e| format!("Failed to read compressed audio: {}", e));
+                let _ = std::fs::remove_file(&temp_path);
+                (bytes?, "audio/mp4")
+            } else {
+                let bytes = std::fs::read(audio_path).map_err(|e| format!("Failed to read audio: {}", e))?;
+                (bytes, mime_for(audio_path))
+            };
+            let encoded = base64::engine::general_purpose::STANDARD.encode(bytes);
+            Ok(Some(AudioSource {
+                src: format!("data:{};base64,{}", mime, encoded),
+                mime,
+            }))
+        }
+        HtmlAudioMode::Reference => {
+            if compress {
+                let file_name = format!("{}.m4a", stem);
+                compress_audio(audio_path, &output_dir.join(&file_name))?;
+                return Ok(Some(AudioSource { src: file_name, mime: "audio/mp4" }));
+            }
+            // Files in the same folder as the page are linked relatively so the pair can be moved together
+            let src = if audio_path.parent() == Some(output_dir) {
+                audio_path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+            } else {
+                let absolute = audio_path.canonicalize().unwrap_or_else(|_| audio_path.to_path_buf());
+                format!("file://{}", absolute.to_string_lossy().replace('\\', "/"))
+            };
+            Ok(Some(AudioSource { src, mime: mime_for(audio_path) }))
+        }
+        HtmlAudioMode::None => Ok(None),
+    }
+}
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Roboto, sans-serif; max-width: 820px; margin: 2rem auto; padding: 0 1rem; color: #1f2937; }
+h1 { margin-bottom: 0.25rem; }
+.meta { color: #6b7280; margin-bottom: 1.5rem; }
+audio { width: 100%; position: sticky; top: 0; background: #fff; padding: 0.5rem 0; }
+.line { padding: 0.25rem 0.5rem; border-radius: 4px; }
+.line.active { background: #e0ecff; }
+.ts { color: #2563eb; cursor: pointer; font-variant-numeric: tabular-nums; margin-right: 0.5rem; text-decoration: none; }
+.ts.plain { color: #6b7280; cursor: default; }
+"#;
+
+const SCRIPT: &str = r#"
+(function () {
+  var audio = document.getElementById('recording');
+  var lines = Array.prototype.slice.call(document.querySelectorAll('.line[data-start]'));
+  document.querySelectorAll('a.ts').forEach(function (link) {
+    link.addEventListener('click', function (event) {
+      event.preventDefault();
+      if (!audio) return;
+      audio.currentTime = parseFloat(link.getAttribute('data-seek'));
+      audio.play();
+    });
+  });
+  if (!audio) return;
+  audio.addEventListener('timeupdate', function () {
+    var current = null;
+    lines.forEach(function (line) {
+      if (parseFloat(line.getAttribute('data-start')) <= audio.currentTime) current = line;
+      line.classList.remove('active');
+    });
+    if (current) current.classList.add('active');
+  });
+})();
+"#;
+
+pub(super) fn render_html(document: &ExportDocument, audio: Option<&AudioSource>) -> String {
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n", escape_html(&document.title), STYLE));
+    let meta = match document.provenance() {
+        Some(provenance) => format!("{} \u{00b7} {}", document.display_date(), provenance),
+        None => document.display_date(),
+    };
+    html.push_str(&format!("<h1>{}</h1>\n<div class=\"meta\">{}</div>\n", escape_html(&document.title), escape_html(&meta)));
+
+    if let Some(audio) = audio {
+        html.push_str(&format!(
+            "<audio id=\"recording\" controls preload=\"metadata\"><source src=\"{}\" type=\"{}\"></audio>\n",
+            escape_html(&audio.src),
+            audio.mime
+        ));
+    }
+
+    for section in &document.sections {
+        html.push_str(&format!("<h2>{}</h2>\n", escape_html(&section.title)));
+        let mut in_list = false;
+        for block in &section.blocks {
+            let is_bullet = block.kind == BlockKind::Bullet;
+            if is_bullet && !in_list {
+                html.push_str("<ul>\n");
+            } else if !is_bullet && in_list {
+                html.push_str("</ul>\n");
+            }
+            in_list = is_bullet;
+            let content = escape_html(&block.content);
+            match block.kind {
+                BlockKind::Heading1 => html.push_str(&format!("<h3>{}</h3>\n", content)),
+                BlockKind::Heading2 => html.push_str(&format!("<h4>{}</h4>\n", content)),
+                BlockKind::Bullet => html.push_str(&format!("<li>{}</li>\n", content)),
+                BlockKind::Text => html.push_str(&format!("<p>{}</p>\n", content)),
+            }
+        }
+        if in_list {
+            html.push_str("</ul>\n");
+        }
+    }
+
+    if !document.transcript.is_empty() {
+        html.push_str("<h2>Transcript</h2>\n");
+        for line in &document.transcript {
+            let text = escape_html(&line.text);
+            let timestamp = escape_html(&line.timestamp);
+            match timestamp_to_seconds(&line.timestamp).filter(|_| audio.is_some()) {
+                Some(seconds) => html.push_str(&format!(
+                    "<div class=\"line\" data-start=\"{0}\"><a class=\"ts\" href=\"#\" data-seek=\"{0}\">{1}</a>{2}</div>\n",
+                    seconds, timestamp, text
+                )),
+                None => html.push_str(&format!("<div class=\"line\"><span class=\"ts plain\">{}</span>{}</div>\n", timestamp, text)),
+            }
+        }
+    }
+
+    html.push_str(&format!("<script>{}</script>\n</body>\n</html>\n", SCRIPT));
+    html
+}
+
+#[tauri::command]
+pub async fn export_meeting_html<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    output_path: String,
+    audio_path: Option<String>,
+    audio_mode: HtmlAudioMode,
+    compress_audio: bool,
+    auth_token: Option<String>,
+) -> Result<String, String> {
+    log_info!("export_meeting_html called for meeting_id: {}, audio_mode: {:?}, compress: {}", meeting_id, audio_mode, compress_audio);
+
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+    let output = PathBuf::from(&output_path);
+
+    let audio = match (&audio_path, audio_mode) {
+        (Some(path), mode) if mode != HtmlAudioMode::None => {
+            let path = PathBuf::from(path);
+            let output = output.clone();
+            tokio::task::spawn_blocking(move || prepare_audio(&path, mode, compress_audio, &output))
+                .await
+                .map_err(|e| format!("Audio preparation task failed: {}", e))??
+        }
+        _ => None,
+    };
+
+    atomic_file::write(&output, render_html(&document, audio.as_ref())).map_err(|e| {
+        let error_msg = format!("Failed to write HTML export: {}", e);
+        log_error!("{}", error_msg);
+        error_msg
+    })?;
+
+    log_info!("Exported meeting {} to {}", meeting_id, output_path);
+    super::hooks::run_after_export(&app, output, "html");
+    Ok(output_path)
+}
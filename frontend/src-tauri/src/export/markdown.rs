@@ -0,0 +1,38 @@
+use super::{BlockKind, ExportDocument};
+
+pub fn render_markdown(document: &ExportDocument) -> String {
+    let mut md = format!("# {}\n\n", document.title.trim());
+    let mut byline = vec![format!("*{}*", document.display_date())];
+    byline.extend(document.provenance().map(|p| format!("*{}*", p)));
+    md.push_str(&byline.join(" \u{00b7} "));
+    md.push_str("\n\n");
+
+    for section in &document.sections {
+        md.push_str(&format!("## {}\n\n", section.title));
+        let mut in_list = false;
+        for block in &section.blocks {
+            let is_bullet = block.kind == BlockKind::Bullet;
+            if in_list && !is_bullet {
+                md.push('\n');
+            }
+            in_list = is_bullet;
+            match block.kind {
+                BlockKind::Heading1 => md.push_str(&format!("### {}\n\n", block.content)),
+                BlockKind::Heading2 => md.push_str(&format!("#### {}\n\n", block.content)),
+                BlockKind::Bullet => md.push_str(&format!("- {}\n", block.content)),
+                BlockKind::Text => md.push_str(&format!("{}\n\n", block.content)),
+            }
+        }
+        if in_list {
+            md.push('\n');
+        }
+    }
+
+    if !document.transcript.is_empty() {
+        md.push_str("## Transcript\n\n");
+        for line in &document.transcript {
+            md.push_str(&format!("**[{}]** {}\n\n", line.timestamp, line.text.trim()));
+        }
+    }
+    md
+}
@@ -0,0 +1,233 @@
+// src/export/mod.rs
+pub mod confluence;
+pub mod docx;
+pub mod hooks;
+pub mod html;
+pub mod markdown;
+pub mod notion;
+pub mod pdf;
+pub mod share;
+pub mod stems;
+pub mod subtitles;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api::{make_api_request, MeetingDetails, SummaryResponse};
+use crate::atomic_file;
+use crate::duplicates::check_meeting_id;
+use crate::storage;
+use crate::summary::{MeetingSummary, SummaryActionItem, SummarySection};
+
+pub use crate::summary::BlockKind;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportTranscriptLine {
+    pub timestamp: String,
+    pub text: String,
+}
+
+/// Format-independent view of a meeting that every exporter renders from.
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportDocument {
+    pub meeting_id: String,
+    pub title: String,
+    pub created_at: String,
+    /// Engine/model that produced the transcript, from the meeting metadata
+    pub transcription_model: Option<String>,
+    /// Provider/model that produced the summary, from the meeting metadata
+    pub summary_model: Option<String>,
+    pub sections: Vec<SummarySection>,
+    pub agenda: Vec<String>,
+    pub decisions: Vec<String>,
+    pub action_items: Vec<SummaryActionItem>,
+    pub transcript: Vec<ExportTranscriptLine>,
+}
+
+impl ExportDocument {
+    pub fn from_meeting(meeting: &MeetingDetails, summary: Option<&MeetingSummary>) -> Self {
+        let title = summary
+            .map(|s| s.meeting_name.as_str())
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&meeting.title)
+            .to_string();
+
+        Self {
+            meeting_id: meeting.id.clone(),
+            title,
+            created_at: meeting.created_at.clone(),
+            transcription_model: meeting.metadata.as_ref().and_then(transcription_model),
+            summary_model: meeting.metadata.as_ref().and_then(summary_model),
+            sections: summary.map(|s| s.sections.clone()).unwrap_or_default(),
+            agenda: summary.map(|s| s.agenda.clone()).unwrap_or_default(),
+            decisions: summary.map(|s| s.decisions.clone()).unwrap_or_default(),
+            action_items: summary.map(|s| s.action_items.clone()).unwrap_or_default(),
+            transcript: meeting
+                .transcripts
+                .iter()
+                .map(|t| ExportTranscriptLine {
+                    timestamp: t.timestamp.clone(),
+                    text: t.text.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Human readable meeting date, falling back to the raw value if it cannot be parsed.
+    pub fn display_date(&self) -> String {
+        chrono::DateTime::parse_from_rfc3339(&self.created_at)
+            .map(|d| d.format("%B %-d, %Y").to_string())
+            .or_else(|_| {
+                chrono::NaiveDateTime::parse_from_str(&self.created_at, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map(|d| d.format("%B %-d, %Y").to_string())
+            })
+            .unwrap_or_else(|_| self.created_at.clone())
+    }
+
+    /// "Transcribed with ... · Summarized with ..." for the models that are known.
+    pub fn provenance(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            self.transcription_model.as_ref().map(|m| format!("Transcribed with {}", m)),
+            self.summary_model.as_ref().map(|m| format!("Summarized with {}", m)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        (!parts.is_empty()).then(|| parts.join(" \u{00b7} "))
+    }
+}
+
+fn transcription_model(metadata: &Value) -> Option<String> {
+    let transcription = metadata.get("transcription")?;
+    let engine = transcription.get("engine")?.as_str()?;
+    let local_model = transcription.get("local_model").and_then(|m| m.as_str());
+    let fallback_used = transcription.get("fallback_used").and_then(|f| f.as_bool()).unwrap_or(false);
+    Some(match local_model {
+        Some(model) if engine == "WhisperLocal" => format!("{} ({})", engine, model),
+        Some(model) if fallback_used => format!("{} with local fallback ({})", engine, model),
+        _ => engine.to_string(),
+    })
+}
+
+fn summary_model(metadata: &Value) -> Option<String> {
+    let summarization = metadata.get("summarization")?;
+    let provider = summarization.get("provider")?.as_str()?;
+    Some(match summarization.get("model").and_then(|m| m.as_str()) {
+        Some(model) => format!("{} / {}", provider, model),
+        None => provider.to_string(),
+    })
+}
+
+/// Fetch a meeting and its summary from the backend and build the export model.
+/// A missing summary is not an error; the document then only contains the transcript.
+pub async fn load_document<R: Runtime>(
+    app: &AppHandle<R>,
+    meeting_id: &str,
+    auth_token: Option<String>,
+) -> Result<ExportDocument, String> {
+    check_meeting_id(meeting_id)?;
+    let meeting = make_api_request::<R, MeetingDetails>(
+        app,
+        &format!("/get-meeting/{}", meeting_id),
+        "GET",
+        None,
+        None,
+        auth_token.clone(),
+    )
+    .await?;
+
+    let summary = match make_api_request::<R, SummaryResponse>(
+        app,
+        &format!("/get-summary/{}", meeting_id),
+        "GET",
+        None,
+        None,
+        auth_token,
+    )
+    .await
+    {
+        Ok(response) => response.summary().unwrap_or_else(|e| {
+            log_warn!("Exporting {} without its summary: {}", meeting_id, e);
+            None
+        }),
+        Err(e) => {
+            log_warn!("No summary available for export of {}: {}", meeting_id, e);
+            None
+        }
+    };
+
+    log_info!("Loaded meeting {} for export", meeting_id);
+    Ok(ExportDocument::from_meeting(&meeting, summary.as_ref()))
+}
+
+/// Build the export model for a meeting in the local store, for setups without the backend.
+pub async fn load_local_document<R: Runtime>(app: &AppHandle<R>, meeting_id: &str) -> Result<ExportDocument, String> {
+    let meeting = storage::meetings::get_meeting(app, meeting_id)?;
+    let summary = storage::meetings::local_get_summary(app.clone(), meeting_id.to_string()).await?;
+    Ok(ExportDocument::from_meeting(&meeting, summary.as_ref()))
+}
+
+/// A page created in an external knowledge base.
+#[derive(Debug, Clone, Serialize)]
+pub struct PublishedPage {
+    pub id: String,
+    pub url: String,
+}
+
+/// Formats written by `export_meeting`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MinutesFormat {
+    Markdown,
+    Pdf,
+}
+
+impl MinutesFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            MinutesFormat::Markdown => "md",
+            MinutesFormat::Pdf => "pdf",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MinutesFormat::Markdown => "markdown",
+            MinutesFormat::Pdf => "pdf",
+        }
+    }
+}
+
+/// Write an export document as Markdown or PDF minutes.
+pub fn write_minutes(document: &ExportDocument, format: MinutesFormat, output: &std::path::Path) -> Result<(), String> {
+    match format {
+        MinutesFormat::Markdown => atomic_file::write(output, markdown::render_markdown(document))
+            .map_err(|e| format!("Failed to write Markdown export: {}", e)),
+        MinutesFormat::Pdf => pdf::write_pdf(document, output),
+    }
+}
+
+/// Write a meeting's title, summary and transcript as Markdown or PDF minutes.
+#[tauri::command]
+pub async fn export_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    format: MinutesFormat,
+    path: String,
+    auth_token: Option<String>,
+) -> Result<String, String> {
+    log_info!("export_meeting called for meeting_id: {}, format: {:?}", meeting_id, format);
+
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+    let output = std::path::PathBuf::from(&path);
+    write_minutes(&document, format, &output).map_err(|e| {
+        log_error!("{}", e);
+        e
+    })?;
+
+    log_info!("Exported meeting {} to {}", meeting_id, path);
+    hooks::run_after_export(&app, output, format.name());
+    Ok(path)
+}
@@ -0,0 +1,199 @@
+// Export to a Notion database: each meeting becomes a page whose title property is the
+// meeting title, with the summary, action items (as to-dos) and transcript as the page
+// content. Uses an internal integration token, kept in the keychain; the database has to
+// be shared with that integration in Notion.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::info as log_info;
+
+use super::{load_document, BlockKind, ExportDocument, PublishedPage};
+use crate::http;
+use crate::keychain;
+use crate::telemetry::Dependency;
+
+const NOTION_STORE_KEY: &str = "notion";
+const TOKEN_ACCOUNT: &str = "notion-token";
+const API_URL: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+// Notion limits: characters per rich text object and blocks per request
+const MAX_TEXT_CHARS: usize = 2000;
+const MAX_BLOCKS_PER_REQUEST: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotionConfig {
+    pub database_id: String,
+    /// Name of the database's title property
+    #[serde(default = "default_title_property")]
+    pub title_property: String,
+    /// Date property to fill with the meeting date, if the database has one
+    #[serde(default)]
+    pub date_property: Option<String>,
+}
+
+fn default_title_property() -> String {
+    "Name".to_string()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NotionStatus {
+    pub config: Option<NotionConfig>,
+    pub has_token: bool,
+}
+
+#[derive(Deserialize)]
+struct CreatedPage {
+    id: String,
+    url: String,
+}
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> Option<NotionConfig> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(NOTION_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+}
+
+// Notion takes rich text in pieces of at most MAX_TEXT_CHARS
+fn rich_text(text: &str) -> Value {
+    let chars: Vec<char> = text.trim().chars().collect();
+    let pieces: Vec<Value> = chars
+        .chunks(MAX_TEXT_CHARS)
+        .map(|piece| json!({ "type": "text", "text": { "content": piece.iter().collect::<String>() } }))
+        .collect();
+    Value::Array(pieces)
+}
+
+fn block(kind: &str, text: &str) -> Value {
+    json!({ "object": "block", "type": kind, kind: { "rich_text": rich_text(text) } })
+}
+
+fn page_blocks(document: &ExportDocument) -> Vec<Value> {
+    let mut blocks = Vec::new();
+    for section in &document.sections {
+        blocks.push(block("heading_2", &section.title));
+        for summary_block in section.blocks.iter().filter(|b| !b.content.trim().is_empty()) {
+            let kind = match summary_block.kind {
+                BlockKind::Heading1 | BlockKind::Heading2 => "heading_3",
+                BlockKind::Bullet => "bulleted_list_item",
+                BlockKind::Text => "paragraph",
+            };
+            blocks.push(block(kind, &summary_block.content));
+        }
+    }
+    if !document.decisions.is_empty() {
+        blocks.push(block("heading_2", "Decisions"));
+        blocks.extend(document.decisions.iter().map(|decision| block("bulleted_list_item", decision)));
+    }
+    if !document.action_items.is_empty() {
+        blocks.push(block("heading_2", "Action items"));
+        for item in &document.action_items {
+            let text = match item.owner.as_deref() {
+                Some(owner) => format!("{} ({})", item.text.trim(), owner.trim()),
+                None => item.text.clone(),
+            };
+            blocks.push(json!({ "object": "block", "type": "to_do", "to_do": { "rich_text": rich_text(&text), "checked": false } }));
+        }
+    }
+    if !document.transcript.is_empty() {
+        blocks.push(json!({ "object": "block", "type": "divider", "divider": {} }));
+        blocks.push(block("heading_2", "Transcript"));
+        blocks.extend(
+            document
+                .transcript
+                .iter()
+                .map(|line| block("paragraph", &format!("[{}] {}", line.timestamp, line.text.trim()))),
+        );
+    }
+    blocks
+}
+
+async fn notion_request<R: Runtime>(
+    app: &AppHandle<R>,
+    token: &str,
+    method: reqwest::Method,
+    path: &str,
+    body: &Value,
+    label: &str,
+) -> Result<reqwest::Response, String> {
+    // Creating a page or appending blocks again would duplicate content
+    let options = http::policy(app).options(Dependency::Integration, false);
+    http::send(&options, label, || {
+        http::client()
+            .request(method.clone(), format!("{}{}", API_URL, path))
+            .bearer_auth(token)
+            .header("Notion-Version", NOTION_VERSION)
+            .json(body)
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_notion_config<R: Runtime>(app: AppHandle<R>) -> Result<NotionStatus, String> {
+    Ok(NotionStatus { config: load_config(&app), has_token: keychain::get_secret(TOKEN_ACCOUNT)?.is_some() })
+}
+
+/// Save the Notion settings. A `token` replaces the stored integration token; an empty one
+/// removes it, and leaving it out keeps the current one.
+#[tauri::command]
+pub async fn set_notion_config<R: Runtime>(app: AppHandle<R>, config: NotionConfig, token: Option<String>) -> Result<(), String> {
+    let database_id = config.database_id.trim().to_string();
+    if database_id.is_empty() {
+        return Err("Enter the Notion database to export to".to_string());
+    }
+    if config.title_property.trim().is_empty() {
+        return Err("Enter the name of the database's title property".to_string());
+    }
+    let config = NotionConfig { database_id, ..config };
+    match token.as_deref().map(str::trim) {
+        Some("") => keychain::delete_secret(TOKEN_ACCOUNT)?,
+        Some(token) => keychain::set_secret(TOKEN_ACCOUNT, token)?,
+        None => {}
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(NOTION_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
+/// Create a page for the meeting in the configured Notion database.
+#[tauri::command]
+pub async fn export_to_notion<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<PublishedPage, String> {
+    log_info!("export_to_notion called for meeting_id: {}", meeting_id);
+    let config = load_config(&app).ok_or("Notion export is not set up yet")?;
+    let token = keychain::get_secret(TOKEN_ACCOUNT)?.ok_or("Save a Notion integration token first")?;
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+
+    let mut properties = serde_json::Map::new();
+    properties.insert(config.title_property.clone(), json!({ "title": rich_text(&document.title) }));
+    if let Some(date_property) = config.date_property.as_deref().filter(|p| !p.trim().is_empty()) {
+        properties.insert(date_property.to_string(), json!({ "date": { "start": document.created_at } }));
+    }
+
+    let blocks = page_blocks(&document);
+    let mut chunks = blocks.chunks(MAX_BLOCKS_PER_REQUEST);
+    let body = json!({
+        "parent": { "database_id": config.database_id },
+        "properties": properties,
+        "children": chunks.next().unwrap_or_default(),
+    });
+    let page: CreatedPage = notion_request(&app, &token, reqwest::Method::POST, "/pages", &body, "Notion page")
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read Notion response: {}", e))?;
+
+    // Long transcripts go in with follow-up requests
+    for chunk in chunks {
+        let path = format!("/blocks/{}/children", page.id);
+        notion_request(&app, &token, reqwest::Method::PATCH, &path, &json!({ "children": chunk }), "Notion blocks").await?;
+    }
+
+    log_info!("Exported meeting {} to Notion page {}", meeting_id, page.url);
+    Ok(PublishedPage { id: page.id, url: page.url })
+}
@@ -0,0 +1,146 @@
+// PDF rendering with printpdf's built-in fonts, so no font files ship with the app. The
+// built-in fonts only cover the Windows-1252 character set; other characters come out as
+// placeholders, and the Markdown or DOCX export is the better choice for such meetings.
+use std::io::BufWriter;
+use std::path::Path;
+use printpdf::{BuiltinFont, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+
+use super::{BlockKind, ExportDocument};
+use crate::atomic_file::AtomicFile;
+
+const PAGE_WIDTH_MM: f32 = 210.0;
+const PAGE_HEIGHT_MM: f32 = 297.0;
+const MARGIN_MM: f32 = 20.0;
+const PT_PER_MM: f32 = 2.834_646;
+// Average Helvetica glyph width as a fraction of the font size, for line wrapping
+const AVERAGE_GLYPH_WIDTH: f32 = 0.5;
+const LINE_SPACING: f32 = 1.4;
+
+#[derive(Clone, Copy)]
+enum Weight {
+    Regular,
+    Bold,
+}
+
+struct Line {
+    text: String,
+    size: f32,
+    weight: Weight,
+    indent_mm: f32,
+    /// Extra space above the line
+    space_before_mm: f32,
+}
+
+fn line(text: impl Into<String>, size: f32, weight: Weight) -> Line {
+    Line { text: text.into(), size, weight, indent_mm: 0.0, space_before_mm: 0.0 }
+}
+
+// Greedy word wrap by estimated width
+fn wrap(text: &str, size: f32, width_mm: f32) -> Vec<String> {
+    let max_chars = ((width_mm * PT_PER_MM) / (size * AVERAGE_GLYPH_WIDTH)).max(10.0) as usize;
+    let mut lines = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > max_chars {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        lines.push(current);
+    }
+    lines
+}
+
+fn layout(document: &ExportDocument) -> Vec<Line> {
+    let mut lines = vec![line(document.title.trim(), 20.0, Weight::Bold)];
+    let mut byline = document.display_date();
+    if let Some(provenance) = document.provenance() {
+        byline = format!("{} - {}", byline, provenance);
+    }
+    lines.push(Line { space_before_mm: 2.0, ..line(byline, 9.0, Weight::Regular) });
+
+    for section in &document.sections {
+        lines.push(Line { space_before_mm: 8.0, ..line(&section.title, 14.0, Weight::Bold) });
+        for block in &section.blocks {
+            lines.push(match block.kind {
+                BlockKind::Heading1 => Line { space_before_mm: 4.0, ..line(&block.content, 12.0, Weight::Bold) },
+                BlockKind::Heading2 => Line { space_before_mm: 3.0, ..line(&block.content, 11.0, Weight::Bold) },
+                BlockKind::Bullet => Line { indent_mm: 5.0, ..line(format!("- {}", block.content), 10.0, Weight::Regular) },
+                BlockKind::Text => Line { space_before_mm: 1.5, ..line(&block.content, 10.0, Weight::Regular) },
+            });
+        }
+    }
+
+    if !document.transcript.is_empty() {
+        lines.push(Line { space_before_mm: 8.0, ..line("Transcript", 14.0, Weight::Bold) });
+        for entry in &document.transcript {
+            let text = format!("[{}] {}", entry.timestamp, entry.text.trim());
+            lines.push(Line { space_before_mm: 1.0, ..line(text, 9.0, Weight::Regular) });
+        }
+    }
+    lines
+}
+
+struct Writer<'a> {
+    doc: &'a PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    // Baseline of the next line, from the bottom of the page
+    y_mm: f32,
+    pages: usize,
+}
+
+impl Writer<'_> {
+    fn new_page(&mut self) {
+        self.pages += 1;
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), format!("Page {}", self.pages));
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y_mm = PAGE_HEIGHT_MM - MARGIN_MM;
+    }
+
+    fn write(&mut self, line: &Line) {
+        let height_mm = line.size * LINE_SPACING / PT_PER_MM;
+        let width_mm = PAGE_WIDTH_MM - 2.0 * MARGIN_MM - line.indent_mm;
+        self.y_mm -= line.space_before_mm;
+        for text in wrap(&line.text, line.size, width_mm) {
+            if self.y_mm - height_mm < MARGIN_MM {
+                self.new_page();
+            }
+            self.y_mm -= height_mm;
+            let font = match line.weight {
+                Weight::Regular => &self.regular,
+                Weight::Bold => &self.bold,
+            };
+            self.layer.use_text(text, line.size, Mm(MARGIN_MM + line.indent_mm), Mm(self.y_mm), font);
+        }
+    }
+}
+
+pub fn write_pdf(document: &ExportDocument, output_path: &Path) -> Result<(), String> {
+    let (doc, page, layer) = PdfDocument::new(&document.title, Mm(PAGE_WIDTH_MM), Mm(PAGE_HEIGHT_MM), "Page 1");
+    let regular = doc.add_builtin_font(BuiltinFont::Helvetica).map_err(|e| format!("Failed to load PDF font: {}", e))?;
+    let bold = doc.add_builtin_font(BuiltinFont::HelveticaBold).map_err(|e| format!("Failed to load PDF font: {}", e))?;
+
+    let mut writer = Writer {
+        layer: doc.get_page(page).get_layer(layer),
+        doc: &doc,
+        regular,
+        bold,
+        y_mm: PAGE_HEIGHT_MM - MARGIN_MM,
+        pages: 1,
+    };
+    for line in layout(document) {
+        writer.write(&line);
+    }
+
+    let file = AtomicFile::create(output_path).map_err(|e| format!("Failed to create {}: {}", output_path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+    doc.save(&mut writer).map_err(|e| format!("Failed to write PDF: {}", e))?;
+    let file = writer.into_inner().map_err(|e| format!("Failed to write PDF: {}", e.error()))?;
+    file.commit().map_err(|e| format!("Failed to write PDF: {}", e))
+}
@@ -0,0 +1,170 @@
+// Hand the minutes to someone in the same room: the meeting is rendered as a standalone
+// HTML page and served once over a short-lived HTTP endpoint, and the link is returned
+// as a QR code for a phone camera. The endpoint listens on the local network so phones
+// can reach it, but only answers the path carrying a random one-time token and shuts
+// down after the first download or when the link expires.
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::Mutex;
+use std::time::Duration;
+use base64::Engine;
+use once_cell::sync::Lazy;
+use qrcode::render::svg;
+use qrcode::QrCode;
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use log::{info as log_info, debug as log_debug, warn as log_warn};
+
+use super::html::render_html;
+use super::load_document;
+
+const LINK_LIFETIME: Duration = Duration::from_secs(10 * 60);
+// Requests are a single GET line plus headers; anything bigger is not a browser
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+// Connections are answered one at a time, so an idle one can't hold up the real download
+const CONNECTION_TIMEOUT: Duration = Duration::from_secs(5);
+const QR_SIZE: u32 = 320;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ShareLink {
+    pub url: String,
+    /// QR code for `url` as an SVG data URL, ready for an <img> tag
+    pub qr_code: String,
+    pub expires_at: String,
+}
+
+static ACTIVE_SHARE: Lazy<Mutex<Option<tokio::task::JoinHandle<()>>>> = Lazy::new(|| Mutex::new(None));
+
+/// Address of this machine on the local network. No packet is sent; connecting a UDP
+/// socket only selects the interface that routes outward.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 9))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn qr_data_url(url: &str) -> Result<String, String> {
+    let svg = QrCode::new(url.as_bytes())
+        .map_err(|e| format!("Failed to create QR code: {}", e))?
+        .render::<svg::Color>()
+        .min_dimensions(QR_SIZE, QR_SIZE)
+        .build();
+    let encoded = base64::engine::general_purpose::STANDARD.encode(svg);
+    Ok(format!("data:image/svg+xml;base64,{}", encoded))
+}
+
+fn response(status: &str, content_type: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Answer one connection. Returns true once the page has been delivered.
+async fn handle_connection(mut stream: TcpStream, path: &str, page: &[u8]) -> bool {
+    let mut request = Vec::new();
+    let mut buffer = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+        match stream.read(&mut buffer).await {
+            Ok(0) | Err(_) => return false,
+            Ok(n) => request.extend_from_slice(&buffer[..n]),
+        }
+    }
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.lines().next().unwrap_or_default().split_whitespace();
+    let matches = request_line.next() == Some("GET") && request_line.next() == Some(path);
+
+    let reply = if matches {
+        response("200 OK", "text/html; charset=utf-8", page)
+    } else {
+        response("404 Not Found", "text/plain", b"Not found")
+    };
+    match stream.write_all(&reply).await {
+        Ok(()) => matches,
+        Err(e) => {
+            log_debug!("Failed to answer share request: {}", e);
+            false
+        }
+    }
+}
+
+async fn serve_once(listener: TcpListener, path: String, page: Vec<u8>) {
+    let serve = async {
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    log_warn!("Share endpoint stopped accepting connections: {}", e);
+                    return;
+                }
+            };
+            match tokio::time::timeout(CONNECTION_TIMEOUT, handle_connection(stream, &path, &page)).await {
+                Ok(true) => {
+                    log_info!("Shared meeting downloaded by {}", peer.ip());
+                    return;
+                }
+                Ok(false) => {}
+                Err(_) => log_debug!("Dropped share connection from {} after it timed out", peer.ip()),
+            }
+        }
+    };
+    if tokio::time::timeout(LINK_LIFETIME, serve).await.is_err() {
+        log_info!("Share link expired without being used");
+    }
+}
+
+fn stop_active_share() {
+    if let Some(task) = ACTIVE_SHARE.lock().ok().and_then(|mut share| share.take()) {
+        task.abort();
+    }
+}
+
+/// Serve a meeting's minutes once on the local network and return the link as a QR code.
+/// Starting a new share revokes the previous link.
+#[tauri::command]
+pub async fn share_meeting_qr<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<ShareLink, String> {
+    log_info!("share_meeting_qr called for meeting_id: {}", meeting_id);
+
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+    let page = render_html(&document, None).into_bytes();
+
+    stop_active_share();
+    let listener = TcpListener::bind(SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 0))
+        .await
+        .map_err(|e| format!("Failed to start share endpoint: {}", e))?;
+    let port = listener.local_addr().map_err(|e| format!("Failed to start share endpoint: {}", e))?.port();
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    let path = format!("/{}", token);
+    let url = format!("http://{}/{}", SocketAddr::new(lan_address(), port), token);
+    let qr_code = qr_data_url(&url)?;
+
+    let task = tokio::spawn(serve_once(listener, path, page));
+    if let Ok(mut share) = ACTIVE_SHARE.lock() {
+        *share = Some(task);
+    }
+
+    let expires_at = chrono::Utc::now() + chrono::Duration::from_std(LINK_LIFETIME).unwrap_or_default();
+    log_info!("Sharing meeting {} on port {} until {}", meeting_id, port, expires_at.to_rfc3339());
+    Ok(ShareLink { url, qr_code, expires_at: expires_at.to_rfc3339() })
+}
+
+/// Revoke the current share link before it is used or expires.
+#[tauri::command]
+pub fn stop_meeting_share() {
+    stop_active_share();
+    log_info!("Meeting share stopped");
+}
@@ -0,0 +1,161 @@
+// Per-speaker audio stems rendered from a saved recording. Each stem keeps the full
+// length of the recording and is silent wherever its speaker is not talking, so the
+// stems line up when dropped into a multitrack editor.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::audio::diarization::Diarizer;
+use crate::audio::vad;
+use crate::audio::wav_writer::WavWriter;
+use crate::audio::{decode_audio_file, transcode_recording, AudioFormat};
+use crate::{load_diarizer, resample_audio, WHISPER_SAMPLE_RATE};
+
+// Stretch of audio attributed to a single speaker; the embedding model needs at least 1 s
+const STEM_WINDOW_SECS: f32 = 1.5;
+// Fade at the edges of each speaker turn so stems don't click
+const FADE_SECS: f32 = 0.01;
+const VAD_SENSITIVITY: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerStem {
+    pub speaker: String,
+    pub path: String,
+    pub speech_secs: f64,
+}
+
+/// Speaker of each window of the recording, None for windows without identifiable speech.
+fn label_windows(diarizer: &mut Diarizer, samples: &[f32], sample_rate: u32) -> Vec<Option<String>> {
+    let speech = if sample_rate == WHISPER_SAMPLE_RATE {
+        samples.to_vec()
+    } else {
+        resample_audio(samples, sample_rate, WHISPER_SAMPLE_RATE)
+    };
+    let window_len = (WHISPER_SAMPLE_RATE as f32 * STEM_WINDOW_SECS) as usize;
+    speech
+        .chunks(window_len)
+        .map(|window| {
+            if !vad::contains_speech(window, VAD_SENSITIVITY).unwrap_or(true) {
+                return None;
+            }
+            diarizer.identify(window).unwrap_or_else(|e| {
+                log_warn!("Speaker identification failed: {}", e);
+                None
+            })
+        })
+        .collect()
+}
+
+/// One full-length track per speaker, with each speaker's windows copied from the
+/// recording and silence everywhere else.
+fn render_stems(samples: &[f32], sample_rate: u32, labels: &[Option<String>]) -> BTreeMap<String, Vec<f32>> {
+    let window_len = (sample_rate as f32 * STEM_WINDOW_SECS) as usize;
+    let fade_len = ((sample_rate as f32 * FADE_SECS) as usize).max(1);
+    let mut stems: BTreeMap<String, Vec<f32>> = BTreeMap::new();
+
+    for (index, label) in labels.iter().enumerate() {
+        let Some(speaker) = label else { continue };
+        let start = (index * window_len).min(samples.len());
+        let end = ((index + 1) * window_len).min(samples.len());
+        let stem = stems.entry(speaker.clone()).or_insert_with(|| vec![0.0; samples.len()]);
+        let continues_turn = index > 0 && labels[index - 1].as_ref() == Some(speaker);
+        let turn_ends = labels.get(index + 1).and_then(|next| next.as_ref()) != Some(speaker);
+        for (offset, sample) in samples[start..end].iter().enumerate() {
+            let mut gain = 1.0;
+            if !continues_turn && offset < fade_len {
+                gain = offset as f32 / fade_len as f32;
+            }
+            let remaining = end - start - offset;
+            if turn_ends && remaining <= fade_len {
+                gain = gain.min(remaining as f32 / fade_len as f32);
+            }
+            stem[start + offset] = sample * gain;
+        }
+    }
+    stems
+}
+
+fn file_safe(name: &str) -> String {
+    let safe: String = name
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+    safe.trim_matches('_').to_string()
+}
+
+fn write_stem(samples: &[f32], sample_rate: u32, format: AudioFormat, output: &Path) -> Result<(), String> {
+    let wav_path = output.with_extension(AudioFormat::Wav.extension());
+    let mut writer = WavWriter::create(&wav_path, sample_rate).map_err(|e| format!("Failed to create stem: {}", e))?;
+    writer.write_samples(samples).map_err(|e| format!("Failed to write stem: {}", e))?;
+    writer.finalize().map_err(|e| format!("Failed to write stem: {}", e))?;
+    if format == AudioFormat::Wav {
+        return Ok(());
+    }
+    let result = transcode_recording(&wav_path, format, &output.to_path_buf()).map_err(|e| format!("Failed to encode stem: {}", e));
+    if let Err(e) = std::fs::remove_file(&wav_path) {
+        log_warn!("Failed to remove intermediate stem {:?}: {}", wav_path, e);
+    }
+    result
+}
+
+fn export_stems(
+    mut diarizer: Diarizer,
+    audio_path: &Path,
+    output_dir: &Path,
+    format: AudioFormat,
+) -> Result<Vec<SpeakerStem>, String> {
+    let (samples, sample_rate) = decode_audio_file(audio_path).map_err(|e| format!("Failed to decode recording: {}", e))?;
+    if samples.is_empty() {
+        return Err("Recording contains no audio".to_string());
+    }
+    let labels = label_windows(&mut diarizer, &samples, sample_rate);
+    let stems = render_stems(&samples, sample_rate, &labels);
+    if stems.is_empty() {
+        return Err("No speakers could be identified in the recording".to_string());
+    }
+
+    std::fs::create_dir_all(output_dir).map_err(|e| format!("Failed to create output directory: {}", e))?;
+    let base = audio_path.file_stem().and_then(|s| s.to_str()).unwrap_or("recording");
+    let mut exported = Vec::with_capacity(stems.len());
+    for (speaker, stem) in stems {
+        let path = output_dir
+            .join(format!("{}-{}", base, file_safe(&speaker)))
+            .with_extension(format.extension());
+        write_stem(&stem, sample_rate, format, &path)?;
+        let windows = labels.iter().filter(|label| label.as_ref() == Some(&speaker)).count();
+        let speech_secs = (windows as f64 * STEM_WINDOW_SECS as f64).min(samples.len() as f64 / sample_rate as f64);
+        exported.push(SpeakerStem { speaker, path: path.to_string_lossy().to_string(), speech_secs });
+    }
+    Ok(exported)
+}
+
+/// Split a saved recording into one audio file per speaker. Speakers are identified
+/// again from the recording with the diarization model, so labels restart at
+/// "Speaker 1" rather than following names given during the live session.
+#[tauri::command]
+pub async fn export_speaker_stems<R: Runtime>(
+    app: AppHandle<R>,
+    audio_path: String,
+    output_dir: String,
+    format: Option<AudioFormat>,
+) -> Result<Vec<SpeakerStem>, String> {
+    log_info!("export_speaker_stems called for {}, format: {:?}", audio_path, format);
+
+    let diarizer = load_diarizer(&app)
+        .ok_or_else(|| "Speaker stems need the speaker embedding model; download it first".to_string())?;
+    let audio_path = PathBuf::from(audio_path);
+    let output_dir = PathBuf::from(output_dir);
+    let format = format.unwrap_or_default();
+
+    let stems = tokio::task::spawn_blocking(move || export_stems(diarizer, &audio_path, &output_dir, format))
+        .await
+        .map_err(|e| format!("Stem export task failed: {}", e))?
+        .map_err(|e| {
+            log_error!("{}", e);
+            e
+        })?;
+    log_info!("Exported {} speaker stems", stems.len());
+    Ok(stems)
+}
@@ -0,0 +1,128 @@
+// Subtitle export. Stored transcript lines only carry their start time, so each cue runs
+// until the next line starts, capped at a reading-length estimate so a line doesn't stay on
+// screen through a long silence.
+use serde::Deserialize;
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use super::{load_document, ExportTranscriptLine};
+use crate::atomic_file;
+use crate::utils::{format_timestamp_millis, parse_timestamp};
+
+const MIN_CUE_SECS: f64 = 1.0;
+const MAX_CUE_SECS: f64 = 7.0;
+const SECS_PER_WORD: f64 = 0.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubtitleFormat {
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    fn name(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+}
+
+struct Cue<'a> {
+    start: f64,
+    end: f64,
+    text: &'a str,
+}
+
+fn build_cues(transcript: &[ExportTranscriptLine]) -> Vec<Cue<'_>> {
+    let mut timed: Vec<(f64, &str)> = Vec::with_capacity(transcript.len());
+    for line in transcript {
+        match parse_timestamp(&line.timestamp) {
+            Some(start) if !line.text.trim().is_empty() => timed.push((start, line.text.trim())),
+            Some(_) => {}
+            None => log_warn!("Skipping transcript line with unusable timestamp '{}'", line.timestamp),
+        }
+    }
+    timed.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    timed
+        .iter()
+        .enumerate()
+        .map(|(i, &(start, text))| {
+            let reading = (text.split_whitespace().count() as f64 * SECS_PER_WORD).clamp(MIN_CUE_SECS, MAX_CUE_SECS);
+            let end = match timed.get(i + 1) {
+                Some(&(next, _)) if next > start => next.min(start + reading),
+                _ => start + reading,
+            };
+            Cue { start, end, text }
+        })
+        .collect()
+}
+
+// Cue text may not contain blank lines in either format
+fn cue_text(text: &str) -> String {
+    text.lines().map(str::trim).filter(|l| !l.is_empty()).collect::<Vec<_>>().join("\n")
+}
+
+fn render_srt(cues: &[Cue]) -> String {
+    cues.iter()
+        .enumerate()
+        .map(|(i, cue)| {
+            format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                format_timestamp_millis(cue.start, ','),
+                format_timestamp_millis(cue.end, ','),
+                cue_text(cue.text)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_vtt(cues: &[Cue]) -> String {
+    let mut vtt = String::from("WEBVTT\n");
+    for cue in cues {
+        let text = cue_text(cue.text).replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+        vtt.push_str(&format!(
+            "\n{} --> {}\n{}\n",
+            format_timestamp_millis(cue.start, '.'),
+            format_timestamp_millis(cue.end, '.'),
+            text
+        ));
+    }
+    vtt
+}
+
+/// Write a meeting's transcript as an SRT or WebVTT subtitle file.
+#[tauri::command]
+pub async fn export_transcript<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    format: SubtitleFormat,
+    output_path: String,
+    auth_token: Option<String>,
+) -> Result<String, String> {
+    log_info!("export_transcript called for meeting_id: {}, format: {:?}", meeting_id, format);
+
+    let document = load_document(&app, &meeting_id, auth_token).await?;
+    let cues = build_cues(&document.transcript);
+    if cues.is_empty() {
+        return Err(format!("Meeting {} has no timed transcript lines to export", meeting_id));
+    }
+    let contents = match format {
+        SubtitleFormat::Srt => render_srt(&cues),
+        SubtitleFormat::Vtt => render_vtt(&cues),
+    };
+
+    atomic_file::write(&output_path, contents).map_err(|e| {
+        let error_msg = format!("Failed to write {} export: {}", format.name(), e);
+        log_error!("{}", error_msg);
+        error_msg
+    })?;
+
+    log_info!("Exported {} transcript cues of meeting {} to {}", cues.len(), meeting_id, output_path);
+    super::hooks::run_after_export(&app, std::path::PathBuf::from(&output_path), format.name());
+    Ok(output_path)
+}
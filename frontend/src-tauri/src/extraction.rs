@@ -0,0 +1,173 @@
+// Structured facts pulled out of a meeting: action items with owner and due date,
+// decisions and questions left open. The configured model is asked for a fixed JSON shape
+// and its answer is checked here before anything is returned, so callers can rely on the
+// types instead of on the model following instructions. Long meetings are read in chunks
+// and the results merged. The result is saved in the meeting's metadata under `extraction`.
+use std::collections::HashSet;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, warn as log_warn};
+
+use crate::api::{make_api_request, MeetingDetails, SaveMeetingMetadataRequest};
+use crate::llm;
+use crate::summarize::{chunk_transcript, CHUNK_TOKENS};
+
+const EXTRACTION_PROMPT: &str = "You extract structured information from meeting transcripts. Answer \
+with a single JSON object and nothing else, in this shape: {\"action_items\": [{\"text\": string, \
+\"owner\": string or null, \"due_date\": \"YYYY-MM-DD\" or null}], \"decisions\": [{\"text\": string}], \
+\"open_questions\": [{\"text\": string}]}. Only include what the transcript states; use null for an \
+owner or due date nobody mentioned and empty lists when there is nothing to report. Relative dates \
+such as \"next Friday\" are relative to the meeting date given.";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedActionItem {
+    pub text: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+    #[serde(default, deserialize_with = "lenient_date")]
+    pub due_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtractedDecision {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpenQuestion {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MeetingExtraction {
+    #[serde(default)]
+    pub action_items: Vec<ExtractedActionItem>,
+    #[serde(default)]
+    pub decisions: Vec<ExtractedDecision>,
+    #[serde(default)]
+    pub open_questions: Vec<OpenQuestion>,
+}
+
+// Models sometimes answer "next week" or "TBD" despite being asked for a date; that is
+// treated as no due date rather than as a broken answer
+fn lenient_date<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.and_then(|date| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok()))
+}
+
+// Text trimmed and empty values dropped, so "" and " " never reach the caller
+fn clean(text: &str) -> Option<String> {
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+impl MeetingExtraction {
+    /// Read a model's answer, dropping entries without text.
+    fn parse(answer: &str) -> Result<Self, String> {
+        let value = llm::extract_json(answer).ok_or("The model did not answer with JSON")?;
+        let extraction: MeetingExtraction =
+            serde_json::from_value(value).map_err(|e| format!("The model's answer does not match the schema: {}", e))?;
+        Ok(extraction.cleaned())
+    }
+
+    fn cleaned(self) -> Self {
+        MeetingExtraction {
+            action_items: self
+                .action_items
+                .into_iter()
+                .filter_map(|item| {
+                    Some(ExtractedActionItem {
+                        text: clean(&item.text)?,
+                        owner: item.owner.as_deref().and_then(clean),
+                        due_date: item.due_date,
+                    })
+                })
+                .collect(),
+            decisions: self
+                .decisions
+                .into_iter()
+                .filter_map(|decision| Some(ExtractedDecision { text: clean(&decision.text)? }))
+                .collect(),
+            open_questions: self
+                .open_questions
+                .into_iter()
+                .filter_map(|question| Some(OpenQuestion { text: clean(&question.text)? }))
+                .collect(),
+        }
+    }
+
+    // Chunks overlap in topic, so the same item can come back more than once
+    fn merge(&mut self, other: MeetingExtraction) {
+        let key = |text: &str| text.to_lowercase();
+        let mut seen: HashSet<String> = self.action_items.iter().map(|item| key(&item.text)).collect();
+        self.action_items.extend(other.action_items.into_iter().filter(|item| seen.insert(key(&item.text))));
+        let mut seen: HashSet<String> = self.decisions.iter().map(|decision| key(&decision.text)).collect();
+        self.decisions.extend(other.decisions.into_iter().filter(|decision| seen.insert(key(&decision.text))));
+        let mut seen: HashSet<String> = self.open_questions.iter().map(|question| key(&question.text)).collect();
+        self.open_questions.extend(other.open_questions.into_iter().filter(|question| seen.insert(key(&question.text))));
+    }
+}
+
+/// Extract action items, decisions and open questions from a meeting with the configured
+/// model, and save them with the meeting.
+#[tauri::command]
+pub async fn extract_action_items<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    auth_token: Option<String>,
+) -> Result<MeetingExtraction, String> {
+    log_info!("extract_action_items called for meeting_id: {}", meeting_id);
+    let meeting = make_api_request::<R, MeetingDetails>(
+        &app,
+        &format!("/get-meeting/{}", meeting_id),
+        "GET",
+        None,
+        None,
+        auth_token.clone(),
+    )
+    .await?;
+    let transcript: String = meeting
+        .transcripts
+        .iter()
+        .map(|segment| format!("[{}] {}\n", segment.timestamp, segment.text))
+        .collect();
+    let config = llm::configured(&app, auth_token.clone()).await?;
+    let chunks = chunk_transcript(&transcript, CHUNK_TOKENS, llm::provider(&config)?.as_ref());
+    if chunks.is_empty() {
+        return Err(format!("Meeting {} has no transcript", meeting_id));
+    }
+    let meeting_date = chrono::DateTime::parse_from_rfc3339(&meeting.created_at)
+        .map(|date| date.date_naive())
+        .unwrap_or_else(|_| Utc::now().date_naive());
+    let mut extraction = MeetingExtraction::default();
+    for (index, chunk) in chunks.iter().enumerate() {
+        let prompt = format!(
+            "Meeting: {}\nMeeting date: {}\nPart {} of {} of the transcript:\n\n{}",
+            meeting.title,
+            meeting_date,
+            index + 1,
+            chunks.len(),
+            chunk
+        );
+        let answer = llm::complete(&config, EXTRACTION_PROMPT, &prompt, true).await?;
+        extraction.merge(MeetingExtraction::parse(&answer)?);
+    }
+
+    let request = SaveMeetingMetadataRequest {
+        meeting_id: meeting_id.clone(),
+        metadata: serde_json::json!({ "extraction": &extraction }),
+    };
+    let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    if let Err(e) = make_api_request::<R, serde_json::Value>(&app, "/save-meeting-metadata", "POST", Some(&body), None, auth_token).await {
+        log_warn!("Failed to save extracted action items for {}: {}", meeting_id, e);
+    }
+    log_info!(
+        "Extracted {} action items, {} decisions and {} open questions from meeting {}",
+        extraction.action_items.len(),
+        extraction.decisions.len(),
+        extraction.open_questions.len(),
+        meeting_id
+    );
+    Ok(extraction)
+}
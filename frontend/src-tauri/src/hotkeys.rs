@@ -0,0 +1,166 @@
+// System-wide keyboard shortcuts, which work while another app (usually the meeting) has
+// focus. One starts or stops a recording, another drops a "Bookmark" marker at the current
+// moment so it can be found again later.
+//
+// Shortcuts are stored under `hotkeys` in the accelerator syntax Tauri menus use, such as
+// `CmdOrCtrl+Shift+R`. An empty shortcut turns that action off.
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::{is_recording, markers};
+
+const HOTKEYS_STORE_KEY: &str = "hotkeys";
+const MARKER_LABEL: &str = "Bookmark";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HotkeyConfig {
+    pub toggle_recording: Option<String>,
+    pub drop_marker: Option<String>,
+}
+
+impl Default for HotkeyConfig {
+    fn default() -> Self {
+        Self {
+            toggle_recording: Some("CmdOrCtrl+Shift+R".to_string()),
+            drop_marker: Some("CmdOrCtrl+Shift+M".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    ToggleRecording,
+    DropMarker,
+}
+
+// The shortcuts currently registered, and what each one does
+static REGISTERED: Lazy<Mutex<Vec<(Shortcut, Action)>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> HotkeyConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(HOTKEYS_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn parse(accelerator: &Option<String>) -> Result<Option<Shortcut>, String> {
+    match accelerator.as_deref().map(str::trim).filter(|a| !a.is_empty()) {
+        Some(accelerator) => accelerator
+            .parse::<Shortcut>()
+            .map(Some)
+            .map_err(|e| format!("Invalid shortcut '{}': {}", accelerator, e)),
+        None => Ok(None),
+    }
+}
+
+fn bindings(config: &HotkeyConfig) -> Result<Vec<(Shortcut, Action)>, String> {
+    let mut bindings = Vec::new();
+    if let Some(shortcut) = parse(&config.toggle_recording)? {
+        bindings.push((shortcut, Action::ToggleRecording));
+    }
+    if let Some(shortcut) = parse(&config.drop_marker)? {
+        if bindings.iter().any(|(existing, _)| *existing == shortcut) {
+            return Err("The recording and marker shortcuts must be different".to_string());
+        }
+        bindings.push((shortcut, Action::DropMarker));
+    }
+    Ok(bindings)
+}
+
+fn register<R: Runtime>(app: &AppHandle<R>, bindings: Vec<(Shortcut, Action)>) -> Result<(), String> {
+    let global_shortcut = app.global_shortcut();
+    global_shortcut
+        .unregister_all()
+        .map_err(|e| format!("Failed to clear shortcuts: {}", e))?;
+    let mut registered = REGISTERED.lock().map_err(|e| format!("Failed to lock shortcuts: {}", e))?;
+    registered.clear();
+    for (shortcut, action) in bindings {
+        global_shortcut
+            .register(shortcut)
+            .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))?;
+        registered.push((shortcut, action));
+    }
+    Ok(())
+}
+
+/// Register the saved shortcuts. Called once at startup; a shortcut taken by another app
+/// is logged and skipped.
+pub fn register_saved<R: Runtime>(app: &AppHandle<R>) {
+    let result = bindings(&load_config(app)).and_then(|bindings| register(app, bindings));
+    match result {
+        Ok(()) => log_info!("Global shortcuts registered"),
+        Err(e) => log_warn!("{}", e),
+    }
+}
+
+fn drop_marker<R: Runtime>(app: &AppHandle<R>) {
+    if !is_recording() {
+        log_info!("Ignoring marker shortcut while not recording");
+        return;
+    }
+    if let Err(e) = markers::add(app, MARKER_LABEL) {
+        log_error!("Marker shortcut failed: {}", e);
+    }
+}
+
+async fn toggle_recording<R: Runtime>(app: &AppHandle<R>) {
+    let result = if is_recording() {
+        crate::stop_recording_with_defaults(app).await
+    } else {
+        crate::start_recording_with_defaults(app).await
+    };
+    if let Err(e) = result {
+        log_error!("Recording shortcut failed: {}", e);
+        if let Err(e) = app.emit("hotkey-action-failed", e) {
+            log_error!("Failed to emit hotkey-action-failed event: {}", e);
+        }
+    }
+}
+
+/// Handler for the global shortcut plugin. Acts on key press only.
+pub fn handle<R: Runtime>(app: &AppHandle<R>, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = REGISTERED
+        .lock()
+        .ok()
+        .and_then(|registered| registered.iter().find(|(s, _)| s == shortcut).map(|(_, action)| *action));
+    match action {
+        Some(Action::DropMarker) => drop_marker(app),
+        Some(Action::ToggleRecording) => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move { toggle_recording(&app).await });
+        }
+        None => {}
+    }
+}
+
+#[tauri::command]
+pub async fn get_hotkeys<R: Runtime>(app: AppHandle<R>) -> Result<HotkeyConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save and register new shortcuts. Nothing is saved if a shortcut is invalid or already
+/// taken by another application.
+#[tauri::command]
+pub async fn set_hotkeys<R: Runtime>(app: AppHandle<R>, config: HotkeyConfig) -> Result<(), String> {
+    let new_bindings = bindings(&config)?;
+    if let Err(e) = register(&app, new_bindings) {
+        // Put the previous shortcuts back
+        register_saved(&app);
+        return Err(e);
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(HOTKEYS_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Global shortcuts updated");
+    Ok(())
+}
@@ -0,0 +1,192 @@
+// Timeouts and retries shared by every HTTP call to our own servers: backend API requests
+// and chunk uploads to the transcription server. Each attempt gets a timeout, failed
+// attempts are retried with exponential backoff plus jitter (so clients that failed
+// together don't retry together), and a `Retry-After` from the server is honoured.
+//
+// Retries are idempotency-aware: a request that may have reached the server is only sent
+// again if repeating it is harmless. Anything else is retried only when the connection
+// itself failed, i.e. the server never saw it.
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use rand::Rng;
+use reqwest::{RequestBuilder, Response};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, warn as log_warn};
+
+use crate::error::AppError;
+use crate::telemetry::{self, Dependency};
+
+const HTTP_POLICY_STORE_KEY: &str = "httpPolicy";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RETRIES: u32 = 10;
+const MAX_TIMEOUT_SECS: u64 = 600;
+const MAX_BACKOFF_MS: u64 = 60_000;
+
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// A shared client for backend requests; connections are reused between calls.
+pub fn client() -> &'static reqwest::Client {
+    &CLIENT
+}
+
+/// Timeout and retry settings for backend API requests.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpPolicy {
+    /// Limit for one attempt, from connecting to the last byte of the response
+    pub timeout_secs: u64,
+    pub max_retries: u32,
+    /// Wait before the first retry
+    pub initial_backoff_ms: u64,
+    /// Each further wait is this many times longer than the previous one
+    pub backoff_multiplier: f64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for HttpPolicy {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            max_retries: 2,
+            initial_backoff_ms: 250,
+            backoff_multiplier: 2.0,
+            max_backoff_ms: 5_000,
+        }
+    }
+}
+
+impl HttpPolicy {
+    fn validate(&self) -> Result<(), String> {
+        if self.timeout_secs == 0 || self.timeout_secs > MAX_TIMEOUT_SECS {
+            return Err(format!("Timeout must be between 1 and {} seconds", MAX_TIMEOUT_SECS));
+        }
+        if self.max_retries > MAX_RETRIES {
+            return Err(format!("Retry count must be at most {}", MAX_RETRIES));
+        }
+        if !(1.0..=10.0).contains(&self.backoff_multiplier) {
+            return Err("Backoff multiplier must be between 1 and 10".to_string());
+        }
+        if self.initial_backoff_ms > self.max_backoff_ms || self.max_backoff_ms > MAX_BACKOFF_MS {
+            return Err(format!("Backoff must be between the initial wait and at most {} ms", MAX_BACKOFF_MS));
+        }
+        Ok(())
+    }
+
+    /// Options for one request under this policy.
+    pub fn options(&self, dependency: Dependency, idempotent: bool) -> RequestOptions {
+        RequestOptions {
+            dependency,
+            timeout: Duration::from_secs(self.timeout_secs),
+            max_retries: self.max_retries,
+            initial_backoff: Duration::from_millis(self.initial_backoff_ms),
+            backoff_multiplier: self.backoff_multiplier,
+            max_backoff: Duration::from_millis(self.max_backoff_ms),
+            idempotent,
+        }
+    }
+}
+
+/// How one request is sent: per-attempt timeout, retries, and whether sending it twice is safe.
+#[derive(Debug, Clone)]
+pub struct RequestOptions {
+    /// Where attempts are recorded in telemetry
+    pub dependency: Dependency,
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub backoff_multiplier: f64,
+    pub max_backoff: Duration,
+    /// The request may be repeated after it reached the server
+    pub idempotent: bool,
+}
+
+impl RequestOptions {
+    /// Wait before retry number `attempt` (starting at 1): the exponential delay, shortened
+    /// by up to half at random.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self.backoff_multiplier.powi(attempt.saturating_sub(1) as i32);
+        let delay = self.initial_backoff.mul_f64(factor).min(self.max_backoff);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+}
+
+/// GET, PUT, DELETE and HEAD may be repeated without changing the outcome.
+pub fn is_idempotent(method: &str) -> bool {
+    matches!(method.to_uppercase().as_str(), "GET" | "PUT" | "DELETE" | "HEAD")
+}
+
+fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers().get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+    seconds.trim().parse().ok().map(Duration::from_secs)
+}
+
+/// Send a request built by `build` (called again for every attempt) and return the first
+/// successful response, or the error of the last attempt.
+pub async fn send(options: &RequestOptions, label: &str, mut build: impl FnMut() -> RequestBuilder) -> Result<Response, AppError> {
+    let mut attempt = 0;
+    loop {
+        let started = Instant::now();
+        let (error, retryable, wait) = match build().timeout(options.timeout).send().await {
+            Ok(response) if response.status().is_success() => {
+                telemetry::record(options.dependency, started.elapsed(), None);
+                return Ok(response);
+            }
+            Ok(response) => {
+                let status = response.status();
+                let wait = retry_after(&response);
+                let body = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+                let error = AppError::from_status(status, &body);
+                let retryable = options.idempotent && error.is_retryable();
+                (error, retryable, wait)
+            }
+            Err(e) => {
+                let retryable = e.is_connect() || (options.idempotent && (e.is_timeout() || e.is_request()));
+                (AppError::from_reqwest(&e), retryable, None)
+            }
+        };
+        telemetry::record(options.dependency, started.elapsed(), Some(error.message()));
+
+        attempt += 1;
+        if !retryable || attempt > options.max_retries {
+            if attempt > 1 {
+                log_warn!("{} failed after {} attempts: {}", label, attempt, error);
+            }
+            return Err(error);
+        }
+        let delay = wait.map_or_else(|| options.backoff(attempt), |wait| wait.min(options.max_backoff));
+        log_info!("{} failed ({}), retry {} of {} in {:?}", label, error, attempt, options.max_retries, delay);
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// The saved policy for backend requests.
+pub fn policy<R: Runtime>(app: &AppHandle<R>) -> HttpPolicy {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(HTTP_POLICY_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_http_policy<R: Runtime>(app: AppHandle<R>) -> Result<HttpPolicy, AppError> {
+    Ok(policy(&app))
+}
+
+/// Save the timeout and retry policy for backend requests; it applies to the next request.
+#[tauri::command]
+pub async fn set_http_policy<R: Runtime>(app: AppHandle<R>, policy: HttpPolicy) -> Result<(), AppError> {
+    policy.validate().map_err(AppError::InvalidInput)?;
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(HTTP_POLICY_STORE_KEY, serde_json::to_value(&policy).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("HTTP policy set to {:?}", policy);
+    Ok(())
+}
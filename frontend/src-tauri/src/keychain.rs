@@ -0,0 +1,32 @@
+// Secrets kept in the OS credential store (Keychain on macOS, Credential Manager on
+// Windows, the Secret Service on Linux) rather than in store.json.
+use keyring::Entry;
+
+const SERVICE: &str = "com.meetily.ai";
+
+fn entry(account: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, account).map_err(|e| format!("Failed to open keychain entry {}: {}", account, e))
+}
+
+/// The secret stored for `account`, or None if there is none.
+pub fn get_secret(account: &str) -> Result<Option<String>, String> {
+    match entry(account)?.get_password() {
+        Ok(secret) => Ok(Some(secret)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Failed to read {} from keychain: {}", account, e)),
+    }
+}
+
+pub fn set_secret(account: &str, secret: &str) -> Result<(), String> {
+    entry(account)?
+        .set_password(secret)
+        .map_err(|e| format!("Failed to save {} to keychain: {}", account, e))
+}
+
+/// Remove the secret; removing one that doesn't exist is not an error.
+pub fn delete_secret(account: &str) -> Result<(), String> {
+    match entry(account)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to remove {} from keychain: {}", account, e)),
+    }
+}
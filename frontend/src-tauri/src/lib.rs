@@ -1,5 +1,5 @@
 use std::fs;
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, Ordering}};
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering}};
 use std::time::Duration;
 use std::collections::VecDeque;
 use serde::{Deserialize, Serialize};
@@ -11,17 +11,87 @@ pub mod analytics;
 pub mod api;
 pub mod utils;
 pub mod console_utils;
-
+pub mod transcript_sync;
+pub mod duplicates;
+pub mod telemetry;
+pub mod diagnostics;
+pub mod self_test;
+pub mod export;
+pub mod action_items;
+pub mod pipeline_config;
+pub mod meeting_metadata;
+pub mod api_version;
+pub mod wake_word;
+pub mod voice_commands;
+pub mod chunk_spill;
+pub mod session_recovery;
+pub mod recording_indicator;
+pub mod privacy_pause;
+pub mod streaming;
+pub mod translation;
+pub mod server_capabilities;
+pub mod transcription_failover;
+pub mod playback;
+pub mod summary;
+pub mod storage;
+pub mod activity;
+pub mod workspace;
+pub mod permissions;
+pub mod meeting_timer;
+pub mod keychain;
+pub mod secrets;
+pub mod crash;
+pub mod recordings;
+pub mod sandbox;
+pub mod artifact_storage;
+pub mod atomic_file;
+pub mod logging;
+pub mod mixer;
+pub mod plugins;
+pub mod device_watchdog;
+pub mod capture_process;
+pub mod meeting_detector;
+pub mod calendar;
+pub mod hotkeys;
+pub mod markers;
+pub mod summarize;
+pub mod llm;
+pub mod extraction;
+pub mod embeddings;
+pub mod ask;
+pub mod prompt_templates;
+pub mod backend_status;
+pub mod http;
+pub mod error;
+pub mod batch;
+pub mod backup;
+pub mod retention;
+pub mod webhooks;
+pub mod usage_stats;
+pub mod slack;
+pub mod email;
+
+use error::AppError;
 use audio::{
-    default_input_device, default_output_device, AudioStream,
-    encode_single_audio,
+    default_input_device, default_output_device, AggregateDevice, AudioDevice, AudioStream,
+    AudioTranscriptionEngine, DeviceType, encode_single_audio, transcode_recording, AudioFormat,
 };
 use ollama::{OllamaModel};
 use analytics::{AnalyticsClient, AnalyticsConfig};
 use utils::format_timestamp;
-use tauri::{Runtime, AppHandle, Emitter};
+use audio::diarization::{self, Diarizer, Speaker};
+use audio::audio_processing::{AutoGainControl, HighPassFilter, NoiseGate};
+use audio::echo::EchoCanceller;
+use audio::gain::{apply_gain, GainMatcher};
+use audio::level::{LevelMeter, LEVEL_INTERVAL};
+use audio::vad;
+use audio::wav_writer::WavWriter;
+use telemetry::Dependency;
+use server_capabilities::SampleFormat;
+use transcription_failover::{FailoverConfig, FailoverState};
+use tauri::{Runtime, AppHandle, Emitter, Manager, State};
 use tauri_plugin_store::StoreExt;
-use log::{info as log_info, error as log_error, debug as log_debug};
+use log::{info as log_info, error as log_error, debug as log_debug, warn as log_warn};
 use reqwest::multipart::{Form, Part};
 use tokio::sync::mpsc;
 
@@ -29,19 +99,12 @@ static RECORDING_FLAG: AtomicBool = AtomicBool::new(false);
 static SEQUENCE_COUNTER: AtomicU64 = AtomicU64::new(0);
 static CHUNK_ID_COUNTER: AtomicU64 = AtomicU64::new(0);
 static DROPPED_CHUNK_COUNTER: AtomicU64 = AtomicU64::new(0);
-static mut MIC_BUFFER: Option<Arc<Mutex<Vec<f32>>>> = None;
-static mut SYSTEM_BUFFER: Option<Arc<Mutex<Vec<f32>>>> = None;
-static mut AUDIO_CHUNK_QUEUE: Option<Arc<Mutex<VecDeque<AudioChunk>>>> = None;
-static mut MIC_STREAM: Option<Arc<AudioStream>> = None;
-static mut SYSTEM_STREAM: Option<Arc<AudioStream>> = None;
-static mut IS_RUNNING: Option<Arc<AtomicBool>> = None;
-static mut RECORDING_START_TIME: Option<std::time::Instant> = None;
-static mut TRANSCRIPTION_TASK: Option<tokio::task::JoinHandle<()>> = None;
-static mut AUDIO_COLLECTION_TASK: Option<tokio::task::JoinHandle<()>> = None;
+static SPILLED_CHUNK_COUNTER: AtomicU64 = AtomicU64::new(0);
+static SKIPPED_SILENT_CHUNKS: AtomicU64 = AtomicU64::new(0);
 static mut ANALYTICS_CLIENT: Option<Arc<AnalyticsClient>> = None;
-static mut ERROR_EVENT_EMITTED: bool = false;
 static LAST_TRANSCRIPTION_ACTIVITY: AtomicU64 = AtomicU64::new(0);
 static ACTIVE_WORKERS: AtomicU64 = AtomicU64::new(0);
+static UPLOAD_PART_SIZE_BYTES: AtomicUsize = AtomicUsize::new(DEFAULT_UPLOAD_PART_SIZE_BYTES);
 
 // Audio configuration constants
 const CHUNK_DURATION_MS: u32 = 30000; // 30 seconds per chunk for better sentence processing
@@ -51,15 +114,38 @@ const WAV_CHANNELS: u16 = 2; // Stereo for WAV files
 const WHISPER_CHANNELS: u16 = 1; // Mono for Whisper API
 const SENTENCE_TIMEOUT_MS: u64 = 1000; // Emit incomplete sentence after 1 second of silence
 const MIN_CHUNK_DURATION_MS: u32 = 2000; // Minimum duration before sending chunk
+const CHUNK_OVERLAP_MS: u32 = 1500; // Audio repeated across chunk boundaries
+// Recently transcribed words kept per source to drop repeats from overlapping audio
+const BOUNDARY_WORDS: usize = 32;
 const MIN_RECORDING_DURATION_MS: u64 = 2000; // 2 seconds minimum
 const MAX_AUDIO_QUEUE_SIZE: usize = 10; // Maximum number of chunks in queue
+const DEFAULT_UPLOAD_PART_SIZE_BYTES: usize = 64 * 1024; // Size of each streamed piece of the upload body
+const MIN_UPLOAD_PART_SIZE_BYTES: usize = 4 * 1024;
+const MAX_UPLOAD_PART_SIZE_BYTES: usize = 8 * 1024 * 1024;
+const MAX_CHUNK_RETRIES: u32 = 10;
+const AUTO_LANGUAGE: &str = "auto";
+const MAX_CHUNK_BACKOFF_MS: u64 = 60_000;
+const CHUNK_UPLOAD_TIMEOUT: Duration = Duration::from_secs(120); // Slow servers can take a while on a long chunk
+const RECORDING_SYNC_INTERVAL: Duration = Duration::from_secs(5); // How often the in-progress recording is synced to disk
 
 // Server configuration constants
-const TRANSCRIPT_SERVER_URL: &str = "http://127.0.0.1:8178";
+const DEFAULT_TRANSCRIPT_SERVER_URL: &str = "http://127.0.0.1:8178";
+const TRANSCRIPT_SERVER_CHECK_TIMEOUT: Duration = Duration::from_secs(3);
 
 #[derive(Debug, Deserialize)]
 struct RecordingArgs {
     save_path: String,
+    /// Overrides the saved recording format for this recording
+    #[serde(default)]
+    format: Option<AudioFormat>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+struct RecordingSaved {
+    path: String,
+    format: AudioFormat,
+    duration_secs: f64,
+    size_bytes: u64,
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -67,9 +153,17 @@ struct TranscriptionStatus {
     chunks_in_queue: usize,
     is_processing: bool,
     last_activity_ms: u64,
+    /// Chunks the VAD gate kept from the transcription queue this recording
+    silent_chunks_skipped: u64,
+    /// Chunks written to disk because the queue was full, transcribed after recording
+    chunks_spilled: u64,
+    /// Set once the recording has switched to the fallback transcription provider
+    provider_failover: Option<transcription_failover::ProviderSwitch>,
+    /// Mix levels and mutes currently applied to the two streams
+    mix: mixer::MixStatus,
 }
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct TranscriptUpdate {
     text: String,
     timestamp: String,
@@ -77,28 +171,320 @@ struct TranscriptUpdate {
     sequence_id: u64,
     chunk_start_time: f64,
     is_partial: bool,
+    speaker: Option<String>,
+    // The line in the live translation target, when whisper translates it alongside
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    translated_text: Option<String>,
+    // Word timings and confidence, when the engine reports them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    words: Vec<TranscriptWord>,
+}
+
+// A word of a transcript line, timed in seconds from the start of the recording so the
+// UI can seek the recording to it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct TranscriptWord {
+    word: String,
+    start: f64,
+    end: f64,
+    // 0 to 1; low values mark words the model was unsure of
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    confidence: Option<f32>,
+}
+
+// Which capture stream a chunk came from. Each is transcribed on its own queue so
+// transcript lines can be attributed to the local user or the remote side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CaptureSource {
+    Mic,
+    System,
+}
+
+impl CaptureSource {
+    fn label(&self) -> &'static str {
+        match self {
+            CaptureSource::Mic => "You",
+            CaptureSource::System => "Others",
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match self {
+            CaptureSource::Mic => "mic",
+            CaptureSource::System => "system",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "mic" => Some(CaptureSource::Mic),
+            "system" => Some(CaptureSource::System),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 struct AudioChunk {
     samples: Vec<f32>,
+    source: CaptureSource,
     timestamp: f64,
+    // Length of audio at the start repeated from the previous chunk
+    overlap_secs: f64,
     chunk_id: u64,
     start_time: std::time::Instant,
     recording_start_time: std::time::Instant,
 }
 
+// Transcription errors seen by the workers of a session, used to decide when to give up
+#[derive(Debug, Default)]
+struct ErrorWindow {
+    count: u32,
+    last_error_time: Option<std::time::Instant>,
+}
+
+// State shared between a recording session and its background tasks
+#[derive(Clone)]
+struct SessionHandles {
+    mic_queue: Arc<Mutex<VecDeque<AudioChunk>>>,
+    system_queue: Arc<Mutex<VecDeque<AudioChunk>>>,
+    is_running: Arc<AtomicBool>,
+    error_event_emitted: Arc<AtomicBool>,
+    error_window: Arc<Mutex<ErrorWindow>>,
+    fallback_notified: Arc<AtomicBool>,
+    failover: Arc<FailoverState>,
+    diarizer: Option<Arc<Mutex<Diarizer>>>,
+    // Last words transcribed per source, shared by that source's workers
+    mic_recent_words: Arc<Mutex<VecDeque<String>>>,
+    system_recent_words: Arc<Mutex<VecDeque<String>>>,
+    detected_language: Arc<Mutex<Option<String>>>,
+}
+
+impl SessionHandles {
+    fn new(is_running: Arc<AtomicBool>, diarizer: Option<Diarizer>) -> Self {
+        Self {
+            mic_queue: Arc::new(Mutex::new(VecDeque::new())),
+            system_queue: Arc::new(Mutex::new(VecDeque::new())),
+            is_running,
+            error_event_emitted: Arc::new(AtomicBool::new(false)),
+            error_window: Arc::new(Mutex::new(ErrorWindow::default())),
+            fallback_notified: Arc::new(AtomicBool::new(false)),
+            failover: Arc::new(FailoverState::default()),
+            diarizer: diarizer.map(|d| Arc::new(Mutex::new(d))),
+            mic_recent_words: Arc::new(Mutex::new(VecDeque::new())),
+            system_recent_words: Arc::new(Mutex::new(VecDeque::new())),
+            detected_language: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    fn recent_words(&self, source: CaptureSource) -> Arc<Mutex<VecDeque<String>>> {
+        match source {
+            CaptureSource::Mic => self.mic_recent_words.clone(),
+            CaptureSource::System => self.system_recent_words.clone(),
+        }
+    }
+
+    fn queue(&self, source: CaptureSource) -> &Arc<Mutex<VecDeque<AudioChunk>>> {
+        match source {
+            CaptureSource::Mic => &self.mic_queue,
+            CaptureSource::System => &self.system_queue,
+        }
+    }
+
+    fn source_queue_len(&self, source: CaptureSource) -> usize {
+        self.queue(source).lock().map(|queue| queue.len()).unwrap_or(0)
+    }
+
+    fn queue_len(&self) -> usize {
+        self.source_queue_len(CaptureSource::Mic) + self.source_queue_len(CaptureSource::System)
+    }
+}
+
+/// Everything owned by an active recording. Held in Tauri managed state so
+/// starting and stopping never go through unsynchronized globals.
+pub struct RecordingSession {
+    mic_stream: Arc<AudioStream>,
+    system_stream: Arc<AudioStream>,
+    // Writes the gain-matched mix of both sources to a WAV file while recording
+    // Resolves to the recording's path, sample count and sample rate
+    recording_writer: Option<tokio::task::JoinHandle<Result<(std::path::PathBuf, u64, u32), String>>>,
+    start_time: std::time::Instant,
+    handles: SessionHandles,
+    audio_collection_task: Option<tokio::task::JoinHandle<()>>,
+    transcription_tasks: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl RecordingSession {
+    async fn stop_streams(&self) {
+        log_info!("Stopping microphone stream...");
+        if let Err(e) = self.mic_stream.stop().await {
+            log_error!("Error stopping mic stream: {}", e);
+        } else {
+            log_info!("Microphone stream stopped successfully");
+        }
+
+        log_info!("Stopping system stream...");
+        if let Err(e) = self.system_stream.stop().await {
+            log_error!("Error stopping system stream: {}", e);
+        } else {
+            log_info!("System stream stopped successfully");
+        }
+    }
+}
+
+pub type RecordingState = Mutex<Option<RecordingSession>>;
+
+/// Streams opened only to show input levels, e.g. while picking devices before a meeting.
+pub struct LevelMonitor {
+    mic_stream: Arc<AudioStream>,
+    system_stream: Arc<AudioStream>,
+    is_running: Arc<AtomicBool>,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl LevelMonitor {
+    async fn stop(self) {
+        self.is_running.store(false, Ordering::SeqCst);
+        self.task.abort();
+        if let Err(e) = self.mic_stream.stop().await {
+            log_error!("Error stopping level monitor mic stream: {}", e);
+        }
+        if let Err(e) = self.system_stream.stop().await {
+            log_error!("Error stopping level monitor system stream: {}", e);
+        }
+    }
+}
+
+pub type LevelMonitorState = Mutex<Option<LevelMonitor>>;
+
+// Which engine transcribes chunks, and the local model used directly or as a fallback
+#[derive(Debug, Clone)]
+struct TranscriptionConfig {
+    engine: AudioTranscriptionEngine,
+    local_model: Option<std::path::PathBuf>,
+    // Send 1 second frames to the server's streaming endpoint instead of chunks
+    streaming: bool,
+    retry: ChunkRetryPolicy,
+    // Spoken language code, "auto" to detect it, or None for the engine's default
+    language: Option<String>,
+    // Ask the engine for an English translation of every segment
+    translate: bool,
+    // Negotiated with the server before recording; the defaults suit any server
+    sample_format: SampleFormat,
+    server_diarization: bool,
+    // Provider taking over when the engine keeps failing
+    failover: FailoverConfig,
+}
+
+/// How often a chunk upload to the transcription server is retried before the chunk
+/// goes to the dead-letter store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChunkRetryPolicy {
+    pub max_retries: u32,
+    /// Wait before the first retry
+    pub initial_backoff_ms: u64,
+    /// Each further wait is this many times longer than the previous one
+    pub backoff_multiplier: f64,
+    pub max_backoff_ms: u64,
+}
+
+impl Default for ChunkRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff_ms: 200,
+            backoff_multiplier: 2.0,
+            max_backoff_ms: 10_000,
+        }
+    }
+}
+
+impl ChunkRetryPolicy {
+    fn validate(&self) -> Result<(), String> {
+        if self.max_retries > MAX_CHUNK_RETRIES {
+            return Err(format!("Retry count must be at most {}", MAX_CHUNK_RETRIES));
+        }
+        if !(1.0..=10.0).contains(&self.backoff_multiplier) {
+            return Err("Backoff multiplier must be between 1 and 10".to_string());
+        }
+        if self.initial_backoff_ms > self.max_backoff_ms || self.max_backoff_ms > MAX_CHUNK_BACKOFF_MS {
+            return Err(format!(
+                "Backoff must be between the initial wait and at most {} ms",
+                MAX_CHUNK_BACKOFF_MS
+            ));
+        }
+        Ok(())
+    }
+
+    // Re-sending a chunk only transcribes it again, so any failure may be retried
+    fn request_options(&self) -> http::RequestOptions {
+        http::RequestOptions {
+            dependency: Dependency::WhisperServer,
+            timeout: CHUNK_UPLOAD_TIMEOUT,
+            max_retries: self.max_retries,
+            initial_backoff: Duration::from_millis(self.initial_backoff_ms),
+            backoff_multiplier: self.backoff_multiplier,
+            max_backoff: Duration::from_millis(self.max_backoff_ms),
+            idempotent: true,
+        }
+    }
+}
+
+impl TranscriptionConfig {
+    fn uses_streaming(&self) -> bool {
+        self.streaming && self.engine == AudioTranscriptionEngine::WhisperServer
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct AudioDeviceInfo {
+    #[serde(flatten)]
+    device: AudioDevice,
+    /// Sub-devices of a macOS Aggregate Device, so the UI can explain what will be captured
+    aggregate: Option<AggregateDevice>,
+    captures_system_audio: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct TranscriptionEngineSettings {
+    engine: String,
+    local_model_path: Option<String>,
+    streaming: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct TranscriptSegment {
     text: String,
     t0: f32,
     t1: f32,
+    // Filled in by diarization after transcription
+    #[serde(default)]
+    speaker: Option<String>,
+    // English translation, when requested
+    #[serde(default)]
+    translation: Option<String>,
+    #[serde(default)]
+    words: Vec<SegmentWord>,
+}
+
+// A word of a segment as the engine reports it, timed like the segment
+#[derive(Debug, Deserialize)]
+struct SegmentWord {
+    word: String,
+    t0: f32,
+    t1: f32,
+    #[serde(default)]
+    p: Option<f32>,
 }
 
 #[derive(Debug, Deserialize)]
 struct TranscriptResponse {
     segments: Vec<TranscriptSegment>,
     buffer_size_ms: i32,
+    // Language the model transcribed in, when the engine reports it
+    #[serde(default)]
+    language: Option<String>,
 }
 
 // Helper struct to accumulate transcript segments
@@ -111,10 +497,37 @@ struct TranscriptAccumulator {
     current_chunk_id: u64,
     current_chunk_start_time: f64,
     recording_start_time: Option<std::time::Instant>,
+    source: CaptureSource,
+    sentence_speaker: Option<String>,
+    current_translation: String,
+    current_words: Vec<TranscriptWord>,
+    current_chunk_overlap_secs: f64,
+    recent_words: Arc<Mutex<VecDeque<String>>>,
+}
+
+// Lowercase a word and drop punctuation so repeats compare equal
+fn normalize_word(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+// Number of leading words of `words` already transcribed at the end of `recent`. The
+// previous chunk may have cut its last word short, in which case the full word is kept.
+fn repeated_prefix_len(recent: &[String], words: &[String]) -> usize {
+    for k in (1..=recent.len().min(words.len())).rev() {
+        let tail = &recent[recent.len() - k..];
+        let head = &words[..k];
+        if tail == head {
+            return k;
+        }
+        if tail[..k - 1] == head[..k - 1] && !tail[k - 1].is_empty() && head[k - 1].starts_with(&tail[k - 1]) {
+            return k - 1;
+        }
+    }
+    0
 }
 
 impl TranscriptAccumulator {
-    fn new() -> Self {
+    fn new(source: CaptureSource, recent_words: Arc<Mutex<VecDeque<String>>>) -> Self {
         Self {
             current_sentence: String::new(),
             sentence_start_time: 0.0,
@@ -123,18 +536,50 @@ impl TranscriptAccumulator {
             current_chunk_id: 0,
             current_chunk_start_time: 0.0,
             recording_start_time: None,
+            source,
+            sentence_speaker: None,
+            current_translation: String::new(),
+            current_words: Vec::new(),
+            current_chunk_overlap_secs: 0.0,
+            recent_words,
         }
     }
 
-    fn set_chunk_context(&mut self, chunk_id: u64, chunk_start_time: f64, recording_start_time: std::time::Instant) {
+    fn set_chunk_context(&mut self, chunk_id: u64, chunk_start_time: f64, overlap_secs: f64, recording_start_time: std::time::Instant) {
         self.current_chunk_id = chunk_id;
         self.current_chunk_start_time = chunk_start_time;
+        self.current_chunk_overlap_secs = overlap_secs;
         // Store recording start time for calculating actual elapsed times
         self.recording_start_time = Some(recording_start_time);
     }
 
+    // Drop the words of a segment that starts in the overlap and repeats what the
+    // previous chunk already produced
+    fn strip_overlap(&self, text: &str) -> String {
+        let Ok(recent) = self.recent_words.lock() else {
+            return text.to_string();
+        };
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+        let words: Vec<String> = tokens.iter().map(|t| normalize_word(t)).collect();
+        let recent: Vec<String> = recent.iter().cloned().collect();
+        let repeated = repeated_prefix_len(&recent, &words);
+        if repeated > 0 {
+            log_debug!("Dropping {} words repeated across the chunk boundary", repeated);
+        }
+        tokens[repeated..].join(" ")
+    }
+
+    fn remember_words(&self, text: &str) {
+        if let Ok(mut recent) = self.recent_words.lock() {
+            recent.extend(text.split_whitespace().map(normalize_word));
+            while recent.len() > BOUNDARY_WORDS {
+                recent.pop_front();
+            }
+        }
+    }
+
     fn add_segment(&mut self, segment: &TranscriptSegment) -> Option<TranscriptUpdate> {
-        log_info!("Processing new transcript segment: {:?}", segment);
+        log_info!("Processing new transcript segment ({:.2} - {:.2}): {}", segment.t0, segment.t1, logging::redact(&segment.text));
         
         // Update the last update time
         self.last_update_time = std::time::Instant::now();
@@ -145,9 +590,18 @@ impl TranscriptAccumulator {
             .replace("[AUDIO OUT]", "")
             .trim()
             .to_string();
+
+        // Segment times are in whisper's 10 ms units
+        let word_count = clean_text.split_whitespace().count();
+        let clean_text = if (segment.t0 as f64 / 100.0) < self.current_chunk_overlap_secs {
+            self.strip_overlap(&clean_text)
+        } else {
+            clean_text
+        };
+        let repeated_words = word_count - clean_text.split_whitespace().count();
             
         if !clean_text.is_empty() {
-            log_info!("Clean transcript text: {}", clean_text);
+            log_info!("Clean transcript text: {}", logging::redact(&clean_text));
         }
 
         // Skip empty segments or very short segments (less than 1 second)
@@ -166,7 +620,7 @@ impl TranscriptAccumulator {
 
         // Skip if this is a duplicate segment
         if segment_hash == self.last_segment_hash {
-            log_info!("Skipping duplicate segment: {}", clean_text);
+            log_info!("Skipping duplicate segment: {}", logging::redact(&clean_text));
             return None;
         }
         self.last_segment_hash = segment_hash;
@@ -174,6 +628,7 @@ impl TranscriptAccumulator {
         // If this is the start of a new sentence, store the start time
         if self.current_sentence.is_empty() {
             self.sentence_start_time = segment.t0;
+            self.sentence_speaker = segment.speaker.clone();
         }
 
         // Add the new text with proper spacing
@@ -181,6 +636,26 @@ impl TranscriptAccumulator {
             self.current_sentence.push(' ');
         }
         self.current_sentence.push_str(&clean_text);
+        self.remember_words(&clean_text);
+        let chunk_start = self.current_chunk_start_time;
+        self.current_words.extend(
+            segment.words
+                .iter()
+                .filter(|w| !w.word.starts_with('['))
+                .skip(repeated_words)
+                .map(|w| TranscriptWord {
+                    word: w.word.trim().to_string(),
+                    start: chunk_start + w.t0 as f64 / 100.0,
+                    end: chunk_start + w.t1 as f64 / 100.0,
+                    confidence: w.p,
+                }),
+        );
+        if let Some(translation) = segment.translation.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            if !self.current_translation.is_empty() {
+                self.current_translation.push(' ');
+            }
+            self.current_translation.push_str(translation);
+        }
 
         // Check if we have a complete sentence (including common sentence endings)
         let has_sentence_ending = clean_text.ends_with('.') || clean_text.ends_with('?') || clean_text.ends_with('!') ||
@@ -206,18 +681,50 @@ impl TranscriptAccumulator {
             let update = TranscriptUpdate {
                 text: sentence.trim().to_string(),
                 timestamp: format!("{}", format_timestamp(start_elapsed)),
-                source: "Mixed Audio".to_string(),
+                source: self.source.label().to_string(),
                 sequence_id,
                 chunk_start_time: self.current_chunk_start_time,
                 is_partial: false,
+                speaker: self.sentence_speaker.take(),
+                translated_text: self.take_translation(),
+                words: std::mem::take(&mut self.current_words),
             };
-            log_info!("Generated transcript update: {:?}", update);
+            log_info!(
+                "Generated transcript update {} ({}, partial: {}): {}",
+                update.sequence_id,
+                update.source,
+                update.is_partial,
+                logging::redact(&update.text)
+            );
             Some(update)
         } else {
             None
         }
     }
 
+    fn take_translation(&mut self) -> Option<String> {
+        Some(std::mem::take(&mut self.current_translation)).filter(|t| !t.is_empty())
+    }
+
+    // Whatever sentence is still open, e.g. when the worker stops
+    fn take_partial(&mut self) -> Option<TranscriptUpdate> {
+        if self.current_sentence.is_empty() {
+            return None;
+        }
+        let sentence = std::mem::take(&mut self.current_sentence);
+        Some(TranscriptUpdate {
+            text: sentence.trim().to_string(),
+            timestamp: format!("{}", format_timestamp(self.current_chunk_start_time + (self.sentence_start_time as f64 / 1000.0))),
+            source: self.source.label().to_string(),
+            sequence_id: SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+            chunk_start_time: self.current_chunk_start_time,
+            is_partial: true,
+            speaker: self.sentence_speaker.take(),
+            translated_text: self.take_translation(),
+            words: std::mem::take(&mut self.current_words),
+        })
+    }
+
     fn check_timeout(&mut self) -> Option<TranscriptUpdate> {
         if !self.current_sentence.is_empty() && 
            self.last_update_time.elapsed() > Duration::from_millis(SENTENCE_TIMEOUT_MS) {
@@ -240,10 +747,13 @@ impl TranscriptAccumulator {
             let update = TranscriptUpdate {
                 text: sentence.trim().to_string(),
                 timestamp: format!("{}", format_timestamp(start_elapsed)),
-                source: "Mixed Audio".to_string(),
+                source: self.source.label().to_string(),
                 sequence_id,
                 chunk_start_time: self.current_chunk_start_time,
                 is_partial: true,
+                speaker: self.sentence_speaker.take(),
+                translated_text: self.take_translation(),
+                words: std::mem::take(&mut self.current_words),
             };
             Some(update)
         } else {
@@ -252,28 +762,132 @@ impl TranscriptAccumulator {
     }
 }
 
+fn enqueue_chunk<R: Runtime>(queue: &Mutex<VecDeque<AudioChunk>>, audio_chunk: AudioChunk, app_handle: &AppHandle<R>) {
+    let chunk_id = audio_chunk.chunk_id;
+    let source = audio_chunk.source;
+    if let Ok(mut queue_guard) = queue.lock() {
+        // Move the oldest chunks to disk if the queue is full; they are only lost if that fails
+        while queue_guard.len() >= MAX_AUDIO_QUEUE_SIZE {
+            if let Some(dropped_chunk) = queue_guard.pop_front() {
+                match chunk_spill::spill(app_handle, &dropped_chunk) {
+                    Ok(path) => {
+                        session_recovery::chunk_done(dropped_chunk.chunk_id);
+                        session_recovery::record_spilled(&path);
+                        let spill_count = SPILLED_CHUNK_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+                        log_info!("Spilled {:?} audio chunk {} to {:?} due to queue overflow (total spilled: {})", dropped_chunk.source, dropped_chunk.chunk_id, path, spill_count);
+                        if spill_count == 1 {
+                            if let Err(e) = app_handle.emit("chunk-spilled", dropped_chunk.chunk_id) {
+                                log_error!("Failed to emit chunk-spilled event: {}", e);
+                            }
+                        }
+                        continue;
+                    }
+                    Err(e) => log_error!("{}", e),
+                }
+                let drop_count = DROPPED_CHUNK_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
+                log_info!("Dropped old {:?} audio chunk {} due to queue overflow (total drops: {})", dropped_chunk.source, dropped_chunk.chunk_id, drop_count);
+                
+                // // Emit warning event every 10th drop
+                // if drop_count % 10 == 0 {
+                if drop_count == 1 {
+                    let warning_message = format!("Transcription process is very slow. Audio chunk {} was dropped. Please choose a smaller model, or run whisper natively.", dropped_chunk.chunk_id);
+                    log_info!("Emitting chunk-drop-warning event: {}", warning_message);
+                    activity::record(app_handle, activity::ActivityKind::ChunksDropped, &warning_message, None, None);
+                    
+                    if let Err(e) = app_handle.emit("chunk-drop-warning", &warning_message) {
+                        log_error!("Failed to emit chunk-drop-warning event: {}", e);
+                    }
+                }
+            }
+        }
+        queue_guard.push_back(audio_chunk);
+        log_info!("Added {:?} chunk {} to queue (queue size: {})", source, chunk_id, queue_guard.len());
+    }
+}
+
+// Mic clean-up at the whisper rate, rebuilt whenever the device's filters change
+struct MicFilterChain {
+    high_pass: Option<HighPassFilter>,
+    noise_gate: Option<NoiseGate>,
+}
+
+impl MicFilterChain {
+    fn new(filters: &pipeline_config::DeviceFilters) -> Self {
+        Self {
+            high_pass: filters
+                .high_pass_enabled
+                .then(|| HighPassFilter::new(filters.high_pass_hz, WHISPER_SAMPLE_RATE)),
+            noise_gate: filters
+                .noise_gate_enabled
+                .then(|| NoiseGate::new(filters.noise_gate_threshold_db, WHISPER_SAMPLE_RATE)),
+        }
+    }
+
+    fn process(&mut self, samples: &mut [f32]) {
+        if let Some(filter) = self.high_pass.as_mut() {
+            filter.process(samples);
+        }
+        // After the high-pass, so rumble doesn't hold the gate open
+        if let Some(gate) = self.noise_gate.as_mut() {
+            gate.process(samples);
+        }
+    }
+}
+
 async fn audio_collection_task<R: Runtime>(
     mic_stream: Arc<AudioStream>,
     system_stream: Arc<AudioStream>,
-    is_running: Arc<AtomicBool>,
-    sample_rate: u32,
+    handles: SessionHandles,
+    recording_sender: mpsc::UnboundedSender<Vec<f32>>,
+    mic_sample_rate: u32,
+    system_sample_rate: u32,
     recording_start_time: std::time::Instant,
     app_handle: AppHandle<R>,
+    streaming: bool,
 ) -> Result<(), String> {
     log_info!("Audio collection task started");
     
     let mut mic_receiver = mic_stream.subscribe().await;
     let mut system_receiver = system_stream.subscribe().await;
     
-    let chunk_samples = (WHISPER_SAMPLE_RATE as f32 * (CHUNK_DURATION_MS as f32 / 1000.0)) as usize;
+    let (mut config_watcher, mut config) = pipeline_config::ConfigWatcher::start(&app_handle);
+    let mut mic_filters = config.filters_for(&mic_stream.device.name);
+    let mut mic_chain = MicFilterChain::new(&mic_filters);
+    // Runs at the device rate on every block, so loudness matching sees the corrected level
+    let new_agc = |config: &pipeline_config::PipelineConfig| {
+        config.agc_enabled.then(|| AutoGainControl::new(config.agc_target_db, mic_sample_rate))
+    };
+    let mut mic_agc = new_agc(&config);
+    // Works on whole chunks at the whisper rate, where mic and system audio line up
+    let new_echo_canceller = |config: &pipeline_config::PipelineConfig| {
+        if !config.echo_cancellation {
+            return None;
+        }
+        match EchoCanceller::new(WHISPER_SAMPLE_RATE) {
+            Ok(canceller) => Some(canceller),
+            Err(e) => {
+                log_warn!("Recording without echo cancellation: {}", e);
+                None
+            }
+        }
+    };
+    let mut echo_canceller = new_echo_canceller(&config);
+    let (mut chunk_samples, mut overlap_samples) = chunk_sizes(&config, streaming);
     let min_samples = (WHISPER_SAMPLE_RATE as f32 * (MIN_CHUNK_DURATION_MS as f32 / 1000.0)) as usize;
-    let mut current_chunk: Vec<f32> = Vec::with_capacity(chunk_samples);
+    let mut mic_chunk: Vec<f32> = Vec::with_capacity(chunk_samples);
+    let mut system_chunk: Vec<f32> = Vec::with_capacity(chunk_samples);
+    // End of the previous chunk per source, sent again ahead of the next one
+    let mut mic_overlap: Vec<f32> = Vec::new();
+    let mut system_overlap: Vec<f32> = Vec::new();
+    // 16kHz samples cut into chunks so far, to locate chunks in the recording for crash recovery
+    let mut chunked_samples: u64 = 0;
     let mut last_chunk_time = std::time::Instant::now();
     let chunk_start_time = std::time::Instant::now();
+    let mut gain_matcher = GainMatcher::new();
+    let mut level_meter = LevelMeter::new();
     
-    while is_running.load(Ordering::SeqCst) {
+    while handles.is_running.load(Ordering::SeqCst) {
         // Collect audio samples
-        let mut new_samples = Vec::new();
         let mut mic_samples = Vec::new();
         let mut system_samples = Vec::new();
         
@@ -288,76 +902,135 @@ async fn audio_collection_task<R: Runtime>(
             log_debug!("Received {} system samples", chunk.len());
             system_samples.extend(chunk);
         }
+        // Keep the timeline running through a privacy pause or mute, but never keep what was played
+        if privacy_pause::is_paused() || mixer::is_muted(CaptureSource::System) {
+            system_samples.fill(0.0);
+        }
+        if mixer::is_muted(CaptureSource::Mic) {
+            mic_samples.fill(0.0);
+        }
+        if let Some(agc) = mic_agc.as_mut() {
+            agc.process(&mut mic_samples);
+        }
         
-        // Mix samples (80% mic, 20% system)
-        let max_len = mic_samples.len().max(system_samples.len());
-        for i in 0..max_len {
-            let mic_sample = if i < mic_samples.len() { mic_samples[i] } else { 0.0 };
-            let system_sample = if i < system_samples.len() { system_samples[i] } else { 0.0 };
-            new_samples.push((mic_sample * 0.8) + (system_sample * 0.2));
+        gain_matcher.observe(&mic_samples, &system_samples);
+        level_meter.observe(&mic_samples, &system_samples);
+        if let Some(levels) = level_meter.take_if_due() {
+            if let Err(e) = app_handle.emit("audio-level", levels) {
+                log_debug!("Failed to emit audio-level event: {}", e);
+            }
         }
         
-        // Add samples to current chunk
-        for sample in new_samples {
-            current_chunk.push(sample);
+        // The recording is kept at the microphone's rate, untouched by the transcription
+        // processing, and written as it is captured. System audio is brought to that rate
+        // first, so the mix (and the loudness envelope used for duplicate-recording
+        // detection) has one timeline.
+        let (mic_gain, system_gain) = gain_matcher.gains();
+        let system_recording = audio::audio_processing::resample_linear(&system_samples, system_sample_rate, mic_sample_rate);
+        let recording_mix = mix_samples(&mic_samples, &system_recording, (mic_gain * config.mic_level, system_gain * config.system_level));
+        duplicates::observe_samples(&recording_mix, mic_sample_rate);
+        if !recording_mix.is_empty() && recording_sender.send(recording_mix).is_err() {
+            log_debug!("Recording writer has stopped, audio not saved");
         }
         
-        // Check if we should create a chunk
-        let should_create_chunk = current_chunk.len() >= chunk_samples || 
-                                (current_chunk.len() >= min_samples && 
-                                 last_chunk_time.elapsed() >= Duration::from_millis(CHUNK_DURATION_MS as u64));
+        mic_chunk.extend(mic_samples);
+        system_chunk.extend(system_samples);
         
-        if should_create_chunk && !current_chunk.is_empty() {
-            // Process chunk for Whisper API
-            let whisper_samples = if sample_rate != WHISPER_SAMPLE_RATE {
-                log_debug!("Resampling audio from {} to {}", sample_rate, WHISPER_SAMPLE_RATE);
-                resample_audio(&current_chunk, sample_rate, WHISPER_SAMPLE_RATE)
-            } else {
-                current_chunk.clone()
+        // Both sources are cut on the same schedule so their chunks share a timestamp
+        let longest = mic_chunk.len().max(system_chunk.len());
+        let should_create_chunk = longest >= chunk_samples || 
+                                (longest >= min_samples && 
+                                 last_chunk_time.elapsed() >= Duration::from_millis(config.chunk_duration_ms as u64));
+        
+        if should_create_chunk {
+            // Process chunks for Whisper API
+            let to_whisper_rate = |samples: &[f32], sample_rate: u32| {
+                if sample_rate != WHISPER_SAMPLE_RATE {
+                    log_debug!("Resampling audio from {} to {}", sample_rate, WHISPER_SAMPLE_RATE);
+                    resample_audio(samples, sample_rate, WHISPER_SAMPLE_RATE)
+                } else {
+                    samples.to_vec()
+                }
             };
+            let mut mic_whisper = to_whisper_rate(&mic_chunk, mic_sample_rate);
+            let mut system_whisper = to_whisper_rate(&system_chunk, system_sample_rate);
             
-            // Create audio chunk
-            let chunk_id = CHUNK_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
-            let chunk_timestamp = chunk_start_time.elapsed().as_secs_f64();
-            let audio_chunk = AudioChunk {
-                samples: whisper_samples,
-                timestamp: chunk_timestamp,
-                chunk_id,
-                start_time: std::time::Instant::now(),
-                recording_start_time,
-            };
+            if let Some(canceller) = echo_canceller.as_mut() {
+                if let Err(e) = canceller.process(&mut mic_whisper, &system_whisper) {
+                    log_warn!("{}", e);
+                }
+            }
+            mic_chain.process(&mut mic_whisper);
             
-            // Add to queue (with overflow protection)
-            unsafe {
-                if let Some(queue) = &AUDIO_CHUNK_QUEUE {
-                    if let Ok(mut queue_guard) = queue.lock() {
-                        // Remove oldest chunks if queue is full
-                        while queue_guard.len() >= MAX_AUDIO_QUEUE_SIZE {
-                            if let Some(dropped_chunk) = queue_guard.pop_front() {
-                                let drop_count = DROPPED_CHUNK_COUNTER.fetch_add(1, Ordering::SeqCst) + 1;
-                                log_info!("Dropped old audio chunk {} due to queue overflow (total drops: {})", dropped_chunk.chunk_id, drop_count);
-                                
-                                // // Emit warning event every 10th drop
-                                // if drop_count % 10 == 0 {
-                                if drop_count == 1 {
-                                    let warning_message = format!("Transcription process is very slow. Audio chunk {} was dropped. Please choose a smaller model, or run whisper natively.", dropped_chunk.chunk_id);
-                                    log_info!("Emitting chunk-drop-warning event: {}", warning_message);
-                                    
-                                    if let Err(e) = app_handle.emit("chunk-drop-warning", &warning_message) {
-                                        log_error!("Failed to emit chunk-drop-warning event: {}", e);
-                                    }
-                                }
-                            }
-                        }
-                        queue_guard.push_back(audio_chunk);
-                        log_info!("Added chunk {} to queue (queue size: {})", chunk_id, queue_guard.len());
-                    }
+            // Level-match both sides so neither is drowned out or overpowering
+            let (mic_gain, system_gain) = gain_matcher.gains();
+            let (mic_gain, system_gain) = (mic_gain * config.mic_level, system_gain * config.system_level);
+            log_debug!("Applying gains: mic {:.2}, system {:.2}", mic_gain, system_gain);
+            apply_gain(&mut mic_whisper, mic_gain);
+            apply_gain(&mut system_whisper, system_gain);
+            let mixed_whisper = mix_samples(&mic_whisper, &system_whisper, (1.0, 1.0));
+            duplicates::observe_whisper_samples(&mixed_whisper);
+            let recording_offset = chunked_samples;
+            chunked_samples += mixed_whisper.len() as u64;
+            
+            let chunk_timestamp = chunk_start_time.elapsed().as_secs_f64();
+            for (source, chunk, overlap) in [
+                (CaptureSource::Mic, mic_whisper, &mut mic_overlap),
+                (CaptureSource::System, system_whisper, &mut system_overlap),
+            ] {
+                let overlap_secs = overlap.len() as f64 / WHISPER_SAMPLE_RATE as f64;
+                let mut samples = std::mem::take(overlap);
+                samples.extend_from_slice(&chunk);
+                overlap.extend_from_slice(&chunk[chunk.len().saturating_sub(overlap_samples)..]);
+                // The streaming server needs a continuous timeline, silence included
+                if !streaming && !chunk_has_speech(&samples, &config) {
+                    let skipped = SKIPPED_SILENT_CHUNKS.fetch_add(1, Ordering::SeqCst) + 1;
+                    log_debug!("Skipping {:?} chunk without speech (total skipped: {})", source, skipped);
+                    continue;
                 }
+                let chunk_id = CHUNK_ID_COUNTER.fetch_add(1, Ordering::SeqCst);
+                session_recovery::chunk_queued(chunk_id, recording_offset, recording_offset + chunk.len() as u64);
+                let audio_chunk = AudioChunk {
+                    samples,
+                    source,
+                    timestamp: (chunk_timestamp - overlap_secs).max(0.0),
+                    overlap_secs,
+                    chunk_id,
+                    start_time: std::time::Instant::now(),
+                    recording_start_time,
+                };
+                enqueue_chunk(handles.queue(source), audio_chunk, &app_handle);
             }
             
+            session_recovery::chunked_until(chunked_samples);
+            
             // Reset for next chunk
-            current_chunk.clear();
+            mic_chunk.clear();
+            system_chunk.clear();
             last_chunk_time = std::time::Instant::now();
+            
+            // Configuration changes only take effect between chunks
+            if let Some(new_config) = config_watcher.poll() {
+                let applied_at = recording_start_time.elapsed().as_secs_f64();
+                log_info!("Applying pipeline config at {:.1}s: {:?}", applied_at, new_config);
+                let new_filters = new_config.filters_for(&mic_stream.device.name);
+                if new_filters != mic_filters {
+                    mic_chain = MicFilterChain::new(&new_filters);
+                    mic_filters = new_filters;
+                }
+                if (new_config.agc_enabled, new_config.agc_target_db) != (config.agc_enabled, config.agc_target_db) {
+                    mic_agc = new_agc(&new_config);
+                }
+                if new_config.echo_cancellation != config.echo_cancellation {
+                    echo_canceller = new_echo_canceller(&new_config);
+                }
+                (chunk_samples, overlap_samples) = chunk_sizes(&new_config, streaming);
+                pipeline_config::record_applied(applied_at, &new_config);
+                if let Err(e) = app_handle.emit("pipeline-config-applied", &new_config) {
+                    log_error!("Failed to emit pipeline-config-applied event: {}", e);
+                }
+                config = new_config;
+            }
         }
         
         // Small sleep to prevent busy waiting
@@ -368,96 +1041,378 @@ async fn audio_collection_task<R: Runtime>(
     Ok(())
 }
 
-async fn send_audio_chunk(chunk: Vec<f32>, client: &reqwest::Client, stream_url: &str) -> Result<TranscriptResponse, String> {
-    log_debug!("Preparing to send audio chunk of size: {}", chunk.len());
-    
-    // Convert f32 samples to bytes
-    let bytes: Vec<u8> = chunk.iter()
-        .flat_map(|&sample| {
-            let clamped = sample.max(-1.0).min(1.0);
-            clamped.to_le_bytes().to_vec()
+// Chunk length and overlap in 16kHz samples; streaming sends short frames without overlap
+fn chunk_sizes(config: &pipeline_config::PipelineConfig, streaming: bool) -> (usize, usize) {
+    if streaming {
+        return (streaming::frame_samples(), 0);
+    }
+    let chunk = (WHISPER_SAMPLE_RATE as f32 * (config.chunk_duration_ms as f32 / 1000.0)) as usize;
+    let overlap = (WHISPER_SAMPLE_RATE as f32 * (config.chunk_overlap_ms as f32 / 1000.0)) as usize;
+    (chunk, overlap)
+}
+
+// Append the mixed recording to disk as it arrives. Every captured batch is flushed, so an
+// app crash loses at most the batch being written; the file is synced to disk every few
+// seconds. The task ends once the collection task drops its sender.
+async fn recording_writer_task(
+    mut receiver: mpsc::UnboundedReceiver<Vec<f32>>,
+    mut writer: WavWriter,
+) -> Result<(std::path::PathBuf, u64, u32), String> {
+    let mut sync_interval = tokio::time::interval(RECORDING_SYNC_INTERVAL);
+    let mut unsynced = false;
+    loop {
+        tokio::select! {
+            samples = receiver.recv() => match samples {
+                Some(samples) => {
+                    writer.write_samples(&samples).map_err(|e| format!("Failed to write recording: {}", e))?;
+                    writer.flush().map_err(|e| format!("Failed to flush recording: {}", e))?;
+                    session_recovery::checkpoint();
+                    unsynced = true;
+                }
+                None => break,
+            },
+            _ = sync_interval.tick() => {
+                if unsynced {
+                    writer.sync().map_err(|e| format!("Failed to sync recording: {}", e))?;
+                    log_debug!("Recording synced ({} samples)", writer.samples_written());
+                    unsynced = false;
+                }
+            }
+        }
+    }
+    let sample_rate = writer.sample_rate();
+    let (path, samples) = writer.finalize().map_err(|e| format!("Failed to finalize recording: {}", e))?;
+    Ok((path, samples, sample_rate))
+}
+
+// Mix samples with equal weight after applying the matched (mic, system) gains
+fn mix_samples(mic_samples: &[f32], system_samples: &[f32], (mic_gain, system_gain): (f32, f32)) -> Vec<f32> {
+    let max_len = mic_samples.len().max(system_samples.len());
+    (0..max_len)
+        .map(|i| {
+            let mic_sample = mic_samples.get(i).copied().unwrap_or(0.0) * mic_gain;
+            let system_sample = system_samples.get(i).copied().unwrap_or(0.0) * system_gain;
+            ((mic_sample + system_sample) * 0.5).clamp(-1.0, 1.0)
         })
-        .collect();
+        .collect()
+}
+
+// VAD gate in front of the transcription queue. Near-silent audio is rejected by level
+// alone; the rest goes through the WebRTC VAD unless it is disabled.
+fn chunk_has_speech(samples: &[f32], config: &pipeline_config::PipelineConfig) -> bool {
+    if samples.is_empty() || rms(samples) < config.silence_threshold() {
+        return false;
+    }
+    if !config.vad_enabled {
+        return true;
+    }
+    match vad::contains_speech(samples, config.vad_sensitivity) {
+        Ok(has_speech) => has_speech,
+        Err(e) => {
+            // Never lose audio because the detector failed
+            log_warn!("VAD failed, sending chunk anyway: {}", e);
+            true
+        }
+    }
+}
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+// Build a streaming request body that converts samples to little-endian bytes one
+// part at a time, so the upload never holds a second full copy of the chunk in memory.
+fn audio_body_stream(samples: Arc<Vec<f32>>, part_size_bytes: usize, format: SampleFormat) -> reqwest::Body {
+    let samples_per_part = (part_size_bytes / format.bytes_per_sample()).max(1);
+    let total_samples = samples.len();
+    let parts = (0..total_samples).step_by(samples_per_part).map(move |start| {
+        let end = (start + samples_per_part).min(total_samples);
+        let bytes = format.encode(&samples[start..end]);
+        Ok::<_, std::io::Error>(bytes::Bytes::from(bytes))
+    });
+    reqwest::Body::wrap_stream(futures_util::stream::iter(parts))
+}
+
+async fn send_audio_chunk(samples: Arc<Vec<f32>>, client: &reqwest::Client, stream_url: &str, config: &TranscriptionConfig) -> Result<TranscriptResponse, String> {
+    log_debug!("Preparing to send audio chunk of size: {}", samples.len());
+    
+    // Samples are shared between retries; each attempt streams them again
+    let body_len = (samples.len() * config.sample_format.bytes_per_sample()) as u64;
+    let part_size_bytes = UPLOAD_PART_SIZE_BYTES.load(Ordering::SeqCst);
     
-    // Retry configuration
-    let max_retries = 3;
-    let mut retry_count = 0;
-    let mut last_error = String::new();
-
-    while retry_count <= max_retries {
-        if retry_count > 0 {
-            // Exponential backoff: wait 2^retry_count * 100ms
-            let delay = Duration::from_millis(100 * (2_u64.pow(retry_count as u32)));
-            log::info!("Retry attempt {} of {}. Waiting {:?} before retry...", 
-                      retry_count, max_retries, delay);
-            tokio::time::sleep(delay).await;
-        }
-
-        // Create fresh multipart form for each attempt since Form can't be reused
-        let part = Part::bytes(bytes.clone())
+    // Create fresh multipart form for each attempt since Form can't be reused
+    let build = || {
+        let part = Part::stream_with_length(audio_body_stream(samples.clone(), part_size_bytes, config.sample_format), body_len)
             .file_name("audio.raw")
             .mime_str("audio/x-raw")
             .unwrap();
-        let form = Form::new().part("audio", part);
-
-        match client.post(stream_url)
-            .multipart(form)
-            .send()
-            .await {
-                Ok(response) => {
-                    match response.json::<TranscriptResponse>().await {
-                        Ok(transcript) => return Ok(transcript),
-                        Err(e) => {
-                            last_error = e.to_string();
-                            log::error!("Failed to parse response: {}", last_error);
-                        }
-                    }
-                }
-                Err(e) => {
-                    last_error = e.to_string();
-                    log::error!("Request failed: {}", last_error);
-                }
-            }
+        let mut form = Form::new().part("audio", part).text("format", config.sample_format.as_str());
+        if let Some(language) = &config.language {
+            form = form.text("language", language.clone());
+        }
+        if config.translate {
+            form = form.text("translate", "true");
+        }
+        if config.server_diarization {
+            form = form.text("diarize", "true");
+        }
+        client.post(stream_url).multipart(form)
+    };
+
+    let response = http::send(&config.retry.request_options(), "Audio chunk upload", build).await?;
+    response.json::<TranscriptResponse>().await.map_err(|e| {
+        log::error!("Failed to parse response: {}", e);
+        format!("Failed to parse transcription response: {}", e)
+    })
+}
+
+// Transcribe a chunk in-process. whisper.cpp is CPU bound, so it runs on the blocking pool.
+async fn transcribe_locally(
+    samples: Arc<Vec<f32>>,
+    model_path: std::path::PathBuf,
+    language: Option<String>,
+    translate: bool,
+) -> Result<TranscriptResponse, String> {
+    tokio::task::spawn_blocking(move || {
+        let model = audio::whisper_local::get_or_load(&model_path).map_err(|e| e.to_string())?;
+        let (segments, language) = model.transcribe_language(&samples, language.as_deref(), translate).map_err(|e| e.to_string())?;
+        Ok(TranscriptResponse {
+            segments: segments
+                .into_iter()
+                .map(|s| TranscriptSegment {
+                    text: s.text,
+                    t0: s.t0 as f32,
+                    t1: s.t1 as f32,
+                    speaker: None,
+                    translation: s.translation,
+                    words: s
+                        .words
+                        .into_iter()
+                        .map(|w| SegmentWord { word: w.word, t0: w.t0 as f32, t1: w.t1 as f32, p: Some(w.p) })
+                        .collect(),
+                })
+                .collect(),
+            buffer_size_ms: 0,
+            language,
+        })
+    })
+    .await
+    .map_err(|e| format!("Local transcription task failed: {}", e))?
+}
+
+async fn transcribe_with_openai<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    samples: Arc<Vec<f32>>,
+    client: &reqwest::Client,
+    config: &TranscriptionConfig,
+) -> Result<TranscriptResponse, String> {
+    let api_key = transcription_failover::openai_api_key(app_handle).await?;
+    let language = config.language.as_deref().filter(|language| *language != AUTO_LANGUAGE);
+    let started = std::time::Instant::now();
+    let result = audio::openai::transcribe(client, &api_key, &config.failover.openai_model, &samples, WHISPER_SAMPLE_RATE, language)
+        .await
+        .map_err(|e| e.to_string());
+    telemetry::record(Dependency::CloudProvider, started.elapsed(), result.as_ref().err().map(String::as_str));
+    Ok(TranscriptResponse {
+        // Segment times are converted to whisper's 10ms units
+        segments: result?
+            .into_iter()
+            .map(|s| TranscriptSegment {
+                text: s.text,
+                t0: (s.start * 100.0) as f32,
+                t1: (s.end * 100.0) as f32,
+                speaker: None,
+                translation: None,
+                words: Vec::new(),
+            })
+            .collect(),
+        buffer_size_ms: 0,
+        language: None,
+    })
+}
 
-        retry_count += 1;
+// Transcribe a chunk with the primary engine, switching the rest of the recording to the
+// fallback provider once the primary has failed too often in a row
+async fn transcribe_chunk<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    samples: Arc<Vec<f32>>,
+    client: &reqwest::Client,
+    stream_url: &str,
+    config: &TranscriptionConfig,
+    handles: &SessionHandles,
+) -> Result<TranscriptResponse, String> {
+    transcribe_with_failover(
+        handles,
+        config,
+        |engine| {
+            let samples = samples.clone();
+            async move { transcribe_with_engine(app_handle, &engine, samples, client, stream_url, config, handles).await }
+        },
+        |fallback, error| transcription_failover::record_switch(app_handle, &handles.failover, config, fallback, error),
+    )
+    .await
+}
+
+async fn transcribe_with_failover<T, Fut>(
+    handles: &SessionHandles,
+    config: &TranscriptionConfig,
+    transcribe: T,
+    on_switch: impl FnOnce(&AudioTranscriptionEngine, &str),
+) -> Result<TranscriptResponse, String>
+where
+    T: Fn(AudioTranscriptionEngine) -> Fut,
+    Fut: std::future::Future<Output = Result<TranscriptResponse, String>>,
+{
+    let engine = handles.failover.active(config);
+    let error = match transcribe(engine.clone()).await {
+        Ok(response) => {
+            handles.failover.succeeded();
+            return Ok(response);
+        }
+        Err(e) => e,
+    };
+    if engine != config.engine {
+        return Err(error);
+    }
+
+    let Some(fallback) = handles.failover.failed(config) else {
+        return Err(error);
+    };
+    on_switch(&fallback, &error);
+    transcribe(fallback.clone())
+        .await
+        .map_err(|fallback_error| format!("{}; failover to {} failed: {}", error, fallback, fallback_error))
+}
+
+// Whether a failed chunk should stop the recording. Only the first error of a burst stops
+// it; while failover can still take over, failures just count towards the switch and the
+// chunk waits in the dead-letter store.
+fn should_stop_after_failure(handles: &SessionHandles, config: &TranscriptionConfig) -> bool {
+    if handles.failover.can_fail_over(config) {
+        return false;
     }
+    let mut window = match handles.error_window.lock() {
+        Ok(window) => window,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let now = std::time::Instant::now();
+    match window.last_error_time {
+        Some(last_time) if now.duration_since(last_time).as_secs() < 30 => window.count += 1,
+        _ => window.count = 1,
+    }
+    window.last_error_time = Some(now);
+
+    let should_stop = window.count == 1 && !handles.error_event_emitted.load(Ordering::SeqCst);
+    if should_stop {
+        window.count = 0;
+        window.last_error_time = None;
+    }
+    should_stop
+}
+
+async fn transcribe_with_engine<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    engine: &AudioTranscriptionEngine,
+    samples: Arc<Vec<f32>>,
+    client: &reqwest::Client,
+    stream_url: &str,
+    config: &TranscriptionConfig,
+    handles: &SessionHandles,
+) -> Result<TranscriptResponse, String> {
+    match engine {
+        AudioTranscriptionEngine::WhisperLocal => {
+            return match &config.local_model {
+                Some(model_path) => transcribe_locally(samples, model_path.clone(), config.language.clone(), config.translate).await,
+                None => Err("No local whisper model configured".to_string()),
+            };
+        }
+        AudioTranscriptionEngine::OpenAi => return transcribe_with_openai(app_handle, samples, client, config).await,
+        _ => {}
+    }
+
+    let server_error = match send_audio_chunk(samples.clone(), client, stream_url, config).await {
+        Ok(response) => return Ok(response),
+        Err(e) => e,
+    };
+
+    let model_path = match &config.local_model {
+        Some(model_path) => model_path.clone(),
+        None => return Err(server_error),
+    };
+
+    log_warn!("Transcription server failed ({}), falling back to local whisper model", server_error);
+    if !handles.fallback_notified.swap(true, Ordering::SeqCst) {
+        meeting_metadata::set_field("transcription", "fallback_used", serde_json::json!(true));
+        if let Err(e) = app_handle.emit("transcription-engine-fallback", model_path.display().to_string()) {
+            log_error!("Failed to emit transcription-engine-fallback event: {}", e);
+        }
+    }
+
+    transcribe_locally(samples, model_path, config.language.clone(), config.translate)
+        .await
+        .map_err(|local_error| format!("{}; local fallback failed: {}", server_error, local_error))
+}
+
+// Segment times are in whisper's 10ms units
+async fn assign_speakers(diarizer: Arc<Mutex<Diarizer>>, samples: Arc<Vec<f32>>, segments: &mut [TranscriptSegment]) {
+    let ranges: Vec<(usize, usize)> = segments
+        .iter()
+        .map(|segment| {
+            let start = ((segment.t0.max(0.0) as usize) * WHISPER_SAMPLE_RATE as usize / 100).min(samples.len());
+            let end = ((segment.t1.max(0.0) as usize) * WHISPER_SAMPLE_RATE as usize / 100).min(samples.len());
+            (start, end.max(start))
+        })
+        .collect();
 
-    Err(format!("Failed after {} retries. Last error: {}", max_retries, last_error))
+    let speakers = tokio::task::spawn_blocking(move || {
+        let mut diarizer = match diarizer.lock() {
+            Ok(diarizer) => diarizer,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        ranges
+            .into_iter()
+            .map(|(start, end)| {
+                diarizer.identify(&samples[start..end]).unwrap_or_else(|e| {
+                    log_warn!("Speaker identification failed: {}", e);
+                    None
+                })
+            })
+            .collect::<Vec<_>>()
+    })
+    .await;
+
+    match speakers {
+        Ok(speakers) => {
+            for (segment, speaker) in segments.iter_mut().zip(speakers) {
+                segment.speaker = speaker;
+            }
+        }
+        Err(e) => log_error!("Diarization task failed: {}", e),
+    }
 }
 
 async fn transcription_worker<R: Runtime>(
     client: reqwest::Client,
     stream_url: String,
     app_handle: AppHandle<R>,
+    handles: SessionHandles,
+    config: TranscriptionConfig,
+    source: CaptureSource,
     worker_id: usize,
 ) {
-    log_info!("Transcription worker {} started", worker_id);
-    let mut accumulator = TranscriptAccumulator::new();
+    log_info!("Transcription worker {} started for {:?} audio", worker_id, source);
+    let mut accumulator = TranscriptAccumulator::new(source, handles.recent_words(source));
     
     // Increment active worker count
     ACTIVE_WORKERS.fetch_add(1, Ordering::SeqCst);
     
     // Worker continues until both recording is stopped AND queue is empty
     loop {
-        let is_running = unsafe { 
-            if let Some(is_running) = &IS_RUNNING {
-                is_running.load(Ordering::SeqCst)
-            } else {
-                false
-            }
-        };
-        
-        let queue_has_chunks = unsafe {
-            if let Some(queue) = &AUDIO_CHUNK_QUEUE {
-                if let Ok(queue_guard) = queue.lock() {
-                    !queue_guard.is_empty()
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        };
+        let is_running = handles.is_running.load(Ordering::SeqCst);
+        let queue_has_chunks = handles.source_queue_len(source) > 0;
         
         // Continue if recording is active OR if there are still chunks to process
         if !is_running && !queue_has_chunks {
@@ -467,8 +1422,7 @@ async fn transcription_worker<R: Runtime>(
         // Check for timeout on current sentence
         if let Some(update) = accumulator.check_timeout() {
             log_info!("Worker {}: Emitting timeout transcript-update event with sequence_id: {}", worker_id, update.sequence_id);
-            
-            if let Err(e) = app_handle.emit("transcript-update", &update) {
+            if let Err(e) = publish_update(&app_handle, &update) {
                 log_error!("Worker {}: Failed to send timeout transcript update: {}", worker_id, e);
             } else {
                 log_info!("Worker {}: Successfully emitted timeout transcript-update event", worker_id);
@@ -476,19 +1430,13 @@ async fn transcription_worker<R: Runtime>(
         }
         
         // Try to get a chunk from the queue
-        let audio_chunk = unsafe {
-            if let Some(queue) = &AUDIO_CHUNK_QUEUE {
-                if let Ok(mut queue_guard) = queue.lock() {
-                    queue_guard.pop_front()
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        };
+        let audio_chunk = handles
+            .queue(source)
+            .lock()
+            .ok()
+            .and_then(|mut queue_guard| queue_guard.pop_front());
         
-        if let Some(chunk) = audio_chunk {
+        if let Some(mut chunk) = audio_chunk {
             log_info!("Worker {}: Processing chunk {} with {} samples", 
                      worker_id, chunk.chunk_id, chunk.samples.len());
             
@@ -502,24 +1450,37 @@ async fn transcription_worker<R: Runtime>(
             );
             
             // Set chunk context in accumulator
-            accumulator.set_chunk_context(chunk.chunk_id, chunk.timestamp, chunk.recording_start_time);
+            accumulator.set_chunk_context(chunk.chunk_id, chunk.timestamp, chunk.overlap_secs, chunk.recording_start_time);
             
             // Send chunk for transcription
-            match send_audio_chunk(chunk.samples, &client, &stream_url).await {
-                Ok(response) => {
+            let samples = Arc::new(std::mem::take(&mut chunk.samples));
+            match transcribe_chunk(&app_handle, samples.clone(), &client, &stream_url, &config, &handles).await {
+                Ok(mut response) => {
                     log_info!("Worker {}: Received {} transcript segments for chunk {}", 
                              worker_id, response.segments.len(), chunk.chunk_id);
+                    session_recovery::chunk_done(chunk.chunk_id);
+                    usage_stats::record(
+                        &app_handle,
+                        usage_stats::UsageKind::Transcription,
+                        Some(chunk.start_time.elapsed().as_secs_f64()),
+                        Some(&handles.failover.active(&config).to_string()),
+                    );
+                    if let Some(language) = &response.language {
+                        note_detected_language(&app_handle, &handles, &config, source, language);
+                    }
+                    
+                    if let Some(diarizer) = &handles.diarizer {
+                        assign_speakers(diarizer.clone(), samples, &mut response.segments).await;
+                    }
                     
                     for segment in response.segments {
                         log_info!("Worker {}: Processing segment: {} ({} - {})", 
-                                 worker_id, segment.text.trim(), format_timestamp(segment.t0 as f64), format_timestamp(segment.t1 as f64));
+                                 worker_id, logging::redact(segment.text.trim()), format_timestamp(segment.t0 as f64), format_timestamp(segment.t1 as f64));
                         
                         // Add segment to accumulator and check for complete sentence
                         if let Some(update) = accumulator.add_segment(&segment) {
                             log_info!("Worker {}: Emitting transcript-update event with sequence_id: {}", worker_id, update.sequence_id);
-                            
-                            // Emit the update
-                            if let Err(e) = app_handle.emit("transcript-update", &update) {
+                            if let Err(e) = publish_update(&app_handle, &update) {
                                 log_error!("Worker {}: Failed to emit transcript update: {}", worker_id, e);
                             } else {
                                 log_info!("Worker {}: Successfully emitted transcript-update event", worker_id);
@@ -530,81 +1491,47 @@ async fn transcription_worker<R: Runtime>(
                 Err(e) => {
                     log_error!("Worker {}: Transcription error for chunk {}: {}", 
                               worker_id, chunk.chunk_id, e);
+                    match chunk_spill::dead_letter(&app_handle, &chunk, &samples, &e) {
+                        Ok(path) => log_warn!("Worker {}: Chunk {} kept for a later retry at {}", worker_id, chunk.chunk_id, path.display()),
+                        Err(spill_err) => log_error!("Worker {}: Chunk {} lost: {}", worker_id, chunk.chunk_id, spill_err),
+                    }
                     
-                    // Handle error similar to original logic
-                    static mut ERROR_COUNT: u32 = 0;
-                    static mut LAST_ERROR_TIME: Option<std::time::Instant> = None;
-                    
-                    unsafe {
-                        let now = std::time::Instant::now();
-                        if let Some(last_time) = LAST_ERROR_TIME {
-                            if now.duration_since(last_time).as_secs() < 30 {
-                                ERROR_COUNT += 1;
-                            } else {
-                                ERROR_COUNT = 1;
-                            }
+                    if should_stop_after_failure(&handles, &config) {
+                        crash::capture_task_error(&format!("Transcription worker {}", worker_id), &format!("Too many errors, stopping recording: {}", e));
+                        let error_msg = if e.contains("Failed to connect") || e.contains("Connection refused") {
+                            "Transcription service is not available. Please check if the server is running.".to_string()
+                        } else if e.contains("timeout") {
+                            "Transcription service is not responding. Please check your connection.".to_string()
                         } else {
-                            ERROR_COUNT = 1;
+                            format!("Transcription service error: {}", e)
+                        };
+                        
+                        if let Err(emit_err) = app_handle.emit("transcript-error", error_msg) {
+                            log_error!("Worker {}: Failed to emit transcript error: {}", worker_id, emit_err);
                         }
-                        LAST_ERROR_TIME = Some(now);
                         
-                        if ERROR_COUNT == 1 && !ERROR_EVENT_EMITTED {
-                            log_error!("Worker {}: Too many transcription errors, stopping recording", worker_id);
-                            let error_msg = if e.contains("Failed to connect") || e.contains("Connection refused") {
-                                "Transcription service is not available. Please check if the server is running.".to_string()
-                            } else if e.contains("timeout") {
-                                "Transcription service is not responding. Please check your connection.".to_string()
-                            } else {
-                                format!("Transcription service error: {}", e)
-                            };
-                            
-                            if let Err(emit_err) = app_handle.emit("transcript-error", error_msg) {
-                                log_error!("Worker {}: Failed to emit transcript error: {}", worker_id, emit_err);
-                            }
-                            
-                            ERROR_EVENT_EMITTED = true;
-                            RECORDING_FLAG.store(false, Ordering::SeqCst);
-                            if let Some(is_running) = &IS_RUNNING {
-                                is_running.store(false, Ordering::SeqCst);
+                        handles.error_event_emitted.store(true, Ordering::SeqCst);
+                        RECORDING_FLAG.store(false, Ordering::SeqCst);
+                        handles.is_running.store(false, Ordering::SeqCst);
+                        recording_indicator::set(&app_handle, recording_indicator::IndicatorState::Idle);
+                        meeting_timer::finish_recording();
+                        plugins::finish_recording();
+                        
+                        // Clean up audio streams when stopping due to errors
+                        let cleanup_handle = app_handle.clone();
+                        tokio::spawn(async move {
+                            let session = cleanup_handle
+                                .state::<RecordingState>()
+                                .lock()
+                                .ok()
+                                .and_then(|mut guard| guard.take());
+                            if let Some(session) = session {
+                                log_info!("Cleaning up audio streams after transcription error...");
+                                session.stop_streams().await;
                             }
-                            ERROR_COUNT = 0;
-                            LAST_ERROR_TIME = None;
-                            
-                            // Clean up audio streams when stopping due to errors
-                            tokio::spawn(async {
-                                unsafe {
-                                    // Stop mic stream if it exists
-                                    if let Some(mic_stream) = &MIC_STREAM {
-                                        log_info!("Cleaning up microphone stream after transcription error...");
-                                        if let Err(e) = mic_stream.stop().await {
-                                            log_error!("Error stopping mic stream: {}", e);
-                                        } else {
-                                            log_info!("Microphone stream cleaned up successfully");
-                                        }
-                                    }
-                                    
-                                    // Stop system stream if it exists
-                                    if let Some(system_stream) = &SYSTEM_STREAM {
-                                        log_info!("Cleaning up system stream after transcription error...");
-                                        if let Err(e) = system_stream.stop().await {
-                                            log_error!("Error stopping system stream: {}", e);
-                                        } else {
-                                            log_info!("System stream cleaned up successfully");
-                                        }
-                                    }
-                                    
-                                    // Clear the stream references
-                                    MIC_STREAM = None;
-                                    SYSTEM_STREAM = None;
-                                    IS_RUNNING = None;
-                                    TRANSCRIPTION_TASK = None;
-                                    AUDIO_COLLECTION_TASK = None;
-                                    AUDIO_CHUNK_QUEUE = None;
-                                }
-                            });
-                            
-                            return;
-                        }
+                        });
+                        
+                        return;
                     }
                 }
             }
@@ -617,8 +1544,7 @@ async fn transcription_worker<R: Runtime>(
     // Emit any remaining transcript when worker stops
     if let Some(update) = accumulator.check_timeout() {
         log_info!("Worker {}: Emitting final transcript-update event with sequence_id: {}", worker_id, update.sequence_id);
-        
-        if let Err(e) = app_handle.emit("transcript-update", &update) {
+        if let Err(e) = publish_update(&app_handle, &update) {
             log_error!("Worker {}: Failed to send final transcript update: {}", worker_id, e);
         } else {
             log_info!("Worker {}: Successfully emitted final transcript-update event", worker_id);
@@ -626,41 +1552,92 @@ async fn transcription_worker<R: Runtime>(
     }
     
     // Also flush any partial sentence that might not have been emitted
-    if !accumulator.current_sentence.is_empty() {
-        let sequence_id = SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst);
-        let update = TranscriptUpdate {
-            text: accumulator.current_sentence.trim().to_string(),
-            timestamp: format!("{}", format_timestamp(accumulator.current_chunk_start_time + (accumulator.sentence_start_time as f64 / 1000.0))),
-            source: "Mixed Audio".to_string(),
-            sequence_id,
-            chunk_start_time: accumulator.current_chunk_start_time,
-            is_partial: true,
-        };
-        log_info!("Worker {}: Flushing final partial sentence: {} with sequence_id: {}", worker_id, update.text, update.sequence_id);
-        
-        if let Err(e) = app_handle.emit("transcript-update", &update) {
+    if let Some(update) = accumulator.take_partial() {
+        log_info!("Worker {}: Flushing final partial sentence: {} with sequence_id: {}", worker_id, logging::redact(&update.text), update.sequence_id);
+        if let Err(e) = publish_update(&app_handle, &update) {
             log_error!("Worker {}: Failed to send final partial transcript: {}", worker_id, e);
         } else {
             log_info!("Worker {}: Successfully emitted final partial transcript-update event", worker_id);
         }
     }
     
+    worker_finished(&app_handle, &handles).await;
+    log_info!("Transcription worker {} ended", worker_id);
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct DetectedLanguage {
+    language: String,
+    source: String,
+}
+
+// With auto-detection on, tell the UI which language was picked and whenever it changes
+fn note_detected_language<R: Runtime>(
+    app_handle: &AppHandle<R>,
+    handles: &SessionHandles,
+    config: &TranscriptionConfig,
+    source: CaptureSource,
+    language: &str,
+) {
+    if config.language.as_deref() != Some(AUTO_LANGUAGE) {
+        return;
+    }
+    let Ok(mut detected) = handles.detected_language.lock() else {
+        return;
+    };
+    if detected.as_deref() == Some(language) {
+        return;
+    }
+    log_info!("Detected spoken language: {} ({:?} audio)", language, source);
+    *detected = Some(language.to_string());
+    meeting_metadata::set_field("transcription", "detected_language", serde_json::json!(language));
+    let event = DetectedLanguage { language: language.to_string(), source: source.label().to_string() };
+    if let Err(e) = app_handle.emit("language-detected", event) {
+        log_error!("Failed to emit language-detected event: {}", e);
+    }
+}
+
+// Hand a finished transcript line to everything that follows the live transcript
+fn publish_update<R: Runtime>(app_handle: &AppHandle<R>, update: &TranscriptUpdate) -> tauri::Result<()> {
+    transcript_sync::enqueue_segment(update.sequence_id, &update.text, &update.timestamp);
+    voice_commands::observe(app_handle, update);
+    translation::observe(app_handle, update);
+    session_recovery::record_line(update);
+    playback::record_line(update);
+    plugins::observe(update);
+    app_handle.emit("transcript-update", update)
+}
+
+/// Add a line of our own to the live transcript (a pause, a marker), saved and synced like
+/// any other line but not passed to the transcript observers.
+pub(crate) fn publish_note<R: Runtime>(app_handle: &AppHandle<R>, source: &str, text: &str, offset_secs: f64) {
+    let update = TranscriptUpdate {
+        text: text.to_string(),
+        timestamp: format_timestamp(offset_secs),
+        source: source.to_string(),
+        sequence_id: SEQUENCE_COUNTER.fetch_add(1, Ordering::SeqCst),
+        chunk_start_time: offset_secs,
+        is_partial: false,
+        speaker: None,
+        translated_text: None,
+        words: Vec::new(),
+    };
+    transcript_sync::enqueue_segment(update.sequence_id, &update.text, &update.timestamp);
+    session_recovery::record_line(&update);
+    playback::record_line(&update);
+    if let Err(e) = app_handle.emit("transcript-update", &update) {
+        log_error!("Failed to emit transcript-update event: {}", e);
+    }
+}
+
+// Called by every transcription worker on exit; the last one out reports completion
+async fn worker_finished<R: Runtime>(app_handle: &AppHandle<R>, handles: &SessionHandles) {
     // Decrement active worker count
     ACTIVE_WORKERS.fetch_sub(1, Ordering::SeqCst);
     
     // Check if this was the last active worker and emit completion event
     if ACTIVE_WORKERS.load(Ordering::SeqCst) == 0 {
-        let should_emit = unsafe {
-            if let Some(queue) = &AUDIO_CHUNK_QUEUE {
-                if let Ok(queue_guard) = queue.lock() {
-                    queue_guard.is_empty()
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        };
+        let should_emit = handles.queue_len() == 0;
         
         if should_emit {
             log_info!("All workers finished and queue is empty, waiting for pending segments...");
@@ -674,33 +1651,142 @@ async fn transcription_worker<R: Runtime>(
             }
         }
     }
-    
-    log_info!("Transcription worker {} ended", worker_id);
+}
+
+// Aggregate devices that already carry system audio are listed first
+#[tauri::command]
+async fn list_audio_devices() -> Result<Vec<AudioDeviceInfo>, AppError> {
+    let devices = audio::list_audio_devices().await.map_err(|e| {
+        log_error!("Failed to list audio devices: {}", e);
+        format!("Failed to list audio devices: {}", e)
+    })?;
+    let aggregates = audio::list_aggregate_devices();
+
+    let mut devices: Vec<AudioDeviceInfo> = devices
+        .into_iter()
+        .map(|device| {
+            let aggregate = aggregates.iter().find(|a| a.name == device.name).cloned();
+            let captures_system_audio = device.device_type == DeviceType::Input
+                && aggregate.as_ref().is_some_and(|a| a.captures_system_audio());
+            AudioDeviceInfo { device, aggregate, captures_system_audio }
+        })
+        .collect();
+    devices.sort_by_key(|info| !info.captures_system_audio);
+    Ok(devices)
+}
+
+// Resolve a device picked by the user, falling back to the system default when none is given
+async fn resolve_device(name: Option<String>, device_type: DeviceType) -> Result<AudioDevice, AppError> {
+    let name = match name.filter(|n| !n.trim().is_empty()) {
+        Some(name) => name,
+        None => {
+            let default = match device_type {
+                DeviceType::Input => default_input_device(),
+                DeviceType::Output => default_output_device(),
+            };
+            return default.map_err(|e| {
+                log_error!("Failed to get default {:?} device: {}", device_type, e);
+                AppError::NotFound(e.to_string())
+            });
+        }
+    };
+
+    let devices = audio::list_audio_devices().await.map_err(|e| e.to_string())?;
+    devices
+        .into_iter()
+        .find(|d| d.device_type == device_type && d.name == name)
+        .ok_or_else(|| {
+            log_error!("Selected {:?} device not found: {}", device_type, name);
+            AppError::NotFound(format!("Audio device not found: {}", name))
+        })
+}
+
+/// Start a recording on the default devices for a request that didn't come from the
+/// frontend (tray, detected meeting), taking the microphone over from the wake word listener.
+pub(crate) async fn start_recording_with_defaults<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    wake_word::stop_listener(app).await?;
+    Ok(start_recording(app.clone(), app.state::<RecordingState>(), app.state::<LevelMonitorState>(), None, None).await?)
+}
+
+/// Stop the recording for a request that didn't come from the frontend, saving it in the
+/// app's recordings folder in the configured format.
+pub(crate) async fn stop_recording_with_defaults<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let save_path = recordings_dir(app)?.join(format!("meeting-{}.wav", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    let args = api_version::Versioned {
+        api_version: None,
+        payload: RecordingArgs { save_path: save_path.to_string_lossy().to_string(), format: None },
+    };
+    Ok(stop_recording(app.clone(), app.state::<RecordingState>(), args).await?)
 }
 
 #[tauri::command]
-async fn start_recording<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+async fn start_recording<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, RecordingState>,
+    monitor_state: State<'_, LevelMonitorState>,
+    mic_device_name: Option<String>,
+    system_device_name: Option<String>,
+) -> Result<(), AppError> {
     log_info!("Attempting to start recording...");
     
     if is_recording() {
         log_error!("Recording already in progress");
-        return Err("Recording already in progress".to_string());
+        return Err(AppError::Conflict("Recording already in progress".to_string()));
+    }
+
+    // The recording reports levels itself and may need the same devices
+    stop_level_monitor(&monitor_state).await?;
+    wake_word::stop_listener(&app).await?;
+
+    // Resolve devices before touching any recording state so a bad selection fails cleanly
+    let mic_device = Arc::new(resolve_device(mic_device_name, DeviceType::Input).await?);
+    let system_device = Arc::new(resolve_device(system_device_name, DeviceType::Output).await?);
+    log_info!("Using microphone: {}, system audio: {}", mic_device, system_device);
+    if let Some(aggregate) = audio::list_aggregate_devices().into_iter().find(|a| a.name == mic_device.name) {
+        let parts: Vec<&str> = aggregate.sub_devices.iter().map(|d| d.name.as_str()).collect();
+        log_info!("Microphone is an aggregate device combining: {}", parts.join(", "));
+        if aggregate.captures_system_audio() {
+            log_warn!("Aggregate device already includes system audio via a loopback driver; system audio may be captured twice");
+        }
+    }
+
+    // Make sure the transcript server answers before capturing anything, unless the local model can take over
+    let mut transcription_config = load_transcription_config(&app);
+    let server_url = transcript_server_url(&app);
+    let client = reqwest::Client::new();
+    if transcription_config.engine == AudioTranscriptionEngine::WhisperServer {
+        match server_capabilities::negotiate(&client, &server_url).await {
+            Ok(capabilities) => capabilities.apply(&mut transcription_config),
+            Err(e) => {
+                if transcription_config.local_model.is_none() {
+                    log_error!("{}", e);
+                    return Err(AppError::Offline(e));
+                }
+                log_warn!("{}; chunks will fall back to the local model", e);
+                transcription_config.streaming = false;
+            }
+        }
     }
 
     // Reset dropped chunk counter for new recording session
     DROPPED_CHUNK_COUNTER.store(0, Ordering::SeqCst);
+    SPILLED_CHUNK_COUNTER.store(0, Ordering::SeqCst);
+    SKIPPED_SILENT_CHUNKS.store(0, Ordering::SeqCst);
     log_info!("Reset dropped chunk counter for new recording session");
 
-    // Stop any existing tasks first
-    unsafe {
-        if let Some(task) = AUDIO_COLLECTION_TASK.take() {
+    // Stop any tasks left over from a previous session first
+    let previous_session = state.lock().map_err(|e| e.to_string())?.take();
+    if let Some(mut previous_session) = previous_session {
+        if let Some(task) = previous_session.audio_collection_task.take() {
             log_info!("Stopping existing audio collection task...");
             task.abort();
             tokio::time::sleep(Duration::from_millis(50)).await;
         }
-        if let Some(task) = TRANSCRIPTION_TASK.take() {
-            log_info!("Stopping existing transcription task...");
-            task.abort();
+        if !previous_session.transcription_tasks.is_empty() {
+            log_info!("Stopping existing transcription tasks...");
+            for task in previous_session.transcription_tasks.drain(..) {
+                task.abort();
+            }
             tokio::time::sleep(Duration::from_millis(100)).await;
         }
     }
@@ -709,414 +1795,519 @@ async fn start_recording<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     RECORDING_FLAG.store(true, Ordering::SeqCst);
     log_info!("Recording flag set to true");
     
-    // Reset error event flag and activity tracking for new recording session
-    unsafe {
-        ERROR_EVENT_EMITTED = false;
-    }
-    
     // Reset transcription activity tracking
     LAST_TRANSCRIPTION_ACTIVITY.store(0, Ordering::SeqCst);
     ACTIVE_WORKERS.store(0, Ordering::SeqCst);
 
 
     // Store recording start time
-    unsafe {
-        RECORDING_START_TIME = Some(std::time::Instant::now());
-    }
+    let recording_start_time = std::time::Instant::now();
+    duplicates::begin_recording();
 
     // Initialize audio buffers and queue
-    unsafe {
-        MIC_BUFFER = Some(Arc::new(Mutex::new(Vec::new())));
-        SYSTEM_BUFFER = Some(Arc::new(Mutex::new(Vec::new())));
-        AUDIO_CHUNK_QUEUE = Some(Arc::new(Mutex::new(VecDeque::new())));
-        log_info!("Initialized audio buffers and chunk queue");
-    }
-    
-    // Get default devices
-    let mic_device = Arc::new(default_input_device().map_err(|e| {
-        log_error!("Failed to get default input device: {}", e);
-        e.to_string()
-    })?);
-    
-    let system_device = Arc::new(default_output_device().map_err(|e| {
-        log_error!("Failed to get default output device: {}", e);
-        e.to_string()
-    })?);
+    let is_running = Arc::new(AtomicBool::new(true));
+    // A server that labels speakers itself makes the local diarizer redundant
+    let diarizer = if transcription_config.server_diarization { None } else { load_diarizer(&app) };
+    let handles = SessionHandles::new(is_running.clone(), diarizer);
+    log_info!("Initialized audio buffers and chunk queue");
     
     // Create audio streams
-    let is_running = Arc::new(AtomicBool::new(true));
     
     // Create microphone stream
     let mic_stream = AudioStream::from_device(mic_device.clone(), is_running.clone())
         .await
         .map_err(|e| {
             log_error!("Failed to create microphone stream: {}", e);
-            e.to_string()
+            // Opening the microphone usually fails because access was not granted
+            if permissions::permission_status().all_granted() {
+                AppError::Internal(e.to_string())
+            } else {
+                AppError::PermissionDenied(e.to_string())
+            }
         })?;
     let mic_stream = Arc::new(mic_stream);
     
-    // Create system audio stream
-    let system_stream = AudioStream::from_device(system_device.clone(), is_running.clone())
-        .await
-        .map_err(|e| {
-            log_error!("Failed to create system stream: {}", e);
-            e.to_string()
-        })?;
-    let system_stream = Arc::new(system_stream);
-
-    unsafe {
-        MIC_STREAM = Some(mic_stream.clone());
-        SYSTEM_STREAM = Some(system_stream.clone());
-        IS_RUNNING = Some(is_running.clone());
+    if permissions::system_audio_blocked() {
+        log_warn!("Screen recording permission is missing; system audio will be silent");
+        if let Err(e) = app.emit("system-audio-blocked", ()) {
+            log_error!("Failed to emit system-audio-blocked event: {}", e);
+        }
     }
     
-    // Create HTTP client for transcription
-    let client = reqwest::Client::new();
+    // Create system audio stream, from a single application when one is chosen and running
+    #[cfg(target_os = "windows")]
+    let process_stream = capture_process::resolve(&app).and_then(|process| {
+        AudioStream::from_process(process.pid, &process.name, is_running.clone())
+            .map_err(|e| log_warn!("Capturing the whole output device instead of {}: {}", process.name, e))
+            .ok()
+    });
+    #[cfg(not(target_os = "windows"))]
+    let process_stream: Option<AudioStream> = None;
+    let system_stream = match process_stream {
+        Some(stream) => stream,
+        None => AudioStream::from_device(system_device.clone(), is_running.clone())
+            .await
+            .map_err(|e| {
+                log_error!("Failed to create system stream: {}", e);
+                e.to_string()
+            })?,
+    };
+    let system_stream = Arc::new(system_stream);
+    device_watchdog::spawn(app.clone(), mic_stream.clone(), system_stream.clone(), is_running.clone());
     
-    // Use hardcoded transcript server URL
-    let stream_url = format!("{}/stream", TRANSCRIPT_SERVER_URL);
-    log_info!("Using hardcoded stream URL: {}", stream_url);
+    // Load the configured upload part size
+    if let Ok(store) = app.store("store.json") {
+        if let Some(size) = store.get("uploadPartSizeBytes").and_then(|v| v.as_u64()) {
+            let size = (size as usize).clamp(MIN_UPLOAD_PART_SIZE_BYTES, MAX_UPLOAD_PART_SIZE_BYTES);
+            UPLOAD_PART_SIZE_BYTES.store(size, Ordering::SeqCst);
+        }
+    }
+    log_info!("Using upload part size: {} bytes", UPLOAD_PART_SIZE_BYTES.load(Ordering::SeqCst));
+
+    let stream_url = format!("{}/stream", server_url);
+    log_info!("Using stream URL: {}", stream_url);
+    log_info!("Transcription engine: {}, local model: {:?}", transcription_config.engine, transcription_config.local_model);
+    meeting_metadata::reset();
+    transcript_sync::begin_recording(&app);
+    playback::begin_recording();
+    voice_commands::begin_recording(&app);
+    translation::begin_recording(&app);
+    transcription_failover::begin_recording();
+    privacy_pause::begin_recording(recording_start_time);
+    mixer::begin_recording();
+    plugins::begin_recording(&app);
+    markers::begin_recording();
+    meeting_metadata::set("transcription", serde_json::json!({
+        "engine": transcription_config.engine.to_string(),
+        "server_url": (transcription_config.engine == AudioTranscriptionEngine::WhisperServer).then(|| server_url.clone()),
+        "local_model": transcription_config.local_model.as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string()),
+        "fallback_used": false,
+        "streaming": transcription_config.uses_streaming(),
+        "language": transcription_config.language,
+        "translation": translation::load_config(&app),
+        "sample_format": transcription_config.sample_format.as_str(),
+        "server_diarization": transcription_config.server_diarization,
+        "fallback_engine": transcription_config.failover.fallback().map(|engine| engine.to_string()),
+    }));
+    if handles.diarizer.is_some() {
+        meeting_metadata::set("diarization_model", serde_json::json!(diarization::EMBEDDING_MODEL_FILE));
+    }
 
     let device_config = mic_stream.device_config.clone();
     let sample_rate = device_config.sample_rate().0;
     let channels = device_config.channels();
     
     log_info!("Mic config: {} Hz, {} channels", sample_rate, channels);
+    let system_sample_rate = system_stream.device_config.sample_rate().0;
+    log_info!("System audio config: {} Hz", system_sample_rate);
     
-    // Get recording start time for proper elapsed time calculation
-    let recording_start_time = unsafe { 
-        RECORDING_START_TIME.unwrap_or_else(|| std::time::Instant::now()) 
-    };
+    // Start writing the recording to disk
+    let recording_path = recordings_dir(&app)?
+        .join(format!("in-progress-{}.wav", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    let writer = WavWriter::create(&recording_path, sample_rate)
+        .map_err(|e| format!("Failed to create recording file: {}", e))?;
+    log_info!("Writing recording to {}", recording_path.display());
+    if let Err(e) = session_recovery::begin(&app, &recording_path) {
+        log_warn!("Crash recovery unavailable for this recording: {}", e);
+    }
+    let (recording_sender, recording_receiver) = mpsc::unbounded_channel();
+    let recording_writer = tokio::spawn(recording_writer_task(recording_receiver, writer));
+    
+    let streaming = transcription_config.uses_streaming();
+    if streaming {
+        log_info!("Streaming {} ms frames to the transcription server", streaming::FRAME_MS);
+    }
     
     // Start audio collection task
     let audio_collection_handle = {
         let mic_stream_clone = mic_stream.clone();
         let system_stream_clone = system_stream.clone();
-        let is_running_clone = is_running.clone();
+        let handles_clone = handles.clone();
         let app_handle_clone = app.clone();
         tokio::spawn(async move {
             if let Err(e) = audio_collection_task(
                 mic_stream_clone,
                 system_stream_clone,
-                is_running_clone,
+                handles_clone,
+                recording_sender,
                 sample_rate,
+                system_sample_rate,
                 recording_start_time,
                 app_handle_clone,
+                streaming,
             ).await {
-                log_error!("Audio collection task error: {}", e);
+                crash::capture_task_error("Audio collection", &e);
             }
         })
     };
     
-    // Start multiple transcription workers
-    const NUM_WORKERS: usize = 3;
+    // Start transcription workers; each source gets its own so sentences never mix speakers
+    const WORKERS_PER_SOURCE: usize = 2;
     let mut worker_handles = Vec::new();
     
-    for worker_id in 0..NUM_WORKERS {
+    // A streaming session must see its frames in order, so it gets a single worker
+    let workers_per_source = if streaming { 1 } else { WORKERS_PER_SOURCE };
+    let worker_sources = [CaptureSource::Mic, CaptureSource::System]
+        .into_iter()
+        .flat_map(|source| std::iter::repeat(source).take(workers_per_source));
+    for (worker_id, source) in worker_sources.enumerate() {
+        if streaming {
+            worker_handles.push(tokio::spawn(streaming::stream_worker(
+                client.clone(),
+                server_url.clone(),
+                app.clone(),
+                handles.clone(),
+                transcription_config.clone(),
+                source,
+                worker_id,
+            )));
+            continue;
+        }
         let client_clone = client.clone();
         let stream_url_clone = stream_url.clone();
         let app_handle_clone = app.clone();
+        let handles_clone = handles.clone();
+        let config_clone = transcription_config.clone();
         
         let worker_handle = tokio::spawn(async move {
             transcription_worker(
                 client_clone,
-                stream_url_clone,
-                app_handle_clone,
-                worker_id,
-            ).await;
-        });
-        
-        worker_handles.push(worker_handle);
-    }
-    
-    // Store task handles globally
-    unsafe {
-        AUDIO_COLLECTION_TASK = Some(audio_collection_handle);
-        // Store the first worker as the main transcription task for compatibility
-        if let Some(first_worker) = worker_handles.into_iter().next() {
-            TRANSCRIPTION_TASK = Some(first_worker);
-        }
-    }
-    
-    Ok(())
-}
-
-#[tauri::command]
-async fn stop_recording(args: RecordingArgs) -> Result<(), String> {
-    log_info!("Attempting to stop recording...");
-    
-    // Only check recording state if we haven't already started stopping
-    if !RECORDING_FLAG.load(Ordering::SeqCst) {
-        log_info!("Recording is already stopped");
-        return Ok(());
-    }
-
-    // Check minimum recording duration
-    let elapsed_ms = unsafe {
-        RECORDING_START_TIME
-            .map(|start| start.elapsed().as_millis() as u64)
-            .unwrap_or(0)
-    };
-
-    if elapsed_ms < MIN_RECORDING_DURATION_MS {
-        let remaining = MIN_RECORDING_DURATION_MS - elapsed_ms;
-        log_info!("Waiting for minimum recording duration ({} ms remaining)...", remaining);
-        tokio::time::sleep(Duration::from_millis(remaining)).await;
-    }
-
-    // First set the recording flag to false to prevent new data from being processed
-    RECORDING_FLAG.store(false, Ordering::SeqCst);
-    log_info!("Recording flag set to false");
-    
-    unsafe {
-        // Stop the running flag for audio streams first
-        if let Some(is_running) = &IS_RUNNING {
-            // Set running flag to false first to stop the tokio task
-            is_running.store(false, Ordering::SeqCst);
-            log_info!("Set recording flag to false, waiting for streams to stop...");
-            
-            // Stop the audio collection task
-            if let Some(task) = AUDIO_COLLECTION_TASK.take() {
-                log_info!("Stopping audio collection task...");
-                task.abort();
-                tokio::time::sleep(Duration::from_millis(50)).await;
-            }
-            
-            // Wait for transcription workers to complete processing remaining chunks
-            if TRANSCRIPTION_TASK.is_some() {
-                log_info!("Waiting for transcription workers to complete...");
-                
-                // Wait for all workers to finish processing remaining chunks
-                let mut wait_time = 0;
-                const MAX_WAIT_TIME: u64 = 30000; // 30 seconds max
-                const CHECK_INTERVAL: u64 = 100; // Check every 100ms
-                
-                while wait_time < MAX_WAIT_TIME {
-                    let active_count = ACTIVE_WORKERS.load(Ordering::SeqCst);
-                    let queue_size = unsafe {
-                        if let Some(queue) = &AUDIO_CHUNK_QUEUE {
-                            if let Ok(queue_guard) = queue.lock() {
-                                queue_guard.len()
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        }
-                    };
-                    
-                    log_info!("Worker cleanup status: {} active workers, {} chunks in queue", active_count, queue_size);
-                    
-                    // If no active workers and queue is empty, we're done
-                    if active_count == 0 && queue_size == 0 {
-                        log_info!("All workers completed and queue is empty");
-                        break;
-                    }
-                    
-                    tokio::time::sleep(Duration::from_millis(CHECK_INTERVAL)).await;
-                    wait_time += CHECK_INTERVAL;
-                }
-                
-                if wait_time >= MAX_WAIT_TIME {
-                    log_error!("Transcription worker cleanup timeout after {} seconds", MAX_WAIT_TIME / 1000);
-                }
-                
-                // Now stop the transcription task
-                if let Some(task) = TRANSCRIPTION_TASK.take() {
-                    log_info!("Stopping transcription task...");
-                    task.abort();
-                    tokio::time::sleep(Duration::from_millis(100)).await;
-                }
-            }
-            
-            // Give the tokio task time to finish and release its references
-            tokio::time::sleep(Duration::from_millis(100)).await;
-            
-            // Stop mic stream if it exists
-            if let Some(mic_stream) = &MIC_STREAM {
-                log_info!("Stopping microphone stream...");
-                if let Err(e) = mic_stream.stop().await {
-                    log_error!("Error stopping mic stream: {}", e);
-                } else {
-                    log_info!("Microphone stream stopped successfully");
-                }
-            }
-            
-            // Stop system stream if it exists
-            if let Some(system_stream) = &SYSTEM_STREAM {
-                log_info!("Stopping system stream...");
-                if let Err(e) = system_stream.stop().await {
-                    log_error!("Error stopping system stream: {}", e);
-                } else {
-                    log_info!("System stream stopped successfully");
-                }
-            }
-            
-            // Clear the stream references
-            MIC_STREAM = None;
-            SYSTEM_STREAM = None;
-            IS_RUNNING = None;
-            TRANSCRIPTION_TASK = None;
-            AUDIO_COLLECTION_TASK = None;
-            AUDIO_CHUNK_QUEUE = None;
-            
-            // Give streams time to fully clean up
-            tokio::time::sleep(Duration::from_millis(100)).await;
-        }
+                stream_url_clone,
+                app_handle_clone,
+                handles_clone,
+                config_clone,
+                source,
+                worker_id,
+            ).await;
+        });
+        
+        worker_handles.push(worker_handle);
     }
     
-    // Get final buffers
-    let mic_data = unsafe {
-        if let Some(buffer) = &MIC_BUFFER {
-            if let Ok(guard) = buffer.lock() {
-                guard.clone()
-            } else {
-                Vec::new()
-            }
-        } else {
-            Vec::new()
-        }
-    };
+    // Hand the session over to managed state
+    *state.lock().map_err(|e| e.to_string())? = Some(RecordingSession {
+        mic_stream,
+        system_stream,
+        recording_writer: Some(recording_writer),
+        start_time: recording_start_time,
+        handles,
+        audio_collection_task: Some(audio_collection_handle),
+        transcription_tasks: worker_handles,
+    });
+    recording_indicator::set(&app, recording_indicator::IndicatorState::Recording);
+    meeting_timer::begin_recording(&app, recording_start_time);
+    calendar::begin_recording(&app);
     
-    let system_data = unsafe {
-        if let Some(buffer) = &SYSTEM_BUFFER {
-            if let Ok(guard) = buffer.lock() {
-                guard.clone()
-            } else {
-                Vec::new()
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_recording<R: Runtime>(app: AppHandle<R>, state: State<'_, RecordingState>, args: api_version::Versioned<RecordingArgs>) -> Result<(), AppError> {
+    log_info!("Attempting to stop recording...");
+    let args = args.into_payload().map_err(AppError::InvalidInput)?;
+    
+    // Only check recording state if we haven't already started stopping
+    if !RECORDING_FLAG.load(Ordering::SeqCst) {
+        log_info!("Recording is already stopped");
+        return Ok(());
+    }
+
+    // Take what shutdown needs; the session itself stays in state so status polling keeps working
+    let (handles, start_time, audio_collection_task, transcription_tasks) = {
+        let mut guard = state.lock().map_err(|e| e.to_string())?;
+        match guard.as_mut() {
+            Some(session) => (
+                session.handles.clone(),
+                session.start_time,
+                session.audio_collection_task.take(),
+                std::mem::take(&mut session.transcription_tasks),
+            ),
+            None => {
+                log_info!("No active recording session");
+                RECORDING_FLAG.store(false, Ordering::SeqCst);
+                recording_indicator::set(&app, recording_indicator::IndicatorState::Idle);
+                return Ok(());
             }
-        } else {
-            Vec::new()
         }
     };
-    /*
-    // Mix the audio and convert to 16-bit PCM
-    let max_len = mic_data.len().max(system_data.len());
-    let mut mixed_data = Vec::with_capacity(max_len);
-    
-    for i in 0..max_len {
-        let mic_sample = if i < mic_data.len() { mic_data[i] } else { 0.0 };
-        let system_sample = if i < system_data.len() { system_data[i] } else { 0.0 };
-        mixed_data.push((mic_sample + system_sample) * 0.5);
-    }
 
-    if mixed_data.is_empty() {
-        log_error!("No audio data captured");
-        return Err("No audio data captured".to_string());
+    // Check minimum recording duration
+    let elapsed_ms = start_time.elapsed().as_millis() as u64;
+
+    if elapsed_ms < MIN_RECORDING_DURATION_MS {
+        let remaining = MIN_RECORDING_DURATION_MS - elapsed_ms;
+        log_info!("Waiting for minimum recording duration ({} ms remaining)...", remaining);
+        tokio::time::sleep(Duration::from_millis(remaining)).await;
     }
+
+    // First set the recording flag to false to prevent new data from being processed
+    RECORDING_FLAG.store(false, Ordering::SeqCst);
+    log_info!("Recording flag set to false");
+    recording_indicator::set(&app, recording_indicator::IndicatorState::Idle);
+    duplicates::finish_recording();
+    privacy_pause::finish_recording();
+    transcript_sync::finish_recording();
+    meeting_timer::finish_recording();
     
-    log_info!("Mixed {} audio samples", mixed_data.len());
+    // Set running flag to false first to stop the tokio task
+    handles.is_running.store(false, Ordering::SeqCst);
+    log_info!("Set recording flag to false, waiting for streams to stop...");
     
-    // Resample the audio to 16kHz for Whisper compatibility
-    let original_sample_rate = 48000; // Assuming original sample rate is 48kHz
-    if original_sample_rate != WHISPER_SAMPLE_RATE {
-        log_info!("Resampling audio from {} Hz to {} Hz for Whisper compatibility", 
-                 original_sample_rate, WHISPER_SAMPLE_RATE);
-        mixed_data = resample_audio(&mixed_data, original_sample_rate, WHISPER_SAMPLE_RATE);
-        log_info!("Resampled to {} samples", mixed_data.len());
+    // Stop the audio collection task
+    if let Some(task) = audio_collection_task {
+        log_info!("Stopping audio collection task...");
+        task.abort();
+        tokio::time::sleep(Duration::from_millis(50)).await;
     }
     
-    // Convert to 16-bit PCM samples
-    let mut bytes = Vec::with_capacity(mixed_data.len() * 2);
-    for &sample in mixed_data.iter() {
-        let value = (sample.max(-1.0).min(1.0) * 32767.0) as i16;
-        bytes.extend_from_slice(&value.to_le_bytes());
+    // Wait for transcription workers to complete processing remaining chunks
+    if !transcription_tasks.is_empty() {
+        log_info!("Waiting for transcription workers to complete...");
+        
+        // Wait for all workers to finish processing remaining chunks
+        let mut wait_time = 0;
+        const MAX_WAIT_TIME: u64 = 30000; // 30 seconds max
+        const CHECK_INTERVAL: u64 = 100; // Check every 100ms
+        
+        while wait_time < MAX_WAIT_TIME {
+            let active_count = ACTIVE_WORKERS.load(Ordering::SeqCst);
+            let queue_size = handles.queue_len();
+            
+            log_info!("Worker cleanup status: {} active workers, {} chunks in queue", active_count, queue_size);
+            
+            // If no active workers and queue is empty, we're done
+            if active_count == 0 && queue_size == 0 {
+                log_info!("All workers completed and queue is empty");
+                break;
+            }
+            
+            tokio::time::sleep(Duration::from_millis(CHECK_INTERVAL)).await;
+            wait_time += CHECK_INTERVAL;
+        }
+        
+        if wait_time >= MAX_WAIT_TIME {
+            log_error!("Transcription worker cleanup timeout after {} seconds", MAX_WAIT_TIME / 1000);
+        }
+        
+        // Now stop the transcription tasks
+        log_info!("Stopping transcription tasks...");
+        for task in transcription_tasks {
+            task.abort();
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
     }
     
-    log_info!("Converted to {} bytes of PCM data", bytes.len());
-
-    // Create WAV header
-    let data_size = bytes.len() as u32;
-    let file_size = 36 + data_size;
-    let sample_rate = WHISPER_SAMPLE_RATE; // Use Whisper's required sample rate (16000 Hz)
-    let channels = 1u16; // Mono
-    let bits_per_sample = 16u16;
-    let block_align = channels * (bits_per_sample / 8);
-    let byte_rate = sample_rate * block_align as u32;
-    
-    let mut wav_file = Vec::with_capacity(44 + bytes.len());
+    // Give the tokio task time to finish and release its references
+    tokio::time::sleep(Duration::from_millis(100)).await;
     
-    // RIFF header
-    wav_file.extend_from_slice(b"RIFF");
-    wav_file.extend_from_slice(&file_size.to_le_bytes());
-    wav_file.extend_from_slice(b"WAVE");
-    
-    // fmt chunk
-    wav_file.extend_from_slice(b"fmt ");
-    wav_file.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
-    wav_file.extend_from_slice(&1u16.to_le_bytes()); // audio format (PCM)
-    wav_file.extend_from_slice(&channels.to_le_bytes()); // num channels
-    wav_file.extend_from_slice(&sample_rate.to_le_bytes()); // sample rate
-    wav_file.extend_from_slice(&byte_rate.to_le_bytes()); // byte rate
-    wav_file.extend_from_slice(&block_align.to_le_bytes()); // block align
-    wav_file.extend_from_slice(&bits_per_sample.to_le_bytes()); // bits per sample
-    
-    // data chunk
-    wav_file.extend_from_slice(b"data");
-    wav_file.extend_from_slice(&data_size.to_le_bytes());
-    wav_file.extend_from_slice(&bytes);
-    
-    log_info!("Created WAV file with {} bytes total", wav_file.len());
-    */
+    // Release the session and stop its streams
+    let session = state.lock().map_err(|e| e.to_string())?.take();
+    let recording_writer = match session {
+        Some(mut session) => {
+            session.stop_streams().await;
+            
+            // Give streams time to fully clean up
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            
+            session.recording_writer.take()
+        }
+        None => None,
+    };
+    plugins::finish_recording();
+
+    // The writer finishes once the aborted collection task has released its sender
+    let (recording_path, sample_count, recording_sample_rate) = match recording_writer {
+        Some(writer) => writer.await.map_err(|e| format!("Recording writer task failed: {}", e))??,
+        None => return Err(AppError::Conflict("No audio data captured".to_string())),
+    };
+
+    if sample_count == 0 {
+        log_error!("No audio data captured");
+        let _ = fs::remove_file(&recording_path);
+        session_recovery::finish();
+        return Err(AppError::Conflict("No audio data captured".to_string()));
+    }
+
+    let format = args.format.unwrap_or_else(|| recording_format(&app));
+    let mut save_path = std::path::PathBuf::from(&args.save_path);
+    save_path.set_extension(format.extension());
+
     // Create the save directory if it doesn't exist
-    if let Some(parent) = std::path::Path::new(&args.save_path).parent() {
+    if let Some(parent) = save_path.parent() {
         if !parent.exists() {
             log_info!("Creating directory: {:?}", parent);
-            if let Err(e) = std::fs::create_dir_all(parent) {
+            if let Err(e) = fs::create_dir_all(parent) {
                 let err_msg = format!("Failed to create save directory: {}", e);
                 log_error!("{}", err_msg);
-                return Err(err_msg);
+                return Err(err_msg.into());
             }
         }
     }
 
-    /*
     // Save the recording
-    log_info!("Saving recording to: {}", args.save_path);
-    match fs::write(&args.save_path, wav_file) {
-        Ok(_) => log_info!("Successfully saved recording"),
-        Err(e) => {
-            let err_msg = format!("Failed to save recording: {}", e);
-            log_error!("{}", err_msg);
-            return Err(err_msg);
-        }
+    log_info!("Saving {} samples as {:?} to: {}", sample_count, format, save_path.display());
+    let duration_secs = sample_count as f64 / recording_sample_rate as f64;
+    let save_result = if format == AudioFormat::Wav {
+        // The in-progress file is already the final WAV; copy when rename crosses filesystems
+        fs::rename(&recording_path, &save_path)
+            .or_else(|_| fs::copy(&recording_path, &save_path).map(|_| ()))
+            .map_err(|e| e.to_string())
+    } else {
+        let (input, output) = (recording_path.clone(), save_path.clone());
+        tokio::task::spawn_blocking(move || transcode_recording(&input, format, &output))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|result| result.map_err(|e| e.to_string()))
+    };
+    if let Err(e) = save_result {
+        // The in-progress file is kept so the audio is not lost
+        let err_msg = format!("Failed to save recording: {}", e);
+        log_error!("{} (audio kept at {})", err_msg, recording_path.display());
+        return Err(err_msg.into());
     }
-    */
-    
-    // Clean up
-    unsafe {
-        MIC_BUFFER = None;
-        SYSTEM_BUFFER = None;
-        MIC_STREAM = None;
-        SYSTEM_STREAM = None;
-        IS_RUNNING = None;
-        RECORDING_START_TIME = None;
-        TRANSCRIPTION_TASK = None;
-        AUDIO_COLLECTION_TASK = None;
-        AUDIO_CHUNK_QUEUE = None;
+    if recording_path.exists() {
+        let _ = fs::remove_file(&recording_path);
     }
-    
+
+    let size_bytes = fs::metadata(&save_path).map(|m| m.len()).unwrap_or(0);
+    log_info!("Successfully saved recording ({:.1}s, {} bytes)", duration_secs, size_bytes);
+    let saved = RecordingSaved {
+        path: save_path.to_string_lossy().to_string(),
+        format,
+        duration_secs,
+        size_bytes,
+    };
+    playback::recording_saved(&save_path);
+    usage_stats::record(&app, usage_stats::UsageKind::Recording, Some(duration_secs), None);
+    artifact_storage::upload_in_background(&app, save_path.clone(), artifact_storage::ArtifactKind::Recording);
+    activity::record(
+        &app,
+        activity::ActivityKind::RecordingFinished,
+        &format!("Recording saved ({}) to {}", format_timestamp(duration_secs), save_path.display()),
+        None,
+        None,
+    );
+    if let Err(e) = app.emit("recording-saved", &saved) {
+        log_error!("Failed to emit recording-saved event: {}", e);
+    }
+    session_recovery::finish();
+    wake_word::resume_if_enabled(&app).await;
+
     Ok(())
 }
 
+// Format for saved recordings; WAV unless the user picked another one
+fn recording_format<R: Runtime>(app: &AppHandle<R>) -> AudioFormat {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get("recordingFormat"))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
 #[tauri::command]
-fn is_recording() -> bool {
-    RECORDING_FLAG.load(Ordering::SeqCst)
+async fn get_recording_format<R: Runtime>(app: AppHandle<R>) -> Result<AudioFormat, AppError> {
+    Ok(recording_format(&app))
 }
 
 #[tauri::command]
-fn get_transcription_status() -> TranscriptionStatus {
-    let chunks_in_queue = unsafe {
-        if let Some(queue) = &AUDIO_CHUNK_QUEUE {
-            if let Ok(queue_guard) = queue.lock() {
-                queue_guard.len()
-            } else {
-                0
+async fn set_recording_format<R: Runtime>(app: AppHandle<R>, format: AudioFormat) -> Result<(), AppError> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set("recordingFormat", serde_json::json!(format));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Recording format set to {:?}", format);
+    Ok(())
+}
+
+/// Emit `audio-level` events for the given devices without recording. Replaces any
+/// monitor already running; recording stops it.
+#[tauri::command]
+async fn start_audio_level_monitor<R: Runtime>(
+    app: AppHandle<R>,
+    monitor_state: State<'_, LevelMonitorState>,
+    mic_device_name: Option<String>,
+    system_device_name: Option<String>,
+) -> Result<(), AppError> {
+    if is_recording() {
+        return Err(AppError::Conflict("Levels are already reported while recording".to_string()));
+    }
+    stop_level_monitor(&monitor_state).await?;
+
+    let mic_device = Arc::new(resolve_device(mic_device_name, DeviceType::Input).await?);
+    let system_device = Arc::new(resolve_device(system_device_name, DeviceType::Output).await?);
+    let is_running = Arc::new(AtomicBool::new(true));
+    let mic_stream = Arc::new(
+        AudioStream::from_device(mic_device.clone(), is_running.clone())
+            .await
+            .map_err(|e| format!("Failed to open microphone: {}", e))?,
+    );
+    let system_stream = Arc::new(
+        AudioStream::from_device(system_device.clone(), is_running.clone())
+            .await
+            .map_err(|e| format!("Failed to open system audio: {}", e))?,
+    );
+    log_info!("Monitoring levels of {} and {}", mic_device, system_device);
+
+    let task = {
+        let mut mic_receiver = mic_stream.subscribe().await;
+        let mut system_receiver = system_stream.subscribe().await;
+        let is_running = is_running.clone();
+        tokio::spawn(async move {
+            let mut level_meter = LevelMeter::new();
+            while is_running.load(Ordering::SeqCst) {
+                while let Ok(chunk) = mic_receiver.try_recv() {
+                    level_meter.observe(&chunk, &[]);
+                }
+                while let Ok(chunk) = system_receiver.try_recv() {
+                    level_meter.observe(&[], &chunk);
+                }
+                if let Some(levels) = level_meter.take_if_due() {
+                    if let Err(e) = app.emit("audio-level", levels) {
+                        log_debug!("Failed to emit audio-level event: {}", e);
+                    }
+                }
+                tokio::time::sleep(LEVEL_INTERVAL / 4).await;
             }
-        } else {
-            0
-        }
+        })
     };
+
+    *monitor_state.lock().map_err(|e| e.to_string())? = Some(LevelMonitor {
+        mic_stream,
+        system_stream,
+        is_running,
+        task,
+    });
+    Ok(())
+}
+
+async fn stop_level_monitor(monitor_state: &LevelMonitorState) -> Result<(), String> {
+    let monitor = monitor_state.lock().map_err(|e| e.to_string())?.take();
+    if let Some(monitor) = monitor {
+        monitor.stop().await;
+        log_info!("Level monitor stopped");
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn stop_audio_level_monitor(monitor_state: State<'_, LevelMonitorState>) -> Result<(), AppError> {
+    Ok(stop_level_monitor(&monitor_state).await?)
+}
+
+#[tauri::command]
+fn is_recording() -> bool {
+    RECORDING_FLAG.load(Ordering::SeqCst)
+}
+
+#[tauri::command]
+fn get_transcription_status(state: State<'_, RecordingState>) -> TranscriptionStatus {
+    let (chunks_in_queue, provider_failover) = state
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|session| (session.handles.queue_len(), session.handles.failover.switch())))
+        .unwrap_or((0, None));
     
     let is_processing = ACTIVE_WORKERS.load(Ordering::SeqCst) > 0 || chunks_in_queue > 0;
     
@@ -1135,19 +2326,378 @@ fn get_transcription_status() -> TranscriptionStatus {
         chunks_in_queue,
         is_processing,
         last_activity_ms: elapsed_since_activity,
+        silent_chunks_skipped: SKIPPED_SILENT_CHUNKS.load(Ordering::SeqCst),
+        chunks_spilled: SPILLED_CHUNK_COUNTER.load(Ordering::SeqCst),
+        provider_failover,
+        mix: mixer::status(),
+    }
+}
+
+#[tauri::command]
+async fn set_upload_part_size<R: Runtime>(app: AppHandle<R>, part_size_bytes: usize) -> Result<(), AppError> {
+    if !(MIN_UPLOAD_PART_SIZE_BYTES..=MAX_UPLOAD_PART_SIZE_BYTES).contains(&part_size_bytes) {
+        return Err(AppError::InvalidInput(format!(
+            "Upload part size must be between {} and {} bytes",
+            MIN_UPLOAD_PART_SIZE_BYTES, MAX_UPLOAD_PART_SIZE_BYTES
+        )));
+    }
+
+    UPLOAD_PART_SIZE_BYTES.store(part_size_bytes, Ordering::SeqCst);
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set("uploadPartSizeBytes", serde_json::json!(part_size_bytes));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log_info!("Upload part size set to {} bytes", part_size_bytes);
+    Ok(())
+}
+
+#[tauri::command]
+fn get_upload_part_size() -> usize {
+    UPLOAD_PART_SIZE_BYTES.load(Ordering::SeqCst)
+}
+
+fn load_transcription_language<R: Runtime>(app: &AppHandle<R>) -> Option<String> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get("transcriptionLanguage"))
+        .and_then(|v| v.as_str().map(|s| s.to_string()))
+}
+
+#[tauri::command]
+async fn get_transcription_language<R: Runtime>(app: AppHandle<R>) -> Result<Option<String>, AppError> {
+    Ok(load_transcription_language(&app))
+}
+
+/// Set the spoken language as a whisper language code ("en", "de", ...), "auto" to
+/// detect it from the audio, or None for the model's default. Applies from the next recording.
+#[tauri::command]
+async fn set_transcription_language<R: Runtime>(app: AppHandle<R>, language: Option<String>) -> Result<(), AppError> {
+    let language = language.map(|l| l.trim().to_lowercase()).filter(|l| !l.is_empty());
+    if let Some(code) = &language {
+        let valid = code == AUTO_LANGUAGE || ((2..=3).contains(&code.len()) && code.chars().all(|c| c.is_ascii_lowercase()));
+        if !valid {
+            return Err(AppError::InvalidInput(format!("Unknown language code: {}", code)));
+        }
+        let english_only = find_local_whisper_model(&app)
+            .and_then(|path| path.file_name().map(|n| n.to_string_lossy().contains(".en")))
+            .unwrap_or(false);
+        if english_only && code != "en" {
+            log_warn!("The local whisper model is English-only; {} audio will only transcribe on the server", code);
+        }
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    match &language {
+        Some(code) => store.set("transcriptionLanguage", serde_json::json!(code)),
+        None => {
+            store.delete("transcriptionLanguage");
+        }
+    }
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Transcription language set to {}", language.as_deref().unwrap_or("model default"));
+    Ok(())
+}
+
+fn load_chunk_retry_policy<R: Runtime>(app: &AppHandle<R>) -> ChunkRetryPolicy {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get("chunkRetryPolicy"))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+async fn get_chunk_retry_policy<R: Runtime>(app: AppHandle<R>) -> Result<ChunkRetryPolicy, AppError> {
+    Ok(load_chunk_retry_policy(&app))
+}
+
+/// Save the retry policy; it applies from the next recording.
+#[tauri::command]
+async fn set_chunk_retry_policy<R: Runtime>(app: AppHandle<R>, policy: ChunkRetryPolicy) -> Result<(), AppError> {
+    policy.validate().map_err(AppError::InvalidInput)?;
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set("chunkRetryPolicy", serde_json::to_value(&policy).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Chunk retry policy set to {:?}", policy);
+    Ok(())
+}
+
+/// Trims the URL and checks it is an absolute http(s) URL with a host.
+fn normalize_server_url(url: &str) -> Result<String, String> {
+    let trimmed = url.trim().trim_end_matches('/');
+    let parsed = reqwest::Url::parse(trimmed).map_err(|e| format!("Invalid server URL '{}': {}", url, e))?;
+    if parsed.scheme() != "http" && parsed.scheme() != "https" {
+        return Err(format!("Server URL must use http or https: {}", url));
+    }
+    if parsed.host_str().is_none() {
+        return Err(format!("Server URL has no host: {}", url));
+    }
+    Ok(trimmed.to_string())
+}
+
+/// The whisper server base URL, as configured in the store or the default local server.
+pub(crate) fn transcript_server_url<R: Runtime>(app: &AppHandle<R>) -> String {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get("transcriptServerUrl"))
+        .and_then(|v| v.as_str().and_then(|url| normalize_server_url(url).ok()))
+        .unwrap_or_else(|| DEFAULT_TRANSCRIPT_SERVER_URL.to_string())
+}
+
+// Any HTTP response means the server is up; whisper-server does not serve every path
+async fn check_transcript_server(client: &reqwest::Client, server_url: &str) -> Result<(), String> {
+    let started = std::time::Instant::now();
+    match client.get(server_url).timeout(TRANSCRIPT_SERVER_CHECK_TIMEOUT).send().await {
+        Ok(_) => {
+            telemetry::record(Dependency::WhisperServer, started.elapsed(), None);
+            Ok(())
+        }
+        Err(e) => {
+            let error = format!("Transcript server at {} is not reachable: {}", server_url, e);
+            telemetry::record(Dependency::WhisperServer, started.elapsed(), Some(&error));
+            Err(error)
+        }
+    }
+}
+
+#[tauri::command]
+async fn set_transcript_server_url<R: Runtime>(app: AppHandle<R>, url: String) -> Result<String, AppError> {
+    if is_recording() {
+        return Err(AppError::Conflict("Cannot change the transcript server while recording".to_string()));
+    }
+
+    let url = normalize_server_url(&url).map_err(AppError::InvalidInput)?;
+    check_transcript_server(&reqwest::Client::new(), &url).await.map_err(AppError::Offline)?;
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set("transcriptServerUrl", serde_json::json!(url));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log_info!("Transcript server URL set to {}", url);
+    Ok(url)
+}
+
+#[tauri::command]
+async fn get_transcript_server_url<R: Runtime>(app: AppHandle<R>) -> Result<String, AppError> {
+    Ok(transcript_server_url(&app))
+}
+
+// A configured model path wins; otherwise use the first model in the app's models folder
+fn find_local_whisper_model<R: Runtime>(app: &AppHandle<R>) -> Option<std::path::PathBuf> {
+    let configured = app
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get("localWhisperModelPath"))
+        .and_then(|v| v.as_str().map(std::path::PathBuf::from))
+        .filter(|path| path.is_file());
+    configured.or_else(|| models_dir(app).ok().and_then(|dir| audio::whisper_local::find_model_in(&dir)))
+}
+
+fn load_transcription_config<R: Runtime>(app: &AppHandle<R>) -> TranscriptionConfig {
+    let engine = app
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get("transcriptionEngine"))
+        .and_then(|v| v.as_str().and_then(AudioTranscriptionEngine::from_setting))
+        .unwrap_or(AudioTranscriptionEngine::WhisperServer);
+    let streaming = app
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get("transcriptionStreaming"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    TranscriptionConfig {
+        engine,
+        local_model: find_local_whisper_model(app),
+        streaming,
+        retry: load_chunk_retry_policy(app),
+        language: load_transcription_language(app),
+        translate: translation::load_config(app).uses_whisper(),
+        sample_format: SampleFormat::default(),
+        server_diarization: false,
+        failover: transcription_failover::load_config(app),
+    }
+}
+
+#[tauri::command]
+async fn set_transcription_engine<R: Runtime>(
+    app: AppHandle<R>,
+    engine: String,
+    local_model_path: Option<String>,
+    streaming: Option<bool>,
+) -> Result<(), AppError> {
+    let parsed = AudioTranscriptionEngine::from_setting(&engine)
+        .ok_or_else(|| AppError::InvalidInput(format!("Unknown transcription engine: {}", engine)))?;
+
+    if let Some(path) = &local_model_path {
+        if !std::path::Path::new(path).is_file() {
+            return Err(AppError::NotFound(format!("Whisper model not found: {}", path)));
+        }
+    }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set("transcriptionEngine", serde_json::json!(engine));
+    if let Some(path) = &local_model_path {
+        store.set("localWhisperModelPath", serde_json::json!(path));
+    }
+    if let Some(streaming) = streaming {
+        store.set("transcriptionStreaming", serde_json::json!(streaming));
+    }
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    if parsed == AudioTranscriptionEngine::WhisperLocal && find_local_whisper_model(&app).is_none() {
+        log_warn!("Local transcription selected but no whisper model is available yet");
+    }
+
+    log_info!("Transcription engine set to {}", parsed);
+    Ok(())
+}
+
+// In-progress recordings are written here before being moved to their save path
+fn recordings_dir<R: Runtime>(app: &AppHandle<R>) -> Result<std::path::PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join("recordings"))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create recordings directory: {}", e))?;
+    Ok(dir)
+}
+
+fn models_dir<R: Runtime>(app: &AppHandle<R>) -> Result<std::path::PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("models"))
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))
+}
+
+fn speaker_similarity_threshold<R: Runtime>(app: &AppHandle<R>) -> f32 {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get("speakerSimilarityThreshold"))
+        .and_then(|v| v.as_f64())
+        .map(|v| v as f32)
+        .unwrap_or(diarization::DEFAULT_SIMILARITY_THRESHOLD)
+}
+
+// Diarization runs whenever the embedding model has been downloaded
+fn load_diarizer<R: Runtime>(app: &AppHandle<R>) -> Option<Diarizer> {
+    let model_path = models_dir(app).ok().and_then(|dir| diarization::find_embedding_model(&dir))?;
+    match Diarizer::new(&model_path, speaker_similarity_threshold(app)) {
+        Ok(diarizer) => {
+            log_info!("Speaker diarization enabled");
+            Some(diarizer)
+        }
+        Err(e) => {
+            log_error!("Failed to load speaker embedding model, continuing without diarization: {}", e);
+            None
+        }
+    }
+}
+
+fn with_diarizer<T>(state: &RecordingState, f: impl FnOnce(&mut Diarizer) -> T) -> Result<Option<T>, String> {
+    let guard = state.lock().map_err(|e| e.to_string())?;
+    let diarizer = match guard.as_ref().and_then(|session| session.handles.diarizer.clone()) {
+        Some(diarizer) => diarizer,
+        None => return Ok(None),
+    };
+    drop(guard);
+    let mut diarizer = diarizer.lock().map_err(|e| e.to_string())?;
+    Ok(Some(f(&mut diarizer)))
+}
+
+#[tauri::command]
+async fn download_diarization_model<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    let dir = models_dir(&app)?;
+    if let Some(path) = diarization::find_embedding_model(&dir) {
+        return Ok(path.display().to_string());
+    }
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create models directory: {}", e))?;
+
+    log_info!("Downloading speaker embedding model from {}", diarization::EMBEDDING_MODEL_URL);
+    let response = reqwest::get(diarization::EMBEDDING_MODEL_URL)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download embedding model: {}", e))?;
+    let bytes = response.bytes().await.map_err(|e| format!("Failed to download embedding model: {}", e))?;
+
+    // Written atomically so an interrupted download is never picked up as a model
+    let path = dir.join(diarization::EMBEDDING_MODEL_FILE);
+    atomic_file::write(&path, &bytes).map_err(|e| format!("Failed to save embedding model: {}", e))?;
+
+    log_info!("Speaker embedding model saved to {}", path.display());
+    Ok(path.display().to_string())
+}
+
+#[tauri::command]
+fn get_speakers(state: State<'_, RecordingState>) -> Result<Vec<Speaker>, AppError> {
+    Ok(with_diarizer(&state, |diarizer| diarizer.speakers())?.unwrap_or_default())
+}
+
+/// Renames a speaker for the rest of the session. Emits `speaker-renamed` so the UI can
+/// relabel lines that were already shown.
+#[tauri::command]
+fn rename_speaker<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, RecordingState>,
+    speaker: String,
+    name: String,
+) -> Result<(), AppError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(AppError::InvalidInput("Speaker name cannot be empty".to_string()));
+    }
+    match with_diarizer(&state, |diarizer| diarizer.rename(&speaker, &name))? {
+        Some(true) => {
+            log_info!("Renamed {} to {}", speaker, name);
+            if let Err(e) = app.emit("speaker-renamed", serde_json::json!({ "from": speaker, "to": name })) {
+                log_error!("Failed to emit speaker-renamed event: {}", e);
+            }
+            Ok(())
+        }
+        Some(false) => Err(AppError::NotFound(format!("Speaker not found: {}", speaker))),
+        None => Err(AppError::Conflict("Speaker diarization is not active".to_string())),
     }
 }
 
+/// Cosine similarity above which a segment is attributed to an existing speaker.
+/// Lower values merge voices more eagerly; higher values split them more often.
 #[tauri::command]
-fn read_audio_file(file_path: String) -> Result<Vec<u8>, String> {
-    match std::fs::read(&file_path) {
-        Ok(data) => Ok(data),
-        Err(e) => Err(format!("Failed to read audio file: {}", e))
+fn set_speaker_similarity_threshold<R: Runtime>(
+    app: AppHandle<R>,
+    state: State<'_, RecordingState>,
+    threshold: f32,
+) -> Result<(), AppError> {
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(AppError::InvalidInput("Similarity threshold must be between 0 and 1".to_string()));
     }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set("speakerSimilarityThreshold", serde_json::json!(threshold));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    with_diarizer(&state, |diarizer| diarizer.set_threshold(threshold))?;
+    log_info!("Speaker similarity threshold set to {}", threshold);
+    Ok(())
+}
+
+#[tauri::command]
+async fn get_transcription_engine<R: Runtime>(app: AppHandle<R>) -> Result<TranscriptionEngineSettings, AppError> {
+    let config = load_transcription_config(&app);
+    Ok(TranscriptionEngineSettings {
+        engine: config.engine.setting_name().unwrap_or("whisper_server").to_string(),
+        local_model_path: config.local_model.map(|p| p.display().to_string()),
+        streaming: config.streaming,
+    })
+}
+
+#[tauri::command]
+fn read_audio_file(file_path: String) -> Result<Vec<u8>, AppError> {
+    Ok(std::fs::read(&file_path)?)
 }
 
 #[tauri::command]
-async fn save_transcript(file_path: String, content: String) -> Result<(), String> {
+async fn save_transcript(file_path: String, content: String) -> Result<(), AppError> {
     log::info!("Saving transcript to: {}", file_path);
 
     // Ensure parent directory exists
@@ -1159,7 +2709,7 @@ async fn save_transcript(file_path: String, content: String) -> Result<(), Strin
     }
 
     // Write content to file
-    std::fs::write(&file_path, content)
+    atomic_file::write(&file_path, content)
         .map_err(|e| format!("Failed to write transcript: {}", e))?;
 
     log::info!("Transcript saved successfully");
@@ -1167,15 +2717,19 @@ async fn save_transcript(file_path: String, content: String) -> Result<(), Strin
 }
 
 // Analytics commands
+
+/// Start analytics with `config`, or with the project the build was configured with. Without
+/// an API key the client is created disabled, so tracking calls do nothing instead of failing.
 #[tauri::command]
-async fn init_analytics() -> Result<(), String> {
-    let config = AnalyticsConfig {
-        api_key:"phc_cohhHPgfQfnNWl33THRRpCftuRtWx2k5svtKrkpFb04".to_string(),
-        host: Some("https://us.i.posthog.com".to_string()),
-        enabled: true ,
-    };
+async fn init_analytics<R: Runtime>(app: AppHandle<R>, config: Option<AnalyticsConfig>) -> Result<(), String> {
+    let mut config = config.unwrap_or_else(AnalyticsConfig::from_build_env);
+    if config.api_key.trim().is_empty() {
+        log_info!("No analytics key configured; analytics disabled");
+        config.enabled = false;
+    }
     
-    let client = Arc::new(AnalyticsClient::new(config).await);
+    let queue_path = app.path().app_data_dir().ok().map(|dir| dir.join(analytics::QUEUE_FILE));
+    let client = Arc::new(AnalyticsClient::new(config, queue_path).await);
     
     unsafe {
         ANALYTICS_CLIENT = Some(client);
@@ -1186,8 +2740,9 @@ async fn init_analytics() -> Result<(), String> {
 
 #[tauri::command]
 async fn disable_analytics() -> Result<(), String> {
-    unsafe {
-        ANALYTICS_CLIENT = None;
+    let client = unsafe { ANALYTICS_CLIENT.take() };
+    if let Some(client) = client {
+        client.discard_queue().await;
     }
     Ok(())
 }
@@ -1430,10 +2985,13 @@ fn stereo_to_mono(stereo: &[i16]) -> Vec<i16> {
 }
 
 pub fn run() {
-    log::set_max_level(log::LevelFilter::Info);
+    logging::init();
     
     tauri::Builder::default()
-        .setup(|_app| {
+        .manage(RecordingState::default())
+        .manage(LevelMonitorState::default())
+        .manage(wake_word::WakeWordState::default())
+        .setup(|app| {
             log::info!("Application setup complete");
 
             // Trigger microphone permission request on startup
@@ -1441,13 +2999,61 @@ pub fn run() {
                 log::error!("Failed to trigger audio permission: {}", e);
             }
 
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                session_recovery::log_unfinished(&handle);
+                wake_word::resume_if_enabled(&handle).await;
+            });
+            ollama::load_base_url(app.handle());
+            api::outbox::start(app.handle());
+            backend_status::start(app.handle());
+            retention::start(app.handle());
+            meeting_detector::start_if_enabled(app.handle());
+            recording_indicator::init(app.handle());
+            hotkeys::register_saved(app.handle());
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
+            list_audio_devices,
             start_recording,
             stop_recording,
+            api_version::get_api_version,
+            api_version::check_api_compatibility,
+            start_audio_level_monitor,
+            stop_audio_level_monitor,
+            wake_word::get_wake_word_config,
+            wake_word::set_wake_word_config,
+            wake_word::get_wake_word_status,
+            pipeline_config::get_pipeline_config,
+            pipeline_config::update_pipeline_config,
+            pipeline_config::get_applied_pipeline_configs,
+            pipeline_config::get_device_filters,
+            pipeline_config::set_device_filters,
+            pipeline_config::set_mic_agc,
+            get_recording_format,
+            set_recording_format,
             is_recording,
             get_transcription_status,
+            set_upload_part_size,
+            get_upload_part_size,
+            get_chunk_retry_policy,
+            get_transcription_language,
+            set_transcription_language,
+            translation::get_translation_config,
+            translation::set_translation_config,
+            server_capabilities::get_transcription_server_capabilities,
+            transcription_failover::get_transcription_failover,
+            transcription_failover::set_transcription_failover,
+            set_chunk_retry_policy,
+            set_transcription_engine,
+            get_transcription_engine,
+            set_transcript_server_url,
+            get_transcript_server_url,
+            download_diarization_model,
+            get_speakers,
+            rename_speaker,
+            set_speaker_similarity_threshold,
             read_audio_file,
             save_transcript,
             init_analytics,
@@ -1473,6 +3079,12 @@ pub fn run() {
             track_model_changed,
             track_custom_prompt_used,
             ollama::get_ollama_models,
+            ollama::pull_ollama_model,
+            ollama::delete_ollama_model,
+            ollama::show_ollama_model,
+            ollama::check_ollama_health,
+            ollama::get_ollama_base_url,
+            ollama::set_ollama_base_url,
             api::api_get_meetings,
             api::api_search_transcripts,
             api::api_get_profile,
@@ -1484,22 +3096,177 @@ pub fn run() {
             api::api_get_transcript_config,
             api::api_save_transcript_config,
             api::api_get_transcript_api_key,
+            secrets::get_secret,
+            secrets::set_secret,
             api::api_delete_meeting,
             api::api_get_meeting,
             api::api_save_meeting_title,
+            api::api_link_meeting_series,
+            api::api_get_meeting_series,
             api::api_save_meeting_summary,
+            api::api_save_meeting_metadata,
             api::api_get_summary,
+            api::api_get_meeting_summary,
+            summary::compare_summaries,
+            storage::meetings::local_create_meeting,
+            storage::meetings::local_list_meetings,
+            storage::meetings::local_get_meeting,
+            storage::meetings::local_update_meeting,
+            storage::meetings::local_delete_meeting,
+            storage::meetings::local_add_transcripts,
+            storage::meetings::local_save_summary,
+            storage::meetings::local_get_summary,
+            storage::tags::add_tag,
+            storage::tags::remove_tag,
+            storage::tags::list_tags,
+            storage::tags::get_meetings_by_tag,
+            storage::folders::list_folders,
+            storage::folders::create_folder,
+            storage::folders::rename_folder,
+            storage::folders::delete_folder,
+            storage::folders::move_meeting_to_folder,
+            batch::api_delete_meetings,
+            batch::export_meetings,
+            batch::retag_meetings,
+            backup::create_backup,
+            backup::restore_backup,
+            retention::get_retention_policy,
+            retention::set_retention_policy,
+            retention::preview_retention,
+            retention::run_retention_now,
+            webhooks::list_webhooks,
+            webhooks::add_webhook,
+            webhooks::set_webhook_enabled,
+            webhooks::remove_webhook,
+            webhooks::test_webhook,
+            usage_stats::get_usage_stats,
+            usage_stats::clear_usage_stats,
+            slack::get_slack_config,
+            slack::set_slack_config,
+            slack::post_summary_to_slack,
+            email::get_email_config,
+            email::set_email_config,
+            email::test_email_connection,
+            email::send_meeting_email,
+            storage::search::search_transcripts_local,
+            storage::search::search_in_meeting,
+            storage::semantic::semantic_search,
+            storage::semantic::update_semantic_index,
+            activity::get_activity_feed,
+            workspace::list_workspaces,
+            workspace::get_active_workspace,
+            workspace::switch_workspace,
+            permissions::get_permission_status,
+            permissions::check_audio_permissions,
+            activity::mark_activity_read,
             api::api_save_transcript,
             api::api_process_transcript,
+            transcript_sync::start_transcript_sync,
+            transcript_sync::resolve_concurrent_recording,
+            playback::get_audio_segment,
+            playback::get_playback_index,
+            meeting_timer::set_meeting_end_time,
+            meeting_timer::get_meeting_time_status,
+            artifact_storage::get_remote_storage_config,
+            artifact_storage::set_remote_storage_config,
+            artifact_storage::upload_to_remote_storage,
+            transcript_sync::stop_transcript_sync,
+            transcript_sync::get_transcript_sync_status,
+            duplicates::check_duplicate_recording,
+            duplicates::register_recording,
+            duplicates::resolve_duplicate_recording,
+            duplicates::find_meeting_by_clip,
+            telemetry::get_dependency_health,
+            diagnostics::get_diagnostics,
+            self_test::run_self_test,
+            export::docx::export_meeting_docx,
+            export::docx::get_docx_template,
+            export::docx::set_docx_template,
+            export::html::export_meeting_html,
+            export::stems::export_speaker_stems,
+            export::subtitles::export_transcript,
+            export::export_meeting,
+            export::share::share_meeting_qr,
+            export::share::stop_meeting_share,
+            export::hooks::get_export_hook,
+            export::hooks::set_export_hook,
+            export::notion::get_notion_config,
+            export::notion::set_notion_config,
+            export::notion::export_to_notion,
+            export::confluence::get_confluence_config,
+            export::confluence::set_confluence_config,
+            export::confluence::export_to_confluence,
+            action_items::list_action_items,
+            action_items::update_action_item,
+            action_items::import_meeting_action_items,
+            action_items::sync_action_items,
+            voice_commands::get_voice_command_config,
+            voice_commands::set_voice_command_config,
+            voice_commands::get_voice_commands,
+            chunk_spill::get_pending_chunk_count,
+            chunk_spill::retranscribe_pending_chunks,
+            chunk_spill::get_dead_letter_chunks,
+            chunk_spill::retry_dead_letter_chunks,
+            session_recovery::get_unfinished_session,
+            session_recovery::recover_last_session,
+            session_recovery::discard_unfinished_session,
+            privacy_pause::set_privacy_pause,
+            privacy_pause::toggle_privacy_pause,
+            privacy_pause::get_privacy_pause_status,
+            mixer::set_mix_ratio,
+            mixer::mute_source,
+            plugins::list_plugins,
+            plugins::set_plugin,
+            plugins::remove_plugin,
+            plugins::get_plugin_annotations,
+            capture_process::set_capture_process,
+            capture_process::get_capture_process,
+            meeting_detector::get_meeting_detection_config,
+            meeting_detector::set_meeting_detection_config,
+            meeting_detector::get_detected_meeting,
+            calendar::get_calendar_config,
+            calendar::set_calendar_config,
+            calendar::get_current_calendar_events,
+            calendar::get_linked_calendar_event,
+            calendar::get_speaker_candidates,
+            hotkeys::get_hotkeys,
+            hotkeys::set_hotkeys,
+            markers::add_marker,
+            markers::list_markers,
+            summarize::summarize_transcript,
+            prompt_templates::list_prompt_templates,
+            prompt_templates::create_prompt_template,
+            prompt_templates::update_prompt_template,
+            prompt_templates::delete_prompt_template,
+            extraction::extract_action_items,
+            ask::ask_meeting,
     
             api::test_backend_connection,
             api::debug_backend_connection,
+            api::get_backend_url,
+            api::set_backend_url,
+            backend_status::get_backend_status,
+            api::outbox::get_sync_status,
+            http::get_http_policy,
+            http::set_http_policy,
             api::open_external_url,
             console_utils::show_console,
             console_utils::hide_console,
             console_utils::toggle_console,
+            logging::set_log_level,
+            logging::get_log_levels,
+            logging::set_transcript_logging,
+            logging::export_logs,
+            crash::get_last_crash_report,
+            crash::dismiss_crash_report,
+            crash::upload_crash_report,
         ])
         .plugin(tauri_plugin_store::Builder::new().build())
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(|app, shortcut, event| hotkeys::handle(app, shortcut, event))
+                .build(),
+        )
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -1510,16 +3277,73 @@ fn resample_audio(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
         return samples.to_vec();
     }
     
-    let ratio = to_rate as f32 / from_rate as f32;
-    let new_len = (samples.len() as f32 * ratio) as usize;
-    let mut resampled = Vec::with_capacity(new_len);
-    
-    for i in 0..new_len {
-        let src_idx = (i as f32 / ratio) as usize;
-        if src_idx < samples.len() {
-            resampled.push(samples[src_idx]);
+    // Band-limited resampling; dropping samples aliases everything above the new Nyquist into the speech band
+    match audio::audio_processing::resample_sinc(samples, from_rate, to_rate) {
+        Ok(resampled) => resampled,
+        Err(e) => {
+            log_warn!("Sinc resampling from {} to {} Hz failed ({}), using linear interpolation", from_rate, to_rate, e);
+            audio::audio_processing::resample_linear(samples, from_rate, to_rate)
         }
     }
-    
-    resampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_fallback(fallback: Option<&str>) -> TranscriptionConfig {
+        TranscriptionConfig {
+            engine: AudioTranscriptionEngine::WhisperServer,
+            local_model: None,
+            streaming: false,
+            retry: ChunkRetryPolicy::default(),
+            language: None,
+            translate: false,
+            sample_format: SampleFormat::default(),
+            server_diarization: false,
+            failover: FailoverConfig { fallback_engine: fallback.map(str::to_string), ..FailoverConfig::default() },
+        }
+    }
+
+    // Run one chunk as the worker does: transcribe with failover, then decide whether the
+    // failure stops the recording. Only the fallback engine succeeds.
+    async fn run_chunk(handles: &SessionHandles, config: &TranscriptionConfig, switches: &Mutex<u32>) -> (bool, bool) {
+        let result = transcribe_with_failover(
+            handles,
+            config,
+            |engine| async move {
+                match engine {
+                    AudioTranscriptionEngine::OpenAi => Ok(TranscriptResponse { segments: Vec::new(), buffer_size_ms: 0, language: None }),
+                    _ => Err("Failed to connect".to_string()),
+                }
+            },
+            |_, _| *switches.lock().unwrap() += 1,
+        )
+        .await;
+        let stopped = result.is_err() && should_stop_after_failure(handles, config);
+        (result.is_ok(), stopped)
+    }
+
+    #[tokio::test]
+    async fn failing_chunks_fail_over_instead_of_stopping() {
+        let handles = SessionHandles::new(Arc::new(AtomicBool::new(true)), None);
+        let config = config_with_fallback(Some("openai"));
+        let switches = Mutex::new(0);
+
+        assert_eq!(run_chunk(&handles, &config, &switches).await, (false, false));
+        assert_eq!(run_chunk(&handles, &config, &switches).await, (false, false));
+        assert_eq!(run_chunk(&handles, &config, &switches).await, (true, false));
+        assert_eq!(*switches.lock().unwrap(), 1);
+        assert_eq!(handles.failover.active(&config), AudioTranscriptionEngine::OpenAi);
+    }
+
+    #[tokio::test]
+    async fn failing_chunk_stops_without_failover() {
+        let handles = SessionHandles::new(Arc::new(AtomicBool::new(true)), None);
+        let config = config_with_fallback(None);
+        let switches = Mutex::new(0);
+
+        assert_eq!(run_chunk(&handles, &config, &switches).await, (false, true));
+        assert_eq!(*switches.lock().unwrap(), 0);
+    }
 }
@@ -0,0 +1,428 @@
+// Calls to the summarization model the user picked in the model settings, made directly
+// from the app rather than through the backend. Each kind of API is a `Provider`: Ollama,
+// OpenAI-compatible chat completions (OpenAI and Groq) and Anthropic's messages API, so
+// summaries, extraction and questions work the same against any of them. The settings and
+// settings still live on the backend while API keys come from the keychain; a local Ollama
+// model needs neither the backend nor a network connection.
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+use log::info as log_info;
+
+use crate::api::{make_api_request, ModelConfig};
+use crate::ollama;
+use crate::secrets::{self, KeyKind};
+use crate::telemetry::{self, Dependency};
+
+const OPENAI_CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
+const GROQ_CHAT_URL: &str = "https://api.groq.com/openai/v1/chat/completions";
+const ANTHROPIC_MESSAGES_URL: &str = "https://api.anthropic.com/v1/messages";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+const MAX_OUTPUT_TOKENS: u32 = 4096;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+// Characters per token for English text with the common tokenizers
+const CHARS_PER_TOKEN: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct LlmConfig {
+    pub provider: String,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+
+/// One prompt: instructions for the model and the text to work on.
+#[derive(Debug, Clone, Copy)]
+pub struct Prompt<'a> {
+    pub system: &'a str,
+    pub user: &'a str,
+    /// Ask for a single JSON object, where the provider supports enforcing it
+    pub json_output: bool,
+}
+
+#[async_trait]
+pub trait Provider: Send + Sync {
+    /// The provider as named in the model settings, e.g. `ollama`
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    /// Send a prompt and return the whole answer.
+    async fn generate(&self, prompt: Prompt<'_>) -> Result<String, String>;
+
+    /// Send a prompt and pass the answer to `on_token` piece by piece as it is written.
+    /// Returns the whole answer once the model is done.
+    async fn stream(&self, prompt: Prompt<'_>, on_token: &mut (dyn FnMut(&str) + Send)) -> Result<String, String>;
+
+    /// Roughly how many tokens `text` is for this model, for sizing prompts.
+    fn count_tokens(&self, text: &str) -> usize {
+        text.chars().count().div_ceil(CHARS_PER_TOKEN)
+    }
+}
+
+/// The provider the configured model runs on.
+pub fn provider(config: &LlmConfig) -> Result<Box<dyn Provider>, String> {
+    let api_key = || {
+        config
+            .api_key
+            .clone()
+            .ok_or_else(|| format!("No API key is saved for {}", config.provider))
+    };
+    match config.provider.as_str() {
+        "ollama" => Ok(Box::new(Ollama { model: config.model.clone() })),
+        "openai" => Ok(Box::new(OpenAiCompatible {
+            name: "openai",
+            url: OPENAI_CHAT_URL,
+            model: config.model.clone(),
+            api_key: api_key()?,
+        })),
+        "groq" => Ok(Box::new(OpenAiCompatible {
+            name: "groq",
+            url: GROQ_CHAT_URL,
+            model: config.model.clone(),
+            api_key: api_key()?,
+        })),
+        "claude" => Ok(Box::new(Anthropic { model: config.model.clone(), api_key: api_key()? })),
+        other => Err(format!("Unsupported summarization provider: {}", other)),
+    }
+}
+
+async fn api_key_for<R: Runtime>(app: &AppHandle<R>, provider: &str, auth_token: Option<String>) -> Option<String> {
+    if provider == "ollama" {
+        return None;
+    }
+    secrets::api_key(app, KeyKind::Summary, provider, auth_token).await.ok().flatten()
+}
+
+/// The configured summarization model, with its API key for cloud providers.
+pub async fn configured<R: Runtime>(app: &AppHandle<R>, auth_token: Option<String>) -> Result<LlmConfig, String> {
+    let config = make_api_request::<R, Option<ModelConfig>>(app, "/get-model-config", "GET", None, None, auth_token.clone())
+        .await?
+        .ok_or_else(|| "No summarization model is configured".to_string())?;
+    let api_key = api_key_for(app, &config.provider, auth_token).await;
+    Ok(LlmConfig { provider: config.provider, model: config.model, api_key })
+}
+
+/// An explicitly chosen provider and model, or the configured one when either is missing.
+/// A chosen Ollama model needs nothing from the backend.
+pub async fn resolve<R: Runtime>(
+    app: &AppHandle<R>,
+    provider: Option<String>,
+    model: Option<String>,
+    auth_token: Option<String>,
+) -> Result<LlmConfig, String> {
+    match (provider, model) {
+        (Some(provider), Some(model)) => {
+            let api_key = api_key_for(app, &provider, auth_token).await;
+            Ok(LlmConfig { provider, model, api_key })
+        }
+        _ => configured(app, auth_token).await,
+    }
+}
+
+async fn post_json(request: reqwest::RequestBuilder, body: &Value) -> Result<reqwest::Response, String> {
+    let response = request
+        .timeout(REQUEST_TIMEOUT)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach the model: {}", e))?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        return Err(format!("Model request failed with status {}: {}", status, text));
+    }
+    Ok(response)
+}
+
+// Read a streamed answer line by line; `token` picks the text out of one line
+async fn read_stream(
+    response: reqwest::Response,
+    token: impl Fn(&str) -> Result<Option<String>, String>,
+    on_token: &mut (dyn FnMut(&str) + Send),
+) -> Result<String, String> {
+    let mut answer = String::new();
+    let mut pending = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(bytes) = stream.next().await {
+        let bytes = bytes.map_err(|e| format!("Failed to read the model's answer: {}", e))?;
+        pending.extend_from_slice(&bytes);
+        while let Some(newline) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(token) = token(line)? {
+                on_token(&token);
+                answer.push_str(&token);
+            }
+        }
+    }
+    Ok(answer)
+}
+
+fn parse_event(data: &str) -> Result<Value, String> {
+    let event: Value = serde_json::from_str(data).map_err(|e| format!("Failed to parse streamed answer: {}", e))?;
+    if let Some(error) = event.get("error") {
+        let message = error
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string());
+        return Err(format!("The model stopped with an error: {}", message));
+    }
+    Ok(event)
+}
+
+// Server-sent events carry their JSON on `data:` lines
+fn sse_event(line: &str) -> Result<Option<Value>, String> {
+    match line.strip_prefix("data:").map(str::trim) {
+        Some("[DONE]") | None => Ok(None),
+        Some(data) => parse_event(data).map(Some),
+    }
+}
+
+fn text_at(event: &Value, pointer: &str) -> Option<String> {
+    event
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+}
+
+/// A model served by Ollama, local or at the configured address.
+struct Ollama {
+    model: String,
+}
+
+impl Ollama {
+    fn request(&self, prompt: Prompt<'_>, stream: bool) -> (reqwest::RequestBuilder, Value) {
+        let mut body = json!({
+            "model": self.model,
+            "stream": stream,
+            "messages": [
+                { "role": "system", "content": prompt.system },
+                { "role": "user", "content": prompt.user },
+            ],
+        });
+        if prompt.json_output {
+            body["format"] = json!("json");
+        }
+        (ollama::client().post(format!("{}/api/chat", ollama::base_url())), body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatMessage {
+    content: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaChatResponse {
+    message: ChatMessage,
+}
+
+#[async_trait]
+impl Provider for Ollama {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate(&self, prompt: Prompt<'_>) -> Result<String, String> {
+        let (request, body) = self.request(prompt, false);
+        let response: OllamaChatResponse = post_json(request, &body)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+        Ok(response.message.content)
+    }
+
+    async fn stream(&self, prompt: Prompt<'_>, on_token: &mut (dyn FnMut(&str) + Send)) -> Result<String, String> {
+        let (request, body) = self.request(prompt, true);
+        let response = post_json(request, &body).await?;
+        // One JSON object per line
+        read_stream(response, |line| Ok(text_at(&parse_event(line)?, "/message/content")), on_token).await
+    }
+}
+
+/// The OpenAI chat completions API, which Groq and many others also offer.
+struct OpenAiCompatible {
+    name: &'static str,
+    url: &'static str,
+    model: String,
+    api_key: String,
+}
+
+impl OpenAiCompatible {
+    fn request(&self, prompt: Prompt<'_>, stream: bool) -> (reqwest::RequestBuilder, Value) {
+        let mut body = json!({
+            "model": self.model,
+            "stream": stream,
+            "messages": [
+                { "role": "system", "content": prompt.system },
+                { "role": "user", "content": prompt.user },
+            ],
+        });
+        if prompt.json_output {
+            body["response_format"] = json!({ "type": "json_object" });
+        }
+        (reqwest::Client::new().post(self.url).bearer_auth(&self.api_key), body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiChoice {
+    message: ChatMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiResponse {
+    choices: Vec<OpenAiChoice>,
+}
+
+#[async_trait]
+impl Provider for OpenAiCompatible {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate(&self, prompt: Prompt<'_>) -> Result<String, String> {
+        let (request, body) = self.request(prompt, false);
+        let response: OpenAiResponse = post_json(request, &body)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse {} response: {}", self.name, e))?;
+        response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message.content)
+            .ok_or_else(|| format!("{} returned no answer", self.name))
+    }
+
+    async fn stream(&self, prompt: Prompt<'_>, on_token: &mut (dyn FnMut(&str) + Send)) -> Result<String, String> {
+        let (request, body) = self.request(prompt, true);
+        let response = post_json(request, &body).await?;
+        read_stream(
+            response,
+            |line| Ok(sse_event(line)?.and_then(|event| text_at(&event, "/choices/0/delta/content"))),
+            on_token,
+        )
+        .await
+    }
+}
+
+/// Claude models through Anthropic's messages API.
+struct Anthropic {
+    model: String,
+    api_key: String,
+}
+
+impl Anthropic {
+    fn request(&self, prompt: Prompt<'_>, stream: bool) -> (reqwest::RequestBuilder, Value) {
+        // No JSON mode; the system prompt asking for JSON is what keeps Claude to it
+        let body = json!({
+            "model": self.model,
+            "max_tokens": MAX_OUTPUT_TOKENS,
+            "stream": stream,
+            "system": prompt.system,
+            "messages": [{ "role": "user", "content": prompt.user }],
+        });
+        let request = reqwest::Client::new()
+            .post(ANTHROPIC_MESSAGES_URL)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_VERSION);
+        (request, body)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicContent {
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<AnthropicContent>,
+}
+
+#[async_trait]
+impl Provider for Anthropic {
+    fn name(&self) -> &str {
+        "claude"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    async fn generate(&self, prompt: Prompt<'_>) -> Result<String, String> {
+        let (request, body) = self.request(prompt, false);
+        let response: AnthropicResponse = post_json(request, &body)
+            .await?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Claude response: {}", e))?;
+        Ok(response.content.into_iter().map(|content| content.text).collect())
+    }
+
+    async fn stream(&self, prompt: Prompt<'_>, on_token: &mut (dyn FnMut(&str) + Send)) -> Result<String, String> {
+        let (request, body) = self.request(prompt, true);
+        let response = post_json(request, &body).await?;
+        read_stream(response, |line| Ok(sse_event(line)?.and_then(|event| text_at(&event, "/delta/text"))), on_token)
+            .await
+    }
+}
+
+fn dependency(provider: &dyn Provider) -> Dependency {
+    if provider.name() == "ollama" {
+        Dependency::Ollama
+    } else {
+        Dependency::CloudProvider
+    }
+}
+
+/// Send one prompt to the configured model and return the whole answer.
+pub async fn complete(config: &LlmConfig, system: &str, user: &str, json_output: bool) -> Result<String, String> {
+    let provider = provider(config)?;
+    log_info!("Prompting {} model {}", provider.name(), provider.model());
+    let started = Instant::now();
+    let result = provider.generate(Prompt { system, user, json_output }).await;
+    telemetry::record(dependency(provider.as_ref()), started.elapsed(), result.as_ref().err().map(|e| e.as_str()));
+    result
+}
+
+/// Send one prompt to the configured model, passing the answer to `on_token` as it is
+/// written, and return the whole answer.
+pub async fn stream(
+    config: &LlmConfig,
+    system: &str,
+    user: &str,
+    mut on_token: impl FnMut(&str) + Send,
+) -> Result<String, String> {
+    let provider = provider(config)?;
+    log_info!("Prompting {} model {} with a streamed answer", provider.name(), provider.model());
+    provider.stream(Prompt { system, user, json_output: false }, &mut on_token).await
+}
+
+/// Pull the JSON object out of a model's answer, ignoring any text around it.
+pub fn extract_json(answer: &str) -> Option<Value> {
+    let start = answer.find('{')?;
+    let end = answer.rfind('}')?;
+    serde_json::from_str(answer.get(start..=end)?).ok()
+}
@@ -0,0 +1,292 @@
+// Log output and its verbosity. Records from the `log` macros used throughout the app are
+// routed into a tracing subscriber whose filter can be swapped at runtime, so one
+// subsystem can be turned up to debug while the rest stays at info, without a restart.
+//
+// Targets are module paths; short names like `audio` or `api` are taken to mean the
+// app's own module of that name (`app_lib::audio`).
+//
+// Besides the console, logs go to `logs/` in the app data directory, one file per day with
+// the last week kept; `export_logs` zips them up for a bug report. What was said in a
+// meeting stays out of the logs: transcript text is written through `redact`, which only
+// shows the length unless transcript logging is switched on for debugging.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::{Lazy, OnceCell};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{EnvFilter, LevelFilter};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Registry;
+use zip::write::FileOptions;
+use zip::{CompressionMethod, ZipWriter};
+use log::{info as log_info, warn as log_warn};
+
+use crate::atomic_file::AtomicFile;
+use crate::diagnostics;
+
+const CRATE_TARGET: &str = "app_lib";
+const DEFAULT_LEVEL: LevelFilter = LevelFilter::INFO;
+// Matches the bundle identifier, so logs sit next to the rest of the app data
+const APP_IDENTIFIER: &str = "com.meetily.ai";
+const LOG_FILE_PREFIX: &str = "meetily";
+const MAX_LOG_FILES: usize = 7;
+
+static LOG_TRANSCRIPTS: AtomicBool = AtomicBool::new(false);
+/// Precedes transcript text written unredacted, so those lines can be found again later
+pub(crate) const TRANSCRIPT_MARKER: &str = "[transcript]";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LogLevels {
+    pub default: String,
+    /// Per-target overrides, keyed by module path
+    pub targets: BTreeMap<String, String>,
+    /// Whether transcript text is written to the logs instead of being redacted
+    pub log_transcripts: bool,
+}
+
+/// Text from a meeting, shown in logs only as its length unless transcript logging is on.
+pub struct Redacted<'a>(&'a str);
+
+pub fn redact(text: &str) -> Redacted<'_> {
+    Redacted(text)
+}
+
+impl fmt::Display for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if LOG_TRANSCRIPTS.load(Ordering::Relaxed) {
+            write!(f, "{} {}", TRANSCRIPT_MARKER, self.0)
+        } else {
+            write!(f, "[{} chars redacted]", self.0.chars().count())
+        }
+    }
+}
+
+/// The app data directory. Logging starts before Tauri can resolve it, so it is worked out here.
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join(APP_IDENTIFIER))
+}
+
+/// Folder the log files are written to.
+pub fn log_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("logs"))
+}
+
+fn file_appender() -> Option<RollingFileAppender> {
+    let dir = log_dir()?;
+    RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| eprintln!("Logging to the console only; failed to open log files in {}: {}", dir.display(), e))
+        .ok()
+}
+
+struct Levels {
+    default: LevelFilter,
+    targets: BTreeMap<String, LevelFilter>,
+}
+
+impl Levels {
+    fn directives(&self) -> String {
+        std::iter::once(self.default.to_string())
+            .chain(self.targets.iter().map(|(target, level)| format!("{}={}", target, level)))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    fn most_verbose(&self) -> LevelFilter {
+        self.targets.values().copied().fold(self.default, LevelFilter::max)
+    }
+}
+
+static LEVELS: Lazy<Mutex<Levels>> =
+    Lazy::new(|| Mutex::new(Levels { default: DEFAULT_LEVEL, targets: BTreeMap::new() }));
+static FILTER: OnceCell<reload::Handle<EnvFilter, Registry>> = OnceCell::new();
+
+// `log` checks its own maximum before a record ever reaches the filter
+fn set_log_max_level(level: LevelFilter) {
+    let max = match level.into_level() {
+        None => log::LevelFilter::Off,
+        Some(tracing::Level::ERROR) => log::LevelFilter::Error,
+        Some(tracing::Level::WARN) => log::LevelFilter::Warn,
+        Some(tracing::Level::INFO) => log::LevelFilter::Info,
+        Some(tracing::Level::DEBUG) => log::LevelFilter::Debug,
+        Some(tracing::Level::TRACE) => log::LevelFilter::Trace,
+    };
+    log::set_max_level(max);
+}
+
+/// Install the logger at info level, or with the directives in RUST_LOG when it is set.
+/// Safe to call more than once; later calls do nothing.
+pub fn init() {
+    if FILTER.get().is_some() {
+        return;
+    }
+    let filter = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|directives| EnvFilter::try_new(directives).ok())
+        .unwrap_or_else(|| EnvFilter::new(DEFAULT_LEVEL.to_string()));
+    let max_level = filter.max_level_hint().unwrap_or(LevelFilter::TRACE);
+    let (filter, handle) = reload::Layer::new(filter);
+    let file_layer = file_appender().map(|appender| tracing_subscriber::fmt::layer().with_ansi(false).with_writer(appender));
+    let installed = tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
+        .try_init();
+    if installed.is_ok() {
+        let _ = FILTER.set(handle);
+        set_log_max_level(max_level);
+    }
+}
+
+fn qualify(target: &str) -> String {
+    if target == CRATE_TARGET || target.contains("::") {
+        target.to_string()
+    } else {
+        format!("{}::{}", CRATE_TARGET, target)
+    }
+}
+
+fn apply(levels: &Levels) -> Result<(), String> {
+    let handle = FILTER.get().ok_or("Logging is not initialized")?;
+    let filter = EnvFilter::try_new(levels.directives()).map_err(|e| format!("Invalid log filter: {}", e))?;
+    handle.reload(filter).map_err(|e| format!("Failed to apply log level: {}", e))?;
+    set_log_max_level(levels.most_verbose());
+    Ok(())
+}
+
+fn snapshot(levels: &Levels) -> LogLevels {
+    LogLevels {
+        default: levels.default.to_string(),
+        targets: levels.targets.iter().map(|(target, level)| (target.clone(), level.to_string())).collect(),
+        log_transcripts: LOG_TRANSCRIPTS.load(Ordering::Relaxed),
+    }
+}
+
+fn log_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| {
+                    path.is_file()
+                        && path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with(LOG_FILE_PREFIX))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// The last `count` lines written to the log files, oldest first.
+pub(crate) fn recent_lines(count: usize) -> Vec<String> {
+    let Some(dir) = log_dir() else {
+        return Vec::new();
+    };
+    let mut lines = Vec::new();
+    // Shortly after midnight the lines wanted can be spread over two files
+    for path in log_files(&dir).iter().rev() {
+        let Ok(contents) = std::fs::read(path) else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(&contents);
+        let earlier: Vec<String> = text.lines().rev().take(count - lines.len()).map(str::to_string).collect();
+        lines.extend(earlier);
+        if lines.len() >= count {
+            break;
+        }
+    }
+    lines.reverse();
+    lines
+}
+
+/// Set the log level of one target (e.g. `audio`, `api`, `app_lib::transcript_sync`), or
+/// the default for everything else when `target` is omitted or `*`. `level` is one of
+/// off, error, warn, info, debug or trace; `reset` removes a target's override.
+#[tauri::command]
+pub fn set_log_level(target: Option<String>, level: String) -> Result<LogLevels, String> {
+    let mut levels = LEVELS.lock().map_err(|e| e.to_string())?;
+    let target = target.map(|t| t.trim().to_string()).filter(|t| !t.is_empty() && t != "*");
+    match (target, level.trim().to_lowercase().as_str()) {
+        (Some(target), "reset") => {
+            levels.targets.remove(&qualify(&target));
+        }
+        (None, "reset") => levels.default = DEFAULT_LEVEL,
+        (target, level) => {
+            let level = LevelFilter::from_str(level).map_err(|_| format!("Unknown log level: {}", level))?;
+            match target {
+                Some(target) => {
+                    levels.targets.insert(qualify(&target), level);
+                }
+                None => levels.default = level,
+            }
+        }
+    }
+    apply(&levels)?;
+    log_info!("Log filter is now {}", levels.directives());
+    Ok(snapshot(&levels))
+}
+
+#[tauri::command]
+pub fn get_log_levels() -> Result<LogLevels, String> {
+    let levels = LEVELS.lock().map_err(|e| e.to_string())?;
+    Ok(snapshot(&levels))
+}
+
+/// Write transcript text to the logs instead of redacting it, e.g. while debugging
+/// transcription. Off again at the next start.
+#[tauri::command]
+pub fn set_transcript_logging(enabled: bool) -> Result<LogLevels, String> {
+    LOG_TRANSCRIPTS.store(enabled, Ordering::Relaxed);
+    if enabled {
+        log_warn!("Transcript text is now written to the logs");
+    }
+    let levels = LEVELS.lock().map_err(|e| e.to_string())?;
+    Ok(snapshot(&levels))
+}
+
+/// Zip the log files, plus a diagnostics report, into `path` to attach to a bug report.
+/// Returns the path written.
+#[tauri::command]
+pub fn export_logs<R: Runtime>(app: AppHandle<R>, path: String) -> Result<String, String> {
+    let dir = log_dir().ok_or("No log folder on this system")?;
+    let files = log_files(&dir);
+    if files.is_empty() {
+        return Err(format!("No log files found in {}", dir.display()));
+    }
+    let report = serde_json::to_vec_pretty(&diagnostics::get_diagnostics(app)).map_err(|e| e.to_string())?;
+
+    let zip_error = |e: zip::result::ZipError| format!("Failed to write log archive: {}", e);
+    // Written aside and moved into place, so a failed export leaves no truncated archive
+    let file = AtomicFile::create(&path).map_err(|e| format!("Failed to create {}: {}", path, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("diagnostics.json", options).map_err(zip_error)?;
+    zip.write_all(&report).map_err(|e| e.to_string())?;
+    for log_file in &files {
+        let name = log_file.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        // Today's file is still being written to; whatever is in it so far is exported
+        let contents = std::fs::read(log_file).map_err(|e| format!("Failed to read {}: {}", log_file.display(), e))?;
+        zip.start_file(name, options).map_err(zip_error)?;
+        zip.write_all(&contents).map_err(|e| e.to_string())?;
+    }
+    zip.finish()
+        .map_err(zip_error)?
+        .commit()
+        .map_err(|e| format!("Failed to save log archive: {}", e))?;
+    log_info!("Exported {} log files to {}", files.len(), path);
+    Ok(path)
+}
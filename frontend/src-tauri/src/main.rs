@@ -4,11 +4,10 @@
 )]
 
 use log;
-use env_logger;
 
 fn main() {
-    std::env::set_var("RUST_LOG", "info");
-    env_logger::init();
+    app_lib::logging::init();
+    app_lib::crash::install_panic_hook();
     log::info!("Starting application...");
     app_lib::run();
 }
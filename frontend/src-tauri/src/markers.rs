@@ -0,0 +1,108 @@
+// Markers: labelled points in a recording ("decision", "action item", or a plain bookmark
+// from the shortcut) that can be jumped back to later. Each one is tied to the elapsed
+// recording time, shows up as a line in the live transcript and is saved in the meeting's
+// metadata under `markers`, so it is still there when the meeting is opened again.
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error};
+
+use crate::api::{make_api_request, MeetingDetails};
+use crate::utils::format_timestamp;
+use crate::{is_recording, meeting_metadata, meeting_timer, publish_note, transcript_sync};
+
+const MARKERS_METADATA_KEY: &str = "markers";
+const TRANSCRIPT_SOURCE: &str = "Marker";
+const MAX_LABEL_CHARS: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Marker {
+    pub id: String,
+    pub label: String,
+    /// Seconds since the recording started
+    pub offset_secs: f64,
+    pub timestamp: String,
+    pub created_at: DateTime<Utc>,
+}
+
+static MARKERS: Lazy<Mutex<Vec<Marker>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Forget the previous recording's markers. Called when a new recording starts.
+pub fn begin_recording() {
+    if let Ok(mut markers) = MARKERS.lock() {
+        markers.clear();
+    }
+}
+
+/// Mark the current moment of the recording with `label`.
+pub fn add<R: Runtime>(app: &AppHandle<R>, label: &str) -> Result<Marker, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("A marker needs a label".to_string());
+    }
+    if label.chars().count() > MAX_LABEL_CHARS {
+        return Err(format!("Marker labels are limited to {} characters", MAX_LABEL_CHARS));
+    }
+    let elapsed = meeting_timer::elapsed()
+        .filter(|_| is_recording())
+        .ok_or_else(|| "Markers can only be added while recording".to_string())?;
+
+    let offset_secs = elapsed.as_secs_f64();
+    let marker = Marker {
+        id: uuid::Uuid::new_v4().to_string(),
+        label: label.to_string(),
+        offset_secs,
+        timestamp: format_timestamp(offset_secs),
+        created_at: Utc::now(),
+    };
+    {
+        let mut markers = MARKERS.lock().map_err(|e| format!("Failed to lock markers: {}", e))?;
+        markers.push(marker.clone());
+        meeting_metadata::set(MARKERS_METADATA_KEY, serde_json::json!(&*markers));
+    }
+    publish_note(app, TRANSCRIPT_SOURCE, label, offset_secs);
+    log_info!("Marker '{}' added at {}", marker.label, marker.timestamp);
+    if let Err(e) = app.emit("marker-added", &marker) {
+        log_error!("Failed to emit marker-added event: {}", e);
+    }
+    Ok(marker)
+}
+
+#[tauri::command]
+pub async fn add_marker<R: Runtime>(app: AppHandle<R>, label: String) -> Result<Marker, String> {
+    add(&app, &label)
+}
+
+/// Markers of a meeting in recording order. Without a meeting id, or for the meeting being
+/// recorded, they come from the current recording; otherwise from the saved meeting.
+#[tauri::command]
+pub async fn list_markers<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: Option<String>,
+    auth_token: Option<String>,
+) -> Result<Vec<Marker>, String> {
+    let current_meeting = transcript_sync::get_transcript_sync_status().meeting_id;
+    let meeting_id = match meeting_id {
+        Some(id) if current_meeting.as_deref() != Some(id.as_str()) => id,
+        _ => return Ok(MARKERS.lock().map(|markers| markers.clone()).unwrap_or_default()),
+    };
+    let meeting = make_api_request::<R, MeetingDetails>(
+        &app,
+        &format!("/get-meeting/{}", meeting_id),
+        "GET",
+        None,
+        None,
+        auth_token,
+    )
+    .await?;
+    let saved = meeting.metadata.and_then(|mut metadata| metadata.get_mut(MARKERS_METADATA_KEY).map(serde_json::Value::take));
+    let mut markers: Vec<Marker> = match saved {
+        Some(value) => serde_json::from_value(value)
+            .map_err(|e| format!("Failed to read markers of meeting {}: {}", meeting_id, e))?,
+        None => Vec::new(),
+    };
+    markers.sort_by(|a, b| a.offset_secs.total_cmp(&b.offset_secs));
+    Ok(markers)
+}
@@ -0,0 +1,250 @@
+// Notices when a call starts in a meeting app, so the user can be offered a recording (or,
+// if they opted in, have one started for them). Every few seconds the running processes
+// and window titles are matched against known meeting apps: Zoom, Microsoft Teams, Google
+// Meet in a browser and Webex. Only a process or title that means a call is in progress
+// counts, not the app merely being open.
+//
+// `meeting-detected` goes out when a call has been seen on two checks in a row and
+// `meeting-ended` once it has been gone for a while, since a title can briefly change
+// while the user moves between windows. Titles are only read on this machine and are
+// never stored or sent anywhere.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, debug as log_debug};
+
+use crate::is_recording;
+
+const MEETING_DETECTION_KEY: &str = "meetingDetection";
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+// Checks a call must be seen on before it counts, and missing on before it has ended
+const CONFIRM_CHECKS: u32 = 2;
+const END_CHECKS: u32 = 6;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MeetingDetectionConfig {
+    pub enabled: bool,
+    /// Start recording as soon as a call is detected instead of only offering to
+    pub auto_start: bool,
+}
+
+struct MeetingApp {
+    name: &'static str,
+    /// Processes that only run during a call, lowercase without `.exe`
+    processes: &'static [&'static str],
+    /// Window titles of a call: every part of one entry must appear, ignoring case
+    titles: &'static [&'static [&'static str]],
+}
+
+const MEETING_APPS: [MeetingApp; 4] = [
+    MeetingApp {
+        name: "Zoom",
+        processes: &["cpthost"],
+        titles: &[&["zoom meeting"], &["zoom webinar"]],
+    },
+    MeetingApp {
+        name: "Microsoft Teams",
+        processes: &[],
+        titles: &[&["microsoft teams", "meeting"], &["microsoft teams", "call with"]],
+    },
+    MeetingApp {
+        name: "Google Meet",
+        processes: &[],
+        titles: &[&["meet - "], &["google meet"]],
+    },
+    MeetingApp {
+        name: "Webex",
+        processes: &["atmgr", "webexmta"],
+        titles: &[&["webex", "meeting"]],
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DetectedMeeting {
+    pub app: String,
+    /// What gave the call away: `process` or `window`
+    pub signal: String,
+    pub auto_start: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct MeetingEnded {
+    app: String,
+    duration_secs: u64,
+}
+
+#[derive(Default)]
+struct Tracker {
+    candidate: Option<(&'static str, u32)>,
+    active: Option<(&'static str, Instant)>,
+    missed: u32,
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static CURRENT: Lazy<Mutex<Option<DetectedMeeting>>> = Lazy::new(|| Mutex::new(None));
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> MeetingDetectionConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(MEETING_DETECTION_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn process_key(name: &str) -> String {
+    let name = name.to_lowercase();
+    name.strip_suffix(".exe").map(str::to_string).unwrap_or(name)
+}
+
+fn window_titles() -> Vec<String> {
+    match xcap::Window::all() {
+        Ok(windows) => windows
+            .iter()
+            .filter(|window| !window.is_minimized())
+            .map(|window| window.title().to_lowercase())
+            .collect(),
+        Err(e) => {
+            log_debug!("Window titles unavailable: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+// The first meeting app with a call in progress, and the signal that showed it
+fn find_meeting(system: &mut System) -> Option<(&'static str, &'static str)> {
+    system.refresh_processes();
+    let processes: Vec<String> = system.processes().values().map(|process| process_key(process.name())).collect();
+    let titles = window_titles();
+    MEETING_APPS.iter().find_map(|meeting_app| {
+        if meeting_app.processes.iter().any(|wanted| processes.iter().any(|name| name == wanted)) {
+            return Some((meeting_app.name, "process"));
+        }
+        let title_matches = |title: &String| {
+            meeting_app.titles.iter().any(|parts| parts.iter().all(|part| title.contains(part)))
+        };
+        titles.iter().any(title_matches).then_some((meeting_app.name, "window"))
+    })
+}
+
+fn emit<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    if let Err(e) = app.emit(event, payload) {
+        log_error!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+async fn auto_start<R: Runtime>(app: &AppHandle<R>, meeting_app: &str) {
+    log_info!("Starting recording for {} call", meeting_app);
+    if let Err(e) = crate::start_recording_with_defaults(app).await {
+        log_error!("Failed to start recording for detected meeting: {}", e);
+        emit(app, "meeting-auto-start-failed", e);
+    }
+}
+
+async fn check<R: Runtime>(app: &AppHandle<R>, system: &mut System, tracker: &mut Tracker) {
+    let found = find_meeting(system);
+    match (tracker.active, found) {
+        (Some((meeting_app, _)), Some((found_app, _))) if found_app == meeting_app => tracker.missed = 0,
+        (Some((meeting_app, started)), _) => {
+            tracker.missed += 1;
+            if tracker.missed >= END_CHECKS {
+                log_info!("{} call ended", meeting_app);
+                tracker.active = None;
+                tracker.missed = 0;
+                if let Ok(mut current) = CURRENT.lock() {
+                    *current = None;
+                }
+                emit(app, "meeting-ended", MeetingEnded {
+                    app: meeting_app.to_string(),
+                    duration_secs: started.elapsed().as_secs(),
+                });
+            }
+        }
+        (None, Some((found_app, signal))) => {
+            let seen = match tracker.candidate {
+                Some((candidate, seen)) if candidate == found_app => seen + 1,
+                _ => 1,
+            };
+            tracker.candidate = Some((found_app, seen));
+            if seen < CONFIRM_CHECKS {
+                return;
+            }
+            tracker.candidate = None;
+            tracker.active = Some((found_app, Instant::now()));
+            let config = load_config(app);
+            let detected = DetectedMeeting {
+                app: found_app.to_string(),
+                signal: signal.to_string(),
+                auto_start: config.auto_start && !is_recording(),
+            };
+            log_info!("{} call detected from its {}", found_app, signal);
+            if let Ok(mut current) = CURRENT.lock() {
+                *current = Some(detected.clone());
+            }
+            emit(app, "meeting-detected", detected.clone());
+            if detected.auto_start {
+                auto_start(app, found_app).await;
+            }
+        }
+        (None, None) => tracker.candidate = None,
+    }
+}
+
+/// Start watching for meetings if the user opted in. Does nothing if already watching.
+pub fn start_if_enabled<R: Runtime>(app: &AppHandle<R>) {
+    if !load_config(app).enabled || RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        log_info!("Watching for meeting apps");
+        let mut system = System::new();
+        let mut tracker = Tracker::default();
+        while RUNNING.load(Ordering::SeqCst) {
+            check(&app, &mut system, &mut tracker).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        if let Ok(mut current) = CURRENT.lock() {
+            *current = None;
+        }
+        log_info!("Stopped watching for meeting apps");
+    });
+}
+
+#[tauri::command]
+pub async fn get_meeting_detection_config<R: Runtime>(app: AppHandle<R>) -> Result<MeetingDetectionConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the detection settings and start or stop watching to match.
+#[tauri::command]
+pub async fn set_meeting_detection_config<R: Runtime>(
+    app: AppHandle<R>,
+    config: MeetingDetectionConfig,
+) -> Result<(), String> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(MEETING_DETECTION_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!(
+        "Meeting detection {}{}",
+        if config.enabled { "enabled" } else { "disabled" },
+        if config.enabled && config.auto_start { " with auto-start" } else { "" }
+    );
+    if config.enabled {
+        start_if_enabled(&app);
+    } else {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+    Ok(())
+}
+
+/// The call currently in progress, if one has been detected.
+#[tauri::command]
+pub fn get_detected_meeting() -> Option<DetectedMeeting> {
+    CURRENT.lock().ok().and_then(|current| current.clone())
+}
@@ -0,0 +1,38 @@
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde_json::{Map, Value};
+
+// How the current (or last) recording was produced: engine, models, pipeline settings.
+// Attached to the meeting when its transcript is saved.
+static RECORDING_METADATA: Lazy<Mutex<Map<String, Value>>> = Lazy::new(|| Mutex::new(Map::new()));
+
+/// Forget the previous recording's metadata. Called when a new recording starts.
+pub fn reset() {
+    if let Ok(mut metadata) = RECORDING_METADATA.lock() {
+        metadata.clear();
+    }
+}
+
+pub fn set(key: &str, value: Value) {
+    if let Ok(mut metadata) = RECORDING_METADATA.lock() {
+        metadata.insert(key.to_string(), value);
+    }
+}
+
+/// Update one field of an object-valued entry, creating the entry if needed.
+pub fn set_field(key: &str, field: &str, value: Value) {
+    if let Ok(mut metadata) = RECORDING_METADATA.lock() {
+        let entry = metadata.entry(key.to_string()).or_insert_with(|| Value::Object(Map::new()));
+        if let Some(object) = entry.as_object_mut() {
+            object.insert(field.to_string(), value);
+        }
+    }
+}
+
+pub fn snapshot() -> Option<Value> {
+    let metadata = RECORDING_METADATA.lock().ok()?;
+    if metadata.is_empty() {
+        return None;
+    }
+    Some(Value::Object(metadata.clone()))
+}
@@ -0,0 +1,169 @@
+// Elapsed and remaining time of the meeting being recorded. While recording, a
+// `meeting-time-status` event goes out every few seconds with the time so far and how long
+// is left before the meeting is due to end. The end comes from the linked calendar event
+// when there is one; otherwise it is estimated by assuming the meeting was booked in
+// half-hour slots. A `meeting-wrap-up` event fires once when a scheduled end is close.
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error};
+
+const STATUS_INTERVAL: Duration = Duration::from_secs(5);
+// Meetings without a calendar event are assumed to be booked in slots of this length
+const ESTIMATE_SLOT_SECS: i64 = 30 * 60;
+// How long before the scheduled end the wrap-up reminder fires
+const WRAP_UP_LEAD_SECS: i64 = 5 * 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndSource {
+    Calendar,
+    Estimated,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingTimeStatus {
+    pub elapsed_secs: u64,
+    pub expected_end: String,
+    pub end_source: EndSource,
+    /// Zero once the expected end has passed
+    pub remaining_secs: u64,
+    /// How far past the expected end the meeting has run; zero until then
+    pub overrun_secs: u64,
+    pub overrun: bool,
+}
+
+struct Timer {
+    started: Instant,
+    started_at: DateTime<Utc>,
+    wrap_up_sent: bool,
+    task: Option<tauri::async_runtime::JoinHandle<()>>,
+}
+
+static TIMER: Lazy<Mutex<Option<Timer>>> = Lazy::new(|| Mutex::new(None));
+// Kept across recordings until cleared, so an event can be linked before recording starts
+static SCHEDULED_END: Lazy<Mutex<Option<DateTime<Utc>>>> = Lazy::new(|| Mutex::new(None));
+
+fn status_of(timer: &Timer, scheduled_end: Option<DateTime<Utc>>) -> MeetingTimeStatus {
+    let elapsed_secs = timer.started.elapsed().as_secs();
+    let (expected_end, end_source) = match scheduled_end {
+        Some(end) => (end, EndSource::Calendar),
+        None => {
+            let slots = (elapsed_secs as i64 / ESTIMATE_SLOT_SECS + 1).max(1);
+            (timer.started_at + chrono::Duration::seconds(slots * ESTIMATE_SLOT_SECS), EndSource::Estimated)
+        }
+    };
+    let left = (expected_end - Utc::now()).num_seconds();
+    MeetingTimeStatus {
+        elapsed_secs,
+        expected_end: expected_end.to_rfc3339(),
+        end_source,
+        remaining_secs: left.max(0) as u64,
+        overrun_secs: (-left).max(0) as u64,
+        overrun: left < 0,
+    }
+}
+
+fn current_status() -> Option<MeetingTimeStatus> {
+    let scheduled_end = SCHEDULED_END.lock().ok().and_then(|end| *end);
+    let timer = TIMER.lock().ok()?;
+    timer.as_ref().map(|timer| status_of(timer, scheduled_end))
+}
+
+fn emit_status<R: Runtime>(app: &AppHandle<R>) {
+    let Some(status) = current_status() else { return };
+    if let Err(e) = app.emit("meeting-time-status", &status) {
+        log_error!("Failed to emit meeting-time-status event: {}", e);
+    }
+
+    let wrap_up_due = status.end_source == EndSource::Calendar && status.remaining_secs as i64 <= WRAP_UP_LEAD_SECS;
+    let first = wrap_up_due
+        && TIMER
+            .lock()
+            .ok()
+            .and_then(|mut timer| timer.as_mut().map(|timer| !std::mem::replace(&mut timer.wrap_up_sent, true)))
+            .unwrap_or(false);
+    if first {
+        log_info!("Meeting is due to end in {} s, sending wrap-up reminder", status.remaining_secs);
+        if let Err(e) = app.emit("meeting-wrap-up", &status) {
+            log_error!("Failed to emit meeting-wrap-up event: {}", e);
+        }
+    }
+}
+
+/// Start timing a new recording, which began capturing at `started`, and reporting its
+/// status until `finish_recording`.
+pub fn begin_recording<R: Runtime>(app: &AppHandle<R>, started: Instant) {
+    finish_timer();
+    let ticker = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        loop {
+            emit_status(&ticker);
+            tokio::time::sleep(STATUS_INTERVAL).await;
+        }
+    });
+    if let Ok(mut timer) = TIMER.lock() {
+        let started_at = Utc::now() - chrono::Duration::from_std(started.elapsed()).unwrap_or_default();
+        *timer = Some(Timer { started, started_at, wrap_up_sent: false, task: Some(task) });
+    }
+}
+
+/// Time since the current recording started, on the same clock as transcript timestamps.
+pub fn elapsed() -> Option<Duration> {
+    TIMER.lock().ok()?.as_ref().map(|timer| timer.started.elapsed())
+}
+
+fn finish_timer() {
+    let task = TIMER.lock().ok().and_then(|mut timer| timer.take()).and_then(|timer| timer.task);
+    if let Some(task) = task {
+        task.abort();
+    }
+}
+
+/// Stop reporting and forget the linked end time, which belonged to this meeting.
+pub fn finish_recording() {
+    finish_timer();
+    if let Ok(mut end) = SCHEDULED_END.lock() {
+        *end = None;
+    }
+}
+
+/// Link the end time of the calendar event being recorded, or clear it to fall back to the
+/// estimate.
+pub fn set_scheduled_end<R: Runtime>(app: &AppHandle<R>, end: Option<DateTime<Utc>>) {
+    if let Ok(mut scheduled) = SCHEDULED_END.lock() {
+        *scheduled = end;
+    }
+    // A later end time makes a new reminder due
+    if let Ok(mut timer) = TIMER.lock() {
+        if let Some(timer) = timer.as_mut() {
+            timer.wrap_up_sent = false;
+        }
+    }
+    log_info!("Meeting end time set to {:?}", end);
+    emit_status(app);
+}
+
+/// Set the end time of the calendar event the recording belongs to (RFC 3339), or clear
+/// it to fall back to the estimate. Takes effect at the next status update.
+#[tauri::command]
+pub async fn set_meeting_end_time<R: Runtime>(app: AppHandle<R>, end_time: Option<String>) -> Result<(), String> {
+    let end = end_time
+        .map(|end| {
+            DateTime::parse_from_rfc3339(&end)
+                .map(|end| end.with_timezone(&Utc))
+                .map_err(|e| format!("Invalid meeting end time: {}", e))
+        })
+        .transpose()?;
+    set_scheduled_end(&app, end);
+    Ok(())
+}
+
+/// The current timing of the recording; None when nothing is being recorded.
+#[tauri::command]
+pub fn get_meeting_time_status() -> Option<MeetingTimeStatus> {
+    current_status()
+}
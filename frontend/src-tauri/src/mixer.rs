@@ -0,0 +1,74 @@
+// Live control over how the two capture streams are mixed. The mix levels are part of the
+// pipeline configuration and switch at the next chunk; muting is immediate. A muted
+// stream is replaced by silence before it reaches the mix, the recording or the
+// transcriber, and every recording starts with both streams unmuted.
+use std::sync::atomic::{AtomicBool, Ordering};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error};
+
+use crate::{pipeline_config, CaptureSource};
+
+static MIC_MUTED: AtomicBool = AtomicBool::new(false);
+static SYSTEM_MUTED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MixStatus {
+    pub mic_level: f32,
+    pub system_level: f32,
+    pub mic_muted: bool,
+    pub system_muted: bool,
+}
+
+fn flag(source: CaptureSource) -> &'static AtomicBool {
+    match source {
+        CaptureSource::Mic => &MIC_MUTED,
+        CaptureSource::System => &SYSTEM_MUTED,
+    }
+}
+
+pub(crate) fn is_muted(source: CaptureSource) -> bool {
+    flag(source).load(Ordering::SeqCst)
+}
+
+pub fn begin_recording() {
+    MIC_MUTED.store(false, Ordering::SeqCst);
+    SYSTEM_MUTED.store(false, Ordering::SeqCst);
+}
+
+pub fn status() -> MixStatus {
+    let config = pipeline_config::current();
+    MixStatus {
+        mic_level: config.mic_level,
+        system_level: config.system_level,
+        mic_muted: MIC_MUTED.load(Ordering::SeqCst),
+        system_muted: SYSTEM_MUTED.load(Ordering::SeqCst),
+    }
+}
+
+fn emit_status<R: Runtime>(app: &AppHandle<R>) -> MixStatus {
+    let status = status();
+    if let Err(e) = app.emit("mix-changed", &status) {
+        log_error!("Failed to emit mix-changed event: {}", e);
+    }
+    status
+}
+
+/// Set the mic and system levels applied on top of loudness matching (0 to 4, 1 leaves a
+/// stream as matched). A running recording uses them from its next chunk.
+#[tauri::command]
+pub async fn set_mix_ratio<R: Runtime>(app: AppHandle<R>, mic: f32, system: f32) -> Result<MixStatus, String> {
+    pipeline_config::set_mix_levels(&app, mic, system)?;
+    log_info!("Mix levels set to mic {:.2}, system {:.2}", mic, system);
+    Ok(emit_status(&app))
+}
+
+/// Mute or unmute one stream ("mic" or "system"); `muted` defaults to true.
+#[tauri::command]
+pub fn mute_source<R: Runtime>(app: AppHandle<R>, source: String, muted: Option<bool>) -> Result<MixStatus, String> {
+    let source = CaptureSource::from_key(&source).ok_or_else(|| format!("Unknown audio source: {}", source))?;
+    let muted = muted.unwrap_or(true);
+    flag(source).store(muted, Ordering::SeqCst);
+    log_info!("{} audio {}", source.key(), if muted { "muted" } else { "unmuted" });
+    Ok(emit_status(&app))
+}
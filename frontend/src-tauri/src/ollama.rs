@@ -1,7 +1,80 @@
-use std::process::Command;
+// Client for the local Ollama server: listing, installing and inspecting models, and the
+// address every other Ollama call (summaries, embeddings, the model settings) goes to. All
+// requests are async with timeouts, so a stalled server never holds up a command.
+//
+// The server address is saved under `ollamaBaseUrl`, for Ollama running on another port
+// or machine; it defaults to the standard local address.
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use futures_util::StreamExt;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use tauri::command;
-use reqwest::blocking::Client;
+use tauri::{command, AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use tokio::process::Command;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::error::AppError;
+use crate::telemetry::{self, Dependency};
+
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+const OLLAMA_URL_STORE_KEY: &str = "ollamaBaseUrl";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+// For quick metadata calls; generation and downloads set no overall limit
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const CLI_TIMEOUT: Duration = Duration::from_secs(10);
+
+static BASE_URL: Lazy<RwLock<String>> = Lazy::new(|| RwLock::new(DEFAULT_OLLAMA_URL.to_string()));
+static CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+/// The Ollama server address, without a trailing slash.
+pub fn base_url() -> String {
+    BASE_URL
+        .read()
+        .map(|url| url.clone())
+        .unwrap_or_else(|_| DEFAULT_OLLAMA_URL.to_string())
+}
+
+/// A shared client for Ollama requests. It only limits connecting; callers add a request
+/// timeout where a response should come back quickly.
+pub fn client() -> &'static reqwest::Client {
+    &CLIENT
+}
+
+/// Load the saved server address. Called once at startup.
+pub fn load_base_url<R: Runtime>(app: &AppHandle<R>) {
+    let saved = app
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get(OLLAMA_URL_STORE_KEY))
+        .and_then(|value| value.as_str().map(str::to_string));
+    if let Some(url) = saved {
+        match normalize_url(&url) {
+            Ok(url) => set_base_url(url),
+            Err(e) => log_warn!("Ignoring saved Ollama address: {}", e),
+        }
+    }
+}
+
+fn set_base_url(url: String) {
+    if let Ok(mut base_url) = BASE_URL.write() {
+        *base_url = url;
+    }
+}
+
+fn normalize_url(url: &str) -> Result<String, AppError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::InvalidInput(format!("Invalid Ollama address '{}': {}", url, e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(AppError::InvalidInput(format!("Ollama address must be an http(s) URL: '{}'", url)));
+    }
+    Ok(parsed.as_str().trim_end_matches('/').to_string())
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OllamaModel {
@@ -24,25 +97,47 @@ struct OllamaApiModel {
     size: i64,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaVersion {
+    version: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OllamaHealth {
+    pub base_url: String,
+    pub reachable: bool,
+    pub version: Option<String>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+}
+
 #[command]
-pub fn get_ollama_models() -> Result<Vec<OllamaModel>, String> {
+pub async fn get_ollama_models() -> Result<Vec<OllamaModel>, AppError> {
     // First try the HTTP API
-    match get_models_via_http() {
+    match get_models_via_http().await {
         Ok(models) => Ok(models),
         Err(http_err) => {
             // Fallback to CLI if HTTP fails
-            get_models_via_cli().map_err(|cli_err| {
-                format!("HTTP API error: {}\nCLI error: {}", http_err, cli_err)
+            get_models_via_cli().await.map_err(|cli_err| {
+                AppError::Offline(format!("HTTP API error: {}\nCLI error: {}", http_err, cli_err))
             })
         }
     }
 }
 
-fn get_models_via_http() -> Result<Vec<OllamaModel>, String> {
-    let client = Client::new();
-    let response = client
-        .get("http://localhost:11434/api/tags")
+async fn get_models_via_http() -> Result<Vec<OllamaModel>, String> {
+    let started = Instant::now();
+    let result = fetch_models_via_http().await;
+    telemetry::record(Dependency::Ollama, started.elapsed(), result.as_ref().err().map(|e| e.as_str()));
+    result
+}
+
+async fn fetch_models_via_http() -> Result<Vec<OllamaModel>, String> {
+    let response = client()
+        .get(format!("{}/api/tags", base_url()))
+        .timeout(REQUEST_TIMEOUT)
         .send()
+        .await
         .map_err(|e| format!("Failed to make HTTP request: {}", e))?;
 
     if !response.status().is_success() {
@@ -51,6 +146,7 @@ fn get_models_via_http() -> Result<Vec<OllamaModel>, String> {
 
     let api_response: OllamaApiResponse = response
         .json()
+        .await
         .map_err(|e| format!("Failed to parse JSON response: {}", e))?;
 
     Ok(api_response.models.into_iter().map(|m| OllamaModel {
@@ -61,10 +157,10 @@ fn get_models_via_http() -> Result<Vec<OllamaModel>, String> {
     }).collect())
 }
 
-fn get_models_via_cli() -> Result<Vec<OllamaModel>, String> {
-    let output = Command::new("ollama")
-        .arg("list")
-        .output()
+async fn get_models_via_cli() -> Result<Vec<OllamaModel>, String> {
+    let output = tokio::time::timeout(CLI_TIMEOUT, Command::new("ollama").arg("list").kill_on_drop(true).output())
+        .await
+        .map_err(|_| "ollama list timed out".to_string())?
         .map_err(|e| format!("Failed to execute ollama command: {}", e))?;
 
     if !output.status.success() {
@@ -101,3 +197,237 @@ fn format_size(size: i64) -> String {
         format!("{:.1} GB", size as f64 / (1024.0 * 1024.0 * 1024.0))
     }
 }
+
+/// Whether the Ollama server answers, and which version it runs.
+#[command]
+pub async fn check_ollama_health() -> OllamaHealth {
+    let base_url = base_url();
+    let started = Instant::now();
+    let result = async {
+        let response = client()
+            .get(format!("{}/api/version", base_url))
+            .timeout(REQUEST_TIMEOUT)
+            .send()
+            .await
+            .map_err(request_error)?;
+        check_status(response)
+            .await?
+            .json::<OllamaVersion>()
+            .await
+            .map_err(|e| AppError::Internal(format!("Failed to parse Ollama version: {}", e)))
+    }
+    .await;
+    let latency = started.elapsed();
+    telemetry::record(Dependency::Ollama, latency, result.as_ref().err().map(AppError::message));
+    let (version, error) = match result {
+        Ok(version) => (Some(version.version), None),
+        Err(e) => (None, Some(e.to_string())),
+    };
+    OllamaHealth { base_url, reachable: error.is_none(), version, latency_ms: latency.as_millis() as u64, error }
+}
+
+#[command]
+pub fn get_ollama_base_url() -> String {
+    base_url()
+}
+
+/// Point the app at another Ollama server, e.g. `http://192.168.1.20:11434`. Pass nothing
+/// to go back to the local default.
+#[command]
+pub async fn set_ollama_base_url<R: Runtime>(app: AppHandle<R>, url: Option<String>) -> Result<String, AppError> {
+    let url = match url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        Some(url) => Some(normalize_url(url)?),
+        None => None,
+    };
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    match &url {
+        Some(url) => store.set(OLLAMA_URL_STORE_KEY, serde_json::json!(url)),
+        None => {
+            store.delete(OLLAMA_URL_STORE_KEY);
+        }
+    }
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    let url = url.unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string());
+    log_info!("Ollama address set to {}", url);
+    set_base_url(url.clone());
+    Ok(url)
+}
+
+#[derive(Debug, Serialize)]
+struct ModelNameRequest<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullStatus {
+    #[serde(default)]
+    status: String,
+    #[serde(default)]
+    total: Option<u64>,
+    #[serde(default)]
+    completed: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Progress of a model download, sent as `ollama-pull-progress` events.
+#[derive(Debug, Clone, Serialize)]
+pub struct PullProgress {
+    pub model: String,
+    /// Ollama's description of the current step, e.g. "pulling manifest" or "verifying sha256 digest"
+    pub status: String,
+    pub completed: Option<u64>,
+    pub total: Option<u64>,
+    /// 0-100 while a layer downloads, `None` for steps without a size
+    pub percent: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OllamaModelDetails {
+    pub format: String,
+    pub family: String,
+    pub parameter_size: String,
+    pub quantization_level: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaModelInfo {
+    pub name: String,
+    #[serde(default)]
+    pub details: OllamaModelDetails,
+    #[serde(default)]
+    pub parameters: String,
+    #[serde(default)]
+    pub template: String,
+    #[serde(default)]
+    pub license: String,
+    /// Architecture facts such as context length and embedding size, as Ollama reports them
+    #[serde(default)]
+    pub model_info: serde_json::Value,
+}
+
+fn valid_model_name(name: &str) -> Result<&str, AppError> {
+    let name = name.trim();
+    if name.is_empty() || name.chars().any(char::is_whitespace) {
+        return Err(AppError::InvalidInput(format!("Invalid model name: '{}'", name)));
+    }
+    Ok(name)
+}
+
+// Unreachable and slow servers are told apart, so the UI can suggest starting Ollama
+fn request_error(error: reqwest::Error) -> AppError {
+    let message = format!("Failed to reach Ollama: {}", error);
+    if error.is_timeout() {
+        AppError::Timeout(message)
+    } else {
+        AppError::Offline(message)
+    }
+}
+
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, AppError> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    Err(AppError::from_status(status, &body))
+}
+
+fn emit_pull_progress<R: Runtime>(app: &AppHandle<R>, model: &str, status: &PullStatus) {
+    let percent = match (status.completed, status.total) {
+        (Some(completed), Some(total)) if total > 0 => Some(completed as f64 * 100.0 / total as f64),
+        _ => None,
+    };
+    let progress = PullProgress {
+        model: model.to_string(),
+        status: status.status.clone(),
+        completed: status.completed,
+        total: status.total,
+        percent,
+    };
+    if let Err(e) = app.emit("ollama-pull-progress", progress) {
+        log_error!("Failed to emit ollama-pull-progress event: {}", e);
+    }
+}
+
+/// Download a model from the Ollama library, reporting progress as `ollama-pull-progress`
+/// events. Resolves once the model is ready to use.
+#[command]
+pub async fn pull_ollama_model<R: Runtime>(app: AppHandle<R>, name: String) -> Result<(), AppError> {
+    let name = valid_model_name(&name)?;
+    log_info!("Pulling Ollama model {}", name);
+    let response = client()
+        .post(format!("{}/api/pull", base_url()))
+        .json(&ModelNameRequest { name, stream: Some(true) })
+        .send()
+        .await
+        .map_err(request_error)?;
+    let response = check_status(response).await?;
+
+    let mut pending = Vec::new();
+    let mut stream = response.bytes_stream();
+    let mut last_status = String::new();
+    while let Some(bytes) = stream.next().await {
+        let bytes = bytes.map_err(request_error)?;
+        pending.extend_from_slice(&bytes);
+        while let Some(newline) = pending.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            if line.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+            let status: PullStatus =
+                serde_json::from_slice(&line).map_err(|e| format!("Failed to parse pull progress: {}", e))?;
+            if let Some(error) = status.error {
+                return Err(format!("Failed to pull {}: {}", name, error).into());
+            }
+            emit_pull_progress(&app, name, &status);
+            last_status = status.status;
+        }
+    }
+    if last_status != "success" {
+        return Err(AppError::Offline(format!("Download of {} stopped before it finished", name)));
+    }
+    log_info!("Pulled Ollama model {}", name);
+    Ok(())
+}
+
+/// Remove a downloaded model to free its disk space.
+#[command]
+pub async fn delete_ollama_model(name: String) -> Result<(), AppError> {
+    let name = valid_model_name(&name)?;
+    let response = client()
+        .delete(format!("{}/api/delete", base_url()))
+        .timeout(REQUEST_TIMEOUT)
+        .json(&ModelNameRequest { name, stream: None })
+        .send()
+        .await
+        .map_err(request_error)?;
+    check_status(response).await?;
+    log_info!("Deleted Ollama model {}", name);
+    Ok(())
+}
+
+/// Details of a downloaded model: family, size, quantization, parameters and template.
+#[command]
+pub async fn show_ollama_model(name: String) -> Result<OllamaModelInfo, AppError> {
+    let name = valid_model_name(&name)?;
+    let response = client()
+        .post(format!("{}/api/show", base_url()))
+        .timeout(REQUEST_TIMEOUT)
+        .json(&ModelNameRequest { name, stream: None })
+        .send()
+        .await
+        .map_err(request_error)?;
+    let mut info: serde_json::Value = check_status(response)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse model details: {}", e))?;
+    if let Some(info) = info.as_object_mut() {
+        info.insert("name".to_string(), serde_json::json!(name));
+    }
+    serde_json::from_value(info).map_err(|e| AppError::Internal(format!("Failed to parse model details: {}", e)))
+}
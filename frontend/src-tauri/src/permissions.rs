@@ -0,0 +1,383 @@
+// Audio permission report. On Linux most capture failures come from sandboxing rather than
+// from the devices: a Flatpak without the PulseAudio socket or a Snap whose audio
+// interfaces aren't connected sees no devices at all. The report names the packaging, the
+// sound server and each permission capture depends on, with the command that fixes it.
+//
+// On macOS capture needs the microphone permission and, for system audio through
+// ScreenCaptureKit, the screen recording permission. Without the latter the system stream
+// still opens but only ever delivers silence, so it has to be checked up front.
+use serde::Serialize;
+use log::{info as log_info, warn as log_warn};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Packaging {
+    Native,
+    Flatpak,
+    Snap,
+    AppImage,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AudioServer {
+    PipeWire,
+    PulseAudio,
+    /// No sound server socket found; capture goes straight to ALSA
+    Alsa,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckState {
+    Granted,
+    Missing,
+    /// The user hasn't been asked yet; capturing will show the prompt
+    NotDetermined,
+    /// Blocked by a device management policy the user can't change
+    Restricted,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionCheck {
+    pub name: String,
+    pub state: CheckState,
+    pub detail: String,
+    /// What to run or change when the check is not granted
+    pub remediation: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PermissionStatus {
+    pub os: String,
+    pub packaging: Packaging,
+    pub audio_server: Option<AudioServer>,
+    pub checks: Vec<PermissionCheck>,
+}
+
+impl PermissionStatus {
+    pub fn all_granted(&self) -> bool {
+        self.checks.iter().all(|check| check.state == CheckState::Granted)
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
+
+    use super::{AudioServer, CheckState, Packaging, PermissionCheck};
+
+    const FLATPAK_INFO: &str = "/.flatpak-info";
+    // Snap interfaces capture needs; audio-record is the microphone
+    const SNAP_INTERFACES: [&str; 2] = ["audio-record", "audio-playback"];
+
+    pub fn packaging() -> Packaging {
+        if Path::new(FLATPAK_INFO).exists() || std::env::var_os("FLATPAK_ID").is_some() {
+            Packaging::Flatpak
+        } else if std::env::var_os("SNAP").is_some() {
+            Packaging::Snap
+        } else if std::env::var_os("APPIMAGE").is_some() {
+            Packaging::AppImage
+        } else {
+            Packaging::Native
+        }
+    }
+
+    fn runtime_dir() -> Option<PathBuf> {
+        std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from)
+    }
+
+    pub fn audio_server() -> AudioServer {
+        let runtime = runtime_dir();
+        let exists = |name: &str| runtime.as_ref().is_some_and(|dir| dir.join(name).exists());
+        if exists("pipewire-0") {
+            AudioServer::PipeWire
+        } else if exists("pulse/native") || std::env::var_os("PULSE_SERVER").is_some() {
+            AudioServer::PulseAudio
+        } else {
+            AudioServer::Alsa
+        }
+    }
+
+    fn check(name: &str, state: CheckState, detail: String, remediation: Option<String>) -> PermissionCheck {
+        PermissionCheck { name: name.to_string(), state, detail, remediation }
+    }
+
+    // Value of `key` in `[section]` of the sandbox's /.flatpak-info, split on ';'
+    fn flatpak_info_list(info: &str, section: &str, key: &str) -> Vec<String> {
+        let mut in_section = false;
+        for line in info.lines().map(str::trim) {
+            if line.starts_with('[') {
+                in_section = line == format!("[{}]", section);
+            } else if in_section {
+                if let Some(value) = line.strip_prefix(key).and_then(|rest| rest.trim_start().strip_prefix('=')) {
+                    return value.split(';').map(str::trim).filter(|v| !v.is_empty()).map(str::to_string).collect();
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    fn flatpak_checks() -> Vec<PermissionCheck> {
+        let info = std::fs::read_to_string(FLATPAK_INFO).unwrap_or_default();
+        let app_id = flatpak_info_list(&info, "Application", "name")
+            .into_iter()
+            .next()
+            .or_else(|| std::env::var("FLATPAK_ID").ok())
+            .unwrap_or_else(|| "<app-id>".to_string());
+        let sockets = flatpak_info_list(&info, "Context", "sockets");
+        let devices = flatpak_info_list(&info, "Context", "devices");
+        let known = !info.is_empty();
+
+        let pulse = if !known {
+            CheckState::Unknown
+        } else if sockets.iter().any(|s| s == "pulseaudio") {
+            CheckState::Granted
+        } else {
+            CheckState::Missing
+        };
+        let direct_devices = if !known {
+            CheckState::Unknown
+        } else if devices.iter().any(|d| d == "all") {
+            CheckState::Granted
+        } else {
+            CheckState::Missing
+        };
+        vec![
+            check(
+                "flatpak_pulseaudio_socket",
+                pulse,
+                format!("Sandbox sockets: {}", if sockets.is_empty() { "none".to_string() } else { sockets.join(", ") }),
+                (pulse != CheckState::Granted)
+                    .then(|| format!("flatpak override --user --socket=pulseaudio {}", app_id)),
+            ),
+            check(
+                "flatpak_device_access",
+                direct_devices,
+                "Direct ALSA device access, only needed without a sound server".to_string(),
+                (direct_devices != CheckState::Granted)
+                    .then(|| format!("flatpak override --user --device=all {}", app_id)),
+            ),
+        ]
+    }
+
+    fn snap_checks() -> Vec<PermissionCheck> {
+        let snap = std::env::var("SNAP_NAME").unwrap_or_else(|_| "<snap-name>".to_string());
+        SNAP_INTERFACES
+            .iter()
+            .map(|interface| {
+                // `snapctl is-connected` exits 0 when connected and 1 when not
+                let state = match Command::new("snapctl").args(["is-connected", interface]).status() {
+                    Ok(status) if status.success() => CheckState::Granted,
+                    Ok(status) if status.code() == Some(1) => CheckState::Missing,
+                    _ => CheckState::Unknown,
+                };
+                check(
+                    &format!("snap_{}", interface.replace('-', "_")),
+                    state,
+                    format!("Snap interface {}", interface),
+                    (state != CheckState::Granted).then(|| format!("sudo snap connect {}:{}", snap, interface)),
+                )
+            })
+            .collect()
+    }
+
+    fn sound_server_check(server: AudioServer) -> PermissionCheck {
+        match server {
+            AudioServer::PipeWire | AudioServer::PulseAudio => check(
+                "sound_server",
+                CheckState::Granted,
+                format!("{:?} socket is reachable", server),
+                None,
+            ),
+            AudioServer::Alsa => check(
+                "sound_server",
+                CheckState::Missing,
+                "No PipeWire or PulseAudio socket in XDG_RUNTIME_DIR; system audio cannot be captured".to_string(),
+                Some("systemctl --user enable --now pipewire pipewire-pulse".to_string()),
+            ),
+        }
+    }
+
+    pub fn checks(packaging: Packaging, server: AudioServer) -> Vec<PermissionCheck> {
+        let mut checks = vec![sound_server_check(server)];
+        match packaging {
+            Packaging::Flatpak => checks.extend(flatpak_checks()),
+            Packaging::Snap => checks.extend(snap_checks()),
+            Packaging::Native | Packaging::AppImage => {}
+        }
+        checks
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod macos {
+    use objc::runtime::{Class, Object};
+    use objc::{msg_send, sel, sel_impl};
+
+    use super::{CheckState, PermissionCheck};
+
+    pub const MICROPHONE: &str = "microphone";
+    pub const SCREEN_RECORDING: &str = "screen_recording";
+    const MICROPHONE_PANE: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone";
+    const SCREEN_RECORDING_PANE: &str = "x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture";
+
+    #[link(name = "AVFoundation", kind = "framework")]
+    extern "C" {
+        static AVMediaTypeAudio: *mut Object;
+    }
+
+    #[link(name = "CoreGraphics", kind = "framework")]
+    extern "C" {
+        fn CGPreflightScreenCaptureAccess() -> bool;
+        fn CGRequestScreenCaptureAccess() -> bool;
+    }
+
+    // AVAuthorizationStatus: 0 not determined, 1 restricted, 2 denied, 3 authorized
+    fn microphone_state() -> CheckState {
+        let Some(class) = Class::get("AVCaptureDevice") else {
+            return CheckState::Unknown;
+        };
+        let status: isize = unsafe { msg_send![class, authorizationStatusForMediaType: AVMediaTypeAudio] };
+        match status {
+            0 => CheckState::NotDetermined,
+            1 => CheckState::Restricted,
+            2 => CheckState::Missing,
+            3 => CheckState::Granted,
+            _ => CheckState::Unknown,
+        }
+    }
+
+    pub fn screen_recording_granted() -> bool {
+        unsafe { CGPreflightScreenCaptureAccess() }
+    }
+
+    // Whether the ScreenCaptureKit host offers a capture device at all
+    fn screen_capture_kit_available() -> bool {
+        use cpal::traits::HostTrait;
+
+        cpal::host_from_id(cpal::HostId::ScreenCaptureKit)
+            .ok()
+            .and_then(|host| host.default_input_device())
+            .is_some()
+    }
+
+    pub fn checks() -> Vec<PermissionCheck> {
+        let microphone = microphone_state();
+        let screen = if screen_recording_granted() { CheckState::Granted } else { CheckState::Missing };
+        vec![
+            PermissionCheck {
+                name: MICROPHONE.to_string(),
+                state: microphone,
+                detail: "Microphone access for recording your voice".to_string(),
+                remediation: (microphone != CheckState::Granted)
+                    .then(|| "System Settings > Privacy & Security > Microphone".to_string()),
+            },
+            PermissionCheck {
+                name: SCREEN_RECORDING.to_string(),
+                state: screen,
+                detail: format!(
+                    "Screen recording access, which ScreenCaptureKit needs to capture system audio ({})",
+                    if screen_capture_kit_available() { "ScreenCaptureKit available" } else { "ScreenCaptureKit unavailable" }
+                ),
+                remediation: (screen != CheckState::Granted)
+                    .then(|| "System Settings > Privacy & Security > Screen & System Audio Recording".to_string()),
+            },
+        ]
+    }
+
+    /// Show the permission prompt where macOS still allows it, otherwise open the pane.
+    pub fn request(permission: &str) -> Result<(), String> {
+        let pane = match permission {
+            MICROPHONE => {
+                if microphone_state() == CheckState::NotDetermined {
+                    return crate::audio::trigger_audio_permission()
+                        .map_err(|e| format!("Failed to request microphone access: {}", e));
+                }
+                MICROPHONE_PANE
+            }
+            SCREEN_RECORDING => {
+                // Only prompts the first time; after a refusal the pane is the only way
+                if unsafe { CGRequestScreenCaptureAccess() } {
+                    return Ok(());
+                }
+                SCREEN_RECORDING_PANE
+            }
+            other => return Err(format!("Unknown permission: {}", other)),
+        };
+        std::process::Command::new("open")
+            .arg(pane)
+            .status()
+            .map_err(|e| format!("Failed to open System Settings: {}", e))?;
+        Ok(())
+    }
+}
+
+/// Whether system audio will come through as silence because capture isn't permitted.
+pub fn system_audio_blocked() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        !macos::screen_recording_granted()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        false
+    }
+}
+
+pub fn permission_status() -> PermissionStatus {
+    #[cfg(target_os = "linux")]
+    {
+        let packaging = linux::packaging();
+        let server = linux::audio_server();
+        PermissionStatus {
+            os: std::env::consts::OS.to_string(),
+            packaging,
+            audio_server: Some(server),
+            checks: linux::checks(packaging, server),
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        PermissionStatus {
+            os: std::env::consts::OS.to_string(),
+            packaging: Packaging::Native,
+            audio_server: None,
+            #[cfg(target_os = "macos")]
+            checks: macos::checks(),
+            #[cfg(not(target_os = "macos"))]
+            checks: Vec::new(),
+        }
+    }
+}
+
+/// What audio capture is allowed to do in this environment, with fixes for what it isn't.
+#[tauri::command]
+pub fn get_permission_status() -> PermissionStatus {
+    let status = permission_status();
+    log_info!(
+        "Permission status: {:?} packaging, {:?} sound server, all granted: {}",
+        status.packaging, status.audio_server, status.all_granted()
+    );
+    status
+}
+
+/// Probe the microphone and system audio permissions. With `open_settings` set to
+/// `microphone` or `screen_recording`, that permission is also requested: the system prompt
+/// is shown if it hasn't been yet, otherwise the matching System Settings pane is opened.
+#[tauri::command]
+pub fn check_audio_permissions(open_settings: Option<String>) -> Result<PermissionStatus, String> {
+    if let Some(permission) = open_settings.as_deref() {
+        #[cfg(target_os = "macos")]
+        macos::request(permission)?;
+        #[cfg(not(target_os = "macos"))]
+        return Err(format!("Cannot open settings for {} on {}", permission, std::env::consts::OS));
+    }
+    let status = permission_status();
+    for check in status.checks.iter().filter(|check| check.state != CheckState::Granted) {
+        log_warn!("Audio permission {} is {:?}: {}", check.name, check.state, check.detail);
+    }
+    Ok(status)
+}
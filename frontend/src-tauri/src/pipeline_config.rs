@@ -0,0 +1,311 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, warn as log_warn};
+
+use crate::api_version::Versioned;
+use crate::meeting_metadata;
+use crate::{CHUNK_DURATION_MS, CHUNK_OVERLAP_MS, MIN_CHUNK_DURATION_MS};
+
+const PIPELINE_CONFIG_KEY: &str = "pipelineConfig";
+const MAX_LEVEL: f32 = 4.0;
+const MAX_CHUNK_DURATION_MS: u32 = 60000;
+const MAX_CHUNK_OVERLAP_MS: u32 = 5000;
+const MIN_HIGH_PASS_HZ: f32 = 20.0;
+const MAX_HIGH_PASS_HZ: f32 = 400.0;
+const DEFAULT_HIGH_PASS_HZ: f32 = 80.0;
+const MIN_NOISE_GATE_DB: f32 = -90.0;
+const DEFAULT_NOISE_GATE_DB: f32 = -50.0;
+const MIN_AGC_TARGET_DB: f32 = -40.0;
+const MAX_AGC_TARGET_DB: f32 = -6.0;
+const DEFAULT_AGC_TARGET_DB: f32 = -20.0;
+
+/// Clean-up applied to one microphone before its audio is transcribed and recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceFilters {
+    /// Remove rumble below the cutoff, e.g. HVAC or desk knocks
+    pub high_pass_enabled: bool,
+    pub high_pass_hz: f32,
+    /// Mute the mic while its level stays under the threshold
+    pub noise_gate_enabled: bool,
+    pub noise_gate_threshold_db: f32,
+}
+
+impl Default for DeviceFilters {
+    fn default() -> Self {
+        Self {
+            high_pass_enabled: false,
+            high_pass_hz: DEFAULT_HIGH_PASS_HZ,
+            noise_gate_enabled: false,
+            noise_gate_threshold_db: DEFAULT_NOISE_GATE_DB,
+        }
+    }
+}
+
+impl DeviceFilters {
+    fn validate(&self) -> Result<(), String> {
+        if !(MIN_HIGH_PASS_HZ..=MAX_HIGH_PASS_HZ).contains(&self.high_pass_hz) {
+            return Err(format!(
+                "High-pass cutoff must be between {} and {} Hz",
+                MIN_HIGH_PASS_HZ, MAX_HIGH_PASS_HZ
+            ));
+        }
+        if !(MIN_NOISE_GATE_DB..=0.0).contains(&self.noise_gate_threshold_db) {
+            return Err(format!("Noise gate threshold must be between {} and 0 dB", MIN_NOISE_GATE_DB));
+        }
+        Ok(())
+    }
+}
+
+/// Tuning parameters of the capture pipeline. Changes made during a recording are
+/// picked up by the collection task at the next chunk boundary.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineConfig {
+    /// Extra level applied to the mic on top of loudness matching
+    pub mic_level: f32,
+    /// Extra level applied to system audio on top of loudness matching
+    pub system_level: f32,
+    pub chunk_duration_ms: u32,
+    /// Audio from the end of each chunk repeated at the start of the next, so words
+    /// cut at a boundary are transcribed whole
+    pub chunk_overlap_ms: u32,
+    /// Run the voice activity detector on each chunk before it is queued
+    pub vad_enabled: bool,
+    /// 0 keeps only clear speech, 1 sends nearly everything
+    pub vad_sensitivity: f32,
+    /// Cutoff of the mic high-pass filter for devices without their own filters,
+    /// disabled when unset
+    pub high_pass_hz: Option<f32>,
+    /// Filters per microphone, keyed by device name
+    pub device_filters: BTreeMap<String, DeviceFilters>,
+    /// Normalize the mic level before it is mixed, for quiet or distant speakers
+    pub agc_enabled: bool,
+    /// RMS level the automatic gain control aims for, in dBFS
+    pub agc_target_db: f32,
+    /// Remove system audio picked up by the mic, for meetings played through speakers
+    pub echo_cancellation: bool,
+}
+
+impl Default for PipelineConfig {
+    fn default() -> Self {
+        Self {
+            mic_level: 1.0,
+            system_level: 1.0,
+            chunk_duration_ms: CHUNK_DURATION_MS,
+            chunk_overlap_ms: CHUNK_OVERLAP_MS,
+            vad_enabled: true,
+            vad_sensitivity: 0.5,
+            high_pass_hz: None,
+            device_filters: BTreeMap::new(),
+            agc_enabled: false,
+            agc_target_db: DEFAULT_AGC_TARGET_DB,
+            echo_cancellation: false,
+        }
+    }
+}
+
+impl PipelineConfig {
+    fn validate(&self) -> Result<(), String> {
+        if !(0.0..=MAX_LEVEL).contains(&self.mic_level) || !(0.0..=MAX_LEVEL).contains(&self.system_level) {
+            return Err(format!("Mix levels must be between 0 and {}", MAX_LEVEL));
+        }
+        if !(MIN_CHUNK_DURATION_MS..=MAX_CHUNK_DURATION_MS).contains(&self.chunk_duration_ms) {
+            return Err(format!(
+                "Chunk duration must be between {} and {} ms",
+                MIN_CHUNK_DURATION_MS, MAX_CHUNK_DURATION_MS
+            ));
+        }
+        if self.chunk_overlap_ms > MAX_CHUNK_OVERLAP_MS || self.chunk_overlap_ms * 2 > self.chunk_duration_ms {
+            return Err(format!(
+                "Chunk overlap must be at most {} ms and half the chunk duration",
+                MAX_CHUNK_OVERLAP_MS
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.vad_sensitivity) {
+            return Err("VAD sensitivity must be between 0 and 1".to_string());
+        }
+        for filters in self.device_filters.values() {
+            filters.validate()?;
+        }
+        if !(MIN_AGC_TARGET_DB..=MAX_AGC_TARGET_DB).contains(&self.agc_target_db) {
+            return Err(format!(
+                "Gain control target must be between {} and {} dB",
+                MIN_AGC_TARGET_DB, MAX_AGC_TARGET_DB
+            ));
+        }
+        if let Some(cutoff) = self.high_pass_hz {
+            if !(MIN_HIGH_PASS_HZ..=MAX_HIGH_PASS_HZ).contains(&cutoff) {
+                return Err(format!(
+                    "High-pass cutoff must be between {} and {} Hz",
+                    MIN_HIGH_PASS_HZ, MAX_HIGH_PASS_HZ
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Filters for a microphone. Devices without their own entry only get the global
+    /// high-pass cutoff, if one is set.
+    pub fn filters_for(&self, device_name: &str) -> DeviceFilters {
+        self.device_filters.get(device_name).cloned().unwrap_or_else(|| DeviceFilters {
+            high_pass_enabled: self.high_pass_hz.is_some(),
+            high_pass_hz: self.high_pass_hz.unwrap_or(DEFAULT_HIGH_PASS_HZ),
+            ..DeviceFilters::default()
+        })
+    }
+
+    /// RMS below which a chunk counts as silence. The default sensitivity gives 1e-4,
+    /// which only drops digital silence.
+    pub fn silence_threshold(&self) -> f32 {
+        10f32.powf(-2.0 - 4.0 * self.vad_sensitivity)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppliedPipelineConfig {
+    /// Seconds into the recording at which the configuration took effect
+    pub applied_at_secs: f64,
+    #[serde(flatten)]
+    pub config: PipelineConfig,
+}
+
+static CURRENT: Lazy<Mutex<PipelineConfig>> = Lazy::new(|| Mutex::new(PipelineConfig::default()));
+static VERSION: AtomicU64 = AtomicU64::new(0);
+static APPLIED: Lazy<Mutex<Vec<AppliedPipelineConfig>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> PipelineConfig {
+    let config: PipelineConfig = app
+        .store("store.json")
+        .ok()
+        .and_then(|store| store.get(PIPELINE_CONFIG_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default();
+    match config.validate() {
+        Ok(()) => config,
+        Err(e) => {
+            log_warn!("Ignoring stored pipeline config: {}", e);
+            PipelineConfig::default()
+        }
+    }
+}
+
+/// Tracks which configuration the collection task is running with.
+pub struct ConfigWatcher {
+    version: u64,
+}
+
+impl ConfigWatcher {
+    /// The configuration a new recording starts with. Previously applied snapshots are cleared.
+    pub fn start<R: Runtime>(app: &AppHandle<R>) -> (Self, PipelineConfig) {
+        let config = load_config(app);
+        if let Ok(mut current) = CURRENT.lock() {
+            *current = config.clone();
+        }
+        if let Ok(mut applied) = APPLIED.lock() {
+            applied.clear();
+        }
+        record_applied(0.0, &config);
+        (Self { version: VERSION.load(Ordering::SeqCst) }, config)
+    }
+
+    /// Returns the new configuration if it changed since the last call.
+    pub fn poll(&mut self) -> Option<PipelineConfig> {
+        let version = VERSION.load(Ordering::SeqCst);
+        if version == self.version {
+            return None;
+        }
+        self.version = version;
+        CURRENT.lock().ok().map(|config| config.clone())
+    }
+}
+
+pub fn record_applied(applied_at_secs: f64, config: &PipelineConfig) {
+    if let Ok(mut applied) = APPLIED.lock() {
+        applied.push(AppliedPipelineConfig { applied_at_secs, config: config.clone() });
+        meeting_metadata::set("pipeline_config", serde_json::json!(&*applied));
+    }
+}
+
+/// The configuration the current (or last) recording runs with.
+pub fn current() -> PipelineConfig {
+    CURRENT.lock().map(|config| config.clone()).unwrap_or_default()
+}
+
+pub(crate) fn set_mix_levels<R: Runtime>(app: &AppHandle<R>, mic_level: f32, system_level: f32) -> Result<(), String> {
+    let mut config = load_config(app);
+    config.mic_level = mic_level;
+    config.system_level = system_level;
+    save_config(app, config)
+}
+
+#[tauri::command]
+pub async fn get_pipeline_config<R: Runtime>(app: AppHandle<R>) -> Result<PipelineConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the pipeline configuration. A running recording switches to it at its next chunk.
+#[tauri::command]
+pub async fn update_pipeline_config<R: Runtime>(app: AppHandle<R>, config: Versioned<PipelineConfig>) -> Result<(), String> {
+    save_config(&app, config.into_payload()?)
+}
+
+fn save_config<R: Runtime>(app: &AppHandle<R>, config: PipelineConfig) -> Result<(), String> {
+    config.validate()?;
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(PIPELINE_CONFIG_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+
+    log_info!("Pipeline config updated: {:?}", config);
+    *CURRENT.lock().map_err(|e| e.to_string())? = config;
+    VERSION.fetch_add(1, Ordering::SeqCst);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_device_filters<R: Runtime>(app: AppHandle<R>, device_name: String) -> Result<DeviceFilters, String> {
+    Ok(load_config(&app).filters_for(&device_name))
+}
+
+/// Set the filters of one microphone, or reset it to the defaults when `filters` is
+/// omitted. A running recording picks them up at its next chunk.
+#[tauri::command]
+pub async fn set_device_filters<R: Runtime>(
+    app: AppHandle<R>,
+    device_name: String,
+    filters: Option<DeviceFilters>,
+) -> Result<(), String> {
+    let mut config = load_config(&app);
+    match filters {
+        Some(filters) => {
+            config.device_filters.insert(device_name, filters);
+        }
+        None => {
+            config.device_filters.remove(&device_name);
+        }
+    }
+    save_config(&app, config)
+}
+
+/// Turn automatic gain control of the mic on or off, optionally with a new target level.
+/// A running recording picks it up at its next chunk.
+#[tauri::command]
+pub async fn set_mic_agc<R: Runtime>(app: AppHandle<R>, enabled: bool, target_db: Option<f32>) -> Result<(), String> {
+    let mut config = load_config(&app);
+    config.agc_enabled = enabled;
+    if let Some(target_db) = target_db {
+        config.agc_target_db = target_db;
+    }
+    save_config(&app, config)
+}
+
+#[tauri::command]
+pub fn get_applied_pipeline_configs() -> Vec<AppliedPipelineConfig> {
+    APPLIED.lock().map(|applied| applied.clone()).unwrap_or_default()
+}
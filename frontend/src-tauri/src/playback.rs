@@ -0,0 +1,249 @@
+// Playback of saved recordings in step with the transcript. When a recording is saved, an
+// index is written next to it mapping each transcript line's sequence_id to where the line
+// is in the audio, so clicking a line can replay that moment. `get_audio_segment` hands the
+// frontend a short, self-contained WAV for any range of a recording.
+//
+// Byte offsets are only meaningful for WAV recordings, which are read by seeking straight
+// to them; compressed recordings are decoded once and sliced.
+use std::fs::File;
+use std::io::{Cursor, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use log::{info as log_info, error as log_error};
+
+use crate::atomic_file;
+use crate::audio::decode_audio_file;
+use crate::TranscriptUpdate;
+
+const INDEX_EXTENSION: &str = "index.json";
+// Longest range returned at once; the frontend asks for more as playback moves on
+const MAX_SEGMENT_MS: u64 = 60_000;
+
+/// Where one transcript line sits in the recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackEntry {
+    pub sequence_id: u64,
+    pub start_ms: u64,
+    /// None when the line had no word timings; it then runs until the next line
+    pub end_ms: Option<u64>,
+    /// Offset of the line's first sample in the file; WAV recordings only
+    pub byte_offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaybackIndex {
+    pub recording_path: String,
+    pub sample_rate: u32,
+    /// Start of the sample data in the file; WAV recordings only
+    pub data_offset: Option<u64>,
+    pub bytes_per_sample: Option<u16>,
+    pub entries: Vec<PlaybackEntry>,
+}
+
+// Layout of a PCM WAV's sample data
+#[derive(Debug, Clone, Copy)]
+struct WavLayout {
+    sample_rate: u32,
+    channels: u16,
+    bits_per_sample: u16,
+    data_offset: u64,
+    data_len: u64,
+}
+
+impl WavLayout {
+    fn block_align(&self) -> u64 {
+        self.channels as u64 * (self.bits_per_sample as u64 / 8)
+    }
+
+    fn offset_of(&self, ms: u64) -> u64 {
+        let frame = ms * self.sample_rate as u64 / 1000;
+        self.data_offset + (frame * self.block_align()).min(self.data_len)
+    }
+}
+
+fn wav_layout(path: &Path) -> Option<WavLayout> {
+    if !path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("wav")) {
+        return None;
+    }
+    // Unbuffered, so the reader's position after the header is where the samples start
+    let reader = hound::WavReader::new(File::open(path).ok()?).ok()?;
+    let spec = reader.spec();
+    if spec.sample_format != hound::SampleFormat::Int || spec.bits_per_sample != 16 {
+        return None;
+    }
+    let data_len = reader.len() as u64 * (spec.bits_per_sample as u64 / 8);
+    let data_offset = reader.into_inner().stream_position().ok()?;
+    Some(WavLayout {
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        bits_per_sample: spec.bits_per_sample,
+        data_offset,
+        data_len,
+    })
+}
+
+struct Session {
+    lines: Vec<TranscriptUpdate>,
+    // Set once the recording is saved, so lines that arrive while the queue drains still get indexed
+    saved_recording: Option<PathBuf>,
+}
+
+static SESSION: Lazy<Mutex<Session>> = Lazy::new(|| Mutex::new(Session { lines: Vec::new(), saved_recording: None }));
+
+// Last compressed recording decoded for playback, with its sample rate
+static DECODED: Lazy<Mutex<Option<(PathBuf, Arc<Vec<f32>>, u32)>>> = Lazy::new(|| Mutex::new(None));
+
+pub fn begin_recording() {
+    if let Ok(mut session) = SESSION.lock() {
+        session.lines.clear();
+        session.saved_recording = None;
+    }
+}
+
+pub(crate) fn record_line(update: &TranscriptUpdate) {
+    let Ok(mut session) = SESSION.lock() else { return };
+    session.lines.push(update.clone());
+    if let Some(recording) = session.saved_recording.clone() {
+        write_index(&recording, &session.lines);
+    }
+}
+
+/// Index the transcript against the saved recording and keep it current until the next recording.
+pub fn recording_saved(recording: &Path) {
+    let Ok(mut session) = SESSION.lock() else { return };
+    session.saved_recording = Some(recording.to_path_buf());
+    write_index(recording, &session.lines);
+}
+
+fn index_path(recording: &Path) -> PathBuf {
+    recording.with_extension(INDEX_EXTENSION)
+}
+
+fn line_start_ms(line: &TranscriptUpdate) -> u64 {
+    let secs = line.words.first().map(|word| word.start).unwrap_or(line.chunk_start_time);
+    (secs.max(0.0) * 1000.0) as u64
+}
+
+fn write_index(recording: &Path, lines: &[TranscriptUpdate]) {
+    let layout = wav_layout(recording);
+    let mut entries: Vec<PlaybackEntry> = lines
+        .iter()
+        .map(|line| {
+            let start_ms = line_start_ms(line);
+            PlaybackEntry {
+                sequence_id: line.sequence_id,
+                start_ms,
+                end_ms: line.words.last().map(|word| (word.end.max(0.0) * 1000.0) as u64),
+                byte_offset: layout.map(|layout| layout.offset_of(start_ms)),
+            }
+        })
+        .collect();
+    entries.sort_by_key(|entry| (entry.start_ms, entry.sequence_id));
+
+    let index = PlaybackIndex {
+        recording_path: recording.to_string_lossy().to_string(),
+        sample_rate: layout.map(|layout| layout.sample_rate).unwrap_or(crate::WHISPER_SAMPLE_RATE),
+        data_offset: layout.map(|layout| layout.data_offset),
+        bytes_per_sample: layout.map(|layout| layout.bits_per_sample / 8),
+        entries,
+    };
+    let result = serde_json::to_vec_pretty(&index)
+        .map_err(|e| e.to_string())
+        .and_then(|json| atomic_file::write(index_path(recording), json).map_err(|e| e.to_string()));
+    match result {
+        Ok(()) => log_info!("Indexed {} transcript lines for playback of {}", index.entries.len(), recording.display()),
+        Err(e) => log_error!("Failed to write playback index for {}: {}", recording.display(), e),
+    }
+}
+
+fn wav_bytes(samples: &[i16], sample_rate: u32) -> Result<Vec<u8>, String> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut cursor = Cursor::new(Vec::with_capacity(samples.len() * 2 + 44));
+    let mut writer = hound::WavWriter::new(&mut cursor, spec).map_err(|e| format!("Failed to write segment: {}", e))?;
+    for sample in samples {
+        writer.write_sample(*sample).map_err(|e| format!("Failed to write segment: {}", e))?;
+    }
+    writer.finalize().map_err(|e| format!("Failed to write segment: {}", e))?;
+    Ok(cursor.into_inner())
+}
+
+// Read the range straight from the file, down-mixing if the WAV is not mono
+fn read_wav_segment(path: &Path, layout: &WavLayout, start_ms: u64, end_ms: u64) -> Result<Vec<u8>, String> {
+    let (from, to) = (layout.offset_of(start_ms), layout.offset_of(end_ms));
+    let mut file = File::open(path).map_err(|e| format!("Failed to open recording: {}", e))?;
+    file.seek(SeekFrom::Start(from)).map_err(|e| format!("Failed to seek recording: {}", e))?;
+    let mut bytes = vec![0u8; (to - from) as usize];
+    file.read_exact(&mut bytes).map_err(|e| format!("Failed to read recording: {}", e))?;
+
+    let channels = layout.channels.max(1) as usize;
+    let samples: Vec<i16> = bytes
+        .chunks_exact(2 * channels)
+        .map(|frame| {
+            let sum: i32 = frame.chunks_exact(2).map(|s| i16::from_le_bytes([s[0], s[1]]) as i32).sum();
+            (sum / channels as i32) as i16
+        })
+        .collect();
+    wav_bytes(&samples, layout.sample_rate)
+}
+
+fn decoded_samples(path: &Path) -> Result<(Arc<Vec<f32>>, u32), String> {
+    if let Some((cached, samples, rate)) = DECODED.lock().ok().and_then(|decoded| decoded.clone()) {
+        if cached == path {
+            return Ok((samples, rate));
+        }
+    }
+    let (samples, rate) = decode_audio_file(path).map_err(|e| format!("Failed to decode recording: {}", e))?;
+    let samples = Arc::new(samples);
+    if let Ok(mut decoded) = DECODED.lock() {
+        *decoded = Some((path.to_path_buf(), samples.clone(), rate));
+    }
+    Ok((samples, rate))
+}
+
+fn decoded_segment(path: &Path, start_ms: u64, end_ms: u64) -> Result<Vec<u8>, String> {
+    let (samples, rate) = decoded_samples(path)?;
+    let at = |ms: u64| ((ms * rate as u64 / 1000) as usize).min(samples.len());
+    let segment: Vec<i16> = samples[at(start_ms)..at(end_ms)]
+        .iter()
+        .map(|sample| (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect();
+    wav_bytes(&segment, rate)
+}
+
+/// A range of a saved recording as a mono WAV the frontend can play directly. Ranges past
+/// the end of the recording are cut short; at most a minute is returned per call.
+#[tauri::command]
+pub async fn get_audio_segment(path: String, start_ms: u64, end_ms: u64) -> Result<tauri::ipc::Response, String> {
+    if end_ms <= start_ms {
+        return Err("Segment end must be after its start".to_string());
+    }
+    let end_ms = end_ms.min(start_ms + MAX_SEGMENT_MS);
+    let path = PathBuf::from(path);
+    if !path.is_file() {
+        return Err(format!("Recording not found: {}", path.display()));
+    }
+
+    let bytes = tokio::task::spawn_blocking(move || match wav_layout(&path) {
+        Some(layout) => read_wav_segment(&path, &layout, start_ms, end_ms),
+        None => decoded_segment(&path, start_ms, end_ms),
+    })
+    .await
+    .map_err(|e| format!("Failed to read audio segment: {}", e))??;
+    Ok(tauri::ipc::Response::new(bytes))
+}
+
+/// The transcript index saved with a recording, ordered by position in the audio.
+#[tauri::command]
+pub async fn get_playback_index(path: String) -> Result<PlaybackIndex, String> {
+    let index_path = index_path(Path::new(&path));
+    let json = atomic_file::read_verified(&index_path)
+        .map_err(|e| format!("Failed to read playback index {}: {}", index_path.display(), e))?;
+    serde_json::from_slice(&json).map_err(|e| format!("Failed to parse playback index: {}", e))
+}
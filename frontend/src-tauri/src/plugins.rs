@@ -0,0 +1,345 @@
+// Post-processing plugins. A plugin is an external program the user registers; it is started
+// when a recording begins and talks to the app over a JSON-lines protocol on stdin/stdout,
+// so it can be written in any language and needs no changes to this crate.
+//
+// The app sends one JSON object per line:
+//   {"type":"hello","protocol":1,"plugin_id":"..."}
+//   {"type":"segment","segment":{"sequence_id":..,"text":..,"timestamp":..,"source":..,"speaker":..}}
+//   {"type":"meeting_finished","metadata":{...}}
+// and then closes stdin. The plugin may answer at any time with lines of its own:
+//   {"type":"annotation","label":"crm","text":"...","sequence_id":12}
+//   {"type":"action","name":"open_url","payload":{...}}
+//   {"type":"log","level":"info","message":"..."}
+// Annotations are saved with the meeting metadata; actions are passed to the frontend as
+// `plugin-action` events and only carried out there, with the user's consent. Like export
+// hooks, plugins run directly (never through a shell) with a cleared environment.
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::mpsc;
+use log::{info as log_info, debug as log_debug, error as log_error, warn as log_warn};
+
+use crate::sandbox;
+use crate::{meeting_metadata, TranscriptUpdate};
+
+const PLUGINS_STORE_KEY: &str = "plugins";
+const PROTOCOL_VERSION: u32 = 1;
+// How long a plugin may take to finish after the meeting ends before it is killed
+const DEFAULT_FINISH_TIMEOUT_SECS: u64 = 30;
+const MAX_FINISH_TIMEOUT_SECS: u64 = 300;
+// Per recording and plugin, so a chatty plugin can't grow the metadata without bound
+const MAX_ANNOTATIONS: usize = 1000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginConfig {
+    pub id: String,
+    pub name: String,
+    /// Absolute path of the program to run
+    pub program: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Whether the plugin wants every finished segment or only the end of the meeting
+    #[serde(default = "default_enabled")]
+    pub receive_segments: bool,
+    #[serde(default = "default_finish_timeout")]
+    pub finish_timeout_secs: u64,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_finish_timeout() -> u64 {
+    DEFAULT_FINISH_TIMEOUT_SECS
+}
+
+impl PluginConfig {
+    fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() || !self.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err("Plugin id must be letters, digits, '-' or '_'".to_string());
+        }
+        let program = Path::new(&self.program);
+        if !program.is_absolute() {
+            return Err("Plugin program must be an absolute path".to_string());
+        }
+        if !program.is_file() {
+            return Err(format!("Plugin program not found: {}", self.program));
+        }
+        if !(1..=MAX_FINISH_TIMEOUT_SECS).contains(&self.finish_timeout_secs) {
+            return Err(format!("Plugin timeout must be between 1 and {} seconds", MAX_FINISH_TIMEOUT_SECS));
+        }
+        if self.args.iter().any(|arg| arg.contains('\0')) {
+            return Err("Plugin arguments cannot contain NUL characters".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SegmentMessage<'a> {
+    sequence_id: u64,
+    text: &'a str,
+    timestamp: &'a str,
+    source: &'a str,
+    speaker: Option<&'a str>,
+    chunk_start_time: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum AppMessage<'a> {
+    Hello { protocol: u32, plugin_id: &'a str },
+    Segment { segment: SegmentMessage<'a> },
+    MeetingFinished { metadata: Option<Value> },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum PluginMessage {
+    Annotation {
+        label: String,
+        text: String,
+        #[serde(default)]
+        sequence_id: Option<u64>,
+    },
+    Action {
+        name: String,
+        #[serde(default)]
+        payload: Value,
+    },
+    Log {
+        #[serde(default)]
+        level: String,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginAnnotation {
+    pub plugin_id: String,
+    pub label: String,
+    pub text: String,
+    pub sequence_id: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginAction {
+    pub plugin_id: String,
+    pub name: String,
+    pub payload: Value,
+}
+
+struct RunningPlugin {
+    id: String,
+    receive_segments: bool,
+    sender: mpsc::UnboundedSender<String>,
+}
+
+static RUNNING: Lazy<Mutex<Vec<RunningPlugin>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static ANNOTATIONS: Lazy<Mutex<Vec<PluginAnnotation>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn load_plugins<R: Runtime>(app: &AppHandle<R>) -> Vec<PluginConfig> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(PLUGINS_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save_plugins<R: Runtime>(app: &AppHandle<R>, plugins: &[PluginConfig]) -> Result<(), String> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(PLUGINS_STORE_KEY, serde_json::to_value(plugins).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
+fn send_segment(message: &AppMessage) {
+    let Ok(line) = serde_json::to_string(message) else { return };
+    if let Ok(running) = RUNNING.lock() {
+        for plugin in running.iter().filter(|plugin| plugin.receive_segments) {
+            if plugin.sender.send(line.clone()).is_err() {
+                log_debug!("Plugin {} is no longer running", plugin.id);
+            }
+        }
+    }
+}
+
+fn handle_message<R: Runtime>(app: &AppHandle<R>, plugin_id: &str, message: PluginMessage) {
+    match message {
+        PluginMessage::Annotation { label, text, sequence_id } => {
+            let annotation = PluginAnnotation { plugin_id: plugin_id.to_string(), label, text, sequence_id };
+            let Ok(mut annotations) = ANNOTATIONS.lock() else { return };
+            if annotations.iter().filter(|a| a.plugin_id == plugin_id).count() >= MAX_ANNOTATIONS {
+                log_warn!("Plugin {} reached {} annotations; ignoring the rest", plugin_id, MAX_ANNOTATIONS);
+                return;
+            }
+            annotations.push(annotation.clone());
+            meeting_metadata::set("plugin_annotations", serde_json::json!(&*annotations));
+            if let Err(e) = app.emit("plugin-annotation", &annotation) {
+                log_error!("Failed to emit plugin-annotation event: {}", e);
+            }
+        }
+        PluginMessage::Action { name, payload } => {
+            let action = PluginAction { plugin_id: plugin_id.to_string(), name, payload };
+            if let Err(e) = app.emit("plugin-action", &action) {
+                log_error!("Failed to emit plugin-action event: {}", e);
+            }
+        }
+        PluginMessage::Log { level, message } => match level.as_str() {
+            "error" => log_error!("[plugin {}] {}", plugin_id, message),
+            "warn" => log_warn!("[plugin {}] {}", plugin_id, message),
+            _ => log_info!("[plugin {}] {}", plugin_id, message),
+        },
+    }
+}
+
+fn start<R: Runtime>(app: &AppHandle<R>, plugin: PluginConfig) -> Result<RunningPlugin, String> {
+    let mut command = sandbox::command(&plugin.program);
+    command
+        .args(&plugin.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    let mut child = command.spawn().map_err(|e| format!("Failed to start plugin {}: {}", plugin.id, e))?;
+    let mut stdin = child.stdin.take().ok_or("Plugin stdin unavailable")?;
+    let stdout = child.stdout.take().ok_or("Plugin stdout unavailable")?;
+
+    let reader_app = app.clone();
+    let reader_id = plugin.id.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<PluginMessage>(&line) {
+                Ok(message) => handle_message(&reader_app, &reader_id, message),
+                Err(e) => log_warn!("Plugin {} sent an unreadable message: {}", reader_id, e),
+            }
+        }
+    });
+
+    let (sender, mut receiver) = mpsc::unbounded_channel::<String>();
+    let id = plugin.id.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(line) = receiver.recv().await {
+            if let Err(e) = stdin.write_all(format!("{}\n", line).as_bytes()).await {
+                log_warn!("Failed to write to plugin {}: {}", id, e);
+                break;
+            }
+        }
+        // Closing stdin tells the plugin the meeting is over
+        drop(stdin);
+        match tokio::time::timeout(Duration::from_secs(plugin.finish_timeout_secs), child.wait()).await {
+            Ok(Ok(status)) if status.success() => log_info!("Plugin {} finished", id),
+            Ok(Ok(status)) => log_warn!("Plugin {} exited with {}", id, status),
+            Ok(Err(e)) => log_error!("Failed to wait for plugin {}: {}", id, e),
+            Err(_) => {
+                log_warn!("Plugin {} did not finish within {} seconds; stopping it", id, plugin.finish_timeout_secs);
+                let _ = child.kill().await;
+            }
+        }
+    });
+
+    let hello = AppMessage::Hello { protocol: PROTOCOL_VERSION, plugin_id: &plugin.id };
+    let _ = sender.send(serde_json::to_string(&hello).map_err(|e| e.to_string())?);
+    log_info!("Started plugin {} ({})", plugin.name, plugin.id);
+    Ok(RunningPlugin { id: plugin.id, receive_segments: plugin.receive_segments, sender })
+}
+
+/// Start the enabled plugins for a new recording. Plugins that fail to start are skipped.
+pub fn begin_recording<R: Runtime>(app: &AppHandle<R>) {
+    finish_recording();
+    if let Ok(mut annotations) = ANNOTATIONS.lock() {
+        annotations.clear();
+    }
+    let started: Vec<RunningPlugin> = load_plugins(app)
+        .into_iter()
+        .filter(|plugin| plugin.enabled)
+        .filter_map(|plugin| match plugin.validate().and_then(|_| start(app, plugin)) {
+            Ok(running) => Some(running),
+            Err(e) => {
+                log_warn!("{}", e);
+                None
+            }
+        })
+        .collect();
+    if let Ok(mut running) = RUNNING.lock() {
+        *running = started;
+    }
+}
+
+/// Pass a finished transcript segment to the plugins that asked for segments.
+pub(crate) fn observe(update: &TranscriptUpdate) {
+    if update.is_partial {
+        return;
+    }
+    let segment = SegmentMessage {
+        sequence_id: update.sequence_id,
+        text: &update.text,
+        timestamp: &update.timestamp,
+        source: &update.source,
+        speaker: update.speaker.as_deref(),
+        chunk_start_time: update.chunk_start_time,
+    };
+    send_segment(&AppMessage::Segment { segment });
+}
+
+/// Tell the plugins the meeting is over, with its metadata, and let them wind down.
+pub fn finish_recording() {
+    let running = RUNNING.lock().map(|mut running| std::mem::take(&mut *running)).unwrap_or_default();
+    if running.is_empty() {
+        return;
+    }
+    let message = AppMessage::MeetingFinished { metadata: meeting_metadata::snapshot() };
+    if let Ok(line) = serde_json::to_string(&message) {
+        for plugin in &running {
+            let _ = plugin.sender.send(line.clone());
+        }
+    }
+    // Dropping the senders closes each plugin's stdin once the queued lines are written
+}
+
+#[tauri::command]
+pub async fn list_plugins<R: Runtime>(app: AppHandle<R>) -> Result<Vec<PluginConfig>, String> {
+    Ok(load_plugins(&app))
+}
+
+/// Register a plugin, or replace the one with the same id. Takes effect from the next recording.
+#[tauri::command]
+pub async fn set_plugin<R: Runtime>(app: AppHandle<R>, plugin: PluginConfig) -> Result<(), String> {
+    plugin.validate()?;
+    let mut plugins = load_plugins(&app);
+    match plugins.iter_mut().find(|existing| existing.id == plugin.id) {
+        Some(existing) => *existing = plugin.clone(),
+        None => plugins.push(plugin.clone()),
+    }
+    save_plugins(&app, &plugins)?;
+    log_info!("Saved plugin {} ({})", plugin.name, plugin.id);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn remove_plugin<R: Runtime>(app: AppHandle<R>, plugin_id: String) -> Result<(), String> {
+    let mut plugins = load_plugins(&app);
+    let before = plugins.len();
+    plugins.retain(|plugin| plugin.id != plugin_id);
+    if plugins.len() == before {
+        return Err(format!("Plugin not found: {}", plugin_id));
+    }
+    save_plugins(&app, &plugins)
+}
+
+/// Annotations plugins have made during the current recording.
+#[tauri::command]
+pub fn get_plugin_annotations() -> Vec<PluginAnnotation> {
+    ANNOTATIONS.lock().map(|annotations| annotations.clone()).unwrap_or_default()
+}
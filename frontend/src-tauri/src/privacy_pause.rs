@@ -0,0 +1,117 @@
+// "Privacy pause": stops capturing system audio (e.g. while checking a voicemail) while the
+// microphone keeps recording. System audio received during a pause is replaced by silence
+// before it reaches the recording or the transcriber, so the timeline stays continuous and
+// the gap is marked in the transcript instead.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error};
+
+use crate::{is_recording, meeting_metadata, publish_note};
+
+const TRANSCRIPT_SOURCE: &str = "Privacy";
+
+/// A stretch of the recording without system audio, in seconds from the start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivacyPause {
+    pub start_secs: f64,
+    /// None while the pause is still active
+    pub end_secs: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrivacyPauseStatus {
+    pub paused: bool,
+    pub pauses: Vec<PrivacyPause>,
+}
+
+#[derive(Default)]
+struct PauseLog {
+    recording_start: Option<Instant>,
+    pauses: Vec<PrivacyPause>,
+}
+
+static PAUSED: AtomicBool = AtomicBool::new(false);
+static PAUSE_LOG: Lazy<Mutex<PauseLog>> = Lazy::new(|| Mutex::new(PauseLog::default()));
+
+/// Whether system audio is currently being discarded.
+pub fn is_paused() -> bool {
+    PAUSED.load(Ordering::SeqCst)
+}
+
+/// Forget the previous recording's pauses. Every recording starts with system audio on.
+pub fn begin_recording(recording_start: Instant) {
+    PAUSED.store(false, Ordering::SeqCst);
+    if let Ok(mut log) = PAUSE_LOG.lock() {
+        *log = PauseLog { recording_start: Some(recording_start), pauses: Vec::new() };
+    }
+}
+
+/// Close a pause still open when the recording stops.
+pub fn finish_recording() {
+    PAUSED.store(false, Ordering::SeqCst);
+    if let Ok(mut log) = PAUSE_LOG.lock() {
+        let now = log.recording_start.map(|start| start.elapsed().as_secs_f64());
+        if let Some(pause) = log.pauses.last_mut().filter(|pause| pause.end_secs.is_none()) {
+            pause.end_secs = now;
+            meeting_metadata::set("privacy_pauses", serde_json::json!(&log.pauses));
+        }
+    }
+}
+
+fn status() -> PrivacyPauseStatus {
+    PrivacyPauseStatus {
+        paused: is_paused(),
+        pauses: PAUSE_LOG.lock().map(|log| log.pauses.clone()).unwrap_or_default(),
+    }
+}
+
+/// Pause or resume system audio capture for the current recording.
+#[tauri::command]
+pub async fn set_privacy_pause<R: Runtime>(app: AppHandle<R>, paused: bool) -> Result<PrivacyPauseStatus, String> {
+    if !is_recording() {
+        return Err("Privacy pause is only available while recording".to_string());
+    }
+    if PAUSED.swap(paused, Ordering::SeqCst) == paused {
+        return Ok(status());
+    }
+
+    let offset_secs = {
+        let mut log = PAUSE_LOG.lock().map_err(|e| e.to_string())?;
+        let offset_secs = log.recording_start.map(|start| start.elapsed().as_secs_f64()).unwrap_or(0.0);
+        if paused {
+            log.pauses.push(PrivacyPause { start_secs: offset_secs, end_secs: None });
+        } else if let Some(pause) = log.pauses.last_mut() {
+            pause.end_secs = Some(offset_secs);
+        }
+        meeting_metadata::set("privacy_pauses", serde_json::json!(&log.pauses));
+        offset_secs
+    };
+
+    if paused {
+        log_info!("System audio paused for privacy at {:.1}s", offset_secs);
+        publish_note(&app, TRANSCRIPT_SOURCE, "System audio paused for privacy", offset_secs);
+    } else {
+        log_info!("System audio resumed at {:.1}s", offset_secs);
+        publish_note(&app, TRANSCRIPT_SOURCE, "System audio resumed", offset_secs);
+    }
+    let status = status();
+    if let Err(e) = app.emit("privacy-pause", &status) {
+        log_error!("Failed to emit privacy-pause event: {}", e);
+    }
+    Ok(status)
+}
+
+/// Flip the privacy pause; meant for a single keyboard shortcut.
+#[tauri::command]
+pub async fn toggle_privacy_pause<R: Runtime>(app: AppHandle<R>) -> Result<PrivacyPauseStatus, String> {
+    set_privacy_pause(app, !is_paused()).await
+}
+
+#[tauri::command]
+pub fn get_privacy_pause_status() -> PrivacyPauseStatus {
+    status()
+}
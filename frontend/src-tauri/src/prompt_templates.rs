@@ -0,0 +1,186 @@
+// Named prompt templates for summaries, so a standup, a 1:1 and a sales call can each be
+// summarized with their own instructions. Templates are kept in the local store under
+// `promptTemplates` and may use `{{transcript}}`, `{{attendees}}` and `{{date}}`, which are
+// filled in when a summary is generated. A template without `{{transcript}}` gets the
+// transcript appended after its text.
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::info as log_info;
+
+const TEMPLATES_STORE_KEY: &str = "promptTemplates";
+const VARIABLES: [&str; 3] = ["transcript", "attendees", "date"];
+const MAX_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    pub id: String,
+    pub name: String,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Values for a template's variables.
+pub struct TemplateContext<'a> {
+    pub transcript: &'a str,
+    pub attendees: &'a [String],
+    pub date: NaiveDate,
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Vec<PromptTemplate> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(TEMPLATES_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, templates: &[PromptTemplate]) -> Result<(), String> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(TEMPLATES_STORE_KEY, serde_json::to_value(templates).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
+// Names of the `{{...}}` placeholders in a template body
+fn placeholders(body: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else { break };
+        names.push(rest[start + 2..start + 2 + end].trim());
+        rest = &rest[start + 2 + end + 2..];
+    }
+    names
+}
+
+// Name and body trimmed and checked; the name must not be taken by another template
+fn validate(templates: &[PromptTemplate], id: Option<&str>, name: &str, body: &str) -> Result<(String, String), String> {
+    let name = name.trim();
+    let body = body.trim();
+    if name.is_empty() {
+        return Err("A template needs a name".to_string());
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(format!("Template names are limited to {} characters", MAX_NAME_CHARS));
+    }
+    if body.is_empty() {
+        return Err("A template needs some instructions".to_string());
+    }
+    if let Some(unknown) = placeholders(body).into_iter().find(|name| !VARIABLES.contains(name)) {
+        return Err(format!(
+            "Unknown variable {{{{{}}}}}; templates can use {{{{transcript}}}}, {{{{attendees}}}} and {{{{date}}}}",
+            unknown
+        ));
+    }
+    let taken = templates
+        .iter()
+        .any(|template| Some(template.id.as_str()) != id && template.name.eq_ignore_ascii_case(name));
+    if taken {
+        return Err(format!("A template named '{}' already exists", name));
+    }
+    Ok((name.to_string(), body.to_string()))
+}
+
+/// The saved template with `id`.
+pub fn get<R: Runtime>(app: &AppHandle<R>, id: &str) -> Result<PromptTemplate, String> {
+    load(app)
+        .into_iter()
+        .find(|template| template.id == id)
+        .ok_or_else(|| format!("Prompt template {} not found", id))
+}
+
+/// Fill in a template's variables.
+pub fn render(template: &PromptTemplate, context: &TemplateContext) -> String {
+    let attendees = if context.attendees.is_empty() {
+        "not known".to_string()
+    } else {
+        context.attendees.join(", ")
+    };
+    let date = context.date.format("%Y-%m-%d").to_string();
+    let mut rendered = String::with_capacity(template.body.len() + context.transcript.len());
+    let mut has_transcript = false;
+    let mut rest = template.body.as_str();
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start + 2..].find("}}") else { break };
+        rendered.push_str(&rest[..start]);
+        match rest[start + 2..start + 2 + end].trim() {
+            "transcript" => {
+                has_transcript = true;
+                rendered.push_str(context.transcript);
+            }
+            "attendees" => rendered.push_str(&attendees),
+            "date" => rendered.push_str(&date),
+            _ => rendered.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &rest[start + 2 + end + 2..];
+    }
+    rendered.push_str(rest);
+    if !has_transcript {
+        rendered.push_str("\n\nTranscript:\n");
+        rendered.push_str(context.transcript);
+    }
+    rendered
+}
+
+/// All saved templates, sorted by name.
+#[tauri::command]
+pub fn list_prompt_templates<R: Runtime>(app: AppHandle<R>) -> Vec<PromptTemplate> {
+    let mut templates = load(&app);
+    templates.sort_by_key(|template| template.name.to_lowercase());
+    templates
+}
+
+#[tauri::command]
+pub fn create_prompt_template<R: Runtime>(app: AppHandle<R>, name: String, body: String) -> Result<PromptTemplate, String> {
+    let mut templates = load(&app);
+    let (name, body) = validate(&templates, None, &name, &body)?;
+    let now = Utc::now();
+    let template = PromptTemplate {
+        id: uuid::Uuid::new_v4().to_string(),
+        name,
+        body,
+        created_at: now,
+        updated_at: now,
+    };
+    templates.push(template.clone());
+    save(&app, &templates)?;
+    log_info!("Created prompt template '{}'", template.name);
+    Ok(template)
+}
+
+#[tauri::command]
+pub fn update_prompt_template<R: Runtime>(
+    app: AppHandle<R>,
+    id: String,
+    name: String,
+    body: String,
+) -> Result<PromptTemplate, String> {
+    let mut templates = load(&app);
+    let (name, body) = validate(&templates, Some(&id), &name, &body)?;
+    let template = templates
+        .iter_mut()
+        .find(|template| template.id == id)
+        .ok_or_else(|| format!("Prompt template {} not found", id))?;
+    template.name = name;
+    template.body = body;
+    template.updated_at = Utc::now();
+    let updated = template.clone();
+    save(&app, &templates)?;
+    log_info!("Updated prompt template '{}'", updated.name);
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn delete_prompt_template<R: Runtime>(app: AppHandle<R>, id: String) -> Result<(), String> {
+    let mut templates = load(&app);
+    let count = templates.len();
+    templates.retain(|template| template.id != id);
+    if templates.len() == count {
+        return Err(format!("Prompt template {} not found", id));
+    }
+    save(&app, &templates)?;
+    log_info!("Deleted prompt template {}", id);
+    Ok(())
+}
@@ -0,0 +1,223 @@
+// Tray icon with quick recording controls, doubling as the OS-level indicator shown for as
+// long as audio is being captured. While recording, the icon is a pulsing red dot whose
+// tooltip shows the elapsed time, and on Windows the taskbar button carries the dot as an
+// overlay badge too. It is driven by the recording start/stop transitions, independently
+// of the webview, so it stays accurate even when the window is hidden or the UI is
+// unresponsive.
+//
+// The menu starts and stops recordings and pauses system audio through the same functions
+// as the frontend's commands. A recording stopped from the tray is saved in the app's
+// recordings folder. Every action is reported with a `tray-action` event so the UI can
+// follow along.
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use log::{debug as log_debug, error as log_error, info as log_info, warn as log_warn};
+
+use crate::privacy_pause;
+
+const TRAY_ID: &str = "recording-indicator";
+const ICON_SIZE: u32 = 32;
+const DOT_COLOR: [u8; 3] = [0xe5, 0x3e, 0x3e];
+const IDLE_COLOR: [u8; 3] = [0x8a, 0x8a, 0x8a];
+// Time per frame of the pulse animation
+const PULSE_INTERVAL: Duration = Duration::from_millis(700);
+const DIMMED_ALPHA: f32 = 0.35;
+const IDLE_TOOLTIP: &str = "Meetily";
+
+const MENU_START: &str = "tray-start";
+const MENU_STOP: &str = "tray-stop";
+const MENU_PAUSE: &str = "tray-pause";
+const MENU_SHOW: &str = "tray-show";
+const MENU_QUIT: &str = "tray-quit";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndicatorState {
+    Idle,
+    Recording,
+}
+
+static ANIMATION: Lazy<Mutex<Option<tauri::async_runtime::JoinHandle<()>>>> = Lazy::new(|| Mutex::new(None));
+
+/// A filled, anti-aliased dot on a transparent background.
+fn dot_icon(color: [u8; 3], alpha: f32) -> Image<'static> {
+    let size = ICON_SIZE as usize;
+    let center = size as f32 / 2.0;
+    let radius = center * 0.7;
+    let mut rgba = vec![0u8; size * size * 4];
+    for (i, pixel) in rgba.chunks_exact_mut(4).enumerate() {
+        let (x, y) = ((i % size) as f32 + 0.5, (i / size) as f32 + 0.5);
+        let distance = ((x - center).powi(2) + (y - center).powi(2)).sqrt();
+        let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+        pixel[..3].copy_from_slice(&color);
+        pixel[3] = (coverage * alpha * 255.0).round() as u8;
+    }
+    Image::new_owned(rgba, ICON_SIZE, ICON_SIZE)
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let secs = elapsed.as_secs();
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+fn build_menu<R: Runtime>(app: &AppHandle<R>, recording: bool) -> tauri::Result<Menu<R>> {
+    let pause_label = if privacy_pause::is_paused() { "Resume System Audio" } else { "Pause System Audio" };
+    let start = MenuItem::with_id(app, MENU_START, "Start Recording", !recording, None::<&str>)?;
+    let stop = MenuItem::with_id(app, MENU_STOP, "Stop Recording", recording, None::<&str>)?;
+    let pause = MenuItem::with_id(app, MENU_PAUSE, pause_label, recording, None::<&str>)?;
+    let separator = PredefinedMenuItem::separator(app)?;
+    let show = MenuItem::with_id(app, MENU_SHOW, "Show Meetily", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, MENU_QUIT, "Quit", true, None::<&str>)?;
+    Menu::with_items(app, &[&start, &stop, &pause, &separator, &show, &quit])
+}
+
+fn refresh_menu<R: Runtime>(app: &AppHandle<R>, recording: bool) -> Result<(), String> {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return Ok(());
+    };
+    let menu = build_menu(app, recording).map_err(|e| format!("Failed to build tray menu: {}", e))?;
+    tray.set_menu(Some(menu)).map_err(|e| format!("Failed to set tray menu: {}", e))
+}
+
+fn emit_action<R: Runtime>(app: &AppHandle<R>, action: &str) {
+    if let Err(e) = app.emit("tray-action", action) {
+        log_error!("Failed to emit tray-action event: {}", e);
+    }
+}
+
+fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    match id {
+        MENU_SHOW => {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }
+        MENU_QUIT => app.exit(0),
+        MENU_START | MENU_STOP | MENU_PAUSE => {
+            let app = app.clone();
+            let id = id.to_string();
+            tauri::async_runtime::spawn(async move {
+                let result = match id.as_str() {
+                    MENU_START => crate::start_recording_with_defaults(&app).await,
+                    MENU_STOP => crate::stop_recording_with_defaults(&app).await,
+                    _ => privacy_pause::toggle_privacy_pause(app.clone()).await.map(|_| ()),
+                };
+                match result {
+                    Ok(()) => {
+                        log_info!("Tray action {} done", id);
+                        emit_action(&app, id.trim_start_matches("tray-"));
+                        if id == MENU_PAUSE {
+                            if let Err(e) = refresh_menu(&app, true) {
+                                log_debug!("{}", e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        log_error!("Tray action {} failed: {}", id, e);
+                        if let Err(e) = app.emit("tray-action-failed", e) {
+                            log_error!("Failed to emit tray-action-failed event: {}", e);
+                        }
+                    }
+                }
+            });
+        }
+        _ => {}
+    }
+}
+
+/// Create the tray icon in its idle state. Called once at startup.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let result = build_menu(app, false).and_then(|menu| {
+        TrayIconBuilder::with_id(TRAY_ID)
+            .icon(dot_icon(IDLE_COLOR, 1.0))
+            .icon_as_template(false)
+            .tooltip(IDLE_TOOLTIP)
+            .menu(&menu)
+            .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()))
+            .build(app)
+    });
+    if let Err(e) = result {
+        log_warn!("Failed to create tray icon: {}", e);
+    }
+}
+
+fn draw_frame<R: Runtime>(app: &AppHandle<R>, lit: bool, elapsed: Duration) -> Result<(), String> {
+    let alpha = if lit { 1.0 } else { DIMMED_ALPHA };
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_icon(Some(dot_icon(DOT_COLOR, alpha))).map_err(|e| format!("Failed to set indicator icon: {}", e))?;
+        tray.set_tooltip(Some(format!("Meetily is recording ({})", format_elapsed(elapsed))))
+            .map_err(|e| format!("Failed to set indicator tooltip: {}", e))?;
+    }
+    #[cfg(target_os = "windows")]
+    if let Some(window) = app.get_webview_window("main") {
+        window.set_overlay_icon(Some(dot_icon(DOT_COLOR, alpha))).map_err(|e| format!("Failed to set taskbar badge: {}", e))?;
+    }
+    Ok(())
+}
+
+fn show<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    refresh_menu(app, true)?;
+    draw_frame(app, true, Duration::ZERO)
+}
+
+fn hide<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    refresh_menu(app, false)?;
+    if let Some(tray) = app.tray_by_id(TRAY_ID) {
+        tray.set_icon(Some(dot_icon(IDLE_COLOR, 1.0))).map_err(|e| format!("Failed to set indicator icon: {}", e))?;
+        tray.set_tooltip(Some(IDLE_TOOLTIP)).map_err(|e| format!("Failed to set indicator tooltip: {}", e))?;
+    }
+    #[cfg(target_os = "windows")]
+    if let Some(window) = app.get_webview_window("main") {
+        window.set_overlay_icon(None).map_err(|e| format!("Failed to clear taskbar badge: {}", e))?;
+    }
+    Ok(())
+}
+
+fn stop_animation() {
+    if let Some(task) = ANIMATION.lock().ok().and_then(|mut task| task.take()) {
+        task.abort();
+    }
+}
+
+/// Show or clear the indicator. Failures are logged only; a missing indicator must never
+/// stop a recording from starting or being saved.
+pub fn set<R: Runtime>(app: &AppHandle<R>, state: IndicatorState) {
+    stop_animation();
+    match state {
+        IndicatorState::Recording => {
+            if let Err(e) = show(app) {
+                log_warn!("{}", e);
+                return;
+            }
+            let app = app.clone();
+            let started = Instant::now();
+            let task = tauri::async_runtime::spawn(async move {
+                let mut lit = true;
+                loop {
+                    tokio::time::sleep(PULSE_INTERVAL).await;
+                    lit = !lit;
+                    if let Err(e) = draw_frame(&app, lit, started.elapsed()) {
+                        log_debug!("{}", e);
+                    }
+                }
+            });
+            if let Ok(mut animation) = ANIMATION.lock() {
+                *animation = Some(task);
+            }
+        }
+        IndicatorState::Idle => {
+            if let Err(e) = hide(app) {
+                log_warn!("{}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,51 @@
+// Saved recordings on disk. The app saves them to `recordings/` in the app data directory;
+// versions before that saved them in the app data directory itself, so both are searched.
+// Only files named like the app names its recordings count as recordings, so folders the
+// user picks can hold other audio without it being backed up or deleted by mistake.
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+
+pub(crate) const AUDIO_EXTENSIONS: [&str; 5] = ["wav", "flac", "ogg", "mp3", "m4a"];
+// Saved from the UI, saved by tray or automatic stops, and recovered after a crash
+const RECORDING_PREFIXES: [&str; 3] = ["recording-", "meeting-", "recovered-"];
+
+fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+}
+
+/// Whether `path` is a recording the app saved, judging by its name.
+pub(crate) fn is_app_recording(path: &Path) -> bool {
+    is_audio(path)
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| RECORDING_PREFIXES.iter().any(|prefix| name.starts_with(prefix)))
+}
+
+/// Audio files directly inside `dir`.
+pub(crate) fn audio_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file() && is_audio(path)).collect()
+}
+
+/// Folders the app has saved recordings to, current one first.
+pub(crate) fn recording_dirs<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| vec![dir.join("recordings"), dir])
+        .unwrap_or_default()
+}
+
+/// Recordings the app saved in `dir`.
+pub(crate) fn recordings_in(dir: &Path) -> Vec<PathBuf> {
+    audio_files(dir).into_iter().filter(|path| is_app_recording(path)).collect()
+}
+
+/// All recordings the app saved in its own folders.
+pub(crate) fn app_recordings<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    recording_dirs(app).iter().flat_map(|dir| recordings_in(dir)).collect()
+}
@@ -0,0 +1,261 @@
+// Retention policy: old data is deleted automatically, e.g. "audio after 30 days,
+// transcripts after a year". Each kind of data has its own age limit and no limit means
+// it is kept forever, which is the default. The policy is enforced at startup and then once
+// a day; `preview_retention` lists what the next run would delete without deleting it.
+//
+// Ages are measured from when a meeting was created and, for audio files, when the file
+// was last modified. Only recordings the app saved itself are deleted, never other audio
+// that happens to be in the same folder. Deleting transcripts keeps the meeting and its summary; deleting
+// meetings removes them from the local store entirely.
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::activity::{self, ActivityKind};
+use crate::recordings;
+use crate::storage::with_connection;
+
+const RETENTION_STORE_KEY: &str = "retentionPolicy";
+const RUN_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+const MAX_DAYS: u32 = 36_500;
+// Sidecar the playback index writes next to a recording
+const PLAYBACK_INDEX_EXTENSION: &str = "index.json";
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Delete recordings older than this many days
+    pub audio_days: Option<u32>,
+    /// Delete the transcript of meetings older than this many days, keeping the summary
+    pub transcript_days: Option<u32>,
+    /// Delete meetings older than this many days with everything in them
+    pub meeting_days: Option<u32>,
+    /// Folder recordings are saved to; the app's own recordings folders are always included
+    pub audio_dir: Option<String>,
+}
+
+impl RetentionPolicy {
+    fn validate(&self) -> Result<(), String> {
+        for days in [self.audio_days, self.transcript_days, self.meeting_days].into_iter().flatten() {
+            if days == 0 || days > MAX_DAYS {
+                return Err(format!("Retention periods must be between 1 and {} days", MAX_DAYS));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeAudio {
+    pub path: String,
+    pub modified_at: DateTime<Utc>,
+    pub size_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeMeeting {
+    pub id: String,
+    pub title: String,
+    pub created_at: String,
+}
+
+/// What a retention run deletes (or, for a preview, would delete).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RetentionPlan {
+    pub audio_files: Vec<PurgeAudio>,
+    /// Meetings whose transcript is deleted
+    pub transcripts: Vec<PurgeMeeting>,
+    /// Meetings deleted entirely
+    pub meetings: Vec<PurgeMeeting>,
+    pub audio_bytes: u64,
+}
+
+impl RetentionPlan {
+    fn is_empty(&self) -> bool {
+        self.audio_files.is_empty() && self.transcripts.is_empty() && self.meetings.is_empty()
+    }
+}
+
+pub fn policy<R: Runtime>(app: &AppHandle<R>) -> RetentionPolicy {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(RETENTION_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn cutoff(days: u32) -> DateTime<Utc> {
+    Utc::now() - chrono::Duration::days(i64::from(days))
+}
+
+fn audio_dirs<R: Runtime>(app: &AppHandle<R>, policy: &RetentionPolicy) -> Vec<PathBuf> {
+    let mut dirs = recordings::recording_dirs(app);
+    if let Some(dir) = policy.audio_dir.as_ref().map(PathBuf::from).filter(|dir| !dirs.contains(dir)) {
+        dirs.push(dir);
+    }
+    dirs
+}
+
+fn expired_audio(dir: &Path, before: SystemTime) -> Vec<PurgeAudio> {
+    recordings::recordings_in(dir)
+        .into_iter()
+        .filter_map(|path| {
+            let metadata = std::fs::metadata(&path).ok()?;
+            let modified = metadata.modified().ok().filter(|modified| *modified < before)?;
+            Some(PurgeAudio {
+                path: path.display().to_string(),
+                modified_at: modified.into(),
+                size_bytes: metadata.len(),
+            })
+        })
+        .collect()
+}
+
+fn expired_meetings<R: Runtime>(
+    app: &AppHandle<R>,
+    days: u32,
+    with_transcript_only: bool,
+) -> Result<Vec<PurgeMeeting>, String> {
+    let condition = if with_transcript_only { "AND EXISTS (SELECT 1 FROM transcripts t WHERE t.meeting_id = m.id)" } else { "" };
+    with_connection(app, "find expired meetings", |conn| {
+        let mut meetings = conn.prepare(&format!(
+            "SELECT m.id, m.title, m.created_at FROM meetings m
+             WHERE julianday(m.created_at) < julianday(?1) {} ORDER BY m.created_at",
+            condition
+        ))?;
+        let rows = meetings.query_map([cutoff(days).to_rfc3339()], |row| {
+            Ok(PurgeMeeting { id: row.get(0)?, title: row.get(1)?, created_at: row.get(2)? })
+        })?;
+        rows.collect()
+    })
+}
+
+/// Everything the policy currently says should be deleted.
+fn plan<R: Runtime>(app: &AppHandle<R>, policy: &RetentionPolicy) -> Result<RetentionPlan, String> {
+    let mut plan = RetentionPlan::default();
+    if let Some(days) = policy.audio_days {
+        let before = SystemTime::from(cutoff(days));
+        for dir in audio_dirs(app, policy) {
+            plan.audio_files.extend(expired_audio(&dir, before));
+        }
+        plan.audio_bytes = plan.audio_files.iter().map(|file| file.size_bytes).sum();
+    }
+    if let Some(days) = policy.meeting_days {
+        plan.meetings = expired_meetings(app, days, false)?;
+    }
+    if let Some(days) = policy.transcript_days {
+        plan.transcripts = expired_meetings(app, days, true)?;
+        // Meetings that are deleted anyway don't need their transcript deleted first
+        plan.transcripts.retain(|meeting| !plan.meetings.iter().any(|deleted| deleted.id == meeting.id));
+    }
+    Ok(plan)
+}
+
+fn purge<R: Runtime>(app: &AppHandle<R>, plan: &RetentionPlan) -> Result<(), String> {
+    for file in &plan.audio_files {
+        let path = Path::new(&file.path);
+        match std::fs::remove_file(path) {
+            Ok(()) => {
+                let _ = std::fs::remove_file(path.with_extension(PLAYBACK_INDEX_EXTENSION));
+            }
+            Err(e) => log_warn!("Failed to delete expired recording {}: {}", file.path, e),
+        }
+    }
+    with_connection(app, "delete expired data", |conn| {
+        let tx = conn.transaction()?;
+        for meeting in &plan.transcripts {
+            tx.execute("DELETE FROM transcripts WHERE meeting_id = ?1", params![meeting.id])?;
+        }
+        for meeting in &plan.meetings {
+            tx.execute("DELETE FROM meetings WHERE id = ?1", params![meeting.id])?;
+        }
+        tx.commit()
+    })
+}
+
+/// Apply the saved policy now and return what was deleted.
+pub async fn enforce<R: Runtime>(app: &AppHandle<R>) -> Result<RetentionPlan, String> {
+    let mut policy = policy(app);
+    // The recordings folder holds the recording in progress
+    if crate::is_recording() && policy.audio_days.is_some() {
+        log_info!("Recording in progress, leaving audio retention for the next run");
+        policy.audio_days = None;
+    }
+    let plan = plan(app, &policy)?;
+    if plan.is_empty() {
+        return Ok(plan);
+    }
+    purge(app, &plan)?;
+    let message = format!(
+        "Retention policy deleted {} recordings, {} transcripts and {} meetings",
+        plan.audio_files.len(),
+        plan.transcripts.len(),
+        plan.meetings.len()
+    );
+    log_info!("{}", message);
+    activity::record(app, ActivityKind::DataPurged, &message, None, None);
+    if let Err(e) = app.emit("retention-purged", &plan) {
+        log_error!("Failed to emit retention-purged event: {}", e);
+    }
+    Ok(plan)
+}
+
+/// Enforce the policy now and then once a day. Called once at startup.
+pub fn start<R: Runtime>(app: &AppHandle<R>) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            if let Err(e) = enforce(&app).await {
+                log_error!("Retention run failed: {}", e);
+            }
+            tokio::time::sleep(RUN_INTERVAL).await;
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_retention_policy<R: Runtime>(app: AppHandle<R>) -> Result<RetentionPolicy, String> {
+    Ok(policy(&app))
+}
+
+/// Save the retention policy. It is enforced at the next daily run; `run_retention_now`
+/// applies it right away.
+#[tauri::command]
+pub async fn set_retention_policy<R: Runtime>(app: AppHandle<R>, policy: RetentionPolicy) -> Result<(), String> {
+    policy.validate()?;
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(RETENTION_STORE_KEY, serde_json::to_value(&policy).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Retention policy set to {:?}", policy);
+    Ok(())
+}
+
+/// What the saved policy (or `policy`, to try one before saving it) would delete now.
+/// Nothing is deleted.
+#[tauri::command]
+pub async fn preview_retention<R: Runtime>(app: AppHandle<R>, policy: Option<RetentionPolicy>) -> Result<RetentionPlan, String> {
+    let policy = match policy {
+        Some(policy) => {
+            policy.validate()?;
+            policy
+        }
+        None => self::policy(&app),
+    };
+    plan(&app, &policy)
+}
+
+#[tauri::command]
+pub async fn run_retention_now<R: Runtime>(app: AppHandle<R>) -> Result<RetentionPlan, String> {
+    enforce(&app).await
+}
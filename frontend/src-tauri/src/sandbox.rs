@@ -0,0 +1,22 @@
+// User-configured programs, such as export hooks and recording plugins, run as child
+// processes that don't inherit the app's environment, which can hold tokens and keys. Only
+// the variables a program needs to find its tools and home directory are passed through.
+// Programs are executed directly, never through a shell, and killed when the app lets go
+// of them.
+use std::ffi::OsStr;
+use tokio::process::Command;
+
+const PASSTHROUGH_ENV: [&str; 5] = ["PATH", "HOME", "USERPROFILE", "SYSTEMROOT", "TEMP"];
+
+/// A command for `program` with only the passed-through environment, killed when dropped.
+/// Arguments, stdio and the working directory are up to the caller.
+pub(crate) fn command(program: impl AsRef<OsStr>) -> Command {
+    let mut command = Command::new(program);
+    command.env_clear().kill_on_drop(true);
+    for key in PASSTHROUGH_ENV {
+        if let Ok(value) = std::env::var(key) {
+            command.env(key, value);
+        }
+    }
+    command
+}
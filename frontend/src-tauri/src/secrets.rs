@@ -0,0 +1,109 @@
+// Provider API keys (OpenAI, Anthropic, Groq, Deepgram, ...) in the OS keychain. The app
+// reads keys from here rather than from the backend's database and sends them along with the
+// requests that need them. A key that only the backend has yet, saved by an older version, is
+// moved into the keychain the first time it is read and then deleted from the backend.
+// Summary and transcription keys are kept apart because the same provider can have both.
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, warn as log_warn};
+
+use crate::api::{make_api_request, GetApiKeyRequest};
+use crate::keychain;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyKind {
+    /// Key for the summarization model
+    Summary,
+    /// Key for the transcription service
+    Transcript,
+}
+
+impl KeyKind {
+    // Backend endpoint holding keys saved before they moved to the keychain
+    fn legacy_endpoint(self) -> &'static str {
+        match self {
+            KeyKind::Summary => "/get-api-key",
+            KeyKind::Transcript => "/get-transcript-api-key",
+        }
+    }
+
+    fn legacy_delete_endpoint(self) -> &'static str {
+        match self {
+            KeyKind::Summary => "/delete-api-key",
+            KeyKind::Transcript => "/delete-transcript-api-key",
+        }
+    }
+}
+
+fn account(kind: KeyKind, provider: &str) -> Result<String, String> {
+    let provider = provider.trim().to_lowercase();
+    if provider.is_empty() || !provider.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(format!("Invalid provider name '{}'", provider));
+    }
+    Ok(match kind {
+        KeyKind::Summary => format!("api-key-{}", provider),
+        KeyKind::Transcript => format!("transcript-api-key-{}", provider),
+    })
+}
+
+/// Save a provider's key; an empty key removes it.
+pub(crate) fn store_api_key(kind: KeyKind, provider: &str, key: &str) -> Result<(), String> {
+    let account = account(kind, provider)?;
+    match key.trim() {
+        "" => keychain::delete_secret(&account),
+        key => keychain::set_secret(&account, key),
+    }
+}
+
+/// A provider's key from the keychain, falling back to (and migrating) one the backend still
+/// holds. None if there is no key anywhere.
+pub(crate) async fn api_key<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: KeyKind,
+    provider: &str,
+    auth_token: Option<String>,
+) -> Result<Option<String>, String> {
+    let account = account(kind, provider)?;
+    if let Some(key) = keychain::get_secret(&account)? {
+        return Ok(Some(key));
+    }
+
+    let body = serde_json::to_string(&GetApiKeyRequest { provider: provider.to_string() }).map_err(|e| e.to_string())?;
+    let legacy = match make_api_request::<R, Option<String>>(app, kind.legacy_endpoint(), "POST", Some(&body), None, auth_token.clone()).await {
+        Ok(key) => key.filter(|key| !key.trim().is_empty()),
+        Err(e) => {
+            log_warn!("Could not check the backend for a {} key: {}", provider, e);
+            None
+        }
+    };
+    if let Some(key) = &legacy {
+        keychain::set_secret(&account, key)?;
+        log_info!("Moved the {:?} key for {} into the keychain", kind, provider);
+        // The keychain copy is what counts now; a failure here only leaves the old copy behind
+        let delete = make_api_request::<R, serde_json::Value>(app, kind.legacy_delete_endpoint(), "POST", Some(&body), None, auth_token);
+        if let Err(e) = delete.await {
+            log_warn!("Could not delete the {} key from the backend: {}", provider, e);
+        }
+    }
+    Ok(legacy)
+}
+
+/// The saved key for `provider`, if any.
+#[tauri::command]
+pub async fn get_secret<R: Runtime>(
+    app: AppHandle<R>,
+    provider: String,
+    kind: KeyKind,
+    auth_token: Option<String>,
+) -> Result<Option<String>, String> {
+    api_key(&app, kind, &provider, auth_token).await
+}
+
+/// Save the key for `provider`; an empty key removes it.
+#[tauri::command]
+pub async fn set_secret(provider: String, kind: KeyKind, value: String) -> Result<(), String> {
+    store_api_key(kind, &provider, &value)?;
+    log_info!("{:?} key for {} {}", kind, provider, if value.trim().is_empty() { "removed" } else { "saved" });
+    Ok(())
+}
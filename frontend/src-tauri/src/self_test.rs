@@ -0,0 +1,322 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use tokio::process::Command;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::audio::decode_audio_file;
+use crate::telemetry::{self, Dependency};
+use crate::{
+    is_recording, load_transcription_config, resample_audio, send_audio_chunk, transcript_server_url, CaptureSource,
+    TranscriptAccumulator, CHUNK_DURATION_MS, SENTENCE_TIMEOUT_MS, WHISPER_SAMPLE_RATE,
+};
+
+const SELF_TEST_PHRASE: &str = "The quick brown fox jumps over the lazy dog. Meeting minutes are ready.";
+const MIN_WORD_OVERLAP: f32 = 0.5; // Fraction of phrase words that must come back from transcription
+const SILENCE_RMS: f32 = 0.001;
+const TONE_DURATION_SECS: f32 = 3.0;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum StageStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct StageResult {
+    pub stage: String,
+    pub status: StageStatus,
+    pub duration_ms: u64,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct SelfTestReport {
+    pub passed: bool,
+    pub used_speech: bool,
+    pub transcript: String,
+    pub stages: Vec<StageResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OllamaGenerateResponse {
+    response: String,
+}
+
+struct StageTimer {
+    name: &'static str,
+    started: Instant,
+}
+
+impl StageTimer {
+    fn start(name: &'static str) -> Self {
+        log_info!("Self-test stage started: {}", name);
+        Self { name, started: Instant::now() }
+    }
+
+    fn finish(self, status: StageStatus, detail: impl Into<String>) -> StageResult {
+        let detail = detail.into();
+        match status {
+            StageStatus::Failed => log_error!("Self-test stage {} failed: {}", self.name, detail),
+            _ => log_info!("Self-test stage {} {:?}: {}", self.name, status, detail),
+        }
+        StageResult {
+            stage: self.name.to_string(),
+            status,
+            duration_ms: self.started.elapsed().as_millis() as u64,
+            detail,
+        }
+    }
+}
+
+fn skipped(name: &str, reason: &str) -> StageResult {
+    StageResult {
+        stage: name.to_string(),
+        status: StageStatus::Skipped,
+        duration_ms: 0,
+        detail: reason.to_string(),
+    }
+}
+
+// Render the test phrase with the platform's built-in speech synthesizer
+async fn synthesize_speech(path: &Path) -> Result<(), String> {
+    let path_str = path.to_string_lossy().to_string();
+
+    #[cfg(target_os = "macos")]
+    let output = Command::new("say")
+        .args(["--file-format=WAVE", "--data-format=LEI16@16000", "-o", &path_str, SELF_TEST_PHRASE])
+        .output()
+        .await;
+
+    #[cfg(target_os = "windows")]
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            &format!(
+                "Add-Type -AssemblyName System.Speech; \
+                 $s = New-Object System.Speech.Synthesis.SpeechSynthesizer; \
+                 $s.SetOutputToWaveFile('{}'); $s.Speak('{}'); $s.Dispose()",
+                path_str.replace('\'', "''"),
+                SELF_TEST_PHRASE
+            ),
+        ])
+        .output()
+        .await;
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let output = match Command::new("espeak-ng").args(["-w", &path_str, SELF_TEST_PHRASE]).output().await {
+        Ok(output) => Ok(output),
+        Err(_) => Command::new("espeak").args(["-w", &path_str, SELF_TEST_PHRASE]).output().await,
+    };
+
+    let output = output.map_err(|e| format!("Speech synthesizer not available: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+// Amplitude-modulated tone used when no speech synthesizer is installed. It cannot
+// check transcription quality, only that audio flows through every stage.
+fn synthetic_tone() -> Vec<f32> {
+    let total = (WHISPER_SAMPLE_RATE as f32 * TONE_DURATION_SECS) as usize;
+    (0..total)
+        .map(|i| {
+            let t = i as f32 / WHISPER_SAMPLE_RATE as f32;
+            let envelope = 0.5 + 0.5 * (2.0 * std::f32::consts::PI * 4.0 * t).sin();
+            0.3 * envelope * (2.0 * std::f32::consts::PI * 220.0 * t).sin()
+        })
+        .collect()
+}
+
+async fn generate_sample(path: &PathBuf) -> Result<(Vec<f32>, bool, String), String> {
+    match synthesize_speech(path).await {
+        Ok(()) => {
+            let (samples, sample_rate) = decode_audio_file(path).map_err(|e| format!("Failed to decode synthesized speech: {}", e))?;
+            let _ = std::fs::remove_file(path);
+            let samples = if sample_rate != WHISPER_SAMPLE_RATE {
+                resample_audio(&samples, sample_rate, WHISPER_SAMPLE_RATE)
+            } else {
+                samples
+            };
+            let detail = format!("Synthesized {:.1}s of speech", samples.len() as f32 / WHISPER_SAMPLE_RATE as f32);
+            Ok((samples, true, detail))
+        }
+        Err(e) => {
+            log_warn!("Falling back to synthetic tone for self-test: {}", e);
+            Ok((synthetic_tone(), false, format!("No speech synthesizer ({}); using a synthetic tone", e)))
+        }
+    }
+}
+
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+fn word_overlap(expected: &str, actual: &str) -> f32 {
+    let expected = normalize_words(expected);
+    let actual = normalize_words(actual);
+    if expected.is_empty() {
+        return 0.0;
+    }
+    let found = expected.iter().filter(|w| actual.contains(w)).count();
+    found as f32 / expected.len() as f32
+}
+
+async fn summarize_with_ollama(model: &str, transcript: &str) -> Result<String, String> {
+    let client = reqwest::Client::new();
+    let body = serde_json::json!({
+        "model": model,
+        "prompt": format!("Summarize the following transcript in one sentence:\n\n{}", transcript),
+        "stream": false,
+    });
+
+    let started = Instant::now();
+    let result = async {
+        let response = client
+            .post(format!("{}/api/generate", crate::ollama::base_url()))
+            .json(&body)
+            .timeout(Duration::from_secs(120))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Ollama returned status {}", response.status()));
+        }
+        response
+            .json::<OllamaGenerateResponse>()
+            .await
+            .map(|r| r.response)
+            .map_err(|e| format!("Failed to parse Ollama response: {}", e))
+    }
+    .await;
+    telemetry::record(Dependency::Ollama, started.elapsed(), result.as_ref().err().map(|e| e.as_str()));
+    result
+}
+
+/// Runs a short synthetic sample through chunking, transcription, accumulation and
+/// (when `summary_model` is given) summarization, reporting the outcome of each stage.
+#[tauri::command]
+pub async fn run_self_test<R: Runtime>(app: AppHandle<R>, summary_model: Option<String>) -> Result<SelfTestReport, String> {
+    log_info!("run_self_test called, summary_model: {:?}", summary_model);
+
+    if is_recording() {
+        return Err("Cannot run the self-test while recording".to_string());
+    }
+
+    let mut stages = Vec::new();
+
+    // 1. Sample generation (stands in for capture)
+    let timer = StageTimer::start("generate_audio");
+    let sample_path = std::env::temp_dir().join("meetily-self-test.wav");
+    let (samples, used_speech) = match generate_sample(&sample_path).await {
+        Ok((samples, used_speech, detail)) => {
+            stages.push(timer.finish(StageStatus::Passed, detail));
+            (samples, used_speech)
+        }
+        Err(e) => {
+            stages.push(timer.finish(StageStatus::Failed, e));
+            for name in ["chunking", "transcription", "accumulation", "summarization"] {
+                stages.push(skipped(name, "No audio sample available"));
+            }
+            return Ok(SelfTestReport { passed: false, used_speech: false, transcript: String::new(), stages });
+        }
+    };
+
+    // 2. Chunking, using the same chunk size as live recordings
+    let timer = StageTimer::start("chunking");
+    let chunk_samples = (WHISPER_SAMPLE_RATE as f32 * (CHUNK_DURATION_MS as f32 / 1000.0)) as usize;
+    let chunks: Vec<Vec<f32>> = samples.chunks(chunk_samples).map(|c| c.to_vec()).collect();
+    let total: usize = chunks.iter().map(|c| c.len()).sum();
+    let rms = (samples.iter().map(|s| s * s).sum::<f32>() / samples.len().max(1) as f32).sqrt();
+    if chunks.is_empty() || total != samples.len() {
+        stages.push(timer.finish(StageStatus::Failed, "Chunking lost samples"));
+    } else if rms < SILENCE_RMS {
+        stages.push(timer.finish(StageStatus::Failed, format!("Sample is silent (RMS {:.5})", rms)));
+    } else {
+        stages.push(timer.finish(StageStatus::Passed, format!("{} chunk(s), RMS {:.3}", chunks.len(), rms)));
+    }
+
+    // 3. Transcription against the running whisper server
+    let timer = StageTimer::start("transcription");
+    let client = reqwest::Client::new();
+    let stream_url = format!("{}/stream", transcript_server_url(&app));
+    // Same retry and language settings as a real recording
+    let config = load_transcription_config(&app);
+    let mut segments = Vec::new();
+    let mut transcription_error = None;
+    for chunk in chunks {
+        match send_audio_chunk(Arc::new(chunk), &client, &stream_url, &config).await {
+            Ok(response) => segments.extend(response.segments),
+            Err(e) => {
+                transcription_error = Some(e);
+                break;
+            }
+        }
+    }
+    let raw_text = segments.iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" ");
+    match &transcription_error {
+        Some(e) => stages.push(timer.finish(StageStatus::Failed, e.clone())),
+        None if used_speech => {
+            let overlap = word_overlap(SELF_TEST_PHRASE, &raw_text);
+            let status = if overlap >= MIN_WORD_OVERLAP { StageStatus::Passed } else { StageStatus::Failed };
+            stages.push(timer.finish(status, format!("{} segment(s), {:.0}% of expected words recognized", segments.len(), overlap * 100.0)));
+        }
+        None => stages.push(timer.finish(StageStatus::Passed, format!("Server responded with {} segment(s)", segments.len()))),
+    }
+
+    // 4. Accumulation into sentences, as the transcription workers do
+    let mut transcript = String::new();
+    if transcription_error.is_some() {
+        stages.push(skipped("accumulation", "Transcription failed"));
+    } else {
+        let timer = StageTimer::start("accumulation");
+        let mut accumulator = TranscriptAccumulator::new(CaptureSource::Mic, Arc::new(Mutex::new(VecDeque::new())));
+        let mut sentences = Vec::new();
+        for segment in &segments {
+            if let Some(update) = accumulator.add_segment(segment) {
+                sentences.push(update.text);
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(SENTENCE_TIMEOUT_MS + 100)).await;
+        if let Some(update) = accumulator.check_timeout() {
+            sentences.push(update.text);
+        }
+        transcript = sentences.join(" ");
+
+        if transcript.is_empty() && used_speech {
+            stages.push(timer.finish(StageStatus::Failed, "No sentences produced from transcribed segments"));
+        } else {
+            stages.push(timer.finish(StageStatus::Passed, format!("{} sentence(s)", sentences.len())));
+        }
+    }
+
+    // 5. Optional summarization
+    match summary_model.filter(|m| !m.trim().is_empty()) {
+        None => stages.push(skipped("summarization", "No summary model selected")),
+        Some(_) if transcript.is_empty() => stages.push(skipped("summarization", "No transcript to summarize")),
+        Some(model) => {
+            let timer = StageTimer::start("summarization");
+            match summarize_with_ollama(&model, &transcript).await {
+                Ok(summary) if !summary.trim().is_empty() => {
+                    stages.push(timer.finish(StageStatus::Passed, format!("{} produced a {} character summary", model, summary.trim().len())))
+                }
+                Ok(_) => stages.push(timer.finish(StageStatus::Failed, format!("{} returned an empty summary", model))),
+                Err(e) => stages.push(timer.finish(StageStatus::Failed, e)),
+            }
+        }
+    }
+
+    let passed = stages.iter().all(|s| s.status != StageStatus::Failed);
+    log_info!("Self-test finished, passed: {}", passed);
+    Ok(SelfTestReport { passed, used_speech, transcript, stages })
+}
@@ -0,0 +1,189 @@
+// What the transcription server supports, asked for with GET /capabilities before a
+// recording starts so the pipeline is configured to match instead of assuming it. Servers
+// that predate the endpoint get the old assumptions: raw f32 chunks to /stream, no
+// streaming endpoint and no translation.
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use log::{info as log_info, warn as log_warn};
+
+use crate::telemetry::{self, Dependency};
+use crate::{TranscriptionConfig, AUTO_LANGUAGE, TRANSCRIPT_SERVER_CHECK_TIMEOUT, WHISPER_SAMPLE_RATE};
+
+/// Encoding of the raw samples uploaded to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SampleFormat {
+    #[default]
+    #[serde(rename = "f32le")]
+    F32,
+    #[serde(rename = "s16le")]
+    S16,
+}
+
+impl SampleFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SampleFormat::F32 => "f32le",
+            SampleFormat::S16 => "s16le",
+        }
+    }
+
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            SampleFormat::F32 => std::mem::size_of::<f32>(),
+            SampleFormat::S16 => std::mem::size_of::<i16>(),
+        }
+    }
+
+    /// Little-endian bytes of `samples`, clamped to [-1, 1].
+    pub fn encode(self, samples: &[f32]) -> Vec<u8> {
+        let clamped = samples.iter().map(|sample| sample.clamp(-1.0, 1.0));
+        match self {
+            SampleFormat::F32 => clamped.flat_map(f32::to_le_bytes).collect(),
+            SampleFormat::S16 => clamped.flat_map(|sample| ((sample * i16::MAX as f32) as i16).to_le_bytes()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServerCapabilities {
+    pub sample_rate: u32,
+    /// Formats as named by the server; unknown ones are kept so they show up in diagnostics
+    pub sample_formats: Vec<String>,
+    /// Language codes the loaded model transcribes; empty when the server does not say
+    pub languages: Vec<String>,
+    pub language_detection: bool,
+    pub translation: bool,
+    /// Whether /stream/frame is available
+    pub streaming: bool,
+    /// Whether the server labels speakers itself when asked to
+    pub diarization: bool,
+    /// Whether segments carry per-word timings and confidence
+    pub word_timestamps: bool,
+}
+
+impl Default for ServerCapabilities {
+    fn default() -> Self {
+        Self {
+            sample_rate: WHISPER_SAMPLE_RATE,
+            sample_formats: vec![SampleFormat::F32.as_str().to_string()],
+            languages: Vec::new(),
+            language_detection: true,
+            translation: false,
+            streaming: false,
+            diarization: false,
+            word_timestamps: false,
+        }
+    }
+}
+
+impl ServerCapabilities {
+    fn supports_format(&self, format: SampleFormat) -> bool {
+        self.sample_formats.iter().any(|f| f == format.as_str())
+    }
+
+    /// Adjust the transcription settings to what the server can do. Anything the server
+    /// lacks is turned off with a warning rather than failing the recording.
+    pub(crate) fn apply(&self, config: &mut TranscriptionConfig) {
+        if self.sample_rate != WHISPER_SAMPLE_RATE {
+            log_warn!(
+                "Transcription server expects {} Hz audio but chunks are sent at {} Hz",
+                self.sample_rate, WHISPER_SAMPLE_RATE
+            );
+        }
+
+        // 16-bit samples halve the upload and are plenty for speech
+        config.sample_format = if self.supports_format(SampleFormat::S16) {
+            SampleFormat::S16
+        } else {
+            if !self.supports_format(SampleFormat::F32) {
+                log_warn!("Transcription server lists no known sample format ({:?}), sending f32", self.sample_formats);
+            }
+            SampleFormat::F32
+        };
+
+        if config.streaming && !self.streaming {
+            log_warn!("Transcription server has no streaming endpoint, sending chunks instead");
+            config.streaming = false;
+        }
+
+        if let Some(language) = config.language.clone() {
+            let supported = if language == AUTO_LANGUAGE {
+                self.language_detection
+            } else {
+                self.languages.is_empty() || self.languages.contains(&language)
+            };
+            if !supported {
+                log_warn!("Transcription server does not support language '{}', using its default", language);
+                config.language = None;
+            }
+        }
+
+        if config.translate && !self.translation {
+            log_warn!("Transcription server cannot translate with the loaded model; translation is off");
+            config.translate = false;
+        }
+
+        config.server_diarization = self.diarization;
+    }
+}
+
+// Last answer, with the server it came from
+static LAST_CAPABILITIES: Lazy<Mutex<Option<(String, ServerCapabilities)>>> = Lazy::new(|| Mutex::new(None));
+
+/// Ask the server what it supports. Fails only if the server cannot be reached; a server
+/// without the endpoint or with an unreadable answer gets the legacy defaults.
+pub async fn negotiate(client: &reqwest::Client, server_url: &str) -> Result<ServerCapabilities, String> {
+    let started = std::time::Instant::now();
+    let response = match client
+        .get(format!("{}/capabilities", server_url))
+        .timeout(TRANSCRIPT_SERVER_CHECK_TIMEOUT)
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            let error = format!("Transcript server at {} is not reachable: {}", server_url, e);
+            telemetry::record(Dependency::WhisperServer, started.elapsed(), Some(&error));
+            return Err(error);
+        }
+    };
+    telemetry::record(Dependency::WhisperServer, started.elapsed(), None);
+
+    let capabilities = if response.status().is_success() {
+        response.json::<ServerCapabilities>().await.unwrap_or_else(|e| {
+            log_warn!("Failed to parse transcription server capabilities: {}", e);
+            ServerCapabilities::default()
+        })
+    } else {
+        log_info!("Transcription server has no capabilities endpoint ({}), assuming defaults", response.status());
+        ServerCapabilities::default()
+    };
+    log_info!("Transcription server capabilities: {:?}", capabilities);
+
+    if let Ok(mut last) = LAST_CAPABILITIES.lock() {
+        *last = Some((server_url.to_string(), capabilities.clone()));
+    }
+    Ok(capabilities)
+}
+
+/// Capabilities of the configured transcription server, e.g. to offer only the languages it supports.
+#[tauri::command]
+pub async fn get_transcription_server_capabilities<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    refresh: Option<bool>,
+) -> Result<ServerCapabilities, String> {
+    let server_url = crate::transcript_server_url(&app);
+    if !refresh.unwrap_or(false) {
+        let cached = LAST_CAPABILITIES
+            .lock()
+            .ok()
+            .and_then(|last| last.clone())
+            .filter(|(url, _)| *url == server_url);
+        if let Some((_, capabilities)) = cached {
+            return Ok(capabilities);
+        }
+    }
+    negotiate(&reqwest::Client::new(), &server_url).await
+}
@@ -0,0 +1,324 @@
+// Crash recovery for recordings. While recording, a manifest describing the session is
+// rewritten whenever the recording file is flushed: the transcript so far, the parts of
+// the audio still waiting to be transcribed and where spilled chunks went. If the app
+// dies mid-recording, the next launch finds the manifest and `recover_last_session`
+// rebuilds the transcript and transcribes the audio that never made it.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::atomic_file;
+use crate::audio::decode_audio_file;
+use crate::chunk_spill::{self, Retranscriber};
+use crate::meeting_metadata;
+use crate::{is_recording, recordings_dir, resample_audio, CaptureSource, TranscriptUpdate, WHISPER_SAMPLE_RATE};
+
+const MANIFEST_FILE: &str = "session.json";
+// Longest stretch of unprocessed audio sent for transcription at once
+const RECOVERY_CHUNK_SECS: u64 = 30;
+// Label of lines transcribed from the mixed recording, where mic and system can't be told apart
+const RECOVERED_LABEL: &str = "Recovered";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SessionManifest {
+    session_id: String,
+    started_at: DateTime<Utc>,
+    updated_at: DateTime<Utc>,
+    /// The in-progress recording file
+    recording_path: PathBuf,
+    transcript: Vec<TranscriptUpdate>,
+    /// Ranges of the recording queued for transcription but not transcribed yet, by chunk id,
+    /// in 16kHz samples from the start whatever rate the recording is saved at
+    pending_ranges: BTreeMap<u64, (u64, u64)>,
+    /// 16kHz samples of the recording already cut into chunks
+    chunked_until: u64,
+    spilled_chunks: Vec<PathBuf>,
+    metadata: Option<Value>,
+}
+
+struct ActiveSession {
+    manifest: SessionManifest,
+    path: PathBuf,
+    dirty: bool,
+}
+
+static ACTIVE: Lazy<Mutex<Option<ActiveSession>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnfinishedSession {
+    pub session_id: String,
+    pub started_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub recording_path: String,
+    pub recorded_secs: f64,
+    pub transcript_lines: usize,
+    /// Chunks known to need transcription; audio past the last chunk comes on top
+    pub pending_chunks: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct RecoveredSession {
+    session_id: String,
+    started_at: DateTime<Utc>,
+    recording_path: Option<String>,
+    transcript: Vec<TranscriptUpdate>,
+    metadata: Option<Value>,
+    /// Stretches of audio that could not be transcribed
+    failed_ranges: usize,
+}
+
+fn write_manifest(path: &Path, manifest: &SessionManifest) -> Result<(), String> {
+    let data = serde_json::to_vec(manifest).map_err(|e| e.to_string())?;
+    atomic_file::write(path, data).map_err(|e| format!("Failed to write session manifest: {}", e))
+}
+
+fn load_manifest<R: Runtime>(app: &AppHandle<R>) -> Result<Option<(PathBuf, SessionManifest)>, String> {
+    let path = recordings_dir(app)?.join(MANIFEST_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let data = atomic_file::read_verified(&path).map_err(|e| format!("Failed to read session manifest: {}", e))?;
+    match serde_json::from_slice(&data) {
+        Ok(manifest) => Ok(Some((path, manifest))),
+        Err(e) => {
+            log_warn!("Ignoring unreadable session manifest: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Start tracking a new recording that is being written to `recording_path`.
+pub fn begin<R: Runtime>(app: &AppHandle<R>, recording_path: &Path) -> Result<(), String> {
+    let path = recordings_dir(app)?.join(MANIFEST_FILE);
+    if path.exists() {
+        log_warn!("Replacing the manifest of an unrecovered session");
+    }
+    let now = Utc::now();
+    let manifest = SessionManifest {
+        session_id: uuid::Uuid::new_v4().to_string(),
+        started_at: now,
+        updated_at: now,
+        recording_path: recording_path.to_path_buf(),
+        transcript: Vec::new(),
+        pending_ranges: BTreeMap::new(),
+        chunked_until: 0,
+        spilled_chunks: Vec::new(),
+        metadata: None,
+    };
+    write_manifest(&path, &manifest)?;
+    *ACTIVE.lock().map_err(|e| e.to_string())? = Some(ActiveSession { manifest, path, dirty: false });
+    Ok(())
+}
+
+fn update(f: impl FnOnce(&mut SessionManifest)) {
+    if let Ok(mut active) = ACTIVE.lock() {
+        if let Some(session) = active.as_mut() {
+            f(&mut session.manifest);
+            session.dirty = true;
+        }
+    }
+}
+
+pub(crate) fn record_line(line: &TranscriptUpdate) {
+    update(|manifest| manifest.transcript.push(line.clone()));
+}
+
+/// A chunk covering `start..end` samples of the recording entered the transcription queue.
+pub fn chunk_queued(chunk_id: u64, start: u64, end: u64) {
+    update(|manifest| {
+        manifest.pending_ranges.insert(chunk_id, (start, end));
+    });
+}
+
+/// The chunk was transcribed, or spilled to disk where recovery finds it separately.
+pub fn chunk_done(chunk_id: u64) {
+    update(|manifest| {
+        manifest.pending_ranges.remove(&chunk_id);
+    });
+}
+
+pub fn chunked_until(samples: u64) {
+    update(|manifest| manifest.chunked_until = samples);
+}
+
+pub fn record_spilled(path: &Path) {
+    update(|manifest| manifest.spilled_chunks.push(path.to_path_buf()));
+}
+
+/// Write the manifest if anything changed. Called after each flush of the recording file,
+/// so the manifest never describes audio that is not on disk yet.
+pub fn checkpoint() {
+    let Ok(mut active) = ACTIVE.lock() else { return };
+    let Some(session) = active.as_mut().filter(|session| session.dirty) else { return };
+    session.manifest.updated_at = Utc::now();
+    session.manifest.metadata = meeting_metadata::snapshot();
+    match write_manifest(&session.path, &session.manifest) {
+        Ok(()) => session.dirty = false,
+        Err(e) => log_error!("{}", e),
+    }
+}
+
+/// The recording was saved normally; nothing to recover.
+pub fn finish() {
+    let session = ACTIVE.lock().ok().and_then(|mut active| active.take());
+    if let Some(session) = session {
+        if let Err(e) = atomic_file::remove(&session.path) {
+            log_warn!("Failed to remove session manifest: {}", e);
+        }
+    }
+}
+
+fn recorded_secs(path: &Path) -> f64 {
+    // 16-bit mono WAV with a 44 byte header, as written by the recording writer
+    let mut header = [0u8; 44];
+    let sample_rate = std::fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut header))
+        .map(|_| u32::from_le_bytes([header[24], header[25], header[26], header[27]]))
+        .unwrap_or(0);
+    if sample_rate == 0 {
+        return 0.0;
+    }
+    let samples = std::fs::metadata(path).map(|m| m.len().saturating_sub(44) / 2).unwrap_or(0);
+    samples as f64 / sample_rate as f64
+}
+
+// Unprocessed stretches of the recording, merged and in order
+fn remaining_ranges(manifest: &SessionManifest, total_samples: u64) -> Vec<(u64, u64)> {
+    let mut ranges: Vec<(u64, u64)> = manifest.pending_ranges.values().copied().collect();
+    if total_samples > manifest.chunked_until {
+        ranges.push((manifest.chunked_until, total_samples));
+    }
+    ranges.sort();
+    let mut merged: Vec<(u64, u64)> = Vec::new();
+    for (start, end) in ranges {
+        let end = end.min(total_samples);
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ if start < end => merged.push((start, end)),
+            _ => {}
+        }
+    }
+    merged
+}
+
+/// Startup check for a recording that never finished.
+pub fn log_unfinished<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(Some((_, manifest))) = load_manifest(app) {
+        log_warn!(
+            "Found unfinished recording from {} with {} transcript lines; it can be recovered",
+            manifest.started_at,
+            manifest.transcript.len()
+        );
+    }
+}
+
+/// Details of a recording interrupted by a crash, if there is one.
+#[tauri::command]
+pub async fn get_unfinished_session<R: Runtime>(app: AppHandle<R>) -> Result<Option<UnfinishedSession>, String> {
+    if is_recording() {
+        return Ok(None);
+    }
+    Ok(load_manifest(&app)?.map(|(_, manifest)| UnfinishedSession {
+        session_id: manifest.session_id,
+        started_at: manifest.started_at,
+        updated_at: manifest.updated_at,
+        recording_path: manifest.recording_path.to_string_lossy().to_string(),
+        recorded_secs: recorded_secs(&manifest.recording_path),
+        transcript_lines: manifest.transcript.len(),
+        pending_chunks: manifest.pending_ranges.len() + manifest.spilled_chunks.len(),
+    }))
+}
+
+/// Restore the transcript of an interrupted recording and transcribe the audio that was
+/// captured but never transcribed. The recording file is kept as `recovered-*.wav`.
+#[tauri::command]
+pub(crate) async fn recover_last_session<R: Runtime>(app: AppHandle<R>) -> Result<RecoveredSession, String> {
+    if is_recording() {
+        return Err("Cannot recover a session while recording".to_string());
+    }
+    let (manifest_path, manifest) = load_manifest(&app)?.ok_or_else(|| "No unfinished session to recover".to_string())?;
+    log_info!("Recovering session {} started at {}", manifest.session_id, manifest.started_at);
+
+    let recording_path = if manifest.recording_path.is_file() {
+        let file_name = manifest
+            .recording_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.replacen("in-progress-", "recovered-", 1))
+            .unwrap_or_else(|| format!("recovered-{}.wav", manifest.session_id));
+        let recovered = manifest.recording_path.with_file_name(file_name);
+        std::fs::rename(&manifest.recording_path, &recovered).map_err(|e| format!("Failed to keep recording: {}", e))?;
+        Some(recovered)
+    } else {
+        log_warn!("Recording file {:?} is missing; only the transcript is recovered", manifest.recording_path);
+        None
+    };
+
+    let mut transcript = manifest.transcript.clone();
+    match chunk_spill::transcribe_pending(&app).await {
+        Ok(lines) => transcript.extend(lines),
+        Err(e) => log_error!("Failed to transcribe spilled chunks: {}", e),
+    }
+
+    let mut failed_ranges = 0;
+    if let Some(path) = &recording_path {
+        let (samples, sample_rate) = decode_audio_file(path).map_err(|e| format!("Failed to read recording: {}", e))?;
+        // The recording is saved at the device rate; the manifest counts in whisper samples
+        let samples = if sample_rate != WHISPER_SAMPLE_RATE {
+            resample_audio(&samples, sample_rate, WHISPER_SAMPLE_RATE)
+        } else {
+            samples
+        };
+        let piece_len = RECOVERY_CHUNK_SECS * WHISPER_SAMPLE_RATE as u64;
+        let mut retranscriber = Retranscriber::new(&app);
+        let mut piece_id = 0;
+        for (start, end) in remaining_ranges(&manifest, samples.len() as u64) {
+            let mut piece_start = start;
+            while piece_start < end {
+                let piece_end = (piece_start + piece_len).min(end);
+                let piece = samples[piece_start as usize..piece_end as usize].to_vec();
+                let timestamp = piece_start as f64 / WHISPER_SAMPLE_RATE as f64;
+                if let Err(e) = retranscriber
+                    .transcribe(CaptureSource::System, RECOVERED_LABEL, piece_id, timestamp, 0.0, piece)
+                    .await
+                {
+                    log_error!("Failed to transcribe recovered audio at {:.1}s: {}", timestamp, e);
+                    failed_ranges += 1;
+                }
+                piece_id += 1;
+                piece_start = piece_end;
+            }
+        }
+        transcript.extend(retranscriber.finish());
+    }
+    transcript.sort_by(|a, b| a.chunk_start_time.total_cmp(&b.chunk_start_time).then(a.sequence_id.cmp(&b.sequence_id)));
+
+    if let Err(e) = atomic_file::remove(&manifest_path) {
+        log_warn!("Failed to remove session manifest: {}", e);
+    }
+    log_info!("Recovered {} transcript lines ({} stretches failed)", transcript.len(), failed_ranges);
+    Ok(RecoveredSession {
+        session_id: manifest.session_id,
+        started_at: manifest.started_at,
+        recording_path: recording_path.map(|path| path.to_string_lossy().to_string()),
+        transcript,
+        metadata: manifest.metadata,
+        failed_ranges,
+    })
+}
+
+/// Forget an interrupted recording. Its audio file is left where it is.
+#[tauri::command]
+pub async fn discard_unfinished_session<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    if let Some((path, manifest)) = load_manifest(&app)? {
+        atomic_file::remove(&path).map_err(|e| format!("Failed to remove session manifest: {}", e))?;
+        log_info!("Discarded unfinished session {} (audio kept at {:?})", manifest.session_id, manifest.recording_path);
+    }
+    Ok(())
+}
@@ -0,0 +1,233 @@
+// Posting meeting summaries to Slack. The workspace's bot token (xoxb-...) is kept in the
+// OS keychain; store.json only holds the default channel and whether summaries are posted
+// automatically once they are ready. Messages use Block Kit: a header with the meeting
+// title, one section per summary section, decisions, and action items with their owners.
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, warn as log_warn};
+
+use crate::export::{self, BlockKind, ExportDocument};
+use crate::http;
+use crate::keychain;
+use crate::telemetry::Dependency;
+
+const SLACK_STORE_KEY: &str = "slack";
+const TOKEN_ACCOUNT: &str = "slack-bot-token";
+const POST_MESSAGE_URL: &str = "https://slack.com/api/chat.postMessage";
+// Block Kit limits: header text, section text and blocks per message
+const MAX_HEADER_CHARS: usize = 150;
+const MAX_SECTION_CHARS: usize = 3000;
+const MAX_BLOCKS: usize = 50;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlackConfig {
+    /// Channel name (#general) or id used when none is given and for automatic posts
+    pub default_channel: Option<String>,
+    /// Post every summary to the default channel as soon as it is ready
+    pub auto_post: bool,
+}
+
+/// The Slack settings plus whether a bot token is saved; the token itself is never returned.
+#[derive(Debug, Clone, Serialize)]
+pub struct SlackStatus {
+    #[serde(flatten)]
+    pub config: SlackConfig,
+    pub has_token: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SlackPost {
+    pub channel: String,
+    /// Slack's id for the message
+    pub ts: String,
+}
+
+#[derive(Deserialize)]
+struct PostMessageResponse {
+    ok: bool,
+    error: Option<String>,
+    channel: Option<String>,
+    ts: Option<String>,
+}
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> SlackConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(SLACK_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn normalize_channel(channel: &str) -> Option<String> {
+    let channel = channel.trim();
+    (!channel.is_empty()).then(|| channel.to_string())
+}
+
+// Slack treats &, < and > as control characters in mrkdwn
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max_chars - 1).collect();
+    truncated.push('\u{2026}');
+    truncated
+}
+
+fn section(title: &str, lines: &[String]) -> Value {
+    let text = format!("*{}*\n{}", escape(title), lines.join("\n"));
+    json!({ "type": "section", "text": { "type": "mrkdwn", "text": truncate(&text, MAX_SECTION_CHARS) } })
+}
+
+fn bullets(items: &[String]) -> Vec<String> {
+    items.iter().map(|item| format!("\u{2022} {}", escape(item.trim()))).collect()
+}
+
+/// The summary as Block Kit blocks.
+fn summary_blocks(document: &ExportDocument) -> Vec<Value> {
+    let mut blocks = vec![
+        json!({ "type": "header", "text": { "type": "plain_text", "text": truncate(document.title.trim(), MAX_HEADER_CHARS) } }),
+    ];
+    let mut context = vec![document.display_date()];
+    context.extend(document.provenance());
+    blocks.push(json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": escape(&context.join(" \u{00b7} ")) }] }));
+
+    for summary_section in &document.sections {
+        let lines: Vec<String> = summary_section
+            .blocks
+            .iter()
+            .filter(|block| !block.content.trim().is_empty())
+            .map(|block| match block.kind {
+                BlockKind::Heading1 | BlockKind::Heading2 => format!("*{}*", escape(block.content.trim())),
+                BlockKind::Bullet => format!("\u{2022} {}", escape(block.content.trim())),
+                BlockKind::Text => escape(block.content.trim()),
+            })
+            .collect();
+        if !lines.is_empty() {
+            blocks.push(section(&summary_section.title, &lines));
+        }
+    }
+    if !document.decisions.is_empty() {
+        blocks.push(section("Decisions", &bullets(&document.decisions)));
+    }
+    if !document.action_items.is_empty() {
+        let items: Vec<String> = document
+            .action_items
+            .iter()
+            .map(|item| match item.owner.as_deref().map(str::trim).filter(|owner| !owner.is_empty()) {
+                Some(owner) => format!("\u{2610} {} \u{2014} _{}_", escape(item.text.trim()), escape(owner)),
+                None => format!("\u{2610} {}", escape(item.text.trim())),
+            })
+            .collect();
+        blocks.push(json!({ "type": "divider" }));
+        blocks.push(section("Action items", &items));
+    }
+    blocks.truncate(MAX_BLOCKS);
+    blocks
+}
+
+async fn post<R: Runtime>(app: &AppHandle<R>, document: &ExportDocument, channel: &str) -> Result<SlackPost, String> {
+    let token = keychain::get_secret(TOKEN_ACCOUNT)?.ok_or("Connect Slack by saving a bot token first")?;
+    let body = json!({
+        "channel": channel,
+        // Shown in notifications and by clients that can't render blocks
+        "text": format!("Meeting summary: {}", document.title.trim()),
+        "blocks": summary_blocks(document),
+        "unfurl_links": false,
+    });
+
+    // A retried post could show up twice in the channel
+    let options = http::policy(app).options(Dependency::Integration, false);
+    let response = http::send(&options, "Slack post", || {
+        http::client().post(POST_MESSAGE_URL).bearer_auth(&token).json(&body)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    let response: PostMessageResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read Slack response: {}", e))?;
+    if !response.ok {
+        return Err(format!("Slack rejected the message: {}", response.error.unwrap_or_else(|| "unknown error".to_string())));
+    }
+    Ok(SlackPost {
+        channel: response.channel.unwrap_or_else(|| channel.to_string()),
+        ts: response.ts.unwrap_or_default(),
+    })
+}
+
+/// Post the summary of `meeting_id` to the default channel in the background, if automatic
+/// posting is on.
+pub fn auto_post<R: Runtime>(app: &AppHandle<R>, meeting_id: &str, auth_token: Option<String>) {
+    let config = load_config(app);
+    let Some(channel) = config.default_channel.as_deref().and_then(normalize_channel).filter(|_| config.auto_post) else {
+        return;
+    };
+    let app = app.clone();
+    let meeting_id = meeting_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let result = match export::load_document(&app, &meeting_id, auth_token).await {
+            Ok(document) => post(&app, &document, &channel).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(posted) => log_info!("Posted summary of {} to Slack channel {}", meeting_id, posted.channel),
+            Err(e) => log_warn!("Failed to post summary of {} to Slack: {}", meeting_id, e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_slack_config<R: Runtime>(app: AppHandle<R>) -> Result<SlackStatus, String> {
+    Ok(SlackStatus { config: load_config(&app), has_token: keychain::get_secret(TOKEN_ACCOUNT)?.is_some() })
+}
+
+/// Save the Slack settings. A `token` replaces the stored bot token; an empty one disconnects
+/// Slack, and leaving it out keeps the current one.
+#[tauri::command]
+pub async fn set_slack_config<R: Runtime>(app: AppHandle<R>, config: SlackConfig, token: Option<String>) -> Result<(), String> {
+    let config = SlackConfig { default_channel: config.default_channel.as_deref().and_then(normalize_channel), ..config };
+    if config.auto_post && config.default_channel.is_none() {
+        return Err("Choose a default channel to post summaries to automatically".to_string());
+    }
+    match token.as_deref().map(str::trim) {
+        Some("") => keychain::delete_secret(TOKEN_ACCOUNT)?,
+        Some(token) => keychain::set_secret(TOKEN_ACCOUNT, token)?,
+        None => {}
+    }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(SLACK_STORE_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Slack settings saved (auto-post {})", if config.auto_post { "on" } else { "off" });
+    Ok(())
+}
+
+/// Post a meeting's summary and action items to `channel`, or to the default channel if none
+/// is given.
+#[tauri::command]
+pub async fn post_summary_to_slack<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    channel: Option<String>,
+    auth_token: Option<String>,
+) -> Result<SlackPost, String> {
+    let channel = channel
+        .as_deref()
+        .and_then(normalize_channel)
+        .or_else(|| load_config(&app).default_channel.as_deref().and_then(normalize_channel))
+        .ok_or("Choose a Slack channel to post to")?;
+    let document = export::load_document(&app, &meeting_id, auth_token).await?;
+    if document.sections.is_empty() && document.action_items.is_empty() {
+        return Err(format!("Meeting {} has no summary to post yet", meeting_id));
+    }
+    let posted = post(&app, &document, &channel).await?;
+    log_info!("Posted summary of {} to Slack channel {}", meeting_id, posted.channel);
+    Ok(posted)
+}
@@ -0,0 +1,168 @@
+// Folders group a workspace's local meetings, e.g. one folder per recurring meeting. A
+// meeting is in at most one folder. Deleting a folder keeps its meetings and only takes them
+// out of it (the foreign key sets their `folder_id` back to NULL).
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use log::info as log_info;
+
+use super::with_connection;
+use crate::workspace::active_workspace;
+
+const MAX_FOLDER_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Folder {
+    pub id: String,
+    pub name: String,
+    pub created_at: String,
+    pub meeting_count: u64,
+}
+
+fn normalize_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Folder name must not be empty".to_string());
+    }
+    if name.chars().count() > MAX_FOLDER_NAME_CHARS {
+        return Err(format!("Folder names are limited to {} characters", MAX_FOLDER_NAME_CHARS));
+    }
+    Ok(name.to_string())
+}
+
+// Another folder of the workspace already has this name
+fn name_taken(conn: &rusqlite::Connection, workspace_id: &str, name: &str, except: Option<&str>) -> rusqlite::Result<bool> {
+    conn.query_row(
+        "SELECT 1 FROM folders WHERE workspace_id = ?1 AND name = ?2 COLLATE NOCASE AND id IS NOT ?3",
+        params![workspace_id, name, except],
+        |_| Ok(()),
+    )
+    .optional()
+    .map(|found| found.is_some())
+}
+
+fn get_folder<R: Runtime>(app: &AppHandle<R>, folder_id: &str) -> Result<Folder, String> {
+    let workspace_id = active_workspace(app);
+    let folder = with_connection(app, "read folder", |conn| {
+        conn.query_row(
+            "SELECT f.id, f.name, f.created_at, COUNT(m.id) FROM folders f
+             LEFT JOIN meetings m ON m.folder_id = f.id
+             WHERE f.id = ?1 AND f.workspace_id = ?2 GROUP BY f.id",
+            [folder_id, workspace_id.as_str()],
+            |row| {
+                Ok(Folder {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    created_at: row.get(2)?,
+                    meeting_count: row.get::<_, i64>(3)? as u64,
+                })
+            },
+        )
+        .optional()
+    })?;
+    folder.ok_or_else(|| format!("Folder not found: {}", folder_id))
+}
+
+/// Folders of the active workspace by name, with how many meetings each holds.
+#[tauri::command]
+pub async fn list_folders<R: Runtime>(app: AppHandle<R>) -> Result<Vec<Folder>, String> {
+    let workspace_id = active_workspace(&app);
+    with_connection(&app, "list folders", |conn| {
+        let mut folders = conn.prepare(
+            "SELECT f.id, f.name, f.created_at, COUNT(m.id) FROM folders f
+             LEFT JOIN meetings m ON m.folder_id = f.id
+             WHERE f.workspace_id = ?1 GROUP BY f.id ORDER BY f.name COLLATE NOCASE",
+        )?;
+        let rows = folders.query_map([&workspace_id], |row| {
+            Ok(Folder {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                meeting_count: row.get::<_, i64>(3)? as u64,
+            })
+        })?;
+        rows.collect()
+    })
+}
+
+#[tauri::command]
+pub async fn create_folder<R: Runtime>(app: AppHandle<R>, name: String) -> Result<Folder, String> {
+    let name = normalize_name(&name)?;
+    let workspace_id = active_workspace(&app);
+    let folder_id = uuid::Uuid::new_v4().to_string();
+    let created = with_connection(&app, "create folder", |conn| {
+        if name_taken(conn, &workspace_id, &name, None)? {
+            return Ok(false);
+        }
+        conn.execute(
+            "INSERT INTO folders (id, name, workspace_id, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![folder_id, name, workspace_id, Utc::now().to_rfc3339()],
+        )
+        .map(|_| true)
+    })?;
+    if !created {
+        return Err(format!("A folder named '{}' already exists", name));
+    }
+    log_info!("Created folder '{}'", name);
+    get_folder(&app, &folder_id)
+}
+
+#[tauri::command]
+pub async fn rename_folder<R: Runtime>(app: AppHandle<R>, folder_id: String, name: String) -> Result<Folder, String> {
+    let name = normalize_name(&name)?;
+    get_folder(&app, &folder_id)?;
+    let workspace_id = active_workspace(&app);
+    let renamed = with_connection(&app, "rename folder", |conn| {
+        if name_taken(conn, &workspace_id, &name, Some(&folder_id))? {
+            return Ok(false);
+        }
+        conn.execute(
+            "UPDATE folders SET name = ?1 WHERE id = ?2 AND workspace_id = ?3",
+            params![name, folder_id, workspace_id],
+        )
+        .map(|_| true)
+    })?;
+    if !renamed {
+        return Err(format!("A folder named '{}' already exists", name));
+    }
+    get_folder(&app, &folder_id)
+}
+
+/// Delete a folder. Its meetings are kept and no longer filed anywhere.
+#[tauri::command]
+pub async fn delete_folder<R: Runtime>(app: AppHandle<R>, folder_id: String) -> Result<(), String> {
+    let workspace_id = active_workspace(&app);
+    let deleted = with_connection(&app, "delete folder", |conn| {
+        conn.execute("DELETE FROM folders WHERE id = ?1 AND workspace_id = ?2", [&folder_id, &workspace_id])
+    })?;
+    if deleted == 0 {
+        return Err(format!("Folder not found: {}", folder_id));
+    }
+    log_info!("Deleted folder {}", folder_id);
+    Ok(())
+}
+
+/// File a local meeting in a folder, or take it out of its folder with `folder_id` None.
+#[tauri::command]
+pub async fn move_meeting_to_folder<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    folder_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(folder_id) = &folder_id {
+        get_folder(&app, folder_id)?;
+    }
+    let workspace_id = active_workspace(&app);
+    let moved = with_connection(&app, "move meeting", |conn| {
+        conn.execute(
+            "UPDATE meetings SET folder_id = ?1, updated_at = ?2 WHERE id = ?3 AND workspace_id = ?4",
+            params![folder_id, Utc::now().to_rfc3339(), meeting_id, workspace_id],
+        )
+    })?;
+    if moved == 0 {
+        return Err(format!("Meeting not found: {}", meeting_id));
+    }
+    log_info!("Moved meeting {} to folder {}", meeting_id, folder_id.as_deref().unwrap_or("(none)"));
+    Ok(())
+}
@@ -0,0 +1,324 @@
+// Meeting CRUD against the local store. Meetings come back in the same shapes the backend
+// API uses (`Meeting`, `MeetingDetails`), so the frontend can show either source the same way.
+// Every query is scoped to the active workspace.
+use chrono::Utc;
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
+use serde::Deserialize;
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+use log::info as log_info;
+
+use super::with_connection;
+use crate::api::{Meeting, MeetingDetails, MeetingPage, MeetingQuery, MeetingSort, MeetingTranscript, SortOrder};
+use crate::summary::MeetingSummary;
+use crate::workspace::active_workspace;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewTranscriptLine {
+    pub text: String,
+    pub timestamp: String,
+}
+
+fn metadata_column(metadata: Option<&Value>) -> Option<String> {
+    metadata.map(Value::to_string)
+}
+
+fn parse_metadata(row: &Row, index: usize) -> rusqlite::Result<Option<Value>> {
+    let raw: Option<String> = row.get(index)?;
+    Ok(raw.and_then(|raw| serde_json::from_str(&raw).ok()))
+}
+
+// Seconds between the start and end of the recording noted in the metadata
+const DURATION_SQL: &str = "(julianday(json_extract(metadata, '$.recording.ended_at'))
+    - julianday(json_extract(metadata, '$.recording.started_at'))) * 86400";
+
+// Append lines after the meeting's last one
+fn insert_transcripts(conn: &rusqlite::Connection, meeting_id: &str, lines: &[NewTranscriptLine]) -> rusqlite::Result<()> {
+    let next: i64 = conn.query_row(
+        "SELECT COALESCE(MAX(position) + 1, 0) FROM transcripts WHERE meeting_id = ?1",
+        [meeting_id],
+        |row| row.get(0),
+    )?;
+    let mut insert = conn.prepare(
+        "INSERT INTO transcripts (id, meeting_id, position, text, timestamp) VALUES (?1, ?2, ?3, ?4, ?5)",
+    )?;
+    for (offset, line) in lines.iter().enumerate() {
+        insert.execute(params![
+            uuid::Uuid::new_v4().to_string(),
+            meeting_id,
+            next + offset as i64,
+            line.text,
+            line.timestamp
+        ])?;
+    }
+    Ok(())
+}
+
+// Mark the meeting as changed; 0 when it is not in the workspace
+fn touch(conn: &rusqlite::Connection, meeting_id: &str, workspace_id: &str) -> rusqlite::Result<usize> {
+    conn.execute(
+        "UPDATE meetings SET updated_at = ?1 WHERE id = ?2 AND workspace_id = ?3",
+        params![Utc::now().to_rfc3339(), meeting_id, workspace_id],
+    )
+}
+
+pub(crate) fn get_meeting<R: Runtime>(app: &AppHandle<R>, meeting_id: &str) -> Result<MeetingDetails, String> {
+    let workspace_id = active_workspace(app);
+    let meeting = with_connection(app, "read meeting", |conn| {
+        let meeting = conn
+            .query_row(
+                "SELECT id, title, created_at, updated_at, series_id, metadata, workspace_id FROM meetings
+                 WHERE id = ?1 AND workspace_id = ?2",
+                [meeting_id, workspace_id.as_str()],
+                |row| {
+                    Ok(MeetingDetails {
+                        id: row.get(0)?,
+                        title: row.get(1)?,
+                        created_at: row.get(2)?,
+                        updated_at: row.get(3)?,
+                        series_id: row.get(4)?,
+                        metadata: parse_metadata(row, 5)?,
+                        workspace_id: row.get(6)?,
+                        transcripts: Vec::new(),
+                    })
+                },
+            )
+            .optional()?;
+        let Some(mut meeting) = meeting else { return Ok(None) };
+        let mut lines = conn.prepare(
+            "SELECT id, text, timestamp FROM transcripts WHERE meeting_id = ?1 ORDER BY position",
+        )?;
+        meeting.transcripts = lines
+            .query_map([meeting_id], |row| {
+                Ok(MeetingTranscript { id: row.get(0)?, text: row.get(1)?, timestamp: row.get(2)? })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(Some(meeting))
+    })?;
+    meeting.ok_or_else(|| format!("Meeting not found: {}", meeting_id))
+}
+
+/// Create a meeting in the local store, optionally with its transcript.
+#[tauri::command]
+pub async fn local_create_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    title: String,
+    transcripts: Option<Vec<NewTranscriptLine>>,
+    metadata: Option<Value>,
+) -> Result<MeetingDetails, String> {
+    if title.trim().is_empty() {
+        return Err("Meeting title must not be empty".to_string());
+    }
+    let meeting_id = format!("meeting-{}", Utc::now().timestamp_millis());
+    let now = Utc::now().to_rfc3339();
+    let workspace_id = active_workspace(&app);
+    with_connection(&app, "create meeting", |conn| {
+        let tx = conn.transaction()?;
+        tx.execute(
+            "INSERT INTO meetings (id, title, created_at, updated_at, metadata, workspace_id) VALUES (?1, ?2, ?3, ?3, ?4, ?5)",
+            params![meeting_id, title.trim(), now, metadata_column(metadata.as_ref()), workspace_id],
+        )?;
+        insert_transcripts(&tx, &meeting_id, transcripts.as_deref().unwrap_or_default())?;
+        tx.commit()
+    })?;
+    log_info!("Created local meeting {}", meeting_id);
+    get_meeting(&app, &meeting_id)
+}
+
+/// One page of the active workspace's meetings matching `query`.
+pub(crate) fn list_meetings<R: Runtime>(app: &AppHandle<R>, query: &MeetingQuery) -> Result<MeetingPage, String> {
+    let mut filters = vec!["workspace_id = ?"];
+    let mut values = vec![SqlValue::Text(active_workspace(app))];
+    if let Some(from) = query.from {
+        filters.push("julianday(created_at) >= julianday(?)");
+        values.push(SqlValue::Text(from.to_rfc3339()));
+    }
+    if let Some(to) = query.to {
+        filters.push("julianday(created_at) <= julianday(?)");
+        values.push(SqlValue::Text(to.to_rfc3339()));
+    }
+    if let Some(tag) = &query.tag {
+        filters.push("EXISTS (SELECT 1 FROM json_each(metadata, '$.tags') WHERE value = ? COLLATE NOCASE)");
+        values.push(SqlValue::Text(tag.clone()));
+    }
+    if let Some(folder_id) = &query.folder_id {
+        filters.push("folder_id = ?");
+        values.push(SqlValue::Text(folder_id.clone()));
+    }
+    match query.has_summary {
+        Some(true) => filters.push("EXISTS (SELECT 1 FROM summaries s WHERE s.meeting_id = meetings.id)"),
+        Some(false) => filters.push("NOT EXISTS (SELECT 1 FROM summaries s WHERE s.meeting_id = meetings.id)"),
+        None => {}
+    }
+    let conditions = filters.join(" AND ");
+    let order = match query.order {
+        SortOrder::Asc => "ASC",
+        SortOrder::Desc => "DESC",
+    };
+    // Meetings without a recorded duration go last either way
+    let order_by = match query.sort {
+        MeetingSort::CreatedAt => format!("created_at {}", order),
+        MeetingSort::Title => format!("title COLLATE NOCASE {}, created_at DESC", order),
+        MeetingSort::Duration => format!("duration IS NULL, duration {}, created_at DESC", order),
+    };
+
+    with_connection(app, "list meetings", |conn| {
+        let total: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM meetings WHERE {}", conditions),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )?;
+        let mut meetings = conn.prepare(&format!(
+            "SELECT id, title, workspace_id, created_at, {} AS duration, folder_id FROM meetings
+             WHERE {} ORDER BY {} LIMIT ? OFFSET ?",
+            DURATION_SQL, conditions, order_by
+        ))?;
+        let page_values = values
+            .iter()
+            .cloned()
+            .chain([SqlValue::Integer(i64::from(query.limit)), SqlValue::Integer(query.offset() as i64)]);
+        let rows = meetings.query_map(params_from_iter(page_values), |row| {
+            Ok(Meeting {
+                id: row.get(0)?,
+                title: row.get(1)?,
+                workspace_id: row.get(2)?,
+                created_at: row.get(3)?,
+                duration_secs: row.get(4)?,
+                folder_id: row.get(5)?,
+            })
+        })?;
+        Ok(MeetingPage {
+            meetings: rows.collect::<rusqlite::Result<_>>()?,
+            total: total as u64,
+            page: query.page,
+            limit: query.limit,
+        })
+    })
+}
+
+/// Local meetings of the active workspace, one page at a time. Without a query this is the
+/// first 50, most recently created first.
+#[tauri::command]
+pub async fn local_list_meetings<R: Runtime>(app: AppHandle<R>, query: Option<MeetingQuery>) -> Result<MeetingPage, String> {
+    list_meetings(&app, &query.unwrap_or_default().normalized())
+}
+
+#[tauri::command]
+pub async fn local_get_meeting<R: Runtime>(app: AppHandle<R>, meeting_id: String) -> Result<MeetingDetails, String> {
+    get_meeting(&app, &meeting_id)
+}
+
+/// Change a meeting's title and/or metadata; metadata keys are merged into the existing ones.
+#[tauri::command]
+pub async fn local_update_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    title: Option<String>,
+    metadata: Option<Value>,
+) -> Result<MeetingDetails, String> {
+    if title.as_deref().is_some_and(|t| t.trim().is_empty()) {
+        return Err("Meeting title must not be empty".to_string());
+    }
+    let existing = get_meeting(&app, &meeting_id)?;
+    let workspace_id = active_workspace(&app);
+    let metadata = match (existing.metadata, metadata) {
+        (Some(Value::Object(mut merged)), Some(Value::Object(update))) => {
+            merged.extend(update);
+            Some(Value::Object(merged))
+        }
+        (existing, update) => update.or(existing),
+    };
+    with_connection(&app, "update meeting", |conn| {
+        conn.execute(
+            "UPDATE meetings SET title = ?1, metadata = ?2 WHERE id = ?3 AND workspace_id = ?4",
+            params![
+                title.as_deref().map(str::trim).unwrap_or(&existing.title),
+                metadata_column(metadata.as_ref()),
+                meeting_id,
+                workspace_id
+            ],
+        )?;
+        touch(conn, &meeting_id, &workspace_id)
+    })?;
+    get_meeting(&app, &meeting_id)
+}
+
+/// Delete a meeting with its transcript and summary.
+#[tauri::command]
+pub async fn local_delete_meeting<R: Runtime>(app: AppHandle<R>, meeting_id: String) -> Result<(), String> {
+    let workspace_id = active_workspace(&app);
+    let deleted = with_connection(&app, "delete meeting", |conn| {
+        conn.execute("DELETE FROM meetings WHERE id = ?1 AND workspace_id = ?2", [&meeting_id, &workspace_id])
+    })?;
+    if deleted == 0 {
+        return Err(format!("Meeting not found: {}", meeting_id));
+    }
+    log_info!("Deleted local meeting {}", meeting_id);
+    Ok(())
+}
+
+/// Append transcript lines to a local meeting.
+#[tauri::command]
+pub async fn local_add_transcripts<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    transcripts: Vec<NewTranscriptLine>,
+) -> Result<(), String> {
+    let workspace_id = active_workspace(&app);
+    let updated = with_connection(&app, "save transcript", |conn| {
+        let tx = conn.transaction()?;
+        if touch(&tx, &meeting_id, &workspace_id)? == 0 {
+            return Ok(false);
+        }
+        insert_transcripts(&tx, &meeting_id, &transcripts)?;
+        tx.commit().map(|_| true)
+    })?;
+    if !updated {
+        return Err(format!("Meeting not found: {}", meeting_id));
+    }
+    Ok(())
+}
+
+/// Store a meeting's summary; it must be readable as a `MeetingSummary`.
+#[tauri::command]
+pub async fn local_save_summary<R: Runtime>(app: AppHandle<R>, meeting_id: String, summary: Value) -> Result<(), String> {
+    MeetingSummary::from_value(&summary)?;
+    let workspace_id = active_workspace(&app);
+    let updated = with_connection(&app, "save summary", |conn| {
+        let tx = conn.transaction()?;
+        if touch(&tx, &meeting_id, &workspace_id)? == 0 {
+            return Ok(false);
+        }
+        tx.execute(
+            "INSERT INTO summaries (meeting_id, data, updated_at) VALUES (?1, ?2, ?3)
+             ON CONFLICT(meeting_id) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at",
+            params![meeting_id, summary.to_string(), Utc::now().to_rfc3339()],
+        )?;
+        tx.commit().map(|_| true)
+    })?;
+    if !updated {
+        return Err(format!("Meeting not found: {}", meeting_id));
+    }
+    Ok(())
+}
+
+/// A local meeting's summary; None if it has none yet.
+#[tauri::command]
+pub async fn local_get_summary<R: Runtime>(app: AppHandle<R>, meeting_id: String) -> Result<Option<MeetingSummary>, String> {
+    let workspace_id = active_workspace(&app);
+    let data = with_connection(&app, "read summary", |conn| {
+        conn.query_row(
+            "SELECT s.data FROM summaries s JOIN meetings m ON m.id = s.meeting_id
+             WHERE s.meeting_id = ?1 AND m.workspace_id = ?2",
+            [&meeting_id, &workspace_id],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+    })?;
+    data.map(|data| {
+        let value: Value = serde_json::from_str(&data).map_err(|e| format!("Failed to parse stored summary: {}", e))?;
+        MeetingSummary::from_value(&value)
+    })
+    .transpose()
+}
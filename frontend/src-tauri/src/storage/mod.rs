@@ -0,0 +1,201 @@
+// Embedded SQLite store for meetings and the activity feed, so the app keeps working when
+// the Python backend at localhost:5167 isn't running. The database lives in the app data
+// directory and is opened on first use; its schema is versioned with `PRAGMA user_version`
+// and migrated forward one step at a time.
+pub mod folders;
+pub mod meetings;
+pub mod search;
+pub mod semantic;
+pub mod tags;
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use rusqlite::{Connection, OpenFlags};
+use tauri::{AppHandle, Manager, Runtime};
+use log::info as log_info;
+
+const DATABASE_FILE: &str = "meetings.sqlite";
+
+// Applied in order; entry N brings the schema from user_version N to N + 1. Never edit a
+// migration that has shipped, add a new one instead.
+const MIGRATIONS: &[&str] = &[
+    "CREATE TABLE meetings (
+        id TEXT PRIMARY KEY,
+        title TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        updated_at TEXT NOT NULL,
+        series_id TEXT,
+        metadata TEXT
+    );
+    CREATE TABLE transcripts (
+        id TEXT PRIMARY KEY,
+        meeting_id TEXT NOT NULL REFERENCES meetings(id) ON DELETE CASCADE,
+        position INTEGER NOT NULL,
+        text TEXT NOT NULL,
+        timestamp TEXT NOT NULL
+    );
+    CREATE INDEX transcripts_by_meeting ON transcripts(meeting_id, position);
+    CREATE TABLE summaries (
+        meeting_id TEXT PRIMARY KEY REFERENCES meetings(id) ON DELETE CASCADE,
+        data TEXT NOT NULL,
+        updated_at TEXT NOT NULL
+    );",
+    "CREATE TABLE activity (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        kind TEXT NOT NULL,
+        message TEXT NOT NULL,
+        meeting_id TEXT,
+        dedupe_key TEXT UNIQUE,
+        created_at TEXT NOT NULL,
+        read_at TEXT
+    );",
+    "CREATE VIRTUAL TABLE transcripts_fts USING fts5(
+        text,
+        content = 'transcripts',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER transcripts_fts_insert AFTER INSERT ON transcripts BEGIN
+        INSERT INTO transcripts_fts (rowid, text) VALUES (new.rowid, new.text);
+    END;
+    CREATE TRIGGER transcripts_fts_delete AFTER DELETE ON transcripts BEGIN
+        INSERT INTO transcripts_fts (transcripts_fts, rowid, text) VALUES ('delete', old.rowid, old.text);
+    END;
+    CREATE TRIGGER transcripts_fts_update AFTER UPDATE OF text ON transcripts BEGIN
+        INSERT INTO transcripts_fts (transcripts_fts, rowid, text) VALUES ('delete', old.rowid, old.text);
+        INSERT INTO transcripts_fts (rowid, text) VALUES (new.rowid, new.text);
+    END;
+    INSERT INTO transcripts_fts (transcripts_fts) VALUES ('rebuild');",
+    "ALTER TABLE meetings ADD COLUMN workspace_id TEXT NOT NULL DEFAULT 'personal';
+    CREATE INDEX meetings_by_workspace ON meetings(workspace_id, created_at);",
+    "CREATE TABLE transcript_embeddings (
+        transcript_id TEXT PRIMARY KEY REFERENCES transcripts(id) ON DELETE CASCADE,
+        model TEXT NOT NULL,
+        vector BLOB NOT NULL
+    );",
+    "CREATE TABLE folders (
+        id TEXT PRIMARY KEY,
+        name TEXT NOT NULL,
+        workspace_id TEXT NOT NULL,
+        created_at TEXT NOT NULL
+    );
+    CREATE UNIQUE INDEX folders_by_name ON folders(workspace_id, name COLLATE NOCASE);
+    ALTER TABLE meetings ADD COLUMN folder_id TEXT REFERENCES folders(id) ON DELETE SET NULL;
+    CREATE INDEX meetings_by_folder ON meetings(folder_id);",
+    "CREATE TABLE usage_events (
+        id INTEGER PRIMARY KEY,
+        kind TEXT NOT NULL,
+        occurred_at TEXT NOT NULL,
+        value REAL,
+        model TEXT
+    );
+    CREATE INDEX usage_events_by_kind ON usage_events(kind, occurred_at);",
+];
+
+/// Schema version of a database with every migration applied.
+pub(crate) const SCHEMA_VERSION: usize = MIGRATIONS.len();
+
+static CONNECTION: Lazy<Mutex<Option<Connection>>> = Lazy::new(|| Mutex::new(None));
+
+fn database_path<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data directory: {}", e))?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create app data directory: {}", e))?;
+    Ok(dir.join(DATABASE_FILE))
+}
+
+fn open<R: Runtime>(app: &AppHandle<R>) -> Result<Connection, String> {
+    let path = database_path(app)?;
+
+    let mut conn = Connection::open(&path).map_err(|e| format!("Failed to open local database: {}", e))?;
+    conn.pragma_update(None, "foreign_keys", true)
+        .and_then(|_| conn.pragma_update(None, "journal_mode", "WAL"))
+        .map_err(|e| format!("Failed to configure local database: {}", e))?;
+    migrate(&mut conn).map_err(|e| format!("Failed to migrate local database: {}", e))?;
+    log_info!("Opened local meeting store at {}", path.display());
+    Ok(conn)
+}
+
+fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+    let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+        tx.commit()?;
+        log_info!("Migrated local meeting store to schema version {}", index + 1);
+    }
+    Ok(())
+}
+
+/// Run `f` against the store, opening it first if needed. `action` names what `f` does,
+/// for the error message.
+pub(crate) fn with_connection<R: Runtime, T>(
+    app: &AppHandle<R>,
+    action: &str,
+    f: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+) -> Result<T, String> {
+    let mut guard = CONNECTION.lock().map_err(|_| "Local database lock poisoned".to_string())?;
+    let conn = match &mut *guard {
+        Some(conn) => conn,
+        empty => empty.insert(open(app)?),
+    };
+    f(conn).map_err(|e| format!("Failed to {}: {}", action, e))
+}
+
+/// Write a consistent copy of the store to `dest` and return its schema version.
+pub(crate) fn snapshot<R: Runtime>(app: &AppHandle<R>, dest: &Path) -> Result<usize, String> {
+    with_connection(app, "copy local database", |conn| {
+        conn.execute("VACUUM INTO ?1", [dest.to_string_lossy()])?;
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+    })
+}
+
+/// Schema version of the database file at `path`, after checking that it is intact.
+pub(crate) fn check_database(path: &Path) -> Result<usize, String> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .map_err(|e| format!("Failed to open database: {}", e))?;
+    let integrity: String = conn
+        .query_row("PRAGMA integrity_check", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to check database: {}", e))?;
+    if integrity != "ok" {
+        return Err(format!("Database is damaged: {}", integrity));
+    }
+    conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read database version: {}", e))
+}
+
+/// Replace the store with the database at `source`, migrating it forward if it is older.
+/// The current database is kept next to it as `meetings.sqlite.before-restore`.
+pub(crate) fn replace_database<R: Runtime>(app: &AppHandle<R>, source: &Path) -> Result<(), String> {
+    let mut guard = CONNECTION.lock().map_err(|_| "Local database lock poisoned".to_string())?;
+    // Closing the connection checkpoints the WAL into the database file
+    drop(guard.take());
+
+    let path = database_path(app)?;
+    let previous = path.with_extension("sqlite.before-restore");
+    if path.exists() {
+        std::fs::rename(&path, &previous).map_err(|e| format!("Failed to set the current database aside: {}", e))?;
+    }
+    for suffix in ["-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+    }
+    std::fs::copy(source, &path).map_err(|e| format!("Failed to copy restored database: {}", e))?;
+
+    match open(app) {
+        Ok(conn) => {
+            *guard = Some(conn);
+            Ok(())
+        }
+        Err(e) => {
+            // Put the previous database back so the app keeps working
+            if previous.exists() {
+                let _ = std::fs::remove_file(&path);
+                let _ = std::fs::rename(&previous, &path);
+            }
+            Err(e)
+        }
+    }
+}
@@ -0,0 +1,183 @@
+// Full-text search over the active workspace's transcripts in the local store, backed by an
+// FTS5 index that triggers keep in step with the transcripts table. Results are ranked with
+// bm25 and carry the matching passage with the matched terms marked.
+//
+// `search_in_meeting` is find-in-page for one meeting: a plain substring or regex scan of
+// its transcript lines, without the index, so it matches anywhere inside words.
+use regex::RegexBuilder;
+use rusqlite::params;
+use serde::Serialize;
+use tauri::{AppHandle, Runtime};
+use log::info as log_info;
+
+use super::meetings::get_meeting;
+use super::with_connection;
+use crate::logging;
+use crate::workspace::active_workspace;
+
+pub(crate) const DEFAULT_SEARCH_LIMIT: u32 = 50;
+// Tokens of context around the matched terms
+const SNIPPET_TOKENS: i64 = 24;
+// Sentinels FTS5 puts around matched terms, turned into `highlights` afterwards
+const MATCH_START: char = '\u{2}';
+const MATCH_END: char = '\u{3}';
+// Matches returned by one in-meeting search; enough for any find bar
+const MAX_MEETING_MATCHES: usize = 1000;
+// Compiled size limit for user-supplied patterns
+const PATTERN_SIZE_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalSearchHit {
+    pub meeting_id: String,
+    pub title: String,
+    pub transcript_id: String,
+    pub timestamp: String,
+    /// The passage around the match, "…" where it was cut
+    pub context: String,
+    /// Character ranges of `context` that matched, as [start, end)
+    pub highlights: Vec<[usize; 2]>,
+    /// Lower is a better match
+    pub rank: f64,
+}
+
+/// A transcript line of one meeting with every place the pattern matched it.
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingLineMatch {
+    pub transcript_id: String,
+    pub timestamp: String,
+    pub text: String,
+    /// Character ranges of `text` that matched, as [start, end)
+    pub matches: Vec<[usize; 2]>,
+}
+
+// Each word becomes a quoted term so user input can't be read as FTS5 syntax; the last
+// one matches as a prefix, so results show up while the word is still being typed.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    let last = terms.len().checked_sub(1)?;
+    Some(
+        terms
+            .iter()
+            .enumerate()
+            .map(|(i, term)| if i == last { format!("{}*", term) } else { term.clone() })
+            .collect::<Vec<_>>()
+            .join(" "),
+    )
+}
+
+fn split_highlights(snippet: &str) -> (String, Vec<[usize; 2]>) {
+    let mut context = String::with_capacity(snippet.len());
+    let mut highlights = Vec::new();
+    let mut start = None;
+    let mut chars = 0;
+    for c in snippet.chars() {
+        match c {
+            MATCH_START => start = Some(chars),
+            MATCH_END => highlights.extend(start.take().map(|start| [start, chars])),
+            c => {
+                context.push(c);
+                chars += 1;
+            }
+        }
+    }
+    (context, highlights)
+}
+
+pub(crate) fn search<R: Runtime>(app: &AppHandle<R>, query: &str, limit: u32) -> Result<Vec<LocalSearchHit>, String> {
+    let Some(fts_query) = fts_query(query) else { return Ok(Vec::new()) };
+    let workspace_id = active_workspace(app);
+    let hits = with_connection(app, "search transcripts", |conn| {
+        let mut search = conn.prepare(
+            "SELECT m.id, m.title, t.id, t.timestamp,
+                    snippet(transcripts_fts, 0, ?2, ?3, '…', ?4), bm25(transcripts_fts)
+             FROM transcripts_fts
+             JOIN transcripts t ON t.rowid = transcripts_fts.rowid
+             JOIN meetings m ON m.id = t.meeting_id
+             WHERE transcripts_fts MATCH ?1 AND m.workspace_id = ?6
+             ORDER BY bm25(transcripts_fts), m.created_at DESC
+             LIMIT ?5",
+        )?;
+        let rows = search.query_map(
+            params![fts_query, MATCH_START.to_string(), MATCH_END.to_string(), SNIPPET_TOKENS, limit, workspace_id],
+            |row| {
+                let (context, highlights) = split_highlights(&row.get::<_, String>(4)?);
+                Ok(LocalSearchHit {
+                    meeting_id: row.get(0)?,
+                    title: row.get(1)?,
+                    transcript_id: row.get(2)?,
+                    timestamp: row.get(3)?,
+                    context,
+                    highlights,
+                    rank: row.get(5)?,
+                })
+            },
+        )?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+    log_info!("Local search for '{}' found {} matches", logging::redact(query), hits.len());
+    Ok(hits)
+}
+
+/// Search every transcript in the local store, best matches first.
+#[tauri::command]
+pub async fn search_transcripts_local<R: Runtime>(
+    app: AppHandle<R>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<LocalSearchHit>, String> {
+    search(&app, &query, limit.unwrap_or(DEFAULT_SEARCH_LIMIT))
+}
+
+/// Find a pattern in one local meeting's transcript, in transcript order. Plain patterns
+/// match case-insensitively; with `regex` the pattern is a regular expression, and `(?i)`
+/// makes it case-insensitive. Empty matches are skipped.
+#[tauri::command]
+pub async fn search_in_meeting<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    pattern: String,
+    regex: bool,
+) -> Result<Vec<MeetingLineMatch>, String> {
+    if pattern.is_empty() {
+        return Ok(Vec::new());
+    }
+    let matcher = if regex {
+        RegexBuilder::new(&pattern).size_limit(PATTERN_SIZE_LIMIT).build()
+    } else {
+        RegexBuilder::new(&regex::escape(&pattern)).case_insensitive(true).build()
+    }
+    .map_err(|e| format!("Invalid search pattern: {}", e))?;
+
+    let meeting = get_meeting(&app, &meeting_id)?;
+    let mut found = 0;
+    let mut lines = Vec::new();
+    for transcript in meeting.transcripts {
+        if found >= MAX_MEETING_MATCHES {
+            break;
+        }
+        // Byte offsets from the regex become character offsets for the frontend
+        let mut matches = Vec::new();
+        let (mut byte, mut chars) = (0, 0);
+        for m in matcher.find_iter(&transcript.text).filter(|m| !m.is_empty()).take(MAX_MEETING_MATCHES - found) {
+            chars += transcript.text[byte..m.start()].chars().count();
+            let len = m.as_str().chars().count();
+            matches.push([chars, chars + len]);
+            chars += len;
+            byte = m.end();
+        }
+        if matches.is_empty() {
+            continue;
+        }
+        found += matches.len();
+        lines.push(MeetingLineMatch {
+            transcript_id: transcript.id,
+            timestamp: transcript.timestamp,
+            text: transcript.text,
+            matches,
+        });
+    }
+    Ok(lines)
+}
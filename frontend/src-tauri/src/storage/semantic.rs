@@ -0,0 +1,144 @@
+// Semantic search over the local store: every transcript line gets an embedding vector,
+// stored next to it, and a query is matched by meaning against all of them, so "budget
+// concerns" also finds "we can't afford the second license". Lines are embedded
+// incrementally; whatever is missing is caught up before each search.
+//
+// Vectors are compared by brute force. Even a few thousand hours of meetings is well under
+// a million lines, which a linear scan handles in a fraction of a second.
+use rusqlite::params;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error};
+
+use super::with_connection;
+use crate::embeddings::{self, cosine_similarity, DEFAULT_EMBEDDING_MODEL};
+use crate::logging;
+use crate::workspace::active_workspace;
+
+const DEFAULT_TOP_K: usize = 10;
+const MAX_TOP_K: usize = 100;
+// Lines embedded between progress events and database writes
+const INDEX_BATCH_SIZE: usize = 256;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SemanticSearchHit {
+    pub meeting_id: String,
+    pub title: String,
+    pub transcript_id: String,
+    pub timestamp: String,
+    pub text: String,
+    /// Cosine similarity to the query, higher is closer
+    pub score: f32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct IndexProgress {
+    indexed: usize,
+    total: usize,
+}
+
+fn to_blob(vector: &[f32]) -> Vec<u8> {
+    vector.iter().flat_map(|value| value.to_le_bytes()).collect()
+}
+
+fn from_blob(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect()
+}
+
+// Lines of the active workspace without a vector from the current model
+fn unindexed<R: Runtime>(app: &AppHandle<R>) -> Result<Vec<(String, String)>, String> {
+    let workspace_id = active_workspace(app);
+    with_connection(app, "find unindexed transcripts", |conn| {
+        let mut lines = conn.prepare(
+            "SELECT t.id, t.text FROM transcripts t
+             JOIN meetings m ON m.id = t.meeting_id
+             LEFT JOIN transcript_embeddings e ON e.transcript_id = t.id AND e.model = ?2
+             WHERE m.workspace_id = ?1 AND e.transcript_id IS NULL AND TRIM(t.text) != ''",
+        )?;
+        let rows = lines.query_map(params![workspace_id, DEFAULT_EMBEDDING_MODEL], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    })
+}
+
+/// Embed every transcript line that has no vector yet, reporting `semantic-index-progress`
+/// events along the way. Returns how many lines were added.
+pub(crate) async fn update_index<R: Runtime>(app: &AppHandle<R>) -> Result<usize, String> {
+    let missing = unindexed(app)?;
+    let total = missing.len();
+    for (done, batch) in missing.chunks(INDEX_BATCH_SIZE).enumerate() {
+        let texts: Vec<String> = batch.iter().map(|(_, text)| text.clone()).collect();
+        let vectors = embeddings::embed(&texts).await?;
+        with_connection(app, "save transcript embeddings", |conn| {
+            let tx = conn.transaction()?;
+            {
+                let mut insert = tx.prepare(
+                    "INSERT OR REPLACE INTO transcript_embeddings (transcript_id, model, vector) VALUES (?1, ?2, ?3)",
+                )?;
+                for ((transcript_id, _), vector) in batch.iter().zip(&vectors) {
+                    insert.execute(params![transcript_id, DEFAULT_EMBEDDING_MODEL, to_blob(vector)])?;
+                }
+            }
+            tx.commit()
+        })?;
+        let progress = IndexProgress { indexed: (done * INDEX_BATCH_SIZE + batch.len()).min(total), total };
+        if let Err(e) = app.emit("semantic-index-progress", progress) {
+            log_error!("Failed to emit semantic-index-progress event: {}", e);
+        }
+    }
+    if total > 0 {
+        log_info!("Added {} transcript lines to the semantic index", total);
+    }
+    Ok(total)
+}
+
+fn nearest<R: Runtime>(app: &AppHandle<R>, query: &[f32], top_k: usize) -> Result<Vec<SemanticSearchHit>, String> {
+    let workspace_id = active_workspace(app);
+    let mut hits = with_connection(app, "search transcript embeddings", |conn| {
+        let mut vectors = conn.prepare(
+            "SELECT m.id, m.title, t.id, t.timestamp, t.text, e.vector FROM transcript_embeddings e
+             JOIN transcripts t ON t.id = e.transcript_id
+             JOIN meetings m ON m.id = t.meeting_id
+             WHERE m.workspace_id = ?1 AND e.model = ?2",
+        )?;
+        let rows = vectors.query_map(params![workspace_id, DEFAULT_EMBEDDING_MODEL], |row| {
+            let vector: Vec<u8> = row.get(5)?;
+            Ok(SemanticSearchHit {
+                meeting_id: row.get(0)?,
+                title: row.get(1)?,
+                transcript_id: row.get(2)?,
+                timestamp: row.get(3)?,
+                text: row.get(4)?,
+                score: cosine_similarity(query, &from_blob(&vector)),
+            })
+        })?;
+        rows.collect::<rusqlite::Result<Vec<_>>>()
+    })?;
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(top_k);
+    Ok(hits)
+}
+
+/// Find the transcript lines across all local meetings closest in meaning to `query`,
+/// best first. Lines not embedded yet are indexed first, which takes a while the first time.
+#[tauri::command]
+pub async fn semantic_search<R: Runtime>(
+    app: AppHandle<R>,
+    query: String,
+    top_k: Option<usize>,
+) -> Result<Vec<SemanticSearchHit>, String> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    update_index(&app).await?;
+    let vector = embeddings::embed(&[query.to_string()]).await?.remove(0);
+    let hits = nearest(&app, &vector, top_k.unwrap_or(DEFAULT_TOP_K).clamp(1, MAX_TOP_K))?;
+    log_info!("Semantic search for '{}' found {} matches", logging::redact(&query), hits.len());
+    Ok(hits)
+}
+
+/// Embed any transcript lines not in the semantic index yet, e.g. after importing meetings.
+#[tauri::command]
+pub async fn update_semantic_index<R: Runtime>(app: AppHandle<R>) -> Result<usize, String> {
+    update_index(&app).await
+}
@@ -0,0 +1,124 @@
+// Tags on local meetings. A meeting's tags are the `tags` array in its metadata, where
+// calendar import already puts an event's categories, so tags from both sources show up
+// together and `MeetingQuery::tag` filters on them. Tags compare case-insensitively; the
+// spelling a meeting was first tagged with is kept.
+use chrono::Utc;
+use rusqlite::params;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tauri::{AppHandle, Runtime};
+use log::info as log_info;
+
+use super::meetings::{get_meeting, list_meetings};
+use super::with_connection;
+use crate::api::{MeetingPage, MeetingQuery};
+use crate::workspace::active_workspace;
+
+const MAX_TAG_CHARS: usize = 50;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    /// Meetings in the active workspace with this tag
+    pub meetings: u64,
+}
+
+pub(crate) fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err("Tag must not be empty".to_string());
+    }
+    if tag.chars().count() > MAX_TAG_CHARS {
+        return Err(format!("Tags are limited to {} characters", MAX_TAG_CHARS));
+    }
+    Ok(tag.to_string())
+}
+
+// Apply `change` to the meeting's tags and save them if it reports a change
+fn update_tags<R: Runtime>(
+    app: &AppHandle<R>,
+    meeting_id: &str,
+    change: impl FnOnce(&mut Vec<String>) -> bool,
+) -> Result<Vec<String>, String> {
+    let meeting = get_meeting(app, meeting_id)?;
+    let mut metadata = match meeting.metadata {
+        Some(Value::Object(metadata)) => metadata,
+        _ => Map::new(),
+    };
+    let mut tags: Vec<String> = metadata
+        .get("tags")
+        .and_then(|tags| serde_json::from_value(tags.clone()).ok())
+        .unwrap_or_default();
+    if !change(&mut tags) {
+        return Ok(tags);
+    }
+    metadata.insert("tags".to_string(), serde_json::json!(tags));
+
+    let workspace_id = active_workspace(app);
+    with_connection(app, "save tags", |conn| {
+        conn.execute(
+            "UPDATE meetings SET metadata = ?1, updated_at = ?2 WHERE id = ?3 AND workspace_id = ?4",
+            params![Value::Object(metadata).to_string(), Utc::now().to_rfc3339(), meeting_id, workspace_id],
+        )
+    })?;
+    Ok(tags)
+}
+
+/// Tag a local meeting and return its tags; tagging twice is not an error.
+pub(crate) fn tag_meeting<R: Runtime>(app: &AppHandle<R>, meeting_id: &str, tag: &str) -> Result<Vec<String>, String> {
+    let tag = normalize_tag(tag)?;
+    let tags = update_tags(app, meeting_id, |tags| {
+        if tags.iter().any(|existing| existing.eq_ignore_ascii_case(&tag)) {
+            return false;
+        }
+        tags.push(tag.clone());
+        true
+    })?;
+    log_info!("Tagged meeting {} with '{}'", meeting_id, tag);
+    Ok(tags)
+}
+
+/// Tag a local meeting. Returns the meeting's tags.
+#[tauri::command]
+pub async fn add_tag<R: Runtime>(app: AppHandle<R>, meeting_id: String, tag: String) -> Result<Vec<String>, String> {
+    tag_meeting(&app, &meeting_id, &tag)
+}
+
+/// Remove a tag from a local meeting. Returns the meeting's remaining tags.
+#[tauri::command]
+pub async fn remove_tag<R: Runtime>(app: AppHandle<R>, meeting_id: String, tag: String) -> Result<Vec<String>, String> {
+    let tag = tag.trim();
+    update_tags(&app, &meeting_id, |tags| {
+        let count = tags.len();
+        tags.retain(|existing| !existing.eq_ignore_ascii_case(tag));
+        tags.len() != count
+    })
+}
+
+/// Every tag used in the active workspace with the number of meetings carrying it, by name.
+#[tauri::command]
+pub async fn list_tags<R: Runtime>(app: AppHandle<R>) -> Result<Vec<TagCount>, String> {
+    let workspace_id = active_workspace(&app);
+    with_connection(&app, "list tags", |conn| {
+        let mut tags = conn.prepare(
+            "SELECT MIN(t.value), COUNT(DISTINCT m.id) FROM meetings m, json_each(m.metadata, '$.tags') t
+             WHERE m.workspace_id = ?1 AND t.type = 'text'
+             GROUP BY lower(t.value) ORDER BY lower(t.value)",
+        )?;
+        let rows = tags.query_map([&workspace_id], |row| {
+            Ok(TagCount { tag: row.get(0)?, meetings: row.get::<_, i64>(1)? as u64 })
+        })?;
+        rows.collect()
+    })
+}
+
+/// Local meetings with `tag`, paged and sorted like `local_list_meetings`.
+#[tauri::command]
+pub async fn get_meetings_by_tag<R: Runtime>(
+    app: AppHandle<R>,
+    tag: String,
+    query: Option<MeetingQuery>,
+) -> Result<MeetingPage, String> {
+    let query = MeetingQuery { tag: Some(normalize_tag(&tag)?), ..query.unwrap_or_default() };
+    list_meetings(&app, &query.normalized())
+}
@@ -0,0 +1,238 @@
+// Low-latency transcription against the whisper server. Instead of 30-second chunks, each
+// source sends ~1 second frames to the server's /stream/frame endpoint and gets back the
+// segments that are final plus a partial transcript of the words still being spoken, so
+// the first words show up within a couple of seconds.
+//
+// Frames travel through the same per-source queues as chunks, so spilling, crash recovery
+// and the completion event work unchanged. Speaker identification needs whole utterances
+// and is not applied in this mode.
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use reqwest::multipart::{Form, Part};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error, debug as log_debug, warn as log_warn};
+
+use crate::telemetry::{self, Dependency};
+use crate::utils::format_timestamp;
+use crate::{
+    note_detected_language, publish_update, session_recovery, worker_finished, CaptureSource, SessionHandles,
+    TranscriptAccumulator, TranscriptSegment, TranscriptionConfig, ACTIVE_WORKERS, LAST_TRANSCRIPTION_ACTIVITY,
+    WHISPER_SAMPLE_RATE,
+};
+
+pub const FRAME_MS: u32 = 1000;
+// Frames the server could not take are resent with the next one, up to this much audio
+const MAX_BACKLOG_SECS: usize = 30;
+
+#[derive(Debug, Deserialize)]
+struct FrameResponse {
+    /// Final segments, timed relative to `buffer_start_ms`
+    #[serde(default)]
+    segments: Vec<TranscriptSegment>,
+    #[serde(default)]
+    partial: String,
+    #[serde(default)]
+    buffer_start_ms: i64,
+    #[serde(default)]
+    language: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct PartialTranscript {
+    source: String,
+    text: String,
+    timestamp: String,
+}
+
+/// Size of a streaming frame in 16kHz samples.
+pub fn frame_samples() -> usize {
+    (WHISPER_SAMPLE_RATE as u64 * FRAME_MS as u64 / 1000) as usize
+}
+
+async fn send_frame(
+    client: &reqwest::Client,
+    frame_url: &str,
+    session_id: &str,
+    config: &TranscriptionConfig,
+    samples: &[f32],
+    offset_secs: f64,
+    is_final: bool,
+) -> Result<FrameResponse, String> {
+    let part = Part::bytes(config.sample_format.encode(samples))
+        .file_name("audio.raw")
+        .mime_str("audio/x-raw")
+        .map_err(|e| e.to_string())?;
+    let mut form = Form::new()
+        .part("audio", part)
+        .text("session", session_id.to_string())
+        .text("offset_ms", ((offset_secs * 1000.0) as i64).to_string())
+        .text("final", is_final.to_string())
+        .text("format", config.sample_format.as_str());
+    if let Some(language) = &config.language {
+        form = form.text("language", language.clone());
+    }
+    if config.translate {
+        form = form.text("translate", "true");
+    }
+
+    let started = std::time::Instant::now();
+    let result = async {
+        let response = client.post(frame_url).multipart(form).send().await.map_err(|e| e.to_string())?;
+        response.json::<FrameResponse>().await.map_err(|e| format!("Failed to parse response: {}", e))
+    }
+    .await;
+    telemetry::record(Dependency::WhisperServer, started.elapsed(), result.as_ref().err().map(String::as_str));
+    result
+}
+
+struct StreamState<R: Runtime> {
+    app_handle: AppHandle<R>,
+    handles: SessionHandles,
+    config: TranscriptionConfig,
+    source: CaptureSource,
+    accumulator: TranscriptAccumulator,
+    last_partial: String,
+    worker_id: usize,
+}
+
+impl<R: Runtime> StreamState<R> {
+    fn apply(&mut self, response: FrameResponse, chunk_id: u64, recording_start_time: std::time::Instant) {
+        if let Some(language) = &response.language {
+            note_detected_language(&self.app_handle, &self.handles, &self.config, self.source, language);
+        }
+        let buffer_start = response.buffer_start_ms.max(0) as f64 / 1000.0;
+        self.accumulator.set_chunk_context(chunk_id, buffer_start, 0.0, recording_start_time);
+        for segment in &response.segments {
+            if let Some(update) = self.accumulator.add_segment(segment) {
+                log_info!("Worker {}: Emitting transcript-update event with sequence_id: {}", self.worker_id, update.sequence_id);
+                if let Err(e) = publish_update(&self.app_handle, &update) {
+                    log_error!("Worker {}: Failed to emit transcript update: {}", self.worker_id, e);
+                }
+            }
+        }
+        self.show_partial(response.partial.trim(), buffer_start);
+    }
+
+    // Words still being decided are only shown live; they arrive as a final segment later
+    fn show_partial(&mut self, text: &str, timestamp: f64) {
+        if text == self.last_partial {
+            return;
+        }
+        self.last_partial = text.to_string();
+        let partial = PartialTranscript {
+            source: self.source.label().to_string(),
+            text: text.to_string(),
+            timestamp: format_timestamp(timestamp),
+        };
+        if let Err(e) = self.app_handle.emit("transcript-partial", partial) {
+            log_debug!("Failed to emit transcript-partial event: {}", e);
+        }
+    }
+
+    fn publish_timeout(&mut self) {
+        if let Some(update) = self.accumulator.check_timeout() {
+            if let Err(e) = publish_update(&self.app_handle, &update) {
+                log_error!("Worker {}: Failed to send timeout transcript update: {}", self.worker_id, e);
+            }
+        }
+    }
+}
+
+/// Stream one source's frames to the server until the recording stops and its queue is empty.
+pub(crate) async fn stream_worker<R: Runtime>(
+    client: reqwest::Client,
+    server_url: String,
+    app_handle: AppHandle<R>,
+    handles: SessionHandles,
+    config: TranscriptionConfig,
+    source: CaptureSource,
+    worker_id: usize,
+) {
+    log_info!("Streaming worker {} started for {:?} audio", worker_id, source);
+    ACTIVE_WORKERS.fetch_add(1, Ordering::SeqCst);
+
+    let frame_url = format!("{}/stream/frame", server_url);
+    let session_id = uuid::Uuid::new_v4().simple().to_string();
+    let max_backlog = WHISPER_SAMPLE_RATE as usize * MAX_BACKLOG_SECS;
+    let mut state = StreamState {
+        app_handle: app_handle.clone(),
+        handles: handles.clone(),
+        config,
+        source,
+        accumulator: TranscriptAccumulator::new(source, handles.recent_words(source)),
+        last_partial: String::new(),
+        worker_id,
+    };
+    let mut backlog: Vec<f32> = Vec::new();
+    let mut backlog_start = 0.0;
+    let mut backlog_chunks: Vec<u64> = Vec::new();
+    let mut last_chunk = None;
+
+    loop {
+        if !handles.is_running.load(Ordering::SeqCst) && handles.source_queue_len(source) == 0 {
+            break;
+        }
+        state.publish_timeout();
+
+        let frame = handles.queue(source).lock().ok().and_then(|mut queue| queue.pop_front());
+        let Some(frame) = frame else {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            continue;
+        };
+        LAST_TRANSCRIPTION_ACTIVITY.store(
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            Ordering::SeqCst,
+        );
+
+        if backlog.is_empty() {
+            backlog_start = frame.timestamp;
+        }
+        backlog.extend_from_slice(&frame.samples);
+        backlog_chunks.push(frame.chunk_id);
+        last_chunk = Some((frame.chunk_id, frame.recording_start_time));
+        if backlog.len() > max_backlog {
+            let excess = backlog.len() - max_backlog;
+            backlog.drain(..excess);
+            backlog_start += excess as f64 / WHISPER_SAMPLE_RATE as f64;
+            log_warn!("Worker {}: Server unreachable, dropped {:.1}s of {:?} audio", worker_id, excess as f64 / WHISPER_SAMPLE_RATE as f64, source);
+        }
+
+        match send_frame(&client, &frame_url, &session_id, &state.config, &backlog, backlog_start, false).await {
+            Ok(response) => {
+                for chunk_id in backlog_chunks.drain(..) {
+                    session_recovery::chunk_done(chunk_id);
+                }
+                backlog.clear();
+                state.apply(response, frame.chunk_id, frame.recording_start_time);
+            }
+            Err(e) => log_warn!("Worker {}: Streaming frame failed, resending with the next one: {}", worker_id, e),
+        }
+    }
+
+    // The server holds back the last words until told nothing more is coming
+    if let Some((chunk_id, recording_start_time)) = last_chunk {
+        match send_frame(&client, &frame_url, &session_id, &state.config, &backlog, backlog_start, true).await {
+            Ok(response) => {
+                for chunk_id in backlog_chunks.drain(..) {
+                    session_recovery::chunk_done(chunk_id);
+                }
+                state.apply(response, chunk_id, recording_start_time);
+            }
+            Err(e) => log_error!("Worker {}: Failed to close streaming session: {}", worker_id, e),
+        }
+    }
+    state.publish_timeout();
+    if let Some(update) = state.accumulator.take_partial() {
+        if let Err(e) = publish_update(&app_handle, &update) {
+            log_error!("Worker {}: Failed to send final partial transcript: {}", worker_id, e);
+        }
+    }
+    state.show_partial("", 0.0);
+
+    worker_finished(&app_handle, &handles).await;
+    log_info!("Streaming worker {} ended", worker_id);
+}
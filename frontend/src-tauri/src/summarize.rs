@@ -0,0 +1,232 @@
+// Meeting summaries generated by the app itself with any configured provider; with a
+// local Ollama model they work without the backend or a network connection. Long
+// transcripts are split into chunks that each get condensed into notes first; the notes
+// (or a short transcript as is) are then turned into one summary, which the model is asked
+// to return as JSON in the `MeetingSummary` shape.
+//
+// A prompt template (see `prompt_templates`) can replace the default instructions for the
+// final pass, so each kind of meeting gets the summary it needs.
+//
+// Generation is streamed: every token is passed to the frontend as a `summary-progress`
+// event, so the user can watch the notes being written instead of waiting on a spinner.
+use std::time::Instant;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::calendar;
+use crate::llm::{self, Prompt, Provider};
+use crate::prompt_templates::{self, TemplateContext};
+use crate::summary::{BlockKind, MeetingSummary, SummaryActionItem, SummaryBlock, SummarySection, SUMMARY_SCHEMA_VERSION};
+use crate::usage_stats::{self, UsageKind};
+
+// Leaves room for the prompt and the answer in a 4k-8k context
+pub(crate) const CHUNK_TOKENS: usize = 3_000;
+
+const NOTES_PROMPT: &str = "You are taking notes on part {part} of {parts} of a meeting transcript. \
+Write concise bullet points covering what was discussed, decisions made, action items (with owners \
+when named), risks and who took part. Only use information from the transcript.";
+
+const SUMMARY_PROMPT: &str = "Summarize the meeting below. Answer with a single JSON object and nothing \
+else, in this shape: {\"meeting_name\": string, \"agenda\": [string], \"key_points\": [string], \
+\"decisions\": [string], \"action_items\": [{\"text\": string, \"owner\": string or null}], \
+\"risks\": [string], \"attendees\": [string]}. Use empty lists for anything the meeting did not cover \
+and only use information from the meeting.";
+
+#[derive(Debug, Clone, Serialize)]
+struct SummaryProgress<'a> {
+    /// `notes` while a chunk of the transcript is condensed, `summary` for the final pass
+    stage: &'a str,
+    part: usize,
+    parts: usize,
+    token: &'a str,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct GeneratedSummary {
+    meeting_name: String,
+    agenda: Vec<String>,
+    key_points: Vec<String>,
+    decisions: Vec<String>,
+    action_items: Vec<SummaryActionItem>,
+    risks: Vec<String>,
+    attendees: Vec<String>,
+}
+
+/// Split a transcript into chunks of about `max_tokens` for `provider`, breaking between
+/// lines only (a single overlong line becomes a chunk of its own).
+pub(crate) fn chunk_transcript(transcript: &str, max_tokens: usize, provider: &dyn Provider) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_tokens = 0;
+    for line in transcript.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let line_tokens = provider.count_tokens(line);
+        if !current.is_empty() && current_tokens + line_tokens > max_tokens {
+            chunks.push(std::mem::take(&mut current));
+            current_tokens = 0;
+        }
+        current.push_str(line);
+        current.push('\n');
+        current_tokens += line_tokens;
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// Run one prompt, emitting each token as it arrives, and return the full answer.
+async fn generate<R: Runtime>(
+    app: &AppHandle<R>,
+    provider: &dyn Provider,
+    prompt: Prompt<'_>,
+    stage: &str,
+    part: usize,
+    parts: usize,
+) -> Result<String, String> {
+    let mut on_token = |token: &str| {
+        let progress = SummaryProgress { stage, part, parts, token };
+        if let Err(e) = app.emit("summary-progress", progress) {
+            log_error!("Failed to emit summary-progress event: {}", e);
+        }
+    };
+    provider.stream(prompt, &mut on_token).await
+}
+
+fn bullet_section(title: &str, items: &[String]) -> Option<SummarySection> {
+    (!items.is_empty()).then(|| SummarySection {
+        title: title.to_string(),
+        blocks: items
+            .iter()
+            .map(|item| SummaryBlock { kind: BlockKind::Bullet, content: item.clone() })
+            .collect(),
+    })
+}
+
+fn into_meeting_summary(generated: GeneratedSummary, fallback_name: &str) -> Result<MeetingSummary, String> {
+    let action_items: Vec<SummaryActionItem> = generated
+        .action_items
+        .into_iter()
+        .filter(|item| !item.text.trim().is_empty())
+        .collect();
+    let action_lines: Vec<String> = action_items
+        .iter()
+        .map(|item| match &item.owner {
+            Some(owner) => format!("{} ({})", item.text, owner),
+            None => item.text.clone(),
+        })
+        .collect();
+    let sections = [
+        bullet_section("Agenda", &generated.agenda),
+        bullet_section("Key Points", &generated.key_points),
+        bullet_section("Decisions", &generated.decisions),
+        bullet_section("Action Items", &action_lines),
+        bullet_section("Risks", &generated.risks),
+        bullet_section("Attendees", &generated.attendees),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let meeting_name = if generated.meeting_name.trim().is_empty() {
+        fallback_name.to_string()
+    } else {
+        generated.meeting_name
+    };
+
+    let summary = MeetingSummary {
+        version: SUMMARY_SCHEMA_VERSION,
+        meeting_name,
+        agenda: generated.agenda,
+        key_points: generated.key_points,
+        decisions: generated.decisions,
+        action_items,
+        risks: generated.risks,
+        attendees: generated.attendees,
+        sections,
+    };
+    // Same checks as a summary read back from the backend
+    MeetingSummary::from_value(&serde_json::to_value(&summary).map_err(|e| e.to_string())?)
+}
+
+/// Summarize a transcript with the configured model, or with `provider` and `model` when
+/// both are given (a local Ollama model then works fully offline), following the prompt
+/// template `template_id` if given. Progress is reported through `summary-progress` events;
+/// the result is a structured summary ready to be saved.
+#[tauri::command]
+pub async fn summarize_transcript<R: Runtime>(
+    app: AppHandle<R>,
+    transcript: String,
+    meeting_name: Option<String>,
+    provider: Option<String>,
+    model: Option<String>,
+    template_id: Option<String>,
+    auth_token: Option<String>,
+) -> Result<MeetingSummary, String> {
+    let template = template_id.map(|id| prompt_templates::get(&app, &id)).transpose()?;
+    let config = llm::resolve(&app, provider, model, auth_token).await?;
+    let provider = llm::provider(&config)?;
+    let started = Instant::now();
+    let chunks = chunk_transcript(&transcript, CHUNK_TOKENS, provider.as_ref());
+    if chunks.is_empty() {
+        return Err("The transcript is empty".to_string());
+    }
+    log_info!(
+        "summarize_transcript called with {} model {} for {} chunk(s)",
+        provider.name(),
+        provider.model(),
+        chunks.len()
+    );
+
+    let meeting = if chunks.len() == 1 {
+        chunks.into_iter().next().unwrap_or_default()
+    } else {
+        let parts = chunks.len();
+        let mut notes = Vec::with_capacity(parts);
+        for (index, chunk) in chunks.iter().enumerate() {
+            let instructions = NOTES_PROMPT
+                .replace("{part}", &(index + 1).to_string())
+                .replace("{parts}", &parts.to_string());
+            let user = format!("Transcript:\n{}", chunk);
+            let prompt = Prompt { system: &instructions, user: &user, json_output: false };
+            notes.push(generate(&app, provider.as_ref(), prompt, "notes", index + 1, parts).await?);
+        }
+        notes.join("\n\n")
+    };
+
+    let meeting = match meeting_name.as_deref().filter(|name| !name.trim().is_empty()) {
+        Some(name) => format!("Meeting title: {}\n\n{}", name, meeting),
+        None => meeting,
+    };
+    let user = match &template {
+        Some(template) => {
+            log_info!("Summarizing with prompt template '{}'", template.name);
+            let context = TemplateContext {
+                transcript: &meeting,
+                attendees: &calendar::get_speaker_candidates(),
+                date: chrono::Local::now().date_naive(),
+            };
+            prompt_templates::render(template, &context)
+        }
+        None => meeting,
+    };
+    let prompt = Prompt { system: SUMMARY_PROMPT, user: &user, json_output: true };
+    let response = generate(&app, provider.as_ref(), prompt, "summary", 1, 1).await?;
+
+    let generated: GeneratedSummary = match llm::extract_json(&response) {
+        Some(value) => serde_json::from_value(value).map_err(|e| format!("Model returned an invalid summary: {}", e))?,
+        None => {
+            log_warn!("Model response contained no JSON summary");
+            return Err("The model did not return a summary; try again or pick another model".to_string());
+        }
+    };
+    let summary = into_meeting_summary(generated, meeting_name.as_deref().unwrap_or_default())?;
+    log_info!("Generated summary with {} sections", summary.sections.len());
+    usage_stats::record(
+        &app,
+        UsageKind::Summary,
+        Some(started.elapsed().as_secs_f64()),
+        Some(&format!("{} / {}", provider.name(), provider.model())),
+    );
+    Ok(summary)
+}
@@ -0,0 +1,302 @@
+// Typed meeting summaries. The backend stores whatever JSON the summarizer or the editor
+// produced; this is the one place that JSON is interpreted, so exports and integrations work
+// with `MeetingSummary` instead of probing `serde_json::Value`s.
+//
+// Two shapes are accepted: the versioned schema below (anything with a "version" key), and
+// the section map the summarizer has always produced (`MeetingName`, `_section_order` and
+// one `{title, blocks}` object per section), which is classified into the typed fields by
+// section title.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, warn as log_warn};
+
+use crate::api::make_api_request;
+use crate::secrets::{self, KeyKind};
+
+pub const SUMMARY_SCHEMA_VERSION: u32 = 1;
+
+// Keys in the section map that are metadata rather than sections
+const SUMMARY_METADATA_KEYS: [&str; 2] = ["MeetingName", "_section_order"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    Heading1,
+    Heading2,
+    Bullet,
+    Text,
+}
+
+impl BlockKind {
+    fn from_block_type(block_type: &str) -> Self {
+        match block_type {
+            "heading1" => BlockKind::Heading1,
+            "heading2" => BlockKind::Heading2,
+            "bullet" => BlockKind::Bullet,
+            _ => BlockKind::Text,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryBlock {
+    pub kind: BlockKind,
+    pub content: String,
+}
+
+/// A section of the meeting notes as written, in display order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummarySection {
+    pub title: String,
+    #[serde(default)]
+    pub blocks: Vec<SummaryBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryActionItem {
+    pub text: String,
+    #[serde(default)]
+    pub owner: Option<String>,
+}
+
+/// Newer versions are read as far as this version understands them; fields added later
+/// are ignored rather than rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeetingSummary {
+    pub version: u32,
+    #[serde(default)]
+    pub meeting_name: String,
+    #[serde(default)]
+    pub agenda: Vec<String>,
+    #[serde(default)]
+    pub key_points: Vec<String>,
+    #[serde(default)]
+    pub decisions: Vec<String>,
+    #[serde(default)]
+    pub action_items: Vec<SummaryActionItem>,
+    #[serde(default)]
+    pub risks: Vec<String>,
+    #[serde(default)]
+    pub attendees: Vec<String>,
+    /// The full notes; the typed fields above are drawn from these for legacy summaries
+    #[serde(default)]
+    pub sections: Vec<SummarySection>,
+}
+
+impl MeetingSummary {
+    /// Read a stored summary in either shape, rejecting JSON that is neither.
+    pub fn from_value(value: &Value) -> Result<Self, String> {
+        let object = value.as_object().ok_or("Summary must be a JSON object")?;
+        let summary = if object.contains_key("version") {
+            serde_json::from_value::<MeetingSummary>(value.clone())
+                .map_err(|e| format!("Invalid meeting summary: {}", e))?
+        } else {
+            Self::from_section_map(value)?
+        };
+        summary.validate()?;
+        Ok(summary)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.version == 0 {
+            return Err("Summary version must be at least 1".to_string());
+        }
+        if self.version > SUMMARY_SCHEMA_VERSION {
+            log_warn!(
+                "Summary has schema version {}, newer than {}; unknown fields are ignored",
+                self.version, SUMMARY_SCHEMA_VERSION
+            );
+        }
+        if self.action_items.iter().any(|item| item.text.trim().is_empty()) {
+            return Err("Summary action items must have text".to_string());
+        }
+        if self.sections.iter().any(|section| section.title.trim().is_empty()) {
+            return Err("Summary sections must have a title".to_string());
+        }
+        Ok(())
+    }
+
+    // Sections follow `_section_order` when present, otherwise the order of the JSON object
+    fn from_section_map(value: &Value) -> Result<Self, String> {
+        let object = value.as_object().ok_or("Summary must be a JSON object")?;
+        let order: Vec<String> = match value.get("_section_order") {
+            Some(order) => serde_json::from_value(order.clone())
+                .map_err(|e| format!("Invalid summary section order: {}", e))?,
+            None => object
+                .keys()
+                .filter(|k| !SUMMARY_METADATA_KEYS.contains(&k.as_str()))
+                .cloned()
+                .collect(),
+        };
+
+        let mut summary = MeetingSummary {
+            version: SUMMARY_SCHEMA_VERSION,
+            meeting_name: value.get("MeetingName").and_then(|v| v.as_str()).unwrap_or_default().to_string(),
+            agenda: Vec::new(),
+            key_points: Vec::new(),
+            decisions: Vec::new(),
+            action_items: Vec::new(),
+            risks: Vec::new(),
+            attendees: Vec::new(),
+            sections: Vec::new(),
+        };
+        for key in &order {
+            let Some(section) = object.get(key).filter(|section| section.is_object()) else { continue };
+            let title = section.get("title").and_then(|t| t.as_str()).unwrap_or(key).to_string();
+            let blocks: Vec<SummaryBlock> = section
+                .get("blocks")
+                .and_then(|b| b.as_array())
+                .map(|blocks| blocks.iter().filter_map(parse_block).collect())
+                .unwrap_or_default();
+            summary.classify(&title, &blocks);
+            summary.sections.push(SummarySection { title, blocks });
+        }
+        Ok(summary)
+    }
+
+    // Route a legacy section's items into the typed field its title names
+    fn classify(&mut self, title: &str, blocks: &[SummaryBlock]) {
+        let Some(field) = SummaryField::for_section_title(title) else { return };
+        let items = blocks
+            .iter()
+            .filter(|block| matches!(block.kind, BlockKind::Bullet | BlockKind::Text))
+            .map(|block| block.content.clone());
+        match field {
+            SummaryField::Agenda => self.agenda.extend(items),
+            SummaryField::ActionItems => {
+                self.action_items.extend(items.map(|text| SummaryActionItem { text, owner: None }))
+            }
+            SummaryField::Decisions => self.decisions.extend(items),
+            SummaryField::Risks => self.risks.extend(items),
+            SummaryField::Attendees => self.attendees.extend(items),
+            SummaryField::KeyPoints => self.key_points.extend(items),
+        }
+    }
+}
+
+/// The typed `MeetingSummary` fields a notes section can stand for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SummaryField {
+    Agenda,
+    KeyPoints,
+    Decisions,
+    ActionItems,
+    Risks,
+    Attendees,
+}
+
+impl SummaryField {
+    /// Which field a section with this title holds, judged by the words the summarizer uses.
+    pub fn for_section_title(title: &str) -> Option<Self> {
+        let title = title.to_lowercase();
+        if title.contains("agenda") {
+            Some(SummaryField::Agenda)
+        } else if title.contains("action") {
+            Some(SummaryField::ActionItems)
+        } else if title.contains("decision") {
+            Some(SummaryField::Decisions)
+        } else if title.contains("risk") || title.contains("blocker") {
+            Some(SummaryField::Risks)
+        } else if title.contains("people") || title.contains("attendee") || title.contains("participant") {
+            Some(SummaryField::Attendees)
+        } else if title.contains("summary") || title.contains("key point") {
+            Some(SummaryField::KeyPoints)
+        } else {
+            None
+        }
+    }
+}
+
+fn parse_block(block: &Value) -> Option<SummaryBlock> {
+    let content = block.get("content")?.as_str()?.trim();
+    if content.is_empty() {
+        return None;
+    }
+    let block_type = block.get("type").and_then(|t| t.as_str()).unwrap_or("text");
+    Some(SummaryBlock {
+        kind: BlockKind::from_block_type(block_type),
+        content: content.to_string(),
+    })
+}
+
+/// A provider and model to summarize with, as configured in the model settings.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SummaryModelChoice {
+    pub provider: String,
+    pub model: String,
+    /// Filled in from the keychain before the request goes to the backend
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CompareSummariesRequest<'a> {
+    meeting_id: &'a str,
+    models: &'a [SummaryModelChoice],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    custom_prompt: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawComparedSummary {
+    provider: String,
+    model: String,
+    summary: Option<Value>,
+    duration_seconds: f64,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CompareSummariesResponse {
+    results: Vec<RawComparedSummary>,
+}
+
+/// One model's result in a comparison; `error` is set instead of `summary` when it failed.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedSummary {
+    pub provider: String,
+    pub model: String,
+    pub summary: Option<MeetingSummary>,
+    pub duration_seconds: f64,
+    pub error: Option<String>,
+}
+
+/// Summarize a meeting with two or three models at once and return the results side by
+/// side, in the order the models were given. The meeting's saved summary is not touched.
+#[tauri::command]
+pub async fn compare_summaries<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_id: String,
+    models: Vec<SummaryModelChoice>,
+    custom_prompt: Option<String>,
+    auth_token: Option<String>,
+) -> Result<Vec<ComparedSummary>, String> {
+    log_info!("compare_summaries called for meeting_id: {} with {} models", meeting_id, models.len());
+    if !(2..=3).contains(&models.len()) {
+        return Err("Compare two or three models".to_string());
+    }
+
+    let mut models = models;
+    for choice in &mut models {
+        choice.api_key = secrets::api_key(&app, KeyKind::Summary, &choice.provider, auth_token.clone()).await?;
+    }
+    let request = CompareSummariesRequest { meeting_id: &meeting_id, models: &models, custom_prompt: custom_prompt.as_deref() };
+    let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+    let response =
+        make_api_request::<R, CompareSummariesResponse>(&app, "/compare-summaries", "POST", Some(&body), None, auth_token)
+            .await?;
+
+    Ok(response
+        .results
+        .into_iter()
+        .map(|raw| {
+            let (summary, error) = match raw.summary.as_ref().map(MeetingSummary::from_value) {
+                Some(Ok(summary)) => (Some(summary), raw.error),
+                Some(Err(e)) => (None, Some(e)),
+                None => (None, raw.error.or_else(|| Some("No summary was produced".to_string()))),
+            };
+            ComparedSummary { provider: raw.provider, model: raw.model, summary, duration_seconds: raw.duration_seconds, error }
+        })
+        .collect())
+}
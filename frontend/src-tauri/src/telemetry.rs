@@ -0,0 +1,215 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
+// Upper bounds (inclusive) of the latency histogram buckets; the last bucket is open-ended
+const LATENCY_BUCKETS_MS: [u64; 10] = [10, 25, 50, 100, 250, 500, 1000, 2500, 5000, 10000];
+const RECENT_WINDOW: usize = 200; // Calls used for error rate and percentiles
+const DEGRADED_ERROR_RATE: f64 = 0.1;
+const DOWN_ERROR_RATE: f64 = 0.5;
+
+/// External services the app talks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Dependency {
+    WhisperServer,
+    Backend,
+    Ollama,
+    CloudProvider,
+    /// Webhooks and other third-party services meetings are sent to
+    Integration,
+}
+
+impl Dependency {
+    const ALL: [Dependency; 5] = [
+        Dependency::WhisperServer,
+        Dependency::Backend,
+        Dependency::Ollama,
+        Dependency::CloudProvider,
+        Dependency::Integration,
+    ];
+
+    // p95 latency above which the dependency is reported as slow
+    fn slow_threshold_ms(&self) -> u64 {
+        match self {
+            Dependency::WhisperServer => 10000, // a 30s chunk should transcribe well under 10s
+            Dependency::Backend => 2000,
+            Dependency::Ollama => 2000,
+            Dependency::CloudProvider => 5000,
+            Dependency::Integration => 5000,
+        }
+    }
+}
+
+#[derive(Default)]
+struct DependencyStats {
+    bucket_counts: [u64; LATENCY_BUCKETS_MS.len() + 1],
+    total_calls: u64,
+    error_count: u64,
+    total_latency_ms: u64,
+    max_latency_ms: u64,
+    recent: VecDeque<(u64, bool)>,
+    last_error: Option<String>,
+    last_error_at: Option<DateTime<Utc>>,
+    last_success_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBucket {
+    /// Upper bound of the bucket in milliseconds, `None` for the overflow bucket
+    pub le_ms: Option<u64>,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyHealth {
+    pub dependency: Dependency,
+    pub status: String,
+    pub total_calls: u64,
+    pub error_count: u64,
+    pub recent_error_rate: f64,
+    pub avg_latency_ms: f64,
+    pub p50_latency_ms: u64,
+    pub p95_latency_ms: u64,
+    pub max_latency_ms: u64,
+    pub histogram: Vec<HistogramBucket>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+static STATS: Lazy<Mutex<HashMap<Dependency, DependencyStats>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Record one call to an external dependency. `error` is `None` for successful calls.
+pub fn record(dependency: Dependency, latency: Duration, error: Option<&str>) {
+    let latency_ms = latency.as_millis() as u64;
+    let mut guard = match STATS.lock() {
+        Ok(guard) => guard,
+        Err(_) => return,
+    };
+    let stats = guard.entry(dependency).or_default();
+
+    let bucket = LATENCY_BUCKETS_MS
+        .iter()
+        .position(|&upper| latency_ms <= upper)
+        .unwrap_or(LATENCY_BUCKETS_MS.len());
+    stats.bucket_counts[bucket] += 1;
+    stats.total_calls += 1;
+    stats.total_latency_ms += latency_ms;
+    stats.max_latency_ms = stats.max_latency_ms.max(latency_ms);
+
+    if stats.recent.len() >= RECENT_WINDOW {
+        stats.recent.pop_front();
+    }
+    stats.recent.push_back((latency_ms, error.is_none()));
+
+    match error {
+        Some(message) => {
+            stats.error_count += 1;
+            stats.last_error = Some(message.to_string());
+            stats.last_error_at = Some(Utc::now());
+        }
+        None => stats.last_success_at = Some(Utc::now()),
+    }
+}
+
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let index = ((sorted.len() - 1) as f64 * p).round() as usize;
+    sorted[index.min(sorted.len() - 1)]
+}
+
+fn health_for(dependency: Dependency, stats: Option<&DependencyStats>) -> DependencyHealth {
+    let stats = match stats {
+        Some(stats) => stats,
+        None => {
+            return DependencyHealth {
+                dependency,
+                status: "unknown".to_string(),
+                total_calls: 0,
+                error_count: 0,
+                recent_error_rate: 0.0,
+                avg_latency_ms: 0.0,
+                p50_latency_ms: 0,
+                p95_latency_ms: 0,
+                max_latency_ms: 0,
+                histogram: Vec::new(),
+                last_error: None,
+                last_error_at: None,
+                last_success_at: None,
+            }
+        }
+    };
+
+    let mut latencies: Vec<u64> = stats.recent.iter().map(|(latency, _)| *latency).collect();
+    latencies.sort_unstable();
+    let recent_errors = stats.recent.iter().filter(|(_, ok)| !ok).count();
+    let recent_error_rate = if stats.recent.is_empty() {
+        0.0
+    } else {
+        recent_errors as f64 / stats.recent.len() as f64
+    };
+    let p95 = percentile(&latencies, 0.95);
+
+    let status = if stats.recent.is_empty() {
+        "unknown"
+    } else if recent_error_rate >= DOWN_ERROR_RATE {
+        "down"
+    } else if recent_error_rate >= DEGRADED_ERROR_RATE || p95 > dependency.slow_threshold_ms() {
+        "degraded"
+    } else {
+        "healthy"
+    };
+
+    let histogram = stats
+        .bucket_counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| HistogramBucket {
+            le_ms: LATENCY_BUCKETS_MS.get(i).copied(),
+            count,
+        })
+        .collect();
+
+    DependencyHealth {
+        dependency,
+        status: status.to_string(),
+        total_calls: stats.total_calls,
+        error_count: stats.error_count,
+        recent_error_rate,
+        avg_latency_ms: if stats.total_calls > 0 {
+            stats.total_latency_ms as f64 / stats.total_calls as f64
+        } else {
+            0.0
+        },
+        p50_latency_ms: percentile(&latencies, 0.5),
+        p95_latency_ms: p95,
+        max_latency_ms: stats.max_latency_ms,
+        histogram,
+        last_error: stats.last_error.clone(),
+        last_error_at: stats.last_error_at,
+        last_success_at: stats.last_success_at,
+    }
+}
+
+/// Snapshot of every dependency, including ones that have not been called yet.
+pub fn snapshot() -> Vec<DependencyHealth> {
+    let guard = match STATS.lock() {
+        Ok(guard) => guard,
+        Err(_) => return Vec::new(),
+    };
+    Dependency::ALL
+        .iter()
+        .map(|dependency| health_for(*dependency, guard.get(dependency)))
+        .collect()
+}
+
+#[tauri::command]
+pub fn get_dependency_health() -> Vec<DependencyHealth> {
+    snapshot()
+}
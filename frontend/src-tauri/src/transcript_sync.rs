@@ -0,0 +1,606 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use tokio::sync::Notify;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::activity::{self, ActivityKind};
+use crate::crash;
+use crate::api::{make_api_request, DeleteMeetingRequest, SaveMeetingMetadataRequest, TranscriptSegment};
+use crate::meeting_metadata;
+
+// Incremental sync configuration
+const SYNC_INTERVAL_MS: u64 = 5000; // Push pending segments every 5 seconds
+const MAX_BACKOFF_MS: u64 = 60000; // Never wait more than a minute between retries
+const FINAL_FLUSH_ATTEMPTS: u32 = 3; // Attempts made when the sync session is stopped
+const FAILURES_BEFORE_ACTIVITY: u32 = 3; // Consecutive failures before the activity feed hears about it
+const CONCURRENCY_CHECK_INTERVAL: Duration = Duration::from_secs(30); // Look for the same meeting recorded elsewhere
+const DEVICE_ID_KEY: &str = "deviceId";
+
+#[derive(Debug, Serialize)]
+struct SaveTranscriptBatchRequest {
+    meeting_title: String,
+    transcripts: Vec<TranscriptSegment>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct AppendTranscriptRequest {
+    meeting_id: String,
+    transcripts: Vec<TranscriptSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SaveTranscriptBatchResponse {
+    meeting_id: String,
+}
+
+#[derive(Debug, Serialize)]
+struct FindConcurrentRecordingsRequest {
+    meeting_id: String,
+    device_id: String,
+    started_at: String,
+    ended_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FindConcurrentRecordingsResponse {
+    meetings: Vec<ConcurrentRecording>,
+}
+
+#[derive(Debug, Serialize)]
+struct MergeMeetingsRequest {
+    source_meeting_id: String,
+    target_meeting_id: String,
+}
+
+/// A meeting of the same account recorded on another device at the same time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConcurrentRecording {
+    pub meeting_id: String,
+    pub title: String,
+    pub device_id: Option<String>,
+    pub device_name: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+}
+
+/// What to do about a meeting recorded twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConcurrentRecordingResolution {
+    /// Keep both meetings as they are
+    KeepBoth,
+    /// Move this device's transcript into the other meeting and keep appending there
+    MergeIntoOther,
+    /// Delete this device's copy on the backend and stop syncing it; the recording stays local
+    DiscardThis,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ConcurrentRecordingDetected {
+    meeting_id: String,
+    concurrent: Vec<ConcurrentRecording>,
+    options: Vec<ConcurrentRecordingResolution>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct TranscriptSyncStatus {
+    pub active: bool,
+    pub meeting_id: Option<String>,
+    pub pending_segments: usize,
+    pub synced_segments: usize,
+    pub last_error: Option<String>,
+}
+
+struct SyncSession {
+    meeting_title: String,
+    // Makes segment ids unique per session, so the backend can skip lines it already stored
+    // without mistaking them for lines another recording merged into the same meeting
+    segment_prefix: String,
+    meeting_id: Option<String>,
+    auth_token: Option<String>,
+    pending: Vec<TranscriptSegment>,
+    synced: usize,
+    last_error: Option<String>,
+    stopping: bool,
+    task: Option<tokio::task::JoinHandle<()>>,
+    // Concurrent meetings already reported, so each is raised once
+    reported_concurrent: HashSet<String>,
+    last_concurrency_check: Option<Instant>,
+    // Set when this device's copy was discarded; nothing more is pushed
+    detached: bool,
+}
+
+impl SyncSession {
+    fn status(&self) -> TranscriptSyncStatus {
+        TranscriptSyncStatus {
+            active: !self.stopping,
+            meeting_id: self.meeting_id.clone(),
+            pending_segments: self.pending.len(),
+            synced_segments: self.synced,
+            last_error: self.last_error.clone(),
+        }
+    }
+}
+
+static SYNC_SESSION: Lazy<Mutex<Option<SyncSession>>> = Lazy::new(|| Mutex::new(None));
+static STOP_SIGNAL: Lazy<Notify> = Lazy::new(Notify::new);
+// Held for the whole of a push, so a merge can wait for one in flight and keep others out
+static FLUSH_LOCK: Lazy<tokio::sync::Mutex<()>> = Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// Queue a finalized transcript line for the next incremental push.
+/// Does nothing when no sync session is active.
+pub fn enqueue_segment(sequence_id: u64, text: &str, timestamp: &str) {
+    if let Ok(mut guard) = SYNC_SESSION.lock() {
+        if let Some(session) = guard.as_mut().filter(|session| !session.detached) {
+            session.pending.push(TranscriptSegment {
+                id: format!("seg-{}-{}", session.segment_prefix, sequence_id),
+                text: text.to_string(),
+                timestamp: timestamp.to_string(),
+            });
+        }
+    }
+}
+
+fn current_status() -> TranscriptSyncStatus {
+    SYNC_SESSION
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(|session| session.status()))
+        .unwrap_or(TranscriptSyncStatus {
+            active: false,
+            meeting_id: None,
+            pending_segments: 0,
+            synced_segments: 0,
+            last_error: None,
+        })
+}
+
+// Push all pending segments in one request. The first successful push creates the
+// meeting on the backend; later pushes append to it.
+async fn flush_pending<R: Runtime>(app: &AppHandle<R>) -> Result<usize, String> {
+    let _flushing = FLUSH_LOCK.lock().await;
+    flush_locked(app).await
+}
+
+// Caller holds FLUSH_LOCK
+async fn flush_locked<R: Runtime>(app: &AppHandle<R>) -> Result<usize, String> {
+    let (batch, meeting_id, meeting_title, auth_token) = {
+        let mut guard = SYNC_SESSION.lock().map_err(|e| e.to_string())?;
+        let session = match guard.as_mut() {
+            Some(session) => session,
+            None => return Ok(0),
+        };
+        if session.detached {
+            session.pending.clear();
+        }
+        if session.pending.is_empty() {
+            return Ok(0);
+        }
+        (
+            std::mem::take(&mut session.pending),
+            session.meeting_id.clone(),
+            session.meeting_title.clone(),
+            session.auth_token.clone(),
+        )
+    };
+
+    let batch_len = batch.len();
+    let result = match &meeting_id {
+        Some(id) => {
+            let request = AppendTranscriptRequest { meeting_id: id.clone(), transcripts: batch };
+            let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+            make_api_request::<R, serde_json::Value>(app, "/append-transcript", "POST", Some(&body), None, auth_token)
+                .await
+                .map(|_| id.clone())
+                .map_err(|e| (e.to_string(), request.transcripts))
+        }
+        None => {
+            let request = SaveTranscriptBatchRequest {
+                meeting_title,
+                transcripts: batch,
+                metadata: meeting_metadata::snapshot(),
+            };
+            let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+            make_api_request::<R, SaveTranscriptBatchResponse>(app, "/save-transcript", "POST", Some(&body), None, auth_token)
+                .await
+                .map(|response| response.meeting_id)
+                .map_err(|e| (e.to_string(), request.transcripts))
+        }
+    };
+
+    let mut guard = SYNC_SESSION.lock().map_err(|e| e.to_string())?;
+    let session = match guard.as_mut() {
+        Some(session) => session,
+        None => return Ok(0),
+    };
+
+    match result {
+        Ok(id) => {
+            session.meeting_id.get_or_insert(id);
+            session.synced += batch_len;
+            session.last_error = None;
+            Ok(batch_len)
+        }
+        Err((e, mut failed_batch)) => {
+            // Put the batch back in front of anything queued while the request was in flight
+            failed_batch.extend(session.pending.drain(..));
+            session.pending = failed_batch;
+            session.last_error = Some(e.clone());
+            Err(e)
+        }
+    }
+}
+
+// Settings can change after the meeting was created, so the final metadata is pushed at the end
+async fn push_metadata<R: Runtime>(app: &AppHandle<R>) {
+    let (meeting_id, auth_token) = match SYNC_SESSION.lock() {
+        Ok(guard) => match guard.as_ref() {
+            Some(SyncSession { meeting_id: Some(id), auth_token, .. }) => (id.clone(), auth_token.clone()),
+            _ => return,
+        },
+        Err(_) => return,
+    };
+    let metadata = match meeting_metadata::snapshot() {
+        Some(metadata) => metadata,
+        None => return,
+    };
+
+    let request = SaveMeetingMetadataRequest { meeting_id, metadata };
+    let body = match serde_json::to_string(&request) {
+        Ok(body) => body,
+        Err(e) => {
+            log_error!("Failed to serialize meeting metadata: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = make_api_request::<R, serde_json::Value>(app, "/save-meeting-metadata", "POST", Some(&body), None, auth_token).await {
+        log_warn!("Failed to save meeting metadata for {}: {}", request.meeting_id, e);
+    }
+}
+
+/// Identifies this installation, so recordings of one meeting from two machines can be told apart.
+pub fn device_id<R: Runtime>(app: &AppHandle<R>) -> String {
+    let store = match app.store("store.json") {
+        Ok(store) => store,
+        Err(e) => {
+            log_warn!("Failed to open store for the device id: {}", e);
+            return uuid::Uuid::new_v4().to_string();
+        }
+    };
+    if let Some(id) = store.get(DEVICE_ID_KEY).and_then(|v| v.as_str().map(str::to_string)) {
+        return id;
+    }
+    let id = uuid::Uuid::new_v4().to_string();
+    store.set(DEVICE_ID_KEY, serde_json::json!(id));
+    if let Err(e) = store.save() {
+        log_warn!("Failed to save the device id: {}", e);
+    }
+    id
+}
+
+fn device_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| std::env::consts::OS.to_string())
+}
+
+fn utc_now() -> String {
+    chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+}
+
+/// Note which device is recording and since when; the backend compares this across meetings.
+pub fn begin_recording<R: Runtime>(app: &AppHandle<R>) {
+    meeting_metadata::set("recording", serde_json::json!({
+        "device_id": device_id(app),
+        "device_name": device_name(),
+        "started_at": utc_now(),
+        "ended_at": null,
+    }));
+}
+
+pub fn finish_recording() {
+    meeting_metadata::set_field("recording", "ended_at", serde_json::json!(utc_now()));
+}
+
+// Ask the backend for meetings recorded elsewhere at the same time and report new ones
+async fn check_concurrent_recordings<R: Runtime>(app: &AppHandle<R>) {
+    let (meeting_id, auth_token) = {
+        let Ok(mut guard) = SYNC_SESSION.lock() else {
+            return;
+        };
+        let Some(session) = guard.as_mut() else {
+            return;
+        };
+        let due = session.last_concurrency_check.map_or(true, |last| last.elapsed() >= CONCURRENCY_CHECK_INTERVAL);
+        let Some(meeting_id) = session.meeting_id.clone().filter(|_| due && !session.detached) else {
+            return;
+        };
+        session.last_concurrency_check = Some(Instant::now());
+        (meeting_id, session.auth_token.clone())
+    };
+    let Some(recording) = meeting_metadata::snapshot().and_then(|metadata| metadata.get("recording").cloned()) else {
+        return;
+    };
+    let field = |name: &str| recording.get(name).and_then(|v| v.as_str()).map(str::to_string);
+    let (Some(device_id), Some(started_at)) = (field("device_id"), field("started_at")) else {
+        return;
+    };
+
+    let request = FindConcurrentRecordingsRequest { meeting_id: meeting_id.clone(), device_id, started_at, ended_at: field("ended_at") };
+    let body = match serde_json::to_string(&request) {
+        Ok(body) => body,
+        Err(e) => {
+            log_error!("Failed to serialize concurrent recording request: {}", e);
+            return;
+        }
+    };
+    let response = match make_api_request::<R, FindConcurrentRecordingsResponse>(
+        app, "/find-concurrent-recordings", "POST", Some(&body), None, auth_token,
+    )
+    .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            log_warn!("Failed to check for concurrent recordings: {}", e);
+            return;
+        }
+    };
+
+    let concurrent: Vec<ConcurrentRecording> = match SYNC_SESSION.lock() {
+        Ok(mut guard) => match guard.as_mut() {
+            Some(session) => response
+                .meetings
+                .into_iter()
+                .filter(|other| session.reported_concurrent.insert(other.meeting_id.clone()))
+                .collect(),
+            None => return,
+        },
+        Err(_) => return,
+    };
+    if concurrent.is_empty() {
+        return;
+    }
+
+    log_warn!(
+        "Meeting {} is also being recorded on {:?}",
+        meeting_id,
+        concurrent.iter().map(|other| other.device_name.as_deref().unwrap_or("another device")).collect::<Vec<_>>()
+    );
+    let event = ConcurrentRecordingDetected {
+        meeting_id,
+        concurrent,
+        options: vec![
+            ConcurrentRecordingResolution::KeepBoth,
+            ConcurrentRecordingResolution::MergeIntoOther,
+            ConcurrentRecordingResolution::DiscardThis,
+        ],
+    };
+    if let Err(e) = app.emit("concurrent-recording-detected", event) {
+        log_error!("Failed to emit concurrent-recording-detected event: {}", e);
+    }
+}
+
+fn report_sync_failure<R: Runtime>(app: &AppHandle<R>, error: &str) {
+    let status = current_status();
+    let message = format!("Transcript sync failed with {} lines not saved: {}", status.pending_segments, error);
+    activity::record(app, ActivityKind::SyncFailed, &message, status.meeting_id.as_deref(), None);
+}
+
+fn emit_status<R: Runtime>(app: &AppHandle<R>) {
+    if let Err(e) = app.emit("transcript-sync-status", current_status()) {
+        log_error!("Failed to emit transcript-sync-status event: {}", e);
+    }
+}
+
+async fn sync_loop<R: Runtime>(app: AppHandle<R>) {
+    log_info!("Transcript sync task started");
+    let mut failures: u32 = 0;
+
+    loop {
+        let delay = (SYNC_INTERVAL_MS * 2_u64.pow(failures.min(4))).min(MAX_BACKOFF_MS);
+        tokio::select! {
+            _ = tokio::time::sleep(Duration::from_millis(delay)) => {}
+            _ = STOP_SIGNAL.notified() => {}
+        }
+
+        let stopping = SYNC_SESSION
+            .lock()
+            .map(|guard| guard.as_ref().map(|s| s.stopping).unwrap_or(true))
+            .unwrap_or(true);
+
+        if stopping {
+            let mut last_error = None;
+            for attempt in 1..=FINAL_FLUSH_ATTEMPTS {
+                match flush_pending(&app).await {
+                    Ok(_) => {
+                        last_error = None;
+                        break;
+                    }
+                    Err(e) => {
+                        log_warn!("Final transcript sync attempt {} of {} failed: {}", attempt, FINAL_FLUSH_ATTEMPTS, e);
+                        last_error = Some(e);
+                        tokio::time::sleep(Duration::from_millis(500 * attempt as u64)).await;
+                    }
+                }
+            }
+            if let Some(e) = last_error {
+                crash::capture_task_error("Transcript sync", &e);
+                report_sync_failure(&app, &e);
+            }
+            push_metadata(&app).await;
+            emit_status(&app);
+            break;
+        }
+
+        match flush_pending(&app).await {
+            Ok(count) => {
+                if count > 0 {
+                    log_info!("Synced {} transcript segments", count);
+                    emit_status(&app);
+                }
+                failures = 0;
+                check_concurrent_recordings(&app).await;
+            }
+            Err(e) => {
+                failures += 1;
+                log_warn!("Transcript sync failed ({} consecutive failures): {}", failures, e);
+                if failures == FAILURES_BEFORE_ACTIVITY {
+                    report_sync_failure(&app, &e);
+                }
+                emit_status(&app);
+            }
+        }
+    }
+
+    log_info!("Transcript sync task ended");
+}
+
+#[tauri::command]
+pub async fn start_transcript_sync<R: Runtime>(
+    app: AppHandle<R>,
+    meeting_title: String,
+    meeting_id: Option<String>,
+    auth_token: Option<String>,
+) -> Result<(), String> {
+    // Untitled meetings take the title of the calendar event being recorded
+    let meeting_title = match crate::calendar::current_title() {
+        Some(title) if meeting_title.trim().is_empty() => title,
+        _ => meeting_title,
+    };
+    log_info!("start_transcript_sync called for meeting: {}, meeting_id: {:?}", meeting_title, meeting_id);
+
+    let mut guard = SYNC_SESSION.lock().map_err(|e| e.to_string())?;
+    match guard.as_ref() {
+        // The old loop works through the global session until stop_transcript_sync takes it,
+        // so replacing it now would send its final flush to the new meeting
+        Some(session) if session.stopping => {
+            return Err("The previous transcript sync is still finishing, try again in a moment".to_string())
+        }
+        Some(_) => return Err("Transcript sync already in progress".to_string()),
+        None => {}
+    }
+
+    let task = tokio::spawn(sync_loop(app.clone()));
+    *guard = Some(SyncSession {
+        meeting_title,
+        segment_prefix: uuid::Uuid::new_v4().simple().to_string()[..8].to_string(),
+        meeting_id,
+        auth_token,
+        pending: Vec::new(),
+        synced: 0,
+        last_error: None,
+        stopping: false,
+        task: Some(task),
+        reported_concurrent: HashSet::new(),
+        last_concurrency_check: None,
+        detached: false,
+    });
+
+    Ok(())
+}
+
+/// Stops the sync session after a final flush. Segments that could still not be
+/// pushed are reported in `pending_segments` so the caller can fall back to a bulk save.
+#[tauri::command]
+pub async fn stop_transcript_sync() -> Result<TranscriptSyncStatus, String> {
+    log_info!("stop_transcript_sync called");
+
+    let task = {
+        let mut guard = SYNC_SESSION.lock().map_err(|e| e.to_string())?;
+        match guard.as_mut() {
+            Some(session) => {
+                session.stopping = true;
+                session.task.take()
+            }
+            None => return Err("No transcript sync in progress".to_string()),
+        }
+    };
+
+    STOP_SIGNAL.notify_one();
+    if let Some(task) = task {
+        if let Err(e) = task.await {
+            log_error!("Transcript sync task failed: {}", e);
+        }
+    }
+
+    let session = SYNC_SESSION
+        .lock()
+        .map_err(|e| e.to_string())?
+        .take()
+        .ok_or_else(|| "No transcript sync in progress".to_string())?;
+
+    let mut status = session.status();
+    status.active = false;
+    Ok(status)
+}
+
+/// Act on a `concurrent-recording-detected` event for the meeting being synced.
+#[tauri::command]
+pub async fn resolve_concurrent_recording<R: Runtime>(
+    app: AppHandle<R>,
+    other_meeting_id: String,
+    resolution: ConcurrentRecordingResolution,
+) -> Result<TranscriptSyncStatus, String> {
+    log_info!("resolve_concurrent_recording called with {:?} for meeting {}", resolution, other_meeting_id);
+
+    let (meeting_id, auth_token) = {
+        let guard = SYNC_SESSION.lock().map_err(|e| e.to_string())?;
+        match guard.as_ref() {
+            Some(SyncSession { meeting_id: Some(id), auth_token, detached: false, .. }) => (id.clone(), auth_token.clone()),
+            _ => return Err("No synced meeting to resolve".to_string()),
+        }
+    };
+
+    match resolution {
+        ConcurrentRecordingResolution::KeepBoth => {}
+        ConcurrentRecordingResolution::MergeIntoOther => {
+            // Wait for a push in flight and hold off the sync loop until the session points at
+            // the other meeting, so no lines land on the meeting being merged away
+            let _flushing = FLUSH_LOCK.lock().await;
+            // Push what is queued first so it moves along with the rest; anything left over
+            // goes to the other meeting afterwards
+            if let Err(e) = flush_locked(&app).await {
+                log_warn!("Failed to push pending segments before merging: {}", e);
+            }
+            let request = MergeMeetingsRequest { source_meeting_id: meeting_id.clone(), target_meeting_id: other_meeting_id.clone() };
+            let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+            make_api_request::<R, serde_json::Value>(&app, "/merge-meetings", "POST", Some(&body), None, auth_token)
+                .await
+                .map_err(|e| format!("Failed to merge meetings: {}", e))?;
+            if let Some(session) = SYNC_SESSION.lock().map_err(|e| e.to_string())?.as_mut() {
+                session.meeting_id = Some(other_meeting_id.clone());
+            }
+            log_info!("Merged meeting {} into {}; syncing continues there", meeting_id, other_meeting_id);
+        }
+        ConcurrentRecordingResolution::DiscardThis => {
+            // Likewise, a push in flight must not recreate lines on the deleted meeting
+            let _flushing = FLUSH_LOCK.lock().await;
+            if let Some(session) = SYNC_SESSION.lock().map_err(|e| e.to_string())?.as_mut() {
+                session.detached = true;
+                session.meeting_id = None;
+                session.pending.clear();
+            }
+            let request = DeleteMeetingRequest { meeting_id: meeting_id.clone() };
+            let body = serde_json::to_string(&request).map_err(|e| e.to_string())?;
+            make_api_request::<R, serde_json::Value>(&app, "/delete-meeting", "POST", Some(&body), None, auth_token)
+                .await
+                .map_err(|e| format!("Failed to delete meeting {}: {}", meeting_id, e))?;
+            log_info!("Discarded meeting {} in favour of {}", meeting_id, other_meeting_id);
+        }
+    }
+
+    emit_status(&app);
+    Ok(current_status())
+}
+
+#[tauri::command]
+pub fn get_transcript_sync_status() -> TranscriptSyncStatus {
+    current_status()
+}
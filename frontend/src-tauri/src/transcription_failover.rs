@@ -0,0 +1,192 @@
+// Failover between transcription providers during a meeting. The configured engine is the
+// primary; once it has failed `after_failures` chunks in a row, the remaining chunks of the
+// recording go to the fallback provider instead (e.g. local whisper first, the OpenAI API
+// when the local model breaks). The switch lasts until the recording ends, so the
+// transcript doesn't alternate between providers sentence by sentence.
+//
+// Failover covers chunked transcription; streaming mode talks to the whisper server only.
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::audio::{self, AudioTranscriptionEngine};
+use crate::{meeting_metadata, TranscriptionConfig};
+
+const FAILOVER_KEY: &str = "transcriptionFailover";
+const OPENAI_PROVIDER: &str = "openai";
+const MAX_FAILURES_BEFORE_FAILOVER: u32 = 20;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FailoverConfig {
+    /// Engine setting name, as for the primary engine; None disables failover
+    pub fallback_engine: Option<String>,
+    /// Consecutive failed chunks on the primary before switching
+    pub after_failures: u32,
+    /// Model used when OpenAI transcribes
+    pub openai_model: String,
+}
+
+impl Default for FailoverConfig {
+    fn default() -> Self {
+        Self {
+            fallback_engine: None,
+            after_failures: 3,
+            openai_model: audio::openai::DEFAULT_MODEL.to_string(),
+        }
+    }
+}
+
+impl FailoverConfig {
+    pub fn fallback(&self) -> Option<AudioTranscriptionEngine> {
+        self.fallback_engine.as_deref().and_then(AudioTranscriptionEngine::from_setting)
+    }
+}
+
+/// When and why a recording switched providers.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderSwitch {
+    pub from: String,
+    pub to: String,
+    pub after_failures: u32,
+    pub error: String,
+    pub switched_at: String,
+}
+
+/// Failover progress of one recording, shared by its transcription workers.
+#[derive(Debug, Default)]
+pub(crate) struct FailoverState {
+    consecutive_failures: AtomicU32,
+    failed_over: AtomicBool,
+    switch: Mutex<Option<ProviderSwitch>>,
+}
+
+impl FailoverState {
+    /// Engine that should transcribe the next chunk.
+    pub(crate) fn active(&self, config: &TranscriptionConfig) -> AudioTranscriptionEngine {
+        match config.failover.fallback() {
+            Some(fallback) if self.failed_over.load(Ordering::SeqCst) => fallback,
+            _ => config.engine.clone(),
+        }
+    }
+
+    /// Whether a fallback is configured and hasn't taken over yet. Until then a failed chunk
+    /// only counts towards the switch instead of stopping the recording.
+    pub(crate) fn can_fail_over(&self, config: &TranscriptionConfig) -> bool {
+        config.failover.fallback().is_some() && !self.failed_over.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn succeeded(&self) {
+        self.consecutive_failures.store(0, Ordering::SeqCst);
+    }
+
+    /// Count a failed chunk on the primary. Returns the fallback engine when this failure
+    /// is the one that triggers the switch.
+    pub(crate) fn failed(&self, config: &TranscriptionConfig) -> Option<AudioTranscriptionEngine> {
+        let fallback = config.failover.fallback()?;
+        let failures = self.consecutive_failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures < config.failover.after_failures || self.failed_over.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        Some(fallback)
+    }
+
+    pub(crate) fn switch(&self) -> Option<ProviderSwitch> {
+        self.switch.lock().ok().and_then(|switch| switch.clone())
+    }
+}
+
+/// Record the switch in the meeting's pipeline metadata and tell the UI.
+pub(crate) fn record_switch<R: Runtime>(
+    app: &AppHandle<R>,
+    state: &FailoverState,
+    config: &TranscriptionConfig,
+    to: &AudioTranscriptionEngine,
+    error: &str,
+) {
+    let switch = ProviderSwitch {
+        from: config.engine.to_string(),
+        to: to.to_string(),
+        after_failures: config.failover.after_failures,
+        error: error.to_string(),
+        switched_at: chrono::Utc::now().to_rfc3339(),
+    };
+    log_warn!(
+        "Transcription failed over from {} to {} after {} failed chunks: {}",
+        switch.from, switch.to, switch.after_failures, error
+    );
+    meeting_metadata::set_field("transcription", "failover", serde_json::json!(&switch));
+    if let Err(e) = app.emit("transcription-provider-failover", &switch) {
+        log_error!("Failed to emit transcription-provider-failover event: {}", e);
+    }
+    if let Ok(mut recorded) = state.switch.lock() {
+        *recorded = Some(switch);
+    }
+}
+
+pub fn load_config<R: Runtime>(app: &AppHandle<R>) -> FailoverConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(FAILOVER_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+static OPENAI_API_KEY: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// Forget the cached OpenAI key so a key changed since the last recording is picked up.
+pub fn begin_recording() {
+    if let Ok(mut key) = OPENAI_API_KEY.lock() {
+        *key = None;
+    }
+}
+
+/// The OpenAI key saved with the backend's transcript settings, fetched once per recording.
+pub(crate) async fn openai_api_key<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    if let Some(key) = OPENAI_API_KEY.lock().ok().and_then(|key| key.clone()) {
+        return Ok(key);
+    }
+    let key = crate::api::api_get_transcript_api_key(app.clone(), OPENAI_PROVIDER.to_string(), None)
+        .await
+        .map_err(|e| format!("Failed to get OpenAI API key: {}", e))?;
+    if key.trim().is_empty() {
+        return Err("No OpenAI API key is configured".to_string());
+    }
+    if let Ok(mut cached) = OPENAI_API_KEY.lock() {
+        *cached = Some(key.clone());
+    }
+    Ok(key)
+}
+
+#[tauri::command]
+pub async fn get_transcription_failover<R: Runtime>(app: AppHandle<R>) -> Result<FailoverConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the fallback provider. Takes effect from the next recording.
+#[tauri::command]
+pub async fn set_transcription_failover<R: Runtime>(app: AppHandle<R>, config: FailoverConfig) -> Result<(), String> {
+    if let Some(engine) = &config.fallback_engine {
+        AudioTranscriptionEngine::from_setting(engine)
+            .ok_or_else(|| format!("Unknown transcription engine: {}", engine))?;
+    }
+    if !(1..=MAX_FAILURES_BEFORE_FAILOVER).contains(&config.after_failures) {
+        return Err(format!("Failover must happen after 1 to {} failed chunks", MAX_FAILURES_BEFORE_FAILOVER));
+    }
+    if config.openai_model.trim().is_empty() {
+        return Err("OpenAI model must not be empty".to_string());
+    }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(FAILOVER_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    match config.fallback() {
+        Some(fallback) => log_info!("Transcription failover to {} after {} failed chunks", fallback, config.after_failures),
+        None => log_info!("Transcription failover disabled"),
+    }
+    Ok(())
+}
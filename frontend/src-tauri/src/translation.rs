@@ -0,0 +1,180 @@
+// Live translation of the transcript. Whisper can only translate into English, so that
+// target is handled by the transcription engine itself and arrives in the same
+// `transcript-update` as the original text. Other targets are translated line by line
+// with a local Ollama model and follow shortly after as `transcript-translation` events.
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::telemetry::{self, Dependency};
+use crate::TranscriptUpdate;
+
+const TRANSLATION_KEY: &str = "liveTranslation";
+/// The only target whisper translates into
+pub const WHISPER_TARGET: &str = "en";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TranslationConfig {
+    pub enabled: bool,
+    /// Language code of the translation
+    pub target_language: String,
+    /// Ollama model used for targets other than English
+    pub ollama_model: Option<String>,
+}
+
+impl Default for TranslationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            target_language: WHISPER_TARGET.to_string(),
+            ollama_model: None,
+        }
+    }
+}
+
+impl TranslationConfig {
+    /// Whether the transcription engine should produce the translation.
+    pub fn uses_whisper(&self) -> bool {
+        self.enabled && self.target_language == WHISPER_TARGET
+    }
+
+    fn ollama_model(&self) -> Option<&str> {
+        if !self.enabled || self.uses_whisper() {
+            return None;
+        }
+        self.ollama_model.as_deref()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct TranscriptTranslation {
+    sequence_id: u64,
+    source: String,
+    target_language: String,
+    translated_text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GenerateRequest<'a> {
+    model: &'a str,
+    prompt: String,
+    stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GenerateResponse {
+    response: String,
+}
+
+static CONFIG: Lazy<Mutex<TranslationConfig>> = Lazy::new(|| Mutex::new(TranslationConfig::default()));
+
+pub fn load_config<R: Runtime>(app: &AppHandle<R>) -> TranslationConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(TRANSLATION_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Pick up the translation settings for a new recording.
+pub fn begin_recording<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = load_config(app);
+    }
+}
+
+async fn translate(client: &reqwest::Client, model: &str, target_language: &str, text: &str) -> Result<String, String> {
+    let request = GenerateRequest {
+        model,
+        prompt: format!(
+            "Translate this line of a meeting transcript into the language with code \"{}\". \
+             Reply with the translation only, without quotes or notes.\n\n{}",
+            target_language, text
+        ),
+        stream: false,
+    };
+    let started = std::time::Instant::now();
+    let result = async {
+        let response = client
+            .post(format!("{}/api/generate", crate::ollama::base_url()))
+            .json(&request)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to reach Ollama: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Ollama returned {}", response.status()));
+        }
+        response
+            .json::<GenerateResponse>()
+            .await
+            .map(|r| r.response.trim().to_string())
+            .map_err(|e| format!("Failed to parse Ollama response: {}", e))
+    }
+    .await;
+    telemetry::record(Dependency::Ollama, started.elapsed(), result.as_ref().err().map(String::as_str));
+    result
+}
+
+/// Translate a finished line in the background when the target needs an LLM.
+pub(crate) fn observe<R: Runtime>(app: &AppHandle<R>, update: &TranscriptUpdate) {
+    let (model, target_language) = match CONFIG.lock() {
+        Ok(config) => match config.ollama_model() {
+            Some(model) => (model.to_string(), config.target_language.clone()),
+            None => return,
+        },
+        Err(_) => return,
+    };
+    if update.text.trim().is_empty() {
+        return;
+    }
+
+    let app = app.clone();
+    let (sequence_id, source, text) = (update.sequence_id, update.source.clone(), update.text.clone());
+    tauri::async_runtime::spawn(async move {
+        let client = reqwest::Client::new();
+        match translate(&client, &model, &target_language, &text).await {
+            Ok(translated_text) => {
+                let event = TranscriptTranslation { sequence_id, source, target_language, translated_text };
+                if let Err(e) = app.emit("transcript-translation", event) {
+                    log_error!("Failed to emit transcript-translation event: {}", e);
+                }
+            }
+            Err(e) => log_warn!("Failed to translate transcript line {}: {}", sequence_id, e),
+        }
+    });
+}
+
+#[tauri::command]
+pub async fn get_translation_config<R: Runtime>(app: AppHandle<R>) -> Result<TranslationConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the translation settings. They apply from the next recording.
+#[tauri::command]
+pub async fn set_translation_config<R: Runtime>(app: AppHandle<R>, config: TranslationConfig) -> Result<(), String> {
+    let target = config.target_language.trim().to_lowercase();
+    if !(2..=3).contains(&target.len()) || !target.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(format!("Unknown target language code: {}", config.target_language));
+    }
+    let config = TranslationConfig { target_language: target, ..config };
+    if config.enabled && !config.uses_whisper() && config.ollama_model.as_deref().map_or(true, |m| m.trim().is_empty()) {
+        return Err(format!(
+            "Translating into {} needs an Ollama model; only English is translated by the transcription engine",
+            config.target_language
+        ));
+    }
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(TRANSLATION_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!(
+        "Live translation {} (target: {})",
+        if config.enabled { "enabled" } else { "disabled" },
+        config.target_language
+    );
+    Ok(())
+}
@@ -0,0 +1,174 @@
+// Personal usage statistics, kept only in the local database and never sent anywhere: how
+// many meetings were recorded and for how long, how far transcription lagged behind the
+// audio, and which transcription engines and summary models were used. `get_usage_stats`
+// sums them up for the insights view; `clear_usage_stats` deletes them.
+//
+// Each recording, transcribed chunk and generated summary adds one row to `usage_events`.
+// Recording stats must never get in the way, so failures are only logged.
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Runtime};
+use log::{info as log_info, warn as log_warn};
+
+use crate::storage::with_connection;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageKind {
+    /// A saved recording; the value is its length in seconds
+    Recording,
+    /// A transcribed chunk; the value is the lag in seconds from capture to transcript
+    Transcription,
+    /// A generated summary; the value is how long generation took in seconds
+    Summary,
+}
+
+impl UsageKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            UsageKind::Recording => "recording",
+            UsageKind::Transcription => "transcription",
+            UsageKind::Summary => "summary",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UsagePeriod {
+    Day,
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl UsagePeriod {
+    fn since(self) -> Option<DateTime<Utc>> {
+        let days = match self {
+            UsagePeriod::Day => 1,
+            UsagePeriod::Week => 7,
+            UsagePeriod::Month => 30,
+            UsagePeriod::Year => 365,
+            UsagePeriod::All => return None,
+        };
+        Some(Utc::now() - chrono::Duration::days(days))
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelUsage {
+    pub model: String,
+    pub uses: u64,
+}
+
+/// Recording totals for one local calendar day.
+#[derive(Debug, Clone, Serialize)]
+pub struct DailyUsage {
+    pub date: String,
+    pub meetings: u64,
+    pub recorded_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageStats {
+    pub period: UsagePeriod,
+    /// Start of the period; None for all time
+    pub since: Option<DateTime<Utc>>,
+    pub meetings_recorded: u64,
+    pub recorded_hours: f64,
+    /// Average seconds from capturing a chunk to its transcript; None without transcriptions
+    pub average_transcription_lag_secs: Option<f64>,
+    pub summaries_generated: u64,
+    /// Most used first
+    pub transcription_models: Vec<ModelUsage>,
+    pub summary_models: Vec<ModelUsage>,
+    pub daily: Vec<DailyUsage>,
+}
+
+fn timestamp(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Add a usage event; `value` is the kind's measurement and `model` the engine or model used.
+pub fn record<R: Runtime>(app: &AppHandle<R>, kind: UsageKind, value: Option<f64>, model: Option<&str>) {
+    let result = with_connection(app, "record usage", |conn| {
+        conn.execute(
+            "INSERT INTO usage_events (kind, occurred_at, value, model) VALUES (?1, ?2, ?3, ?4)",
+            params![kind.as_str(), timestamp(Utc::now()), value, model],
+        )
+    });
+    if let Err(e) = result {
+        log_warn!("Failed to record {} usage: {}", kind.as_str(), e);
+    }
+}
+
+fn model_usage(conn: &rusqlite::Connection, kind: UsageKind, since: &str) -> rusqlite::Result<Vec<ModelUsage>> {
+    let mut models = conn.prepare(
+        "SELECT model, COUNT(*) FROM usage_events WHERE kind = ?1 AND occurred_at >= ?2 AND model IS NOT NULL
+         GROUP BY model ORDER BY COUNT(*) DESC, model",
+    )?;
+    let rows = models.query_map(params![kind.as_str(), since], |row| {
+        Ok(ModelUsage { model: row.get(0)?, uses: row.get::<_, i64>(1)? as u64 })
+    })?;
+    rows.collect()
+}
+
+/// Usage totals for the last day, week, month, year or all time.
+#[tauri::command]
+pub async fn get_usage_stats<R: Runtime>(app: AppHandle<R>, period: UsagePeriod) -> Result<UsageStats, String> {
+    let since = period.since();
+    // Everything is on or after the empty string
+    let from = since.map(timestamp).unwrap_or_default();
+    with_connection(&app, "read usage stats", |conn| {
+        let (meetings_recorded, recorded_secs) = conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(value), 0) FROM usage_events WHERE kind = ?1 AND occurred_at >= ?2",
+            params![UsageKind::Recording.as_str(), from],
+            |row| Ok((row.get::<_, i64>(0)? as u64, row.get::<_, f64>(1)?)),
+        )?;
+        let average_transcription_lag_secs = conn.query_row(
+            "SELECT AVG(value) FROM usage_events WHERE kind = ?1 AND occurred_at >= ?2 AND value IS NOT NULL",
+            params![UsageKind::Transcription.as_str(), from],
+            |row| row.get::<_, Option<f64>>(0),
+        )?;
+        let summaries_generated = conn.query_row(
+            "SELECT COUNT(*) FROM usage_events WHERE kind = ?1 AND occurred_at >= ?2",
+            params![UsageKind::Summary.as_str(), from],
+            |row| row.get::<_, i64>(0),
+        )? as u64;
+
+        let mut days = conn.prepare(
+            "SELECT date(occurred_at, 'localtime') AS day, COUNT(*), COALESCE(SUM(value), 0) FROM usage_events
+             WHERE kind = ?1 AND occurred_at >= ?2 GROUP BY day ORDER BY day",
+        )?;
+        let daily = days
+            .query_map(params![UsageKind::Recording.as_str(), from], |row| {
+                Ok(DailyUsage {
+                    date: row.get(0)?,
+                    meetings: row.get::<_, i64>(1)? as u64,
+                    recorded_hours: row.get::<_, f64>(2)? / 3600.0,
+                })
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(UsageStats {
+            period,
+            since,
+            meetings_recorded,
+            recorded_hours: recorded_secs / 3600.0,
+            average_transcription_lag_secs,
+            summaries_generated,
+            transcription_models: model_usage(conn, UsageKind::Transcription, &from)?,
+            summary_models: model_usage(conn, UsageKind::Summary, &from)?,
+            daily,
+        })
+    })
+}
+
+/// Delete all recorded usage statistics.
+#[tauri::command]
+pub async fn clear_usage_stats<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    let deleted = with_connection(&app, "clear usage stats", |conn| conn.execute("DELETE FROM usage_events", []))?;
+    log_info!("Cleared {} usage events", deleted);
+    Ok(())
+}
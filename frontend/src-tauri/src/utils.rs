@@ -4,4 +4,24 @@ pub fn format_timestamp(seconds: f64) -> String {
     let minutes = (total_seconds % 3600) / 60;
     let secs = total_seconds % 60;
     format!("{:02}:{:02}:{:02}", hours, minutes, secs)
-} 
\ No newline at end of file
+}
+
+/// `format_timestamp` with milliseconds after `separator`: `,` for SRT, `.` for WebVTT.
+pub fn format_timestamp_millis(seconds: f64, separator: char) -> String {
+    let total_millis = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!("{}{}{:03}", format_timestamp((total_millis / 1000) as f64), separator, total_millis % 1000)
+}
+
+/// Seconds from an `HH:MM:SS` or `MM:SS` timestamp, optionally with a fraction and in
+/// brackets (`[00:01:02.5]`). None for anything else, e.g. wall-clock dates.
+pub fn parse_timestamp(timestamp: &str) -> Option<f64> {
+    let timestamp = timestamp.trim().trim_start_matches('[').trim_end_matches(']');
+    let parts: Vec<&str> = timestamp.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+    let (whole, last) = parts.split_at(parts.len() - 1);
+    let seconds: f64 = last[0].replace(',', ".").parse().ok()?;
+    let whole = whole.iter().try_fold(0u64, |acc, part| part.parse::<u64>().ok().map(|v| acc * 60 + v))?;
+    (0.0..60.0).contains(&seconds).then(|| whole as f64 * 60.0 + seconds)
+}
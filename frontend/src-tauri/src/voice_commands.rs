@@ -0,0 +1,194 @@
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::meeting_metadata;
+use crate::TranscriptUpdate;
+
+const VOICE_COMMANDS_KEY: &str = "voiceCommands";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceCommandKind {
+    /// Bookmark the moment, with whatever follows the phrase as its note
+    Marker,
+    /// Whatever follows the phrase becomes an action item of the meeting
+    ActionItem,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandPhrase {
+    pub phrase: String,
+    pub kind: VoiceCommandKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceCommandConfig {
+    pub enabled: bool,
+    pub phrases: Vec<CommandPhrase>,
+}
+
+impl Default for VoiceCommandConfig {
+    fn default() -> Self {
+        let phrase = |phrase: &str, kind| CommandPhrase { phrase: phrase.to_string(), kind };
+        Self {
+            enabled: true,
+            phrases: vec![
+                phrase("note to self", VoiceCommandKind::Marker),
+                phrase("mark this", VoiceCommandKind::Marker),
+                phrase("action item", VoiceCommandKind::ActionItem),
+            ],
+        }
+    }
+}
+
+/// A command spoken during the current recording.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoiceCommand {
+    pub kind: VoiceCommandKind,
+    pub phrase: String,
+    /// What was said after the phrase
+    pub text: String,
+    pub timestamp: String,
+    pub source: String,
+    pub sequence_id: u64,
+}
+
+static CONFIG: Lazy<Mutex<VoiceCommandConfig>> = Lazy::new(|| Mutex::new(VoiceCommandConfig::default()));
+static COMMANDS: Lazy<Mutex<Vec<VoiceCommand>>> = Lazy::new(|| Mutex::new(Vec::new()));
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> VoiceCommandConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(VOICE_COMMANDS_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn normalize(word: &str) -> String {
+    word.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+// Words of `text` with the byte offset just past each one
+fn words_with_ends(text: &str) -> Vec<(String, usize)> {
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain(std::iter::once((text.len(), ' '))) {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                let word = normalize(&text[s..i]);
+                if !word.is_empty() {
+                    words.push((word, i));
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    words
+}
+
+/// The first configured phrase spoken in `text`, with what follows it.
+fn match_command<'a>(text: &str, phrases: &'a [CommandPhrase]) -> Option<(&'a CommandPhrase, String)> {
+    let words = words_with_ends(text);
+    phrases
+        .iter()
+        .filter_map(|command| {
+            let phrase: Vec<String> = command.phrase.split_whitespace().map(normalize).filter(|w| !w.is_empty()).collect();
+            if phrase.is_empty() {
+                return None;
+            }
+            let start = words
+                .windows(phrase.len())
+                .position(|window| window.iter().map(|(w, _)| w).eq(phrase.iter()))?;
+            let end = words[start + phrase.len() - 1].1;
+            Some((start, command, end))
+        })
+        .min_by_key(|(start, _, _)| *start)
+        .map(|(_, command, end)| {
+            let rest = text[end..].trim_start_matches(|c: char| c.is_whitespace() || ":,;-–—".contains(c));
+            (command, rest.trim_end().to_string())
+        })
+}
+
+/// Load the phrase list and forget commands from the previous recording.
+pub fn begin_recording<R: Runtime>(app: &AppHandle<R>) {
+    if let Ok(mut config) = CONFIG.lock() {
+        *config = load_config(app);
+    }
+    if let Ok(mut commands) = COMMANDS.lock() {
+        commands.clear();
+    }
+}
+
+/// Check a finished transcript line for a spoken command. Recognized commands are kept
+/// in the meeting metadata so they are saved with the transcript.
+pub(crate) fn observe<R: Runtime>(app: &AppHandle<R>, update: &TranscriptUpdate) {
+    let matched = match CONFIG.lock() {
+        Ok(config) if config.enabled => match_command(&update.text, &config.phrases)
+            .map(|(command, text)| (command.clone(), text)),
+        _ => None,
+    };
+    let Some((command, text)) = matched else {
+        return;
+    };
+    if command.kind == VoiceCommandKind::ActionItem && text.is_empty() {
+        log_warn!("Ignoring \"{}\" command without an action item", command.phrase);
+        return;
+    }
+
+    let voice_command = VoiceCommand {
+        kind: command.kind,
+        phrase: command.phrase,
+        text,
+        timestamp: update.timestamp.clone(),
+        source: update.source.clone(),
+        sequence_id: update.sequence_id,
+    };
+    log_info!("Voice command {:?} at {}", voice_command.kind, voice_command.timestamp);
+    if let Ok(mut commands) = COMMANDS.lock() {
+        commands.push(voice_command.clone());
+        meeting_metadata::set("voice_commands", serde_json::json!(&*commands));
+    }
+    if let Err(e) = app.emit("voice-command", &voice_command) {
+        log_error!("Failed to emit voice-command event: {}", e);
+    }
+}
+
+/// Spoken commands recorded in a meeting's metadata.
+pub fn from_metadata(metadata: Option<&serde_json::Value>) -> Vec<VoiceCommand> {
+    metadata
+        .and_then(|metadata| metadata.get("voice_commands"))
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+        .unwrap_or_default()
+}
+
+#[tauri::command]
+pub async fn get_voice_command_config<R: Runtime>(app: AppHandle<R>) -> Result<VoiceCommandConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the phrase list. A running recording uses it from the next transcript line.
+#[tauri::command]
+pub async fn set_voice_command_config<R: Runtime>(app: AppHandle<R>, config: VoiceCommandConfig) -> Result<(), String> {
+    if config.phrases.iter().any(|p| p.phrase.split_whitespace().all(|w| normalize(w).is_empty())) {
+        return Err("Voice command phrases cannot be empty".to_string());
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(VOICE_COMMANDS_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Saved {} voice command phrases", config.phrases.len());
+    *CONFIG.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+/// Commands recognized so far in the current (or last) recording.
+#[tauri::command]
+pub fn get_voice_commands() -> Vec<VoiceCommand> {
+    COMMANDS.lock().map(|commands| commands.clone()).unwrap_or_default()
+}
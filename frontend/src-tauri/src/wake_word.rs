@@ -0,0 +1,299 @@
+// Opt-in wake phrase ("start taking notes") that starts a recording hands-free.
+//
+// Detection is local only: a short rolling window of mic audio is checked with the VAD,
+// and windows containing speech are transcribed with the local Whisper model and matched
+// against the configured phrase. Any phrase works without training a keyword model.
+// Audio is kept in memory only for the length of the window and is never written to disk,
+// logged or sent anywhere; it is discarded as soon as it rolls out of the window or the
+// listener stops.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, debug as log_debug, warn as log_warn};
+
+use crate::audio::{vad, AudioStream, DeviceType};
+use crate::{find_local_whisper_model, is_recording, resample_audio, resolve_device, WHISPER_SAMPLE_RATE};
+
+const WAKE_WORD_KEY: &str = "wakeWord";
+const DEFAULT_PHRASE: &str = "start taking notes";
+// Long enough to hold a short phrase spoken at a relaxed pace
+const WINDOW: Duration = Duration::from_millis(2500);
+// How often the window is checked for the phrase
+const HOP: Duration = Duration::from_millis(1000);
+const SPEECH_SENSITIVITY: f32 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WakeWordConfig {
+    /// Listen for the phrase whenever the app is idle
+    pub enabled: bool,
+    pub phrase: String,
+    /// Microphone to listen on and record from, the default input when unset
+    pub mic_device_name: Option<String>,
+}
+
+impl Default for WakeWordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            phrase: DEFAULT_PHRASE.to_string(),
+            mic_device_name: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WakeWordStatus {
+    pub listening: bool,
+    pub phrase: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct WakeWordDetected {
+    phrase: String,
+}
+
+pub struct WakeWordListener {
+    mic_stream: Arc<AudioStream>,
+    is_running: Arc<AtomicBool>,
+    task: tokio::task::JoinHandle<()>,
+    phrase: String,
+}
+
+impl WakeWordListener {
+    async fn stop(self) {
+        self.is_running.store(false, Ordering::SeqCst);
+        self.task.abort();
+        if let Err(e) = self.mic_stream.stop().await {
+            log_error!("Error stopping wake word mic stream: {}", e);
+        }
+    }
+}
+
+pub type WakeWordState = Mutex<Option<WakeWordListener>>;
+
+fn load_config<R: Runtime>(app: &AppHandle<R>) -> WakeWordConfig {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(WAKE_WORD_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c.is_whitespace() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether the phrase appears in the transcript as consecutive words, ignoring case and punctuation.
+fn matches_phrase(transcript: &str, phrase: &[String]) -> bool {
+    if phrase.is_empty() {
+        return false;
+    }
+    words(transcript).windows(phrase.len()).any(|window| window == phrase)
+}
+
+fn emit_status<R: Runtime>(app: &AppHandle<R>, listening: bool, phrase: &str) {
+    let status = WakeWordStatus { listening, phrase: phrase.to_string() };
+    if let Err(e) = app.emit("wake-word-listening", status) {
+        log_debug!("Failed to emit wake-word-listening event: {}", e);
+    }
+}
+
+/// Start listening with the stored configuration, replacing any running listener.
+async fn start_listener<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    if is_recording() {
+        return Err("Cannot listen for the wake word while recording".to_string());
+    }
+    stop_listener(app).await?;
+
+    let config = load_config(app);
+    let phrase = words(&config.phrase);
+    if phrase.is_empty() {
+        return Err("Wake phrase is empty".to_string());
+    }
+    let model_path = find_local_whisper_model(app)
+        .ok_or_else(|| "Wake word detection needs a local Whisper model; download one first".to_string())?;
+
+    let mic_device = Arc::new(resolve_device(config.mic_device_name.clone(), DeviceType::Input).await?);
+    let is_running = Arc::new(AtomicBool::new(true));
+    let mic_stream = Arc::new(
+        AudioStream::from_device(mic_device.clone(), is_running.clone())
+            .await
+            .map_err(|e| format!("Failed to open microphone: {}", e))?,
+    );
+    let sample_rate = mic_stream.device_config.sample_rate().0;
+    log_info!("Listening for wake phrase \"{}\" on {}", config.phrase, mic_device);
+
+    let task = {
+        let mut mic_receiver = mic_stream.subscribe().await;
+        let is_running = is_running.clone();
+        let app = app.clone();
+        let mic_device_name = config.mic_device_name.clone();
+        tokio::spawn(async move {
+            let window_len = (WHISPER_SAMPLE_RATE as f32 * WINDOW.as_secs_f32()) as usize;
+            let mut pending: Vec<f32> = Vec::new();
+            let mut window: Vec<f32> = Vec::with_capacity(window_len * 2);
+            let mut last_check = Instant::now();
+
+            while is_running.load(Ordering::SeqCst) {
+                while let Ok(chunk) = mic_receiver.try_recv() {
+                    pending.extend(chunk);
+                }
+                if last_check.elapsed() < HOP {
+                    tokio::time::sleep(HOP / 10).await;
+                    continue;
+                }
+                last_check = Instant::now();
+
+                if sample_rate == WHISPER_SAMPLE_RATE {
+                    window.append(&mut pending);
+                } else {
+                    window.extend(resample_audio(&pending, sample_rate, WHISPER_SAMPLE_RATE));
+                    pending.clear();
+                }
+                // Older audio falls out of the window and is dropped for good
+                if window.len() > window_len {
+                    window.drain(..window.len() - window_len);
+                }
+
+                match vad::contains_speech(&window, SPEECH_SENSITIVITY) {
+                    Ok(true) => {}
+                    Ok(false) => continue,
+                    Err(e) => {
+                        log_debug!("Wake word VAD failed: {}", e);
+                        continue;
+                    }
+                }
+
+                let samples = window.clone();
+                let model_path = model_path.clone();
+                let result = tokio::task::spawn_blocking(move || {
+                    let model = crate::audio::whisper_local::get_or_load(&model_path)?;
+                    model.transcribe(&samples)
+                })
+                .await;
+                let transcript = match result {
+                    Ok(Ok(segments)) => segments.iter().map(|s| s.text.as_str()).collect::<Vec<_>>().join(" "),
+                    Ok(Err(e)) => {
+                        log_warn!("Wake word transcription failed: {}", e);
+                        continue;
+                    }
+                    Err(e) => {
+                        log_error!("Wake word transcription task failed: {}", e);
+                        continue;
+                    }
+                };
+                if !matches_phrase(&transcript, &phrase) {
+                    continue;
+                }
+
+                log_info!("Wake phrase detected, starting recording");
+                window.clear();
+                is_running.store(false, Ordering::SeqCst);
+                if let Err(e) = app.emit("wake-word-detected", WakeWordDetected { phrase: phrase.join(" ") }) {
+                    log_debug!("Failed to emit wake-word-detected event: {}", e);
+                }
+                // Stopping the listener aborts this task, so the hand-off runs in a separate one
+                let app = app.clone();
+                let mic_device_name = mic_device_name.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = stop_listener(&app).await {
+                        log_error!("Failed to stop wake word listener: {}", e);
+                    }
+                    if let Err(e) = crate::start_recording(
+                        app.clone(),
+                        app.state::<crate::RecordingState>(),
+                        app.state::<crate::LevelMonitorState>(),
+                        mic_device_name,
+                        None,
+                    )
+                    .await
+                    {
+                        log_error!("Failed to start recording from wake word: {}", e);
+                        if let Err(e) = app.emit("wake-word-error", e) {
+                            log_debug!("Failed to emit wake-word-error event: {}", e);
+                        }
+                    }
+                });
+                return;
+            }
+        })
+    };
+
+    let state = app.state::<WakeWordState>();
+    *state.lock().map_err(|e| e.to_string())? = Some(WakeWordListener {
+        mic_stream,
+        is_running,
+        task,
+        phrase: config.phrase.clone(),
+    });
+    emit_status(app, true, &config.phrase);
+    Ok(())
+}
+
+/// Stop the listener and release the microphone. Buffered audio is dropped with it.
+pub async fn stop_listener<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let listener = app.state::<WakeWordState>().lock().map_err(|e| e.to_string())?.take();
+    if let Some(listener) = listener {
+        let phrase = listener.phrase.clone();
+        listener.stop().await;
+        emit_status(app, false, &phrase);
+        log_info!("Wake word listener stopped");
+    }
+    Ok(())
+}
+
+/// Start listening again if the user opted in, e.g. at startup or after a recording ends.
+pub async fn resume_if_enabled<R: Runtime>(app: &AppHandle<R>) {
+    if !load_config(app).enabled || is_recording() {
+        return;
+    }
+    if let Err(e) = start_listener(app).await {
+        log_warn!("Wake word listener not started: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_wake_word_config<R: Runtime>(app: AppHandle<R>) -> Result<WakeWordConfig, String> {
+    Ok(load_config(&app))
+}
+
+/// Save the wake word settings and start or stop listening to match.
+#[tauri::command]
+pub async fn set_wake_word_config<R: Runtime>(app: AppHandle<R>, config: WakeWordConfig) -> Result<(), String> {
+    if config.enabled && words(&config.phrase).is_empty() {
+        return Err("Wake phrase is empty".to_string());
+    }
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(WAKE_WORD_KEY, serde_json::to_value(&config).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Wake word {}", if config.enabled { "enabled" } else { "disabled" });
+
+    if config.enabled {
+        if !is_recording() {
+            start_listener(&app).await?;
+        }
+    } else {
+        stop_listener(&app).await?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_wake_word_status<R: Runtime>(app: AppHandle<R>) -> Result<WakeWordStatus, String> {
+    let state = app.state::<WakeWordState>();
+    let listener = state.lock().map_err(|e| e.to_string())?;
+    Ok(match listener.as_ref() {
+        Some(listener) => WakeWordStatus { listening: true, phrase: listener.phrase.clone() },
+        None => WakeWordStatus { listening: false, phrase: load_config(&app).phrase },
+    })
+}
@@ -0,0 +1,270 @@
+// Outgoing webhooks, so services like Zapier or n8n can act on finished meetings. Users
+// register URLs together with the events they want; when a transcript is saved or a summary
+// is ready, each matching webhook receives a JSON POST:
+//
+//   { "event": "summary.generated", "delivery_id": "...", "sent_at": "...",
+//     "meeting": { "id": "...", "title": "...", "summary": {...}, "transcript_url": "..." } }
+//
+// Every request is signed so the receiver can check it came from this app: the
+// `X-Meeting-Minutes-Signature` header is `sha256=` followed by the hex HMAC-SHA256 of
+// `<timestamp>.<body>`, keyed with the webhook's secret, where the timestamp is the
+// `X-Meeting-Minutes-Timestamp` header. Secrets are generated when a webhook is added, shown
+// once and kept in the OS keychain.
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, warn as log_warn};
+
+use crate::api::get_server_address;
+use crate::http;
+use crate::keychain;
+use crate::telemetry::Dependency;
+
+const WEBHOOKS_STORE_KEY: &str = "webhooks";
+const SIGNATURE_HEADER: &str = "X-Meeting-Minutes-Signature";
+const TIMESTAMP_HEADER: &str = "X-Meeting-Minutes-Timestamp";
+const EVENT_HEADER: &str = "X-Meeting-Minutes-Event";
+const DELIVERY_HEADER: &str = "X-Meeting-Minutes-Delivery";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WebhookEvent {
+    #[serde(rename = "transcript.completed")]
+    TranscriptCompleted,
+    #[serde(rename = "summary.generated")]
+    SummaryGenerated,
+}
+
+impl WebhookEvent {
+    fn name(self) -> &'static str {
+        match self {
+            WebhookEvent::TranscriptCompleted => "transcript.completed",
+            WebhookEvent::SummaryGenerated => "summary.generated",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Webhook {
+    pub id: String,
+    pub url: String,
+    pub events: Vec<WebhookEvent>,
+    pub enabled: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A newly added webhook with its signing secret, which is only ever returned here.
+#[derive(Debug, Clone, Serialize)]
+pub struct NewWebhook {
+    pub webhook: Webhook,
+    pub secret: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub webhook_id: String,
+    pub success: bool,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// The meeting a webhook payload is about.
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingPayload {
+    pub id: String,
+    pub title: Option<String>,
+    pub summary: Option<Value>,
+    pub transcript_url: String,
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    event: &'static str,
+    delivery_id: String,
+    sent_at: DateTime<Utc>,
+    meeting: &'a MeetingPayload,
+}
+
+fn load<R: Runtime>(app: &AppHandle<R>) -> Vec<Webhook> {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(WEBHOOKS_STORE_KEY))
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn save<R: Runtime>(app: &AppHandle<R>, webhooks: &[Webhook]) -> Result<(), String> {
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(WEBHOOKS_STORE_KEY, serde_json::to_value(webhooks).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| format!("Failed to save store: {}", e))
+}
+
+fn secret_account(webhook_id: &str) -> String {
+    format!("webhook-{}", webhook_id)
+}
+
+fn validate_url(url: &str) -> Result<String, String> {
+    let parsed = reqwest::Url::parse(url.trim()).map_err(|e| format!("Invalid webhook URL '{}': {}", url, e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(format!("Webhook URL must use http or https: {}", url));
+    }
+    if parsed.host_str().is_none() {
+        return Err(format!("Webhook URL has no host: {}", url));
+    }
+    Ok(parsed.to_string())
+}
+
+fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(format!("{}.{}", timestamp, body).as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+async fn deliver<R: Runtime>(app: &AppHandle<R>, webhook: &Webhook, event: WebhookEvent, meeting: &MeetingPayload) -> WebhookDelivery {
+    let result = async {
+        let secret = keychain::get_secret(&secret_account(&webhook.id))?
+            .ok_or_else(|| "Webhook secret is missing from the keychain; remove and add the webhook again".to_string())?;
+        let delivery_id = uuid::Uuid::new_v4().to_string();
+        let sent_at = Utc::now();
+        let body = serde_json::to_string(&Payload { event: event.name(), delivery_id: delivery_id.clone(), sent_at, meeting })
+            .map_err(|e| e.to_string())?;
+        let signature = sign(&secret, sent_at.timestamp(), &body);
+
+        // Receivers can drop repeats by delivery id, so retrying is safe
+        let options = http::policy(app).options(Dependency::Integration, true);
+        let response = http::send(&options, "Webhook delivery", || {
+            http::client()
+                .post(&webhook.url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(SIGNATURE_HEADER, &signature)
+                .header(TIMESTAMP_HEADER, sent_at.timestamp().to_string())
+                .header(EVENT_HEADER, event.name())
+                .header(DELIVERY_HEADER, &delivery_id)
+                .body(body.clone())
+        })
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok::<_, String>(response.status().as_u16())
+    }
+    .await;
+
+    match result {
+        Ok(status) => WebhookDelivery { webhook_id: webhook.id.clone(), success: true, status: Some(status), error: None },
+        Err(e) => {
+            log_warn!("Webhook {} failed for {}: {}", webhook.url, event.name(), e);
+            WebhookDelivery { webhook_id: webhook.id.clone(), success: false, status: None, error: Some(e) }
+        }
+    }
+}
+
+/// The link a webhook receiver can fetch the meeting's transcript from.
+pub async fn transcript_url<R: Runtime>(app: &AppHandle<R>, meeting_id: &str) -> String {
+    let server = get_server_address(app).await.unwrap_or_default();
+    format!("{}/get-meeting/{}", server, meeting_id)
+}
+
+/// Send `event` to every enabled webhook that subscribed to it, in the background.
+pub fn notify<R: Runtime>(app: &AppHandle<R>, event: WebhookEvent, meeting_id: &str, title: Option<String>, summary: Option<Value>) {
+    let webhooks: Vec<Webhook> = load(app)
+        .into_iter()
+        .filter(|webhook| webhook.enabled && webhook.events.contains(&event))
+        .collect();
+    if webhooks.is_empty() {
+        return;
+    }
+    let app = app.clone();
+    let meeting_id = meeting_id.to_string();
+    tauri::async_runtime::spawn(async move {
+        let meeting = MeetingPayload {
+            transcript_url: transcript_url(&app, &meeting_id).await,
+            id: meeting_id,
+            title,
+            summary,
+        };
+        for webhook in &webhooks {
+            let delivery = deliver(&app, webhook, event, &meeting).await;
+            if delivery.success {
+                log_info!("Sent {} for {} to webhook {}", event.name(), meeting.id, webhook.url);
+            }
+        }
+    });
+}
+
+#[tauri::command]
+pub fn list_webhooks<R: Runtime>(app: AppHandle<R>) -> Vec<Webhook> {
+    load(&app)
+}
+
+/// Register a webhook for `events`. The returned secret is needed to verify signatures and
+/// can't be shown again.
+#[tauri::command]
+pub fn add_webhook<R: Runtime>(app: AppHandle<R>, url: String, events: Vec<WebhookEvent>) -> Result<NewWebhook, String> {
+    let url = validate_url(&url)?;
+    if events.is_empty() {
+        return Err("Pick at least one event for the webhook".to_string());
+    }
+    let mut webhooks = load(&app);
+    let webhook = Webhook {
+        id: uuid::Uuid::new_v4().to_string(),
+        url,
+        events,
+        enabled: true,
+        created_at: Utc::now(),
+    };
+    let mut secret = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut secret);
+    let secret = hex::encode(secret);
+    keychain::set_secret(&secret_account(&webhook.id), &secret)?;
+
+    webhooks.push(webhook.clone());
+    save(&app, &webhooks)?;
+    log_info!("Added webhook {} for {:?}", webhook.url, webhook.events);
+    Ok(NewWebhook { webhook, secret })
+}
+
+#[tauri::command]
+pub fn set_webhook_enabled<R: Runtime>(app: AppHandle<R>, webhook_id: String, enabled: bool) -> Result<Webhook, String> {
+    let mut webhooks = load(&app);
+    let webhook = webhooks
+        .iter_mut()
+        .find(|webhook| webhook.id == webhook_id)
+        .ok_or_else(|| format!("Webhook {} not found", webhook_id))?;
+    webhook.enabled = enabled;
+    let updated = webhook.clone();
+    save(&app, &webhooks)?;
+    Ok(updated)
+}
+
+#[tauri::command]
+pub fn remove_webhook<R: Runtime>(app: AppHandle<R>, webhook_id: String) -> Result<(), String> {
+    let mut webhooks = load(&app);
+    let count = webhooks.len();
+    webhooks.retain(|webhook| webhook.id != webhook_id);
+    if webhooks.len() == count {
+        return Err(format!("Webhook {} not found", webhook_id));
+    }
+    save(&app, &webhooks)?;
+    keychain::delete_secret(&secret_account(&webhook_id))?;
+    log_info!("Removed webhook {}", webhook_id);
+    Ok(())
+}
+
+/// Send a sample `summary.generated` delivery to one webhook and report how it went.
+#[tauri::command]
+pub async fn test_webhook<R: Runtime>(app: AppHandle<R>, webhook_id: String) -> Result<WebhookDelivery, String> {
+    let webhook = load(&app)
+        .into_iter()
+        .find(|webhook| webhook.id == webhook_id)
+        .ok_or_else(|| format!("Webhook {} not found", webhook_id))?;
+    let meeting = MeetingPayload {
+        id: "test-meeting".to_string(),
+        title: Some("Webhook test".to_string()),
+        summary: Some(serde_json::json!({ "overview": "This is a test delivery." })),
+        transcript_url: transcript_url(&app, "test-meeting").await,
+    };
+    Ok(deliver(&app, &webhook, WebhookEvent::SummaryGenerated, &meeting).await)
+}
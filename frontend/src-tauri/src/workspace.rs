@@ -0,0 +1,103 @@
+// Workspaces group meetings for team deployments of the backend. The active workspace is
+// kept in the settings store and sent with every backend request as `X-Workspace-Id`; the
+// local store keeps each workspace's meetings apart. A backend without workspace support
+// ignores the header and everything lives in the personal workspace.
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Runtime};
+use tauri_plugin_store::StoreExt;
+use log::{info as log_info, error as log_error, warn as log_warn};
+
+use crate::api::make_api_request;
+use crate::storage::with_connection;
+
+pub const DEFAULT_WORKSPACE_ID: &str = "personal";
+pub const WORKSPACE_HEADER: &str = "X-Workspace-Id";
+const ACTIVE_WORKSPACE_KEY: &str = "activeWorkspace";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    /// The user's role in the workspace, when the backend reports one
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+fn personal_workspace() -> Workspace {
+    Workspace {
+        id: DEFAULT_WORKSPACE_ID.to_string(),
+        name: "Personal".to_string(),
+        role: None,
+    }
+}
+
+pub fn active_workspace<R: Runtime>(app: &AppHandle<R>) -> String {
+    app.store("store.json")
+        .ok()
+        .and_then(|store| store.get(ACTIVE_WORKSPACE_KEY))
+        .and_then(|value| value.as_str().map(str::to_string))
+        .filter(|id| !id.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_WORKSPACE_ID.to_string())
+}
+
+// Workspaces that only exist in the local store, e.g. one the backend no longer lists
+fn local_workspaces<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    with_connection(app, "list local workspaces", |conn| {
+        let mut query = conn.prepare("SELECT DISTINCT workspace_id FROM meetings")?;
+        let ids = query.query_map([], |row| row.get(0))?;
+        ids.collect()
+    })
+    .unwrap_or_else(|e| {
+        log_warn!("{}", e);
+        Vec::new()
+    })
+}
+
+/// Workspaces the user belongs to. The personal workspace is always included.
+#[tauri::command]
+pub async fn list_workspaces<R: Runtime>(app: AppHandle<R>, auth_token: Option<String>) -> Result<Vec<Workspace>, String> {
+    let mut workspaces = match make_api_request::<R, Vec<Workspace>>(&app, "/workspaces", "GET", None, None, auth_token).await {
+        Ok(workspaces) => workspaces,
+        Err(e) => {
+            log_warn!("Backend did not list workspaces, using local ones: {}", e);
+            Vec::new()
+        }
+    };
+    if !workspaces.iter().any(|w| w.id == DEFAULT_WORKSPACE_ID) {
+        workspaces.insert(0, personal_workspace());
+    }
+    for id in local_workspaces(&app) {
+        if !workspaces.iter().any(|w| w.id == id) {
+            workspaces.push(Workspace { name: id.clone(), id, role: None });
+        }
+    }
+    Ok(workspaces)
+}
+
+#[tauri::command]
+pub async fn get_active_workspace<R: Runtime>(app: AppHandle<R>) -> Result<String, String> {
+    Ok(active_workspace(&app))
+}
+
+/// Make `workspace_id` the workspace meetings are listed from and saved to.
+#[tauri::command]
+pub async fn switch_workspace<R: Runtime>(
+    app: AppHandle<R>,
+    workspace_id: String,
+    auth_token: Option<String>,
+) -> Result<Workspace, String> {
+    let workspace = list_workspaces(app.clone(), auth_token)
+        .await?
+        .into_iter()
+        .find(|w| w.id == workspace_id)
+        .ok_or_else(|| format!("Unknown workspace: {}", workspace_id))?;
+
+    let store = app.store("store.json").map_err(|e| format!("Failed to open store: {}", e))?;
+    store.set(ACTIVE_WORKSPACE_KEY, serde_json::json!(workspace.id));
+    store.save().map_err(|e| format!("Failed to save store: {}", e))?;
+    log_info!("Switched to workspace {} ({})", workspace.name, workspace.id);
+    if let Err(e) = app.emit("workspace-changed", &workspace) {
+        log_error!("Failed to emit workspace-changed event: {}", e);
+    }
+    Ok(workspace)
+}
@@ -17,6 +17,7 @@ import { useRouter } from 'next/navigation';
 import type { CurrentMeeting } from '@/components/Sidebar/SidebarProvider';
 import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert";
 import Analytics from '@/lib/analytics';
+import { checkApiCompatibility, FRONTEND_API_VERSION } from '@/lib/apiVersion';
 
 
 
@@ -137,6 +138,13 @@ export default function Home() {
     Analytics.trackPageView('home');
   }, []);
 
+  useEffect(() => {
+    checkApiCompatibility().catch((error) => {
+      console.error('Command API mismatch:', error);
+      alert(String(error));
+    });
+  }, []);
+
   useEffect(() => {
     setCurrentMeeting({ id: 'intro-call', title: meetingTitle });
     
@@ -443,6 +451,12 @@ export default function Home() {
       const { invoke } = await import('@tauri-apps/api/core');
       const randomTitle = `Meeting ${Math.random().toString(36).substring(2, 8)}`;
       setMeetingTitle(randomTitle);
+
+      // Push transcript lines to the backend while recording. A session left over from a
+      // recording stopped outside this page is finished first.
+      await invoke('stop_transcript_sync').catch(() => {});
+      await invoke('start_transcript_sync', { meetingTitle: randomTitle })
+        .catch(error => console.warn('Incremental transcript sync not started:', error));
       
       // Only check if we're already recording, but don't try to stop it first
       const isCurrentlyRecording = await invoke('is_recording');
@@ -465,6 +479,9 @@ export default function Home() {
     } catch (error) {
       console.error('Failed to start recording:', error);
       alert('Failed to start recording. Check console for details.');
+      import('@tauri-apps/api/core')
+        .then(({ invoke }) => invoke('stop_transcript_sync'))
+        .catch(() => {});
       setIsRecordingState(false); // Reset state on error
       Analytics.trackButtonClick('start_recording_error', 'home_page');
     }
@@ -490,16 +507,17 @@ export default function Home() {
     try {
       console.log('Stopping recording...');
       const { invoke } = await import('@tauri-apps/api/core');
-      const { appDataDir } = await import('@tauri-apps/api/path');
+      const { appDataDir, join } = await import('@tauri-apps/api/path');
       
       const dataDir = await appDataDir();
       const timestamp = new Date().toISOString().replace(/[:.]/g, '-');
       const transcriptPath = `${dataDir}transcript-${timestamp}.txt`;
-      const audioPath = `${dataDir}recording-${timestamp}.wav`;
+      const audioPath = await join(dataDir, 'recordings', `recording-${timestamp}.wav`);
 
       // Stop recording and save audio
       await invoke('stop_recording', { 
         args: { 
+          api_version: FRONTEND_API_VERSION,
           save_path: audioPath,
           model_config: modelConfig
         }
@@ -551,17 +569,18 @@ export default function Home() {
         current_transcript_count: transcripts.length
       });
       const { invoke } = await import('@tauri-apps/api/core');
-      const { appDataDir } = await import('@tauri-apps/api/path');
+      const { appDataDir, join } = await import('@tauri-apps/api/path');
       const { listen } = await import('@tauri-apps/api/event');
       
       const dataDir = await appDataDir();
       const timestamp = new Date().toISOString().replace(/[:.]/g, '-');
       const transcriptPath = `${dataDir}transcript-${timestamp}.txt`;
-      const audioPath = `${dataDir}recording-${timestamp}.wav`;
+      const audioPath = await join(dataDir, 'recordings', `recording-${timestamp}.wav`);
       
       // Stop recording and get audio path
       await invoke('stop_recording', { 
         args: { 
+          api_version: FRONTEND_API_VERSION,
           model_config: modelConfig,
           save_path: audioPath
         }
@@ -660,6 +679,13 @@ export default function Home() {
       console.log('Waiting for transcript state updates to complete...');
       await new Promise(resolve => setTimeout(resolve, 500));
 
+      // Finish incremental sync; its meeting is kept when every line reached the backend
+      const syncStatus = await invoke<{ meeting_id: string | null; pending_segments: number }>('stop_transcript_sync')
+        .catch(error => {
+          console.warn('Failed to stop transcript sync:', error);
+          return null;
+        });
+
       // Save to SQLite
       if (isCallApi && transcriptionComplete == true) {
 
@@ -686,15 +712,26 @@ export default function Home() {
           last_transcript: freshTranscripts.length > 0 ? freshTranscripts[freshTranscripts.length - 1].text.substring(0, 30) + '...' : 'none'
         });
         
-        const responseData = await invoke('api_save_transcript', {
-          meetingTitle: meetingTitle,
-          transcripts: freshTranscripts, // Use fresh state, not stale closure
-        }) as any;
-
-        const meetingId = responseData.meeting_id;
-        if (!meetingId) {
-          console.error('No meeting_id in response:', responseData);
-          throw new Error('No meeting ID received from save operation');
+        let meetingId: string | undefined;
+        if (syncStatus?.meeting_id && syncStatus.pending_segments === 0) {
+          console.log('Transcript already synced during the recording to meeting:', syncStatus.meeting_id);
+          meetingId = syncStatus.meeting_id;
+        } else {
+          if (syncStatus?.meeting_id) {
+            // Some lines never reached the synced meeting; replace it with a full save
+            await invoke('api_delete_meeting', { meetingId: syncStatus.meeting_id })
+              .catch(error => console.warn('Failed to delete partially synced meeting:', error));
+          }
+          const responseData = await invoke('api_save_transcript', {
+            meetingTitle: meetingTitle,
+            transcripts: freshTranscripts, // Use fresh state, not stale closure
+          }) as any;
+
+          meetingId = responseData.meeting_id;
+          if (!meetingId) {
+            console.error('No meeting_id in response:', responseData);
+            throw new Error('No meeting ID received from save operation');
+          }
         }
         
         console.log('Successfully saved transcript with meeting ID:', meetingId);
@@ -1,13 +1,14 @@
 'use client';
 
 import { invoke } from '@tauri-apps/api/core';
-import { appDataDir } from '@tauri-apps/api/path';
+import { appDataDir, join } from '@tauri-apps/api/path';
 import { useCallback, useEffect, useState, useRef } from 'react';
 import { Play, Pause, Square, Mic } from 'lucide-react';
 import { ProcessRequest, SummaryResponse } from '@/types/summary';
 import { listen } from '@tauri-apps/api/event';
 import { Alert, AlertDescription, AlertTitle } from "@/components/ui/alert"
 import Analytics from '@/lib/analytics';
+import { FRONTEND_API_VERSION } from '@/lib/apiVersion';
 
 interface RecordingControlsProps {
   isRecording: boolean;
@@ -88,11 +89,12 @@ export const RecordingControls: React.FC<RecordingControlsProps> = ({
       setIsProcessing(true);
       const dataDir = await appDataDir();
       const timestamp = new Date().toISOString().replace(/[:.]/g, '-');
-      const savePath = `${dataDir}/recording-${timestamp}.wav`;
+      const savePath = await join(dataDir, 'recordings', `recording-${timestamp}.wav`);
       
       console.log('Saving recording to:', savePath);
       const result = await invoke('stop_recording', { 
         args: {
+          api_version: FRONTEND_API_VERSION,
           save_path: savePath
         }
       });
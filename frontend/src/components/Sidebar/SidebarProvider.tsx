@@ -79,8 +79,14 @@ export function SidebarProvider({ children }: { children: React.ReactNode }) {
     const fetchMeetings = async () => {
         if (serverAddress) {
           try {
-        const meetings = await invoke('api_get_meetings') as Array<{id: string, title: string}>;
-        const transformedMeetings = meetings.map((meeting: any) => ({
+        // Meetings come in pages of at most 500; the sidebar lists all of them
+        const allMeetings: Array<{id: string, title: string}> = [];
+        for (let pageNumber = 1; ; pageNumber++) {
+          const page = await invoke('api_get_meetings', { query: { page: pageNumber, limit: 500 } }) as {meetings: Array<{id: string, title: string}>, total: number};
+          allMeetings.push(...page.meetings);
+          if (page.meetings.length === 0 || allMeetings.length >= page.total) break;
+        }
+        const transformedMeetings = allMeetings.map((meeting: any) => ({
             id: meeting.id,
             title: meeting.title
         }));
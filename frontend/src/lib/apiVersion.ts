@@ -0,0 +1,18 @@
+import { invoke } from '@tauri-apps/api/core';
+
+// Command API version this frontend was built against. Must match the major version
+// of API_VERSION in src-tauri/src/api_version.rs.
+export const FRONTEND_API_VERSION = { major: 1, minor: 0 };
+
+export interface ApiVersionInfo {
+  api_version: { major: number; minor: number };
+  app_version: string;
+}
+
+// Resolves with the app's version info, or rejects with a readable message when the
+// frontend and the app cannot talk to each other.
+export async function checkApiCompatibility(): Promise<ApiVersionInfo> {
+  return invoke<ApiVersionInfo>('check_api_compatibility', {
+    clientVersion: FRONTEND_API_VERSION,
+  });
+}